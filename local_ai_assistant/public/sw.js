@@ -0,0 +1,90 @@
+// iDoris service worker
+//
+// Caches the app shell and static assets so the installed app opens
+// instantly, and shows an offline page when the iDoris server can't be
+// reached. Server function calls and the /v1 API always go to the network.
+
+const CACHE = "idoris-v1";
+const OFFLINE_PAGE = "/offline.html";
+const PRECACHE = ["/", OFFLINE_PAGE, "/manifest.webmanifest", "/icon-192.png", "/icon-512.png"];
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(
+    caches.open(CACHE)
+      .then((cache) => cache.addAll(PRECACHE))
+      .then(() => self.skipWaiting())
+  );
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches.keys()
+      .then((keys) => Promise.all(keys.filter((key) => key !== CACHE).map((key) => caches.delete(key))))
+      .then(() => self.clients.claim())
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  const url = new URL(request.url);
+  if (request.method !== "GET" || url.origin !== self.location.origin) {
+    return;
+  }
+  if (url.pathname.startsWith("/api/") || url.pathname.startsWith("/v1/") || url.pathname === "/login") {
+    return;
+  }
+
+  if (request.mode === "navigate") {
+    event.respondWith(navigate(request));
+  } else if (url.pathname.startsWith("/assets/")) {
+    // Bundled assets have content hashes in their names and never change
+    event.respondWith(cacheFirst(request));
+  } else {
+    event.respondWith(networkFirst(request));
+  }
+});
+
+// Pages come from the server when it is up; otherwise the cached shell
+async function navigate(request) {
+  try {
+    const response = await fetch(request);
+    // Don't cache the sign-in page a redirect led to
+    if (response.ok && !response.redirected && new URL(request.url).pathname === "/") {
+      const cache = await caches.open(CACHE);
+      cache.put("/", response.clone());
+    }
+    return response;
+  } catch (e) {
+    return (await caches.match("/")) || (await caches.match(OFFLINE_PAGE));
+  }
+}
+
+async function cacheFirst(request) {
+  const cached = await caches.match(request);
+  if (cached) {
+    return cached;
+  }
+  const response = await fetch(request);
+  if (response.ok) {
+    const cache = await caches.open(CACHE);
+    cache.put(request, response.clone());
+  }
+  return response;
+}
+
+async function networkFirst(request) {
+  try {
+    const response = await fetch(request);
+    if (response.ok && !response.redirected) {
+      const cache = await caches.open(CACHE);
+      cache.put(request, response.clone());
+    }
+    return response;
+  } catch (e) {
+    const cached = await caches.match(request);
+    if (cached) {
+      return cached;
+    }
+    throw e;
+  }
+}
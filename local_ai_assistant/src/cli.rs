@@ -0,0 +1,309 @@
+//! Headless Command Line Interface
+//!
+//! Runs batch operations without the UI, for scripting and automation.
+//! Built with the `cli` feature; when the binary is started with a known
+//! command it runs that command and exits instead of serving the app.
+//!
+//! ```text
+//! local_ai_assistant ingest <folder> [--collection <name>]
+//! local_ai_assistant blog <topic> [--out <file>]
+//! local_ai_assistant narrate <text-file> [--out <file>]
+//! local_ai_assistant sessions
+//! local_ai_assistant export-session <session-id> [--out <file>] [--json]
+//! local_ai_assistant export-dataset [--liked] [--folder <name>] [--from <date>] [--to <date>]
+//!                                   [--profile <id>] [--system <prompt>] [--out <file>]
+//! ```
+//!
+//! Commands that read chat history unlock encrypted history with the
+//! passphrase in `IDORIS_PASSPHRASE`.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::core::{content_generator, encryption, fine_tune, llm, quick_capture, tts};
+use crate::models::{FineTuneFilter, GenerationStage, JobState, PipelineDefinition};
+use crate::storage::database;
+
+const USAGE: &str = "Usage:
+  local_ai_assistant ingest <folder> [--collection <name>]   Copy .md/.txt files into the knowledge base
+  local_ai_assistant blog <topic> [--out <file>]             Research and draft a blog post
+  local_ai_assistant narrate <text-file> [--out <file>]      Read a text file aloud with TTS
+  local_ai_assistant sessions                                List chat sessions
+  local_ai_assistant export-session <id> [--out <file>] [--json]
+                                                             Export a chat session
+  local_ai_assistant export-dataset [--liked] [--folder <name>] [--from <YYYY-MM-DD>] [--to <YYYY-MM-DD>]
+                                    [--profile <id>] [--system <prompt>] [--out <file>]
+                                                             Export chat history as a JSONL fine-tuning dataset";
+
+/// Extensions the knowledge base indexes
+const INGEST_EXTENSIONS: &[&str] = &["md", "txt"];
+/// How often `blog` checks the pipeline job
+const JOB_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Ingest { folder: PathBuf, collection: Option<String> },
+    Blog { topic: String, out: Option<PathBuf> },
+    Narrate { file: PathBuf, out: Option<PathBuf> },
+    Sessions,
+    ExportSession { id: String, out: Option<PathBuf>, json: bool },
+    ExportDataset { filter: FineTuneFilter, out: Option<PathBuf> },
+}
+
+/// Parses the command line; `None` when there is no command and the app
+/// should start normally
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Option<Result<Command, String>> {
+    let mut args = args.into_iter();
+    let name = args.next()?;
+
+    let mut positional = Vec::new();
+    let mut out = None;
+    let mut collection = None;
+    let mut json = false;
+    let mut filter = FineTuneFilter::default();
+    let parsed = (|| -> Result<Command, String> {
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--out" | "-o" => out = Some(PathBuf::from(args.next().ok_or("--out needs a file")?)),
+                "--collection" => collection = Some(args.next().ok_or("--collection needs a name")?),
+                "--json" => json = true,
+                "--liked" => filter.liked_only = true,
+                "--folder" => filter.folder = Some(args.next().ok_or("--folder needs a name")?),
+                "--profile" => filter.profile_id = Some(args.next().ok_or("--profile needs an ID")?),
+                "--system" => filter.system_prompt = Some(args.next().ok_or("--system needs a prompt")?),
+                "--from" => filter.from = Some(parse_date(&args.next().ok_or("--from needs a date")?, false)?),
+                "--to" => filter.to = Some(parse_date(&args.next().ok_or("--to needs a date")?, true)?),
+                flag if flag.starts_with('-') => return Err(format!("Unknown option {}", flag)),
+                _ => positional.push(arg),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let mut required = |what: &str| positional.next().ok_or(format!("Missing {}", what));
+        Ok(match name.as_str() {
+            "ingest" => Command::Ingest { folder: PathBuf::from(required("folder")?), collection },
+            "blog" => Command::Blog { topic: required("topic")?, out },
+            "narrate" => Command::Narrate { file: PathBuf::from(required("text file")?), out },
+            "sessions" => Command::Sessions,
+            "export-session" => Command::ExportSession { id: required("session ID")?, out, json },
+            "export-dataset" => Command::ExportDataset { filter, out },
+            "help" | "--help" | "-h" => return Err(String::new()),
+            other => return Err(format!("Unknown command {}", other)),
+        })
+    })();
+
+    Some(parsed.map_err(|e| if e.is_empty() { USAGE.to_string() } else { format!("{}\n\n{}", e, USAGE) }))
+}
+
+/// Parses a YYYY-MM-DD date as the start or end of that day in UTC
+fn parse_date(value: &str, end_of_day: bool) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("Invalid date {}", value))?;
+    let time = if end_of_day { date.and_hms_opt(23, 59, 59) } else { date.and_hms_opt(0, 0, 0) };
+    Ok(time.ok_or(format!("Invalid date {}", value))?.and_utc())
+}
+
+/// Runs a command and returns the process exit code
+pub fn run(command: Command) -> i32 {
+    let runtime = match tokio::runtime::Builder::new_multi_thread().enable_all().build() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start runtime: {}", e);
+            return 1;
+        }
+    };
+
+    match runtime.block_on(execute(command)) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+async fn execute(command: Command) -> Result<(), String> {
+    match command {
+        Command::Ingest { folder, collection } => ingest(&folder, collection.as_deref()),
+        Command::Blog { topic, out } => blog(topic, out).await,
+        Command::Narrate { file, out } => narrate(&file, out).await,
+        Command::Sessions => sessions().await,
+        Command::ExportSession { id, out, json } => export_session(&id, out, json).await,
+        Command::ExportDataset { filter, out } => export_dataset(&filter, out).await,
+    }
+}
+
+/// Copies supported files into the context folder; the app indexes them on
+/// its next start or "Reload documents"
+fn ingest(folder: &Path, collection: Option<&str>) -> Result<(), String> {
+    if !folder.is_dir() {
+        return Err(format!("Not a folder: {}", folder.display()));
+    }
+    let target = quick_capture::collection_dir(collection)?;
+
+    let mut copied = 0;
+    let mut pending = vec![folder.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let supported = path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| INGEST_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+            if !supported {
+                continue;
+            }
+
+            let relative = path.strip_prefix(folder).unwrap_or(&path);
+            let dest = target.join(relative);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::copy(&path, &dest).map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+            copied += 1;
+        }
+    }
+
+    println!("Copied {} file(s) into {}", copied, target.display());
+    println!("They are indexed the next time the app starts or reloads documents.");
+    Ok(())
+}
+
+/// Runs research, outline, draft and SEO without checkpoints
+async fn blog(topic: String, out: Option<PathBuf>) -> Result<(), String> {
+    eprintln!("Loading model...");
+    llm::init_chat_model().await?;
+
+    let pipeline = PipelineDefinition {
+        stages: vec![GenerationStage::Research, GenerationStage::Outline, GenerationStage::Draft, GenerationStage::Seo],
+        checkpoints: Vec::new(),
+        ..PipelineDefinition::default()
+    };
+    let job_id = content_generator::start(topic, pipeline)?;
+
+    let mut last_message = String::new();
+    let job = loop {
+        tokio::time::sleep(JOB_POLL_INTERVAL).await;
+        let job = content_generator::get_job(job_id).ok_or("Job disappeared")?;
+        if job.progress.message != last_message {
+            last_message = job.progress.message.clone();
+            eprintln!("[{:>3}%] {}", job.progress.percent(), last_message);
+        }
+        match &job.progress.state {
+            JobState::Completed => break job,
+            JobState::Failed(reason) => return Err(reason.clone()),
+            JobState::Running | JobState::AwaitingApproval => {}
+        }
+    };
+
+    write_output(out.as_deref(), job.package.to_markdown().as_bytes())
+}
+
+async fn narrate(file: &Path, out: Option<PathBuf>) -> Result<(), String> {
+    let text = std::fs::read_to_string(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+    let audio = tts::speak_text(&text).await?;
+    let out = out.unwrap_or_else(|| file.with_extension(&audio.format));
+    std::fs::write(&out, &audio.data).map_err(|e| format!("{}: {}", out.display(), e))?;
+    println!("Wrote {} ({:.1}s)", out.display(), audio.duration_ms as f32 / 1000.0);
+    Ok(())
+}
+
+async fn sessions() -> Result<(), String> {
+    open_database().await?;
+    for session in database::get_all_sessions().await.map_err(|e| e.to_string())? {
+        println!("{}  {}  {}", session.id, session.updated_at.format("%Y-%m-%d %H:%M"), session.title);
+    }
+    Ok(())
+}
+
+async fn export_session(id: &str, out: Option<PathBuf>, json: bool) -> Result<(), String> {
+    let uuid = uuid::Uuid::parse_str(id).map_err(|_| format!("Invalid session ID: {}", id))?;
+    open_database().await?;
+
+    let session = database::get_all_sessions().await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|s| s.id == uuid)
+        .ok_or_else(|| format!("Session not found: {}", id))?;
+    let messages = database::get_session_messages(uuid).await.map_err(|e| e.to_string())?;
+
+    let content = if json {
+        serde_json::to_string_pretty(&serde_json::json!({ "session": session, "messages": messages }))
+            .map_err(|e| e.to_string())?
+    } else {
+        session.to_markdown(&messages)
+    };
+    write_output(out.as_deref(), content.as_bytes())
+}
+
+async fn export_dataset(filter: &FineTuneFilter, out: Option<PathBuf>) -> Result<(), String> {
+    open_database().await?;
+    let dataset = fine_tune::export_dataset(filter).await?;
+    eprintln!("{} example(s) from {} session(s)", dataset.examples, dataset.sessions);
+    write_output(out.as_deref(), dataset.jsonl.as_bytes())
+}
+
+/// Opens the database, unlocking encrypted chat history with the
+/// `IDORIS_PASSPHRASE` environment variable
+async fn open_database() -> Result<(), String> {
+    database::init().await.map_err(|e| e.to_string())?;
+    if encryption::status().is_locked() {
+        let passphrase = std::env::var("IDORIS_PASSPHRASE")
+            .map_err(|_| "Chat history is encrypted; set IDORIS_PASSPHRASE to unlock it")?;
+        encryption::unlock(&passphrase).await?;
+    }
+    Ok(())
+}
+
+/// Writes to the file, or to stdout without one
+fn write_output(out: Option<&Path>, content: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+
+    match out {
+        Some(path) => {
+            std::fs::write(path, content).map_err(|e| format!("{}: {}", path.display(), e))?;
+            eprintln!("Wrote {}", path.display());
+            Ok(())
+        }
+        None => std::io::stdout().write_all(content).map_err(|e| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(Vec::new()), None);
+        assert_eq!(
+            parse_args(args("ingest ./notes --collection research")),
+            Some(Ok(Command::Ingest { folder: "./notes".into(), collection: Some("research".into()) }))
+        );
+        assert_eq!(
+            parse_args(args("export-session abc --json -o out.json")),
+            Some(Ok(Command::ExportSession { id: "abc".into(), out: Some("out.json".into()), json: true }))
+        );
+        assert_eq!(
+            parse_args(args("export-dataset --liked --folder work --to 2026-01-31")),
+            Some(Ok(Command::ExportDataset {
+                filter: FineTuneFilter {
+                    liked_only: true,
+                    folder: Some("work".into()),
+                    to: Some(parse_date("2026-01-31", true).unwrap()),
+                    ..Default::default()
+                },
+                out: None,
+            }))
+        );
+        assert!(parse_args(args("export-dataset --from yesterday")).unwrap().is_err());
+        assert!(parse_args(args("blog")).unwrap().unwrap_err().starts_with("Missing topic"));
+        assert!(parse_args(args("frobnicate")).unwrap().is_err());
+    }
+}
@@ -1,9 +1,10 @@
 //! Main Application Component
 
 use dioxus::prelude::*;
-use crate::models::{Session, ChatMessage, AppSettings};
-use crate::server_functions::get_session_messages;
-use super::{Sidebar, Chat, SettingsPage, ImageGenPanel, TtsPanel, ContentEditorPanel, VideoGenPanel};
+use crate::models::{Session, ChatMessage, ChatAttachment, AppSettings, FontSize, PlaybackQueue, Profile, error_message};
+use crate::server_functions::{get_session_messages, get_sessions, open_chat_session, get_profile_settings, save_profile_settings, get_encryption_status, reload_context_database};
+use super::i18n::{t, use_ui_language_provider};
+use super::{refine_session_title, Sidebar, Chat, SpeechPlayer, SettingsPage, ImageGenPanel, TtsPanel, VoicePanel, ContentEditorPanel, ContentPipelinePanel, VideoGenPanel, StatusBar, QuickCaptureDialog, NotificationCenter, LockScreen, JobsPanel, AssetsPanel, TrashPanel, CalendarPanel, ComparePanel, summarize_into, use_quick_capture_hotkey, CommandPalette, PaletteCommand, SettingsTab, use_command_palette_hotkey, use_settings_opener_provider, use_capabilities_provider};
 
 /// Active panel types in the main content area
 #[derive(Clone, Copy, PartialEq, Default)]
@@ -12,8 +13,52 @@ pub enum ActivePanel {
     Chat,
     ImageGen,
     Tts,
+    Voice,
     ContentEditor,
+    ContentPipeline,
+    Calendar,
     VideoGen,
+    Compare,
+    Assets,
+    Jobs,
+    Trash,
+}
+
+impl ActivePanel {
+    pub fn all() -> &'static [ActivePanel] {
+        &[
+            ActivePanel::Chat,
+            ActivePanel::ImageGen,
+            ActivePanel::Tts,
+            ActivePanel::Voice,
+            ActivePanel::ContentEditor,
+            ActivePanel::ContentPipeline,
+            ActivePanel::Calendar,
+            ActivePanel::VideoGen,
+            ActivePanel::Compare,
+            ActivePanel::Assets,
+            ActivePanel::Jobs,
+            ActivePanel::Trash,
+        ]
+    }
+
+    /// Name shown in the header and the command palette
+    pub fn title(&self) -> &'static str {
+        match self {
+            ActivePanel::Chat => "Chat",
+            ActivePanel::ImageGen => "Image Generation",
+            ActivePanel::Tts => "Text to Speech",
+            ActivePanel::Voice => "Voice",
+            ActivePanel::ContentEditor => "Content Editor",
+            ActivePanel::ContentPipeline => "Content Pipeline",
+            ActivePanel::Calendar => "Content Calendar",
+            ActivePanel::VideoGen => "Video Generation",
+            ActivePanel::Compare => "Compare Models",
+            ActivePanel::Assets => "Assets",
+            ActivePanel::Jobs => "Jobs",
+            ActivePanel::Trash => "Trash",
+        }
+    }
 }
 
 /// Main application component
@@ -37,13 +82,104 @@ pub fn App() -> Element {
     // Loading state
     let is_loading: Signal<bool> = use_signal(|| false);
 
-    // Settings state
-    let settings: Signal<AppSettings> = use_signal(AppSettings::default);
+    // Library assets attached from the Assets panel, picked up by the chat
+    let mut pending_attachments: Signal<Vec<ChatAttachment>> = use_signal(Vec::new);
+
+    // Second chat shown beside the first in split view, with its own session
+    let mut split_view: Signal<bool> = use_signal(|| false);
+    let mut split_session: Signal<Option<Session>> = use_signal(|| None);
+    let mut split_messages: Signal<Vec<ChatMessage>> = use_signal(Vec::new);
+    let split_attachments: Signal<Vec<ChatAttachment>> = use_signal(Vec::new);
+
+    // Messages queued for reading aloud, from either chat
+    let playback: Signal<PlaybackQueue> = use_signal(PlaybackQueue::default);
+
+    // Settings state, persisted per profile once loaded
+    let mut settings: Signal<AppSettings> = use_signal(AppSettings::default);
+    let mut settings_loaded: Signal<bool> = use_signal(|| false);
     let mut show_settings: Signal<bool> = use_signal(|| false);
+    // Tab the settings page opens on
+    let mut settings_tab: Signal<SettingsTab> = use_signal(SettingsTab::default);
+    use_ui_language_provider(settings);
+    use_settings_opener_provider(show_settings, settings_tab);
+    use_capabilities_provider();
+    use_font_size(settings);
+
+    use_effect(move || {
+        spawn(async move {
+            match get_profile_settings().await {
+                Ok(loaded) => settings.set(loaded),
+                Err(e) => tracing::error!("Error loading settings: {:?}", e),
+            }
+            settings_loaded.set(true);
+        });
+    });
+
+    // Save settings whenever they change
+    use_effect(move || {
+        let current = settings();
+        if !settings_loaded() {
+            return;
+        }
+        spawn(async move {
+            if let Err(e) = save_profile_settings(current).await {
+                tracing::error!("Error saving settings: {:?}", e);
+            }
+        });
+    });
 
     // Sidebar collapsed state
     let mut sidebar_collapsed: Signal<bool> = use_signal(|| false);
 
+    // True while "Rename with AI" is running
+    let mut is_renaming: Signal<bool> = use_signal(|| false);
+
+    // True while "Summarize" is running
+    let mut is_summarizing: Signal<bool> = use_signal(|| false);
+
+    // Quick capture dialog, opened with Ctrl/Cmd+Shift+K
+    let mut show_quick_capture: Signal<bool> = use_signal(|| false);
+    use_quick_capture_hotkey(show_quick_capture);
+
+    // Command palette, opened with Ctrl/Cmd+K
+    let show_palette: Signal<bool> = use_signal(|| false);
+    use_command_palette_hotkey(show_palette);
+
+    let mut new_chat = move || {
+        let new_session = Session::default_title();
+        sessions.write().insert(0, new_session.clone());
+        current_session.set(Some(new_session));
+        messages.write().clear();
+        active_panel.set(ActivePanel::Chat);
+    };
+
+    // Tray icon with quick actions in the desktop app
+    #[cfg(feature = "desktop")]
+    crate::desktop::use_tray(new_chat, show_quick_capture);
+
+    // Encrypted chat history stays locked until the passphrase is entered;
+    // None until the status is known
+    let mut history_locked: Signal<Option<bool>> = use_signal(|| None);
+    use_effect(move || {
+        spawn(async move {
+            match get_encryption_status().await {
+                Ok(status) => history_locked.set(Some(status.is_locked())),
+                Err(e) => {
+                    tracing::error!("Error checking history encryption: {:?}", e);
+                    history_locked.set(Some(false));
+                }
+            }
+        });
+    });
+
+    match history_locked() {
+        None => return rsx! { div { class: "h-screen bg-slate-900" } },
+        Some(true) => return rsx! {
+            LockScreen { on_unlocked: move |_| history_locked.set(Some(false)) }
+        },
+        Some(false) => {}
+    }
+
     // Get theme classes from settings
     let theme = settings.read().theme.clone();
     let bg_class = theme.bg_class();
@@ -78,43 +214,81 @@ pub fn App() -> Element {
                 sessions: sessions,
                 current_session: current_session,
                 active_panel: active_panel,
-                on_new_session: move |_| {
-                    let new_session = Session::default_title();
-                    sessions.write().insert(0, new_session.clone());
-                    current_session.set(Some(new_session));
-                    messages.write().clear();
-                    active_panel.set(ActivePanel::Chat);
-                },
+                on_new_session: move |_| new_chat(),
                 on_select_session: move |session: Session| {
-                    let session_id = session.id.to_string();
-                    current_session.set(Some(session));
+                    open_session(session, current_session, messages);
                     active_panel.set(ActivePanel::Chat);
-                    // Load messages for selected session
-                    spawn(async move {
-                        match get_session_messages(session_id).await {
-                            Ok(loaded_messages) => {
-                                messages.set(loaded_messages);
-                            }
-                            Err(e) => {
-                                println!("Error loading messages: {:?}", e);
-                                messages.set(Vec::new());
-                            }
-                        }
-                    });
                 },
                 on_toggle_settings: move |_| {
+                    settings_tab.set(SettingsTab::default());
                     show_settings.set(!show_settings());
                 },
                 on_select_panel: move |panel: ActivePanel| {
                     active_panel.set(panel);
                 },
+                on_switch_profile: move |profile: Profile| {
+                    tracing::info!("Active profile: {}", profile.name);
+                    current_session.set(None);
+                    messages.write().clear();
+                    split_session.set(None);
+                    split_messages.write().clear();
+                    settings_loaded.set(false);
+                    spawn(async move {
+                        match get_sessions().await {
+                            Ok(loaded) => sessions.set(loaded),
+                            Err(e) => {
+                                tracing::error!("Error loading sessions: {:?}", e);
+                                sessions.set(Vec::new());
+                            }
+                        }
+                        match get_profile_settings().await {
+                            Ok(loaded) => settings.set(loaded),
+                            Err(e) => tracing::error!("Error loading settings: {:?}", e),
+                        }
+                        settings_loaded.set(true);
+                    });
+                },
                 sidebar_collapsed: sidebar_collapsed,
             }
 
+            QuickCaptureDialog { open: show_quick_capture }
+
+            CommandPalette {
+                open: show_palette,
+                sessions: sessions,
+                on_command: move |command: PaletteCommand| match command {
+                    PaletteCommand::NewChat => new_chat(),
+                    PaletteCommand::OpenSession(session) => {
+                        open_session(session, current_session, messages);
+                        active_panel.set(ActivePanel::Chat);
+                    }
+                    PaletteCommand::OpenPanel(panel) => active_panel.set(panel),
+                    PaletteCommand::OpenSettings(tab) => {
+                        settings_tab.set(tab);
+                        show_settings.set(true);
+                    }
+                    PaletteCommand::ReloadContext => {
+                        spawn(async move {
+                            match reload_context_database().await {
+                                Ok(msg) => tracing::info!("{}", msg),
+                                Err(e) => tracing::error!("Error reloading context: {:?}", e),
+                            }
+                        });
+                    }
+                    PaletteCommand::QuickCapture => show_quick_capture.set(true),
+                    PaletteCommand::ToggleSplitView => {
+                        split_view.set(!split_view());
+                        active_panel.set(ActivePanel::Chat);
+                    }
+                },
+            }
+
             // Settings page (full-page overlay)
             if show_settings() {
                 SettingsPage {
+                    key: "{settings_tab:?}",
                     settings: settings,
+                    initial_tab: settings_tab(),
                     on_close: move |_| show_settings.set(false),
                 }
             }
@@ -158,10 +332,59 @@ pub fn App() -> Element {
                                     rsx! { "Local AI Assistant" }
                                 }
                             }
-                            ActivePanel::ImageGen => rsx! { "Image Generation" },
-                            ActivePanel::Tts => rsx! { "Text to Speech" },
-                            ActivePanel::ContentEditor => rsx! { "Content Editor" },
-                            ActivePanel::VideoGen => rsx! { "Video Generation" },
+                            panel => rsx! { {panel.title()} },
+                        }
+                    }
+
+                    // Rename current session with the LLM
+                    if active_panel() == ActivePanel::Chat && current_session().is_some() && model_ready() {
+                        button {
+                            class: "ml-3 px-2 py-1 text-xs rounded-md text-slate-400 hover:text-white hover:bg-slate-700 transition-colors disabled:opacity-50",
+                            title: "Generate a title from the conversation",
+                            disabled: is_renaming(),
+                            onclick: move |_| {
+                                let Some(session) = current_session() else { return };
+                                is_renaming.set(true);
+                                spawn(async move {
+                                    refine_session_title(session.id, current_session, sessions).await;
+                                    is_renaming.set(false);
+                                });
+                            },
+                            if is_renaming() { "Renaming..." } else { "✨ Rename with AI" }
+                        }
+                    }
+
+                    // Summarize current session with the LLM
+                    if active_panel() == ActivePanel::Chat && current_session().is_some() && model_ready() {
+                        button {
+                            class: "ml-1 px-2 py-1 text-xs rounded-md text-slate-400 hover:text-white hover:bg-slate-700 transition-colors disabled:opacity-50",
+                            title: "Summarize the conversation with its takeaways and action items",
+                            disabled: is_summarizing(),
+                            onclick: move |_| {
+                                let Some(session) = current_session() else { return };
+                                is_summarizing.set(true);
+                                spawn(async move {
+                                    if let Err(e) = summarize_into(session.id, current_session, sessions).await {
+                                        tracing::error!("Error summarizing session: {}", e);
+                                    }
+                                    is_summarizing.set(false);
+                                });
+                            },
+                            if is_summarizing() { "Summarizing..." } else { "📝 Summarize" }
+                        }
+                    }
+
+                    // Open a second chat side by side
+                    if active_panel() == ActivePanel::Chat {
+                        button {
+                            class: if split_view() {
+                                "ml-1 px-2 py-1 text-xs rounded-md bg-slate-700 text-white transition-colors"
+                            } else {
+                                "ml-1 px-2 py-1 text-xs rounded-md text-slate-400 hover:text-white hover:bg-slate-700 transition-colors"
+                            },
+                            title: "Show two chats side by side, each with its own session",
+                            onclick: move |_| split_view.set(!split_view()),
+                            if split_view() { "◫ Close split view" } else { "◫ Split view" }
                         }
                     }
 
@@ -176,23 +399,61 @@ pub fn App() -> Element {
                             if model_ready() { "Ready" } else { "Loading..." }
                         }
                     }
+
+                    // Job notifications
+                    div {
+                        class: "ml-3",
+                        NotificationCenter { settings: settings }
+                    }
                 }
 
                 // Content area based on active panel
                 match active_panel() {
+                    // The first chat keeps its place when the split opens, so a
+                    // streaming answer isn't interrupted
                     ActivePanel::Chat => rsx! {
-                        Chat {
-                            messages: messages,
-                            current_session: current_session,
-                            sessions: sessions,
-                            is_loading: is_loading,
-                            model_ready: model_ready,
-                            settings: settings,
+                        div {
+                            class: "flex-1 min-h-0 flex",
+                            div {
+                                class: "flex-1 min-w-0 flex flex-col",
+                                if split_view() {
+                                    SessionPicker { sessions: sessions, current_session: current_session, messages: messages }
+                                }
+                                Chat {
+                                    messages: messages,
+                                    current_session: current_session,
+                                    sessions: sessions,
+                                    is_loading: is_loading,
+                                    model_ready: model_ready,
+                                    settings: settings,
+                                    pending_attachments: pending_attachments,
+                                    playback: playback,
+                                }
+                            }
+                            if split_view() {
+                                div {
+                                    class: "flex-1 min-w-0 flex flex-col border-l border-gray-700",
+                                    SessionPicker { sessions: sessions, current_session: split_session, messages: split_messages }
+                                    Chat {
+                                        messages: split_messages,
+                                        current_session: split_session,
+                                        sessions: sessions,
+                                        is_loading: is_loading,
+                                        model_ready: model_ready,
+                                        settings: settings,
+                                        pending_attachments: split_attachments,
+                                        playback: playback,
+                                        pane: 1,
+                                    }
+                                }
+                            }
                         }
+                        SpeechPlayer { queue: playback, settings: settings }
                     },
                     ActivePanel::ImageGen => rsx! {
                         ImageGenPanel {
                             embedded: true,
+                            settings: settings,
                             on_open_settings: EventHandler::new(move |_| {
                                 show_settings.set(true);
                             }),
@@ -200,11 +461,15 @@ pub fn App() -> Element {
                     },
                     ActivePanel::Tts => rsx! {
                         TtsPanel {
+                            settings: settings,
                             on_open_settings: EventHandler::new(move |_| {
                                 show_settings.set(true);
                             }),
                         }
                     },
+                    ActivePanel::Voice => rsx! {
+                        VoicePanel { settings: settings }
+                    },
                     ActivePanel::ContentEditor => rsx! {
                         ContentEditorPanel {
                             on_open_settings: EventHandler::new(move |_| {
@@ -212,6 +477,12 @@ pub fn App() -> Element {
                             }),
                         }
                     },
+                    ActivePanel::ContentPipeline => rsx! {
+                        ContentPipelinePanel {}
+                    },
+                    ActivePanel::Calendar => rsx! {
+                        CalendarPanel {}
+                    },
                     ActivePanel::VideoGen => rsx! {
                         VideoGenPanel {
                             on_close: EventHandler::new(move |_| {
@@ -219,8 +490,118 @@ pub fn App() -> Element {
                             }),
                         }
                     },
+                    ActivePanel::Compare => rsx! {
+                        ComparePanel {}
+                    },
+                    ActivePanel::Assets => rsx! {
+                        AssetsPanel {
+                            on_attach: move |attachment: ChatAttachment| {
+                                pending_attachments.write().push(attachment);
+                                active_panel.set(ActivePanel::Chat);
+                            },
+                        }
+                    },
+                    ActivePanel::Jobs => rsx! {
+                        JobsPanel {}
+                    },
+                    ActivePanel::Trash => rsx! {
+                        TrashPanel { sessions: sessions }
+                    },
                 }
+
+                // Compact resource monitor
+                StatusBar {}
             }
         }
     }
 }
+
+/// Shows a session in a chat, loads its messages and prepares its chat
+/// on the server
+fn open_session(session: Session, mut current_session: Signal<Option<Session>>, mut messages: Signal<Vec<ChatMessage>>) {
+    let session_id = session.id.to_string();
+    current_session.set(Some(session));
+    spawn(async move {
+        match get_session_messages(session_id.clone()).await {
+            Ok(loaded_messages) => messages.set(loaded_messages),
+            Err(e) => {
+                tracing::error!("Error loading messages: {:?}", e);
+                messages.set(Vec::new());
+            }
+        }
+        // Rebuild the session's chat on the server before the first turn
+        if let Err(e) = open_chat_session(session_id).await {
+            tracing::error!("Error opening conversation: {}", error_message(&e));
+        }
+    });
+}
+
+/// Chooses the session shown in one pane of the split view
+#[component]
+fn SessionPicker(
+    sessions: Signal<Vec<Session>>,
+    mut current_session: Signal<Option<Session>>,
+    mut messages: Signal<Vec<ChatMessage>>,
+) -> Element {
+    let selected = current_session().map(|s| s.id.to_string()).unwrap_or_default();
+
+    rsx! {
+        div {
+            class: "h-10 px-3 flex items-center border-b border-gray-700",
+            select {
+                class: "flex-1 min-w-0 px-2 py-1 bg-slate-800 border border-slate-700 rounded text-sm text-white focus:outline-none",
+                onchange: move |e| {
+                    let id = e.value();
+                    let session = sessions.read().iter().find(|s| s.id.to_string() == id).cloned();
+                    match session {
+                        Some(session) => open_session(session, current_session, messages),
+                        None => {
+                            current_session.set(None);
+                            messages.write().clear();
+                        }
+                    }
+                },
+                option { value: "", {t("sidebar.new_chat")} }
+                for session in sessions.read().iter() {
+                    option {
+                        key: "{session.id}",
+                        value: "{session.id}",
+                        selected: session.id.to_string() == selected,
+                        "{session.title}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Scales the whole UI with the font size setting by setting the root font
+/// size, and changes it with Ctrl/Cmd and +, - or 0
+fn use_font_size(mut settings: Signal<AppSettings>) {
+    use_effect(move || {
+        let px = settings.read().font_size.root_px();
+        let _ = document::eval(&format!("document.documentElement.style.fontSize = '{}px';", px));
+    });
+
+    use_effect(move || {
+        spawn(async move {
+            let mut listener = document::eval(
+                r#"document.addEventListener('keydown', (e) => {
+                    if (!(e.ctrlKey || e.metaKey) || e.altKey) return;
+                    const step = { '=': 1, '+': 1, '-': -1, '_': -1, '0': 0 }[e.key];
+                    if (step === undefined) return;
+                    e.preventDefault();
+                    dioxus.send(step);
+                });"#,
+            );
+            while let Ok(step) = listener.recv::<i32>().await {
+                let current = settings.read().font_size.clone();
+                settings.write().font_size = match step {
+                    1 => current.larger(),
+                    -1 => current.smaller(),
+                    _ => FontSize::default(),
+                };
+            }
+        });
+    });
+}
@@ -0,0 +1,307 @@
+//! Assets Panel Component
+//!
+//! The library of generated images, audio and video from every feature,
+//! with search, filters, tags, and actions to attach an asset to a chat or
+//! insert it into an article.
+
+use dioxus::prelude::*;
+use crate::models::{Asset, AssetFilter, AssetKind, AssetOrigin, ChatAttachment, error_message};
+use crate::server_functions::{
+    get_assets, get_asset_preview, get_asset_media, get_asset_snippet, get_asset_attachment, set_asset_tags, delete_asset,
+};
+
+/// Library view. `on_attach` and `on_insert` add the matching action to
+/// each asset; `compact` is for the picker embedded in the content editor.
+#[component]
+pub fn AssetsPanel(
+    #[props(default = false)]
+    compact: bool,
+    on_attach: Option<EventHandler<ChatAttachment>>,
+    on_insert: Option<EventHandler<String>>,
+) -> Element {
+    let mut filter = use_signal(AssetFilter::default);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+    // Bumped to reload the list after a change
+    let mut revision = use_signal(|| 0u32);
+
+    let assets = use_resource(move || async move {
+        let _ = revision();
+        get_assets(filter()).await.map_err(error_message)
+    });
+
+    let list: Vec<Asset> = match assets() {
+        Some(Ok(list)) => list,
+        _ => Vec::new(),
+    };
+    let mut tags: Vec<String> = list.iter().flat_map(|a| a.tags.iter().cloned()).collect();
+    tags.sort();
+    tags.dedup();
+    if let Some(tag) = filter().tag.filter(|t| !tags.contains(t)) {
+        tags.push(tag);
+    }
+
+    rsx! {
+        div {
+            class: if compact { "mt-3 p-3 bg-slate-900/50 rounded-lg space-y-3" } else { "flex-1 overflow-y-auto p-6" },
+            div {
+                class: if compact { "space-y-3" } else { "max-w-5xl mx-auto space-y-4" },
+
+                if !compact {
+                    h2 {
+                        class: "text-lg font-semibold text-white",
+                        "Assets"
+                        span { class: "ml-2 text-sm font-normal text-slate-400", "{list.len()} shown" }
+                    }
+                }
+
+                // Search and filters
+                div {
+                    class: "flex flex-wrap items-center gap-2",
+                    input {
+                        class: "flex-1 min-w-[12rem] px-3 py-1.5 bg-slate-700 border border-slate-600 rounded text-sm text-white placeholder-slate-400",
+                        placeholder: "Search prompts, file names and tags...",
+                        value: "{filter().query}",
+                        oninput: move |e| filter.write().query = e.value(),
+                    }
+                    select {
+                        class: "px-2 py-1.5 bg-slate-700 border border-slate-600 rounded text-sm text-white",
+                        onchange: move |e| filter.write().kind = AssetKind::from_id(&e.value()),
+                        option { value: "", selected: filter().kind.is_none(), "All media" }
+                        for kind in AssetKind::all() {
+                            option { value: "{kind.id()}", selected: filter().kind == Some(*kind), "{kind.display_name()}" }
+                        }
+                    }
+                    select {
+                        class: "px-2 py-1.5 bg-slate-700 border border-slate-600 rounded text-sm text-white",
+                        onchange: move |e| filter.write().origin = AssetOrigin::from_id(&e.value()),
+                        option { value: "", selected: filter().origin.is_none(), "From anywhere" }
+                        for origin in AssetOrigin::all() {
+                            option { value: "{origin.id()}", selected: filter().origin == Some(*origin), "{origin.display_name()}" }
+                        }
+                    }
+                    if !tags.is_empty() {
+                        select {
+                            class: "px-2 py-1.5 bg-slate-700 border border-slate-600 rounded text-sm text-white",
+                            onchange: move |e| {
+                                let tag = e.value();
+                                filter.write().tag = (!tag.is_empty()).then_some(tag);
+                            },
+                            option { value: "", selected: filter().tag.is_none(), "Any tag" }
+                            for tag in tags {
+                                option { value: "{tag}", selected: filter().tag.as_ref() == Some(&tag), "#{tag}" }
+                            }
+                        }
+                    }
+                }
+
+                if let Some(Err(message)) = assets() {
+                    p { class: "text-sm text-red-400", "{message}" }
+                }
+                if let Some(message) = error() {
+                    p { class: "text-sm text-red-400", "{message}" }
+                }
+
+                if assets().is_some() && list.is_empty() {
+                    p {
+                        class: "text-sm text-slate-500",
+                        if filter() == AssetFilter::default() {
+                            "No generated media yet. Images, speech and videos from chat, the generation panels and content packages show up here."
+                        } else {
+                            "Nothing matches these filters."
+                        }
+                    }
+                }
+
+                div {
+                    class: if compact { "grid grid-cols-2 lg:grid-cols-3 gap-3 max-h-96 overflow-y-auto" } else { "grid grid-cols-2 lg:grid-cols-3 gap-4" },
+                    for asset in list {
+                        AssetCard {
+                            key: "{asset.id}",
+                            asset,
+                            on_attach,
+                            on_insert,
+                            on_changed: move |result: Result<(), String>| match result {
+                                Ok(()) => {
+                                    error.set(None);
+                                    revision += 1;
+                                }
+                                Err(e) => error.set(Some(e)),
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn AssetCard(
+    asset: Asset,
+    on_attach: Option<EventHandler<ChatAttachment>>,
+    on_insert: Option<EventHandler<String>>,
+    on_changed: EventHandler<Result<(), String>>,
+) -> Element {
+    let asset_id = asset.id.to_string();
+    let mut tag_text = use_signal(|| asset.tags.join(", "));
+    let mut editing_tags = use_signal(|| false);
+    // Full media, loaded when the user opens the asset
+    let mut media: Signal<Option<String>> = use_signal(|| None);
+    let mut busy = use_signal(|| false);
+
+    let preview = use_resource(use_reactive!(|(asset_id,)| async move {
+        get_asset_preview(asset_id).await.ok().flatten()
+    }));
+    let created = asset.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+    let size_mb = asset.size_bytes as f64 / (1024.0 * 1024.0);
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg overflow-hidden flex flex-col",
+
+            // Preview, replaced by the player once opened
+            match (asset.kind, media()) {
+                (AssetKind::Audio, Some(url)) => rsx! {
+                    audio { class: "w-full", controls: true, autoplay: true, src: "{url}" }
+                },
+                (AssetKind::Video, Some(url)) => rsx! {
+                    video { class: "w-full aspect-video bg-black", controls: true, autoplay: true, src: "{url}" }
+                },
+                _ => rsx! {
+                    button {
+                        class: "w-full aspect-video flex items-center justify-center bg-slate-900 text-3xl text-slate-500",
+                        title: if asset.kind == AssetKind::Image { "" } else { "Play" },
+                        disabled: asset.kind == AssetKind::Image,
+                        onclick: {
+                            let asset_id = asset.id.to_string();
+                            move |_| {
+                                let asset_id = asset_id.clone();
+                                spawn(async move {
+                                    match get_asset_media(asset_id).await {
+                                        Ok(url) => media.set(Some(url)),
+                                        Err(e) => on_changed.call(Err(error_message(&e))),
+                                    }
+                                });
+                            }
+                        },
+                        if let Some(Some(url)) = preview() {
+                            img { class: "w-full h-full object-cover", src: "{url}" }
+                        } else {
+                            "{asset.kind.icon()}"
+                        }
+                    }
+                },
+            }
+
+            div {
+                class: "p-3 flex-1 flex flex-col gap-1",
+                p { class: "text-sm text-white truncate", title: "{asset.prompt}", "{asset.title()}" }
+                p {
+                    class: "text-xs text-slate-500",
+                    "{asset.origin.display_name()} · {created} · {size_mb:.1} MB"
+                }
+
+                if editing_tags() {
+                    div {
+                        class: "flex gap-1",
+                        input {
+                            class: "flex-1 min-w-0 px-2 py-1 bg-slate-700 border border-slate-600 rounded text-xs text-white",
+                            placeholder: "tag, another tag",
+                            value: "{tag_text}",
+                            oninput: move |e| tag_text.set(e.value()),
+                        }
+                        button {
+                            class: "px-2 py-1 text-xs bg-blue-600 text-white rounded hover:bg-blue-700",
+                            onclick: {
+                                let asset_id = asset.id.to_string();
+                                move |_| {
+                                    let asset_id = asset_id.clone();
+                                    spawn(async move {
+                                        match set_asset_tags(asset_id, tag_text()).await {
+                                            Ok(tags) => {
+                                                tag_text.set(tags.join(", "));
+                                                editing_tags.set(false);
+                                                on_changed.call(Ok(()));
+                                            }
+                                            Err(e) => on_changed.call(Err(error_message(&e))),
+                                        }
+                                    });
+                                }
+                            },
+                            "Save"
+                        }
+                    }
+                } else {
+                    div {
+                        class: "flex flex-wrap gap-1",
+                        for tag in asset.tags.iter() {
+                            span { class: "px-1.5 py-0.5 text-xs rounded bg-slate-700 text-slate-300", "#{tag}" }
+                        }
+                        button {
+                            class: "px-1.5 py-0.5 text-xs text-slate-400 hover:text-white",
+                            onclick: move |_| editing_tags.set(true),
+                            if asset.tags.is_empty() { "+ Tags" } else { "Edit" }
+                        }
+                    }
+                }
+
+                div {
+                    class: "mt-auto pt-2 flex flex-wrap gap-2",
+                    if let Some(on_insert) = on_insert {
+                        button {
+                            class: "px-2 py-1 text-xs bg-purple-600 text-white rounded hover:bg-purple-700",
+                            disabled: busy(),
+                            onclick: {
+                                let asset_id = asset.id.to_string();
+                                move |_| {
+                                    let asset_id = asset_id.clone();
+                                    busy.set(true);
+                                    spawn(async move {
+                                        match get_asset_snippet(asset_id).await {
+                                            Ok(snippet) => on_insert.call(snippet),
+                                            Err(e) => on_changed.call(Err(error_message(&e))),
+                                        }
+                                        busy.set(false);
+                                    });
+                                }
+                            },
+                            "Insert into article"
+                        }
+                    }
+                    if let Some(on_attach) = on_attach {
+                        button {
+                            class: "px-2 py-1 text-xs bg-blue-600 text-white rounded hover:bg-blue-700",
+                            disabled: busy(),
+                            onclick: {
+                                let asset_id = asset.id.to_string();
+                                move |_| {
+                                    let asset_id = asset_id.clone();
+                                    spawn(async move {
+                                        match get_asset_attachment(asset_id).await {
+                                            Ok(attachment) => on_attach.call(attachment),
+                                            Err(e) => on_changed.call(Err(error_message(&e))),
+                                        }
+                                    });
+                                }
+                            },
+                            "Attach to chat"
+                        }
+                    }
+                    if asset.origin != AssetOrigin::ContentPackage {
+                        button {
+                            class: "px-2 py-1 text-xs text-slate-400 hover:text-red-400 transition-colors",
+                            title: "Move to trash",
+                            onclick: move |_| {
+                                let asset_id = asset_id.clone();
+                                spawn(async move {
+                                    on_changed.call(delete_asset(asset_id).await.map_err(error_message));
+                                });
+                            },
+                            "Delete"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,440 @@
+//! Content Calendar Panel
+//!
+//! Month view of planned topics, drafts, scheduled posts and published
+//! items. Clicking a day adds an entry for it; clicking an entry edits it.
+//! Scheduled WordPress entries are published by the server at their time,
+//! using the site connected at the bottom of the panel.
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, Utc};
+use dioxus::prelude::*;
+use crate::models::{PipelineJob, Publication, PublicationStatus, PublishPlatform, WordPressConfig, month_weeks, shift_month, error_message};
+use crate::server_functions::{delete_publication, get_content_jobs, get_publications, get_wordpress_config, publish_now, save_publication, save_wordpress_config};
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const DATETIME_INPUT_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+fn status_class(status: PublicationStatus) -> &'static str {
+    match status {
+        PublicationStatus::Planned => "bg-slate-600 text-slate-100",
+        PublicationStatus::Draft => "bg-amber-700 text-amber-50",
+        PublicationStatus::Scheduled => "bg-blue-700 text-blue-50",
+        PublicationStatus::Published => "bg-green-700 text-green-50",
+        PublicationStatus::Failed => "bg-red-700 text-red-50",
+    }
+}
+
+/// Status, platform and local time of an entry in the month grid
+fn entry_tooltip(publication: &Publication) -> String {
+    format!(
+        "{} · {} · {}",
+        publication.status.display_name(),
+        publication.platform.display_name(),
+        publication.date.with_timezone(&Local).format("%H:%M")
+    )
+}
+
+/// A new entry on `day` at 9:00 local time
+fn entry_for_day(day: NaiveDate) -> Publication {
+    let date = day.and_hms_opt(9, 0, 0)
+        .and_then(|at| at.and_local_timezone(Local).earliest())
+        .map(|at| at.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now);
+    Publication::new("", date)
+}
+
+#[component]
+pub fn CalendarPanel() -> Element {
+    let today = Local::now().date_naive();
+    let mut month: Signal<(i32, u32)> = use_signal(|| (today.year(), today.month()));
+    let mut publications: Signal<Vec<Publication>> = use_signal(Vec::new);
+    let mut editing: Signal<Option<Publication>> = use_signal(|| None);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+    let jobs = use_resource(|| async move { get_content_jobs().await.unwrap_or_default() });
+
+    let refresh = move || {
+        spawn(async move {
+            match get_publications().await {
+                Ok(latest) => publications.set(latest),
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+        });
+    };
+    use_effect(move || refresh());
+
+    let (year, month_number) = month();
+    let month_name = NaiveDate::from_ymd_opt(year, month_number, 1)
+        .map(|d| d.format("%B %Y").to_string())
+        .unwrap_or_default();
+
+    rsx! {
+        div {
+            class: "flex-1 overflow-y-auto p-6",
+            div {
+                class: "max-w-5xl mx-auto space-y-4",
+
+                div {
+                    class: "flex items-center justify-between",
+                    div {
+                        h2 { class: "text-lg font-semibold text-white", "Content Calendar" }
+                        p {
+                            class: "text-sm text-slate-400",
+                            "Plan topics, track drafts and schedule posts. Scheduled WordPress posts are published automatically."
+                        }
+                    }
+                    div {
+                        class: "flex items-center gap-2",
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                            onclick: move |_| month.set(shift_month(year, month_number, -1)),
+                            "‹"
+                        }
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                            onclick: move |_| month.set((today.year(), today.month())),
+                            "Today"
+                        }
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                            onclick: move |_| month.set(shift_month(year, month_number, 1)),
+                            "›"
+                        }
+                        span { class: "w-36 text-center text-sm font-medium text-white", "{month_name}" }
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-blue-600 hover:bg-blue-700 text-white rounded-lg transition-colors",
+                            onclick: move |_| editing.set(Some(entry_for_day(today))),
+                            "Add entry"
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex flex-wrap gap-2 text-xs",
+                    for status in PublicationStatus::all().iter() {
+                        span {
+                            key: "{status.id()}",
+                            class: "px-2 py-0.5 rounded {status_class(*status)}",
+                            "{status.display_name()}"
+                        }
+                    }
+                }
+
+                if let Some(message) = error() {
+                    p { class: "text-sm text-red-400", "{message}" }
+                }
+
+                div {
+                    class: "grid grid-cols-7 gap-px bg-slate-700 rounded-lg overflow-hidden text-xs",
+                    for weekday in WEEKDAYS {
+                        div { key: "{weekday}", class: "bg-slate-800 px-2 py-1 text-slate-400 font-medium", "{weekday}" }
+                    }
+                    for day in month_weeks(year, month_number).into_iter().flatten() {
+                        div {
+                            key: "{day}",
+                            class: if day.month() == month_number { "bg-slate-900 min-h-24 p-1 cursor-pointer hover:bg-slate-800" } else { "bg-slate-900/60 min-h-24 p-1 cursor-pointer hover:bg-slate-800 opacity-50" },
+                            onclick: move |_| editing.set(Some(entry_for_day(day))),
+                            div {
+                                class: if day == today { "w-6 h-6 flex items-center justify-center rounded-full bg-blue-600 text-white" } else { "w-6 h-6 flex items-center justify-center text-slate-400" },
+                                "{day.day()}"
+                            }
+                            for publication in publications().into_iter().filter(|p| p.date.with_timezone(&Local).date_naive() == day) {
+                                button {
+                                    key: "{publication.id}",
+                                    class: "w-full mt-1 px-1.5 py-0.5 rounded text-left truncate {status_class(publication.status)}",
+                                    title: "{entry_tooltip(&publication)}",
+                                    onclick: move |e| {
+                                        e.stop_propagation();
+                                        editing.set(Some(publication.clone()));
+                                    },
+                                    "{publication.title}"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if let Some(publication) = editing() {
+                    PublicationEditor {
+                        key: "{publication.id}",
+                        publication,
+                        jobs: jobs().unwrap_or_default(),
+                        on_done: move |_| {
+                            editing.set(None);
+                            refresh();
+                        },
+                    }
+                }
+
+                WordPressSettings {}
+            }
+        }
+    }
+}
+
+/// Form for one entry; `on_done` runs after it was saved, published,
+/// deleted or closed
+#[component]
+fn PublicationEditor(publication: Publication, jobs: Vec<PipelineJob>, on_done: EventHandler<()>) -> Element {
+    let mut draft = use_signal(|| publication.clone());
+    let mut busy = use_signal(|| false);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+    let is_new = publication.title.is_empty();
+
+    let current = draft();
+    let date_value = current.date.with_timezone(&Local).format(DATETIME_INPUT_FORMAT).to_string();
+    let input_class = "w-full px-3 py-2 bg-slate-900 border border-slate-700 rounded-lg text-sm text-white focus:outline-none focus:border-blue-500";
+    let schedule_hint = match (current.status, current.platform.can_auto_publish()) {
+        (PublicationStatus::Scheduled, true) => Some("Published automatically at this time."),
+        (PublicationStatus::Scheduled, false) => Some("A reminder only; publish it by hand and add the URL."),
+        _ => None,
+    };
+
+    let save = move |_| {
+        busy.set(true);
+        spawn(async move {
+            match save_publication(draft()).await {
+                Ok(_) => on_done.call(()),
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+            busy.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 { class: "text-sm font-semibold text-white", if is_new { "New entry" } else { "Edit entry" } }
+
+            input {
+                class: input_class,
+                placeholder: "Title or topic",
+                value: "{current.title}",
+                oninput: move |e| draft.write().title = e.value(),
+            }
+
+            div {
+                class: "grid grid-cols-3 gap-3",
+                label {
+                    class: "text-xs text-slate-400 space-y-1",
+                    span { "Status" }
+                    select {
+                        class: input_class,
+                        onchange: move |e| {
+                            if let Some(status) = PublicationStatus::from_id(&e.value()) {
+                                draft.write().status = status;
+                            }
+                        },
+                        for status in PublicationStatus::all().iter() {
+                            option {
+                                key: "{status.id()}",
+                                value: "{status.id()}",
+                                selected: current.status == *status,
+                                "{status.display_name()}"
+                            }
+                        }
+                    }
+                }
+                label {
+                    class: "text-xs text-slate-400 space-y-1",
+                    span { "Platform" }
+                    select {
+                        class: input_class,
+                        onchange: move |e| draft.write().platform = PublishPlatform::from_id(&e.value()),
+                        for platform in PublishPlatform::all().iter() {
+                            option {
+                                key: "{platform.id()}",
+                                value: "{platform.id()}",
+                                selected: current.platform == *platform,
+                                "{platform.display_name()}"
+                            }
+                        }
+                    }
+                }
+                label {
+                    class: "text-xs text-slate-400 space-y-1",
+                    span { "Date" }
+                    input {
+                        class: input_class,
+                        r#type: "datetime-local",
+                        value: "{date_value}",
+                        onchange: move |e| {
+                            let date = NaiveDateTime::parse_from_str(&e.value(), DATETIME_INPUT_FORMAT)
+                                .ok()
+                                .and_then(|at| at.and_local_timezone(Local).earliest());
+                            if let Some(date) = date {
+                                draft.write().date = DateTime::<Utc>::from(date);
+                            }
+                        },
+                    }
+                }
+            }
+
+            if let Some(hint) = schedule_hint {
+                p { class: "text-xs text-slate-400", "{hint}" }
+            }
+
+            label {
+                class: "block text-xs text-slate-400 space-y-1",
+                span { "Content package" }
+                select {
+                    class: input_class,
+                    onchange: move |e| draft.write().job_id = uuid::Uuid::parse_str(&e.value()).ok(),
+                    option { value: "", selected: current.job_id.is_none(), "None" }
+                    for job in jobs.iter() {
+                        option {
+                            key: "{job.progress.job_id}",
+                            value: "{job.progress.job_id}",
+                            selected: current.job_id == Some(job.progress.job_id),
+                            "{job.progress.topic} ({job.progress.percent()}%)"
+                        }
+                    }
+                }
+            }
+
+            input {
+                class: input_class,
+                placeholder: "Published URL",
+                value: "{current.url.clone().unwrap_or_default()}",
+                oninput: move |e| {
+                    let url = e.value();
+                    draft.write().url = (!url.trim().is_empty()).then_some(url);
+                },
+            }
+
+            textarea {
+                class: "{input_class} h-20 resize-none",
+                placeholder: "Notes",
+                value: "{current.notes}",
+                oninput: move |e| draft.write().notes = e.value(),
+            }
+
+            if let Some(failure) = current.error.as_ref().filter(|_| current.status == PublicationStatus::Failed) {
+                p { class: "text-xs text-red-400", "Last attempt failed: {failure}" }
+            }
+            if let Some(message) = error() {
+                p { class: "text-sm text-red-400", "{message}" }
+            }
+
+            div {
+                class: "flex items-center gap-2",
+                button {
+                    class: "px-3 py-1.5 text-sm bg-blue-600 hover:bg-blue-700 disabled:opacity-50 text-white rounded-lg transition-colors",
+                    disabled: busy(),
+                    onclick: save,
+                    "Save"
+                }
+                if current.platform.can_auto_publish() && current.status != PublicationStatus::Published {
+                    button {
+                        class: "px-3 py-1.5 text-sm bg-green-600 hover:bg-green-700 disabled:opacity-50 text-white rounded-lg transition-colors",
+                        disabled: busy() || current.job_id.is_none(),
+                        title: "Publish the content package now",
+                        onclick: move |_| {
+                            busy.set(true);
+                            spawn(async move {
+                                match publish_now(draft()).await {
+                                    Ok(_) => on_done.call(()),
+                                    Err(e) => error.set(Some(error_message(&e))),
+                                }
+                                busy.set(false);
+                            });
+                        },
+                        if busy() { "Publishing..." } else { "Publish now" }
+                    }
+                }
+                if !is_new {
+                    button {
+                        class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-red-700 disabled:opacity-50 rounded-lg transition-colors",
+                        disabled: busy(),
+                        onclick: move |_| {
+                            let id = draft().id.to_string();
+                            spawn(async move {
+                                match delete_publication(id).await {
+                                    Ok(_) => on_done.call(()),
+                                    Err(e) => error.set(Some(error_message(&e))),
+                                }
+                            });
+                        },
+                        "Delete"
+                    }
+                }
+                button {
+                    class: "ml-auto px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                    onclick: move |_| on_done.call(()),
+                    "Close"
+                }
+            }
+        }
+    }
+}
+
+/// Connection to the WordPress site scheduled posts are published to
+#[component]
+fn WordPressSettings() -> Element {
+    let mut config: Signal<WordPressConfig> = use_signal(WordPressConfig::default);
+    let mut status: Signal<Option<Result<String, String>>> = use_signal(|| None);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(saved) = get_wordpress_config().await {
+                config.set(saved);
+            }
+        });
+    });
+
+    let current = config();
+    let input_class = "w-full px-3 py-2 bg-slate-900 border border-slate-700 rounded-lg text-sm text-white focus:outline-none focus:border-blue-500";
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            div {
+                h3 { class: "text-sm font-semibold text-white", "WordPress" }
+                p {
+                    class: "text-xs text-slate-400",
+                    "Create an application password under Users → Profile on your site. Only the article text is published; images stay in the package."
+                }
+            }
+            div {
+                class: "grid grid-cols-3 gap-3",
+                input {
+                    class: input_class,
+                    placeholder: "https://blog.example.com",
+                    value: "{current.site_url}",
+                    oninput: move |e| config.write().site_url = e.value(),
+                }
+                input {
+                    class: input_class,
+                    placeholder: "Username",
+                    value: "{current.username}",
+                    oninput: move |e| config.write().username = e.value(),
+                }
+                input {
+                    class: input_class,
+                    r#type: "password",
+                    placeholder: "Application password",
+                    value: "{current.app_password}",
+                    oninput: move |e| config.write().app_password = e.value(),
+                }
+            }
+            div {
+                class: "flex items-center gap-3",
+                button {
+                    class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                    onclick: move |_| {
+                        spawn(async move {
+                            let result = save_wordpress_config(config()).await
+                                .map(|_| "Saved".to_string())
+                                .map_err(error_message);
+                            status.set(Some(result));
+                        });
+                    },
+                    "Save connection"
+                }
+                match status() {
+                    Some(Ok(message)) => rsx! { span { class: "text-xs text-green-400", "{message}" } },
+                    Some(Err(message)) => rsx! { span { class: "text-xs text-red-400", "{message}" } },
+                    None => rsx! {},
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Missing Tool Notices
+//!
+//! The optional tools found at startup are loaded once and shared with all
+//! children. Panels whose feature can't work show what to install, with a
+//! button to look again once it is installed.
+
+use dioxus::prelude::*;
+use crate::models::{Capabilities, Feature, Tool, error_message};
+use crate::server_functions::{get_capabilities, refresh_capabilities};
+
+/// The detected tools, None until they are loaded
+#[derive(Clone, Copy)]
+struct DetectedTools(Signal<Option<Capabilities>>);
+
+/// Loads the detected tools for all children
+pub fn use_capabilities_provider() {
+    let mut detected: Signal<Option<Capabilities>> = use_signal(|| None);
+    use_context_provider(|| DetectedTools(detected));
+    use_effect(move || {
+        spawn(async move {
+            match get_capabilities().await {
+                Ok(capabilities) => detected.set(Some(capabilities)),
+                Err(e) => tracing::error!("Error loading capabilities: {}", error_message(&e)),
+            }
+        });
+    });
+}
+
+/// Whether the feature's tools were found; true until known otherwise
+pub fn use_feature_available(feature: Feature) -> bool {
+    try_consume_context::<DetectedTools>()
+        .and_then(|detected| detected.0())
+        .is_none_or(|capabilities| capabilities.supports(feature))
+}
+
+/// Whether the tool was found; true until known otherwise
+pub fn use_tool_available(tool: Tool) -> bool {
+    try_consume_context::<DetectedTools>()
+        .and_then(|detected| detected.0())
+        .is_none_or(|capabilities| capabilities.has(tool))
+}
+
+/// Looks for the tools again and shares what was found
+fn check_again(mut detected: Signal<Option<Capabilities>>, mut checking: Signal<bool>) {
+    checking.set(true);
+    spawn(async move {
+        match refresh_capabilities().await {
+            Ok(capabilities) => detected.set(Some(capabilities)),
+            Err(e) => tracing::error!("Error detecting tools: {}", error_message(&e)),
+        }
+        checking.set(false);
+    });
+}
+
+/// Install steps for the feature's missing tools; nothing when it works
+#[component]
+pub fn MissingTools(feature: Feature) -> Element {
+    let checking = use_signal(|| false);
+    let Some(DetectedTools(detected)) = try_consume_context::<DetectedTools>() else {
+        return rsx! {};
+    };
+    let missing = detected().map(|capabilities| capabilities.missing_for(feature)).unwrap_or_default();
+    if missing.is_empty() {
+        return rsx! {};
+    }
+    let (_, needs_all) = feature.requirements();
+
+    rsx! {
+        div {
+            class: "px-4 py-3 rounded-lg bg-amber-900/30 border border-amber-700 text-sm space-y-2",
+            div {
+                class: "flex items-start justify-between gap-3",
+                div {
+                    p { class: "font-medium text-amber-200", "{feature.name()} is not available" }
+                    p {
+                        class: "text-amber-300 text-xs",
+                        if needs_all { "Install the following, then check again:" } else { "Install one of the following, then check again:" }
+                    }
+                }
+                button {
+                    class: "shrink-0 px-3 py-1 rounded-md bg-amber-700 hover:bg-amber-600 text-white text-xs disabled:opacity-50",
+                    disabled: checking(),
+                    onclick: move |_| check_again(detected, checking),
+                    if checking() { "Checking..." } else { "Check again" }
+                }
+            }
+            ul {
+                class: "space-y-1",
+                for tool in missing {
+                    li {
+                        key: "{tool.name()}",
+                        span { class: "text-amber-100", "{tool.name()}" }
+                        span { class: "text-amber-400 text-xs", " — {tool.purpose()}" }
+                        code { class: "block mt-0.5 px-2 py-1 rounded bg-slate-900 text-xs text-slate-300 select-all break-words", "{tool.install_hint()}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Every optional tool, whether it was found and how to install it
+#[component]
+pub fn OptionalTools() -> Element {
+    let checking = use_signal(|| false);
+    let Some(DetectedTools(detected)) = try_consume_context::<DetectedTools>() else {
+        return rsx! {};
+    };
+    let tools = detected().map(|capabilities| capabilities.tools).unwrap_or_default();
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            div {
+                class: "flex items-center justify-between",
+                h3 { class: "text-sm font-medium text-slate-300", "Optional tools" }
+                button {
+                    class: "px-3 py-1 text-xs bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors disabled:opacity-50",
+                    disabled: checking(),
+                    onclick: move |_| check_again(detected, checking),
+                    if checking() { "Checking..." } else { "Check again" }
+                }
+            }
+            if tools.is_empty() {
+                p { class: "text-xs text-slate-500", "Looking for tools..." }
+            }
+            for status in tools {
+                div {
+                    key: "{status.tool.name()}",
+                    class: "text-xs",
+                    div {
+                        class: "flex items-center gap-2",
+                        span { class: if status.available { "text-green-400" } else { "text-slate-500" }, if status.available { "✓" } else { "✕" } }
+                        span { class: "text-slate-200", "{status.tool.name()}" }
+                        span { class: "text-slate-500 truncate", "{status.tool.purpose()}" }
+                    }
+                    if let Some(detail) = status.detail.clone() {
+                        p { class: "ml-5 text-slate-500 break-all", "{detail}" }
+                    }
+                    if !status.available {
+                        code { class: "block ml-5 mt-0.5 text-slate-400 select-all break-words", "{status.tool.install_hint()}" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -4,9 +4,12 @@
 
 use dioxus::prelude::*;
 use dioxus::html::input_data::keyboard_types::Key;
-use crate::models::{ChatMessage, Session, AppSettings};
-use crate::server_functions::{get_response, reset_chat, search_context, init_llm_model, init_embedding_model, init_db, init_sqlite_db, create_session, save_message, update_session_title, get_sessions};
-use super::Message;
+use crate::models::{AutoScroll, ChatAttachment, ChatMessage, PlaybackQueue, GenerationStats, Session, AppSettings, RetrievalOptions, ToolRun, AttachmentMode, CODE_REFERENCES_HEADING, MAX_ATTACHMENT_BYTES, SOURCES_HEADING, continuation_prompt, get_available_models, keyword_title, TitleMethod, format_web_results, format_web_sources, format_code_references, parse_document_tags, parse_filter_date, SourceType, QUERY_HISTORY_MESSAGES, error_message};
+use crate::server_functions::{get_response, get_llm_queue, reset_chat, search_context, init_llm_model, init_embedding_model, init_db, init_sqlite_db, create_session, save_message, update_session_title, get_sessions, generate_session_title, recall_memories, remember_from_message, get_tool_response, response_was_truncated, search_web, prepare_chat_attachment, get_current_model, list_capture_collections, list_codebases, list_document_tags};
+use super::{ErrorNotice, Message, SessionSettingsBar, SessionSummaryCard, tag_sessions};
+use super::i18n::t;
+use super::clipboard::use_pasted_files;
+use super::status_bar::sleep_ms;
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
@@ -19,6 +22,32 @@ struct ChatState {
     is_database_loading: bool,
     cancel_token: bool,
     use_context: bool,
+    use_tools: bool,
+    use_web: bool,
+    /// Per-session opt-in for the code sandbox tool
+    allow_code_execution: bool,
+    /// Files ready to go with the next message
+    attachments: Vec<ChatAttachment>,
+    /// Names of files still being read or summarized
+    attaching: Vec<String>,
+    attachment_error: Option<String>,
+    /// Why the last answer could not be generated
+    response_error: Option<String>,
+    /// Retrieval settings for the next message only, which then uses the
+    /// knowledge base even if "Use Context" is off
+    retrieval_override: Option<RetrievalOptions>,
+    /// Split-view pane the chat is shown in
+    pane: usize,
+}
+
+/// ID of one of the chat's elements, unique per split-view pane. The first
+/// pane keeps the plain IDs.
+fn pane_id(base: &str, pane: usize) -> String {
+    if pane == 0 {
+        base.to_string()
+    } else {
+        format!("{}-{}", base, pane)
+    }
 }
 
 #[component]
@@ -29,6 +58,13 @@ pub fn Chat(
     is_loading: Signal<bool>,
     model_ready: Signal<bool>,
     settings: Signal<AppSettings>,
+    /// Attachments added from other panels, moved into the message draft
+    pending_attachments: Signal<Vec<ChatAttachment>>,
+    /// Messages read aloud, shared by the chats of a split view
+    playback: Signal<PlaybackQueue>,
+    /// Split-view pane, so two chats can be open side by side
+    #[props(default = 0)]
+    pane: usize,
 ) -> Element {
     let mut state = use_signal(|| ChatState {
         input_message: String::new(),
@@ -37,6 +73,23 @@ pub fn Chat(
         is_database_loading: true,
         cancel_token: false,
         use_context: false,
+        use_tools: false,
+        use_web: false,
+        allow_code_execution: false,
+        attachments: Vec::new(),
+        attaching: Vec::new(),
+        attachment_error: None,
+        response_error: None,
+        retrieval_override: None,
+        pane,
+    });
+    let ime = use_ime_state();
+    // Enter and leave also fire for every child the drag crosses, so they
+    // are counted to know when files are over the chat
+    let mut drag_depth: Signal<u32> = use_signal(|| 0);
+
+    use_pasted_files(pane_id("chat-panel", pane), move |file| {
+        spawn(attach_bytes(state, file.name, file.data));
     });
 
     use_effect(move || {
@@ -44,8 +97,34 @@ pub fn Chat(
     });
 
     use_effect(move || {
-        if !messages().is_empty() {
-            scroll_to_bottom();
+        if !pending_attachments.read().is_empty() {
+            let added = std::mem::take(&mut *pending_attachments.write());
+            state.write().attachments.extend(added);
+        }
+    });
+
+    // Code execution must be re-enabled for every session
+    use_effect(move || {
+        let _session_id = current_session().map(|s| s.id);
+        if state.peek().allow_code_execution {
+            state.write().allow_code_execution = false;
+        }
+    });
+
+    // Streaming text only scrolls the chat as the setting allows, so
+    // earlier text can be read meanwhile; a new message always scrolls
+    let at_bottom = use_at_bottom(pane);
+    let mut message_count: Signal<usize> = use_signal(|| 0);
+    use_effect(move || {
+        let count = messages().len();
+        if count == 0 {
+            return;
+        }
+        let added = count != *message_count.peek();
+        message_count.set(count);
+        let mode = settings.peek().auto_scroll;
+        if (added && mode != AutoScroll::Never) || mode.follows(*at_bottom.peek()) {
+            scroll_to_bottom(pane);
         }
     });
 
@@ -53,21 +132,47 @@ pub fn Chat(
 
     rsx! {
         div {
-            class: "flex-1 flex flex-col h-full bg-gradient-to-b from-slate-900 via-slate-800 to-slate-900",
+            id: pane_id("chat-panel", pane),
+            class: "relative flex-1 min-h-0 flex flex-col bg-gradient-to-b from-slate-900 via-slate-800 to-slate-900",
+            ondragenter: move |_| drag_depth += 1,
+            ondragleave: move |_| drag_depth.set(drag_depth().saturating_sub(1)),
+            ondragover: move |e: DragEvent| e.prevent_default(),
+            ondrop: move |e: DragEvent| {
+                e.prevent_default();
+                drag_depth.set(0);
+                for file in e.files() {
+                    spawn(attach_file(state, file));
+                }
+            },
+
+            if drag_depth() > 0 {
+                div {
+                    class: "absolute inset-3 z-40 flex items-center justify-center rounded-2xl border-2 border-dashed border-blue-500 bg-slate-900/80 pointer-events-none",
+                    p { class: "text-lg text-blue-300", {t("chat.drop_files")} }
+                }
+            }
 
             // Loading overlay
             if is_loading_state {
                 { render_loading_screen() }
             }
 
+            SessionSettingsBar { current_session: current_session, sessions: sessions }
+
             // Messages area - centered with max width
             div {
-                id: "chat-container",
+                id: pane_id("chat-container", pane),
                 class: "flex-1 overflow-y-auto",
 
                 div {
                     class: "max-w-3xl mx-auto px-4 py-6",
 
+                    SessionSummaryCard {
+                        current_session: current_session,
+                        sessions: sessions,
+                        message_count: messages().len(),
+                    }
+
                     if messages().is_empty() {
                         { render_empty_state() }
                     } else {
@@ -79,15 +184,30 @@ pub fn Chat(
                                     messages: messages,
                                     index: index,
                                     settings: settings,
+                                    playback: playback,
+                                    on_continue: move |id| continue_response(state, messages, id),
                                 }
                             }
                         }
                     }
                 }
+
+                // Stays at the bottom of the view while scrolled up
+                if !at_bottom() && !messages().is_empty() {
+                    div {
+                        class: "sticky bottom-4 flex justify-center pointer-events-none",
+                        button {
+                            class: "pointer-events-auto px-4 py-1.5 rounded-full bg-blue-600 hover:bg-blue-500 text-sm text-white shadow-lg",
+                            onclick: move |_| scroll_to_bottom(pane),
+                            "↓ "
+                            {t("chat.jump_to_latest")}
+                        }
+                    }
+                }
             }
 
             // Input area - fixed at bottom
-            { render_input_area(&state, &messages, &current_session, &sessions, &settings) }
+            { render_input_area(&state, &messages, &current_session, &sessions, &settings, ime) }
         }
     }
 }
@@ -153,7 +273,7 @@ fn render_empty_state() -> Element {
 fn render_loading_screen() -> Element {
     rsx! {
         div {
-            class: "absolute inset-0 bg-slate-900/95 backdrop-blur-sm flex flex-col items-center justify-center z-50",
+            class: "fixed inset-0 bg-slate-900/95 backdrop-blur-sm flex flex-col items-center justify-center z-50",
 
             // Animated loading indicator with glow effect
             div {
@@ -249,24 +369,27 @@ fn render_input_area(
     current_session: &Signal<Option<Session>>,
     sessions: &Signal<Vec<Session>>,
     settings: &Signal<AppSettings>,
+    ime: ImeState,
 ) -> Element {
     let current_state = state.read();
+    let send_shortcut = settings.read().send_shortcut;
     let is_disabled = current_state.is_model_answering ||
                       current_state.is_model_loading ||
                       current_state.is_database_loading;
 
     let placeholder = if current_state.is_model_loading || current_state.is_database_loading {
-        "Initializing..."
+        t("chat.initializing")
     } else if current_state.is_model_answering {
-        "AI is thinking..."
+        t("chat.thinking")
     } else {
-        "Type your message..."
+        t("chat.placeholder")
     };
 
     let is_answering = current_state.is_model_answering;
     let is_loading = current_state.is_model_loading || current_state.is_database_loading;
-    let is_empty = current_state.input_message.trim().is_empty();
-    let can_send = !is_loading && !is_empty;
+    let is_empty = current_state.input_message.trim().is_empty() && current_state.attachments.is_empty();
+    let is_attaching = !current_state.attaching.is_empty();
+    let can_send = !is_loading && !is_empty && !is_attaching;
 
     rsx! {
         div {
@@ -275,40 +398,17 @@ fn render_input_area(
             div {
                 class: "max-w-3xl mx-auto p-4",
 
-                // RAG Toggle
+                // RAG, tool and web search toggles
                 div {
                     class: "flex items-center justify-between mb-3",
 
-                    label {
-                        class: "flex items-center gap-3 cursor-pointer group",
-
-                        div {
-                            class: "relative",
-                            input {
-                                disabled: is_disabled,
-                                r#type: "checkbox",
-                                class: "sr-only peer",
-                                checked: "{current_state.use_context}",
-                                onchange: {
-                                    let mut state = state.clone();
-                                    move |e| {
-                                        let mut new_state = state.read().clone();
-                                        new_state.use_context = e.value().parse::<bool>().unwrap_or(false);
-                                        state.set(new_state);
-                                    }
-                                },
-                            }
-                            div {
-                                class: "w-9 h-5 bg-slate-700 rounded-full peer peer-checked:bg-blue-600 transition-colors"
-                            }
-                            div {
-                                class: "absolute left-0.5 top-0.5 w-4 h-4 bg-white rounded-full transition-transform peer-checked:translate-x-4"
-                            }
-                        }
-
-                        span {
-                            class: "text-sm text-slate-400 group-hover:text-slate-300 transition-colors",
-                            "Use Context (RAG)"
+                    div {
+                        class: "flex items-center gap-5",
+                        { render_toggle(*state, is_disabled, current_state.use_context, "Use Context (RAG)", |s, v| s.use_context = v) }
+                        { render_toggle(*state, is_disabled, current_state.use_tools, "Use Tools", |s, v| s.use_tools = v) }
+                        { render_toggle(*state, is_disabled, current_state.use_web, "Search Web", |s, v| s.use_web = v) }
+                        if current_state.use_tools {
+                            { render_toggle(*state, is_disabled, current_state.allow_code_execution, "Allow Code Execution", |s, v| s.allow_code_execution = v) }
                         }
                     }
 
@@ -322,10 +422,14 @@ fn render_input_area(
                         disabled: is_loading || is_answering,
                         onclick: {
                             let mut messages = messages.clone();
+                            let current_session = *current_session;
                             move |_| {
+                                let session_id = current_session.read().as_ref().map(|s| s.id.to_string());
                                 spawn(async move {
-                                    if let Err(e) = reset_chat().await {
-                                        println!("Error resetting chat: {:?}", e);
+                                    if let Some(session_id) = session_id {
+                                        if let Err(e) = reset_chat(session_id).await {
+                                            tracing::error!("Error resetting chat: {:?}", e);
+                                        }
                                     }
                                     messages.set(Vec::new());
                                 });
@@ -335,15 +439,58 @@ fn render_input_area(
                     }
                 }
 
+                if is_answering && messages.read().last().is_some_and(|m| m.content.is_empty()) {
+                    if let Some(session) = current_session.read().as_ref() {
+                        QueuePosition { key: "{session.id}", owner: session.id.to_string() }
+                    }
+                }
+
+                if let Some(error) = current_state.response_error.clone() {
+                    ErrorNotice {
+                        error: error,
+                        on_dismiss: {
+                            let mut state = *state;
+                            move |_| state.write().response_error = None
+                        },
+                    }
+                }
+
+                { render_attachments(*state) }
+
                 // Input container
                 div {
                     class: "relative flex items-end gap-3",
 
+                    // Attach button
+                    label {
+                        class: if is_disabled {
+                            "w-12 h-12 rounded-xl bg-slate-800 border border-slate-700 flex items-center justify-center text-slate-600 cursor-not-allowed"
+                        } else {
+                            "w-12 h-12 rounded-xl bg-slate-800 border border-slate-700 flex items-center justify-center text-slate-400 hover:text-white cursor-pointer transition-colors"
+                        },
+                        title: t("chat.attach_files"),
+                        "📎"
+                        input {
+                            r#type: "file",
+                            class: "hidden",
+                            multiple: true,
+                            disabled: is_disabled,
+                            onchange: {
+                                let state = *state;
+                                move |e: FormEvent| {
+                                    for file in e.files() {
+                                        spawn(attach_file(state, file));
+                                    }
+                                }
+                            },
+                        }
+                    }
+
                     // Textarea
                     div {
                         class: "flex-1 relative",
                         textarea {
-                            id: "message-input",
+                            id: pane_id("message-input", current_state.pane),
                             rows: "1",
                             class: "w-full px-4 py-3 bg-slate-800 border border-slate-700 rounded-2xl text-white placeholder-slate-500 resize-none focus:outline-none focus:border-blue-500 focus:ring-1 focus:ring-blue-500 transition-all min-h-[48px] max-h-[200px]",
                             placeholder: placeholder,
@@ -357,19 +504,24 @@ fn render_input_area(
                                     state.set(new_state);
                                 }
                             },
+                            oncompositionstart: move |_| ime.start(),
+                            oncompositionend: move |_| ime.end(),
                             onkeydown: {
                                 let state = state.clone();
                                 let messages = messages.clone();
                                 let session = current_session.clone();
                                 let sessions = sessions.clone();
                                 let settings = settings.clone();
-                                move |event| {
-                                    if event.key() == Key::Enter && !event.modifiers().shift() {
+                                move |event: KeyboardEvent| {
+                                    let modifiers = event.modifiers();
+                                    let ctrl_or_cmd = modifiers.ctrl() || modifiers.meta();
+                                    if ime.is_enter(&event) && send_shortcut.sends(ctrl_or_cmd, modifiers.shift()) {
                                         event.prevent_default();
                                         let current = state.read().clone();
                                         // Only send if model is ready and input is not empty
                                         let is_ready = !current.is_model_loading && !current.is_database_loading;
-                                        if is_ready && !current.input_message.trim().is_empty() {
+                                        let has_content = !current.input_message.trim().is_empty() || !current.attachments.is_empty();
+                                        if is_ready && has_content && current.attaching.is_empty() {
                                             spawn(handle_message_send(state.clone(), messages.clone(), session.clone(), sessions.clone(), settings.clone()));
                                         }
                                     }
@@ -378,6 +530,8 @@ fn render_input_area(
                         }
                     }
 
+                    RetrievalPopover { state: *state, disabled: is_disabled }
+
                     // Send button
                     button {
                         class: if is_answering {
@@ -434,15 +588,336 @@ fn render_input_area(
                 // Hint text
                 p {
                     class: "text-xs text-slate-500 mt-2 text-center",
-                    "Press Enter to send, Shift+Enter for new line"
+                    {t(send_shortcut.hint_key())}
+                }
+            }
+        }
+    }
+}
+
+/// Files attached to the next message, those still being prepared, and the
+/// last attachment error
+fn render_attachments(mut state: Signal<ChatState>) -> Element {
+    let current = state.read().clone();
+    if current.attachments.is_empty() && current.attaching.is_empty() && current.attachment_error.is_none() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "flex flex-wrap items-center gap-2 mb-3 text-xs",
+            for attachment in current.attachments {
+                div {
+                    key: "{attachment.id}",
+                    class: "flex items-center gap-2 px-3 py-1.5 rounded-lg bg-slate-800 border border-slate-700 text-slate-300",
+                    span { "📎 {attachment.name}" }
+                    span {
+                        class: "text-slate-500",
+                        if attachment.mode == AttachmentMode::Summary { "summarized" } else { "inline" }
+                    }
+                    button {
+                        class: "text-slate-500 hover:text-red-400",
+                        title: "Remove",
+                        onclick: move |_| state.write().attachments.retain(|a| a.id != attachment.id),
+                        "✕"
+                    }
+                }
+            }
+            for name in current.attaching {
+                div {
+                    class: "flex items-center gap-2 px-3 py-1.5 rounded-lg bg-slate-800/60 border border-slate-700 text-slate-400 animate-pulse",
+                    "Reading {name}…"
+                }
+            }
+            if let Some(error) = current.attachment_error {
+                div {
+                    class: "flex items-center gap-2 text-red-400",
+                    "{error}"
+                    button {
+                        class: "text-slate-500 hover:text-slate-300",
+                        onclick: move |_| state.write().attachment_error = None,
+                        "✕"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Uploads a dropped or picked file and adds it to the pending attachments
+async fn attach_file(mut state: Signal<ChatState>, file: dioxus::html::FileData) {
+    let name = file.name();
+    if file.size() > MAX_ATTACHMENT_BYTES as u64 {
+        state.write().attachment_error = Some(format!("{} is too large to attach", name));
+        return;
+    }
+    match file.read_bytes().await {
+        Ok(bytes) => attach_bytes(state, name, bytes.to_vec()).await,
+        Err(e) => state.write().attachment_error = Some(format!("Could not read {}: {}", name, e)),
+    }
+}
+
+/// Uploads a file's contents, such as a pasted one, and adds it to the
+/// pending attachments. Long files are summarized on the server, which can
+/// take a while.
+async fn attach_bytes(mut state: Signal<ChatState>, name: String, data: Vec<u8>) {
+    if data.len() > MAX_ATTACHMENT_BYTES {
+        state.write().attachment_error = Some(format!("{} is too large to attach", name));
+        return;
+    }
+
+    state.write().attaching.push(name.clone());
+    let result = prepare_chat_attachment(name.clone(), data).await.map_err(error_message);
+
+    let mut current = state.write();
+    if let Some(pos) = current.attaching.iter().position(|n| *n == name) {
+        current.attaching.remove(pos);
+    }
+    match result {
+        Ok(attachment) => {
+            current.attachments.push(attachment);
+            current.attachment_error = None;
+        }
+        Err(e) => current.attachment_error = Some(e),
+    }
+}
+
+/// Switch in the input toolbar bound to one `ChatState` flag
+/// Per-message retrieval settings, opened from a button next to Send
+#[component]
+fn RetrievalPopover(mut state: Signal<ChatState>, disabled: bool) -> Element {
+    let mut open = use_signal(|| false);
+    let collections = use_resource(|| async move { list_capture_collections().await.unwrap_or_default() });
+    let codebases = use_resource(|| async move { list_codebases().await.unwrap_or_default() });
+    let known_tags = use_resource(|| async move { list_document_tags().await.unwrap_or_default() });
+    let mut tag_text = use_signal(String::new);
+
+    // The override is cleared once a message is sent
+    use_effect(move || {
+        if state.read().retrieval_override.is_none() {
+            tag_text.set(String::new());
+        }
+    });
+
+    let current = state.read().retrieval_override.clone();
+    let active = current.is_some();
+    let options = current.unwrap_or_default();
+    let mut update = move |change: &dyn Fn(&mut RetrievalOptions)| {
+        let mut options = state.peek().retrieval_override.clone().unwrap_or_default();
+        change(&mut options);
+        state.write().retrieval_override = Some(options);
+    };
+
+    rsx! {
+        div {
+            class: "relative",
+            button {
+                class: if active {
+                    "w-12 h-12 rounded-xl bg-blue-600/20 border border-blue-500 flex items-center justify-center text-blue-300 transition-colors"
+                } else {
+                    "w-12 h-12 rounded-xl bg-slate-800 border border-slate-700 flex items-center justify-center text-slate-400 hover:text-white transition-colors disabled:opacity-50"
+                },
+                title: "Knowledge base settings for this message",
+                disabled: disabled,
+                onclick: move |_| open.set(!open()),
+                "📚"
+            }
+
+            if open() {
+                div {
+                    class: "absolute bottom-14 right-0 w-72 bg-slate-800 border border-slate-700 rounded-xl p-4 space-y-3 shadow-xl z-20 text-sm",
+                    label {
+                        class: "flex items-center gap-2 text-slate-200",
+                        input {
+                            r#type: "checkbox",
+                            checked: active,
+                            onchange: move |e| {
+                                let enabled = e.value().parse::<bool>().unwrap_or(false);
+                                state.write().retrieval_override = enabled.then(RetrievalOptions::default);
+                            },
+                        }
+                        "Use knowledge base for this message"
+                    }
+                    label {
+                        class: "block text-xs text-slate-400",
+                        "Collection"
+                        select {
+                            class: "mt-1 w-full px-2 py-1.5 bg-slate-700 border border-slate-600 rounded text-sm text-white",
+                            onchange: move |e| {
+                                let name = e.value();
+                                update(&|o| o.collection = Some(name.clone()).filter(|n| !n.is_empty()));
+                            },
+                            option { value: "", selected: options.collection.is_none(), "All collections" }
+                            for name in collections().unwrap_or_default() {
+                                option {
+                                    value: "{name}",
+                                    selected: options.collection.as_deref() == Some(name.as_str()),
+                                    "{name}"
+                                }
+                            }
+                            for codebase in codebases().unwrap_or_default() {
+                                option {
+                                    value: "{codebase.name}",
+                                    selected: options.collection.as_deref() == Some(codebase.name.as_str()),
+                                    "💻 {codebase.name}"
+                                }
+                            }
+                        }
+                    }
+                    label {
+                        class: "flex items-center gap-2 text-xs text-slate-400",
+                        "Documents"
+                        input {
+                            r#type: "number",
+                            class: "ml-auto w-16 px-2 py-1 bg-slate-700 border border-slate-600 rounded text-white",
+                            min: "1",
+                            max: "20",
+                            value: "{options.top_k}",
+                            oninput: move |e| {
+                                if let Ok(top_k) = e.value().parse::<usize>() {
+                                    update(&|o| o.top_k = top_k.clamp(1, 20));
+                                }
+                            },
+                        }
+                    }
+                    label {
+                        class: "flex items-center gap-2 text-xs text-slate-400",
+                        "Min. score"
+                        input {
+                            r#type: "range",
+                            class: "flex-1",
+                            min: "0",
+                            max: "1",
+                            step: "0.05",
+                            value: "{options.min_score}",
+                            oninput: move |e| {
+                                if let Ok(min_score) = e.value().parse::<f32>() {
+                                    update(&|o| o.min_score = min_score);
+                                }
+                            },
+                        }
+                        span { class: "w-10 text-right", "{options.min_score:.2}" }
+                    }
+                    div {
+                        class: "text-xs text-slate-400",
+                        "Sources"
+                        div {
+                            class: "mt-1 flex flex-wrap gap-1",
+                            for source_type in SourceType::ALL {
+                                {
+                                    let selected = options.filter.source_types.contains(&source_type);
+                                    rsx! {
+                                        button {
+                                            class: if selected {
+                                                "px-2 py-0.5 rounded-full bg-blue-600 text-white"
+                                            } else {
+                                                "px-2 py-0.5 rounded-full bg-slate-700 text-slate-300 hover:text-white"
+                                            },
+                                            onclick: move |_| update(&|o| {
+                                                let types = &mut o.filter.source_types;
+                                                match types.iter().position(|t| *t == source_type) {
+                                                    Some(i) => { types.remove(i); }
+                                                    None => types.push(source_type),
+                                                }
+                                            }),
+                                            "{source_type.display_name()}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    label {
+                        class: "block text-xs text-slate-400",
+                        "Tagged with any of"
+                        input {
+                            class: "mt-1 w-full px-2 py-1.5 bg-slate-700 border border-slate-600 rounded text-sm text-white placeholder-slate-500",
+                            list: "document-tags",
+                            placeholder: "e.g. api-docs",
+                            value: "{tag_text}",
+                            oninput: move |e| {
+                                let tags = parse_document_tags(&e.value());
+                                tag_text.set(e.value());
+                                update(&|o| o.filter.tags = tags.clone());
+                            },
+                        }
+                        datalist {
+                            id: "document-tags",
+                            for tag in known_tags().unwrap_or_default() {
+                                option { value: "{tag}" }
+                            }
+                        }
+                    }
+                    div {
+                        class: "grid grid-cols-2 gap-2 text-xs text-slate-400",
+                        label {
+                            "Added after"
+                            input {
+                                r#type: "date",
+                                class: "mt-1 w-full px-2 py-1 bg-slate-700 border border-slate-600 rounded text-white",
+                                value: options.filter.added_after.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+                                onchange: move |e| {
+                                    let date = parse_filter_date(&e.value());
+                                    update(&|o| o.filter.added_after = date);
+                                },
+                            }
+                        }
+                        label {
+                            "Added before"
+                            input {
+                                r#type: "date",
+                                class: "mt-1 w-full px-2 py-1 bg-slate-700 border border-slate-600 rounded text-white",
+                                value: options.filter.added_before.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+                                onchange: move |e| {
+                                    let date = parse_filter_date(&e.value());
+                                    update(&|o| o.filter.added_before = date);
+                                },
+                            }
+                        }
+                    }
+                    p { class: "text-xs text-slate-500", "Applies to the next message only." }
+                }
+            }
+        }
+    }
+}
+
+fn render_toggle(mut state: Signal<ChatState>, disabled: bool, checked: bool, label: &str, apply: fn(&mut ChatState, bool)) -> Element {
+    rsx! {
+        label {
+            class: "flex items-center gap-3 cursor-pointer group",
+
+            div {
+                class: "relative",
+                input {
+                    disabled: disabled,
+                    r#type: "checkbox",
+                    class: "sr-only peer",
+                    checked: "{checked}",
+                    onchange: move |e| {
+                        let mut new_state = state.read().clone();
+                        apply(&mut new_state, e.value().parse::<bool>().unwrap_or(false));
+                        state.set(new_state);
+                    },
+                }
+                div {
+                    class: "w-9 h-5 bg-slate-700 rounded-full peer peer-checked:bg-blue-600 transition-colors"
+                }
+                div {
+                    class: "absolute left-0.5 top-0.5 w-4 h-4 bg-white rounded-full transition-transform peer-checked:translate-x-4"
                 }
             }
+
+            span {
+                class: "text-sm text-slate-400 group-hover:text-slate-300 transition-colors",
+                "{label}"
+            }
         }
     }
 }
 
 fn initialize_systems(state: Signal<ChatState>, model_ready: Signal<bool>, sessions: Signal<Vec<Session>>) {
-    initialize_language_model(state.clone(), model_ready.clone());
+    initialize_language_model(state.clone(), model_ready.clone(), sessions);
     initialize_database(state.clone());
     initialize_embedding_model();
     initialize_sqlite_database(sessions);
@@ -452,26 +927,26 @@ fn initialize_sqlite_database(mut sessions: Signal<Vec<Session>>) {
     spawn(async move {
         match init_sqlite_db().await {
             Ok(_) => {
-                println!("SQLite database initialized successfully");
+                tracing::info!("SQLite database initialized successfully");
                 // Load sessions after SQLite is ready
                 match get_sessions().await {
                     Ok(loaded_sessions) => {
-                        println!("Loaded {} sessions from database", loaded_sessions.len());
+                        tracing::info!("Loaded {} sessions from database", loaded_sessions.len());
                         sessions.set(loaded_sessions);
                     }
                     Err(e) => {
-                        println!("Error loading sessions: {:?}", e);
+                        tracing::error!("Error loading sessions: {:?}", e);
                     }
                 }
             }
             Err(e) => {
-                println!("Error initializing SQLite database: {}", e);
+                tracing::error!("Error initializing SQLite database: {}", e);
             }
         }
     });
 }
 
-fn initialize_language_model(mut state: Signal<ChatState>, mut model_ready: Signal<bool>) {
+fn initialize_language_model(mut state: Signal<ChatState>, mut model_ready: Signal<bool>, sessions: Signal<Vec<Session>>) {
     spawn(async move {
         match init_llm_model().await {
             Ok(_) => {
@@ -479,12 +954,14 @@ fn initialize_language_model(mut state: Signal<ChatState>, mut model_ready: Sign
                 current_state.is_model_loading = false;
                 state.set(current_state);
                 model_ready.set(true);
+                // Tag sessions added since the last run
+                tag_sessions(sessions, false).await;
             }
             Err(e) => {
                 let mut current_state = state.read().clone();
                 current_state.is_model_loading = false;
                 state.set(current_state);
-                println!("Error initializing model: {}", e);
+                tracing::error!("Error initializing model: {}", e);
             }
         }
     });
@@ -502,7 +979,7 @@ fn initialize_database(mut state: Signal<ChatState>) {
                 let mut current_state = state.read().clone();
                 current_state.is_database_loading = false;
                 state.set(current_state);
-                println!("Error initializing database: {}", e);
+                tracing::error!("Error initializing database: {}", e);
             }
         }
     });
@@ -511,7 +988,7 @@ fn initialize_database(mut state: Signal<ChatState>) {
 fn initialize_embedding_model() {
     spawn(async move {
         if let Err(e) = init_embedding_model().await {
-            println!("Error initializing embeddings: {}", e);
+            tracing::error!("Error initializing embeddings: {}", e);
         }
     });
 }
@@ -538,10 +1015,19 @@ async fn handle_message_send(
         return;
     }
 
-    if current_state.input_message.trim().is_empty() {
+    if (current_state.input_message.trim().is_empty() && current_state.attachments.is_empty())
+        || !current_state.attaching.is_empty()
+    {
         return;
     }
 
+    // A message may consist of attachments alone; the session is then
+    // titled after the first file
+    let (input_message, title_source) = match current_state.input_message.trim() {
+        "" => (ATTACHMENTS_ONLY_MESSAGE.to_string(), current_state.attachments[0].name.clone()),
+        text => (text.to_string(), text.to_string()),
+    };
+
     // Auto-create session if none exists and add to sidebar history
     // Also update title if session has default "New Chat" title
     let session = match session {
@@ -549,8 +1035,8 @@ async fn handle_message_send(
             // Check if this is a new session with default title and no messages yet
             let needs_title_update = s.title == "New Chat" && messages.read().is_empty();
             if needs_title_update {
-                let first_msg = current_state.input_message.trim();
-                let new_title = extract_session_title(first_msg);
+                let first_msg = title_source.as_str();
+                let new_title = keyword_title(first_msg, &settings.peek().session_titles);
 
                 // Update title in database
                 let _ = update_session_title(s.id.to_string(), new_title.clone()).await;
@@ -575,8 +1061,8 @@ async fn handle_message_send(
         },
         None => {
             // Generate session title from first message using keyword extraction
-            let first_msg = current_state.input_message.trim();
-            let title = extract_session_title(first_msg);
+            let first_msg = title_source.as_str();
+            let title = keyword_title(first_msg, &settings.peek().session_titles);
 
             // Create session on server (persisted to SQLite)
             match create_session(Some(title.clone())).await {
@@ -588,7 +1074,7 @@ async fn handle_message_send(
                     new_session
                 }
                 Err(e) => {
-                    println!("Error creating session: {:?}", e);
+                    tracing::error!("Error creating session: {:?}", e);
                     // Fallback to local-only session
                     let new_session = Session::new(title);
                     sessions.write().insert(0, new_session.clone());
@@ -602,8 +1088,10 @@ async fn handle_message_send(
     let mut new_state = current_state.clone();
     new_state.cancel_token = false;
     new_state.is_model_answering = true;
-    let user_message = current_state.input_message.trim().to_string();
-    let user_msg = ChatMessage::user(session.id, user_message.clone());
+    let user_message = input_message;
+    let mut user_msg = ChatMessage::user(session.id, user_message.clone());
+    user_msg.attachments = current_state.attachments.clone();
+    let attachment_preamble = ChatAttachment::preamble(&user_msg.attachments);
     let assistant_msg = ChatMessage::assistant(session.id, String::new());
 
     // Save user message to database
@@ -612,8 +1100,16 @@ async fn handle_message_send(
     messages.write().push(user_msg);
     messages.write().push(assistant_msg.clone());
     new_state.input_message = String::new();
+    new_state.attachments.clear();
+    new_state.attachment_error = None;
+    new_state.response_error = None;
+    new_state.retrieval_override = None;
     state.set(new_state);
 
+    // Knowledge base retrieval for this message, if any
+    let retrieval = current_state.retrieval_override.clone()
+        .or_else(|| current_state.use_context.then(RetrievalOptions::default));
+
     // Keep track of assistant message ID for saving later
     let assistant_msg_id = assistant_msg.id;
 
@@ -623,21 +1119,60 @@ async fn handle_message_send(
         settings_guard.language.prompt_instruction().to_string()
     };
 
-    process_response(state.clone(), messages.clone(), user_message, language_instruction, session.id, assistant_msg_id);
+    let title_method = settings.peek().session_titles.method;
+    process_response(state.clone(), messages.clone(), current_session, sessions, user_message, attachment_preamble, language_instruction, retrieval, title_method, session.id, assistant_msg_id);
 }
 
-fn process_response(mut state: Signal<ChatState>, mut messages: Signal<Vec<ChatMessage>>, user_message: String, language_instruction: String, session_id: uuid::Uuid, assistant_msg_id: uuid::Uuid) {
+fn process_response(mut state: Signal<ChatState>, mut messages: Signal<Vec<ChatMessage>>, current_session: Signal<Option<Session>>, sessions: Signal<Vec<Session>>, user_message: String, attachment_preamble: String, language_instruction: String, retrieval: Option<RetrievalOptions>, title_method: TitleMethod, session_id: uuid::Uuid, assistant_msg_id: uuid::Uuid) {
     spawn(async move {
         #[cfg(target_arch = "wasm32")]
         web_sys::console::log_1(&"[WASM] process_response started".into());
 
-        let use_context_enabled = state.read().use_context;
+        // Long-term memories relevant to this message
+        let memory_preamble = recall_memories(user_message.clone()).await.unwrap_or_default();
+
+        // Web results to ground the answer, cited by number
+        let web_results = if state.read().use_web {
+            search_web(user_message.clone()).await.unwrap_or_else(|e| {
+                tracing::warn!("Web search failed: {:?}", e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        let web_preamble = if web_results.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "=== WEB RESULTS ===\n{}\n=== END WEB RESULTS ===\n\
+Use the web results above where relevant and cite them inline as [1], [2], etc.\n\n",
+                format_web_results(&web_results)
+            )
+        };
+
+        // Retrieved code chunks, linked below the answer
+        let mut code_references = Vec::new();
 
         // Build the final prompt with RAG context if enabled
-        let final_message = if use_context_enabled {
+        let final_message = if let Some(options) = retrieval {
+            // Earlier messages, so a follow-up question can be rewritten
+            // into a standalone search query
+            let history: Vec<String> = {
+                let sent = messages.peek();
+                let earlier: Vec<String> = sent.iter()
+                    .filter(|m| m.id != assistant_msg_id && !m.content.trim().is_empty())
+                    .map(|m| format!("{}: {}", m.role, m.content))
+                    .collect();
+                // The last one is the question being answered
+                let end = earlier.len().saturating_sub(1);
+                earlier[end.saturating_sub(QUERY_HISTORY_MESSAGES)..end].to_vec()
+            };
+
             // Search for relevant context first
-            match search_context(user_message.clone()).await {
-                Ok(context) if !context.trim().is_empty() => {
+            match search_context(user_message.clone(), options, history).await {
+                Ok((context, references)) if !context.trim().is_empty() => {
+                    code_references = references;
+
                     #[cfg(target_arch = "wasm32")]
                     web_sys::console::log_1(&format!("[WASM] RAG context found: {}", &context[..context.len().min(200)]).into());
 
@@ -649,7 +1184,7 @@ fn process_response(mut state: Signal<ChatState>, mut messages: Signal<Vec<ChatM
 === END REFERENCES ===\n\n\
 INSTRUCTIONS:\n\
 1. Answer the following question using ONLY the reference documents above.\n\
-2. If the question relates to information in the references, cite the relevant reference number (e.g., \"According to Reference 1...\").\n\
+2. If the question relates to information in the references, cite the relevant reference number (e.g., \"According to Reference 1...\"). For audio transcripts, also give the [mm:ss] time of the passage; for code, give the file and line (e.g. \"src/main.rs:42\").\n\
 3. If the references don't contain relevant information for the question, clearly state: \"The provided context does not contain information about this topic.\"\n\
 4. Be concise and accurate. Do not make up information.\n\n\
 Question: {}\n\n\
@@ -681,48 +1216,112 @@ Answer:",
             format!("{} {}", language_instruction, enhanced_message)
         };
 
+        let final_message = format!("{}{}{}{}", memory_preamble, web_preamble, attachment_preamble, final_message);
+
         #[cfg(target_arch = "wasm32")]
         web_sys::console::log_1(&format!("[WASM] Calling get_response with: {}", final_message).into());
 
-        // Get and process response stream
-        match get_response(final_message).await {
-            Ok(mut stream) => {
-                #[cfg(target_arch = "wasm32")]
-                web_sys::console::log_1(&"[WASM] Got stream, starting to consume".into());
+        // Timing for the message footer
+        let session_model = current_session.peek().as_ref()
+            .filter(|s| s.id == session_id)
+            .and_then(|s| s.overrides.model_id.clone());
+        let model = match session_model {
+            Some(id) => get_available_models().into_iter().find(|m| m.id == id).map(|m| m.name).unwrap_or(id),
+            None => get_current_model().await.map(|m| m.name).unwrap_or_default(),
+        };
+        let started_at = chrono::Utc::now();
+        let mut tokens: u32 = 0;
+        let mut first_token_ms = None;
+        let mut truncated = false;
+
+        if state.read().use_tools {
+            // Tool calls run to completion server-side, so the answer arrives at once
+            let allow_code_execution = state.read().allow_code_execution;
+            let content = match get_tool_response(final_message, allow_code_execution).await {
+                Ok(run) => format_tool_run(&run),
+                Err(e) => {
+                    let content = format!("Error: {}", error_message(&e));
+                    state.write().response_error = Some(e.to_string());
+                    content
+                }
+            };
+            let mut current_messages = messages.read().clone();
+            if let Some(last_message) = current_messages.last_mut() {
+                last_message.content = content;
+                messages.set(current_messages);
+            }
+        } else {
+            // Get and process response stream
+            match get_response(final_message, session_id.to_string()).await {
+                Ok(mut stream) => {
+                    #[cfg(target_arch = "wasm32")]
+                    web_sys::console::log_1(&"[WASM] Got stream, starting to consume".into());
+
+                    let mut chunk_count = 0;
+                    while let Some(result) = stream.next().await {
+                        chunk_count += 1;
+                        match result {
+                            Ok(chunk) => {
+                                #[cfg(target_arch = "wasm32")]
+                                web_sys::console::log_1(&format!("[WASM] Chunk {}: '{}'", chunk_count, chunk).into());
+
+                                // Check if response was canceled
+                                if state.read().cancel_token {
+                                    break;
+                                }
 
-                let mut chunk_count = 0;
-                while let Some(result) = stream.next().await {
-                    chunk_count += 1;
-                    match result {
-                        Ok(chunk) => {
-                            #[cfg(target_arch = "wasm32")]
-                            web_sys::console::log_1(&format!("[WASM] Chunk {}: '{}'", chunk_count, chunk).into());
-
-                            // Check if response was canceled
-                            if state.read().cancel_token {
-                                break;
-                            }
+                                tokens += 1;
+                                if first_token_ms.is_none() {
+                                    first_token_ms = Some(elapsed_ms(started_at));
+                                }
 
-                            // Clone, modify, set - same pattern as rusty_bot
-                            let mut current_messages = messages.read().clone();
-                            if let Some(last_message) = current_messages.last_mut() {
-                                last_message.content.push_str(&chunk);
-                                messages.set(current_messages);
+                                // Clone, modify, set - same pattern as rusty_bot
+                                let mut current_messages = messages.read().clone();
+                                if let Some(last_message) = current_messages.last_mut() {
+                                    last_message.content.push_str(&chunk);
+                                    messages.set(current_messages);
+                                }
+                            },
+                            Err(e) => {
+                                #[cfg(target_arch = "wasm32")]
+                                web_sys::console::log_1(&format!("[WASM] Error in chunk {}: {:?}", chunk_count, e).into());
                             }
-                        },
-                        Err(e) => {
-                            #[cfg(target_arch = "wasm32")]
-                            web_sys::console::log_1(&format!("[WASM] Error in chunk {}: {:?}", chunk_count, e).into());
                         }
                     }
+
+                    #[cfg(target_arch = "wasm32")]
+                    web_sys::console::log_1(&format!("[WASM] Stream finished. Total chunks: {}", chunk_count).into());
+
+                    if !state.read().cancel_token {
+                        truncated = response_was_truncated(session_id.to_string()).await.unwrap_or(false);
+                    }
+                },
+                Err(e) => {
+                    #[cfg(target_arch = "wasm32")]
+                    web_sys::console::log_1(&format!("[WASM] Error getting response: {:?}", e).into());
+                    state.write().response_error = Some(e.to_string());
                 }
+            }
+        }
 
-                #[cfg(target_arch = "wasm32")]
-                web_sys::console::log_1(&format!("[WASM] Stream finished. Total chunks: {}", chunk_count).into());
-            },
-            Err(e) => {
-                #[cfg(target_arch = "wasm32")]
-                web_sys::console::log_1(&format!("[WASM] Error getting response: {:?}", e).into());
+        let generation = GenerationStats { model, tokens, first_token_ms, total_ms: elapsed_ms(started_at), truncated };
+        if let Some(message) = messages.write().iter_mut().find(|m| m.id == assistant_msg_id) {
+            message.generation = Some(generation);
+        }
+
+        if !web_results.is_empty() {
+            let mut current_messages = messages.read().clone();
+            if let Some(last_message) = current_messages.last_mut() {
+                last_message.content.push_str(&format!("{}{}", SOURCES_HEADING, format_web_sources(&web_results)));
+                messages.set(current_messages);
+            }
+        }
+
+        if !code_references.is_empty() {
+            let mut current_messages = messages.read().clone();
+            if let Some(last_message) = current_messages.last_mut() {
+                last_message.content.push_str(&format!("{}{}", CODE_REFERENCES_HEADING, format_code_references(&code_references)));
+                messages.set(current_messages);
             }
         }
 
@@ -736,6 +1335,9 @@ Answer:",
                     role: crate::models::ChatRole::Assistant,
                     content: last_msg.content.clone(),
                     created_at: last_msg.created_at,
+                    feedback: last_msg.feedback.clone(),
+                    attachments: Vec::new(),
+                    generation: last_msg.generation.clone(),
                 };
                 let _ = save_message(msg_to_save).await;
             }
@@ -746,33 +1348,228 @@ Answer:",
         current_state.is_model_answering = false;
         state.set(current_state);
 
+        // Once the conversation has some substance, replace the keyword title
+        let user_turns = messages.read().iter()
+            .filter(|m| m.role == crate::models::ChatRole::User)
+            .count();
+        if title_method == TitleMethod::Llm && user_turns == TITLE_REFINEMENT_TURNS {
+            refine_session_title(session_id, current_session, sessions).await;
+        }
+
+        // Learn long-term facts the user shared about themselves
+        if let Err(e) = remember_from_message(session_id.to_string(), user_message).await {
+            tracing::warn!("Error updating memory: {:?}", e);
+        }
+
         // Refocus the input after response is complete
         #[cfg(target_arch = "wasm32")]
-        focus_input();
+        focus_input(state.peek().pane);
 
         #[cfg(target_arch = "wasm32")]
         web_sys::console::log_1(&"[WASM] process_response finished".into());
     });
 }
 
-#[cfg(target_arch = "wasm32")]
-fn scroll_to_bottom() {
-    let window = web_sys::window().expect("no window");
-    let document = window.document().expect("no document");
-    if let Some(element) = document.get_element_by_id("chat-container") {
-        let div = element.dyn_into::<web_sys::HtmlElement>().unwrap();
-        div.set_scroll_top(div.scroll_height());
+/// Streams the rest of an answer that stopped at the token cap into the same
+/// message, rather than starting a new turn
+fn continue_response(mut state: Signal<ChatState>, mut messages: Signal<Vec<ChatMessage>>, message_id: uuid::Uuid) {
+    if state.read().is_model_answering {
+        return;
+    }
+    let Some(message) = messages.read().iter().find(|m| m.id == message_id).cloned() else {
+        return;
+    };
+    let prompt = continuation_prompt(&message.content);
+    {
+        let mut current = state.write();
+        current.is_model_answering = true;
+        current.cancel_token = false;
+    }
+    // Hides the Continue button while the rest streams in
+    if let Some(stats) = messages.write().iter_mut().find(|m| m.id == message_id).and_then(|m| m.generation.as_mut()) {
+        stats.truncated = false;
+    }
+
+    spawn(async move {
+        let started_at = chrono::Utc::now();
+        let mut tokens: u32 = 0;
+        match get_response(prompt, message.session_id.to_string()).await {
+            Ok(mut stream) => {
+                while let Some(result) = stream.next().await {
+                    if state.read().cancel_token {
+                        break;
+                    }
+                    let Ok(chunk) = result else { continue };
+                    tokens += 1;
+                    if let Some(m) = messages.write().iter_mut().find(|m| m.id == message_id) {
+                        m.insert_continuation(&chunk);
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::error!("Error continuing response: {:?}", e);
+                state.write().response_error = Some(e.to_string());
+            }
+        }
+        let truncated = !state.read().cancel_token
+            && response_was_truncated(message.session_id.to_string()).await.unwrap_or(false);
+
+        let updated = messages.write().iter_mut().find(|m| m.id == message_id).map(|m| {
+            if let Some(stats) = m.generation.as_mut() {
+                stats.tokens += tokens;
+                stats.total_ms += elapsed_ms(started_at);
+                stats.truncated = truncated;
+            }
+            m.clone()
+        });
+        if let Some(message) = updated {
+            let _ = save_message(message).await;
+        }
+        state.write().is_model_answering = false;
+
+        #[cfg(target_arch = "wasm32")]
+        focus_input(state.peek().pane);
+    });
+}
+
+/// Milliseconds since `start`; `Instant` isn't available in the browser
+fn elapsed_ms(start: chrono::DateTime<chrono::Utc>) -> u64 {
+    (chrono::Utc::now() - start).num_milliseconds().max(0) as u64
+}
+
+/// Renders the tool calls as a quoted trace above the final answer
+fn format_tool_run(run: &ToolRun) -> String {
+    let mut content = String::new();
+    for call in &run.invocations {
+        let status = if call.is_error { "failed" } else { "ok" };
+        if call.name == "run_code" {
+            // Show the program and its full output
+            let arguments: serde_json::Value = serde_json::from_str(&call.arguments).unwrap_or_default();
+            let language = arguments["language"].as_str().unwrap_or_default();
+            let code = arguments["code"].as_str().unwrap_or_default();
+            content.push_str(&format!("> 🔧 `run_code` {}\n\n```{}\n{}\n```\n\n```text\n{}\n```\n\n", status, language, code.trim_end(), call.result));
+        } else {
+            content.push_str(&format!("> 🔧 `{}` {} — {}\n", call.name, status, call.result.lines().next().unwrap_or_default()));
+        }
+    }
+    if !content.is_empty() {
+        content.push('\n');
+    }
+
+    // Raw HTML is not rendered in messages, but data: image URLs are
+    for media in run.invocations.iter().filter_map(|c| c.media.as_deref()) {
+        if media.starts_with("data:image/") {
+            content.push_str(&format!("![generated image]({})\n\n", media));
+        }
     }
+
+    content.push_str(&run.answer);
+    content
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-fn scroll_to_bottom() {}
+/// How often the queue position is refreshed, in milliseconds
+const QUEUE_POLL_MS: u32 = 1000;
+
+/// How many requests are ahead of the session's reply while it waits for
+/// the model; nothing once it is generating
+#[component]
+fn QueuePosition(owner: String) -> Element {
+    let mut ahead: Signal<Option<usize>> = use_signal(|| None);
+    // Poll for as long as the reply is waiting
+    use_effect(move || {
+        let owner = owner.clone();
+        spawn(async move {
+            loop {
+                if let Ok(queue) = get_llm_queue().await {
+                    ahead.set(queue.position_of_owner(&owner));
+                }
+                sleep_ms(QUEUE_POLL_MS).await;
+            }
+        });
+    });
+
+    match ahead() {
+        Some(ahead) if ahead > 0 => rsx! {
+            p {
+                class: "mb-2 text-xs text-slate-400",
+                if ahead == 1 { "Waiting for the model: 1 request ahead" } else { "Waiting for the model: {ahead} requests ahead" }
+            }
+        },
+        _ => rsx! {},
+    }
+}
+
+/// Number of user messages after which the session title is regenerated by the LLM
+const TITLE_REFINEMENT_TURNS: usize = 3;
+
+/// Text sent when the user sends attachments without writing anything
+const ATTACHMENTS_ONLY_MESSAGE: &str = "Please look at the attached file.";
+
+/// Distance from the end of the chat that still counts as at the bottom
+const AT_BOTTOM_SLACK_PX: u32 = 48;
+
+/// Asks the LLM for a better session title and applies it to the local state
+pub async fn refine_session_title(
+    session_id: uuid::Uuid,
+    mut current_session: Signal<Option<Session>>,
+    mut sessions: Signal<Vec<Session>>,
+) {
+    match generate_session_title(session_id.to_string()).await {
+        Ok(title) => {
+            let mut sessions_list = sessions.read().clone();
+            if let Some(session) = sessions_list.iter_mut().find(|s| s.id == session_id) {
+                session.title = title.clone();
+                sessions.set(sessions_list);
+            }
+            if let Some(mut session) = current_session().filter(|s| s.id == session_id) {
+                session.title = title;
+                current_session.set(Some(session));
+            }
+        }
+        Err(e) => {
+            tracing::error!("Error generating session title: {:?}", e);
+        }
+    }
+}
+
+fn scroll_to_bottom(pane: usize) {
+    let _ = document::eval(&format!(
+        r#"const chat = document.getElementById("{}");
+        if (chat) chat.scrollTop = chat.scrollHeight;"#,
+        pane_id("chat-container", pane)
+    ));
+}
+
+/// Whether the chat is scrolled to the end, updated as the user scrolls
+fn use_at_bottom(pane: usize) -> Signal<bool> {
+    let mut at_bottom = use_signal(|| true);
+    use_effect(move || {
+        let chat_id = pane_id("chat-container", pane);
+        spawn(async move {
+            let mut listener = document::eval(&format!(
+                r#"const chat = document.getElementById("{chat_id}");
+                let last = true;
+                if (chat) chat.addEventListener("scroll", () => {{
+                    const bottom = chat.scrollHeight - chat.scrollTop - chat.clientHeight <= {AT_BOTTOM_SLACK_PX};
+                    if (bottom !== last) {{
+                        last = bottom;
+                        dioxus.send(bottom);
+                    }}
+                }});"#
+            ));
+            while let Ok(bottom) = listener.recv::<bool>().await {
+                at_bottom.set(bottom);
+            }
+        });
+    });
+    at_bottom
+}
 
 #[cfg(target_arch = "wasm32")]
-fn focus_input() {
+fn focus_input(pane: usize) {
     let window = web_sys::window().expect("no window");
     let document = window.document().expect("no document");
-    if let Some(element) = document.get_element_by_id("message-input") {
+    if let Some(element) = document.get_element_by_id(&pane_id("message-input", pane)) {
         if let Ok(input) = element.dyn_into::<web_sys::HtmlElement>() {
             let _ = input.focus();
         }
@@ -780,59 +1577,4 @@ fn focus_input() {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn focus_input() {}
-
-/// Extracts keywords from user message to generate session title
-/// Filters out common stop words and focuses on meaningful content words
-fn extract_session_title(message: &str) -> String {
-    // Common stop words to filter out
-    const STOP_WORDS: &[&str] = &[
-        "a", "an", "the", "is", "are", "was", "were", "be", "been", "being",
-        "have", "has", "had", "do", "does", "did", "will", "would", "could",
-        "should", "may", "might", "must", "can", "to", "of", "in", "for",
-        "on", "with", "at", "by", "from", "as", "into", "through", "during",
-        "before", "after", "above", "below", "between", "under", "again",
-        "further", "then", "once", "here", "there", "when", "where", "why",
-        "how", "all", "each", "few", "more", "most", "other", "some", "such",
-        "no", "nor", "not", "only", "own", "same", "so", "than", "too", "very",
-        "just", "and", "but", "if", "or", "because", "until", "while", "about",
-        "what", "which", "who", "whom", "this", "that", "these", "those", "am",
-        "it", "its", "i", "me", "my", "myself", "we", "our", "ours", "you",
-        "your", "yours", "he", "him", "his", "she", "her", "hers", "they",
-        "them", "their", "please", "help", "want", "need", "tell", "explain",
-        "show", "give", "make", "let", "know", "think", "like",
-        // Chinese stop words
-        "的", "了", "是", "在", "我", "有", "和", "就", "不", "人", "都", "一",
-        "个", "上", "也", "很", "到", "说", "要", "去", "你", "会", "着", "没有",
-        "看", "好", "自己", "这", "那", "吗", "什么", "怎么", "为什么", "如何",
-        "请", "帮", "告诉", "解释", "能", "可以", "想", "用",
-    ];
-
-    // Split message into words and filter
-    let words: Vec<&str> = message
-        .split(|c: char| c.is_whitespace() || c == '?' || c == '!' || c == '.' || c == ',' || c == '。' || c == '？' || c == '！' || c == '，')
-        .filter(|w| !w.is_empty())
-        .filter(|w| w.len() > 1 || w.chars().any(|c| !c.is_ascii()))  // Keep multi-char words or non-ASCII
-        .filter(|w| !STOP_WORDS.contains(&w.to_lowercase().as_str()))
-        .collect();
-
-    // Take up to 4 keywords
-    let keywords: Vec<&str> = words.into_iter().take(4).collect();
-
-    if keywords.is_empty() {
-        // Fallback: use first 25 chars of original message
-        if message.len() > 25 {
-            format!("{}...", &message.chars().take(22).collect::<String>())
-        } else {
-            message.to_string()
-        }
-    } else {
-        let title = keywords.join(" ");
-        // Ensure title isn't too long
-        if title.len() > 30 {
-            format!("{}...", &title.chars().take(27).collect::<String>())
-        } else {
-            title
-        }
-    }
-}
+fn focus_input(_pane: usize) {}
@@ -0,0 +1,63 @@
+//! Clipboard Paste
+//!
+//! Reads files, such as screenshots, pasted with Ctrl/Cmd+V while a panel is
+//! open. Pasted text is left to the browser.
+
+use dioxus::prelude::*;
+use serde::Deserialize;
+
+/// A file from the clipboard
+#[derive(Clone, Debug, Deserialize)]
+pub struct PastedFile {
+    pub name: String,
+    /// MIME type reported by the browser, such as `image/png`
+    pub mime: String,
+    pub data: Vec<u8>,
+    /// Object URL for showing the file before it's uploaded
+    pub preview_url: String,
+}
+
+impl PastedFile {
+    pub fn is_image(&self) -> bool {
+        self.mime.starts_with("image/")
+    }
+}
+
+/// Calls `on_file` for each file pasted while the element with `element_id`
+/// is on the page. The listener removes itself once the element is gone.
+pub fn use_pasted_files(element_id: String, on_file: impl FnMut(PastedFile) + 'static) {
+    let mut on_file = Some(on_file);
+    use_effect(move || {
+        let Some(mut on_file) = on_file.take() else { return };
+        let element_id = element_id.clone();
+        spawn(async move {
+            let mut listener = document::eval(&format!(
+                r#"
+                const root = document.getElementById("{element_id}");
+                const onPaste = async (e) => {{
+                    if (!root || !root.isConnected) {{
+                        document.removeEventListener("paste", onPaste);
+                        return;
+                    }}
+                    const files = Array.from(e.clipboardData ? e.clipboardData.files : []);
+                    if (files.length === 0) return;
+                    e.preventDefault();
+                    for (const file of files) {{
+                        const buffer = await file.arrayBuffer();
+                        dioxus.send({{
+                            name: file.name || "pasted",
+                            mime: file.type,
+                            data: Array.from(new Uint8Array(buffer)),
+                            preview_url: URL.createObjectURL(file),
+                        }});
+                    }}
+                }};
+                if (root) document.addEventListener("paste", onPaste);
+                "#
+            ));
+            while let Ok(file) = listener.recv::<PastedFile>().await {
+                on_file(file);
+            }
+        });
+    });
+}
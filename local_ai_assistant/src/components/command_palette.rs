@@ -0,0 +1,186 @@
+//! Command Palette
+//!
+//! Ctrl/Cmd+K opens a search box that jumps to a chat session, panel or
+//! settings page, or runs an action such as starting a new chat. Typing
+//! filters the list with fuzzy matching; arrow keys and Enter pick an entry.
+
+use dioxus::prelude::*;
+use crate::models::{Session, rank_matches};
+use super::{ActivePanel, SettingsTab};
+use super::i18n::t;
+use super::ime::use_ime_state;
+
+/// What picking a palette entry does
+#[derive(Clone, PartialEq)]
+pub enum PaletteCommand {
+    NewChat,
+    OpenSession(Session),
+    OpenPanel(ActivePanel),
+    OpenSettings(SettingsTab),
+    ReloadContext,
+    QuickCapture,
+    ToggleSplitView,
+}
+
+/// One row of the palette
+#[derive(Clone, PartialEq)]
+struct PaletteEntry {
+    label: String,
+    /// Kind of entry, shown on the right
+    kind: &'static str,
+    command: PaletteCommand,
+}
+
+/// Every entry, actions first, then panels, settings pages and sessions
+fn palette_entries(sessions: &[Session]) -> Vec<PaletteEntry> {
+    let action = |label: &str, command| PaletteEntry { label: label.to_string(), kind: "Action", command };
+    let mut entries = vec![
+        action(t("sidebar.new_chat"), PaletteCommand::NewChat),
+        action("Reload context", PaletteCommand::ReloadContext),
+        action("Quick capture", PaletteCommand::QuickCapture),
+        action("Toggle split view", PaletteCommand::ToggleSplitView),
+    ];
+    entries.extend(ActivePanel::all().iter().map(|panel| PaletteEntry {
+        label: panel.title().to_string(),
+        kind: "Panel",
+        command: PaletteCommand::OpenPanel(*panel),
+    }));
+    entries.extend(SettingsTab::all().iter().map(|tab| PaletteEntry {
+        label: format!("Settings: {}", t(tab.label_key())),
+        kind: "Settings",
+        command: PaletteCommand::OpenSettings(tab.clone()),
+    }));
+    entries.extend(sessions.iter().filter(|s| !s.archived).map(|session| PaletteEntry {
+        label: session.title.clone(),
+        kind: "Chat",
+        command: PaletteCommand::OpenSession(session.clone()),
+    }));
+    entries
+}
+
+/// Registers the global Ctrl/Cmd+K shortcut that toggles the palette
+pub fn use_command_palette_hotkey(mut open: Signal<bool>) {
+    use_effect(move || {
+        spawn(async move {
+            let mut listener = document::eval(
+                r#"document.addEventListener('keydown', (e) => {
+                    if ((e.ctrlKey || e.metaKey) && !e.shiftKey && e.key.toLowerCase() === 'k') {
+                        e.preventDefault();
+                        dioxus.send(true);
+                    }
+                });"#,
+            );
+            while listener.recv::<bool>().await.is_ok() {
+                open.set(!open());
+            }
+        });
+    });
+}
+
+/// Palette overlay; `on_command` runs the picked entry after it closes
+#[component]
+pub fn CommandPalette(
+    open: Signal<bool>,
+    sessions: Signal<Vec<Session>>,
+    on_command: EventHandler<PaletteCommand>,
+) -> Element {
+    let mut query: Signal<String> = use_signal(String::new);
+    let mut selected: Signal<usize> = use_signal(|| 0);
+    let ime = use_ime_state();
+
+    // Start from an empty search each time the palette opens
+    use_effect(move || {
+        if open() {
+            query.set(String::new());
+            selected.set(0);
+        }
+    });
+
+    let matches = use_memo(move || {
+        let entries = palette_entries(&sessions.read());
+        let query = query();
+        let ranked = rank_matches(&query, entries.iter().map(|e| e.label.as_str()));
+        ranked.into_iter().map(|i| entries[i].clone()).collect::<Vec<_>>()
+    });
+
+    if !open() {
+        return rsx! {};
+    }
+
+    let mut run = move |command: PaletteCommand| {
+        open.set(false);
+        on_command.call(command);
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 z-50 flex items-start justify-center pt-24 bg-black/50",
+            onclick: move |_| open.set(false),
+            div {
+                class: "w-full max-w-lg bg-slate-800 rounded-xl shadow-xl overflow-hidden",
+                onclick: move |e| e.stop_propagation(),
+
+                input {
+                    class: "w-full px-4 py-3 bg-slate-800 border-b border-slate-700 text-sm text-white placeholder-slate-500 focus:outline-none",
+                    placeholder: "Search chats, panels, settings and actions...",
+                    value: "{query}",
+                    autofocus: true,
+                    oninput: move |e| {
+                        query.set(e.value());
+                        selected.set(0);
+                    },
+                    oncompositionstart: move |_| ime.start(),
+                    oncompositionend: move |_| ime.end(),
+                    onkeydown: move |e: KeyboardEvent| {
+                        let count = matches.read().len();
+                        match e.key() {
+                            Key::ArrowDown if count > 0 => {
+                                e.prevent_default();
+                                selected.set((selected() + 1) % count);
+                            }
+                            Key::ArrowUp if count > 0 => {
+                                e.prevent_default();
+                                selected.set((selected() + count - 1) % count);
+                            }
+                            Key::Escape => open.set(false),
+                            _ if ime.is_enter(&e) => {
+                                e.prevent_default();
+                                let entry = matches.read().get(selected()).cloned();
+                                if let Some(entry) = entry {
+                                    run(entry.command);
+                                }
+                            }
+                            _ => {}
+                        }
+                    },
+                }
+
+                div {
+                    class: "max-h-96 overflow-y-auto py-1",
+                    if matches.read().is_empty() {
+                        p { class: "px-4 py-3 text-sm text-slate-500", "No matches" }
+                    }
+                    for (i, entry) in matches().into_iter().enumerate() {
+                        button {
+                            key: "{i}-{entry.label}",
+                            class: if i == selected() {
+                                "w-full flex items-center justify-between px-4 py-2 text-left text-sm text-white bg-blue-600/40"
+                            } else {
+                                "w-full flex items-center justify-between px-4 py-2 text-left text-sm text-slate-300 hover:bg-slate-700"
+                            },
+                            onmouseenter: move |_| selected.set(i),
+                            onclick: move |_| run(entry.command.clone()),
+                            span { class: "truncate", "{entry.label}" }
+                            span { class: "ml-3 shrink-0 text-xs text-slate-500", "{entry.kind}" }
+                        }
+                    }
+                }
+
+                div {
+                    class: "px-4 py-2 border-t border-slate-700 text-xs text-slate-500",
+                    "↑↓ to move · Enter to open · Esc to close"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! Compare Panel Component
+//!
+//! Sends one prompt to two models, or one model at two temperatures, and
+//! shows both answers side by side as they stream in. The user votes for
+//! the better answer; comparisons are kept for later review.
+
+use dioxus::prelude::*;
+use crate::models::{
+    CompareAnswer, CompareSide, CompareVote, Comparison, GenerationStats, ModelInfo, ModelType,
+    render_markdown, tally_votes, error_message,
+};
+use crate::server_functions::{get_compare_stream, get_comparisons, list_available_models, save_comparison};
+
+#[component]
+pub fn ComparePanel() -> Element {
+    let mut models: Signal<Vec<ModelInfo>> = use_signal(Vec::new);
+    let mut left_side = use_signal(|| CompareSide { model_id: String::new(), temperature: 0.7 });
+    let mut right_side = use_signal(|| CompareSide { model_id: String::new(), temperature: 0.7 });
+    let mut prompt = use_signal(String::new);
+    let left_answer = use_signal(|| None::<CompareAnswer>);
+    let right_answer = use_signal(|| None::<CompareAnswer>);
+    let mut current: Signal<Option<Comparison>> = use_signal(|| None);
+    let mut history: Signal<Vec<Comparison>> = use_signal(Vec::new);
+    let mut is_running = use_signal(|| false);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(available) = list_available_models().await {
+                let language: Vec<ModelInfo> = available.into_iter().filter(|m| m.model_type == ModelType::Language).collect();
+                if let Some(first) = language.first() {
+                    left_side.write().model_id = first.id.clone();
+                    right_side.write().model_id = language.get(1).unwrap_or(first).id.clone();
+                }
+                models.set(language);
+            }
+            if let Ok(saved) = get_comparisons().await {
+                history.set(saved);
+            }
+        });
+    });
+
+    let run = move |_| {
+        let text = prompt().trim().to_string();
+        if text.is_empty() || is_running() {
+            return;
+        }
+        is_running.set(true);
+        error.set(None);
+        current.set(None);
+        spawn(async move {
+            let (left, right) = futures::join!(
+                stream_side(left_side(), text.clone(), left_answer),
+                stream_side(right_side(), text.clone(), right_answer),
+            );
+            match (left, right) {
+                (Ok(left), Ok(right)) => {
+                    let comparison = Comparison {
+                        id: uuid::Uuid::new_v4(),
+                        prompt: text,
+                        left,
+                        right,
+                        vote: None,
+                        created_at: chrono::Utc::now(),
+                    };
+                    if let Err(e) = save_comparison(comparison.clone()).await {
+                        error.set(Some(error_message(&e)));
+                    }
+                    history.write().insert(0, comparison.clone());
+                    current.set(Some(comparison));
+                }
+                (Err(e), _) | (_, Err(e)) => error.set(Some(e)),
+            }
+            is_running.set(false);
+        });
+    };
+
+    let mut vote = move |choice: CompareVote| {
+        let Some(mut comparison) = current() else { return };
+        comparison.vote = Some(choice);
+        current.set(Some(comparison.clone()));
+        if let Some(saved) = history.write().iter_mut().find(|c| c.id == comparison.id) {
+            saved.vote = Some(choice);
+        }
+        spawn(async move {
+            if let Err(e) = save_comparison(comparison).await {
+                error.set(Some(error_message(&e)));
+            }
+        });
+    };
+
+    let voted = current().and_then(|c| c.vote);
+    let tallies = tally_votes(&history());
+
+    rsx! {
+        div {
+            class: "flex-1 overflow-y-auto p-6",
+            div {
+                class: "max-w-6xl mx-auto space-y-4",
+
+                h2 { class: "text-lg font-semibold text-white", "Compare Models" }
+
+                div {
+                    class: "grid grid-cols-2 gap-4",
+                    SidePicker { label: "Left", side: left_side, models: models(), disabled: is_running() }
+                    SidePicker { label: "Right", side: right_side, models: models(), disabled: is_running() }
+                }
+
+                div {
+                    class: "flex gap-2",
+                    textarea {
+                        class: "flex-1 min-h-[80px] px-3 py-2 bg-slate-800 border border-slate-700 rounded-lg text-white text-sm placeholder-slate-500 resize-y",
+                        placeholder: "Prompt for both models...",
+                        value: "{prompt}",
+                        oninput: move |e| prompt.set(e.value()),
+                    }
+                    button {
+                        class: "px-4 bg-blue-600 hover:bg-blue-700 disabled:opacity-50 rounded-lg text-sm transition-colors",
+                        disabled: is_running() || prompt().trim().is_empty(),
+                        onclick: run,
+                        if is_running() { "Running…" } else { "Run" }
+                    }
+                }
+
+                if let Some(message) = error() {
+                    p { class: "text-sm text-red-400", "{message}" }
+                }
+
+                div {
+                    class: "grid grid-cols-2 gap-4",
+                    AnswerColumn { answer: left_answer(), highlight: voted == Some(CompareVote::Left) }
+                    AnswerColumn { answer: right_answer(), highlight: voted == Some(CompareVote::Right) }
+                }
+
+                if current().is_some() {
+                    div {
+                        class: "flex justify-center gap-2",
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                            onclick: move |_| vote(CompareVote::Left),
+                            "← Left is better"
+                        }
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                            onclick: move |_| vote(CompareVote::Tie),
+                            "Tie"
+                        }
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                            onclick: move |_| vote(CompareVote::Right),
+                            "Right is better →"
+                        }
+                    }
+                }
+
+                if !tallies.is_empty() {
+                    div {
+                        class: "bg-slate-800 rounded-lg p-4",
+                        h3 { class: "text-sm font-medium text-white mb-2", "Votes" }
+                        for tally in tallies {
+                            p {
+                                class: "text-xs text-slate-400",
+                                "{tally.label}: {tally.wins} won · {tally.losses} lost · {tally.ties} tied"
+                            }
+                        }
+                    }
+                }
+
+                if !history().is_empty() {
+                    div {
+                        class: "space-y-2",
+                        h3 { class: "text-sm font-medium text-white", "Earlier comparisons" }
+                        for comparison in history() {
+                            ComparisonRow { key: "{comparison.id}", comparison }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Streams one side's answer into `answer` and returns it once complete
+async fn stream_side(side: CompareSide, prompt: String, mut answer: Signal<Option<CompareAnswer>>) -> Result<CompareAnswer, String> {
+    answer.set(Some(CompareAnswer { side: side.clone(), response: String::new(), generation: None }));
+    let started_at = chrono::Utc::now();
+    let mut first_token_ms = None;
+    let mut tokens = 0u32;
+
+    let mut stream = get_compare_stream(side.model_id.clone(), side.temperature, prompt)
+        .await
+        .map_err(|e| format!("{}: {}", side.label(), e))?;
+    while let Some(Ok(chunk)) = stream.next().await {
+        if first_token_ms.is_none() {
+            first_token_ms = Some(elapsed_ms(started_at));
+        }
+        tokens += 1;
+        if let Some(current) = answer.write().as_mut() {
+            current.response.push_str(&chunk);
+        }
+    }
+
+    let generation = GenerationStats { model: side.model_id.clone(), tokens, first_token_ms, total_ms: elapsed_ms(started_at), truncated: false };
+    let mut complete = answer().unwrap_or(CompareAnswer { side, response: String::new(), generation: None });
+    complete.response = complete.response.trim().to_string();
+    complete.generation = Some(generation);
+    answer.set(Some(complete.clone()));
+    Ok(complete)
+}
+
+fn elapsed_ms(start: chrono::DateTime<chrono::Utc>) -> u64 {
+    (chrono::Utc::now() - start).num_milliseconds().max(0) as u64
+}
+
+#[component]
+fn SidePicker(label: &'static str, side: Signal<CompareSide>, models: Vec<ModelInfo>, disabled: bool) -> Element {
+    let current = side();
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-3 space-y-2",
+            p { class: "text-xs text-slate-400", "{label}" }
+            select {
+                class: "w-full px-2 py-1.5 bg-slate-700 border border-slate-600 rounded text-sm text-white",
+                disabled,
+                value: "{current.model_id}",
+                onchange: move |e| side.write().model_id = e.value(),
+                for model in models {
+                    option { value: "{model.id}", selected: model.id == current.model_id, "{model.name}" }
+                }
+            }
+            label {
+                class: "flex items-center gap-2 text-xs text-slate-400",
+                "Temperature"
+                input {
+                    r#type: "range",
+                    class: "flex-1",
+                    min: "0",
+                    max: "1.5",
+                    step: "0.1",
+                    disabled,
+                    value: "{current.temperature}",
+                    oninput: move |e| {
+                        if let Ok(temperature) = e.value().parse::<f32>() {
+                            side.write().temperature = temperature;
+                        }
+                    },
+                }
+                span { class: "w-8 text-right", "{current.temperature:.1}" }
+            }
+        }
+    }
+}
+
+#[component]
+fn AnswerColumn(answer: Option<CompareAnswer>, highlight: bool) -> Element {
+    let Some(answer) = answer else {
+        return rsx! {
+            div { class: "bg-slate-800/50 rounded-lg p-4 min-h-[120px] text-sm text-slate-500", "No answer yet" }
+        };
+    };
+    let html = render_markdown(&answer.response, true);
+    let border = if highlight { "border-green-500" } else { "border-slate-700" };
+
+    rsx! {
+        div {
+            class: "bg-slate-800 border {border} rounded-lg p-4 min-h-[120px]",
+            p {
+                class: "text-xs text-slate-400 mb-2",
+                "{answer.side.label()}"
+                if let Some(stats) = &answer.generation {
+                    span { class: "ml-2 text-slate-500", "· {stats.summary()}" }
+                }
+            }
+            div {
+                class: "prose prose-invert prose-sm max-w-none",
+                dangerous_inner_html: "{html}"
+            }
+        }
+    }
+}
+
+#[component]
+fn ComparisonRow(comparison: Comparison) -> Element {
+    let mut expanded = use_signal(|| false);
+    let created = comparison.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+    let outcome = match comparison.vote {
+        Some(CompareVote::Left) => format!("{} won", comparison.left.side.label()),
+        Some(CompareVote::Right) => format!("{} won", comparison.right.side.label()),
+        Some(CompareVote::Tie) => "Tie".to_string(),
+        None => "No vote".to_string(),
+    };
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-3",
+            button {
+                class: "w-full flex items-center gap-3 text-left",
+                onclick: move |_| expanded.set(!expanded()),
+                span { class: "flex-1 text-sm text-white truncate", "{comparison.prompt}" }
+                span { class: "text-xs text-slate-400", "{outcome}" }
+                span { class: "text-xs text-slate-500", "{created}" }
+            }
+            if expanded() {
+                div {
+                    class: "grid grid-cols-2 gap-4 mt-3",
+                    AnswerColumn { answer: Some(comparison.left.clone()), highlight: comparison.vote == Some(CompareVote::Left) }
+                    AnswerColumn { answer: Some(comparison.right.clone()), highlight: comparison.vote == Some(CompareVote::Right) }
+                }
+            }
+        }
+    }
+}
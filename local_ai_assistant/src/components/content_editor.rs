@@ -7,14 +7,20 @@
 use dioxus::prelude::*;
 
 use crate::models::content_template::{
-    ArticleTemplate, EditorContent, EditorSection,
-    get_builtin_templates,
+    ArticleTemplate, EditorContent, EditorSection, SectionExpansionEvent,
+    get_builtin_templates, parse_outline, default_outline,
+};
+use crate::models::{
+    ContentSegment, ContentTranslation, DiffKind, FormatProfile, ReadabilityRewrite, ResponseLanguage, SimilarityReport,
+    TARGET_GRADES, grade_label, grade_level, highlight_segments, split_diagrams, word_diff,
 };
 use crate::server_functions::{
-    fetch_rss_entries, extract_article_content, generate_outline, expand_section,
-    generate_image_prompt,
+    fetch_rss_entries, extract_article_content, ingest_youtube_video, generate_outline, expand_section,
+    expand_sections, generate_image_prompt, translate_content, proofread_section,
+    check_draft_similarity, rewrite_section_to_grade,
 };
 use crate::server_functions::server_image_gen::generate_image_simple;
+use super::{AssetsPanel, Diagram, ErrorNotice};
 
 /// Content Editor Panel component
 #[component]
@@ -30,8 +36,32 @@ pub fn ContentEditorPanel(
     let mut rss_url = use_signal(|| String::new());
     let mut rss_entries: Signal<Vec<(String, String, String)>> = use_signal(|| Vec::new()); // (title, url, summary)
     let mut article_url = use_signal(|| String::new());
+    // YouTube video to add to the knowledge base, optionally as the draft's source
+    let mut video_url = use_signal(String::new);
+    let mut draft_from_video = use_signal(|| true);
+    let mut video_status: Signal<Option<String>> = use_signal(|| None);
     let mut active_section: Signal<Option<usize>> = use_signal(|| None);
+    // Section the asset library picker is open for
+    let mut library_section: Signal<Option<usize>> = use_signal(|| None);
+    // Sections still being written by "Expand All"
+    let mut expanding_sections: Signal<Vec<usize>> = use_signal(Vec::new);
+    // Whether outline or section text is streaming in, and "Stop" to abandon it
+    let mut is_streaming = use_signal(|| false);
+    let mut cancel_requested = use_signal(|| false);
+    // Translations of the draft, one per language
+    let mut translations: Signal<Vec<ContentTranslation>> = use_signal(Vec::new);
+    let mut translation_languages: Signal<Vec<ResponseLanguage>> = use_signal(Vec::new);
+    // Extra comma-separated language names or codes, e.g. "ja, pt-BR"
+    let mut custom_languages = use_signal(String::new);
+    // Latest scan of the draft against the knowledge base
+    let mut similarity_report: Signal<Option<SimilarityReport>> = use_signal(|| None);
+    // Platform the header "Export" formats the article for
+    let mut export_profile = use_signal(FormatProfile::default);
+    let formatted_export = use_memo(move || export_profile().apply(&editor_content.read().article()));
     let mut show_preview = use_signal(|| false);
+    // Reading grade sections are rewritten to, and the rewrite under review
+    let mut target_grade = use_signal(|| 8u8);
+    let mut rewrite_preview: Signal<Option<(usize, ReadabilityRewrite)>> = use_signal(|| None);
     
     // File import state (unused for now but prepared for drag/drop)
     let _drag_hover = use_signal(|| false);
@@ -40,6 +70,7 @@ pub fn ContentEditorPanel(
     let mut handle_select_template = move |template: ArticleTemplate| {
         let content = EditorContent::from_template(&template);
         editor_content.set(content);
+        export_profile.set(FormatProfile::for_platform(&template.platform));
         selected_template.set(Some(template));
     };
 
@@ -61,7 +92,7 @@ pub fn ContentEditorPanel(
                     is_generating.set(false);
                 }
                 Err(e) => {
-                    error_message.set(Some(format!("Failed to fetch RSS: {:?}", e)));
+                    error_message.set(Some(format!("Failed to fetch RSS: {}", e)));
                     is_generating.set(false);
                 }
             }
@@ -91,14 +122,51 @@ pub fn ContentEditorPanel(
                     is_generating.set(false);
                 }
                 Err(e) => {
-                    error_message.set(Some(format!("Failed to extract article: {:?}", e)));
+                    error_message.set(Some(format!("Failed to extract article: {}", e)));
                     is_generating.set(false);
                 }
             }
         });
     };
 
-    // Handle outline generation
+    // Handle YouTube ingestion: summary and transcript go to the knowledge
+    // base, and the summary can seed the draft
+    let mut handle_ingest_video = move |_| {
+        let url = video_url.read().trim().to_string();
+        if url.is_empty() {
+            error_message.set(Some("Please enter a YouTube URL".to_string()));
+            return;
+        }
+
+        is_generating.set(true);
+        error_message.set(None);
+        video_status.set(Some("Fetching transcript and summarizing...".to_string()));
+
+        spawn(async move {
+            match ingest_youtube_video(url, None).await {
+                Ok(video) => {
+                    let source = if video.transcribed_locally { "transcribed locally" } else { "from captions" };
+                    video_status.set(Some(format!("Added \"{}\" to the knowledge base ({})", video.title, source)));
+                    if draft_from_video() {
+                        let mut ec = editor_content.read().clone();
+                        ec.title = video.title.clone();
+                        if let Some(section) = ec.sections.first_mut() {
+                            section.content = format!("{}\n\nSource: {}", video.summary, video.url);
+                        }
+                        editor_content.set(ec);
+                    }
+                    video_url.set(String::new());
+                }
+                Err(e) => {
+                    video_status.set(None);
+                    error_message.set(Some(format!("Failed to add video: {}", e)));
+                }
+            }
+            is_generating.set(false);
+        });
+    };
+
+    // Handle outline generation, filling in sections as the outline streams in
     let mut handle_generate_outline = move |_| {
         let title = editor_content.read().title.clone();
         let template_name = selected_template.read()
@@ -112,29 +180,38 @@ pub fn ContentEditorPanel(
         }
 
         is_generating.set(true);
+        cancel_requested.set(false);
+        is_streaming.set(true);
         error_message.set(None);
 
         spawn(async move {
             match generate_outline(title, template_name).await {
-                Ok(sections) => {
-                    let mut ec = editor_content.read().clone();
-                    ec.sections = sections.into_iter().map(|(title, prompt)| {
-                        let mut s = EditorSection::new(&title);
-                        s.content = prompt;
-                        s
-                    }).collect();
-                    editor_content.set(ec);
-                    is_generating.set(false);
+                Ok(mut stream) => {
+                    let mut response = String::new();
+                    while let Some(Ok(chunk)) = stream.next().await {
+                        if cancel_requested() {
+                            break;
+                        }
+                        response.push_str(&chunk);
+                        let parsed = parse_outline(&response);
+                        if !parsed.is_empty() {
+                            set_outline(editor_content, parsed);
+                        }
+                    }
+                    if parse_outline(&response).is_empty() && !cancel_requested() {
+                        set_outline(editor_content, default_outline());
+                    }
                 }
                 Err(e) => {
-                    error_message.set(Some(format!("Failed to generate outline: {:?}", e)));
-                    is_generating.set(false);
+                    error_message.set(Some(format!("Failed to generate outline: {}", e)));
                 }
             }
+            is_streaming.set(false);
+            is_generating.set(false);
         });
     };
 
-    // Handle section expansion
+    // Handle section expansion, showing the text as it streams in
     let mut handle_expand_section = move |index: usize| {
         let ec = editor_content.read().clone();
         if let Some(section) = ec.sections.get(index) {
@@ -142,30 +219,229 @@ pub fn ContentEditorPanel(
             let context = ec.title.clone();
 
             is_generating.set(true);
+            cancel_requested.set(false);
+            is_streaming.set(true);
             active_section.set(Some(index));
 
             spawn(async move {
                 match expand_section(section_title, context).await {
-                    Ok(content) => {
-                        let mut ec = editor_content.read().clone();
-                        if let Some(section) = ec.sections.get_mut(index) {
-                            section.content = content;
-                            section.is_generated = true;
+                    Ok(mut stream) => {
+                        let mut content = String::new();
+                        while let Some(Ok(chunk)) = stream.next().await {
+                            if cancel_requested() {
+                                break;
+                            }
+                            content.push_str(&chunk);
+                            if let Some(section) = editor_content.write().sections.get_mut(index) {
+                                section.content = content.clone();
+                            }
+                        }
+                        if let Some(section) = editor_content.write().sections.get_mut(index) {
+                            section.content = content.trim().to_string();
+                            section.is_generated = !cancel_requested();
                         }
-                        editor_content.set(ec);
-                        is_generating.set(false);
-                        active_section.set(None);
                     }
                     Err(e) => {
-                        error_message.set(Some(format!("Failed to expand section: {:?}", e)));
-                        is_generating.set(false);
-                        active_section.set(None);
+                        error_message.set(Some(format!("Failed to expand section: {}", e)));
                     }
                 }
+                is_streaming.set(false);
+                is_generating.set(false);
+                active_section.set(None);
             });
         }
     };
 
+    // Handle batched expansion of every section not generated yet
+    let handle_expand_all = move |_| {
+        let ec = editor_content.read().clone();
+        let pending: Vec<(usize, String)> = ec.sections.iter()
+            .enumerate()
+            .filter(|(_, s)| !s.is_generated)
+            .map(|(i, s)| (i, s.title.clone()))
+            .collect();
+
+        if ec.title.trim().is_empty() {
+            error_message.set(Some("Please enter a title first".to_string()));
+            return;
+        }
+        if pending.is_empty() {
+            error_message.set(Some("All sections are already expanded".to_string()));
+            return;
+        }
+
+        is_generating.set(true);
+        cancel_requested.set(false);
+        is_streaming.set(true);
+        error_message.set(None);
+        expanding_sections.set(pending.iter().map(|(i, _)| *i).collect());
+
+        spawn(async move {
+            match expand_sections(pending, ec.title).await {
+                Ok(mut stream) => {
+                    let mut buffer = String::new();
+                    let mut failed = Vec::new();
+                    while let Some(Ok(chunk)) = stream.next().await {
+                        if cancel_requested() {
+                            break;
+                        }
+                        buffer.push_str(&chunk);
+                        for event in SectionExpansionEvent::drain_lines(&mut buffer) {
+                            let mut ec = editor_content.read().clone();
+                            match event {
+                                SectionExpansionEvent::Started { index } => {
+                                    if let Some(section) = ec.sections.get_mut(index) {
+                                        section.content.clear();
+                                    }
+                                }
+                                SectionExpansionEvent::Token { index, text } => {
+                                    if let Some(section) = ec.sections.get_mut(index) {
+                                        section.content.push_str(&text);
+                                    }
+                                }
+                                SectionExpansionEvent::Done { index, content } => {
+                                    if let Some(section) = ec.sections.get_mut(index) {
+                                        section.content = content;
+                                        section.is_generated = true;
+                                    }
+                                    expanding_sections.write().retain(|i| *i != index);
+                                }
+                                SectionExpansionEvent::Failed { index, error } => {
+                                    if let Some(section) = ec.sections.get(index) {
+                                        failed.push(format!("{}: {}", section.title, error));
+                                    }
+                                    expanding_sections.write().retain(|i| *i != index);
+                                }
+                            }
+                            editor_content.set(ec);
+                        }
+                    }
+                    if !failed.is_empty() {
+                        error_message.set(Some(format!("Failed to expand sections: {}", failed.join("; "))));
+                    }
+                }
+                Err(e) => {
+                    error_message.set(Some(format!("Failed to expand sections: {}", e)));
+                }
+            }
+            expanding_sections.set(Vec::new());
+            is_streaming.set(false);
+            is_generating.set(false);
+        });
+    };
+
+    // Handle translation of the draft into the selected languages
+    let handle_translate = move |_| {
+        let ec = editor_content.read().clone();
+        let mut languages: Vec<String> = translation_languages.read().iter().map(|l| l.as_str().to_string()).collect();
+        languages.extend(custom_languages.read().split(',').map(|l| l.trim().to_string()).filter(|l| !l.is_empty()));
+
+        if ec.sections.iter().all(|s| s.content.trim().is_empty()) {
+            error_message.set(Some("Write or generate the draft before translating".to_string()));
+            return;
+        }
+        if languages.is_empty() {
+            error_message.set(Some("Select at least one language".to_string()));
+            return;
+        }
+
+        is_generating.set(true);
+        error_message.set(None);
+
+        spawn(async move {
+            let mut package = ec.to_package();
+            package.translations = translations();
+            match translate_content(package, languages).await {
+                Ok(package) => translations.set(package.translations),
+                Err(e) => error_message.set(Some(format!("Failed to translate: {}", e))),
+            }
+            is_generating.set(false);
+        });
+    };
+
+    // Handle proofreading of one section
+    let mut handle_proofread = move |index: usize| {
+        let Some(content) = editor_content.read().sections.get(index).map(|s| s.content.clone()) else {
+            return;
+        };
+        if content.trim().is_empty() {
+            error_message.set(Some("Section is empty. Generate text first.".to_string()));
+            return;
+        }
+
+        is_generating.set(true);
+        active_section.set(Some(index));
+
+        spawn(async move {
+            match proofread_section(content).await {
+                Ok(review) => {
+                    if let Some(section) = editor_content.write().sections.get_mut(index) {
+                        section.review = Some(review);
+                    }
+                }
+                Err(e) => error_message.set(Some(format!("Failed to proofread: {}", e))),
+            }
+            is_generating.set(false);
+            active_section.set(None);
+        });
+    };
+
+    // Rewrites one section to the target reading grade, for review
+    let mut handle_rewrite_level = move |index: usize| {
+        let Some(content) = editor_content.read().sections.get(index).map(|s| s.content.clone()) else {
+            return;
+        };
+        if content.trim().is_empty() {
+            error_message.set(Some("Section is empty. Generate text first.".to_string()));
+            return;
+        }
+
+        is_generating.set(true);
+        active_section.set(Some(index));
+        error_message.set(None);
+
+        spawn(async move {
+            match rewrite_section_to_grade(content, f32::from(target_grade())).await {
+                Ok(rewrite) => rewrite_preview.set(Some((index, rewrite))),
+                Err(e) => error_message.set(Some(format!("Failed to rewrite: {}", e))),
+            }
+            is_generating.set(false);
+            active_section.set(None);
+        });
+    };
+
+    // Replaces an issue's excerpt with its suggestion
+    let mut handle_apply_fix = move |index: usize, issue: usize| {
+        let mut ec = editor_content.write();
+        if let Some(section) = ec.sections.get_mut(index) {
+            if let Some(review) = section.review.as_mut() {
+                if let Some(fixed) = review.apply_fix(&section.content, issue) {
+                    section.content = fixed;
+                }
+            }
+        }
+    };
+
+    // Handle the similarity scan against the knowledge base
+    let handle_check_similarity = move |_| {
+        let sections = editor_content.read().to_package().sections;
+        if sections.iter().all(|(_, text)| text.trim().is_empty()) {
+            error_message.set(Some("Write or generate the draft first".to_string()));
+            return;
+        }
+
+        is_generating.set(true);
+        error_message.set(None);
+
+        spawn(async move {
+            match check_draft_similarity(sections).await {
+                Ok(report) => similarity_report.set(Some(report)),
+                Err(e) => error_message.set(Some(format!("Failed to check similarity: {}", e))),
+            }
+            is_generating.set(false);
+        });
+    };
+
     // Handle Image Generation
     let mut handle_generate_image = move |index: usize| {
          let ec = editor_content.read().clone();
@@ -197,14 +473,14 @@ pub fn ContentEditorPanel(
                                 active_section.set(None);
                             },
                             Err(e) => {
-                                error_message.set(Some(format!("Failed to generate image: {:?}", e)));
+                                error_message.set(Some(format!("Failed to generate image: {}", e)));
                                 is_generating.set(false);
                                 active_section.set(None);
                             }
                         }
                     },
                     Err(e) => {
-                         error_message.set(Some(format!("Failed to generate image prompt: {:?}", e)));
+                         error_message.set(Some(format!("Failed to generate image prompt: {}", e)));
                          is_generating.set(false);
                          active_section.set(None);
                     }
@@ -213,13 +489,6 @@ pub fn ContentEditorPanel(
         }
     };
 
-    // Handle export
-    let handle_export_markdown = move |_| {
-        let md = editor_content.read().to_markdown();
-        // In a real implementation, this would trigger a download
-        web_sys::console::log_1(&format!("Markdown:\n{}", md).into());
-    };
-
     rsx! {
         div {
             class: "flex-1 flex flex-col h-full overflow-hidden",
@@ -250,11 +519,56 @@ pub fn ContentEditorPanel(
                         onclick: move |_| show_preview.set(!show_preview()),
                         "Preview"
                     }
-                    // Export button
+                    // Similarity check
                     button {
+                        class: "px-3 py-1.5 text-sm bg-slate-700 text-slate-300 rounded hover:bg-slate-600 disabled:opacity-50",
+                        disabled: is_generating(),
+                        title: "Flag passages that closely match your knowledge base",
+                        onclick: handle_check_similarity,
+                        "Similarity Check"
+                    }
+                    // Reading grade for "Rewrite to Level"
+                    select {
+                        class: "px-2 py-1.5 text-sm bg-slate-700 border border-slate-600 rounded text-white",
+                        title: "Reading grade sections are rewritten to",
+                        value: "{target_grade()}",
+                        onchange: move |e| {
+                            if let Ok(grade) = e.value().parse::<u8>() {
+                                target_grade.set(grade);
+                            }
+                        },
+                        for (grade, label) in TARGET_GRADES.iter() {
+                            option { key: "{grade}", value: "{grade}", "{label}" }
+                        }
+                    }
+                    // Export, formatted for the selected platform
+                    select {
+                        class: "px-2 py-1.5 text-sm bg-slate-700 border border-slate-600 rounded text-white",
+                        value: "{FormatProfile::all().iter().position(|p| *p == export_profile()).unwrap_or(0)}",
+                        onchange: move |e| {
+                            if let Some(profile) = e.value().parse::<usize>().ok().and_then(|i| FormatProfile::all().get(i)) {
+                                export_profile.set(*profile);
+                            }
+                        },
+                        for (i, profile) in FormatProfile::all().iter().enumerate() {
+                            option { key: "{i}", value: "{i}", "{profile.display_name()}" }
+                        }
+                    }
+                    a {
                         class: "px-3 py-1.5 text-sm bg-green-600 text-white rounded hover:bg-green-700",
-                        onclick: handle_export_markdown,
-                        "Export MD"
+                        href: "{formatted_export.read().data_url()}",
+                        download: "{editor_content.read().title}.{export_profile().file_extension()}",
+                        "Export"
+                    }
+                }
+            }
+
+            // Limits the export exceeds on the selected platform
+            if !formatted_export.read().warnings.is_empty() {
+                div {
+                    class: "px-6 py-2 border-b border-yellow-700 bg-yellow-900/20 text-sm text-yellow-300 space-y-0.5",
+                    for warning in formatted_export.read().warnings.iter() {
+                        p { key: "{warning}", "⚠ {warning}" }
                     }
                 }
             }
@@ -362,6 +676,42 @@ pub fn ContentEditorPanel(
                         }
                     }
                     
+                    // YouTube Import section
+                    div {
+                        class: "p-4 border-b border-slate-700",
+                        h3 {
+                            class: "text-sm font-semibold text-slate-300 mb-3",
+                            "YouTube Video"
+                        }
+                        div {
+                            class: "space-y-2",
+                            input {
+                                class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded text-white text-sm placeholder-slate-400",
+                                placeholder: "https://youtube.com/watch?v=...",
+                                value: "{video_url}",
+                                oninput: move |e| video_url.set(e.value()),
+                            }
+                            label {
+                                class: "flex items-center gap-2 text-xs text-slate-400",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: draft_from_video(),
+                                    onchange: move |e| draft_from_video.set(e.value().parse::<bool>().unwrap_or(false)),
+                                }
+                                "Draft an article from the summary"
+                            }
+                            button {
+                                class: "w-full px-3 py-2 bg-red-600 text-white text-sm rounded hover:bg-red-700",
+                                disabled: is_generating(),
+                                onclick: handle_ingest_video,
+                                if is_generating() { "Working..." } else { "Add Video" }
+                            }
+                            if let Some(status) = video_status() {
+                                div { class: "text-xs text-slate-400", "{status}" }
+                            }
+                        }
+                    }
+
                     // Local File Import
                     div {
                         class: "p-4",
@@ -418,6 +768,86 @@ pub fn ContentEditorPanel(
                                 onclick: handle_generate_outline,
                                 if is_generating() { "Generating..." } else { "Generate Outline" }
                             }
+                            button {
+                                class: "px-4 py-2 bg-slate-600 text-white text-sm rounded hover:bg-slate-500 disabled:opacity-50",
+                                disabled: is_generating() || editor_content.read().sections.is_empty(),
+                                onclick: handle_expand_all,
+                                if expanding_sections.read().is_empty() {
+                                    "Expand All"
+                                } else {
+                                    "Expanding {expanding_sections.read().len()} sections..."
+                                }
+                            }
+                            if is_streaming() {
+                                button {
+                                    class: "px-4 py-2 bg-red-600 text-white text-sm rounded hover:bg-red-700",
+                                    onclick: move |_| cancel_requested.set(true),
+                                    "Stop"
+                                }
+                            }
+                        }
+
+                        // Translations
+                        details {
+                            class: "mt-3 text-sm",
+                            summary { class: "cursor-pointer text-slate-300", "Translations ({translations.read().len()})" }
+                            div {
+                                class: "mt-2 space-y-2",
+                                div {
+                                    class: "flex flex-wrap gap-3",
+                                    for language in ResponseLanguage::all().iter().cloned() {
+                                        label {
+                                            key: "{language.as_str()}",
+                                            class: "flex items-center gap-1 text-slate-300",
+                                            input {
+                                                r#type: "checkbox",
+                                                checked: translation_languages.read().contains(&language),
+                                                onchange: {
+                                                    let language = language.clone();
+                                                    move |_| {
+                                                        let mut selected = translation_languages.write();
+                                                        if let Some(pos) = selected.iter().position(|l| *l == language) {
+                                                            selected.remove(pos);
+                                                        } else {
+                                                            selected.push(language.clone());
+                                                        }
+                                                    }
+                                                },
+                                            }
+                                            "{language.as_str()}"
+                                        }
+                                    }
+                                }
+                                div {
+                                    class: "flex gap-2",
+                                    input {
+                                        class: "flex-1 px-3 py-1.5 bg-slate-700 border border-slate-600 rounded text-white text-sm placeholder-slate-400",
+                                        placeholder: "Other languages, e.g. ja, pt-BR",
+                                        value: "{custom_languages}",
+                                        oninput: move |e| custom_languages.set(e.value()),
+                                    }
+                                    button {
+                                        class: "px-4 py-1.5 bg-teal-600 text-white text-sm rounded hover:bg-teal-700 disabled:opacity-50",
+                                        disabled: is_generating(),
+                                        onclick: handle_translate,
+                                        "Translate"
+                                    }
+                                }
+                                for translation in translations.read().iter() {
+                                    div {
+                                        key: "{translation.language}",
+                                        class: "flex items-center gap-3 px-3 py-2 bg-slate-800 rounded",
+                                        span { class: "text-white", "{translation.language}" }
+                                        span { class: "flex-1 text-slate-400 truncate", "{translation.title}" }
+                                        a {
+                                            class: "px-2 py-1 text-xs bg-green-600 text-white rounded hover:bg-green-700",
+                                            href: "{translation.markdown_data_url()}",
+                                            download: "{editor_content.read().title}-{translation.language}.md",
+                                            "Export MD"
+                                        }
+                                    }
+                                }
+                            }
                         }
                     }
 
@@ -425,6 +855,47 @@ pub fn ContentEditorPanel(
                     div {
                         class: "flex-1 overflow-y-auto p-4 space-y-4",
 
+                        if let Some(report) = similarity_report() {
+                            div {
+                                class: if report.matches.is_empty() {
+                                    "p-3 rounded-lg border border-green-700 bg-green-900/20 text-sm"
+                                } else {
+                                    "p-3 rounded-lg border border-yellow-700 bg-yellow-900/20 text-sm"
+                                },
+                                div {
+                                    class: "flex items-center justify-between",
+                                    span {
+                                        class: "text-slate-200",
+                                        if report.matches.is_empty() {
+                                            "No close matches in {report.passages_checked} passages"
+                                        } else {
+                                            "{report.matches.len()} of {report.passages_checked} passages closely match your knowledge base"
+                                        }
+                                    }
+                                    button {
+                                        class: "text-xs text-slate-400 hover:text-white",
+                                        onclick: move |_| similarity_report.set(None),
+                                        "Dismiss"
+                                    }
+                                }
+                                for (i, m) in report.matches.iter().enumerate() {
+                                    details {
+                                        key: "{i}",
+                                        class: "mt-2",
+                                        summary {
+                                            class: "cursor-pointer text-yellow-300",
+                                            "{m.section} — {m.source_title} ({m.score:.2})"
+                                        }
+                                        div {
+                                            class: "mt-1 grid grid-cols-2 gap-2 text-xs",
+                                            p { class: "p-2 bg-slate-800 rounded text-slate-300 whitespace-pre-wrap", "{m.passage}" }
+                                            p { class: "p-2 bg-slate-800 rounded text-slate-400 whitespace-pre-wrap", "{m.source_text}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
                         if editor_content.read().sections.is_empty() {
                             div {
                                 class: "text-center py-12 text-slate-400",
@@ -450,11 +921,44 @@ pub fn ContentEditorPanel(
                                     }
                                     div {
                                         class: "flex items-center gap-2",
-                                        if active_section() == Some(index) {
+                                        if active_section() == Some(index) || expanding_sections.read().contains(&index) {
                                             div {
                                                 class: "w-4 h-4 border-2 border-orange-400 border-t-transparent rounded-full animate-spin"
                                             }
                                         }
+                                        if let Some(review) = &section.review {
+                                            span {
+                                                class: if review.score >= 85 {
+                                                    "px-2 py-0.5 text-xs rounded bg-green-900/40 text-green-300"
+                                                } else if review.score >= 60 {
+                                                    "px-2 py-0.5 text-xs rounded bg-yellow-900/40 text-yellow-300"
+                                                } else {
+                                                    "px-2 py-0.5 text-xs rounded bg-red-900/40 text-red-300"
+                                                },
+                                                title: "Quality score",
+                                                "{review.score}/100"
+                                            }
+                                        }
+                                        if let Some(grade) = grade_level(&section.content) {
+                                            span {
+                                                class: "px-2 py-0.5 text-xs rounded bg-slate-700 text-slate-300",
+                                                title: "Flesch-Kincaid reading grade: {grade_label(grade)}",
+                                                "Grade {grade:.1}"
+                                            }
+                                        }
+                                        button {
+                                            class: "px-2 py-1 text-xs bg-slate-600 text-white rounded hover:bg-slate-500",
+                                            disabled: is_generating(),
+                                            title: "Rewrite the section until it reads at the selected grade",
+                                            onclick: move |_| handle_rewrite_level(index),
+                                            "Rewrite to Level"
+                                        }
+                                        button {
+                                            class: "px-2 py-1 text-xs bg-slate-600 text-white rounded hover:bg-slate-500",
+                                            disabled: is_generating(),
+                                            onclick: move |_| handle_proofread(index),
+                                            "Proofread"
+                                        }
                                         // Generate Image Button
                                         button {
                                              class: "px-2 py-1 text-xs bg-purple-600 text-white rounded hover:bg-purple-700 flex items-center gap-1",
@@ -474,6 +978,15 @@ pub fn ContentEditorPanel(
                                              }
                                              "Add Image"
                                         }
+                                        button {
+                                            class: "px-2 py-1 text-xs bg-slate-600 text-white rounded hover:bg-slate-500",
+                                            title: "Insert an image or clip from the asset library",
+                                            onclick: move |_| {
+                                                let open = library_section() == Some(index);
+                                                library_section.set(if open { None } else { Some(index) });
+                                            },
+                                            "From Library"
+                                        }
                                         button {
                                             class: "px-3 py-1 text-xs bg-orange-600 text-white rounded hover:bg-orange-700",
                                             disabled: is_generating(),
@@ -500,6 +1013,119 @@ pub fn ContentEditorPanel(
                                             }
                                         },
                                     }
+
+                                    if library_section() == Some(index) {
+                                        AssetsPanel {
+                                            compact: true,
+                                            on_insert: move |snippet: String| {
+                                                let mut ec = editor_content.read().clone();
+                                                if let Some(section) = ec.sections.get_mut(index) {
+                                                    section.content.push_str(&format!("\n\n{}\n\n", snippet));
+                                                }
+                                                editor_content.set(ec);
+                                                library_section.set(None);
+                                            },
+                                        }
+                                    }
+
+                                    // Readability rewrite under review, as a diff against the section
+                                    if let Some((_, rewrite)) = rewrite_preview().filter(|(i, _)| *i == index) {
+                                        div {
+                                            class: "mt-3 p-3 bg-slate-900/50 rounded space-y-2",
+                                            p {
+                                                class: if rewrite.reached_target() { "text-xs text-green-400" } else { "text-xs text-yellow-400" },
+                                                {format!(
+                                                    "Grade {:.1} → {:.1} (target {:.0}, {} rewrites)",
+                                                    rewrite.grade_before.unwrap_or_default(),
+                                                    rewrite.grade_after.unwrap_or_default(),
+                                                    rewrite.target,
+                                                    rewrite.attempts
+                                                )}
+                                                if !rewrite.reached_target() {
+                                                    " — closest version kept"
+                                                }
+                                            }
+                                            if rewrite.text == rewrite.original {
+                                                p { class: "text-xs text-slate-400", "No change needed" }
+                                            } else {
+                                                p {
+                                                    class: "text-sm text-slate-300 whitespace-pre-wrap",
+                                                    for (i, (kind, text)) in word_diff(&rewrite.original, &rewrite.text).into_iter().enumerate() {
+                                                        match kind {
+                                                            DiffKind::Same => rsx! { span { key: "{i}", "{text}" } },
+                                                            DiffKind::Removed => rsx! { del { key: "{i}", class: "bg-red-900/40 text-red-300", "{text}" } },
+                                                            DiffKind::Added => rsx! { ins { key: "{i}", class: "bg-green-900/40 text-green-300 no-underline", "{text}" } },
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                            div {
+                                                class: "flex gap-2",
+                                                button {
+                                                    class: "px-2 py-0.5 text-xs bg-green-600 text-white rounded hover:bg-green-700",
+                                                    onclick: move |_| {
+                                                        if let Some((i, rewrite)) = rewrite_preview() {
+                                                            if let Some(section) = editor_content.write().sections.get_mut(i) {
+                                                                section.content = rewrite.text;
+                                                                section.review = None;
+                                                            }
+                                                        }
+                                                        rewrite_preview.set(None);
+                                                    },
+                                                    "Accept"
+                                                }
+                                                button {
+                                                    class: "px-2 py-0.5 text-xs bg-slate-600 text-white rounded hover:bg-slate-500",
+                                                    onclick: move |_| rewrite_preview.set(None),
+                                                    "Discard"
+                                                }
+                                            }
+                                        }
+                                    }
+
+                                    // Proofreading issues, highlighted in the text
+                                    if let Some(review) = section.review.clone().filter(|r| !r.issues.is_empty()) {
+                                        div {
+                                            class: "mt-3 space-y-2",
+                                            p {
+                                                class: "p-3 bg-slate-900/50 rounded text-sm text-slate-300 whitespace-pre-wrap",
+                                                for (segment, issue) in highlight_segments(&section.content, &review.issues) {
+                                                    if let Some(i) = issue {
+                                                        mark {
+                                                            class: "bg-yellow-500/30 text-yellow-100 rounded",
+                                                            title: "{review.issues[i].kind.display_name()}: {review.issues[i].explanation}",
+                                                            "{segment}"
+                                                        }
+                                                    } else {
+                                                        "{segment}"
+                                                    }
+                                                }
+                                            }
+                                            for (i, issue) in review.issues.iter().enumerate() {
+                                                div {
+                                                    key: "{i}",
+                                                    class: "flex items-center gap-2 text-xs",
+                                                    span { class: "w-24 flex-shrink-0 text-yellow-400", "{issue.kind.display_name()}" }
+                                                    span {
+                                                        class: "flex-1 text-slate-400 truncate",
+                                                        title: "{issue.explanation}",
+                                                        if issue.suggestion.is_empty() {
+                                                            "{issue.excerpt}"
+                                                        } else {
+                                                            "{issue.excerpt} → {issue.suggestion}"
+                                                        }
+                                                    }
+                                                    if !issue.suggestion.is_empty() {
+                                                        button {
+                                                            class: "px-2 py-0.5 bg-green-600 text-white rounded hover:bg-green-700",
+                                                            onclick: move |_| handle_apply_fix(index, i),
+                                                            "Fix"
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -516,7 +1142,14 @@ pub fn ContentEditorPanel(
                         }
                         div {
                             class: "prose prose-invert prose-sm max-w-none",
-                            dangerous_inner_html: "{editor_content.read().to_html()}"
+                            for (i, segment) in split_diagrams(&editor_content.read().to_markdown()).into_iter().enumerate() {
+                                match segment {
+                                    ContentSegment::Markdown(markdown) => rsx! {
+                                        div { key: "{i}", dangerous_inner_html: comrak::markdown_to_html(&markdown, &comrak::Options::default()) }
+                                    },
+                                    ContentSegment::Diagram(block) => rsx! { Diagram { key: "{i}", block } },
+                                }
+                            }
                         }
 
                         // Word count
@@ -531,10 +1164,25 @@ pub fn ContentEditorPanel(
             // Error message
             if let Some(err) = error_message() {
                 div {
-                    class: "px-6 py-3 bg-red-900/50 border-t border-red-700 text-red-300 text-sm",
-                    "{err}"
+                    class: "px-6 py-3 border-t border-red-700",
+                    ErrorNotice { error: err, on_dismiss: move |_| error_message.set(None) }
                 }
             }
         }
     }
 }
+
+/// Replaces the editor sections with an outline, using each description as
+/// the section's placeholder content
+fn set_outline(mut editor_content: Signal<EditorContent>, outline: Vec<(String, String)>) {
+    let mut ec = editor_content.read().clone();
+    // Keep section IDs stable while the outline streams in
+    ec.sections = outline.into_iter().enumerate().map(|(i, (title, prompt))| {
+        let mut s = ec.sections.get(i).cloned().unwrap_or_else(|| EditorSection::new(&title));
+        s.title = title;
+        s.content = prompt;
+        s.is_generated = false;
+        s
+    }).collect();
+    editor_content.set(ec);
+}
@@ -0,0 +1,1412 @@
+//! Content Pipeline Panel Component
+//!
+//! Starts multi-stage content pipeline jobs, or research-only jobs that
+//! write a brief from several sources, shows per-stage progress, and lets
+//! the user review, edit and approve checkpoints. Generated key takeaways
+//! and FAQ can be left out of the article, SEO and sharing metadata
+//! edited, with defaults for the author and site, and keywords researched
+//! against the draft. Generated images can
+//! be cropped, resized, compressed and upscaled, video clips arranged on a
+//! timeline and assembled, and finished packages narrated and exported as a
+//! static site.
+
+use dioxus::prelude::*;
+
+use crate::models::{
+    ArticleExtras, ContentPackage, Feature, FormatProfile, KeywordReport, GenerationStage, ImageEdit, ImageOutputFormat, JobState, PackageImage,
+    PipelineDefinition, PipelineJob, ResearchBrief, SeoDefaults, SeoMetadata, UpscaleFactor, VideoClip, AssembledVideo, SubtitleOptions, SubtitleSource,
+    move_clip, error_message,
+};
+use crate::server_functions::{
+    start_content_pipeline, get_content_jobs, approve_content_stage, resume_content_job, delete_content_job,
+    process_package_image, upscale_package_image, narrate_content_package, export_content_site,
+    add_package_video_clip, set_package_video_clips, set_package_subtitle_options, assemble_package_video,
+    get_video_thumbnail, set_package_extras, set_package_seo, get_seo_defaults, save_seo_defaults,
+    suggest_package_keywords,
+};
+use super::status_bar::sleep_ms;
+use super::{ErrorNotice, MissingTools, use_feature_available};
+
+/// How often job progress is refreshed, in milliseconds
+const POLL_INTERVAL_MS: u32 = 2000;
+
+#[component]
+pub fn ContentPipelinePanel() -> Element {
+    let mut topic = use_signal(String::new);
+    let mut pipeline: Signal<PipelineDefinition> = use_signal(PipelineDefinition::default);
+    let mut jobs: Signal<Vec<PipelineJob>> = use_signal(Vec::new);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    // Poll jobs for as long as the panel is mounted
+    use_effect(move || {
+        spawn(async move {
+            loop {
+                if let Ok(latest) = get_content_jobs().await {
+                    jobs.set(latest);
+                }
+                sleep_ms(POLL_INTERVAL_MS).await;
+            }
+        });
+    });
+
+    let refresh = move || {
+        spawn(async move {
+            if let Ok(latest) = get_content_jobs().await {
+                jobs.set(latest);
+            }
+        });
+    };
+
+    // Starts a job with the canonical stage order
+    let start = move |mut definition: PipelineDefinition| {
+        definition.stages = GenerationStage::all().iter().copied().filter(|s| definition.stages.contains(s)).collect();
+        error.set(None);
+        spawn(async move {
+            match start_content_pipeline(topic(), definition).await {
+                Ok(_) => {
+                    topic.set(String::new());
+                    refresh();
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let current = pipeline();
+    let feeds = current.research_sources.feeds.join("\n");
+    let urls = current.research_sources.urls.join("\n");
+
+    rsx! {
+        div {
+            class: "flex-1 overflow-y-auto p-6",
+            div {
+                class: "max-w-4xl mx-auto space-y-6",
+
+                // New pipeline
+                div {
+                    class: "bg-slate-800 rounded-lg p-4 space-y-4",
+                    h2 { class: "text-lg font-semibold text-white", "New Content Package" }
+                    input {
+                        class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                        placeholder: "Topic, e.g. Running LLMs locally on a Mac",
+                        value: "{topic}",
+                        oninput: move |e| topic.set(e.value()),
+                    }
+                    div {
+                        class: "grid grid-cols-3 gap-2",
+                        for stage in GenerationStage::all().iter().copied() {
+                            div {
+                                key: "{stage.display_name()}",
+                                class: "p-2 bg-slate-700/50 rounded-lg space-y-1",
+                                label {
+                                    class: "flex items-center gap-2 text-sm text-white",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: current.stages.contains(&stage),
+                                        onchange: move |_| toggle_stage(&mut pipeline.write().stages, stage),
+                                    }
+                                    "{stage.display_name()}"
+                                }
+                                label {
+                                    class: "flex items-center gap-2 text-xs text-slate-400",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: current.checkpoints.contains(&stage),
+                                        onchange: move |_| toggle_stage(&mut pipeline.write().checkpoints, stage),
+                                    }
+                                    "Review before continuing"
+                                }
+                            }
+                        }
+                    }
+                    details {
+                        class: "text-sm",
+                        summary { class: "cursor-pointer text-slate-300", "Research sources" }
+                        p {
+                            class: "mt-2 text-xs text-slate-400",
+                            "Research reads these pages and the feed entries about the topic, plus web search hits and the knowledge base, and merges their key points into a brief."
+                        }
+                        div {
+                            class: "mt-2 grid grid-cols-2 gap-2",
+                            textarea {
+                                class: "h-20 px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-xs text-white placeholder-slate-500",
+                                placeholder: "RSS feed URLs, one per line",
+                                value: "{feeds}",
+                                oninput: move |e| pipeline.write().research_sources.feeds = split_lines(&e.value()),
+                            }
+                            textarea {
+                                class: "h-20 px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-xs text-white placeholder-slate-500",
+                                placeholder: "Page URLs, one per line",
+                                value: "{urls}",
+                                oninput: move |e| pipeline.write().research_sources.urls = split_lines(&e.value()),
+                            }
+                        }
+                    }
+                    div {
+                        class: "flex justify-end gap-2",
+                        button {
+                            class: "px-4 py-2 text-sm bg-slate-600 hover:bg-slate-500 rounded-lg transition-colors disabled:opacity-50",
+                            disabled: topic().trim().is_empty(),
+                            title: "Only research the topic and write a brief",
+                            onclick: move |_| start(PipelineDefinition {
+                                stages: vec![GenerationStage::Research],
+                                checkpoints: Vec::new(),
+                                ..pipeline()
+                            }),
+                            "Research Only"
+                        }
+                        button {
+                            class: "px-4 py-2 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors disabled:opacity-50",
+                            disabled: topic().trim().is_empty() || current.stages.is_empty(),
+                            onclick: move |_| start(pipeline()),
+                            "Start Pipeline"
+                        }
+                    }
+                    if let Some(msg) = error() {
+                        ErrorNotice { error: msg, on_dismiss: move |_| error.set(None) }
+                    }
+                }
+                SeoDefaultsSettings {}
+
+                // Jobs
+                for job in jobs() {
+                    PipelineJobCard {
+                        key: "{job.progress.job_id}",
+                        job: job.clone(),
+                        on_changed: move |_| refresh(),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Author and site filled into SEO metadata as it is generated
+#[component]
+fn SeoDefaultsSettings() -> Element {
+    let mut defaults: Signal<SeoDefaults> = use_signal(SeoDefaults::default);
+    let mut status: Signal<Option<Result<String, String>>> = use_signal(|| None);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(saved) = get_seo_defaults().await {
+                defaults.set(saved);
+            }
+        });
+    });
+
+    let current = defaults();
+    let input_class = "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white";
+
+    rsx! {
+        details {
+            class: "bg-slate-800 rounded-lg p-4 text-sm",
+            summary { class: "cursor-pointer text-slate-300", "SEO defaults" }
+            div {
+                class: "mt-3 space-y-3",
+                p {
+                    class: "text-xs text-slate-400",
+                    "Filled into the SEO metadata of new packages for their Open Graph, Twitter Card and schema.org Article tags. A package's canonical URL is the site URL plus its slug."
+                }
+                div {
+                    class: "grid grid-cols-2 gap-3",
+                    input {
+                        class: input_class,
+                        placeholder: "Author",
+                        value: "{current.author}",
+                        oninput: move |e| defaults.write().author = e.value(),
+                    }
+                    input {
+                        class: input_class,
+                        placeholder: "Site name",
+                        value: "{current.site_name}",
+                        oninput: move |e| defaults.write().site_name = e.value(),
+                    }
+                    input {
+                        class: input_class,
+                        placeholder: "https://blog.example.com",
+                        value: "{current.site_url}",
+                        oninput: move |e| defaults.write().site_url = e.value(),
+                    }
+                    input {
+                        class: input_class,
+                        placeholder: "@account",
+                        value: "{current.twitter_handle}",
+                        oninput: move |e| defaults.write().twitter_handle = e.value(),
+                    }
+                }
+                div {
+                    class: "flex items-center gap-3",
+                    button {
+                        class: "px-3 py-1 text-xs bg-slate-600 hover:bg-slate-500 rounded transition-colors",
+                        onclick: move |_| {
+                            spawn(async move {
+                                let result = save_seo_defaults(defaults()).await
+                                    .map(|_| "Saved".to_string())
+                                    .map_err(error_message);
+                                status.set(Some(result));
+                            });
+                        },
+                        "Save defaults"
+                    }
+                    match status() {
+                        Some(Ok(message)) => rsx! { span { class: "text-xs text-green-400", "{message}" } },
+                        Some(Err(message)) => rsx! { span { class: "text-xs text-red-400", "{message}" } },
+                        None => rsx! {},
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Keyword clusters for the topic, least covered by the draft first, with
+/// sections suggested for the ones it misses
+#[component]
+fn KeywordResearch(job_id: String, keywords: Vec<String>, on_add: EventHandler<String>) -> Element {
+    let mut report: Signal<Option<KeywordReport>> = use_signal(|| None);
+    let mut is_loading = use_signal(|| false);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    rsx! {
+        div {
+            class: "space-y-2",
+            button {
+                class: "px-3 py-1 bg-slate-600 hover:bg-slate-500 rounded transition-colors disabled:opacity-50",
+                disabled: is_loading(),
+                title: "Group related terms from the knowledge base and brainstorming, and check how well the draft covers them",
+                onclick: move |_| {
+                    let job_id = job_id.clone();
+                    is_loading.set(true);
+                    error.set(None);
+                    spawn(async move {
+                        match suggest_package_keywords(job_id).await {
+                            Ok(latest) => report.set(Some(latest)),
+                            Err(e) => error.set(Some(error_message(&e))),
+                        }
+                        is_loading.set(false);
+                    });
+                },
+                if is_loading() { "Researching keywords..." } else { "Suggest keywords" }
+            }
+            if let Some(msg) = error() {
+                p { class: "text-red-400", "{msg}" }
+            }
+            if let Some(report) = report() {
+                p {
+                    class: "text-slate-500",
+                    "{report.clusters.len()} keyword groups, {report.passages_read} knowledge base passages read"
+                }
+                for (i, cluster) in report.clusters.iter().enumerate() {
+                    div {
+                        key: "{i}",
+                        class: "p-2 bg-slate-700/50 rounded space-y-1",
+                        p {
+                            class: if cluster.is_covered() { "text-green-400" } else { "text-yellow-400" },
+                            if cluster.is_covered() { "✓ " } else { "○ " }
+                            "{cluster.label}"
+                            span { class: "text-slate-500", " · {(cluster.coverage() * 100.0).round()}% coverage" }
+                        }
+                        div {
+                            class: "flex flex-wrap gap-1",
+                            for keyword in cluster.keywords.iter().cloned() {
+                                button {
+                                    key: "{keyword.term}",
+                                    class: if keyword.is_covered() { "px-2 py-0.5 rounded bg-slate-600 text-slate-200 disabled:opacity-50" } else { "px-2 py-0.5 rounded bg-slate-800 text-slate-400 disabled:opacity-50" },
+                                    title: "{keyword.source.display_name()}, {(keyword.coverage * 100.0).round()}% similar to the closest section. Click to add to the keywords.",
+                                    disabled: keywords.contains(&keyword.term),
+                                    onclick: {
+                                        let term = keyword.term.clone();
+                                        move |_| on_add.call(term.clone())
+                                    },
+                                    "{keyword.term}"
+                                }
+                            }
+                        }
+                        if let Some(section) = &cluster.suggested_section {
+                            p { class: "text-slate-400", span { class: "text-slate-300", "Suggested section: " } "{section}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A package's SEO and sharing metadata, editable, with the fields still
+/// needed for publishing
+#[component]
+fn SeoEditor(job_id: String, seo: SeoMetadata, cover_image: Option<String>, on_save: EventHandler<SeoMetadata>) -> Element {
+    let mut edited = use_signal(|| seo.clone());
+    // Start over from the package whenever its metadata changes
+    use_effect(use_reactive!(|(seo,)| edited.set(seo)));
+
+    let current = edited();
+    let missing = current.missing_fields().join(", ");
+    let schema = current.article_json_ld(cover_image.as_deref());
+    let input_class = "w-full px-2 py-1 bg-slate-700 border border-slate-600 rounded text-xs text-white";
+
+    rsx! {
+        details {
+            class: "text-sm",
+            summary { class: "cursor-pointer text-slate-300", "SEO & sharing" }
+            div {
+                class: "mt-2 space-y-2 text-xs",
+                div {
+                    class: "grid grid-cols-2 gap-2",
+                    input {
+                        class: input_class,
+                        placeholder: "SEO title",
+                        value: "{current.title}",
+                        oninput: move |e| edited.write().title = e.value(),
+                    }
+                    input {
+                        class: input_class,
+                        placeholder: "Slug",
+                        value: "{current.slug}",
+                        oninput: move |e| edited.write().slug = e.value(),
+                    }
+                    input {
+                        class: input_class,
+                        placeholder: "Author",
+                        value: "{current.author}",
+                        oninput: move |e| edited.write().author = e.value(),
+                    }
+                    input {
+                        class: input_class,
+                        placeholder: "Canonical URL",
+                        value: "{current.canonical_url}",
+                        oninput: move |e| edited.write().canonical_url = e.value(),
+                    }
+                    input {
+                        class: input_class,
+                        placeholder: "Site name",
+                        value: "{current.site_name}",
+                        oninput: move |e| edited.write().site_name = e.value(),
+                    }
+                    input {
+                        class: input_class,
+                        placeholder: "@account",
+                        value: "{current.twitter_handle}",
+                        oninput: move |e| edited.write().twitter_handle = e.value(),
+                    }
+                }
+                textarea {
+                    class: "{input_class} h-16",
+                    placeholder: "Meta description",
+                    value: "{current.meta_description}",
+                    oninput: move |e| edited.write().meta_description = e.value(),
+                }
+                p { class: "text-slate-400", span { class: "text-slate-300", "Keywords: " } {current.keywords.join(", ")} }
+                KeywordResearch {
+                    job_id,
+                    keywords: current.keywords.clone(),
+                    on_add: move |term: String| edited.write().keywords.push(term),
+                }
+                p {
+                    class: "text-slate-400",
+                    span { class: "text-slate-300", "Cover image: " }
+                    {cover_image.clone().unwrap_or_else(|| "none".to_string())}
+                }
+                if !missing.is_empty() {
+                    p { class: "text-yellow-400", "Needed before publishing: {missing}" }
+                }
+                details {
+                    summary { class: "cursor-pointer text-slate-400", "Article schema (JSON-LD)" }
+                    pre { class: "mt-1 p-2 bg-slate-900 rounded text-slate-400 whitespace-pre-wrap break-all", "{schema}" }
+                }
+                button {
+                    class: "px-3 py-1 bg-slate-600 hover:bg-slate-500 rounded transition-colors disabled:opacity-50",
+                    disabled: current == seo,
+                    onclick: move |_| on_save.call(edited()),
+                    "Save SEO"
+                }
+            }
+        }
+    }
+}
+
+/// Lines of a text box. Blank lines are kept so a newline being typed isn't
+/// undone; they are skipped when the sources are read.
+fn split_lines(text: &str) -> Vec<String> {
+    text.split('\n').map(|l| l.trim().to_string()).collect()
+}
+
+fn toggle_stage(stages: &mut Vec<GenerationStage>, stage: GenerationStage) {
+    if let Some(pos) = stages.iter().position(|s| *s == stage) {
+        stages.remove(pos);
+    } else {
+        stages.push(stage);
+    }
+}
+
+/// Sources behind the research notes, with a Markdown download
+#[component]
+fn ResearchBriefView(brief: ResearchBrief) -> Element {
+    let download_name = format!("research-{}.md", brief.topic.to_lowercase().split_whitespace().collect::<Vec<_>>().join("-"));
+
+    rsx! {
+        details {
+            class: "text-sm",
+            summary {
+                class: "cursor-pointer text-slate-300",
+                "Research brief ({brief.sources.len()} sources, {brief.key_points.len()} key points)"
+            }
+            div {
+                class: "mt-2 space-y-2",
+                ol {
+                    class: "list-decimal list-inside space-y-1",
+                    for (i, source) in brief.sources.iter().enumerate() {
+                        li {
+                            key: "{i}",
+                            class: "text-slate-400",
+                            if let Some(url) = &source.url {
+                                a { class: "text-blue-400 hover:underline", href: "{url}", target: "_blank", "{source.title}" }
+                            } else {
+                                span { class: "text-white", "{source.title}" }
+                            }
+                            span { class: "text-xs text-slate-500", " · {source.kind.display_name()}" }
+                            if let Some(error) = &source.error {
+                                p { class: "ml-5 text-xs text-red-400", "Not read: {error}" }
+                            } else if !source.summary.is_empty() {
+                                p { class: "ml-5 text-xs", "{source.summary}" }
+                            }
+                        }
+                    }
+                }
+                a {
+                    class: "inline-block px-3 py-1 text-xs bg-slate-600 hover:bg-slate-500 rounded transition-colors",
+                    href: "{brief.markdown_data_url()}",
+                    download: "{download_name}",
+                    "Download brief"
+                }
+            }
+        }
+    }
+}
+
+/// Key takeaways and FAQ with switches for including each in the article
+#[component]
+fn ArticleExtrasView(extras: ArticleExtras, on_change: EventHandler<ArticleExtras>) -> Element {
+    let schema = extras.faq_json_ld();
+
+    rsx! {
+        details {
+            class: "text-sm",
+            summary {
+                class: "cursor-pointer text-slate-300",
+                "Takeaways & FAQ ({extras.key_takeaways.len()} takeaways, {extras.faq.len()} questions)"
+            }
+            div {
+                class: "mt-2 space-y-3",
+                if !extras.key_takeaways.is_empty() {
+                    div {
+                        label {
+                            class: "flex items-center gap-2 text-xs text-slate-300",
+                            input {
+                                r#type: "checkbox",
+                                checked: extras.include_takeaways,
+                                onchange: {
+                                    let extras = extras.clone();
+                                    move |_| on_change.call(ArticleExtras { include_takeaways: !extras.include_takeaways, ..extras.clone() })
+                                },
+                            }
+                            "Include a \"Key takeaways\" box before the article"
+                        }
+                        ul {
+                            class: "mt-1 ml-5 list-disc text-slate-400",
+                            for (i, takeaway) in extras.key_takeaways.iter().enumerate() {
+                                li { key: "{i}", "{takeaway}" }
+                            }
+                        }
+                    }
+                }
+                if !extras.faq.is_empty() {
+                    div {
+                        label {
+                            class: "flex items-center gap-2 text-xs text-slate-300",
+                            input {
+                                r#type: "checkbox",
+                                checked: extras.include_faq,
+                                onchange: {
+                                    let extras = extras.clone();
+                                    move |_| on_change.call(ArticleExtras { include_faq: !extras.include_faq, ..extras.clone() })
+                                },
+                            }
+                            "Include the FAQ after the article, with FAQ schema markup in the exported page"
+                        }
+                        for (i, entry) in extras.faq.iter().enumerate() {
+                            div {
+                                key: "{i}",
+                                class: "mt-1 ml-5",
+                                p { class: "text-white", "{entry.question}" }
+                                p { class: "text-slate-400", "{entry.answer}" }
+                            }
+                        }
+                    }
+                }
+                if let Some(schema) = schema {
+                    details {
+                        class: "text-xs",
+                        summary { class: "cursor-pointer text-slate-400", "FAQ schema (JSON-LD)" }
+                        pre { class: "mt-1 p-2 bg-slate-900 rounded text-slate-400 whitespace-pre-wrap break-all", "{schema}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn PipelineJobCard(job: PipelineJob, on_changed: EventHandler<()>) -> Element {
+    let progress = job.progress.clone();
+    let job_id = progress.job_id.to_string();
+    // Edits to the artifacts under review, seeded from the job
+    let mut draft: Signal<ContentPackage> = use_signal(|| job.package.clone());
+    let mut action_error: Signal<Option<String>> = use_signal(|| None);
+    let mut is_narrating = use_signal(|| false);
+    // Folder of the last static-site export
+    let mut exported_to: Signal<Option<String>> = use_signal(|| None);
+
+    // Reseed the editable copy whenever the job reaches a new checkpoint
+    use_effect(use_reactive!(|(job,)| {
+        if job.progress.state == JobState::AwaitingApproval {
+            draft.set(job.package.clone());
+        }
+    }));
+
+    let awaiting = progress.state == JobState::AwaitingApproval;
+    let package = if awaiting { draft() } else { job.package.clone() };
+    let percent = progress.percent();
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            div {
+                class: "flex items-center gap-3",
+                h3 { class: "text-base font-medium text-white flex-1 truncate", "{progress.topic}" }
+                span {
+                    class: match progress.state {
+                        JobState::Running => "text-xs text-blue-400",
+                        JobState::AwaitingApproval => "text-xs text-yellow-400",
+                        JobState::Failed(_) => "text-xs text-red-400",
+                        JobState::Completed => "text-xs text-green-400",
+                    },
+                    "{progress.message}"
+                }
+                if progress.state != JobState::Running {
+                    button {
+                        class: "px-2 py-0.5 text-xs text-slate-400 hover:text-red-400 transition-colors",
+                        title: "Delete this job and its saved progress",
+                        onclick: {
+                            let job_id = job_id.clone();
+                            move |_| {
+                                let job_id = job_id.clone();
+                                spawn(async move {
+                                    match delete_content_job(job_id).await {
+                                        Ok(_) => on_changed.call(()),
+                                        Err(e) => action_error.set(Some(e.to_string())),
+                                    }
+                                });
+                            }
+                        },
+                        "Delete"
+                    }
+                }
+            }
+            div {
+                class: "w-full h-1.5 bg-slate-700 rounded-full overflow-hidden",
+                div { class: "h-full bg-blue-500 transition-all", style: "width: {percent}%" }
+            }
+            div {
+                class: "flex flex-wrap gap-2",
+                for stage in progress.pipeline.stages.iter().copied() {
+                    span {
+                        key: "{stage.display_name()}",
+                        class: if progress.completed.contains(&stage) {
+                            "px-2 py-0.5 text-xs rounded bg-green-900/40 text-green-300"
+                        } else if progress.current == Some(stage) {
+                            "px-2 py-0.5 text-xs rounded bg-blue-900/40 text-blue-300"
+                        } else {
+                            "px-2 py-0.5 text-xs rounded bg-slate-700 text-slate-400"
+                        },
+                        "{stage.display_name()}"
+                    }
+                }
+            }
+
+            if let JobState::Failed(reason) = &progress.state {
+                div {
+                    class: "flex items-center gap-3",
+                    p { class: "text-xs text-red-400 flex-1", "{reason}" }
+                    button {
+                        class: "px-3 py-1 text-xs bg-slate-600 hover:bg-slate-500 rounded transition-colors",
+                        onclick: {
+                            let job_id = job_id.clone();
+                            move |_| {
+                                let job_id = job_id.clone();
+                                spawn(async move {
+                                    match resume_content_job(job_id).await {
+                                        Ok(_) => on_changed.call(()),
+                                        Err(e) => action_error.set(Some(e.to_string())),
+                                    }
+                                });
+                            }
+                        },
+                        "Resume"
+                    }
+                }
+            }
+
+            // Artifacts
+            if !package.research_notes.is_empty() {
+                details {
+                    class: "text-sm",
+                    open: awaiting && progress.current == Some(GenerationStage::Research),
+                    summary { class: "cursor-pointer text-slate-300", "Research notes" }
+                    if awaiting && progress.current == Some(GenerationStage::Research) {
+                        textarea {
+                            class: "w-full mt-2 h-40 px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white",
+                            value: "{package.research_notes}",
+                            oninput: move |e| draft.write().research_notes = e.value(),
+                        }
+                    } else {
+                        p { class: "mt-2 text-slate-400 whitespace-pre-wrap", "{package.research_notes}" }
+                    }
+                }
+            }
+            if let Some(brief) = package.research_brief.clone() {
+                ResearchBriefView { brief }
+            }
+            if !package.outline.is_empty() {
+                details {
+                    class: "text-sm",
+                    open: awaiting && progress.current == Some(GenerationStage::Outline),
+                    summary { class: "cursor-pointer text-slate-300", "Outline ({package.outline.len()} sections)" }
+                    div {
+                        class: "mt-2 space-y-2",
+                        for (i, (title, brief)) in package.outline.iter().cloned().enumerate() {
+                            if awaiting && progress.current == Some(GenerationStage::Outline) {
+                                div {
+                                    key: "{i}",
+                                    class: "space-y-1",
+                                    input {
+                                        class: "w-full px-2 py-1 bg-slate-700 border border-slate-600 rounded text-sm text-white",
+                                        value: "{title}",
+                                        oninput: move |e| draft.write().outline[i].0 = e.value(),
+                                    }
+                                    input {
+                                        class: "w-full px-2 py-1 bg-slate-700 border border-slate-600 rounded text-xs text-slate-300",
+                                        value: "{brief}",
+                                        oninput: move |e| draft.write().outline[i].1 = e.value(),
+                                    }
+                                }
+                            } else {
+                                p { key: "{i}", class: "text-slate-400", span { class: "text-white", "{title}" } " — {brief}" }
+                            }
+                        }
+                    }
+                }
+            }
+            if !package.sections.is_empty() {
+                details {
+                    class: "text-sm",
+                    open: awaiting && progress.current == Some(GenerationStage::Draft),
+                    summary { class: "cursor-pointer text-slate-300", "Draft" }
+                    div {
+                        class: "mt-2 space-y-3",
+                        for (i, (title, text)) in package.sections.iter().cloned().enumerate() {
+                            div {
+                                key: "{i}",
+                                h4 { class: "text-white font-medium", "{title}" }
+                                if awaiting && progress.current == Some(GenerationStage::Draft) {
+                                    textarea {
+                                        class: "w-full mt-1 h-32 px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white",
+                                        value: "{text}",
+                                        oninput: move |e| draft.write().sections[i].1 = e.value(),
+                                    }
+                                } else {
+                                    p { class: "text-slate-400 whitespace-pre-wrap", "{text}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if !package.extras.is_empty() {
+                ArticleExtrasView {
+                    extras: package.extras.clone(),
+                    on_change: {
+                        let job_id = job_id.clone();
+                        move |extras: ArticleExtras| {
+                            // Under review the edits go out with the approval
+                            if awaiting {
+                                draft.write().extras = extras;
+                                return;
+                            }
+                            let job_id = job_id.clone();
+                            spawn(async move {
+                                match set_package_extras(job_id, extras).await {
+                                    Ok(_) => on_changed.call(()),
+                                    Err(e) => action_error.set(Some(e.to_string())),
+                                }
+                            });
+                        }
+                    },
+                }
+            }
+            for (i, image) in package.images.iter().cloned().enumerate() {
+                div {
+                    key: "{image.caption}",
+                    p {
+                        class: "text-xs text-slate-400",
+                        span { class: "text-slate-300", "🖼 {image.caption}: " }
+                        "{image.prompt}"
+                        if let Some(path) = image.path.clone() {
+                            span { class: "block font-mono text-slate-500", "{path}" }
+                        }
+                    }
+                    if image.path.is_some() && progress.current != Some(GenerationStage::Images) {
+                        ImageAssetEditor {
+                            job_id: job_id.clone(),
+                            index: i,
+                            image: image.clone(),
+                            on_processed: move |_| on_changed.call(()),
+                        }
+                        ImageUpscaler {
+                            job_id: job_id.clone(),
+                            index: i,
+                            image: image.clone(),
+                            on_upscaled: move |_| on_changed.call(()),
+                        }
+                    }
+                }
+            }
+            if let Some(seo) = package.seo.clone() {
+                SeoEditor {
+                    job_id: job_id.clone(),
+                    seo,
+                    cover_image: package.cover_image(),
+                    on_save: {
+                        let job_id = job_id.clone();
+                        move |seo: SeoMetadata| {
+                            // Under review the edits go out with the approval
+                            if awaiting {
+                                draft.write().seo = Some(seo);
+                                return;
+                            }
+                            let job_id = job_id.clone();
+                            spawn(async move {
+                                match set_package_seo(job_id, seo).await {
+                                    Ok(_) => on_changed.call(()),
+                                    Err(e) => action_error.set(Some(e.to_string())),
+                                }
+                            });
+                        }
+                    },
+                }
+            }
+            for post in package.social_posts.iter().cloned() {
+                div {
+                    key: "{post.platform}",
+                    class: "p-2 bg-slate-700/50 rounded text-xs",
+                    p { class: "text-slate-300 font-medium", "{post.platform}" }
+                    p { class: "text-slate-400 whitespace-pre-wrap", "{post.text}" }
+                }
+            }
+
+            if let Some(narration) = package.narration.clone() {
+                p {
+                    class: "text-xs text-slate-400",
+                    span { class: "text-slate-300", "🔊 Narration: " }
+                    "{narration.engine}, about {narration.duration_ms / 1000}s"
+                    span { class: "block font-mono text-slate-500", "{narration.path}" }
+                }
+            }
+            if progress.state != JobState::Running {
+                VideoTimeline {
+                    job_id: job_id.clone(),
+                    clips: package.video_clips.clone(),
+                    video: package.video.clone(),
+                    subtitle_options: package.subtitle_options.clone(),
+                    narrated: package.narration.is_some(),
+                    on_changed: move |_| on_changed.call(()),
+                }
+            }
+            if !package.sections.is_empty() && progress.state != JobState::Running {
+                div {
+                    class: "flex items-center gap-2",
+                    button {
+                        class: "px-3 py-1 text-xs bg-slate-600 hover:bg-slate-500 rounded transition-colors disabled:opacity-50",
+                        disabled: is_narrating(),
+                        title: "Read the article aloud with the best available TTS engine",
+                        onclick: {
+                            let job_id = job_id.clone();
+                            move |_| {
+                                let job_id = job_id.clone();
+                                is_narrating.set(true);
+                                spawn(async move {
+                                    match narrate_content_package(job_id).await {
+                                        Ok(_) => on_changed.call(()),
+                                        Err(e) => action_error.set(Some(e.to_string())),
+                                    }
+                                    is_narrating.set(false);
+                                });
+                            }
+                        },
+                        if is_narrating() {
+                            "Narrating..."
+                        } else if package.narration.is_some() {
+                            "Re-narrate"
+                        } else {
+                            "Narrate"
+                        }
+                    }
+                    button {
+                        class: "px-3 py-1 text-xs bg-slate-600 hover:bg-slate-500 rounded transition-colors",
+                        title: "Write index.html with the images, narration and video into a folder",
+                        onclick: {
+                            let job_id = job_id.clone();
+                            move |_| {
+                                let job_id = job_id.clone();
+                                spawn(async move {
+                                    match export_content_site(job_id).await {
+                                        Ok(dir) => exported_to.set(Some(dir)),
+                                        Err(e) => action_error.set(Some(e.to_string())),
+                                    }
+                                });
+                            }
+                        },
+                        "Export Site"
+                    }
+                    if let Some(dir) = exported_to() {
+                        span { class: "text-xs font-mono text-green-400 truncate", "Exported to {dir}" }
+                    }
+                }
+            }
+
+            if awaiting {
+                div {
+                    class: "flex justify-end",
+                    button {
+                        class: "px-4 py-2 text-sm bg-green-600 hover:bg-green-700 rounded-lg transition-colors",
+                        onclick: {
+                            let job_id = job_id.clone();
+                            move |_| {
+                                let job_id = job_id.clone();
+                                spawn(async move {
+                                    match approve_content_stage(job_id, Some(draft())).await {
+                                        Ok(_) => on_changed.call(()),
+                                        Err(e) => action_error.set(Some(e.to_string())),
+                                    }
+                                });
+                            }
+                        },
+                        "Approve & Continue"
+                    }
+                }
+            }
+            if let Some(msg) = action_error() {
+                ErrorNotice { error: msg, on_dismiss: move |_| action_error.set(None) }
+            }
+        }
+    }
+}
+
+/// Crop, resize and compression settings for one package image
+#[component]
+fn ImageAssetEditor(job_id: String, index: usize, image: PackageImage, on_processed: EventHandler<()>) -> Element {
+    let mut edit: Signal<ImageEdit> = use_signal(|| {
+        image.processed.as_ref().map(|p| p.edit.clone()).unwrap_or_else(|| ImageEdit::for_profile(FormatProfile::default()))
+    });
+    let mut is_processing = use_signal(|| false);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    let current = edit();
+    let input_class = "w-16 px-1.5 py-0.5 bg-slate-700 border border-slate-600 rounded text-white";
+
+    rsx! {
+        details {
+            class: "mt-1 text-xs",
+            summary { class: "cursor-pointer text-slate-300", "Prepare for export" }
+            div {
+                class: "mt-2 p-2 bg-slate-700/50 rounded space-y-2",
+                div {
+                    class: "flex flex-wrap items-center gap-2 text-slate-400",
+                    "Size for"
+                    for profile in FormatProfile::all().iter().copied() {
+                        button {
+                            key: "{profile.display_name()}",
+                            class: "px-2 py-0.5 bg-slate-600 hover:bg-slate-500 rounded text-slate-200",
+                            onclick: move |_| {
+                                let preset = ImageEdit::for_profile(profile);
+                                let mut edit = edit.write();
+                                edit.width = preset.width;
+                                edit.height = preset.height;
+                            },
+                            "{profile.display_name()}"
+                        }
+                    }
+                }
+                div {
+                    class: "flex flex-wrap items-center gap-2 text-slate-400",
+                    "Width"
+                    input {
+                        class: input_class, r#type: "number", min: "1", value: "{current.width}",
+                        oninput: move |e| if let Ok(v) = e.value().parse() { edit.write().width = v },
+                    }
+                    "Height"
+                    input {
+                        class: input_class, r#type: "number", min: "1", value: "{current.height}",
+                        oninput: move |e| if let Ok(v) = e.value().parse() { edit.write().height = v },
+                    }
+                }
+                // Crop region in percent of the original
+                div {
+                    class: "flex flex-wrap items-center gap-2 text-slate-400",
+                    "Crop % — left"
+                    input {
+                        class: input_class, r#type: "number", min: "0", max: "100", value: "{(current.crop.x * 100.0).round()}",
+                        oninput: move |e| if let Ok(v) = e.value().parse::<f32>() { edit.write().crop.x = v / 100.0 },
+                    }
+                    "top"
+                    input {
+                        class: input_class, r#type: "number", min: "0", max: "100", value: "{(current.crop.y * 100.0).round()}",
+                        oninput: move |e| if let Ok(v) = e.value().parse::<f32>() { edit.write().crop.y = v / 100.0 },
+                    }
+                    "width"
+                    input {
+                        class: input_class, r#type: "number", min: "1", max: "100", value: "{(current.crop.width * 100.0).round()}",
+                        oninput: move |e| if let Ok(v) = e.value().parse::<f32>() { edit.write().crop.width = v / 100.0 },
+                    }
+                    "height"
+                    input {
+                        class: input_class, r#type: "number", min: "1", max: "100", value: "{(current.crop.height * 100.0).round()}",
+                        oninput: move |e| if let Ok(v) = e.value().parse::<f32>() { edit.write().crop.height = v / 100.0 },
+                    }
+                }
+                div {
+                    class: "flex flex-wrap items-center gap-2 text-slate-400",
+                    select {
+                        class: "px-1.5 py-0.5 bg-slate-700 border border-slate-600 rounded text-white",
+                        value: "{current.format.extension()}",
+                        onchange: move |e| {
+                            if let Some(format) = ImageOutputFormat::all().iter().find(|f| f.extension() == e.value()) {
+                                edit.write().format = *format;
+                            }
+                        },
+                        for format in ImageOutputFormat::all() {
+                            option { key: "{format.extension()}", value: "{format.extension()}", "{format.display_name()}" }
+                        }
+                    }
+                    if current.format == ImageOutputFormat::Jpeg {
+                        "Quality"
+                        input {
+                            class: input_class, r#type: "number", min: "1", max: "100", value: "{current.quality}",
+                            oninput: move |e| if let Ok(v) = e.value().parse() { edit.write().quality = v },
+                        }
+                    }
+                    "Max KB"
+                    input {
+                        class: input_class, r#type: "number", min: "0", placeholder: "none",
+                        value: "{current.max_kb.map(|kb| kb.to_string()).unwrap_or_default()}",
+                        oninput: move |e| edit.write().max_kb = e.value().parse().ok().filter(|kb| *kb > 0),
+                    }
+                    button {
+                        class: "ml-auto px-3 py-1 bg-blue-600 hover:bg-blue-700 rounded text-white disabled:opacity-50",
+                        disabled: is_processing(),
+                        onclick: move |_| {
+                            let job_id = job_id.clone();
+                            is_processing.set(true);
+                            error.set(None);
+                            spawn(async move {
+                                match process_package_image(job_id, index, edit()).await {
+                                    Ok(_) => on_processed.call(()),
+                                    Err(e) => error.set(Some(error_message(&e))),
+                                }
+                                is_processing.set(false);
+                            });
+                        },
+                        if is_processing() { "Processing..." } else { "Process" }
+                    }
+                }
+                if let Some(processed) = image.processed.clone() {
+                    p {
+                        class: if processed.over_target() { "text-yellow-400" } else { "text-green-400" },
+                        "{processed.width}×{processed.height} · {processed.size_bytes / 1024} KB"
+                        if let Some(quality) = processed.quality {
+                            " · quality {quality}"
+                        }
+                        if processed.over_target() {
+                            " · above the size target"
+                        }
+                        span { class: "block font-mono text-slate-500", "{processed.path}" }
+                    }
+                }
+                if let Some(msg) = error() {
+                    p { class: "text-red-400", "{msg}" }
+                }
+            }
+        }
+    }
+}
+
+/// Makes 2× and 4× copies of a package image, optionally fixing faces
+#[component]
+fn ImageUpscaler(job_id: String, index: usize, image: PackageImage, on_upscaled: EventHandler<()>) -> Element {
+    let mut face_fix = use_signal(|| false);
+    let mut upscaling: Signal<Option<UpscaleFactor>> = use_signal(|| None);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    rsx! {
+        details {
+            class: "mt-1 text-xs",
+            summary { class: "cursor-pointer text-slate-300", "Upscale" }
+            div {
+                class: "mt-2 p-2 bg-slate-700/50 rounded space-y-2",
+                div {
+                    class: "flex flex-wrap items-center gap-2 text-slate-400",
+                    for factor in UpscaleFactor::all().iter().copied() {
+                        button {
+                            key: "{factor.label()}",
+                            class: "px-3 py-1 bg-blue-600 hover:bg-blue-700 rounded text-white disabled:opacity-50",
+                            disabled: upscaling().is_some(),
+                            onclick: {
+                                let job_id = job_id.clone();
+                                move |_| {
+                                    let job_id = job_id.clone();
+                                    upscaling.set(Some(factor));
+                                    error.set(None);
+                                    spawn(async move {
+                                        match upscale_package_image(job_id, index, factor, face_fix()).await {
+                                            Ok(_) => on_upscaled.call(()),
+                                            Err(e) => error.set(Some(error_message(&e))),
+                                        }
+                                        upscaling.set(None);
+                                    });
+                                }
+                            },
+                            if upscaling() == Some(factor) { "Upscaling..." } else { "{factor.label()}" }
+                        }
+                    }
+                    label {
+                        class: "flex items-center gap-1",
+                        input {
+                            r#type: "checkbox",
+                            checked: face_fix(),
+                            onchange: move |e| face_fix.set(e.value().parse::<bool>().unwrap_or(false)),
+                        }
+                        "Fix faces"
+                    }
+                }
+                for copy in image.upscaled.iter() {
+                    p {
+                        key: "{copy.path}",
+                        class: "text-green-400",
+                        "{copy.factor.label()} · {copy.width}×{copy.height} · {copy.method.display_name()}"
+                        if copy.face_fix {
+                            " · faces fixed"
+                        }
+                        span { class: "block font-mono text-slate-500", "{copy.path}" }
+                    }
+                }
+                if let Some(msg) = error() {
+                    p { class: "text-red-400", "{msg}" }
+                }
+            }
+        }
+    }
+}
+
+/// Clips of the package video in play order, with optional title cards,
+/// its subtitle options, and the button that joins them
+#[component]
+fn VideoTimeline(
+    job_id: String,
+    clips: Vec<VideoClip>,
+    video: Option<AssembledVideo>,
+    subtitle_options: SubtitleOptions,
+    narrated: bool,
+    on_changed: EventHandler<()>,
+) -> Element {
+    let mut source = use_signal(String::new);
+    let mut is_adding = use_signal(|| false);
+    let mut is_assembling = use_signal(|| false);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+    let ffmpeg_found = use_feature_available(Feature::VideoAssembly);
+
+    // Every edit saves the whole timeline
+    let save = {
+        let job_id = job_id.clone();
+        move |clips: Vec<VideoClip>| {
+            let job_id = job_id.clone();
+            error.set(None);
+            spawn(async move {
+                match set_package_video_clips(job_id, clips).await {
+                    Ok(_) => on_changed.call(()),
+                    Err(e) => error.set(Some(error_message(&e))),
+                }
+            });
+        }
+    };
+    let save_subtitles = {
+        let job_id = job_id.clone();
+        move |options: SubtitleOptions| {
+            let job_id = job_id.clone();
+            error.set(None);
+            spawn(async move {
+                match set_package_subtitle_options(job_id, options).await {
+                    Ok(_) => on_changed.call(()),
+                    Err(e) => error.set(Some(error_message(&e))),
+                }
+            });
+        }
+    };
+    let count = clips.len();
+    let assemble_hint = if narrated {
+        "Join the clips with the narration as the soundtrack"
+    } else {
+        "Join the clips; narrate the article first to add a soundtrack"
+    };
+    let input_class = "px-2 py-1 bg-slate-700 border border-slate-600 rounded text-white";
+
+    rsx! {
+        details {
+            class: "text-xs",
+            open: !clips.is_empty(),
+            summary { class: "cursor-pointer text-slate-300", "🎬 Video timeline ({count} clips)" }
+            div {
+                class: "mt-2 p-2 bg-slate-700/50 rounded space-y-2",
+                MissingTools { feature: Feature::VideoAssembly }
+                for (i, clip) in clips.iter().cloned().enumerate() {
+                    div {
+                        key: "{clip.id}",
+                        class: "flex items-center gap-2",
+                        span { class: "w-5 text-slate-500", "{i + 1}." }
+                        VideoThumbnail { job_id: job_id.clone(), path: clip.thumbnail.clone() }
+                        div {
+                            class: "flex-1 min-w-0",
+                            p { class: "text-slate-300 truncate", title: "{clip.path}", "{clip.label}" }
+                            input {
+                                class: "w-full mt-1 {input_class}",
+                                placeholder: "Title card (optional)",
+                                value: clip.title.clone().unwrap_or_default(),
+                                onchange: {
+                                    let clips = clips.clone();
+                                    let mut save = save.clone();
+                                    move |e: FormEvent| {
+                                        let mut clips = clips.clone();
+                                        let title = e.value().trim().to_string();
+                                        clips[i].title = if title.is_empty() { None } else { Some(title) };
+                                        save(clips);
+                                    }
+                                },
+                            }
+                        }
+                        button {
+                            class: "px-2 py-1 bg-slate-600 hover:bg-slate-500 rounded disabled:opacity-30",
+                            disabled: i == 0,
+                            title: "Move earlier",
+                            onclick: {
+                                let clips = clips.clone();
+                                let mut save = save.clone();
+                                move |_| {
+                                    let mut clips = clips.clone();
+                                    move_clip(&mut clips, i, i.saturating_sub(1));
+                                    save(clips);
+                                }
+                            },
+                            "↑"
+                        }
+                        button {
+                            class: "px-2 py-1 bg-slate-600 hover:bg-slate-500 rounded disabled:opacity-30",
+                            disabled: i + 1 == count,
+                            title: "Move later",
+                            onclick: {
+                                let clips = clips.clone();
+                                let mut save = save.clone();
+                                move |_| {
+                                    let mut clips = clips.clone();
+                                    move_clip(&mut clips, i, i + 1);
+                                    save(clips);
+                                }
+                            },
+                            "↓"
+                        }
+                        button {
+                            class: "px-2 py-1 bg-slate-600 hover:bg-red-600 rounded",
+                            title: "Remove from the timeline",
+                            onclick: {
+                                let clips = clips.clone();
+                                let mut save = save.clone();
+                                move |_| {
+                                    let mut clips = clips.clone();
+                                    clips.remove(i);
+                                    save(clips);
+                                }
+                            },
+                            "×"
+                        }
+                    }
+                }
+                div {
+                    class: "flex items-center gap-2",
+                    input {
+                        class: "flex-1 {input_class}",
+                        placeholder: "Video URL or file path",
+                        value: "{source}",
+                        oninput: move |e| source.set(e.value()),
+                    }
+                    button {
+                        class: "px-3 py-1 bg-slate-600 hover:bg-slate-500 rounded disabled:opacity-50",
+                        disabled: is_adding() || source().trim().is_empty(),
+                        onclick: {
+                            let job_id = job_id.clone();
+                            move |_| {
+                                let job_id = job_id.clone();
+                                is_adding.set(true);
+                                error.set(None);
+                                spawn(async move {
+                                    match add_package_video_clip(job_id, source(), String::new(), None).await {
+                                        Ok(_) => {
+                                            source.set(String::new());
+                                            on_changed.call(());
+                                        }
+                                        Err(e) => error.set(Some(error_message(&e))),
+                                    }
+                                    is_adding.set(false);
+                                });
+                            }
+                        },
+                        if is_adding() { "Adding..." } else { "Add clip" }
+                    }
+                }
+                div {
+                    class: "flex flex-wrap items-center gap-3 text-slate-400",
+                    label {
+                        class: "flex items-center gap-1",
+                        input {
+                            r#type: "checkbox",
+                            checked: subtitle_options.enabled,
+                            onchange: {
+                                let options = subtitle_options.clone();
+                                let mut save_subtitles = save_subtitles.clone();
+                                move |e: FormEvent| {
+                                    let enabled = e.value().parse::<bool>().unwrap_or(false);
+                                    save_subtitles(SubtitleOptions { enabled, ..options.clone() });
+                                }
+                            },
+                        }
+                        "Subtitles"
+                    }
+                    if subtitle_options.enabled {
+                        select {
+                            class: "{input_class}",
+                            value: "{subtitle_options.source.id()}",
+                            onchange: {
+                                let options = subtitle_options.clone();
+                                let mut save_subtitles = save_subtitles.clone();
+                                move |e: FormEvent| {
+                                    let source = SubtitleSource::from_id(&e.value());
+                                    save_subtitles(SubtitleOptions { source, ..options.clone() });
+                                }
+                            },
+                            for source in SubtitleSource::all().iter() {
+                                option {
+                                    key: "{source.id()}",
+                                    value: "{source.id()}",
+                                    selected: *source == subtitle_options.source,
+                                    "{source.display_name()}"
+                                }
+                            }
+                        }
+                        label {
+                            class: "flex items-center gap-1",
+                            title: "Draw the subtitles into the video as well as saving SRT and VTT files",
+                            input {
+                                r#type: "checkbox",
+                                checked: subtitle_options.burn_in,
+                                onchange: {
+                                    let options = subtitle_options.clone();
+                                    let mut save_subtitles = save_subtitles.clone();
+                                    move |e: FormEvent| {
+                                        let burn_in = e.value().parse::<bool>().unwrap_or(false);
+                                        save_subtitles(SubtitleOptions { burn_in, ..options.clone() });
+                                    }
+                                },
+                            }
+                            "Burn in"
+                        }
+                    }
+                }
+                div {
+                    class: "flex items-center gap-2",
+                    button {
+                        class: "px-3 py-1 bg-blue-600 hover:bg-blue-700 rounded text-white disabled:opacity-50",
+                        disabled: is_assembling() || !ffmpeg_found || clips.is_empty(),
+                        title: "{assemble_hint}",
+                        onclick: {
+                            let job_id = job_id.clone();
+                            move |_| {
+                                let job_id = job_id.clone();
+                                is_assembling.set(true);
+                                error.set(None);
+                                spawn(async move {
+                                    match assemble_package_video(job_id).await {
+                                        Ok(_) => on_changed.call(()),
+                                        Err(e) => error.set(Some(error_message(&e))),
+                                    }
+                                    is_assembling.set(false);
+                                });
+                            }
+                        },
+                        if is_assembling() { "Assembling..." } else { "Assemble video" }
+                    }
+                    if let Some(video) = video.clone() {
+                        VideoThumbnail { job_id: job_id.clone(), path: video.thumbnail.clone() }
+                        span {
+                            class: "text-green-400 truncate",
+                            "{video.clip_count} clips, {video.duration_ms / 1000}s"
+                            if video.with_narration {
+                                ", narrated"
+                            }
+                            if let Some(subtitles) = video.subtitles.clone() {
+                                if subtitles.burned_in {
+                                    ", {subtitles.cue_count} subtitles burned in"
+                                } else {
+                                    ", {subtitles.cue_count} subtitles"
+                                }
+                                span { class: "block font-mono text-slate-500", "{subtitles.srt_path}" }
+                            }
+                            span { class: "block font-mono text-slate-500", "{video.path}" }
+                        }
+                    }
+                }
+                if let Some(msg) = error() {
+                    p { class: "text-red-400", "{msg}" }
+                }
+            }
+        }
+    }
+}
+
+/// Small preview of a package clip or video, or a placeholder when it has
+/// no thumbnail
+#[component]
+fn VideoThumbnail(job_id: String, path: Option<String>) -> Element {
+    let thumbnail = use_resource(use_reactive!(|(job_id, path)| async move {
+        match path {
+            Some(path) => get_video_thumbnail(job_id, path).await.ok(),
+            None => None,
+        }
+    }));
+
+    match thumbnail() {
+        Some(Some(data_url)) => rsx! {
+            img { class: "w-20 h-12 object-cover rounded bg-black shrink-0", src: "{data_url}" }
+        },
+        _ => rsx! {
+            div { class: "w-20 h-12 flex items-center justify-center rounded bg-slate-800 text-slate-500 shrink-0", "🎬" }
+        },
+    }
+}
@@ -0,0 +1,108 @@
+//! Diagram Component
+//!
+//! Shows a Mermaid or Graphviz code block as a diagram rendered on the
+//! server, falling back to the code itself while rendering or when the
+//! renderer is unavailable, with how to install a missing renderer.
+
+use dioxus::prelude::*;
+use crate::models::{DiagramBlock, DiagramKind, Tool, error_message};
+use crate::server_functions::{export_diagram_png, render_diagram};
+use super::download::save_data_url;
+use super::use_tool_available;
+
+#[component]
+pub fn Diagram(block: DiagramBlock) -> Element {
+    let rendered = use_resource(use_reactive!(|(block,)| async move {
+        render_diagram(block.kind, block.source).await
+    }));
+    let mut show_code = use_signal(|| false);
+    let mut exporting = use_signal(|| false);
+    let mut export_error = use_signal(|| None::<String>);
+    let renderer = match block.kind {
+        DiagramKind::Mermaid => Tool::Mermaid,
+        DiagramKind::Graphviz => Tool::Graphviz,
+    };
+    let renderer_found = use_tool_available(renderer);
+
+    let export = {
+        let block = block.clone();
+        move |_| {
+            let block = block.clone();
+            exporting.set(true);
+            spawn(async move {
+                match export_diagram_png(block.kind, block.source).await {
+                    Ok(data_url) => {
+                        export_error.set(None);
+                        save_data_url("diagram.png", data_url);
+                    }
+                    Err(e) => export_error.set(Some(error_message(&e))),
+                }
+                exporting.set(false);
+            });
+        }
+    };
+
+    let code = rsx! {
+        pre {
+            class: "bg-slate-800/80 rounded-lg p-3 my-2 overflow-x-auto text-sm",
+            code { "{block.source}" }
+        }
+    };
+
+    match &*rendered.read() {
+        Some(Ok(svg_url)) => rsx! {
+            div {
+                class: "my-3 not-prose",
+                div {
+                    class: "bg-white rounded-lg p-3 overflow-x-auto flex justify-center",
+                    img {
+                        class: "max-w-full h-auto",
+                        src: "{svg_url}",
+                        alt: "{block.kind.display_name()} diagram",
+                    }
+                }
+                div {
+                    class: "flex items-center gap-3 mt-1 text-xs text-slate-400",
+                    button {
+                        class: "hover:text-white transition-colors",
+                        onclick: move |_| show_code.set(!show_code()),
+                        if show_code() { "Hide code" } else { "Show code" }
+                    }
+                    button {
+                        class: "hover:text-white transition-colors disabled:opacity-50",
+                        disabled: exporting(),
+                        onclick: export,
+                        if exporting() { "Exporting…" } else { "Export PNG" }
+                    }
+                    if let Some(error) = export_error() {
+                        span { class: "text-red-400", "{error}" }
+                    }
+                }
+                if show_code() {
+                    {code}
+                }
+            }
+        },
+        Some(Err(e)) => rsx! {
+            div {
+                class: "not-prose",
+                {code}
+                p {
+                    class: "text-xs text-slate-500 -mt-1 mb-2",
+                    if renderer_found {
+                        "{block.kind.display_name()} diagram not rendered: {e}"
+                    } else {
+                        "Install {renderer.name()} to render {block.kind.display_name()} diagrams: "
+                        code { class: "select-all", "{renderer.install_hint()}" }
+                    }
+                }
+            }
+        },
+        None => rsx! {
+            div {
+                class: "not-prose animate-pulse",
+                {code}
+            }
+        },
+    }
+}
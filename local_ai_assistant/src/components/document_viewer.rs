@@ -0,0 +1,231 @@
+//! Document Viewer
+//!
+//! Modal showing a context document's full stored text and the chunks it
+//! was split into for the index, with actions to drop a chunk from
+//! retrieval or embed it again, and the document's metadata and tags.
+
+use dioxus::prelude::*;
+use crate::models::{parse_document_tags, DocumentChunk, DocumentDetails, DocumentMetadata, error_message};
+use crate::server_functions::{delete_document_chunk, get_context_document_details, reembed_document_chunk, set_context_document_tags};
+
+#[derive(Clone, Copy, PartialEq)]
+enum ViewerTab {
+    Text,
+    Chunks,
+}
+
+/// Modal viewer for one context file; closed by setting `filename` to None
+#[component]
+pub fn DocumentViewer(filename: Signal<Option<String>>) -> Element {
+    let mut details: Signal<Option<DocumentDetails>> = use_signal(|| None);
+    let mut tab = use_signal(|| ViewerTab::Text);
+    let mut busy_chunk: Signal<Option<usize>> = use_signal(|| None);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    use_effect(move || {
+        let Some(name) = filename() else { return };
+        details.set(None);
+        error.set(None);
+        spawn(async move {
+            match get_context_document_details(name).await {
+                Ok(loaded) => details.set(Some(loaded)),
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+        });
+    });
+
+    let mut update_chunk = move |index: usize, reembed: bool| {
+        let Some(name) = filename() else { return };
+        busy_chunk.set(Some(index));
+        error.set(None);
+        spawn(async move {
+            let result = if reembed {
+                reembed_document_chunk(name, index).await
+            } else {
+                delete_document_chunk(name, index).await
+            };
+            match result {
+                Ok(updated) => details.set(Some(updated)),
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+            busy_chunk.set(None);
+        });
+    };
+
+    let Some(name) = filename() else { return rsx! {} };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 z-50 flex items-center justify-center bg-black/50",
+            onclick: move |_| filename.set(None),
+            div {
+                class: "w-full max-w-3xl max-h-[85vh] flex flex-col bg-slate-800 rounded-xl shadow-xl",
+                onclick: move |e| e.stop_propagation(),
+
+                div {
+                    class: "flex items-center gap-3 px-5 py-4 border-b border-slate-700",
+                    div {
+                        class: "flex-1 min-w-0",
+                        h2 { class: "text-base font-semibold text-white truncate", "{name}" }
+                        if let Some(doc) = details() {
+                            p {
+                                class: "text-xs text-slate-400 truncate",
+                                if doc.chunks.is_empty() {
+                                    "Not indexed yet"
+                                } else {
+                                    "{doc.embedded_count()} of {doc.chunks.len()} chunks embedded"
+                                }
+                            }
+                        }
+                    }
+                    div {
+                        class: "flex bg-slate-900 rounded-lg p-0.5 text-xs",
+                        for (label, value) in [("Text", ViewerTab::Text), ("Chunks", ViewerTab::Chunks)] {
+                            button {
+                                class: if tab() == value {
+                                    "px-3 py-1 rounded-md bg-slate-700 text-white"
+                                } else {
+                                    "px-3 py-1 rounded-md text-slate-400 hover:text-white"
+                                },
+                                onclick: move |_| tab.set(value),
+                                "{label}"
+                            }
+                        }
+                    }
+                    button {
+                        class: "p-1 text-slate-400 hover:text-white",
+                        onclick: move |_| filename.set(None),
+                        "×"
+                    }
+                }
+
+                if let Some(metadata) = details().and_then(|doc| doc.metadata) {
+                    MetadataBar { key: "{name}", filename: name.clone(), metadata, details, error }
+                }
+
+                if let Some(message) = error() {
+                    p { class: "px-5 pt-3 text-xs text-red-400", "{message}" }
+                }
+
+                div {
+                    class: "flex-1 overflow-y-auto p-5",
+                    match (details(), tab()) {
+                        (None, _) => rsx! {
+                            p { class: "text-sm text-slate-500", "Loading…" }
+                        },
+                        (Some(doc), ViewerTab::Text) => rsx! {
+                            pre { class: "whitespace-pre-wrap text-sm text-slate-200 font-mono", "{doc.body}" }
+                        },
+                        (Some(doc), ViewerTab::Chunks) if doc.chunks.is_empty() => rsx! {
+                            p {
+                                class: "text-sm text-slate-500",
+                                "This document has no chunks in the index. Click 'Reload Context Database' to index it."
+                            }
+                        },
+                        (Some(doc), ViewerTab::Chunks) => rsx! {
+                            div {
+                                class: "space-y-3",
+                                for chunk in doc.chunks {
+                                    ChunkRow {
+                                        key: "{chunk.index}",
+                                        busy: busy_chunk() == Some(chunk.index),
+                                        on_delete: move |index| update_chunk(index, false),
+                                        on_reembed: move |index| update_chunk(index, true),
+                                        chunk,
+                                    }
+                                }
+                            }
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Source type, date and collection of the document, with its tags
+#[component]
+fn MetadataBar(
+    filename: String,
+    metadata: DocumentMetadata,
+    mut details: Signal<Option<DocumentDetails>>,
+    mut error: Signal<Option<String>>,
+) -> Element {
+    let mut tags = use_signal(|| metadata.tags.join(", "));
+    let mut saving = use_signal(|| false);
+    let changed = parse_document_tags(&tags()) != metadata.tags;
+    let added = metadata.added_at.format("%Y-%m-%d").to_string();
+
+    rsx! {
+        div {
+            class: "flex items-center gap-3 px-5 py-2 border-b border-slate-700 text-xs text-slate-400",
+            span { "{metadata.source_type.display_name()}" }
+            span { "Added {added}" }
+            if let Some(collection) = metadata.collection.clone() {
+                span { "📁 {collection}" }
+            }
+            input {
+                class: "flex-1 px-2 py-1 bg-slate-900 border border-slate-700 rounded text-slate-200 placeholder-slate-600",
+                placeholder: "Tags, comma separated",
+                value: "{tags}",
+                oninput: move |e| tags.set(e.value()),
+            }
+            if changed {
+                button {
+                    class: "px-2 py-1 bg-blue-600 hover:bg-blue-700 rounded text-white disabled:opacity-50",
+                    disabled: saving(),
+                    onclick: move |_| {
+                        let filename = filename.clone();
+                        saving.set(true);
+                        spawn(async move {
+                            match set_context_document_tags(filename, parse_document_tags(&tags())).await {
+                                Ok(updated) => details.set(Some(updated)),
+                                Err(e) => error.set(Some(error_message(&e))),
+                            }
+                            saving.set(false);
+                        });
+                    },
+                    "Save tags"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ChunkRow(chunk: DocumentChunk, busy: bool, on_delete: EventHandler<usize>, on_reembed: EventHandler<usize>) -> Element {
+    let index = chunk.index;
+    let (status, status_class) = if chunk.embedded {
+        ("Embedded", "text-green-400")
+    } else {
+        ("Not embedded", "text-amber-400")
+    };
+
+    rsx! {
+        div {
+            class: if chunk.embedded { "bg-slate-700/60 rounded-lg p-3" } else { "bg-slate-700/30 rounded-lg p-3 opacity-70" },
+            div {
+                class: "flex items-center gap-3 text-xs mb-2",
+                span { class: "text-slate-300 font-medium", "Chunk {index + 1}" }
+                span { class: "text-slate-500", "bytes {chunk.start}–{chunk.end}" }
+                span { class: "{status_class}", "{status}" }
+                div { class: "flex-1" }
+                button {
+                    class: "px-2 py-0.5 rounded-md text-slate-300 hover:text-white hover:bg-slate-600 transition-colors disabled:opacity-50",
+                    disabled: busy,
+                    onclick: move |_| on_reembed.call(index),
+                    if busy { "Working…" } else { "Re-embed" }
+                }
+                if chunk.embedded {
+                    button {
+                        class: "px-2 py-0.5 rounded-md text-red-400 hover:text-red-300 hover:bg-red-900/30 transition-colors disabled:opacity-50",
+                        disabled: busy,
+                        onclick: move |_| on_delete.call(index),
+                        "Delete"
+                    }
+                }
+            }
+            p { class: "whitespace-pre-wrap text-sm text-slate-200", "{chunk.text}" }
+        }
+    }
+}
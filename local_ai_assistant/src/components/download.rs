@@ -0,0 +1,40 @@
+//! File Downloads
+//!
+//! Saves text produced on the server through the browser's download, for
+//! content too large or too late to put in a link's data URL.
+
+use dioxus::prelude::*;
+
+/// Offers `contents` to the user as a file named `file_name`
+pub fn save_text_file(file_name: &str, mime_type: &str, contents: String) {
+    let eval = document::eval(
+        r#"
+        const [name, type, contents] = await dioxus.recv();
+        const url = URL.createObjectURL(new Blob([contents], { type }));
+        const link = document.createElement("a");
+        link.href = url;
+        link.download = name;
+        link.click();
+        URL.revokeObjectURL(url);
+        "#,
+    );
+    if let Err(e) = eval.send((file_name.to_string(), mime_type.to_string(), contents)) {
+        tracing::error!("Error saving {}: {:?}", file_name, e);
+    }
+}
+
+/// Offers a `data:` URL, such as a rendered image, as a file named `file_name`
+pub fn save_data_url(file_name: &str, data_url: String) {
+    let eval = document::eval(
+        r#"
+        const [name, url] = await dioxus.recv();
+        const link = document.createElement("a");
+        link.href = url;
+        link.download = name;
+        link.click();
+        "#,
+    );
+    if let Err(e) = eval.send((file_name.to_string(), data_url)) {
+        tracing::error!("Error saving {}: {:?}", file_name, e);
+    }
+}
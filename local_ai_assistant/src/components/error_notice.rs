@@ -0,0 +1,84 @@
+//! Error Notices
+//!
+//! Shows a failed request with its category's title, the message and what
+//! to try, plus a button for the category's recovery action: opening the
+//! matching settings page, or trying again when the caller can retry.
+
+use dioxus::prelude::*;
+use crate::models::{AppError, RecoveryAction};
+use super::SettingsTab;
+
+/// Opens the settings page on a tab
+#[derive(Clone, Copy)]
+struct SettingsOpener {
+    show_settings: Signal<bool>,
+    settings_tab: Signal<SettingsTab>,
+}
+
+/// Lets error notices in all children open the settings page
+pub fn use_settings_opener_provider(show_settings: Signal<bool>, settings_tab: Signal<SettingsTab>) {
+    use_context_provider(|| SettingsOpener { show_settings, settings_tab });
+}
+
+#[component]
+pub fn ErrorNotice(
+    /// Error text from a server function, tagged or not
+    error: String,
+    on_retry: Option<EventHandler<()>>,
+    on_dismiss: Option<EventHandler<()>>,
+) -> Element {
+    let parsed = AppError::parse(&error);
+    let category = parsed.category;
+    let opener = try_consume_context::<SettingsOpener>();
+    let settings_tab = match category.recovery() {
+        Some(RecoveryAction::OpenModelSettings) => Some(SettingsTab::Models),
+        Some(RecoveryAction::ViewLogs) => Some(SettingsTab::Logs),
+        Some(RecoveryAction::OpenStorageSettings) => Some(SettingsTab::Database),
+        _ => None,
+    };
+    let action = category.recovery().filter(|action| match action {
+        RecoveryAction::Retry => on_retry.is_some(),
+        _ => opener.is_some(),
+    });
+
+    rsx! {
+        div {
+            class: "flex items-start gap-3 px-4 py-3 rounded-lg bg-red-900/40 border border-red-700 text-sm",
+            div {
+                class: "flex-1 min-w-0",
+                p { class: "font-medium text-red-200", "{category.title()}" }
+                p { class: "text-red-300 break-words", "{parsed.message}" }
+                if !category.hint().is_empty() {
+                    p { class: "mt-1 text-xs text-red-400", "{category.hint()}" }
+                }
+            }
+            if let Some(action) = action {
+                button {
+                    class: "shrink-0 px-3 py-1 rounded-md bg-red-700 hover:bg-red-600 text-white text-xs",
+                    onclick: move |_| match action {
+                        RecoveryAction::Retry => {
+                            if let Some(on_retry) = on_retry {
+                                on_retry.call(());
+                            }
+                        }
+                        _ => {
+                            if let (Some(mut opener), Some(tab)) = (opener, settings_tab.clone()) {
+                                opener.settings_tab.set(tab);
+                                opener.show_settings.set(true);
+                            }
+                        }
+                    },
+                    "{action.label()}"
+                }
+            }
+            if let Some(on_dismiss) = on_dismiss {
+                button {
+                    class: "shrink-0 text-red-400 hover:text-red-200",
+                    title: "Dismiss",
+                    onclick: move |_| on_dismiss.call(()),
+                    "✕"
+                }
+            }
+        }
+    }
+}
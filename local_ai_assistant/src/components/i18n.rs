@@ -0,0 +1,24 @@
+//! Interface Language
+//!
+//! The app provides the UI language from settings as context; components
+//! look up their text with [`t`] and re-render when the language changes.
+
+use dioxus::prelude::*;
+use crate::models::{AppSettings, ResponseLanguage, translate};
+
+#[derive(Clone, Copy)]
+struct UiLanguage(Memo<ResponseLanguage>);
+
+/// Makes the settings' UI language available to [`t`] in all children
+pub fn use_ui_language_provider(settings: Signal<AppSettings>) {
+    let language = use_memo(move || settings.read().ui_language.clone());
+    use_context_provider(|| UiLanguage(language));
+}
+
+/// Text for `key` in the current UI language; English outside the app
+pub fn t(key: &'static str) -> &'static str {
+    match try_consume_context::<UiLanguage>() {
+        Some(UiLanguage(language)) => translate(&language.read(), key),
+        None => translate(&ResponseLanguage::English, key),
+    }
+}
@@ -3,9 +3,16 @@
 //! Phase 2.2: Image generation UI for creating images from text prompts.
 
 use dioxus::prelude::*;
+use crate::models::{
+    AppSettings, CustomImageModel, Feature, ImageBaseModel, LoraWeight, UpscaleFactor, DEFAULT_IMAGE_STRENGTH, LORA_SCALE_RANGE,
+    MAX_LORAS, custom_model_name, validate_custom_model, error_message,
+};
 use crate::server_functions::{
-    generate_image, is_image_model_ready, get_image_gen_status, ImageResult
+    check_custom_image_model, check_lora_weights, enhance_image_prompt, generate_image, is_image_model_ready,
+    get_image_gen_status, upload_source_image, upscale_generated_image, ImageResult
 };
+use super::clipboard::use_pasted_files;
+use super::{ErrorNotice, MissingTools, use_feature_available};
 
 /// Props for ImageGenPanel - embedded mode means it's part of the main content area
 #[component]
@@ -13,6 +20,7 @@ pub fn ImageGenPanel(
     #[props(default = false)]
     embedded: bool,
     on_open_settings: Option<EventHandler<()>>,
+    settings: Signal<AppSettings>,
 ) -> Element {
     let mut prompt: Signal<String> = use_signal(|| "a small yellow dog is playing at the grass ground".to_string());
     let mut negative_prompt: Signal<String> = use_signal(String::new);
@@ -30,6 +38,50 @@ pub fn ImageGenPanel(
     let mut gen_progress: Signal<u8> = use_signal(|| 0);
     let mut selected_model: Signal<String> = use_signal(|| "schnell".to_string());  // schnell is free and reliable
     let mut quantize: Signal<u8> = use_signal(|| 4);
+    // Enhanced prompt offered for review before it replaces the prompt
+    let mut suggestion: Signal<Option<String>> = use_signal(|| None);
+    let mut is_enhancing: Signal<bool> = use_signal(|| false);
+    // Upscaled copies of the generated image, with whether faces were fixed
+    let mut upscaled: Signal<Vec<(UpscaleFactor, bool, ImageResult)>> = use_signal(Vec::new);
+    let mut upscaling: Signal<Option<UpscaleFactor>> = use_signal(|| None);
+    let mut face_fix: Signal<bool> = use_signal(|| false);
+    // Pasted image for image-to-image: server ID and a local preview URL
+    let mut source_image: Signal<Option<(String, String)>> = use_signal(|| None);
+    let mut image_strength: Signal<f32> = use_signal(|| DEFAULT_IMAGE_STRENGTH);
+    let mut uploading_source: Signal<bool> = use_signal(|| false);
+    let mflux_found = use_feature_available(Feature::ImageGeneration);
+
+    use_pasted_files("image-gen-panel".to_string(), move |file| {
+        if !file.is_image() {
+            error_message.set(Some(format!("{} is not an image", file.name)));
+            return;
+        }
+        uploading_source.set(true);
+        error_message.set(None);
+        spawn(async move {
+            match upload_source_image(file.data).await {
+                Ok(id) => source_image.set(Some((id, file.preview_url))),
+                Err(e) => error_message.set(Some(e.to_string())),
+            }
+            uploading_source.set(false);
+        });
+    });
+
+    let mut enhance = move || {
+        let rough = prompt().trim().to_string();
+        if rough.is_empty() || is_enhancing() {
+            return;
+        }
+        is_enhancing.set(true);
+        error_message.set(None);
+        spawn(async move {
+            match enhance_image_prompt(rough).await {
+                Ok(enhanced) => suggestion.set(Some(enhanced)),
+                Err(e) => error_message.set(Some(e.to_string())),
+            }
+            is_enhancing.set(false);
+        });
+    };
 
     // Check if model is ready on mount
     use_effect(move || {
@@ -54,6 +106,7 @@ pub fn ImageGenPanel(
     rsx! {
         // Image Generation panel - embedded in main content area
         div {
+            id: "image-gen-panel",
             class: "{container_class}",
 
             // Content area with scroll
@@ -64,6 +117,8 @@ pub fn ImageGenPanel(
                 div {
                     class: "max-w-2xl mx-auto space-y-6",
 
+                MissingTools { feature: Feature::ImageGeneration }
+
                 // Model status info - show different message based on ready state
                 if !is_generating() {
                     if model_ready() {
@@ -155,6 +210,100 @@ pub fn ImageGenPanel(
                         value: "{prompt}",
                         oninput: move |e| prompt.set(e.value()),
                     }
+                    button {
+                        class: "px-3 py-1.5 rounded-lg bg-slate-700 hover:bg-slate-600 text-sm text-slate-200 disabled:opacity-50",
+                        title: "Let the local model add style, lighting and composition",
+                        disabled: is_enhancing() || prompt().trim().is_empty(),
+                        onclick: move |_| enhance(),
+                        if is_enhancing() { "Enhancing…" } else { "✨ Enhance prompt" }
+                    }
+                    if let Some(text) = suggestion() {
+                        div {
+                            class: "p-3 bg-slate-700/50 border border-purple-500/40 rounded-lg space-y-2",
+                            p { class: "text-xs text-purple-300", "Suggested prompt — edit it, then use it or discard it" }
+                            textarea {
+                                class: "w-full px-3 py-2 bg-slate-800 border border-slate-600 rounded-lg text-sm text-white focus:outline-none focus:border-purple-500 resize-none",
+                                rows: "4",
+                                value: "{text}",
+                                oninput: move |e| suggestion.set(Some(e.value())),
+                            }
+                            div {
+                                class: "flex gap-2",
+                                button {
+                                    class: "px-3 py-1.5 rounded-lg bg-purple-600 hover:bg-purple-500 text-sm text-white",
+                                    onclick: move |_| {
+                                        if let Some(text) = suggestion.take() {
+                                            prompt.set(text);
+                                        }
+                                    },
+                                    "Use this prompt"
+                                }
+                                button {
+                                    class: "px-3 py-1.5 rounded-lg bg-slate-700 hover:bg-slate-600 text-sm text-slate-200 disabled:opacity-50",
+                                    disabled: is_enhancing(),
+                                    onclick: move |_| enhance(),
+                                    "Try again"
+                                }
+                                button {
+                                    class: "px-3 py-1.5 rounded-lg text-sm text-slate-400 hover:text-white",
+                                    onclick: move |_| suggestion.set(None),
+                                    "Discard"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Source image for image-to-image, pasted with Ctrl/Cmd+V
+                div {
+                    class: "space-y-2 p-3 bg-slate-700/50 rounded-lg",
+                    label {
+                        class: "block text-sm font-medium text-slate-300",
+                        "Source Image"
+                    }
+                    if let Some((_, preview)) = source_image() {
+                        div {
+                            class: "flex items-start gap-3",
+                            img {
+                                class: "w-24 h-24 object-cover rounded-lg border border-slate-600",
+                                src: "{preview}",
+                                alt: "Source image",
+                            }
+                            div {
+                                class: "flex-1 space-y-2",
+                                div {
+                                    class: "flex items-center justify-between text-sm text-slate-300",
+                                    span { "Keep from source" }
+                                    span { class: "text-slate-400", {format!("{:.0}%", image_strength() * 100.0)} }
+                                }
+                                input {
+                                    r#type: "range",
+                                    class: "w-full",
+                                    min: "0",
+                                    max: "1",
+                                    step: "0.05",
+                                    value: "{image_strength}",
+                                    oninput: move |e| {
+                                        if let Ok(v) = e.value().parse::<f32>() {
+                                            image_strength.set(v);
+                                        }
+                                    },
+                                }
+                                button {
+                                    class: "px-3 py-1.5 rounded-lg bg-slate-600 hover:bg-slate-500 text-sm text-slate-200",
+                                    onclick: move |_| source_image.set(None),
+                                    "Remove"
+                                }
+                            }
+                        }
+                    } else if uploading_source() {
+                        p { class: "text-xs text-slate-400", "Uploading image…" }
+                    } else {
+                        p {
+                            class: "text-xs text-slate-400",
+                            "Paste an image or screenshot (Ctrl/Cmd+V) to use it as the starting point"
+                        }
+                    }
                 }
 
                 // Model selection - always visible
@@ -171,16 +320,26 @@ pub fn ImageGenPanel(
                             let model = e.value();
                             selected_model.set(model.clone());
                             // Update default steps based on model
-                            let default_steps = match model.as_str() {
+                            let custom_steps = custom_model_name(&model).and_then(|name| {
+                                settings.read().custom_image_models.iter().find(|m| m.name == name).map(|m| m.steps)
+                            });
+                            let default_steps = custom_steps.unwrap_or(match model.as_str() {
                                 "dev" => 20,
                                 "z-image-turbo" => 9,
                                 _ => 4, // schnell
-                            };
+                            });
                             steps.set(default_steps);
                         },
                         option { value: "schnell", "FLUX.1 Schnell (4 steps, fast)" }
                         option { value: "dev", "FLUX.1 Dev (20 steps, quality)" }
                         option { value: "z-image-turbo", "Z-Image Turbo (9 steps)" }
+                        for custom in settings.read().custom_image_models.iter() {
+                            option {
+                                key: "{custom.name}",
+                                value: "{custom.model_id()}",
+                                "{custom.name} (custom, {custom.steps} steps)"
+                            }
+                        }
                     }
                     p {
                         class: "text-xs text-amber-400 mt-1",
@@ -374,6 +533,9 @@ pub fn ImageGenPanel(
                                 }
                             }
                         }
+
+                        LoraSettings { settings: settings }
+                        CustomModelSettings { settings: settings, selected_model: selected_model }
                     }
                 }
 
@@ -418,7 +580,7 @@ pub fn ImageGenPanel(
                 // Generate button
                 button {
                     class: "w-full px-4 py-3 bg-purple-600 hover:bg-purple-700 disabled:bg-slate-600 disabled:cursor-not-allowed rounded-lg text-white font-medium transition-colors flex items-center justify-center gap-2",
-                    disabled: is_generating() || prompt().trim().is_empty(),
+                    disabled: is_generating() || !mflux_found || prompt().trim().is_empty(),
                     onclick: move |_| {
                         let p = prompt().trim().to_string();
                         let neg = if negative_prompt().trim().is_empty() { None } else { Some(negative_prompt().trim().to_string()) };
@@ -427,6 +589,9 @@ pub fn ImageGenPanel(
                         let s = steps();
                         let model = selected_model();
                         let quant = quantize();
+                        let loras = settings.read().image_loras.clone();
+                        let source = source_image().map(|(id, _)| id);
+                        let strength = source.is_some().then(|| image_strength());
 
                         if !p.is_empty() {
                             is_generating.set(true);
@@ -476,9 +641,10 @@ pub fn ImageGenPanel(
 
                             // Start the actual generation
                             spawn(async move {
-                                match generate_image(p, neg, Some(w), Some(h), Some(s), Some(model), Some(quant)).await {
+                                match generate_image(p, neg, Some(w), Some(h), Some(s), Some(model), Some(quant), loras, source, strength).await {
                                     Ok(result) => {
                                         generated_image.set(Some(result));
+                                        upscaled.write().clear();
                                         // Calculate generation time
                                         if let Some(start) = start_time() {
                                             #[cfg(target_arch = "wasm32")]
@@ -538,15 +704,7 @@ pub fn ImageGenPanel(
 
                 // Error message
                 if let Some(err) = error_message() {
-                    div {
-                        class: "p-3 bg-red-900/50 border border-red-700 rounded-lg text-red-200 text-sm",
-                        "{err}"
-                        button {
-                            class: "ml-2 text-red-400 hover:text-white",
-                            onclick: move |_| error_message.set(None),
-                            "×"
-                        }
-                    }
+                    ErrorNotice { error: err, on_dismiss: move |_| error_message.set(None) }
                 }
 
                 // Generated image display
@@ -587,7 +745,10 @@ pub fn ImageGenPanel(
                                 // Clear button
                                 button {
                                     class: "px-3 py-1 bg-slate-700 hover:bg-slate-600 rounded text-sm text-slate-300 transition-colors",
-                                    onclick: move |_| generated_image.set(None),
+                                    onclick: move |_| {
+                                        generated_image.set(None);
+                                        upscaled.write().clear();
+                                    },
                                     "Clear"
                                 }
                             }
@@ -600,6 +761,66 @@ pub fn ImageGenPanel(
                                 alt: "Generated image",
                             }
                         }
+                        // Upscaling, saved next to the original
+                        if let Some(path) = img.path.clone() {
+                            div {
+                                class: "flex flex-wrap items-center gap-2 text-sm text-slate-300",
+                                "Upscale"
+                                for factor in UpscaleFactor::all().iter().copied() {
+                                    button {
+                                        key: "{factor.label()}",
+                                        class: "px-3 py-1 bg-slate-700 hover:bg-slate-600 rounded text-white transition-colors disabled:opacity-50",
+                                        disabled: upscaling().is_some(),
+                                        onclick: {
+                                            let path = path.clone();
+                                            move |_| {
+                                                let path = path.clone();
+                                                let fix = face_fix();
+                                                upscaling.set(Some(factor));
+                                                error_message.set(None);
+                                                spawn(async move {
+                                                    match upscale_generated_image(path, factor, fix).await {
+                                                        Ok(result) => {
+                                                            let mut copies = upscaled.write();
+                                                            copies.retain(|(f, faces, _)| (*f, *faces) != (factor, fix));
+                                                            copies.push((factor, fix, result));
+                                                        }
+                                                        Err(e) => error_message.set(Some(e.to_string())),
+                                                    }
+                                                    upscaling.set(None);
+                                                });
+                                            }
+                                        },
+                                        if upscaling() == Some(factor) { "Upscaling..." } else { "{factor.label()}" }
+                                    }
+                                }
+                                label {
+                                    class: "flex items-center gap-1 text-xs text-slate-400",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: face_fix(),
+                                        onchange: move |e| face_fix.set(e.value().parse::<bool>().unwrap_or(false)),
+                                    }
+                                    "Fix faces"
+                                }
+                            }
+                            for (factor, faces, copy) in upscaled() {
+                                div {
+                                    key: "{factor.label()}-{faces}",
+                                    class: "flex items-center justify-between text-xs text-slate-400",
+                                    span {
+                                        "{factor.label()} · {copy.width}×{copy.height}"
+                                        if faces { " · faces fixed" }
+                                    }
+                                    a {
+                                        class: "px-3 py-1 bg-slate-700 hover:bg-slate-600 rounded text-sm text-white transition-colors",
+                                        href: "{copy.data_url}",
+                                        download: "generated-image@{factor.scale()}x.png",
+                                        "Download"
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
 
@@ -613,6 +834,7 @@ pub fn ImageGenPanel(
                         li { "Include style keywords like 'photorealistic', 'oil painting', 'digital art'" }
                         li { "Use negative prompts to exclude unwanted elements" }
                         li { "Higher steps = better quality but slower generation" }
+                        li { "Paste a screenshot to restyle it; keep less of the source for bigger changes" }
                     }
                 }
                 } // Close max-w-2xl div
@@ -620,3 +842,239 @@ pub fn ImageGenPanel(
         } // Close container div
     } // Close rsx!
 }
+
+/// Stacked LoRA weights, kept in settings and applied to every image while
+/// enabled
+#[component]
+fn LoraSettings(mut settings: Signal<AppSettings>) -> Element {
+    let mut new_path = use_signal(String::new);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+    let mut checking = use_signal(|| false);
+
+    let loras = settings.read().image_loras.clone();
+    let full = loras.len() >= MAX_LORAS;
+
+    let mut add = move || {
+        let path = new_path().trim().to_string();
+        if path.is_empty() || checking() {
+            return;
+        }
+        if settings.read().image_loras.iter().any(|l| l.path == path) {
+            error.set(Some("That LoRA is already in the list".to_string()));
+            return;
+        }
+        checking.set(true);
+        error.set(None);
+        spawn(async move {
+            match check_lora_weights(path.clone()).await {
+                Ok(()) => {
+                    settings.write().image_loras.push(LoraWeight::new(&path));
+                    new_path.set(String::new());
+                }
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+            checking.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            class: "space-y-2",
+            label {
+                class: "block text-sm font-medium text-slate-300",
+                "LoRA Weights"
+            }
+            for (i, lora) in loras.into_iter().enumerate() {
+                div {
+                    key: "{lora.path}",
+                    class: "flex items-center gap-2 text-sm",
+                    input {
+                        r#type: "checkbox",
+                        checked: lora.enabled,
+                        title: "Apply this LoRA",
+                        onchange: move |e| {
+                            if let Some(l) = settings.write().image_loras.get_mut(i) {
+                                l.enabled = e.value().parse::<bool>().unwrap_or(false);
+                            }
+                        },
+                    }
+                    span {
+                        class: if lora.enabled { "flex-1 min-w-0 truncate text-slate-200" } else { "flex-1 min-w-0 truncate text-slate-500" },
+                        title: "{lora.path}",
+                        "{lora.label()}"
+                    }
+                    input {
+                        r#type: "range",
+                        class: "w-28",
+                        min: "{LORA_SCALE_RANGE.0}",
+                        max: "{LORA_SCALE_RANGE.1}",
+                        step: "0.05",
+                        value: "{lora.scale}",
+                        oninput: move |e| {
+                            if let (Ok(v), Some(l)) = (e.value().parse::<f32>(), settings.write().image_loras.get_mut(i)) {
+                                l.scale = v;
+                            }
+                        },
+                    }
+                    span { class: "w-10 text-right text-xs text-slate-400", {format!("{:.2}", lora.scale)} }
+                    button {
+                        class: "text-slate-400 hover:text-red-400",
+                        title: "Remove",
+                        onclick: move |_| {
+                            let mut s = settings.write();
+                            if i < s.image_loras.len() {
+                                s.image_loras.remove(i);
+                            }
+                        },
+                        "×"
+                    }
+                }
+            }
+            if !full {
+                div {
+                    class: "flex gap-2",
+                    input {
+                        class: "flex-1 px-3 py-1.5 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-400 focus:outline-none focus:border-blue-500",
+                        placeholder: "/path/to/lora.safetensors",
+                        value: "{new_path}",
+                        oninput: move |e| new_path.set(e.value()),
+                        onkeydown: move |e: KeyboardEvent| if e.key() == Key::Enter { add() },
+                    }
+                    button {
+                        class: "px-3 py-1.5 bg-slate-600 hover:bg-slate-500 rounded-lg text-sm text-white disabled:opacity-50",
+                        disabled: checking() || new_path().trim().is_empty(),
+                        onclick: move |_| add(),
+                        if checking() { "Checking..." } else { "Add" }
+                    }
+                }
+            }
+            if let Some(msg) = error() {
+                p { class: "text-xs text-red-400", "{msg}" }
+            }
+            p {
+                class: "text-xs text-slate-500",
+                "Up to {MAX_LORAS} LoRAs are stacked in order. Strength 1.0 applies a LoRA as trained."
+            }
+        }
+    }
+}
+
+/// Registers models saved on disk so they can be picked like the built-in
+/// ones
+#[component]
+fn CustomModelSettings(mut settings: Signal<AppSettings>, mut selected_model: Signal<String>) -> Element {
+    let mut name = use_signal(String::new);
+    let mut path = use_signal(String::new);
+    let mut base_model: Signal<ImageBaseModel> = use_signal(ImageBaseModel::default);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+    let mut checking = use_signal(|| false);
+
+    let models = settings.read().custom_image_models.clone();
+    let input_class = "px-3 py-1.5 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-400 focus:outline-none focus:border-blue-500";
+
+    rsx! {
+        div {
+            class: "space-y-2",
+            label {
+                class: "block text-sm font-medium text-slate-300",
+                "Custom Models"
+            }
+            for model in models.into_iter() {
+                {
+                    let id = model.model_id();
+                    let model_name = model.name.clone();
+                    rsx! {
+                        div {
+                            key: "{model.name}",
+                            class: "flex items-center gap-2 text-sm",
+                            span { class: "text-slate-200", "{model.name}" }
+                            span {
+                                class: "flex-1 min-w-0 truncate text-xs font-mono text-slate-500",
+                                title: "{model.path}",
+                                "{model.base_model.display_name()} · {model.path}"
+                            }
+                            button {
+                                class: "text-slate-400 hover:text-red-400",
+                                title: "Unregister",
+                                onclick: move |_| {
+                                    if selected_model() == id {
+                                        selected_model.set("schnell".to_string());
+                                    }
+                                    settings.write().custom_image_models.retain(|m| m.name != model_name);
+                                },
+                                "×"
+                            }
+                        }
+                    }
+                }
+            }
+            div {
+                class: "grid grid-cols-2 gap-2",
+                input {
+                    class: input_class,
+                    placeholder: "Name",
+                    value: "{name}",
+                    oninput: move |e| name.set(e.value()),
+                }
+                select {
+                    class: input_class,
+                    value: "{base_model().name()}",
+                    onchange: move |e| {
+                        if let Some(base) = ImageBaseModel::all().iter().find(|b| b.name() == e.value()) {
+                            base_model.set(*base);
+                        }
+                    },
+                    for base in ImageBaseModel::all().iter() {
+                        option { key: "{base.name()}", value: "{base.name()}", "Based on {base.display_name()}" }
+                    }
+                }
+                input {
+                    class: "col-span-2 {input_class}",
+                    placeholder: "/path/to/saved/model",
+                    value: "{path}",
+                    oninput: move |e| path.set(e.value()),
+                }
+            }
+            div {
+                class: "flex items-center justify-between gap-2",
+                p {
+                    class: "text-xs text-slate-500",
+                    "A folder written by mflux-save, e.g. a quantized or fine-tuned model."
+                }
+                button {
+                    class: "px-3 py-1.5 bg-slate-600 hover:bg-slate-500 rounded-lg text-sm text-white disabled:opacity-50",
+                    disabled: checking() || name().trim().is_empty() || path().trim().is_empty(),
+                    onclick: move |_| {
+                        let model = CustomImageModel {
+                            name: name().trim().to_string(),
+                            path: path().trim().to_string(),
+                            base_model: base_model(),
+                            steps: base_model().default_steps(),
+                        };
+                        if let Err(e) = validate_custom_model(&model, &settings.read().custom_image_models) {
+                            error.set(Some(e));
+                            return;
+                        }
+                        checking.set(true);
+                        error.set(None);
+                        spawn(async move {
+                            match check_custom_image_model(model.clone()).await {
+                                Ok(()) => {
+                                    settings.write().custom_image_models.push(model);
+                                    name.set(String::new());
+                                    path.set(String::new());
+                                }
+                                Err(e) => error.set(Some(error_message(&e))),
+                            }
+                            checking.set(false);
+                        });
+                    },
+                    if checking() { "Checking..." } else { "Register" }
+                }
+            }
+            if let Some(msg) = error() {
+                p { class: "text-xs text-red-400", "{msg}" }
+            }
+        }
+    }
+}
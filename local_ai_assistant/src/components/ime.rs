@@ -0,0 +1,47 @@
+//! IME Composition Handling
+//!
+//! Chinese, Japanese and Korean input methods confirm a candidate with
+//! Enter, which must not submit the input. Chrome marks that key press as
+//! composing, but Safari sends `compositionend` before it, so composition is
+//! also tracked here and treated as lasting a moment after it ends.
+
+use dioxus::prelude::*;
+use super::status_bar::sleep_ms;
+
+/// How long after `compositionend` an Enter still belongs to the IME
+const COMPOSITION_GRACE_MS: u32 = 50;
+
+/// Composition state of one input; wire [`ImeState::start`] and
+/// [`ImeState::end`] to its composition events
+#[derive(Clone, Copy, PartialEq)]
+pub struct ImeState {
+    composing: Signal<bool>,
+}
+
+pub fn use_ime_state() -> ImeState {
+    ImeState { composing: use_signal(|| false) }
+}
+
+impl ImeState {
+    pub fn start(mut self) {
+        self.composing.set(true);
+    }
+
+    pub fn end(mut self) {
+        spawn(async move {
+            sleep_ms(COMPOSITION_GRACE_MS).await;
+            self.composing.set(false);
+        });
+    }
+
+    /// Whether the key press confirms an IME candidate rather than
+    /// reaching the input
+    pub fn is_composing(&self, event: &KeyboardEvent) -> bool {
+        event.is_composing() || *self.composing.peek()
+    }
+
+    /// Enter pressed outside of IME composition
+    pub fn is_enter(&self, event: &KeyboardEvent) -> bool {
+        event.key() == Key::Enter && !self.is_composing(event)
+    }
+}
@@ -0,0 +1,193 @@
+//! Jobs Panel Component
+//!
+//! Every background task in one list: model downloads, indexing, video
+//! generation and content pipelines, with progress, logs, and cancel and
+//! retry actions.
+
+use dioxus::prelude::*;
+use crate::models::{BackgroundJob, JobStatus, error_message};
+use crate::server_functions::{get_background_jobs, cancel_background_job, retry_background_job, clear_finished_jobs};
+use super::status_bar::sleep_ms;
+
+/// How often the job list is refreshed, in milliseconds
+const POLL_INTERVAL_MS: u32 = 2000;
+
+#[component]
+pub fn JobsPanel() -> Element {
+    let mut jobs: Signal<Vec<BackgroundJob>> = use_signal(Vec::new);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    // Poll jobs for as long as the panel is mounted
+    use_effect(move || {
+        spawn(async move {
+            loop {
+                if let Ok(latest) = get_background_jobs().await {
+                    jobs.set(latest);
+                }
+                sleep_ms(POLL_INTERVAL_MS).await;
+            }
+        });
+    });
+
+    let refresh = move || {
+        spawn(async move {
+            if let Ok(latest) = get_background_jobs().await {
+                jobs.set(latest);
+            }
+        });
+    };
+
+    let running = jobs().iter().filter(|j| !j.status.is_finished()).count();
+    let has_finished = jobs().iter().any(|j| j.status.is_finished());
+
+    rsx! {
+        div {
+            class: "flex-1 overflow-y-auto p-6",
+            div {
+                class: "max-w-4xl mx-auto space-y-4",
+
+                div {
+                    class: "flex items-center justify-between",
+                    h2 {
+                        class: "text-lg font-semibold text-white",
+                        "Jobs"
+                        span { class: "ml-2 text-sm font-normal text-slate-400", "{running} running" }
+                    }
+                    if has_finished {
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                            onclick: move |_| {
+                                spawn(async move {
+                                    match clear_finished_jobs().await {
+                                        Ok(()) => refresh(),
+                                        Err(e) => error.set(Some(error_message(&e))),
+                                    }
+                                });
+                            },
+                            "Clear finished"
+                        }
+                    }
+                }
+
+                if let Some(message) = error() {
+                    p { class: "text-sm text-red-400", "{message}" }
+                }
+
+                if jobs().is_empty() {
+                    p {
+                        class: "text-sm text-slate-500",
+                        "No background jobs yet. Model downloads, indexing, video generation and content pipelines show up here."
+                    }
+                }
+
+                for job in jobs() {
+                    JobCard {
+                        key: "{job.id}",
+                        job,
+                        on_changed: move |result: Result<(), String>| match result {
+                            Ok(()) => {
+                                error.set(None);
+                                refresh();
+                            }
+                            Err(e) => error.set(Some(e)),
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn JobCard(job: BackgroundJob, on_changed: EventHandler<Result<(), String>>) -> Element {
+    let job_id = job.id.to_string();
+    let percent = job.progress.map(|p| (p * 100.0).round() as u32);
+    let started = job.started_at.with_timezone(&chrono::Local).format("%H:%M:%S").to_string();
+    let elapsed = job.finished_at.unwrap_or_else(chrono::Utc::now) - job.started_at;
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-2",
+            div {
+                class: "flex items-center gap-3",
+                span { title: "{job.kind.display_name()}", "{job.kind.icon()}" }
+                div {
+                    class: "flex-1 min-w-0",
+                    p { class: "text-sm text-white truncate", "{job.title}" }
+                    p {
+                        class: "text-xs text-slate-500",
+                        "{job.kind.display_name()} · started {started} · {elapsed.num_seconds()}s"
+                    }
+                }
+                span {
+                    class: match job.status {
+                        JobStatus::Running => "text-xs text-blue-400",
+                        JobStatus::Completed => "text-xs text-green-400",
+                        JobStatus::Failed(_) => "text-xs text-red-400",
+                        JobStatus::Cancelled => "text-xs text-slate-400",
+                    },
+                    "{job.status.label()}"
+                }
+                if job.status == JobStatus::Running {
+                    button {
+                        class: "px-2 py-0.5 text-xs text-slate-400 hover:text-red-400 transition-colors",
+                        onclick: {
+                            let job_id = job_id.clone();
+                            move |_| {
+                                let job_id = job_id.clone();
+                                spawn(async move {
+                                    on_changed.call(cancel_background_job(job_id).await.map_err(error_message));
+                                });
+                            }
+                        },
+                        "Cancel"
+                    }
+                }
+                if job.can_retry() {
+                    button {
+                        class: "px-3 py-1 text-xs bg-slate-600 hover:bg-slate-500 rounded transition-colors",
+                        onclick: {
+                            let job_id = job_id.clone();
+                            move |_| {
+                                let job_id = job_id.clone();
+                                spawn(async move {
+                                    on_changed.call(retry_background_job(job_id).await.map_err(error_message));
+                                });
+                            }
+                        },
+                        "Retry"
+                    }
+                }
+            }
+
+            if job.status == JobStatus::Running {
+                div {
+                    class: "w-full h-1.5 bg-slate-700 rounded-full overflow-hidden",
+                    match percent {
+                        Some(percent) => rsx! {
+                            div { class: "h-full bg-blue-500 transition-all", style: "width: {percent}%" }
+                        },
+                        None => rsx! {
+                            div { class: "h-full w-1/3 bg-blue-500 animate-pulse" }
+                        },
+                    }
+                }
+            }
+
+            if let JobStatus::Failed(reason) = &job.status {
+                p { class: "text-xs text-red-400 break-words", "{reason}" }
+            }
+
+            if !job.logs.is_empty() {
+                details {
+                    class: "text-xs",
+                    summary { class: "cursor-pointer text-slate-400", "Log ({job.logs.len()} lines)" }
+                    pre {
+                        class: "mt-2 max-h-48 overflow-y-auto p-2 bg-slate-900 rounded text-slate-400 whitespace-pre-wrap",
+                        {job.logs.join("\n")}
+                    }
+                }
+            }
+        }
+    }
+}
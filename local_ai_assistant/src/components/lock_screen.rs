@@ -0,0 +1,72 @@
+//! Lock Screen Component
+//!
+//! Shown on start while encrypted chat history is locked; the rest of the
+//! app loads once the passphrase is entered.
+
+use dioxus::prelude::*;
+use crate::models::error_message;
+use crate::server_functions::unlock_history;
+use super::i18n::t;
+
+#[component]
+pub fn LockScreen(on_unlocked: EventHandler<()>) -> Element {
+    let mut passphrase: Signal<String> = use_signal(String::new);
+    let mut is_unlocking: Signal<bool> = use_signal(|| false);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    let mut unlock = move || {
+        if passphrase().is_empty() || is_unlocking() {
+            return;
+        }
+        is_unlocking.set(true);
+        error.set(None);
+        spawn(async move {
+            match unlock_history(passphrase()).await {
+                Ok(()) => {
+                    passphrase.set(String::new());
+                    on_unlocked.call(());
+                }
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+            is_unlocking.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            class: "flex h-screen items-center justify-center bg-slate-900 text-white",
+            div {
+                class: "w-full max-w-sm bg-slate-800 rounded-xl p-6 space-y-4",
+                div {
+                    class: "text-center space-y-1",
+                    p { class: "text-3xl", "🔒" }
+                    h1 { class: "text-lg font-semibold", {t("lock.title")} }
+                    p { class: "text-sm text-slate-400", {t("lock.prompt")} }
+                }
+                input {
+                    r#type: "password",
+                    class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                    placeholder: t("lock.passphrase"),
+                    autocomplete: "current-password",
+                    autofocus: true,
+                    value: "{passphrase}",
+                    oninput: move |e| passphrase.set(e.value()),
+                    onkeydown: move |e| {
+                        if e.key() == Key::Enter {
+                            unlock();
+                        }
+                    },
+                }
+                if let Some(message) = error() {
+                    p { class: "text-sm text-red-400", "{message}" }
+                }
+                button {
+                    class: "w-full px-3 py-2 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors disabled:opacity-50",
+                    disabled: passphrase().is_empty() || is_unlocking(),
+                    onclick: move |_| unlock(),
+                    if is_unlocking() { {t("lock.unlocking")} } else { {t("lock.unlock")} }
+                }
+            }
+        }
+    }
+}
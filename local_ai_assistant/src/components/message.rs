@@ -2,15 +2,29 @@
 //!
 //! Renders individual chat messages with Markdown support and modern styling.
 
-use comrak::{markdown_to_html_with_plugins, ExtensionOptions, Plugins, RenderOptions, RenderPlugins};
-use comrak::plugins::syntect::SyntectAdapterBuilder;
-use crate::models::{ChatMessage, ChatRole, AppSettings};
+use crate::models::{AttachmentMode, ChatMessage, ChatRole, AppSettings, ContentSegment, DiagramBlock, FeedbackRating, MessageFeedback, PlaybackQueue, render_markdown, split_diagrams, error_message};
+use crate::server_functions::{ingest_chat_attachment, save_message, set_message_feedback, trash_message};
 use dioxus::prelude::*;
+use super::Diagram;
+
+/// Part of a rendered message
+#[derive(Clone, PartialEq)]
+enum Part {
+    Html(String),
+    Diagram(DiagramBlock),
+}
 
 /// Message component for rendering individual chat messages
 /// Uses index-based access to maintain reactivity with the parent's Signal<Vec<ChatMessage>>
 #[component]
-pub fn Message(messages: Signal<Vec<ChatMessage>>, index: usize, settings: Signal<AppSettings>) -> Element {
+pub fn Message(
+    messages: Signal<Vec<ChatMessage>>,
+    index: usize,
+    settings: Signal<AppSettings>,
+    playback: Signal<PlaybackQueue>,
+    /// Resumes a response that stopped at the token cap
+    on_continue: EventHandler<uuid::Uuid>,
+) -> Element {
     // Read the message reactively by accessing the signal
     let is_assistant = use_memo(move || {
         messages.read().get(index).map(|m| m.role == ChatRole::Assistant).unwrap_or(false)
@@ -20,53 +34,128 @@ pub fn Message(messages: Signal<Vec<ChatMessage>>, index: usize, settings: Signa
         messages.read().get(index).map(|m| m.role == ChatRole::Assistant && m.content.is_empty()).unwrap_or(false)
     });
 
-    // Process markdown content to HTML with syntax highlighting
+    // Process markdown content to HTML with syntax highlighting, keeping
+    // diagram blocks apart to render as diagrams
     let content = use_memo(move || {
         let msgs = messages.read();
         let Some(message) = msgs.get(index) else {
-            return String::new();
+            return Vec::new();
         };
-        let msg_content = &message.content;
+        let render_math = settings.read().render_math;
+        split_diagrams(&message.content)
+            .into_iter()
+            .map(|segment| match segment {
+                ContentSegment::Markdown(markdown) => Part::Html(render_markdown(&markdown, render_math)),
+                ContentSegment::Diagram(block) => Part::Diagram(block),
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let feedback = use_memo(move || messages.read().get(index).and_then(|m| m.feedback.clone()));
 
-        if msg_content.is_empty() {
-            return String::new();
+    // Sentences of this message while it is being read aloud, with the one
+    // being spoken
+    let message_id = use_memo(move || messages.read().get(index).map(|m| m.id));
+    let speaking = use_memo(move || {
+        let id = message_id()?;
+        let queue = playback.read();
+        let current = queue.speaking_in(id)?;
+        let sentences = queue.items.first()?.sentences.clone();
+        Some((sentences, current))
+    });
+    let queued = use_memo(move || message_id().is_some_and(|id| playback.read().contains(id)));
+
+    // Queues the message for reading aloud, or takes it off the queue
+    let toggle_speech = move |_: MouseEvent| {
+        let Some(message) = messages.read().get(index).cloned() else {
+            return;
+        };
+        let mut queue = playback.write();
+        if queue.contains(message.id) {
+            queue.remove(message.id);
+        } else {
+            queue.enqueue(message.id, &message.content);
         }
+    };
+    let generation = use_memo(move || messages.read().get(index).and_then(|m| m.generation.clone()));
+    // Only the latest answer can be continued, as the model picks up from
+    // the end of the conversation
+    let can_continue = use_memo(move || {
+        let msgs = messages.read();
+        index + 1 == msgs.len() && msgs[index].generation.as_ref().is_some_and(|g| g.truncated)
+    });
 
-        // Configure syntax highlighter with dark theme
-        let syntec_adapter = SyntectAdapterBuilder::new()
-            .theme("base16-ocean.dark")
-            .build();
-
-        // Set up Comrak plugins for rendering with syntax highlighting
-        let plugins = Plugins::builder()
-            .render(
-                RenderPlugins::builder()
-                    .codefence_syntax_highlighter(&syntec_adapter)
-                    .build()
-            ).build();
-
-        // Configure markdown extension options
-        let extension_options = ExtensionOptions::builder()
-            .strikethrough(true)
-            .tagfilter(true)
-            .autolink(true)
-            .table(true)
-            .build();
-
-        // Configure HTML rendering options
-        let render_options = RenderOptions::builder()
-            .hardbreaks(true)
-            .github_pre_lang(true)
-            .build();
-
-        let options = comrak::Options {
-            extension: extension_options,
-            render: render_options,
-            ..Default::default()
+    // Moves the message to the trash and drops it from the conversation
+    let delete = move |_: MouseEvent| {
+        let Some(id) = message_id() else {
+            return;
         };
+        messages.write().retain(|m| m.id != id);
+        spawn(async move {
+            if let Err(e) = trash_message(id.to_string()).await {
+                tracing::error!("Error deleting message: {:?}", e);
+            }
+        });
+    };
 
-        markdown_to_html_with_plugins(msg_content, &options, &plugins)
-    });
+    let attachments = use_memo(move || messages.read().get(index).map(|m| m.attachments.clone()).unwrap_or_default());
+    let mut ingesting = use_signal(|| None::<uuid::Uuid>);
+    let mut ingest_error = use_signal(|| None::<String>);
+
+    // Adds an attachment's full text to the knowledge base and remembers
+    // that it was added
+    let ingest = move |attachment_id: uuid::Uuid, name: String| {
+        ingesting.set(Some(attachment_id));
+        spawn(async move {
+            match ingest_chat_attachment(attachment_id.to_string(), name).await {
+                Ok(_) => {
+                    ingest_error.set(None);
+                    let updated = {
+                        let mut msgs = messages.write();
+                        msgs.get_mut(index).map(|message| {
+                            for attachment in message.attachments.iter_mut().filter(|a| a.id == attachment_id) {
+                                attachment.ingested = true;
+                            }
+                            message.clone()
+                        })
+                    };
+                    if let Some(message) = updated {
+                        if let Err(e) = save_message(message).await {
+                            tracing::error!("Error saving message: {:?}", e);
+                        }
+                    }
+                }
+                Err(e) => ingest_error.set(Some(error_message(&e))),
+            }
+            ingesting.set(None);
+        });
+    };
+
+    // Updates the rating locally, then persists it; the message is saved with
+    // its feedback when the rating arrives before the stream has finished
+    let mut set_feedback = move |feedback: Option<MessageFeedback>| {
+        let Some(id) = messages.read().get(index).map(|m| m.id) else {
+            return;
+        };
+        if let Some(message) = messages.write().get_mut(index) {
+            message.feedback = feedback.clone();
+        }
+        spawn(async move {
+            if let Err(e) = set_message_feedback(id.to_string(), feedback).await {
+                tracing::error!("Error saving feedback: {:?}", e);
+            }
+        });
+    };
+
+    // Clicking the active rating again clears it
+    let mut rate = move |rating: FeedbackRating| {
+        let current = feedback();
+        let next = match current {
+            Some(f) if f.rating == rating => None,
+            other => Some(MessageFeedback::new(rating, other.and_then(|f| f.comment))),
+        };
+        set_feedback(next);
+    };
 
     rsx! {
         div {
@@ -86,7 +175,7 @@ pub fn Message(messages: Signal<Vec<ChatMessage>>, index: usize, settings: Signa
 
                 // Message bubble
                 div {
-                    class: "px-4 py-3 rounded-2xl",
+                    class: "group px-4 py-3 rounded-2xl",
                     class: if *is_assistant.read() {
                         "bg-slate-700/50 text-slate-100 rounded-tl-sm"
                     } else {
@@ -110,21 +199,143 @@ pub fn Message(messages: Signal<Vec<ChatMessage>>, index: usize, settings: Signa
                                 style: "animation-delay: 300ms;"
                             }
                         }
+                    } else if let Some((sentences, current)) = speaking() {
+                        // Plain sentences while read aloud, following along
+                        p {
+                            class: "leading-relaxed",
+                            for (i, sentence) in sentences.into_iter().enumerate() {
+                                span {
+                                    key: "{i}",
+                                    class: if i == current { "rounded bg-emerald-500/30 text-white" } else { "opacity-70" },
+                                    "{sentence} "
+                                }
+                            }
+                        }
                     } else {
-                        // Render the processed HTML content with dynamic font size
+                        // Render the processed HTML content
+                        div {
+                            class: "prose prose-invert max-w-none",
+                            class: "[&_pre]:bg-slate-800/80 [&_pre]:rounded-lg [&_pre]:p-3 [&_pre]:my-2 [&_pre]:overflow-x-auto",
+                            class: "[&_code]:bg-slate-800/60 [&_code]:px-1.5 [&_code]:py-0.5 [&_code]:rounded [&_code]:text-emerald-400 [&_code]:text-sm",
+                            class: "[&_pre_code]:bg-transparent [&_pre_code]:p-0",
+                            class: "[&_p]:my-1.5 [&_ul]:my-1.5 [&_ol]:my-1.5",
+                            class: "[&_a]:text-blue-400 [&_a:hover]:text-blue-300",
+                            class: "[&_strong]:text-white [&_em]:text-slate-300",
+                            class: "[&_math[display=block]]:my-3 [&_math[display=block]]:overflow-x-auto",
+                            for (i, part) in content().into_iter().enumerate() {
+                                match part {
+                                    Part::Html(html) => rsx! { div { key: "{i}", dangerous_inner_html: html } },
+                                    Part::Diagram(block) => rsx! { Diagram { key: "{i}", block } },
+                                }
+                            }
+                        }
+                    }
+
+                    for attachment in attachments() {
+                        details {
+                            key: "{attachment.id}",
+                            class: "mt-2 rounded-lg bg-black/20 text-sm",
+                            summary {
+                                class: "px-3 py-2 cursor-pointer select-none",
+                                "📎 {attachment.name}"
+                                span {
+                                    class: "ml-2 text-xs opacity-70",
+                                    if attachment.mode == AttachmentMode::Summary { "Summary" } else { "Full text" }
+                                }
+                            }
+                            div {
+                                class: "px-3 pb-3",
+                                div {
+                                    class: "max-h-64 overflow-y-auto whitespace-pre-wrap text-xs opacity-90",
+                                    "{attachment.content}"
+                                }
+                                if attachment.ingested {
+                                    p { class: "mt-2 text-xs opacity-70", "✓ In knowledge base" }
+                                } else {
+                                    button {
+                                        class: "mt-2 px-2 py-1 rounded bg-white/15 hover:bg-white/25 text-xs disabled:opacity-50",
+                                        disabled: ingesting().is_some(),
+                                        onclick: {
+                                            let name = attachment.name.clone();
+                                            let mut ingest = ingest;
+                                            move |_| ingest(attachment.id, name.clone())
+                                        },
+                                        if ingesting() == Some(attachment.id) { "Adding…" } else { "Add to knowledge base" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    if let Some(error) = ingest_error() {
+                        p { class: "mt-2 text-xs text-red-300", "{error}" }
+                    }
+
+                    if !*is_empty.read() {
+                        button {
+                            class: "mt-1 text-[11px] opacity-0 group-hover:opacity-60 hover:!opacity-100 transition-opacity",
+                            title: "Move to trash",
+                            onclick: delete,
+                            "🗑"
+                        }
+                    }
+
+                    if can_continue() {
+                        button {
+                            class: "mt-2 px-3 py-1 rounded-lg bg-slate-700 hover:bg-slate-600 text-xs text-slate-200",
+                            title: "The answer stopped at the token limit",
+                            onclick: move |_| {
+                                if let Some(id) = message_id() {
+                                    on_continue.call(id);
+                                }
+                            },
+                            "Continue ▸"
+                        }
+                    }
+
+                    if let Some(stats) = generation() {
+                        p {
+                            class: "mt-1 text-[11px] text-slate-500 opacity-0 group-hover:opacity-100 transition-opacity",
+                            title: "{stats.model}",
+                            "{stats.summary()}"
+                        }
+                    }
+
+                    if *is_assistant.read() && !*is_empty.read() {
                         {
-                            let font_style = settings.read().font_size.font_style();
+                            let current = feedback();
+                            let rating = current.as_ref().map(|f| f.rating);
+                            let comment = current.as_ref().and_then(|f| f.comment.clone()).unwrap_or_default();
                             rsx! {
                                 div {
-                                    class: "prose prose-invert max-w-none",
-                                    class: "[&_pre]:bg-slate-800/80 [&_pre]:rounded-lg [&_pre]:p-3 [&_pre]:my-2 [&_pre]:overflow-x-auto",
-                                    class: "[&_code]:bg-slate-800/60 [&_code]:px-1.5 [&_code]:py-0.5 [&_code]:rounded [&_code]:text-emerald-400 [&_code]:text-sm",
-                                    class: "[&_pre_code]:bg-transparent [&_pre_code]:p-0",
-                                    class: "[&_p]:my-1.5 [&_ul]:my-1.5 [&_ol]:my-1.5",
-                                    class: "[&_a]:text-blue-400 [&_a:hover]:text-blue-300",
-                                    class: "[&_strong]:text-white [&_em]:text-slate-300",
-                                    style: "{font_style}",
-                                    dangerous_inner_html: content
+                                    class: "flex items-center gap-1 mt-2 text-xs",
+                                    button {
+                                        class: if rating == Some(FeedbackRating::Up) { "px-1.5 py-0.5 rounded bg-emerald-600/40" } else { "px-1.5 py-0.5 rounded opacity-50 hover:opacity-100" },
+                                        title: "Good response",
+                                        onclick: move |_| rate(FeedbackRating::Up),
+                                        "👍"
+                                    }
+                                    button {
+                                        class: if rating == Some(FeedbackRating::Down) { "px-1.5 py-0.5 rounded bg-red-600/40" } else { "px-1.5 py-0.5 rounded opacity-50 hover:opacity-100" },
+                                        title: "Bad response",
+                                        onclick: move |_| rate(FeedbackRating::Down),
+                                        "👎"
+                                    }
+                                    button {
+                                        class: if queued() { "px-1.5 py-0.5 rounded bg-emerald-600/40" } else { "px-1.5 py-0.5 rounded opacity-50 hover:opacity-100" },
+                                        title: if queued() { "Stop reading aloud" } else { "Read aloud" },
+                                        onclick: toggle_speech,
+                                        if queued() { "⏹" } else { "🔊" }
+                                    }
+                                    if let Some(rating) = rating {
+                                        input {
+                                            class: "flex-1 ml-1 px-2 py-0.5 bg-slate-800/60 border border-slate-600 rounded text-slate-200 placeholder-slate-500 focus:outline-none",
+                                            placeholder: if rating == FeedbackRating::Down { "What was wrong? (optional)" } else { "What was good? (optional)" },
+                                            value: "{comment}",
+                                            onchange: move |e: FormEvent| {
+                                                set_feedback(Some(MessageFeedback::new(rating, Some(e.value()))));
+                                            },
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -9,15 +9,56 @@ mod settings_page;
 mod image_gen;
 mod tts_panel;
 mod content_editor;
+mod content_pipeline;
 mod video_gen;
+mod status_bar;
+mod quick_capture;
+mod command_palette;
+mod notifications;
+mod jobs_panel;
+mod assets_panel;
+mod trash_panel;
+mod calendar_panel;
+mod lock_screen;
+mod compare_panel;
+mod download;
+mod ime;
+mod clipboard;
+mod i18n;
+mod diagram;
+mod session_summary;
+mod session_settings;
+mod document_viewer;
+mod speech_player;
+mod voice_panel;
+mod error_notice;
+mod capability_notice;
 pub mod model_manager;
 
 pub use app::{App, ActivePanel};
-pub use sidebar::Sidebar;
-pub use chat::Chat;
+pub use sidebar::{Sidebar, tag_sessions};
+pub use chat::{Chat, refine_session_title};
 pub use message::Message;
-pub use settings_page::SettingsPage;
+pub use diagram::Diagram;
+pub use session_summary::{SessionSummaryCard, summarize_into};
+pub use session_settings::SessionSettingsBar;
+pub use settings_page::{SettingsPage, SettingsTab};
 pub use image_gen::ImageGenPanel;
 pub use tts_panel::TtsPanel;
 pub use content_editor::ContentEditorPanel;
+pub use content_pipeline::ContentPipelinePanel;
 pub use video_gen::VideoGenPanel;
+pub use status_bar::StatusBar;
+pub use quick_capture::{QuickCaptureDialog, use_quick_capture_hotkey};
+pub use command_palette::{CommandPalette, PaletteCommand, use_command_palette_hotkey};
+pub use notifications::NotificationCenter;
+pub use jobs_panel::JobsPanel;
+pub use assets_panel::AssetsPanel;
+pub use trash_panel::TrashPanel;
+pub use calendar_panel::CalendarPanel;
+pub use lock_screen::LockScreen;
+pub use compare_panel::ComparePanel;
+pub use speech_player::SpeechPlayer;
+pub use voice_panel::VoicePanel;
+pub use error_notice::{ErrorNotice, use_settings_opener_provider};
+pub use capability_notice::{MissingTools, OptionalTools, use_capabilities_provider, use_feature_available, use_tool_available};
@@ -2,7 +2,7 @@
 //!
 //! UI component for managing HuggingFace models
 
-use crate::models::{ModelInfo, CacheInfo, ModelType};
+use crate::models::{ModelInfo, CacheInfo, ModelType, error_message};
 use crate::server_functions::{
     list_cached_models, download_model, delete_model, get_cache_info, init_hf_cache
 };
@@ -123,7 +123,7 @@ pub fn ModelManagerPage() -> Element {
                         spawn(async move {
                             match init_hf_cache().await {
                                 Ok(msg) => success_msg.set(msg),
-                                Err(e) => error_msg.set(e.to_string()),
+                                Err(e) => error_msg.set(error_message(&e)),
                             }
                         });
                     },
@@ -197,12 +197,12 @@ async fn load_models(
 
     match list_cached_models().await {
         Ok(m) => models.set(m),
-        Err(e) => error_msg.set(e.to_string()),
+        Err(e) => error_msg.set(error_message(&e)),
     }
 
     match get_cache_info().await {
         Ok(info) => cache_info.set(info),
-        Err(e) => error_msg.set(e.to_string()),
+        Err(e) => error_msg.set(error_message(&e)),
     }
 
     loading.set(false);
@@ -226,7 +226,7 @@ async fn download_model_action(
             // Refresh models list
             load_models(models, cache_info, loading, error_msg).await;
         }
-        Err(e) => error_msg.set(e.to_string()),
+        Err(e) => error_msg.set(error_message(&e)),
     }
 
     loading.set(false);
@@ -250,7 +250,7 @@ async fn delete_model_action(
             // Refresh models list
             load_models(models, cache_info, loading, error_msg).await;
         }
-        Err(e) => error_msg.set(e.to_string()),
+        Err(e) => error_msg.set(error_message(&e)),
     }
 
     loading.set(false);
@@ -0,0 +1,158 @@
+//! Notification Center
+//!
+//! Bell in the header with the notification history, plus toasts for
+//! notifications that arrive while the app is open. In the desktop app new
+//! notifications are also shown by the OS when enabled in settings.
+
+use dioxus::prelude::*;
+use crate::models::{newer_than, AppSettings, Notification, NotificationLevel};
+use crate::server_functions::{clear_notifications, get_notifications, mark_notifications_read};
+use super::status_bar::sleep_ms;
+
+/// How often new notifications are fetched, in milliseconds
+const POLL_INTERVAL_MS: u32 = 3000;
+
+/// How long a toast stays on screen, in milliseconds
+const TOAST_DURATION_MS: u32 = 6000;
+
+#[component]
+pub fn NotificationCenter(settings: Signal<AppSettings>) -> Element {
+    let mut history: Signal<Vec<Notification>> = use_signal(Vec::new);
+    let mut toasts: Signal<Vec<Notification>> = use_signal(Vec::new);
+    let mut show_history = use_signal(|| false);
+
+    use_effect(move || {
+        spawn(async move {
+            // Notifications from before the app opened go to the history only
+            let mut last_seen_id: Option<u64> = None;
+            loop {
+                if let Ok(latest) = get_notifications().await {
+                    let newest = latest.iter().map(|n| n.id).max().unwrap_or(0);
+                    if let Some(seen) = last_seen_id {
+                        for notification in newer_than(&latest, seen) {
+                            if settings.peek().desktop_notifications {
+                                notify_os(&notification);
+                            }
+                            show_toast(toasts, notification);
+                        }
+                    }
+                    last_seen_id = Some(last_seen_id.unwrap_or(0).max(newest));
+                    history.set(latest);
+                }
+                sleep_ms(POLL_INTERVAL_MS).await;
+            }
+        });
+    });
+
+    let unread = history().iter().filter(|n| !n.read).count();
+
+    rsx! {
+        div {
+            class: "relative",
+            button {
+                class: "relative p-1.5 rounded-md text-slate-400 hover:text-white hover:bg-slate-700 transition-colors",
+                title: "Notifications",
+                onclick: move |_| {
+                    let open = !show_history();
+                    show_history.set(open);
+                    if open && unread > 0 {
+                        history.write().iter_mut().for_each(|n| n.read = true);
+                        spawn(async move {
+                            let _ = mark_notifications_read().await;
+                        });
+                    }
+                },
+                "🔔"
+                if unread > 0 {
+                    span {
+                        class: "absolute -top-1 -right-1 min-w-4 h-4 px-1 rounded-full bg-red-600 text-[10px] leading-4 text-white text-center",
+                        "{unread}"
+                    }
+                }
+            }
+
+            if show_history() {
+                div {
+                    class: "absolute right-0 top-full mt-2 w-80 max-h-96 overflow-y-auto bg-slate-800 border border-slate-700 rounded-lg shadow-xl z-40",
+                    div {
+                        class: "flex items-center justify-between px-3 py-2 border-b border-slate-700",
+                        span { class: "text-sm font-medium text-slate-300", "Notifications" }
+                        if !history().is_empty() {
+                            button {
+                                class: "text-xs text-slate-400 hover:text-white",
+                                onclick: move |_| {
+                                    history.set(Vec::new());
+                                    spawn(async move {
+                                        let _ = clear_notifications().await;
+                                    });
+                                },
+                                "Clear all"
+                            }
+                        }
+                    }
+                    if history().is_empty() {
+                        p { class: "px-3 py-6 text-sm text-slate-500 text-center", "No notifications yet" }
+                    }
+                    for notification in history() {
+                        NotificationItem { key: "{notification.id}", notification }
+                    }
+                }
+            }
+        }
+
+        // Toasts
+        div {
+            class: "fixed bottom-10 right-4 z-50 flex flex-col gap-2 w-80",
+            for (id, notification) in toasts().into_iter().map(|n| (n.id, n)) {
+                div {
+                    key: "{id}",
+                    class: match notification.level {
+                        NotificationLevel::Error => "bg-slate-800 border border-red-700 rounded-lg shadow-xl",
+                        _ => "bg-slate-800 border border-slate-600 rounded-lg shadow-xl",
+                    },
+                    onclick: move |_| toasts.write().retain(|t| t.id != id),
+                    NotificationItem { notification }
+                }
+            }
+        }
+    }
+}
+
+fn show_toast(mut toasts: Signal<Vec<Notification>>, notification: Notification) {
+    let id = notification.id;
+    toasts.write().push(notification);
+    spawn(async move {
+        sleep_ms(TOAST_DURATION_MS).await;
+        toasts.write().retain(|t| t.id != id);
+    });
+}
+
+/// Shows the notification through the OS in the desktop app
+fn notify_os(notification: &Notification) {
+    #[cfg(feature = "desktop")]
+    crate::desktop::notify(notification);
+    #[cfg(not(feature = "desktop"))]
+    let _ = notification;
+}
+
+#[component]
+fn NotificationItem(notification: Notification) -> Element {
+    let time = notification.created_at.with_timezone(&chrono::Local).format("%H:%M").to_string();
+    rsx! {
+        div {
+            class: "flex gap-2 px-3 py-2 text-sm",
+            span { "{notification.level.icon()}" }
+            div {
+                class: "flex-1 min-w-0",
+                div {
+                    class: "flex items-center justify-between gap-2",
+                    span { class: "text-white truncate", "{notification.title}" }
+                    span { class: "text-xs text-slate-500 shrink-0", "{time}" }
+                }
+                if !notification.message.is_empty() {
+                    p { class: "text-xs text-slate-400 break-words", "{notification.message}" }
+                }
+            }
+        }
+    }
+}
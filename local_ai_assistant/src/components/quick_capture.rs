@@ -0,0 +1,128 @@
+//! Quick Capture Dialog
+//!
+//! Files clipboard text or a URL into the knowledge base. Opened with
+//! Ctrl/Cmd+Shift+K from anywhere in the app.
+
+use dioxus::prelude::*;
+use crate::models::error_message;
+use crate::server_functions::{list_capture_collections, quick_capture};
+
+/// Registers the global Ctrl/Cmd+Shift+K shortcut that opens the dialog
+pub fn use_quick_capture_hotkey(mut open: Signal<bool>) {
+    use_effect(move || {
+        spawn(async move {
+            let mut listener = document::eval(
+                r#"document.addEventListener('keydown', (e) => {
+                    if ((e.ctrlKey || e.metaKey) && e.shiftKey && e.key.toLowerCase() === 'k') {
+                        e.preventDefault();
+                        dioxus.send(true);
+                    }
+                });"#,
+            );
+            while listener.recv::<bool>().await.is_ok() {
+                open.set(true);
+            }
+        });
+    });
+}
+
+/// Modal dialog for capturing text or a URL
+#[component]
+pub fn QuickCaptureDialog(open: Signal<bool>) -> Element {
+    let mut input: Signal<String> = use_signal(String::new);
+    let mut collection: Signal<String> = use_signal(String::new);
+    let mut collections: Signal<Vec<String>> = use_signal(Vec::new);
+    let mut is_saving: Signal<bool> = use_signal(|| false);
+    let mut status: Signal<Option<(bool, String)>> = use_signal(|| None);
+
+    // Prefill from the clipboard each time the dialog opens
+    use_effect(move || {
+        if !open() {
+            return;
+        }
+        status.set(None);
+        spawn(async move {
+            if let Ok(list) = list_capture_collections().await {
+                collections.set(list);
+            }
+            let clipboard = document::eval("return await navigator.clipboard.readText();");
+            if let Ok(text) = clipboard.join::<String>().await {
+                if !text.trim().is_empty() {
+                    input.set(text);
+                }
+            }
+        });
+    });
+
+    if !open() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "fixed inset-0 z-50 flex items-center justify-center bg-black/50",
+            onclick: move |_| open.set(false),
+            div {
+                class: "w-full max-w-lg bg-slate-800 rounded-xl shadow-xl p-5 space-y-3",
+                onclick: move |e| e.stop_propagation(),
+
+                div {
+                    class: "flex items-center justify-between",
+                    h2 { class: "text-base font-semibold text-white", "Quick Capture" }
+                    span { class: "text-xs text-slate-500", "Ctrl/Cmd+Shift+K" }
+                }
+                textarea {
+                    class: "w-full h-40 px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500 resize-none",
+                    placeholder: "Paste text or a URL...",
+                    value: "{input}",
+                    autofocus: true,
+                    oninput: move |e| input.set(e.value()),
+                }
+                div {
+                    class: "flex items-center gap-2",
+                    select {
+                        class: "flex-1 px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white focus:outline-none focus:border-blue-500",
+                        value: "{collection}",
+                        onchange: move |e| collection.set(e.value()),
+                        option { value: "", "Knowledge base (root)" }
+                        for name in collections() {
+                            option { key: "{name}", value: "{name}", "{name}" }
+                        }
+                    }
+                    button {
+                        class: "px-3 py-2 text-sm text-slate-300 hover:bg-slate-700 rounded-lg transition-colors",
+                        onclick: move |_| open.set(false),
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-3 py-2 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors disabled:opacity-50",
+                        disabled: is_saving() || input().trim().is_empty(),
+                        onclick: move |_| {
+                            let text = input();
+                            let target = Some(collection()).filter(|c| !c.is_empty());
+                            is_saving.set(true);
+                            spawn(async move {
+                                match quick_capture(text, target).await {
+                                    Ok(captured) => {
+                                        let note = if captured.indexed { "" } else { " (will be indexed on next reload)" };
+                                        status.set(Some((true, format!("Saved \"{}\"{}", captured.title, note))));
+                                        input.set(String::new());
+                                    }
+                                    Err(e) => status.set(Some((false, error_message(&e)))),
+                                }
+                                is_saving.set(false);
+                            });
+                        },
+                        if is_saving() { "Saving..." } else { "Save" }
+                    }
+                }
+                if let Some((ok, msg)) = status() {
+                    p {
+                        class: if ok { "text-xs text-green-400" } else { "text-xs text-red-400" },
+                        "{msg}"
+                    }
+                }
+            }
+        }
+    }
+}
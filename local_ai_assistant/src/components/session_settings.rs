@@ -0,0 +1,143 @@
+//! Session Settings Bar
+//!
+//! Compact row above the messages showing the session's model, persona and
+//! temperature, each changeable for this session only.
+
+use dioxus::prelude::*;
+use crate::models::{ModelInfo, ModelType, Persona, Session, SessionOverrides, DEFAULT_TEMPERATURE, TEMPERATURE_CHOICES, error_message};
+use crate::server_functions::{get_current_model, list_available_models, set_session_overrides};
+
+/// Applies changed overrides to the session and saves them. Without a
+/// session, a new one is started with them.
+fn update_overrides(
+    mut current_session: Signal<Option<Session>>,
+    mut sessions: Signal<Vec<Session>>,
+    mut error: Signal<Option<String>>,
+    change: impl FnOnce(&mut SessionOverrides),
+) {
+    let mut session = match current_session() {
+        Some(session) => session,
+        None => {
+            let session = Session::default_title();
+            sessions.write().insert(0, session.clone());
+            session
+        }
+    };
+    change(&mut session.overrides);
+    if let Some(listed) = sessions.write().iter_mut().find(|s| s.id == session.id) {
+        listed.overrides = session.overrides.clone();
+    }
+    current_session.set(Some(session.clone()));
+    spawn(async move {
+        error.set(set_session_overrides(session).await.err().map(|e| error_message(&e)));
+    });
+}
+
+#[component]
+pub fn SessionSettingsBar(
+    current_session: Signal<Option<Session>>,
+    sessions: Signal<Vec<Session>>,
+) -> Element {
+    let models = use_resource(|| async move {
+        list_available_models().await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|m| m.model_type == ModelType::Language)
+            .collect::<Vec<ModelInfo>>()
+    });
+    let active_model = use_resource(|| async move {
+        get_current_model().await.map(|m| m.name).unwrap_or_default()
+    });
+    let error: Signal<Option<String>> = use_signal(|| None);
+
+    let overrides = current_session().map(|s| s.overrides).unwrap_or_default();
+    let models = models().unwrap_or_default();
+    let active_model = active_model().unwrap_or_default();
+    let select_class = "bg-transparent text-slate-300 hover:text-white rounded px-1 py-0.5 focus:outline-none focus:bg-slate-800 cursor-pointer";
+
+    rsx! {
+        div {
+            class: "flex items-center justify-center gap-3 px-4 py-1.5 border-b border-slate-800 text-xs text-slate-500",
+
+            label {
+                class: "flex items-center gap-1",
+                title: "Model for this session",
+                "Model"
+                select {
+                    class: select_class,
+                    onchange: move |e| {
+                        let value = e.value();
+                        update_overrides(current_session, sessions, error, |o| {
+                            o.model_id = (!value.is_empty()).then_some(value);
+                        });
+                    },
+                    option {
+                        value: "",
+                        selected: overrides.model_id.is_none(),
+                        if active_model.is_empty() { "Active model" } else { "Active ({active_model})" }
+                    }
+                    for model in models.iter() {
+                        option {
+                            key: "{model.id}",
+                            value: "{model.id}",
+                            selected: overrides.model_id.as_deref() == Some(model.id.as_str()),
+                            "{model.name}"
+                        }
+                    }
+                }
+            }
+
+            label {
+                class: "flex items-center gap-1",
+                title: "Role the model takes in this session",
+                "Persona"
+                select {
+                    class: select_class,
+                    onchange: move |e| {
+                        if let Some(persona) = e.value().parse::<usize>().ok().and_then(|i| Persona::all().get(i)) {
+                            update_overrides(current_session, sessions, error, |o| o.persona = *persona);
+                        }
+                    },
+                    for (i, persona) in Persona::all().iter().enumerate() {
+                        option {
+                            key: "{i}",
+                            value: "{i}",
+                            selected: overrides.persona == *persona,
+                            "{persona.label()}"
+                        }
+                    }
+                }
+            }
+
+            label {
+                class: "flex items-center gap-1",
+                title: "Lower is more focused, higher more varied",
+                "Temperature"
+                select {
+                    class: select_class,
+                    onchange: move |e| {
+                        let temperature = e.value().parse::<f32>().ok();
+                        update_overrides(current_session, sessions, error, |o| o.temperature = temperature);
+                    },
+                    option {
+                        value: "",
+                        selected: overrides.temperature.is_none(),
+                        "Default ({DEFAULT_TEMPERATURE:.1})"
+                    }
+                    for temperature in TEMPERATURE_CHOICES.iter() {
+                        option {
+                            key: "{temperature}",
+                            value: "{temperature}",
+                            selected: overrides.temperature == Some(*temperature),
+                            "{temperature:.1}"
+                        }
+                    }
+                }
+            }
+
+            if let Some(e) = error() {
+                span { class: "text-red-400", title: "{e}", "Not saved" }
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Session Summary Component
+//!
+//! The stored summary of a conversation, with its takeaways and action
+//! items, shown above the messages.
+
+use dioxus::prelude::*;
+use crate::models::{Session, error_message};
+use crate::server_functions::summarize_session;
+
+/// Summarizes a session and applies the summary to the local state
+pub async fn summarize_into(
+    session_id: uuid::Uuid,
+    mut current_session: Signal<Option<Session>>,
+    mut sessions: Signal<Vec<Session>>,
+) -> Result<(), String> {
+    let summary = summarize_session(session_id.to_string()).await.map_err(error_message)?;
+    if let Some(session) = sessions.write().iter_mut().find(|s| s.id == session_id) {
+        session.summary = Some(summary.clone());
+    }
+    if let Some(mut session) = current_session().filter(|s| s.id == session_id) {
+        session.summary = Some(summary);
+        current_session.set(Some(session));
+    }
+    Ok(())
+}
+
+#[component]
+pub fn SessionSummaryCard(
+    current_session: Signal<Option<Session>>,
+    sessions: Signal<Vec<Session>>,
+    message_count: usize,
+) -> Element {
+    let mut collapsed = use_signal(|| false);
+    let mut is_updating = use_signal(|| false);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    let Some(session) = current_session() else { return rsx! {} };
+    let session_id = session.id;
+    let Some(summary) = session.summary else { return rsx! {} };
+    let outdated = summary.is_outdated(message_count);
+    let created = summary.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+
+    rsx! {
+        div {
+            class: "mb-6 bg-slate-800/70 border border-slate-700 rounded-xl p-4 text-sm",
+            div {
+                class: "flex items-center gap-2",
+                button {
+                    class: "flex-1 text-left font-medium text-white",
+                    onclick: move |_| collapsed.set(!collapsed()),
+                    if collapsed() { "▸ Summary" } else { "▾ Summary" }
+                }
+                span { class: "text-xs text-slate-500", "{created}" }
+                if outdated {
+                    button {
+                        class: "px-2 py-0.5 text-xs rounded-md text-slate-400 hover:text-white hover:bg-slate-700 transition-colors disabled:opacity-50",
+                        title: "New messages were added since this summary",
+                        disabled: is_updating(),
+                        onclick: move |_| {
+                            is_updating.set(true);
+                            spawn(async move {
+                                error.set(summarize_into(session_id, current_session, sessions).await.err());
+                                is_updating.set(false);
+                            });
+                        },
+                        if is_updating() { "Updating..." } else { "Update" }
+                    }
+                }
+            }
+            if !collapsed() {
+                p { class: "mt-2 text-slate-300", "{summary.summary}" }
+                if !summary.takeaways.is_empty() {
+                    p { class: "mt-3 text-xs font-medium text-slate-400 uppercase", "Key takeaways" }
+                    ul {
+                        class: "mt-1 list-disc pl-5 text-slate-300 space-y-0.5",
+                        for takeaway in summary.takeaways.iter() {
+                            li { "{takeaway}" }
+                        }
+                    }
+                }
+                if !summary.action_items.is_empty() {
+                    p { class: "mt-3 text-xs font-medium text-slate-400 uppercase", "Action items" }
+                    ul {
+                        class: "mt-1 pl-1 text-slate-300 space-y-0.5",
+                        for item in summary.action_items.iter() {
+                            li { "☐ {item}" }
+                        }
+                    }
+                }
+            }
+            if let Some(message) = error() {
+                p { class: "mt-2 text-xs text-red-400", "{message}" }
+            }
+        }
+    }
+}
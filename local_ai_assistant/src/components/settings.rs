@@ -3,7 +3,7 @@
 //! Tab-based settings panel with unified model management
 
 use dioxus::prelude::*;
-use crate::models::{AppSettings, ResponseLanguage, Theme, FontSize, ModelInfo};
+use crate::models::{AppSettings, ResponseLanguage, Theme, FontSize, ModelInfo, error_message};
 use crate::server_functions::{
     list_context_files, add_context_document, delete_context_document, reload_context_database, ContextFile,
     list_available_models, get_current_model, switch_llm_model,
@@ -224,11 +224,11 @@ fn ChatModelSection() -> Element {
         spawn(async move {
             match list_available_models().await {
                 Ok(models) => available_models.set(models),
-                Err(e) => println!("Error loading available models: {:?}", e),
+                Err(e) => tracing::error!("Error loading available models: {:?}", e),
             }
             match get_current_model().await {
                 Ok(model) => current_model.set(Some(model)),
-                Err(e) => println!("Error loading current model: {:?}", e),
+                Err(e) => tracing::error!("Error loading current model: {:?}", e),
             }
         });
     });
@@ -325,7 +325,7 @@ fn ChatModelSection() -> Element {
                                                 }
                                             }
                                             Err(e) => {
-                                                status_message.set(Some(format!("Error: {}", e)));
+                                                status_message.set(Some(format!("Error: {}", error_message(&e))));
                                             }
                                         }
                                         is_loading.set(false);
@@ -364,11 +364,11 @@ fn ImageModelSection() -> Element {
         spawn(async move {
             match is_image_model_ready().await {
                 Ok(ready) => model_ready.set(ready),
-                Err(e) => println!("Error checking image model: {:?}", e),
+                Err(e) => tracing::error!("Error checking image model: {:?}", e),
             }
             match get_image_gen_status().await {
                 Ok(s) => status.set(s),
-                Err(e) => println!("Error getting image gen status: {:?}", e),
+                Err(e) => tracing::error!("Error getting image gen status: {:?}", e),
             }
         });
     });
@@ -642,7 +642,7 @@ fn ContextTab() -> Element {
         spawn(async move {
             match list_context_files().await {
                 Ok(files) => context_files.set(files),
-                Err(e) => println!("Error loading context files: {:?}", e),
+                Err(e) => tracing::error!("Error loading context files: {:?}", e),
             }
         });
     });
@@ -718,7 +718,7 @@ fn ContextTab() -> Element {
                                             }
                                         }
                                         Err(e) => {
-                                            status_message.set(Some(format!("Error: {}", e)));
+                                            status_message.set(Some(format!("Error: {}", error_message(&e))));
                                         }
                                     }
                                     is_loading.set(false);
@@ -772,7 +772,7 @@ fn ContextTab() -> Element {
                                                 if let Ok(files) = list_context_files().await {
                                                     context_files.set(files);
                                                 }
-                                                status_message.set(Some("Document deleted.".to_string()));
+                                                status_message.set(Some("Document moved to the trash.".to_string()));
                                             }
                                         });
                                     }
@@ -805,7 +805,7 @@ fn ContextTab() -> Element {
                     spawn(async move {
                         match reload_context_database().await {
                             Ok(msg) => status_message.set(Some(msg)),
-                            Err(e) => status_message.set(Some(format!("Error: {}", e))),
+                            Err(e) => status_message.set(Some(format!("Error: {}", error_message(&e)))),
                         }
                         is_loading.set(false);
                     });
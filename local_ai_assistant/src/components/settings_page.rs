@@ -1,12 +1,38 @@
 //! Settings Page Component - Full-page settings view
 
 use dioxus::prelude::*;
-use crate::models::{AppSettings, ResponseLanguage, Theme, FontSize, ModelInfo, ModelType};
+use crate::models::{AppSettings, AutoScroll, Codebase, TitleMethod, MAX_ATTACHMENT_BYTES, SendShortcut, PITCH_RANGE, PronunciationEntry, QueryExpansion, RECENCY_HALF_LIVES, ResponseLanguage, Theme, FontSize, ModelInfo, ModelType, Feature, error_message};
 use crate::server_functions::{
-    list_context_files, add_context_document, delete_context_document, reload_context_database, ContextFile,
+    list_context_files, add_context_document, upload_context_file, delete_context_document, reload_context_database, get_indexing_jobs, ContextFile,
+    list_capture_collections, transcribe_audio_file, transcribe_audio_url,
+    add_codebase, list_codebases, reindex_codebase, remove_codebase,
     is_image_model_ready, init_image_model,
     list_cached_models, download_model,
+    get_system_stats, get_recent_logs,
+    get_embedding_config, set_embedding_backend, set_embedding_batch_size,
+    get_vector_store_stats, search_vector_store_raw, expand_search_query,
+    get_webhooks, save_webhooks, test_webhook,
+    get_memories, delete_memory, clear_memories, export_feedback,
+    get_sessions, get_profiles, export_fine_tune_dataset,
+    get_web_search_config, save_web_search_config, search_web,
+    get_storage_locations, get_storage_overrides, set_storage_overrides,
+    get_lan_access, save_lan_access,
+    get_proxy_settings, save_proxy_settings,
+    list_audio_output_devices,
+    get_encryption_status, enable_history_encryption, change_history_passphrase, disable_history_encryption, lock_history,
+    get_network_audit, clear_network_audit,
 };
+use super::download::save_text_file;
+use super::document_viewer::DocumentViewer;
+use super::status_bar::sleep_ms;
+use super::ime::use_ime_state;
+use super::i18n::t;
+use super::{MissingTools, OptionalTools, use_feature_available};
+use crate::models::{EncryptionStatus, MIN_PASSPHRASE_LENGTH};
+use crate::models::{NetworkFeature, NetworkRequest, summarize_by_domain};
+use crate::models::{Session, Profile, FineTuneFilter, IndexingJob, IndexStage, StorageLocation, StorageLocationInfo, StoragePaths, LanAccessConfig, LanAccessInfo, MIN_PIN_LENGTH};
+use crate::models::{ProxyConfig, HF_MIRRORS};
+use crate::models::{SystemStats, LogEntry, EmbeddingBackend, LocalEmbeddingModel, DEFAULT_EMBEDDING_BATCH_SIZE, VectorStoreStats, RetrievedChunk, Webhook, WebhookEvent, MemoryEntry, WebSearchConfig, WebSearchProvider, WebSearchResult};
 
 
 // Helper function to format size
@@ -19,24 +45,73 @@ fn format_size(size_mb: u64) -> String {
 }
 
 /// Settings page tabs
-#[derive(Clone, PartialEq, Default)]
+#[derive(Clone, Debug, PartialEq, Default)]
 pub enum SettingsTab {
     #[default]
     Models,
     Appearance,
     Language,
+    Audio,
     Context,
+    WebSearch,
     Database,
+    Memory,
+    System,
+    Logs,
+    Privacy,
+    Webhooks,
     About,
 }
 
+impl SettingsTab {
+    pub fn all() -> &'static [SettingsTab] {
+        &[
+            SettingsTab::Models,
+            SettingsTab::Appearance,
+            SettingsTab::Language,
+            SettingsTab::Audio,
+            SettingsTab::Context,
+            SettingsTab::WebSearch,
+            SettingsTab::Database,
+            SettingsTab::Memory,
+            SettingsTab::System,
+            SettingsTab::Logs,
+            SettingsTab::Privacy,
+            SettingsTab::Webhooks,
+            SettingsTab::About,
+        ]
+    }
+
+    /// Translation key of the tab's name
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            SettingsTab::Models => "settings.tab.models",
+            SettingsTab::Appearance => "settings.tab.appearance",
+            SettingsTab::Language => "settings.tab.language",
+            SettingsTab::Audio => "settings.tab.audio",
+            SettingsTab::Context => "settings.tab.context",
+            SettingsTab::WebSearch => "settings.tab.web_search",
+            SettingsTab::Database => "settings.tab.database",
+            SettingsTab::Memory => "settings.tab.memory",
+            SettingsTab::System => "settings.tab.system",
+            SettingsTab::Logs => "settings.tab.logs",
+            SettingsTab::Privacy => "settings.tab.privacy",
+            SettingsTab::Webhooks => "settings.tab.webhooks",
+            SettingsTab::About => "settings.tab.about",
+        }
+    }
+}
+
 /// Full-page settings component
 #[component]
 pub fn SettingsPage(
     settings: Signal<AppSettings>,
     on_close: EventHandler<()>,
+    /// Tab shown when the page opens
+    #[props(default)]
+    initial_tab: SettingsTab,
 ) -> Element {
-    let active_tab: Signal<SettingsTab> = use_signal(SettingsTab::default);
+    let active_tab: Signal<SettingsTab> = use_signal(move || initial_tab.clone());
 
     rsx! {
         div {
@@ -66,7 +141,7 @@ pub fn SettingsPage(
                     }
                     h1 {
                         class: "text-xl font-semibold text-white",
-                        "Settings"
+                        {t("settings.title")}
                     }
                 }
                 button {
@@ -111,15 +186,22 @@ pub fn SettingsPage(
                                 d: "M10 19l-7-7m0 0l7-7m-7 7h18"
                             }
                         }
-                        "Back"
+                        {t("settings.back")}
                     }
 
-                    { render_nav_item(active_tab.clone(), SettingsTab::Models, "Models", "M9.75 17L9 20l-1 1h8l-1-1-.75-3M3 13h18M5 17h14a2 2 0 002-2V5a2 2 0 00-2-2H5a2 2 0 00-2 2v10a2 2 0 002 2z") }
-                    { render_nav_item(active_tab.clone(), SettingsTab::Appearance, "Appearance", "M7 21a4 4 0 01-4-4V5a2 2 0 012-2h4a2 2 0 012 2v12a4 4 0 01-4 4zm0 0h12a2 2 0 002-2v-4a2 2 0 00-2-2h-2.343M11 7.343l1.657-1.657a2 2 0 012.828 0l2.829 2.829a2 2 0 010 2.828l-8.486 8.485M7 17h.01") }
-                    { render_nav_item(active_tab.clone(), SettingsTab::Language, "Language", "M3 5h12M9 3v2m1.048 9.5A18.022 18.022 0 016.412 9m6.088 9h7M11 21l5-10 5 10M12.751 5C11.783 10.77 8.07 15.61 3 18.129") }
-                    { render_nav_item(active_tab.clone(), SettingsTab::Context, "Context (RAG)", "M9 12h6m-6 4h6m2 5H7a2 2 0 01-2-2V5a2 2 0 012-2h5.586a1 1 0 01.707.293l5.414 5.414a1 1 0 01.293.707V19a2 2 0 01-2 2z") }
-                    { render_nav_item(active_tab.clone(), SettingsTab::Database, "Database", "M4 7v10c0 2.21 3.582 4 8 4s8-1.79 8-4V7M4 7c0 2.21 3.582 4 8 4s8-1.79 8-4M4 7c0-2.21 3.582-4 8-4s8 1.79 8 4m0 5c0 2.21-3.582 4-8 4s-8-1.79-8-4") }
-                    { render_nav_item(active_tab.clone(), SettingsTab::About, "About", "M13 16h-1v-4h-1m1-4h.01M21 12a9 9 0 11-18 0 9 9 0 0118 0z") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::Models, t(SettingsTab::Models.label_key()), "M9.75 17L9 20l-1 1h8l-1-1-.75-3M3 13h18M5 17h14a2 2 0 002-2V5a2 2 0 00-2-2H5a2 2 0 00-2 2v10a2 2 0 002 2z") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::Appearance, t(SettingsTab::Appearance.label_key()), "M7 21a4 4 0 01-4-4V5a2 2 0 012-2h4a2 2 0 012 2v12a4 4 0 01-4 4zm0 0h12a2 2 0 002-2v-4a2 2 0 00-2-2h-2.343M11 7.343l1.657-1.657a2 2 0 012.828 0l2.829 2.829a2 2 0 010 2.828l-8.486 8.485M7 17h.01") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::Language, t(SettingsTab::Language.label_key()), "M3 5h12M9 3v2m1.048 9.5A18.022 18.022 0 016.412 9m6.088 9h7M11 21l5-10 5 10M12.751 5C11.783 10.77 8.07 15.61 3 18.129") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::Audio, t(SettingsTab::Audio.label_key()), "M15.536 8.464a5 5 0 010 7.072m2.828-9.9a9 9 0 010 12.728M5.586 15H4a1 1 0 01-1-1v-4a1 1 0 011-1h1.586l4.707-4.707C10.923 3.663 12 4.109 12 5v14c0 .891-1.077 1.337-1.707.707L5.586 15z") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::Context, t(SettingsTab::Context.label_key()), "M9 12h6m-6 4h6m2 5H7a2 2 0 01-2-2V5a2 2 0 012-2h5.586a1 1 0 01.707.293l5.414 5.414a1 1 0 01.293.707V19a2 2 0 01-2 2z") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::WebSearch, t(SettingsTab::WebSearch.label_key()), "M21 21l-6-6m2-5a7 7 0 11-14 0 7 7 0 0114 0z") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::Database, t(SettingsTab::Database.label_key()), "M4 7v10c0 2.21 3.582 4 8 4s8-1.79 8-4V7M4 7c0 2.21 3.582 4 8 4s8-1.79 8-4M4 7c0-2.21 3.582-4 8-4s8 1.79 8 4m0 5c0 2.21-3.582 4-8 4s-8-1.79-8-4") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::Memory, t(SettingsTab::Memory.label_key()), "M9.663 17h4.673M12 3v1m6.364 1.636l-.707.707M21 12h-1M4 12H3m3.343-5.657l-.707-.707m2.828 9.9a5 5 0 117.072 0l-.548.547A3.374 3.374 0 0014 18.469V19a2 2 0 11-4 0v-.531c0-.895-.356-1.754-.988-2.386l-.548-.547z") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::System, t(SettingsTab::System.label_key()), "M9 3v2m6-2v2M9 19v2m6-2v2M5 9H3m2 6H3m18-6h-2m2 6h-2M7 19h10a2 2 0 002-2V7a2 2 0 00-2-2H7a2 2 0 00-2 2v10a2 2 0 002 2zM9 9h6v6H9V9z") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::Logs, t(SettingsTab::Logs.label_key()), "M4 6h16M4 10h16M4 14h10M4 18h7") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::Privacy, t(SettingsTab::Privacy.label_key()), "M12 15v2m-6 4h12a2 2 0 002-2v-6a2 2 0 00-2-2H6a2 2 0 00-2 2v6a2 2 0 002 2zm10-10V7a4 4 0 00-8 0v4h8z") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::Webhooks, t(SettingsTab::Webhooks.label_key()), "M13.828 10.172a4 4 0 00-5.656 0l-4 4a4 4 0 105.656 5.656l1.102-1.101m-.758-4.899a4 4 0 005.656 0l4-4a4 4 0 00-5.656-5.656l-1.1 1.1") }
+                    { render_nav_item(active_tab.clone(), SettingsTab::About, t(SettingsTab::About.label_key()), "M13 16h-1v-4h-1m1-4h.01M21 12a9 9 0 11-18 0 9 9 0 0118 0z") }
                 }
 
                 // Content area
@@ -129,8 +211,15 @@ pub fn SettingsPage(
                         SettingsTab::Models => rsx! { ModelsSettings { settings: settings } },
                         SettingsTab::Appearance => rsx! { AppearanceSettings { settings: settings } },
                         SettingsTab::Language => rsx! { LanguageSettings { settings: settings } },
-                        SettingsTab::Context => rsx! { ContextSettings {} },
+                        SettingsTab::Audio => rsx! { AudioSettings { settings: settings } },
+                        SettingsTab::Context => rsx! { ContextSettings { settings: settings } },
+                        SettingsTab::WebSearch => rsx! { WebSearchSettings {} },
                         SettingsTab::Database => rsx! { DatabaseSettings {} },
+                        SettingsTab::Memory => rsx! { MemorySettings {} },
+                        SettingsTab::System => rsx! { SystemSettings {} },
+                        SettingsTab::Logs => rsx! { LogsSettings {} },
+                        SettingsTab::Privacy => rsx! { NetworkAuditSettings {} },
+                        SettingsTab::Webhooks => rsx! { WebhookSettings {} },
                         SettingsTab::About => rsx! { AboutSettings {} },
                     }
                 }
@@ -171,6 +260,7 @@ fn render_nav_item(mut active_tab: Signal<SettingsTab>, tab: SettingsTab, label:
 /// Models settings section - Chat Model and Image Gen Model
 #[component]
 fn ModelsSettings(settings: Signal<AppSettings>) -> Element {
+    let mut settings = settings;
     let current = settings.read().clone();
     let mut image_model_ready: Signal<bool> = use_signal(|| false);
     let mut image_model_downloading: Signal<bool> = use_signal(|| false);
@@ -225,7 +315,7 @@ fn ModelsSettings(settings: Signal<AppSettings>) -> Element {
                         llm_status.set("Models loaded".to_string());
                     }
                     Err(e) => {
-                        llm_status.set(format!("Error: {}", e));
+                        llm_status.set(format!("Error: {}", error_message(&e)));
                     }
                 }
             });
@@ -285,6 +375,29 @@ fn ModelsSettings(settings: Signal<AppSettings>) -> Element {
                     option { value: "Llama 3.2 8B", "Llama 3.2 8B" }
                 }
 
+                label {
+                    class: "block text-xs text-slate-400",
+                    "Routing model"
+                    select {
+                        class: "mt-1 w-full px-4 py-2 bg-slate-700 border border-slate-600 rounded-lg text-white focus:outline-none focus:border-blue-500",
+                        onchange: move |e: Event<FormData>| {
+                            settings.write().routing_model = Some(e.value()).filter(|id| !id.is_empty());
+                        },
+                        option { value: "", selected: current.routing_model.is_none(), "Same as chat model" }
+                        for model in models() {
+                            option {
+                                value: "{model.id}",
+                                selected: current.routing_model.as_deref() == Some(model.id.as_str()),
+                                "{model.name} ({model.size})"
+                            }
+                        }
+                    }
+                    p {
+                        class: "mt-1 text-xs text-slate-500",
+                        "A small model for quick steps such as condensing follow-up questions before retrieval. It is loaded next to the chat model."
+                    }
+                }
+
                 div {
                     class: "mt-3 p-3 bg-slate-700/50 rounded-lg space-y-2",
 
@@ -350,12 +463,12 @@ fn ModelsSettings(settings: Signal<AppSettings>) -> Element {
                                                             models.set(llm_models);
                                                         }
                                                         Err(e) => {
-                                                            llm_status.set(format!("Error refreshing models: {}", e));
+                                                            llm_status.set(format!("Error refreshing models: {}", error_message(&e)));
                                                         }
                                                     }
                                                 }
                                                 Err(e) => {
-                                                    llm_status.set(format!("Download failed: {}", e));
+                                                    llm_status.set(format!("Download failed: {}", error_message(&e)));
                                                 }
                                             }
                                             llm_downloading.set(false);
@@ -492,7 +605,7 @@ fn ModelsSettings(settings: Signal<AppSettings>) -> Element {
                                         download_status.set("Ready".to_string());
                                     }
                                     Err(e) => {
-                                        download_status.set(format!("Error: {}", e));
+                                        download_status.set(format!("Error: {}", error_message(&e)));
                                     }
                                 }
                                 image_model_downloading.set(false);
@@ -623,6 +736,141 @@ fn ModelsSettings(settings: Signal<AppSettings>) -> Element {
                 }
             }
 
+            // Generation safeguards
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                h3 {
+                    class: "text-sm font-medium text-slate-300",
+                    "Generation Limits"
+                }
+                p {
+                    class: "text-xs text-slate-500",
+                    "Stops responses that loop or run on. Applies to chat, tools and the local API."
+                }
+                label {
+                    class: "flex items-center justify-between text-sm",
+                    span { class: "text-slate-400", "Max output tokens" }
+                    input {
+                        r#type: "number",
+                        min: "16",
+                        max: "8192",
+                        class: "w-28 px-2 py-1 bg-slate-700 border border-slate-600 rounded text-white text-right",
+                        value: "{current.generation.max_output_tokens}",
+                        onchange: move |e| {
+                            if let Ok(tokens) = e.value().parse::<u32>() {
+                                settings.write().generation.max_output_tokens = tokens.clamp(16, 8192);
+                            }
+                        },
+                    }
+                }
+                label {
+                    class: "flex items-center justify-between text-sm",
+                    span { class: "text-slate-400", "Stop when the output repeats itself" }
+                    input {
+                        r#type: "checkbox",
+                        checked: current.generation.stop_on_repetition,
+                        onchange: move |e| {
+                            settings.write().generation.stop_on_repetition = e.value().parse::<bool>().unwrap_or(false);
+                        },
+                    }
+                }
+                div {
+                    class: "space-y-1",
+                    span { class: "block text-sm text-slate-400", "Stop sequences" }
+                    textarea {
+                        class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded text-sm text-white font-mono placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                        rows: "3",
+                        placeholder: "One per line, e.g. <|im_end|>",
+                        value: current.generation.stop_sequences.join("\n"),
+                        onchange: move |e| {
+                            settings.write().generation.stop_sequences = e.value()
+                                .lines()
+                                .filter(|line| !line.is_empty())
+                                .map(str::to_string)
+                                .collect();
+                        },
+                    }
+                }
+            }
+
+            // Session titles
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                h3 {
+                    class: "text-sm font-medium text-slate-300",
+                    "Chat Titles"
+                }
+                p {
+                    class: "text-xs text-slate-500",
+                    "New chats are named after the keywords of their first message. The LLM can replace that with a title it writes after a few turns."
+                }
+                div {
+                    class: "grid grid-cols-2 gap-2",
+                    for method in TitleMethod::all().iter().copied() {
+                        button {
+                            key: "{method.label()}",
+                            class: if current.session_titles.method == method {
+                                "px-4 py-2 rounded-lg bg-blue-600 text-white text-sm"
+                            } else {
+                                "px-4 py-2 rounded-lg bg-slate-700 text-slate-300 text-sm hover:bg-slate-600 transition-colors"
+                            },
+                            onclick: move |_| settings.write().session_titles.method = method,
+                            "{method.label()}"
+                        }
+                    }
+                }
+                label {
+                    class: "flex items-center justify-between text-sm",
+                    span { class: "text-slate-400", "Keywords per title" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        max: "10",
+                        class: "w-28 px-2 py-1 bg-slate-700 border border-slate-600 rounded text-white text-right",
+                        value: "{current.session_titles.max_keywords}",
+                        onchange: move |e| {
+                            if let Ok(count) = e.value().parse::<usize>() {
+                                settings.write().session_titles.max_keywords = count.clamp(1, 10);
+                            }
+                        },
+                    }
+                }
+                label {
+                    class: "flex items-center justify-between text-sm",
+                    span { class: "text-slate-400", "Max title length (characters)" }
+                    input {
+                        r#type: "number",
+                        min: "10",
+                        max: "100",
+                        class: "w-28 px-2 py-1 bg-slate-700 border border-slate-600 rounded text-white text-right",
+                        value: "{current.session_titles.max_length}",
+                        onchange: move |e| {
+                            if let Ok(length) = e.value().parse::<usize>() {
+                                settings.write().session_titles.max_length = length.clamp(10, 100);
+                            }
+                        },
+                    }
+                }
+                div {
+                    class: "space-y-1",
+                    span { class: "block text-sm text-slate-400", "Extra stop words" }
+                    textarea {
+                        class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                        rows: "2",
+                        placeholder: "Words to leave out of titles, separated by commas, e.g. hey, 那个",
+                        value: current.session_titles.custom_stop_words.join(", "),
+                        onchange: move |e| {
+                            settings.write().session_titles.custom_stop_words = e.value()
+                                .split([',', '，', '\n'])
+                                .map(str::trim)
+                                .filter(|word| !word.is_empty())
+                                .map(str::to_string)
+                                .collect();
+                        },
+                    }
+                }
+            }
+
             // Info box
             div {
                 class: "bg-blue-900/30 border border-blue-800 rounded-lg p-4",
@@ -654,6 +902,7 @@ fn ModelsSettings(settings: Signal<AppSettings>) -> Element {
 /// Appearance settings section
 #[component]
 fn AppearanceSettings(settings: Signal<AppSettings>) -> Element {
+    let mut settings = settings;
     let current = settings.read().clone();
 
     rsx! {
@@ -686,7 +935,11 @@ fn AppearanceSettings(settings: Signal<AppSettings>) -> Element {
                 class: "bg-slate-800 rounded-lg p-4 space-y-3",
                 label {
                     class: "block text-sm font-medium text-slate-300 mb-2",
-                    "Response Font Size"
+                    "Font Size"
+                }
+                p {
+                    class: "text-xs text-slate-500",
+                    "Scales all text in the app. Ctrl/Cmd and + or − change it, Ctrl/Cmd and 0 resets it."
                 }
                 div {
                     class: "space-y-2",
@@ -696,6 +949,75 @@ fn AppearanceSettings(settings: Signal<AppSettings>) -> Element {
                     { render_font_option(settings.clone(), FontSize::ExtraLarge, "Extra Large", "text-xl", current.font_size == FontSize::ExtraLarge) }
                 }
             }
+
+            // Auto-scroll
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                h3 {
+                    class: "text-sm font-medium text-slate-300 mb-1",
+                    "Auto-scroll"
+                }
+                p {
+                    class: "text-xs text-slate-500",
+                    "Whether the chat scrolls down as an answer streams in. Scroll up to stop following; a button jumps back to the latest text."
+                }
+                div {
+                    class: "grid grid-cols-3 gap-2",
+                    for mode in AutoScroll::all().iter().copied() {
+                        button {
+                            key: "{mode.label()}",
+                            class: if current.auto_scroll == mode {
+                                "px-4 py-2 rounded-lg bg-blue-600 text-white text-sm"
+                            } else {
+                                "px-4 py-2 rounded-lg bg-slate-700 text-slate-300 text-sm hover:bg-slate-600 transition-colors"
+                            },
+                            onclick: move |_| settings.write().auto_scroll = mode,
+                            "{mode.label()}"
+                        }
+                    }
+                }
+            }
+
+            // Math
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                h3 {
+                    class: "text-sm font-medium text-slate-300 mb-1",
+                    "Math"
+                }
+                label {
+                    class: "flex items-center justify-between text-sm",
+                    span { class: "text-slate-400", "Render LaTeX math in messages as formatted equations" }
+                    input {
+                        r#type: "checkbox",
+                        checked: current.render_math,
+                        onchange: move |e| {
+                            settings.write().render_math = e.value().parse::<bool>().unwrap_or(false);
+                        },
+                    }
+                }
+            }
+
+            if cfg!(feature = "desktop") {
+                div {
+                    class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                    h3 {
+                        class: "text-sm font-medium text-slate-300 mb-1",
+                        "Notifications"
+                    }
+                    label {
+                        class: "flex items-center justify-between text-sm",
+                        span { class: "text-slate-400", "Show desktop notifications when jobs finish or fail" }
+                        input {
+                            r#type: "checkbox",
+                            checked: current.desktop_notifications,
+                            onchange: move |e| {
+                                settings.write().desktop_notifications = e.value().parse::<bool>().unwrap_or(false);
+                            },
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -750,9 +1072,19 @@ fn render_font_option(mut settings: Signal<AppSettings>, size: FontSize, label:
     }
 }
 
+/// Interface languages with their native names
+const UI_LANGUAGES: &[(ResponseLanguage, &str)] = &[
+    (ResponseLanguage::English, "English"),
+    (ResponseLanguage::Chinese, "中文"),
+    (ResponseLanguage::Thai, "ไทย"),
+    (ResponseLanguage::Spanish, "Español"),
+    (ResponseLanguage::French, "Français"),
+    (ResponseLanguage::German, "Deutsch"),
+];
+
 /// Language settings section
 #[component]
-fn LanguageSettings(settings: Signal<AppSettings>) -> Element {
+fn LanguageSettings(mut settings: Signal<AppSettings>) -> Element {
     let current = settings.read().clone();
 
     rsx! {
@@ -761,18 +1093,45 @@ fn LanguageSettings(settings: Signal<AppSettings>) -> Element {
 
             h2 {
                 class: "text-lg font-semibold text-white mb-4",
-                "Language Settings"
+                {t("settings.language.title")}
+            }
+
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                label {
+                    class: "block text-sm font-medium text-slate-300 mb-2",
+                    {t("settings.language.interface")}
+                }
+                p {
+                    class: "text-xs text-slate-500 mb-3",
+                    {t("settings.language.interface_hint")}
+                }
+                div {
+                    class: "grid grid-cols-1 md:grid-cols-2 gap-2",
+                    for (lang, native) in UI_LANGUAGES.iter().cloned() {
+                        button {
+                            key: "{lang.as_str()}",
+                            class: if current.ui_language == lang {
+                                "px-4 py-3 rounded-lg bg-blue-600 text-white text-left font-medium"
+                            } else {
+                                "px-4 py-3 rounded-lg bg-slate-700 text-slate-300 hover:bg-slate-600 transition-colors text-left font-medium"
+                            },
+                            onclick: move |_| settings.write().ui_language = lang.clone(),
+                            "{native}"
+                        }
+                    }
+                }
             }
 
             div {
                 class: "bg-slate-800 rounded-lg p-4 space-y-3",
                 label {
                     class: "block text-sm font-medium text-slate-300 mb-2",
-                    "Response Language"
+                    {t("settings.language.response")}
                 }
                 p {
                     class: "text-xs text-slate-500 mb-3",
-                    "The AI will respond in this language"
+                    {t("settings.language.response_hint")}
                 }
                 div {
                     class: "grid grid-cols-1 md:grid-cols-2 gap-2",
@@ -784,6 +1143,33 @@ fn LanguageSettings(settings: Signal<AppSettings>) -> Element {
                     { render_lang_option(settings.clone(), ResponseLanguage::Thai, "ไทย", "Thai", current.language == ResponseLanguage::Thai) }
                 }
             }
+
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                label {
+                    class: "block text-sm font-medium text-slate-300 mb-2",
+                    {t("settings.language.send")}
+                }
+                p {
+                    class: "text-xs text-slate-500 mb-3",
+                    {t("settings.language.send_hint")}
+                }
+                div {
+                    class: "grid grid-cols-2 gap-2",
+                    for shortcut in SendShortcut::all().iter().copied() {
+                        button {
+                            key: "{shortcut.as_str()}",
+                            class: if current.send_shortcut == shortcut {
+                                "px-4 py-3 rounded-lg bg-blue-600 text-white"
+                            } else {
+                                "px-4 py-3 rounded-lg bg-slate-700 text-slate-300 hover:bg-slate-600 transition-colors"
+                            },
+                            onclick: move |_| settings.write().send_shortcut = shortcut,
+                            "{shortcut.as_str()}"
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -809,116 +1195,480 @@ fn render_lang_option(mut settings: Signal<AppSettings>, lang: ResponseLanguage,
     }
 }
 
-/// Context (RAG) settings section
+/// Output device, volume, speed and pitch of generated speech, used by the
+/// TTS panel, read-aloud and voice mode
 #[component]
-fn ContextSettings() -> Element {
-    let mut context_files: Signal<Vec<ContextFile>> = use_signal(Vec::new);
-    let mut show_add_form: Signal<bool> = use_signal(|| false);
-    let mut new_title: Signal<String> = use_signal(String::new);
-    let mut new_content: Signal<String> = use_signal(String::new);
-    let mut status_message: Signal<Option<(String, bool)>> = use_signal(|| None); // (message, is_error)
-    let mut is_loading: Signal<bool> = use_signal(|| false);
-
-    // Load context files on mount
-    use_effect(move || {
-        spawn(async move {
-            match list_context_files().await {
-                Ok(files) => context_files.set(files),
-                Err(e) => println!("Error loading context files: {:?}", e),
-            }
-        });
-    });
+fn AudioSettings(mut settings: Signal<AppSettings>) -> Element {
+    let devices = use_resource(|| async move { list_audio_output_devices().await.unwrap_or_default() });
+    let output = settings.read().speech_output.clone();
+    let engine = settings.read().speech_engine.clone();
+    let volume_percent = (output.volume * 100.0).round();
+    let (speed, pitch) = (output.speed, output.pitch);
 
     rsx! {
         div {
-            class: "max-w-3xl space-y-6",
+            class: "max-w-2xl space-y-6",
 
             h2 {
                 class: "text-lg font-semibold text-white mb-4",
-                "Context Documents (RAG)"
+                "Audio"
             }
 
-            // Info box
             div {
-                class: "bg-blue-900/30 border border-blue-800 rounded-lg p-4",
-                div {
-                    class: "flex items-start gap-3",
-                    svg {
-                        class: "w-5 h-5 text-blue-400 mt-0.5",
-                        fill: "none",
-                        stroke: "currentColor",
-                        stroke_width: "2",
-                        view_box: "0 0 24 24",
-                        path {
-                            stroke_linecap: "round",
-                            stroke_linejoin: "round",
-                            d: "M13 16h-1v-4h-1m1-4h.01M21 12a9 9 0 11-18 0 9 9 0 0118 0z"
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                label { class: "block text-sm font-medium text-slate-300", "Output device" }
+                select {
+                    class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white",
+                    onchange: move |e| {
+                        let name = e.value();
+                        settings.write().speech_output.device = (!name.is_empty()).then_some(name);
+                    },
+                    option { value: "", selected: output.device.is_none(), "System default" }
+                    for device in devices().unwrap_or_default() {
+                        option {
+                            value: "{device.name}",
+                            selected: output.device.as_deref() == Some(device.name.as_str()),
+                            if device.is_default { "{device.name} (default)" } else { "{device.name}" }
                         }
                     }
-                    div {
-                        class: "text-sm text-blue-200",
-                        p { "Add documents here to provide context for the AI." }
-                        p { class: "mt-1 text-blue-300/70", "Enable 'Use Context' in chat to use RAG (Retrieval-Augmented Generation)." }
-                    }
+                }
+                p {
+                    class: "text-xs text-slate-500",
+                    "Devices of the machine running iDoris. Speech is sent to the chosen device when the window allows picking an output; otherwise it plays on the default."
                 }
             }
 
-            // Status message
-            if let Some((msg, is_error)) = status_message() {
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-4",
                 div {
-                    class: if is_error {
-                        "bg-red-900/50 border border-red-800 rounded-lg p-3 flex items-center justify-between"
-                    } else {
-                        "bg-green-900/50 border border-green-800 rounded-lg p-3 flex items-center justify-between"
-                    },
-                    span {
-                        class: if is_error { "text-sm text-red-200" } else { "text-sm text-green-200" },
-                        "{msg}"
+                    label { class: "block text-sm font-medium text-slate-300 mb-2", "Voice engine" }
+                    select {
+                        class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white",
+                        onchange: move |e| settings.write().speech_engine = e.value(),
+                        option { value: "system", selected: engine == "system", "System TTS" }
+                        option { value: "kokoro", selected: engine == "kokoro", "Kokoro" }
+                        option { value: "vibevoice", selected: engine == "vibevoice", "VibeVoice" }
                     }
-                    button {
-                        class: "text-slate-400 hover:text-white",
-                        onclick: move |_| status_message.set(None),
-                        "×"
+                }
+                div {
+                    label { class: "block text-sm font-medium text-slate-300 mb-2", "Volume: {volume_percent}%" }
+                    input {
+                        r#type: "range",
+                        class: "w-full",
+                        min: "0",
+                        max: "1",
+                        step: "0.05",
+                        value: "{output.volume}",
+                        oninput: move |e| {
+                            if let Ok(volume) = e.value().parse::<f32>() {
+                                settings.write().speech_output.volume = volume;
+                            }
+                        },
+                    }
+                }
+                div {
+                    label { class: "block text-sm font-medium text-slate-300 mb-2", "Speed: {speed:.2}×" }
+                    input {
+                        r#type: "range",
+                        class: "w-full",
+                        min: "0.5",
+                        max: "2",
+                        step: "0.05",
+                        value: "{output.speed}",
+                        oninput: move |e| {
+                            if let Ok(speed) = e.value().parse::<f32>() {
+                                settings.write().speech_output.speed = speed;
+                            }
+                        },
+                    }
+                }
+                div {
+                    label { class: "block text-sm font-medium text-slate-300 mb-2", "Pitch: {pitch:.2}×" }
+                    input {
+                        r#type: "range",
+                        class: "w-full",
+                        min: PITCH_RANGE.0.to_string(),
+                        max: PITCH_RANGE.1.to_string(),
+                        step: "0.05",
+                        value: "{output.pitch}",
+                        oninput: move |e| {
+                            if let Ok(pitch) = e.value().parse::<f32>() {
+                                settings.write().speech_output.pitch = pitch;
+                            }
+                        },
+                    }
+                    p {
+                        class: "text-xs text-slate-500 mt-1",
+                        "Applied when speech is generated, using ffmpeg."
                     }
                 }
             }
 
-            // Add document section
-            div {
-                class: "bg-slate-800 rounded-lg p-4",
+            PronunciationSettings { settings: settings }
+        }
+    }
+}
+
+/// The pronunciation lexicon, applied to all text before synthesis
+#[component]
+fn PronunciationSettings(mut settings: Signal<AppSettings>) -> Element {
+    let mut term = use_signal(String::new);
+    let mut say = use_signal(String::new);
+    let ime = use_ime_state();
+    let entries = settings.read().pronunciations.clone();
+
+    let mut add = move || {
+        let (new_term, new_say) = (term().trim().to_string(), say().trim().to_string());
+        if new_term.is_empty() || new_say.is_empty() {
+            return;
+        }
+        let mut current = settings.write();
+        current.pronunciations.retain(|e| !e.term.eq_ignore_ascii_case(&new_term));
+        current.pronunciations.push(PronunciationEntry { term: new_term, say: new_say, case_sensitive: false });
+        term.set(String::new());
+        say.set(String::new());
+    };
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 { class: "text-sm font-medium text-slate-300", "Pronunciation" }
+            p {
+                class: "text-xs text-slate-500",
+                "Say terms the engine gets wrong the way you spell them here, e.g. nginx as \"engine x\". Latin terms match whole words; they also match inside Chinese text."
+            }
+            for (i, entry) in entries.into_iter().enumerate() {
                 div {
-                    class: "flex items-center justify-between mb-4",
-                    h3 {
-                        class: "text-sm font-medium text-slate-300",
-                        "Documents"
+                    key: "{entry.term}",
+                    class: "flex items-center gap-2 text-sm",
+                    span { class: "flex-1 text-white truncate", "{entry.term}" }
+                    span { class: "text-slate-500", "→" }
+                    span { class: "flex-1 text-slate-300 truncate", "{entry.say}" }
+                    label {
+                        class: "flex items-center gap-1 text-xs text-slate-400",
+                        input {
+                            r#type: "checkbox",
+                            checked: entry.case_sensitive,
+                            onchange: move |e| {
+                                if let Some(entry) = settings.write().pronunciations.get_mut(i) {
+                                    entry.case_sensitive = e.value().parse::<bool>().unwrap_or(false);
+                                }
+                            },
+                        }
+                        "Match case"
                     }
                     button {
-                        class: if show_add_form() {
-                            "px-3 py-1.5 bg-slate-600 hover:bg-slate-500 rounded-lg text-sm text-white transition-colors"
-                        } else {
-                            "px-3 py-1.5 bg-blue-600 hover:bg-blue-700 rounded-lg text-sm text-white transition-colors"
+                        class: "px-2 py-0.5 rounded text-xs text-slate-400 hover:text-red-400",
+                        onclick: move |_| {
+                            let mut current = settings.write();
+                            if i < current.pronunciations.len() {
+                                current.pronunciations.remove(i);
+                            }
                         },
-                        onclick: move |_| show_add_form.set(!show_add_form()),
-                        if show_add_form() { "Cancel" } else { "+ Add Document" }
+                        "Remove"
                     }
                 }
-
-                // Add form
-                if show_add_form() {
-                    div {
-                        class: "space-y-3 mb-4 p-4 bg-slate-700/50 rounded-lg",
-                        input {
-                            class: "w-full px-4 py-2 bg-slate-600 border border-slate-500 rounded-lg text-white placeholder-slate-400 focus:outline-none focus:border-blue-500",
-                            r#type: "text",
-                            placeholder: "Document title (e.g., API Documentation)",
-                            value: "{new_title}",
-                            oninput: move |e| new_title.set(e.value()),
+            }
+            div {
+                class: "flex gap-2",
+                input {
+                    class: "flex-1 px-3 py-1.5 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500",
+                    placeholder: "Term, e.g. kubectl",
+                    value: "{term}",
+                    oninput: move |e| term.set(e.value()),
+                }
+                input {
+                    class: "flex-1 px-3 py-1.5 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500",
+                    placeholder: "Say, e.g. cube control",
+                    value: "{say}",
+                    oninput: move |e| say.set(e.value()),
+                    oncompositionstart: move |_| ime.start(),
+                    oncompositionend: move |_| ime.end(),
+                    onkeydown: move |e| {
+                        if ime.is_enter(&e) {
+                            add();
                         }
-                        textarea {
-                            class: "w-full px-4 py-2 bg-slate-600 border border-slate-500 rounded-lg text-white placeholder-slate-400 focus:outline-none focus:border-blue-500 resize-none",
-                            rows: "8",
-                            placeholder: "Paste your document content here...\n\nThis can be:\n- Technical documentation\n- Knowledge base articles\n- Code references\n- Any text you want the AI to reference",
-                            value: "{new_content}",
+                    },
+                }
+                button {
+                    class: "px-3 py-1.5 rounded-lg bg-blue-600 hover:bg-blue-500 text-sm text-white",
+                    onclick: move |_| add(),
+                    "Add"
+                }
+            }
+            p {
+                class: "text-xs text-slate-500",
+                "Text sent to TTS can also use markup: <break time=\"500ms\"/> for a pause, <emphasis>word</emphasis> to stress a word, and <sub alias=\"sequel\">SQL</sub> to say something else."
+            }
+        }
+    }
+}
+
+/// State of one file dropped onto the Context tab
+#[derive(Clone, PartialEq)]
+enum UploadStatus {
+    Uploading,
+    Added { indexed: bool },
+    Failed(String),
+}
+
+/// Drop target and file picker that add files to the knowledge base, with
+/// the status of each file
+#[component]
+fn ContextDropZone(on_uploaded: EventHandler<()>) -> Element {
+    let mut uploads: Signal<Vec<(String, UploadStatus)>> = use_signal(Vec::new);
+    // Enter and leave fire for every child the drag crosses
+    let mut drag_depth: Signal<u32> = use_signal(|| 0);
+
+    let mut upload = move |file: dioxus::html::FileData| {
+        let name = file.name();
+        let index = uploads.read().len();
+        uploads.write().push((name.clone(), UploadStatus::Uploading));
+        spawn(async move {
+            let status = if file.size() > MAX_ATTACHMENT_BYTES as u64 {
+                UploadStatus::Failed(format!("Files up to {} MB can be added", MAX_ATTACHMENT_BYTES / (1024 * 1024)))
+            } else {
+                let result = match file.read_bytes().await {
+                    Ok(bytes) => upload_context_file(name.clone(), bytes.to_vec()).await.map_err(error_message),
+                    Err(e) => Err(format!("Could not read {}: {}", name, e)),
+                };
+                match result {
+                    Ok(document) => {
+                        on_uploaded.call(());
+                        UploadStatus::Added { indexed: document.indexed }
+                    }
+                    Err(e) => UploadStatus::Failed(e),
+                }
+            };
+            if let Some(entry) = uploads.write().get_mut(index) {
+                entry.1 = status;
+            }
+        });
+    };
+
+    let is_uploading = uploads.read().iter().any(|(_, s)| *s == UploadStatus::Uploading);
+
+    rsx! {
+        div {
+            class: if drag_depth() > 0 {
+                "rounded-lg border-2 border-dashed border-blue-500 bg-blue-900/20 p-4 transition-colors"
+            } else {
+                "rounded-lg border-2 border-dashed border-slate-600 bg-slate-800/50 p-4 transition-colors"
+            },
+            ondragenter: move |_| drag_depth += 1,
+            ondragleave: move |_| drag_depth.set(drag_depth().saturating_sub(1)),
+            ondragover: move |e: DragEvent| e.prevent_default(),
+            ondrop: move |e: DragEvent| {
+                e.prevent_default();
+                drag_depth.set(0);
+                for file in e.files() {
+                    upload(file);
+                }
+            },
+
+            div {
+                class: "flex items-center justify-between gap-3",
+                p {
+                    class: "text-sm text-slate-400",
+                    if drag_depth() > 0 {
+                        "Drop to add to the knowledge base"
+                    } else {
+                        "Drop PDFs, Markdown, HTML or text files here to add them to the knowledge base"
+                    }
+                }
+                label {
+                    class: "px-3 py-1.5 bg-slate-700 hover:bg-slate-600 rounded-lg text-sm text-white cursor-pointer transition-colors whitespace-nowrap",
+                    "Choose files…"
+                    input {
+                        r#type: "file",
+                        class: "hidden",
+                        multiple: true,
+                        onchange: move |e: FormEvent| {
+                            for file in e.files() {
+                                upload(file);
+                            }
+                        },
+                    }
+                }
+            }
+
+            if !uploads.read().is_empty() {
+                div {
+                    class: "mt-3 space-y-1",
+                    for (index, (name, status)) in uploads().into_iter().enumerate() {
+                        div {
+                            key: "{index}",
+                            class: "flex items-center gap-3 text-sm",
+                            span { class: "flex-1 text-slate-200 truncate", "{name}" }
+                            match status {
+                                UploadStatus::Uploading => rsx! {
+                                    span { class: "w-24 h-1.5 rounded-full bg-slate-700 overflow-hidden",
+                                        span { class: "block h-full w-1/2 bg-blue-500 animate-pulse" }
+                                    }
+                                },
+                                UploadStatus::Added { indexed: true } => rsx! {
+                                    span { class: "text-xs text-green-400", "Added and indexed" }
+                                },
+                                UploadStatus::Added { indexed: false } => rsx! {
+                                    span { class: "text-xs text-yellow-400", "Added, not indexed yet" }
+                                },
+                                UploadStatus::Failed(error) => rsx! {
+                                    span { class: "text-xs text-red-400 truncate max-w-xs", title: "{error}", "{error}" }
+                                },
+                            }
+                        }
+                    }
+                    if !is_uploading {
+                        button {
+                            class: "text-xs text-slate-500 hover:text-slate-300",
+                            onclick: move |_| uploads.write().clear(),
+                            "Clear list"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Context (RAG) settings section
+#[component]
+fn ContextSettings(settings: Signal<AppSettings>) -> Element {
+    let mut context_files: Signal<Vec<ContextFile>> = use_signal(Vec::new);
+    let mut show_add_form: Signal<bool> = use_signal(|| false);
+    let mut new_title: Signal<String> = use_signal(String::new);
+    let mut new_content: Signal<String> = use_signal(String::new);
+    let mut status_message: Signal<Option<(String, bool)>> = use_signal(|| None); // (message, is_error)
+    let mut is_loading: Signal<bool> = use_signal(|| false);
+    let mut indexing_jobs: Signal<Vec<IndexingJob>> = use_signal(Vec::new);
+    let mut viewing: Signal<Option<String>> = use_signal(|| None);
+
+    // Refreshes indexing progress until no job is running
+    let poll_indexing = move || {
+        spawn(async move {
+            while let Ok(jobs) = get_indexing_jobs().await {
+                let running = jobs.iter().any(|j| j.is_running());
+                indexing_jobs.set(jobs);
+                if !running {
+                    break;
+                }
+                sleep_ms(INDEXING_POLL_INTERVAL_MS).await;
+            }
+        });
+    };
+
+    // Load context files on mount
+    use_effect(move || {
+        spawn(async move {
+            match list_context_files().await {
+                Ok(files) => context_files.set(files),
+                Err(e) => tracing::error!("Error loading context files: {:?}", e),
+            }
+        });
+        poll_indexing();
+    });
+
+    rsx! {
+        div {
+            class: "max-w-3xl space-y-6",
+
+            h2 {
+                class: "text-lg font-semibold text-white mb-4",
+                "Context Documents (RAG)"
+            }
+
+            // Info box
+            div {
+                class: "bg-blue-900/30 border border-blue-800 rounded-lg p-4",
+                div {
+                    class: "flex items-start gap-3",
+                    svg {
+                        class: "w-5 h-5 text-blue-400 mt-0.5",
+                        fill: "none",
+                        stroke: "currentColor",
+                        stroke_width: "2",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M13 16h-1v-4h-1m1-4h.01M21 12a9 9 0 11-18 0 9 9 0 0118 0z"
+                        }
+                    }
+                    div {
+                        class: "text-sm text-blue-200",
+                        p { "Add documents here to provide context for the AI." }
+                        p { class: "mt-1 text-blue-300/70", "Enable 'Use Context' in chat to use RAG (Retrieval-Augmented Generation)." }
+                    }
+                }
+            }
+
+            // Status message
+            if let Some((msg, is_error)) = status_message() {
+                div {
+                    class: if is_error {
+                        "bg-red-900/50 border border-red-800 rounded-lg p-3 flex items-center justify-between"
+                    } else {
+                        "bg-green-900/50 border border-green-800 rounded-lg p-3 flex items-center justify-between"
+                    },
+                    span {
+                        class: if is_error { "text-sm text-red-200" } else { "text-sm text-green-200" },
+                        "{msg}"
+                    }
+                    button {
+                        class: "text-slate-400 hover:text-white",
+                        onclick: move |_| status_message.set(None),
+                        "×"
+                    }
+                }
+            }
+
+            ContextDropZone {
+                on_uploaded: move |_| {
+                    spawn(async move {
+                        if let Ok(files) = list_context_files().await {
+                            context_files.set(files);
+                        }
+                    });
+                    poll_indexing();
+                },
+            }
+
+            // Add document section
+            div {
+                class: "bg-slate-800 rounded-lg p-4",
+                div {
+                    class: "flex items-center justify-between mb-4",
+                    h3 {
+                        class: "text-sm font-medium text-slate-300",
+                        "Documents"
+                    }
+                    button {
+                        class: if show_add_form() {
+                            "px-3 py-1.5 bg-slate-600 hover:bg-slate-500 rounded-lg text-sm text-white transition-colors"
+                        } else {
+                            "px-3 py-1.5 bg-blue-600 hover:bg-blue-700 rounded-lg text-sm text-white transition-colors"
+                        },
+                        onclick: move |_| show_add_form.set(!show_add_form()),
+                        if show_add_form() { "Cancel" } else { "+ Add Document" }
+                    }
+                }
+
+                // Add form
+                if show_add_form() {
+                    div {
+                        class: "space-y-3 mb-4 p-4 bg-slate-700/50 rounded-lg",
+                        input {
+                            class: "w-full px-4 py-2 bg-slate-600 border border-slate-500 rounded-lg text-white placeholder-slate-400 focus:outline-none focus:border-blue-500",
+                            r#type: "text",
+                            placeholder: "Document title (e.g., API Documentation)",
+                            value: "{new_title}",
+                            oninput: move |e| new_title.set(e.value()),
+                        }
+                        textarea {
+                            class: "w-full px-4 py-2 bg-slate-600 border border-slate-500 rounded-lg text-white placeholder-slate-400 focus:outline-none focus:border-blue-500 resize-none",
+                            rows: "8",
+                            placeholder: "Paste your document content here...\n\nThis can be:\n- Technical documentation\n- Knowledge base articles\n- Code references\n- Any text you want the AI to reference",
+                            value: "{new_content}",
                             oninput: move |e| new_content.set(e.value()),
                         }
                         button {
@@ -941,7 +1691,7 @@ fn ContextSettings() -> Element {
                                                 }
                                             }
                                             Err(e) => {
-                                                status_message.set(Some((format!("Error: {}", e), true)));
+                                                status_message.set(Some((format!("Error: {}", error_message(&e)), true)));
                                             }
                                         }
                                         is_loading.set(false);
@@ -980,7 +1730,12 @@ fn ContextSettings() -> Element {
                                 key: "{file.name}",
                                 class: "flex items-center justify-between p-3 bg-slate-700 rounded-lg",
                                 div {
-                                    class: "flex-1 min-w-0",
+                                    class: "flex-1 min-w-0 cursor-pointer",
+                                    title: "View document and chunks",
+                                    onclick: {
+                                        let filename = file.name.clone();
+                                        move |_| viewing.set(Some(filename.clone()))
+                                    },
                                     div {
                                         class: "flex items-center gap-2",
                                         svg {
@@ -1016,7 +1771,7 @@ fn ContextSettings() -> Element {
                                                     if let Ok(files) = list_context_files().await {
                                                         context_files.set(files);
                                                     }
-                                                    status_message.set(Some(("Document deleted. Click 'Reload Database' to apply.".to_string(), false)));
+                                                    status_message.set(Some(("Document moved to the trash. Click 'Reload Database' to apply.".to_string(), false)));
                                                 }
                                             });
                                         }
@@ -1040,6 +1795,14 @@ fn ContextSettings() -> Element {
                 }
             }
 
+            AudioSources {}
+
+            Codebases {}
+
+            QueryExpansionSettings { settings }
+
+            RecencySettings { settings }
+
             // Reload database button
             div {
                 class: "bg-slate-800 rounded-lg p-4",
@@ -1053,9 +1816,10 @@ fn ContextSettings() -> Element {
                             match reload_context_database().await {
                                 Ok(msg) => {
                                     status_message.set(Some((msg, false)));
+                                    poll_indexing();
                                 }
                                 Err(e) => {
-                                    status_message.set(Some((format!("Reload failed: {}", e), true)));
+                                    status_message.set(Some((format!("Reload failed: {}", error_message(&e)), true)));
                                 }
                             }
                             is_loading.set(false);
@@ -1080,97 +1844,1942 @@ fn ContextSettings() -> Element {
                     "Re-index all documents after adding or removing"
                 }
             }
+
+            if let Some(job) = indexing_jobs().first().cloned() {
+                IndexingProgress { job }
+            }
+
+            DocumentViewer { filename: viewing }
         }
     }
 }
 
-/// Database settings section
+/// Adds an audio file or podcast episode to the knowledge base as a
+/// timestamped transcript
 #[component]
-fn DatabaseSettings() -> Element {
+fn AudioSources() -> Element {
+    let mut url: Signal<String> = use_signal(String::new);
+    let mut collection: Signal<String> = use_signal(String::new);
+    let mut is_starting: Signal<bool> = use_signal(|| false);
+    let mut status: Signal<Option<(String, bool)>> = use_signal(|| None); // (message, is_error)
+    let collections = use_resource(|| async move { list_capture_collections().await.unwrap_or_default() });
+    let whisper_found = use_feature_available(Feature::Transcription);
+    let blocked = is_starting() || !whisper_found;
+
+    let selected_collection = move || Some(collection()).filter(|c| !c.is_empty());
+    let mut show_result = move |result: Result<String, ServerFnError>| {
+        match result {
+            Ok(msg) => status.set(Some((format!("{}. Progress is shown in Jobs.", msg), false))),
+            Err(e) => status.set(Some((error_message(&e), true))),
+        }
+        is_starting.set(false);
+    };
+
     rsx! {
         div {
-            class: "max-w-2xl space-y-6",
-
-            h2 {
-                class: "text-lg font-semibold text-white mb-4",
-                "Database Settings"
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 { class: "text-sm font-medium text-slate-300", "Audio & Podcasts" }
+            p {
+                class: "text-xs text-slate-500",
+                "Transcribed locally with Whisper (whisper.cpp and ffmpeg must be installed). Transcripts keep [mm:ss] marks so answers can point into the recording."
             }
-
-            // Vector Store Info
+            MissingTools { feature: Feature::Transcription }
             div {
-                class: "bg-slate-800 rounded-lg p-4 space-y-3",
-                h3 {
-                    class: "text-sm font-medium text-slate-300 mb-3",
-                    "Vector Store (RAG)"
+                class: "flex gap-2",
+                input {
+                    class: "flex-1 px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500",
+                    placeholder: "Audio or podcast feed URL",
+                    value: "{url}",
+                    oninput: move |e| url.set(e.value()),
                 }
-                div {
-                    class: "space-y-2 text-sm",
-                    div {
-                        class: "flex justify-between py-2 border-b border-slate-700",
-                        span { class: "text-slate-400", "Engine" }
-                        span { class: "text-white", "SurrealDB + SemanticChunker" }
-                    }
-                    div {
-                        class: "flex justify-between py-2 border-b border-slate-700",
-                        span { class: "text-slate-400", "Embedding Model" }
-                        span { class: "text-white", "BERT (kalosm)" }
-                    }
-                    div {
-                        class: "flex justify-between py-2 border-b border-slate-700",
-                        span { class: "text-slate-400", "Location" }
-                        span { class: "text-white font-mono text-xs", "./db/" }
-                    }
-                    div {
-                        class: "flex justify-between py-2",
-                        span { class: "text-slate-400", "Context Folder" }
-                        span { class: "text-white font-mono text-xs", "./context/" }
-                    }
+                button {
+                    class: "px-3 py-2 bg-blue-600 hover:bg-blue-700 rounded-lg text-sm text-white transition-colors disabled:opacity-50",
+                    disabled: blocked || url().trim().is_empty(),
+                    onclick: move |_| {
+                        is_starting.set(true);
+                        spawn(async move {
+                            let result = transcribe_audio_url(url().trim().to_string(), selected_collection()).await;
+                            if result.is_ok() {
+                                url.set(String::new());
+                            }
+                            show_result(result);
+                        });
+                    },
+                    "Transcribe"
                 }
             }
-
-            // Session Storage Info
             div {
-                class: "bg-slate-800 rounded-lg p-4 space-y-3",
-                h3 {
-                    class: "text-sm font-medium text-slate-300 mb-3",
-                    "Session Storage"
-                }
-                div {
-                    class: "space-y-2 text-sm",
-                    div {
-                        class: "flex justify-between py-2 border-b border-slate-700",
-                        span { class: "text-slate-400", "Engine" }
-                        span { class: "text-white", "SQLite" }
+                class: "flex items-center gap-2",
+                label {
+                    class: if blocked {
+                        "px-3 py-2 bg-slate-700 rounded-lg text-sm text-slate-500 cursor-not-allowed"
+                    } else {
+                        "px-3 py-2 bg-slate-700 hover:bg-slate-600 rounded-lg text-sm text-white cursor-pointer transition-colors"
+                    },
+                    "Upload audio file…"
+                    input {
+                        r#type: "file",
+                        class: "hidden",
+                        accept: "audio/*,video/*",
+                        disabled: blocked,
+                        onchange: move |e: FormEvent| {
+                            let Some(file) = e.files().into_iter().next() else { return };
+                            is_starting.set(true);
+                            spawn(async move {
+                                let name = file.name();
+                                let result = match file.read_bytes().await {
+                                    Ok(bytes) => transcribe_audio_file(name, bytes.to_vec(), selected_collection()).await,
+                                    Err(e) => Err(ServerFnError::new(&format!("Could not read {}: {}", name, e))),
+                                };
+                                show_result(result);
+                            });
+                        },
                     }
-                    div {
-                        class: "flex justify-between py-2",
-                        span { class: "text-slate-400", "Location" }
-                        span { class: "text-white font-mono text-xs", "./data/assistant.db" }
+                }
+                select {
+                    class: "px-2 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white",
+                    onchange: move |e| collection.set(e.value()),
+                    option { value: "", selected: collection().is_empty(), "No collection" }
+                    for name in collections().unwrap_or_default() {
+                        option { value: "{name}", selected: collection() == name, "{name}" }
                     }
                 }
             }
+            if let Some((msg, is_error)) = status() {
+                p {
+                    class: if is_error { "text-xs text-red-400" } else { "text-xs text-slate-400" },
+                    "{msg}"
+                }
+            }
+        }
+    }
+}
 
-            // Warning
-            div {
-                class: "bg-yellow-900/30 border border-yellow-800 rounded-lg p-4",
+/// Source repositories indexed for "chat with my codebase"
+#[component]
+fn Codebases() -> Element {
+    let mut codebases: Signal<Vec<Codebase>> = use_signal(Vec::new);
+    let mut path: Signal<String> = use_signal(String::new);
+    let mut name: Signal<String> = use_signal(String::new);
+    let mut busy: Signal<bool> = use_signal(|| false);
+    let mut status: Signal<Option<(String, bool)>> = use_signal(|| None); // (message, is_error)
+
+    let refresh = move || {
+        spawn(async move {
+            if let Ok(list) = list_codebases().await {
+                codebases.set(list);
+            }
+        });
+    };
+    use_effect(move || refresh());
+
+    let mut finish = move |result: Result<String, ServerFnError>| {
+        match result {
+            Ok(msg) => status.set(Some((msg, false))),
+            Err(e) => status.set(Some((error_message(&e), true))),
+        }
+        busy.set(false);
+        refresh();
+    };
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 { class: "text-sm font-medium text-slate-300", "Codebases" }
+            p {
+                class: "text-xs text-slate-500",
+                "Index a local repository to ask questions about it. Files ignored by .gitignore are skipped; answers link to the file and line."
+            }
+            div {
+                class: "flex gap-2",
+                input {
+                    class: "flex-1 px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500",
+                    placeholder: "/path/to/repository",
+                    value: "{path}",
+                    oninput: move |e| path.set(e.value()),
+                }
+                input {
+                    class: "w-32 px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500",
+                    placeholder: "Name",
+                    value: "{name}",
+                    oninput: move |e| name.set(e.value()),
+                }
+                button {
+                    class: "px-3 py-2 bg-blue-600 hover:bg-blue-700 rounded-lg text-sm text-white transition-colors disabled:opacity-50",
+                    disabled: busy() || path().trim().is_empty(),
+                    onclick: move |_| {
+                        busy.set(true);
+                        status.set(Some(("Indexing… progress is shown in Jobs.".to_string(), false)));
+                        spawn(async move {
+                            let name = Some(name().trim().to_string()).filter(|n| !n.is_empty());
+                            let result = add_codebase(path().trim().to_string(), name).await
+                                .map(|c| format!("Indexed {} ({} files, {} chunks)", c.name, c.file_count, c.chunk_count));
+                            if result.is_ok() {
+                                path.set(String::new());
+                            }
+                            finish(result);
+                        });
+                    },
+                    "Add codebase"
+                }
+            }
+            for codebase in codebases() {
+                {
+                    let id = codebase.id;
+                    let name = codebase.name.clone();
+                    rsx! {
+                        div {
+                            key: "{codebase.id}",
+                            class: "flex items-center justify-between gap-3 text-sm",
+                            div {
+                                class: "min-w-0",
+                                p { class: "text-white truncate", "💻 {codebase.name}" }
+                                p {
+                                    class: "text-xs text-slate-500 truncate",
+                                    title: "{codebase.root}",
+                                    "{codebase.file_count} files · {codebase.chunk_count} chunks · {codebase.root}"
+                                }
+                            }
+                            div {
+                                class: "flex gap-2 shrink-0",
+                                button {
+                                    class: "px-2 py-1 bg-slate-700 hover:bg-slate-600 rounded text-xs text-white disabled:opacity-50",
+                                    disabled: busy(),
+                                    onclick: move |_| {
+                                        busy.set(true);
+                                        status.set(Some((format!("Reindexing {}…", name), false)));
+                                        spawn(async move {
+                                            let result = reindex_codebase(id).await
+                                                .map(|c| format!("Reindexed {} ({} chunks)", c.name, c.chunk_count));
+                                            finish(result);
+                                        });
+                                    },
+                                    "Reindex"
+                                }
+                                button {
+                                    class: "px-2 py-1 bg-slate-700 hover:bg-red-600 rounded text-xs text-white disabled:opacity-50",
+                                    disabled: busy(),
+                                    onclick: move |_| {
+                                        busy.set(true);
+                                        spawn(async move {
+                                            let result = remove_codebase(id).await.map(|_| "Codebase removed".to_string());
+                                            finish(result);
+                                        });
+                                    },
+                                    "Remove"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some((msg, is_error)) = status() {
+                p {
+                    class: if is_error { "text-xs text-red-400" } else { "text-xs text-slate-400" },
+                    "{msg}"
+                }
+            }
+        }
+    }
+}
+
+/// Whether questions are rewritten by the model before retrieval
+#[component]
+fn QueryExpansionSettings(mut settings: Signal<AppSettings>) -> Element {
+    let current = settings.read().query_expansion;
+    let conversational = settings.read().conversational_retrieval;
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 { class: "text-sm font-medium text-slate-300", "Query expansion" }
+            p {
+                class: "text-xs text-slate-500",
+                "Before searching, let the model rewrite a short or follow-up question into a standalone search query, or write a hypothetical answer to search with. Improves recall at the cost of one short generation per message; compare the effect in the Retrieval Playground."
+            }
+            div {
+                class: "flex gap-2",
+                for mode in QueryExpansion::ALL {
+                    button {
+                        class: if current == mode {
+                            "px-3 py-1.5 rounded-lg text-sm bg-blue-600 text-white"
+                        } else {
+                            "px-3 py-1.5 rounded-lg text-sm bg-slate-700 text-slate-300 hover:text-white"
+                        },
+                        onclick: move |_| settings.write().query_expansion = mode,
+                        "{mode.display_name()}"
+                    }
+                }
+            }
+            label {
+                class: "flex items-center gap-2 text-sm text-slate-300",
+                input {
+                    r#type: "checkbox",
+                    checked: conversational,
+                    onchange: move |e| settings.write().conversational_retrieval = e.value().parse::<bool>().unwrap_or(false),
+                }
+                "Use recent messages in the search, so follow-ups like \"what about its pricing?\" find what they refer to"
+            }
+        }
+    }
+}
+
+/// Per-collection time decay, for collections where newer documents should
+/// outrank older ones
+#[component]
+fn RecencySettings(mut settings: Signal<AppSettings>) -> Element {
+    let collections = use_resource(|| async move {
+        let mut names = list_capture_collections().await.unwrap_or_default();
+        names.extend(list_codebases().await.unwrap_or_default().into_iter().map(|c| c.name));
+        names
+    });
+    let half_lives = settings.read().recency_half_life_days.clone();
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 { class: "text-sm font-medium text-slate-300", "Recency" }
+            p {
+                class: "text-xs text-slate-500",
+                "Favour recently added documents in a collection, e.g. for news. A document's score drops towards half as it ages; the half-life sets how fast."
+            }
+            for name in collections().unwrap_or_default() {
+                {
+                    let current = half_lives.get(&name).copied();
+                    let collection = name.clone();
+                    rsx! {
+                        div {
+                            key: "{name}",
+                            class: "flex items-center justify-between gap-3 text-sm",
+                            span { class: "text-white truncate", "{name}" }
+                            select {
+                                class: "px-2 py-1 bg-slate-700 border border-slate-600 rounded text-sm text-white",
+                                onchange: move |e| {
+                                    let days = e.value().parse::<u32>().ok();
+                                    let mut current = settings.write();
+                                    match days {
+                                        Some(days) => current.recency_half_life_days.insert(collection.clone(), days),
+                                        None => current.recency_half_life_days.remove(&collection),
+                                    };
+                                },
+                                option { value: "", selected: current.is_none(), "Off" }
+                                for days in RECENCY_HALF_LIVES {
+                                    option {
+                                        value: "{days}",
+                                        selected: current == Some(days),
+                                        "Half-life {days} days"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if collections().is_some_and(|c| c.is_empty()) {
+                p { class: "text-xs text-slate-500", "Create a collection by capturing into a subfolder of the context folder." }
+            }
+        }
+    }
+}
+
+/// How often indexing progress is refreshed, in milliseconds
+const INDEXING_POLL_INTERVAL_MS: u32 = 1000;
+
+/// Per-document progress of the latest indexing job
+#[component]
+fn IndexingProgress(job: IndexingJob) -> Element {
+    let percent = job.percent();
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            div {
+                class: "flex items-center justify-between",
+                h3 {
+                    class: "text-sm font-medium text-slate-300",
+                    if job.is_running() { "Indexing..." } else { "Last indexing run" }
+                }
+                span { class: "text-xs text-slate-400", "{job.summary()}" }
+            }
+            div {
+                class: "w-full h-1.5 bg-slate-700 rounded-full overflow-hidden",
+                div {
+                    class: "h-full bg-blue-500 transition-all",
+                    style: "width: {percent}%",
+                }
+            }
+            div {
+                class: "max-h-64 overflow-y-auto space-y-1",
+                for document in job.documents.iter() {
+                    div {
+                        key: "{document.name}",
+                        class: "flex items-center justify-between gap-3 text-xs",
+                        span {
+                            class: "text-slate-300 truncate",
+                            title: document.error.clone().unwrap_or_default(),
+                            "{document.name}"
+                        }
+                        span {
+                            class: match document.stage {
+                                IndexStage::Stored => "text-green-400 shrink-0",
+                                IndexStage::Failed => "text-red-400 shrink-0",
+                                IndexStage::Queued => "text-slate-500 shrink-0",
+                                _ => "text-blue-400 shrink-0",
+                            },
+                            "{document.stage.display_name()}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Database settings section
+#[component]
+fn DatabaseSettings() -> Element {
+    let mut embedding_name: Signal<String> = use_signal(|| "Loading...".to_string());
+    let mut store_stats: Signal<Option<VectorStoreStats>> = use_signal(|| None);
+    let mut locations: Signal<Vec<StorageLocationInfo>> = use_signal(Vec::new);
+
+    let mut refresh_stats = move || {
+        spawn(async move {
+            match get_vector_store_stats().await {
+                Ok(stats) => store_stats.set(Some(stats)),
+                Err(e) => tracing::error!("Error loading vector store stats: {:?}", e),
+            }
+        });
+    };
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(config) = get_embedding_config().await {
+                embedding_name.set(config.backend.display_name());
+            }
+            if let Ok(latest) = get_storage_locations().await {
+                locations.set(latest);
+            }
+        });
+        refresh_stats();
+    });
+
+    let stats = store_stats();
+    let unavailable = "n/a".to_string();
+    let path_of = move |location: StorageLocation| {
+        locations().iter().find(|l| l.location == location).map(|l| l.path.clone()).unwrap_or_default()
+    };
+
+    rsx! {
+        div {
+            class: "max-w-2xl space-y-6",
+
+            h2 {
+                class: "text-lg font-semibold text-white mb-4",
+                "Database Settings"
+            }
+
+            EmbeddingModelSettings {
+                on_changed: move |name: String| {
+                    embedding_name.set(name);
+                    refresh_stats();
+                },
+            }
+
+            // Vector Store Info
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                h3 {
+                    class: "text-sm font-medium text-slate-300 mb-3",
+                    "Vector Store (RAG)"
+                }
+                div {
+                    class: "space-y-2 text-sm",
+                    div {
+                        class: "flex justify-between py-2 border-b border-slate-700",
+                        span { class: "text-slate-400", "Engine" }
+                        span { class: "text-white", "SurrealDB + SemanticChunker" }
+                    }
+                    div {
+                        class: "flex justify-between py-2 border-b border-slate-700",
+                        span { class: "text-slate-400", "Embedding Model" }
+                        span { class: "text-white", "{embedding_name}" }
+                    }
+                    div {
+                        class: "flex justify-between py-2 border-b border-slate-700",
+                        span { class: "text-slate-400", "Documents" }
+                        span { class: "text-white", {stats.as_ref().map(|s| s.document_count.to_string()).unwrap_or(unavailable.clone())} }
+                    }
+                    div {
+                        class: "flex justify-between py-2 border-b border-slate-700",
+                        span { class: "text-slate-400", "Chunks" }
+                        span { class: "text-white", {stats.as_ref().and_then(|s| s.chunk_count).map(|c| c.to_string()).unwrap_or(unavailable.clone())} }
+                    }
+                    div {
+                        class: "flex justify-between py-2 border-b border-slate-700",
+                        span { class: "text-slate-400", "Index Size" }
+                        span { class: "text-white", {stats.as_ref().map(|s| format_size(s.index_size_bytes / (1024 * 1024))).unwrap_or(unavailable.clone())} }
+                    }
+                    div {
+                        class: "flex justify-between py-2 border-b border-slate-700",
+                        span { class: "text-slate-400", "Last Indexed" }
+                        span {
+                            class: "text-white",
+                            {stats.as_ref()
+                                .and_then(|s| s.last_indexed_at)
+                                .map(|at| at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                                .unwrap_or(unavailable.clone())}
+                        }
+                    }
+                    div {
+                        class: "flex justify-between py-2 border-b border-slate-700",
+                        span { class: "text-slate-400", "Location" }
+                        span { class: "text-white font-mono text-xs truncate ml-4", "{path_of(StorageLocation::Index)}" }
+                    }
+                    div {
+                        class: "flex justify-between py-2",
+                        span { class: "text-slate-400", "Context Folder" }
+                        span { class: "text-white font-mono text-xs truncate ml-4", "{path_of(StorageLocation::Context)}" }
+                    }
+                }
+            }
+
+            RetrievalPlayground {}
+
+            // Session Storage Info
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                h3 {
+                    class: "text-sm font-medium text-slate-300 mb-3",
+                    "Session Storage"
+                }
+                div {
+                    class: "space-y-2 text-sm",
+                    div {
+                        class: "flex justify-between py-2 border-b border-slate-700",
+                        span { class: "text-slate-400", "Engine" }
+                        span { class: "text-white", "SQLite" }
+                    }
+                    div {
+                        class: "flex justify-between py-2 border-b border-slate-700",
+                        span { class: "text-slate-400", "Location" }
+                        span { class: "text-white font-mono text-xs truncate ml-4", "{path_of(StorageLocation::Data)}/assistant.db" }
+                    }
+                    div {
+                        class: "flex justify-between items-center py-2",
+                        span { class: "text-slate-400", "Response Feedback" }
+                        button {
+                            class: "px-3 py-1 text-xs bg-slate-700 hover:bg-slate-600 text-white rounded transition-colors",
+                            title: "Rated responses with their prompts and comments, one JSON object per line",
+                            onclick: move |_| {
+                                spawn(async move {
+                                    match export_feedback().await {
+                                        Ok(jsonl) => save_text_file("feedback.jsonl", "application/jsonl", jsonl),
+                                        Err(e) => tracing::error!("Error exporting feedback: {:?}", e),
+                                    }
+                                });
+                            },
+                            "Export JSONL"
+                        }
+                    }
+                }
+            }
+
+            FineTuneExport {}
+
+            StorageLocationsSettings {}
+
+            HistoryEncryptionSettings {}
+
+            // Warning
+            div {
+                class: "bg-yellow-900/30 border border-yellow-800 rounded-lg p-4",
+                div {
+                    class: "flex items-start gap-3",
+                    svg {
+                        class: "w-5 h-5 text-yellow-400 mt-0.5",
+                        fill: "none",
+                        stroke: "currentColor",
+                        stroke_width: "2",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M12 9v2m0 4h.01m-6.938 4h13.856c1.54 0 2.502-1.667 1.732-3L13.732 4c-.77-1.333-2.694-1.333-3.464 0L3.34 16c-.77 1.333.192 3 1.732 3z"
+                        }
+                    }
+                    div {
+                        class: "text-sm text-yellow-200",
+                        p { "Database files are stored locally in the folders listed above." }
+                        p { class: "mt-1 text-yellow-300/70", "Do not delete the data or vector index folders to preserve your chat history and context." }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum EncryptionAction {
+    Enable,
+    ChangePassphrase,
+    Disable,
+}
+
+/// Chat history encryption in the Database tab
+#[component]
+fn HistoryEncryptionSettings() -> Element {
+    let mut status: Signal<EncryptionStatus> = use_signal(EncryptionStatus::default);
+    let mut current: Signal<String> = use_signal(String::new);
+    let mut passphrase: Signal<String> = use_signal(String::new);
+    let mut confirmation: Signal<String> = use_signal(String::new);
+    let mut busy: Signal<bool> = use_signal(|| false);
+    let mut result: Signal<Option<(bool, String)>> = use_signal(|| None);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(latest) = get_encryption_status().await {
+                status.set(latest);
+            }
+        });
+    });
+
+    // Runs a change, then clears the fields and reloads the status
+    let mut apply = move |action: EncryptionAction| {
+        busy.set(true);
+        result.set(None);
+        spawn(async move {
+            let outcome = match action {
+                EncryptionAction::Enable => enable_history_encryption(passphrase(), confirmation()).await
+                    .map(|count| format!("Encrypted {} chats and messages", count)),
+                EncryptionAction::ChangePassphrase => change_history_passphrase(current(), passphrase(), confirmation()).await
+                    .map(|count| format!("Passphrase changed; re-encrypted {} chats and messages", count)),
+                EncryptionAction::Disable => disable_history_encryption(current()).await
+                    .map(|count| format!("Encryption turned off; decrypted {} chats and messages", count)),
+            };
+            match outcome {
+                Ok(message) => {
+                    current.set(String::new());
+                    passphrase.set(String::new());
+                    confirmation.set(String::new());
+                    result.set(Some((true, message)));
+                }
+                Err(e) => result.set(Some((false, error_message(&e)))),
+            }
+            if let Ok(latest) = get_encryption_status().await {
+                status.set(latest);
+            }
+            busy.set(false);
+        });
+    };
+
+    let input_class = "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500";
+    let enabled = status().enabled;
+    let passphrase_placeholder = format!(
+        "{} (at least {} characters)",
+        if enabled { "New passphrase" } else { "Passphrase" },
+        MIN_PASSPHRASE_LENGTH,
+    );
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 {
+                class: "text-sm font-medium text-slate-300 mb-1",
+                "History Encryption"
+            }
+            p {
+                class: "text-xs text-slate-500",
+                if enabled {
+                    "Chat titles, summaries, tags, messages, attachments, feedback comments, memories and comparisons are encrypted with your passphrase and locked every time iDoris starts. Changing the passphrase re-encrypts everything with a new key."
+                } else {
+                    "Encrypt chat titles, summaries, tags, messages, attachments, feedback comments, memories and comparisons with a passphrase. iDoris asks for it every time it starts. Memory embeddings, the knowledge base and settings are not encrypted."
+                }
+            }
+            p {
+                class: "text-xs text-yellow-400",
+                "There is no way to recover the history if you forget the passphrase."
+            }
+
+            if enabled {
+                input {
+                    r#type: "password",
+                    class: input_class,
+                    placeholder: "Current passphrase",
+                    autocomplete: "current-password",
+                    value: "{current}",
+                    oninput: move |e| current.set(e.value()),
+                }
+            }
+            input {
+                r#type: "password",
+                class: input_class,
+                placeholder: "{passphrase_placeholder}",
+                autocomplete: "new-password",
+                value: "{passphrase}",
+                oninput: move |e| passphrase.set(e.value()),
+            }
+            input {
+                r#type: "password",
+                class: input_class,
+                placeholder: "Repeat the passphrase",
+                autocomplete: "new-password",
+                value: "{confirmation}",
+                oninput: move |e| confirmation.set(e.value()),
+            }
+
+            div {
+                class: "flex items-center justify-between gap-2",
+                span {
+                    class: match result() {
+                        Some((true, _)) => "text-xs text-green-400",
+                        _ => "text-xs text-red-400",
+                    },
+                    {result().map(|(_, msg)| msg).unwrap_or_default()}
+                }
+                div {
+                    class: "flex gap-2 shrink-0",
+                    if enabled {
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                            title: "Forget the key until the passphrase is entered again",
+                            disabled: busy(),
+                            onclick: move |_| {
+                                spawn(async move {
+                                    if lock_history().await.is_ok() {
+                                        // Reload so nothing decrypted stays on screen
+                                        let _ = document::eval("window.location.reload()");
+                                    }
+                                });
+                            },
+                            "Lock now"
+                        }
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-red-700 rounded-lg transition-colors disabled:opacity-50",
+                            disabled: busy() || current().is_empty(),
+                            onclick: move |_| apply(EncryptionAction::Disable),
+                            "Turn off"
+                        }
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors disabled:opacity-50",
+                            disabled: busy() || current().is_empty() || passphrase().is_empty(),
+                            onclick: move |_| apply(EncryptionAction::ChangePassphrase),
+                            "Change passphrase"
+                        }
+                    } else {
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors disabled:opacity-50",
+                            disabled: busy() || passphrase().is_empty(),
+                            onclick: move |_| apply(EncryptionAction::Enable),
+                            if busy() { "Encrypting..." } else { "Encrypt history" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Storage directory overrides in the Database tab
+#[component]
+fn StorageLocationsSettings() -> Element {
+    let mut locations: Signal<Vec<StorageLocationInfo>> = use_signal(Vec::new);
+    let mut overrides: Signal<StoragePaths> = use_signal(StoragePaths::default);
+    let mut status: Signal<Option<(bool, String)>> = use_signal(|| None);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(latest) = get_storage_locations().await {
+                locations.set(latest);
+            }
+            if let Ok(saved) = get_storage_overrides().await {
+                overrides.set(saved);
+            }
+        });
+    });
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 {
+                class: "text-sm font-medium text-slate-300 mb-1",
+                "Storage Locations"
+            }
+            p {
+                class: "text-xs text-slate-500",
+                "Leave a field empty to use the default. Changes apply after a restart, when existing data is moved to the new folder."
+            }
+
+            for info in locations() {
+                div {
+                    key: "{info.location.env_var()}",
+                    class: "space-y-1",
+                    div {
+                        class: "flex items-center justify-between text-xs",
+                        span { class: "text-slate-400", "{info.location.display_name()}" }
+                        span { class: "text-slate-500 font-mono", "{info.location.env_var()}" }
+                    }
+                    input {
+                        class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white font-mono placeholder-slate-500 focus:outline-none focus:border-blue-500 disabled:opacity-50",
+                        disabled: info.from_env,
+                        title: if info.from_env { "Set by the environment variable" } else { "" },
+                        placeholder: "{info.path}",
+                        value: overrides().get(info.location).unwrap_or_default().to_string(),
+                        oninput: move |e| overrides.write().set(info.location, Some(e.value())),
+                    }
+                }
+            }
+
+            div {
+                class: "flex items-center justify-between",
+                span {
+                    class: match status() {
+                        Some((true, _)) => "text-xs text-green-400",
+                        _ => "text-xs text-red-400",
+                    },
+                    {status().map(|(_, msg)| msg).unwrap_or_default()}
+                }
+                button {
+                    class: "px-3 py-1.5 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors",
+                    onclick: move |_| {
+                        spawn(async move {
+                            match set_storage_overrides(overrides()).await {
+                                Ok(()) => status.set(Some((true, "Saved. Restart iDoris to move your data.".to_string()))),
+                                Err(e) => status.set(Some((false, error_message(&e)))),
+                            }
+                        });
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+/// Export chat history as a chat fine-tuning dataset
+#[component]
+fn FineTuneExport() -> Element {
+    let mut sessions: Signal<Vec<Session>> = use_signal(Vec::new);
+    let mut profiles: Signal<Vec<Profile>> = use_signal(Vec::new);
+    let mut filter: Signal<FineTuneFilter> = use_signal(FineTuneFilter::default);
+    let mut status: Signal<Option<(bool, String)>> = use_signal(|| None);
+    let mut is_exporting = use_signal(|| false);
+
+    use_effect(move || {
+        spawn(async move {
+            match get_sessions().await {
+                Ok(loaded) => sessions.set(loaded),
+                Err(e) => tracing::error!("Error loading sessions: {:?}", e),
+            }
+            match get_profiles().await {
+                Ok(loaded) => profiles.set(loaded),
+                Err(e) => tracing::error!("Error loading profiles: {:?}", e),
+            }
+        });
+    });
+
+    let current = filter();
+    let mut folders: Vec<String> = sessions().iter().filter_map(|s| s.folder.clone()).collect();
+    folders.sort();
+    folders.dedup();
+    // The session list is the active profile's; picking sessions only makes
+    // sense there
+    let other_profile = current.profile_id.is_some();
+
+    // Dates are whole days in UTC
+    let parse_date = |value: String, end_of_day: bool| {
+        chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok().and_then(|d| {
+            if end_of_day { d.and_hms_opt(23, 59, 59) } else { d.and_hms_opt(0, 0, 0) }
+        }).map(|dt| dt.and_utc())
+    };
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 {
+                class: "text-sm font-medium text-slate-300",
+                "Fine-tuning Dataset"
+            }
+            p {
+                class: "text-xs text-slate-500",
+                "Exports conversations as JSONL in chat fine-tuning format. Thumbs-down responses are always left out."
+            }
+
+            div {
+                class: "grid grid-cols-2 gap-3 text-sm",
+                label {
+                    class: "space-y-1",
+                    span { class: "block text-xs text-slate-400", "Profile" }
+                    select {
+                        class: "w-full px-2 py-1.5 bg-slate-700 border border-slate-600 rounded text-white",
+                        onchange: move |e| {
+                            let value = e.value();
+                            let mut f = filter.write();
+                            f.profile_id = (!value.is_empty()).then_some(value);
+                            f.session_ids.clear();
+                            f.folder = None;
+                        },
+                        option { value: "", "Active profile" }
+                        for profile in profiles() {
+                            option { value: "{profile.id}", "{profile.name}" }
+                        }
+                    }
+                }
+                label {
+                    class: "space-y-1",
+                    span { class: "block text-xs text-slate-400", "Folder" }
+                    select {
+                        class: "w-full px-2 py-1.5 bg-slate-700 border border-slate-600 rounded text-white",
+                        disabled: other_profile,
+                        onchange: move |e| {
+                            let value = e.value();
+                            filter.write().folder = (!value.is_empty()).then_some(value);
+                        },
+                        option { value: "", selected: current.folder.is_none(), "Any folder" }
+                        for folder in folders {
+                            option { value: "{folder}", selected: current.folder.as_ref() == Some(&folder), "{folder}" }
+                        }
+                    }
+                }
+                label {
+                    class: "space-y-1",
+                    span { class: "block text-xs text-slate-400", "From" }
+                    input {
+                        r#type: "date",
+                        class: "w-full px-2 py-1.5 bg-slate-700 border border-slate-600 rounded text-white",
+                        onchange: move |e| filter.write().from = parse_date(e.value(), false),
+                    }
+                }
+                label {
+                    class: "space-y-1",
+                    span { class: "block text-xs text-slate-400", "To" }
+                    input {
+                        r#type: "date",
+                        class: "w-full px-2 py-1.5 bg-slate-700 border border-slate-600 rounded text-white",
+                        onchange: move |e| filter.write().to = parse_date(e.value(), true),
+                    }
+                }
+            }
+
+            label {
+                class: "flex items-center gap-2 text-sm text-slate-300",
+                input {
+                    r#type: "checkbox",
+                    checked: current.liked_only,
+                    onchange: move |e| filter.write().liked_only = e.value().parse::<bool>().unwrap_or(false),
+                }
+                "Only thumbs-up responses"
+            }
+
+            textarea {
+                class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                rows: "2",
+                placeholder: "System prompt added to every example (optional)",
+                value: "{current.system_prompt.clone().unwrap_or_default()}",
+                oninput: move |e| {
+                    let value = e.value();
+                    filter.write().system_prompt = (!value.trim().is_empty()).then_some(value);
+                },
+            }
+
+            if !other_profile && !sessions().is_empty() {
+                details {
+                    class: "text-sm",
+                    summary {
+                        class: "cursor-pointer text-slate-400",
+                        if current.session_ids.is_empty() {
+                            "Sessions: all"
+                        } else {
+                            "Sessions: {current.session_ids.len()} selected"
+                        }
+                    }
+                    div {
+                        class: "max-h-48 overflow-y-auto mt-2 space-y-1",
+                        for session in sessions() {
+                            {
+                                let id = session.id;
+                                rsx! {
+                                    label {
+                                        key: "{session.id}",
+                                        class: "flex items-center gap-2 text-slate-300",
+                                        input {
+                                            r#type: "checkbox",
+                                            checked: current.session_ids.contains(&id),
+                                            onchange: move |_| {
+                                                let mut f = filter.write();
+                                                if f.session_ids.contains(&id) {
+                                                    f.session_ids.retain(|s| *s != id);
+                                                } else {
+                                                    f.session_ids.push(id);
+                                                }
+                                            },
+                                        }
+                                        span { class: "truncate", "{session.title}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div {
+                class: "flex items-center gap-3",
+                button {
+                    class: "px-3 py-1.5 text-sm bg-blue-600 hover:bg-blue-700 disabled:opacity-50 text-white rounded transition-colors",
+                    disabled: is_exporting(),
+                    onclick: move |_| {
+                        is_exporting.set(true);
+                        status.set(None);
+                        spawn(async move {
+                            match export_fine_tune_dataset(filter()).await {
+                                Ok(dataset) if dataset.examples == 0 => {
+                                    status.set(Some((false, "No conversations match these filters".to_string())));
+                                }
+                                Ok(dataset) => {
+                                    status.set(Some((true, format!(
+                                        "{} example(s) from {} session(s)", dataset.examples, dataset.sessions
+                                    ))));
+                                    save_text_file("fine-tune.jsonl", "application/jsonl", dataset.jsonl);
+                                }
+                                Err(e) => status.set(Some((false, error_message(&e)))),
+                            }
+                            is_exporting.set(false);
+                        });
+                    },
+                    if is_exporting() { "Exporting..." } else { "Export dataset" }
+                }
+                if let Some((ok, msg)) = status() {
+                    span {
+                        class: if ok { "text-xs text-green-400" } else { "text-xs text-red-400" },
+                        "{msg}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Default number of chunks returned by the retrieval playground
+const PLAYGROUND_TOP_K: usize = 8;
+
+/// Retrieval playground for debugging RAG quality: shows raw top-k chunks and scores
+#[component]
+fn RetrievalPlayground() -> Element {
+    let mut query: Signal<String> = use_signal(String::new);
+    let ime = use_ime_state();
+    let mut top_k: Signal<usize> = use_signal(|| PLAYGROUND_TOP_K);
+    let mut results: Signal<Vec<RetrievedChunk>> = use_signal(Vec::new);
+    let mut expansion: Signal<QueryExpansion> = use_signal(QueryExpansion::default);
+    // Expanded query and its hits, shown next to the plain ones
+    let mut expanded: Signal<Option<(String, Vec<RetrievedChunk>)>> = use_signal(|| None);
+    let mut is_searching: Signal<bool> = use_signal(|| false);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    let mut run_search = move || {
+        let q = query();
+        if q.trim().is_empty() || is_searching() {
+            return;
+        }
+        is_searching.set(true);
+        error.set(None);
+        spawn(async move {
+            match search_vector_store_raw(q.clone(), top_k()).await {
+                Ok(hits) => results.set(hits),
+                Err(e) => {
+                    results.set(Vec::new());
+                    error.set(Some(error_message(&e)));
+                }
+            }
+            let comparison = if expansion() == QueryExpansion::Off {
+                None
+            } else {
+                match expand_search_query(q, expansion()).await {
+                    Ok(text) => search_vector_store_raw(text.clone(), top_k()).await.ok().map(|hits| (text, hits)),
+                    Err(e) => {
+                        error.set(Some(error_message(&e)));
+                        None
+                    }
+                }
+            };
+            expanded.set(comparison);
+            is_searching.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 {
+                class: "text-sm font-medium text-slate-300",
+                "Retrieval Playground"
+            }
+            p {
+                class: "text-xs text-slate-500",
+                "Run a query against the index and inspect the raw top-k chunks. Greyed-out hits fall below the chat similarity threshold. Pick a query expansion to compare its hits with the plain query's."
+            }
+
+            div {
+                class: "flex items-center gap-2",
+                input {
+                    class: "flex-1 px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                    placeholder: "Type a query...",
+                    value: "{query}",
+                    oninput: move |e| query.set(e.value()),
+                    oncompositionstart: move |_| ime.start(),
+                    oncompositionend: move |_| ime.end(),
+                    onkeydown: move |e| {
+                        if ime.is_enter(&e) {
+                            run_search();
+                        }
+                    },
+                }
+                select {
+                    class: "px-2 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white focus:outline-none focus:border-blue-500",
+                    value: "{top_k}",
+                    onchange: move |e| top_k.set(e.value().parse().unwrap_or(PLAYGROUND_TOP_K)),
+                    for k in [3usize, 5, 8, 10, 20] {
+                        option { value: "{k}", "Top {k}" }
+                    }
+                }
+                select {
+                    class: "px-2 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white focus:outline-none focus:border-blue-500",
+                    title: "Compare with an expanded query",
+                    onchange: move |e| {
+                        let mode = QueryExpansion::ALL.into_iter().find(|m| m.display_name() == e.value());
+                        expansion.set(mode.unwrap_or_default());
+                    },
+                    for mode in QueryExpansion::ALL {
+                        option {
+                            value: "{mode.display_name()}",
+                            selected: expansion() == mode,
+                            if mode == QueryExpansion::Off { "No expansion" } else { "{mode.display_name()}" }
+                        }
+                    }
+                }
+                button {
+                    class: "px-3 py-2 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors disabled:opacity-50",
+                    disabled: is_searching() || query().trim().is_empty(),
+                    onclick: move |_| run_search(),
+                    if is_searching() { "Searching..." } else { "Search" }
+                }
+            }
+
+            if let Some(err) = error() {
+                p { class: "text-xs text-red-400", "{err}" }
+            }
+
+            match expanded() {
+                None => rsx! { PlaygroundHits { hits: results() } },
+                Some((text, hits)) => rsx! {
+                    div {
+                        class: "grid grid-cols-2 gap-3",
+                        div {
+                            class: "space-y-2",
+                            p { class: "text-xs font-medium text-slate-300", "As asked" }
+                            PlaygroundHits { hits: results() }
+                        }
+                        div {
+                            class: "space-y-2",
+                            p { class: "text-xs font-medium text-slate-300 truncate", title: "{text}", "Expanded: {text}" }
+                            PlaygroundHits { hits }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Ranked playground hits with how many pass the chat threshold
+#[component]
+fn PlaygroundHits(hits: Vec<RetrievedChunk>) -> Element {
+    let passing = hits.iter().filter(|h| h.passes_threshold).count();
+    let best = hits.iter().map(|h| h.score).fold(0.0f32, f32::max);
+
+    rsx! {
+        if !hits.is_empty() {
+            p { class: "text-xs text-slate-500", "{passing} of {hits.len()} pass · best {best:.3}" }
+        }
+        div {
+            class: "space-y-2 max-h-96 overflow-y-auto",
+            for (i, hit) in hits.into_iter().enumerate() {
+                div {
+                    key: "{i}",
+                    class: if hit.passes_threshold { "p-3 bg-slate-700/50 rounded-lg" } else { "p-3 bg-slate-700/20 rounded-lg opacity-60" },
+                    div {
+                        class: "flex items-center justify-between mb-1",
+                        span { class: "text-xs font-medium text-slate-300 truncate", "#{i + 1} {hit.title}" }
+                        span {
+                            class: if hit.passes_threshold { "text-xs font-mono text-green-400" } else { "text-xs font-mono text-slate-500" },
+                            {format!("{:.3}", hit.score)}
+                        }
+                    }
+                    p { class: "text-xs text-slate-400 whitespace-pre-wrap", "{hit.text}" }
+                }
+            }
+        }
+    }
+}
+
+/// Embedding backend selection card in the Database tab
+#[component]
+fn EmbeddingModelSettings(on_changed: EventHandler<String>) -> Element {
+    // "local:<model id>" or "remote"
+    let mut selection: Signal<String> = use_signal(|| format!("local:{}", LocalEmbeddingModel::default().id()));
+    let mut base_url: Signal<String> = use_signal(|| "http://localhost:11434/v1".to_string());
+    let mut remote_model: Signal<String> = use_signal(|| "nomic-embed-text".to_string());
+    let mut api_key_env: Signal<String> = use_signal(String::new);
+    let mut dimensions: Signal<Option<usize>> = use_signal(|| None);
+    let mut batch_size: Signal<usize> = use_signal(|| DEFAULT_EMBEDDING_BATCH_SIZE);
+    let mut is_applying: Signal<bool> = use_signal(|| false);
+    let mut status: Signal<Option<(bool, String)>> = use_signal(|| None);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(config) = get_embedding_config().await {
+                dimensions.set(config.dimensions);
+                batch_size.set(config.batch_size);
+                match config.backend {
+                    EmbeddingBackend::Local(model) => selection.set(format!("local:{}", model.id())),
+                    EmbeddingBackend::OpenAiCompatible { base_url: url, model, api_key_env: key } => {
+                        selection.set("remote".to_string());
+                        base_url.set(url);
+                        remote_model.set(model);
+                        api_key_env.set(key.unwrap_or_default());
+                    }
+                }
+            }
+        });
+    });
+
+    let is_remote = selection() == "remote";
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 {
+                class: "text-sm font-medium text-slate-300 mb-1",
+                "Embedding Model"
+            }
+            p {
+                class: "text-xs text-slate-500",
+                "Changing the model re-embeds every document in the context folder."
+            }
+
+            select {
+                class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white focus:outline-none focus:border-blue-500",
+                value: "{selection}",
+                onchange: move |e| selection.set(e.value()),
+                for model in LocalEmbeddingModel::all() {
+                    option {
+                        value: "local:{model.id()}",
+                        "{model.display_name()} - {model.dimensions()}d"
+                    }
+                }
+                option { value: "remote", "OpenAI-compatible endpoint" }
+            }
+
+            if is_remote {
+                div {
+                    class: "space-y-2",
+                    input {
+                        class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                        placeholder: "Base URL, e.g. http://localhost:11434/v1",
+                        value: "{base_url}",
+                        oninput: move |e| base_url.set(e.value()),
+                    }
+                    input {
+                        class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                        placeholder: "Model name",
+                        value: "{remote_model}",
+                        oninput: move |e| remote_model.set(e.value()),
+                    }
+                    input {
+                        class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                        placeholder: "API key environment variable (optional)",
+                        value: "{api_key_env}",
+                        oninput: move |e| api_key_env.set(e.value()),
+                    }
+                }
+            }
+
+            div {
+                class: "flex items-center justify-between text-sm",
+                span { class: "text-slate-400", "Batch size" }
+                input {
+                    r#type: "number",
+                    min: "1",
+                    max: "512",
+                    class: "w-28 px-2 py-1 bg-slate-700 border border-slate-600 rounded text-white text-right",
+                    value: "{batch_size}",
+                    onchange: move |e| {
+                        if let Ok(size) = e.value().parse::<usize>() {
+                            let size = size.clamp(1, 512);
+                            batch_size.set(size);
+                            spawn(async move {
+                                if let Err(e) = set_embedding_batch_size(size).await {
+                                    status.set(Some((false, error_message(&e))));
+                                }
+                            });
+                        }
+                    },
+                }
+            }
+
+            div {
+                class: "flex items-center justify-between",
+                span {
+                    class: "text-xs text-slate-500",
+                    if let Some(d) = dimensions() { "Current index: {d} dimensions" }
+                }
+                button {
+                    class: "px-3 py-1.5 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors disabled:opacity-50",
+                    disabled: is_applying(),
+                    onclick: move |_| {
+                        let backend = if selection() == "remote" {
+                            let key = api_key_env().trim().to_string();
+                            EmbeddingBackend::OpenAiCompatible {
+                                base_url: base_url().trim().to_string(),
+                                model: remote_model().trim().to_string(),
+                                api_key_env: (!key.is_empty()).then_some(key),
+                            }
+                        } else {
+                            let id = selection().trim_start_matches("local:").to_string();
+                            EmbeddingBackend::Local(LocalEmbeddingModel::from_id(&id).unwrap_or_default())
+                        };
+                        let name = backend.display_name();
+                        is_applying.set(true);
+                        status.set(None);
+                        spawn(async move {
+                            match set_embedding_backend(backend).await {
+                                Ok(msg) => {
+                                    status.set(Some((true, msg)));
+                                    on_changed.call(name);
+                                    if let Ok(config) = get_embedding_config().await {
+                                        dimensions.set(config.dimensions);
+                                    }
+                                }
+                                Err(e) => status.set(Some((false, error_message(&e)))),
+                            }
+                            is_applying.set(false);
+                        });
+                    },
+                    if is_applying() { "Re-embedding..." } else { "Apply" }
+                }
+            }
+
+            if let Some((ok, msg)) = status() {
+                p {
+                    class: if ok { "text-xs text-green-400" } else { "text-xs text-red-400" },
+                    "{msg}"
+                }
+            }
+        }
+    }
+}
+
+/// System resource monitor section
+#[component]
+fn SystemSettings() -> Element {
+    let mut stats: Signal<Option<SystemStats>> = use_signal(|| None);
+    let mut is_refreshing: Signal<bool> = use_signal(|| false);
+
+    let mut refresh = move || {
+        is_refreshing.set(true);
+        spawn(async move {
+            match get_system_stats().await {
+                Ok(latest) => stats.set(Some(latest)),
+                Err(e) => tracing::error!("Error loading system stats: {:?}", e),
+            }
+            is_refreshing.set(false);
+        });
+    };
+
+    use_effect(move || refresh());
+
+    let current = stats();
+    let unavailable = "n/a".to_string();
+
+    rsx! {
+        div {
+            class: "max-w-2xl space-y-6",
+
+            div {
+                class: "flex items-center justify-between mb-4",
+                h2 {
+                    class: "text-lg font-semibold text-white",
+                    "System"
+                }
+                button {
+                    class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors disabled:opacity-50",
+                    disabled: is_refreshing(),
+                    onclick: move |_| refresh(),
+                    if is_refreshing() { "Refreshing..." } else { "Refresh" }
+                }
+            }
+
+            LanAccessSettings {}
+
+            ProxySettings {}
+
+            OptionalTools {}
+
+            if let Some(s) = current {
+                // Memory
+                div {
+                    class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                    h3 {
+                        class: "text-sm font-medium text-slate-300 mb-3",
+                        "Memory"
+                    }
+                    div {
+                        class: "space-y-2 text-sm",
+                        div {
+                            class: "flex justify-between py-2 border-b border-slate-700",
+                            span { class: "text-slate-400", "Process RAM" }
+                            span { class: "text-white", {s.process_memory_mb.map(format_size).unwrap_or(unavailable.clone())} }
+                        }
+                        div {
+                            class: "flex justify-between py-2 border-b border-slate-700",
+                            span { class: "text-slate-400", "System RAM" }
+                            span { class: "text-white", {s.total_memory_mb.map(format_size).unwrap_or(unavailable.clone())} }
+                        }
+                        div {
+                            class: "flex justify-between py-2 border-b border-slate-700",
+                            span { class: "text-slate-400", "GPU" }
+                            span { class: "text-white", {s.gpu_name.clone().unwrap_or(unavailable.clone())} }
+                        }
+                        div {
+                            class: "flex justify-between py-2",
+                            span { class: "text-slate-400", "GPU Memory" }
+                            span {
+                                class: "text-white",
+                                match (s.gpu_memory_used_mb, s.gpu_memory_total_mb) {
+                                    (Some(used), Some(total)) => format!("{} / {}", format_size(used), format_size(total)),
+                                    (Some(used), None) => format_size(used),
+                                    _ => unavailable.clone(),
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Disk
+                div {
+                    class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                    h3 {
+                        class: "text-sm font-medium text-slate-300 mb-3",
+                        "Disk Usage"
+                    }
+                    div {
+                        class: "space-y-2 text-sm",
+                        div {
+                            class: "flex justify-between py-2 border-b border-slate-700",
+                            span { class: "text-slate-400", "Data Directory" }
+                            span { class: "text-white", {format_size(s.data_dir_size_mb)} }
+                        }
+                        div {
+                            class: "flex justify-between py-2",
+                            span { class: "text-slate-400", "HuggingFace Cache" }
+                            span { class: "text-white", {format_size(s.hf_cache_size_mb)} }
+                        }
+                    }
+                }
+
+                // LLM throughput
+                div {
+                    class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                    h3 {
+                        class: "text-sm font-medium text-slate-300 mb-3",
+                        "LLM Throughput"
+                    }
+                    div {
+                        class: "space-y-2 text-sm",
+                        div {
+                            class: "flex justify-between py-2 border-b border-slate-700",
+                            span { class: "text-slate-400", "Last Response" }
+                            span { class: "text-white", {s.last_tokens_per_sec.map(|t| format!("{:.1} tok/s", t)).unwrap_or(unavailable.clone())} }
+                        }
+                        div {
+                            class: "flex justify-between py-2 border-b border-slate-700",
+                            span { class: "text-slate-400", "Average" }
+                            span { class: "text-white", {s.avg_tokens_per_sec.map(|t| format!("{:.1} tok/s", t)).unwrap_or(unavailable.clone())} }
+                        }
+                        div {
+                            class: "flex justify-between py-2",
+                            span { class: "text-slate-400", "Tokens Generated" }
+                            span { class: "text-white", "{s.total_tokens_generated}" }
+                        }
+                    }
+                }
+            } else {
+                p { class: "text-sm text-slate-500", "Collecting system information..." }
+            }
+        }
+    }
+}
+
+/// Access from other devices on the network, in the System tab
+#[component]
+fn LanAccessSettings() -> Element {
+    let mut info: Signal<LanAccessInfo> = use_signal(LanAccessInfo::default);
+    let mut config: Signal<LanAccessConfig> = use_signal(LanAccessConfig::default);
+    let mut status: Signal<Option<(bool, String)>> = use_signal(|| None);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(latest) = get_lan_access().await {
+                config.set(latest.config.clone());
+                info.set(latest);
+            }
+        });
+    });
+
+    let current = info();
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 {
+                class: "text-sm font-medium text-slate-300 mb-1",
+                "Network Access"
+            }
+            p {
+                class: "text-xs text-slate-500",
+                "Use iDoris from your phone or another computer on the same network. Other devices sign in with the access PIN; this computer never needs it."
+            }
+
+            label {
+                class: "flex items-center gap-2 text-sm text-slate-300",
+                input {
+                    r#type: "checkbox",
+                    checked: config().enabled,
+                    onchange: move |e| config.write().enabled = e.value().parse::<bool>().unwrap_or(false),
+                }
+                "Allow access from the local network"
+            }
+
+            input {
+                r#type: "password",
+                class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                placeholder: "Access PIN (at least {MIN_PIN_LENGTH} characters)",
+                autocomplete: "new-password",
+                value: "{config().pin}",
+                oninput: move |e| config.write().pin = e.value(),
+            }
+
+            div {
+                class: "flex items-center justify-between",
+                span {
+                    class: match status() {
+                        Some((true, _)) => "text-xs text-green-400",
+                        _ => "text-xs text-red-400",
+                    },
+                    {status().map(|(_, msg)| msg).unwrap_or_default()}
+                }
+                button {
+                    class: "px-3 py-1.5 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors",
+                    onclick: move |_| {
+                        spawn(async move {
+                            match save_lan_access(config()).await {
+                                Ok(latest) => {
+                                    status.set(Some((true, "Saved".to_string())));
+                                    info.set(latest);
+                                }
+                                Err(e) => status.set(Some((false, error_message(&e)))),
+                            }
+                        });
+                    },
+                    "Save"
+                }
+            }
+
+            if current.config.enabled && !current.listening {
+                p {
+                    class: "text-xs text-yellow-400",
+                    "Restart iDoris to start listening on the network."
+                }
+            }
+
+            if let Some(url) = current.url.clone() {
+                div {
+                    class: "flex items-center gap-4",
+                    if let Some(svg) = current.qr_svg.clone() {
+                        div {
+                            class: "w-32 h-32 shrink-0 bg-white rounded p-1 [&>svg]:w-full [&>svg]:h-full",
+                            dangerous_inner_html: "{svg}",
+                        }
+                    }
+                    div {
+                        class: "space-y-1 text-xs",
+                        p { class: "text-slate-400", "Scan with your phone or open:" }
+                        p { class: "text-white font-mono break-all", "{url}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Proxy and Hugging Face mirror, in the System tab
+#[component]
+fn ProxySettings() -> Element {
+    let mut config: Signal<ProxyConfig> = use_signal(ProxyConfig::default);
+    let mut status: Signal<Option<(bool, String)>> = use_signal(|| None);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(latest) = get_proxy_settings().await {
+                config.set(latest);
+            }
+        });
+    });
+
+    let mirror = config().mirror().unwrap_or_default();
+    let preset = HF_MIRRORS.iter()
+        .find(|(_, url)| *url == mirror || (mirror.is_empty() && *url == HF_MIRRORS[0].1))
+        .map(|(_, url)| url.to_string())
+        .unwrap_or_else(|| "custom".to_string());
+    let input_class = "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500";
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 space-y-3",
+            h3 {
+                class: "text-sm font-medium text-slate-300 mb-1",
+                "Proxy and Mirror"
+            }
+            p {
+                class: "text-xs text-slate-500",
+                "Route outbound requests through an HTTP or SOCKS proxy, and download models from a Hugging Face mirror where huggingface.co is slow or blocked. Models loaded by the inference libraries and external tools pick these up on the next start."
+            }
+
+            label {
+                class: "block text-xs text-slate-400",
+                "Proxy"
+                input {
+                    class: "mt-1 {input_class}",
+                    placeholder: "http://127.0.0.1:7890 or socks5://host:1080 (empty to connect directly)",
+                    value: "{config().proxy_url}",
+                    oninput: move |e| config.write().proxy_url = e.value(),
+                }
+            }
+            label {
+                class: "block text-xs text-slate-400",
+                "Bypass the proxy for"
+                input {
+                    class: "mt-1 {input_class}",
+                    placeholder: "localhost, 127.0.0.1, .internal.example.com",
+                    value: "{config().no_proxy}",
+                    oninput: move |e| config.write().no_proxy = e.value(),
+                }
+            }
+
+            label {
+                class: "block text-xs text-slate-400",
+                "Hugging Face mirror"
+                select {
+                    class: "mt-1 {input_class}",
+                    value: "{preset}",
+                    onchange: move |e| {
+                        let value = e.value();
+                        config.write().hf_endpoint = if value == "custom" { "https://".to_string() } else { value };
+                    },
+                    for (name, url) in HF_MIRRORS.iter() {
+                        option { value: "{url}", selected: preset == *url, "{name} ({url})" }
+                    }
+                    option { value: "custom", selected: preset == "custom", "Custom" }
+                }
+            }
+            if preset == "custom" {
+                input {
+                    class: input_class,
+                    placeholder: "https://mirror.example.com",
+                    value: "{config().hf_endpoint}",
+                    oninput: move |e| config.write().hf_endpoint = e.value(),
+                }
+            }
+
+            div {
+                class: "flex items-center justify-between",
+                span {
+                    class: match status() {
+                        Some((true, _)) => "text-xs text-green-400",
+                        _ => "text-xs text-red-400",
+                    },
+                    {status().map(|(_, msg)| msg).unwrap_or_default()}
+                }
+                button {
+                    class: "px-3 py-1.5 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors",
+                    onclick: move |_| {
+                        spawn(async move {
+                            match save_proxy_settings(config()).await {
+                                Ok(latest) => {
+                                    status.set(Some((true, "Saved".to_string())));
+                                    config.set(latest);
+                                }
+                                Err(e) => status.set(Some((false, error_message(&e)))),
+                            }
+                        });
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+/// Number of log entries requested by the log viewer
+const LOG_VIEW_LIMIT: usize = 300;
+
+/// Log viewer section
+#[component]
+fn LogsSettings() -> Element {
+    let mut entries: Signal<Vec<LogEntry>> = use_signal(Vec::new);
+    let mut min_level: Signal<String> = use_signal(|| "INFO".to_string());
+    let mut search: Signal<String> = use_signal(String::new);
+    let ime = use_ime_state();
+    let mut is_loading: Signal<bool> = use_signal(|| false);
+
+    let mut load = move || {
+        is_loading.set(true);
+        let level = min_level();
+        let query = search();
+        spawn(async move {
+            let query = (!query.trim().is_empty()).then(|| query.trim().to_string());
+            match get_recent_logs(Some(level), query, LOG_VIEW_LIMIT).await {
+                Ok(loaded) => entries.set(loaded),
+                Err(e) => tracing::error!("Error loading logs: {:?}", e),
+            }
+            is_loading.set(false);
+        });
+    };
+
+    use_effect(move || load());
+
+    rsx! {
+        div {
+            class: "max-w-4xl space-y-4",
+
+            h2 {
+                class: "text-lg font-semibold text-white mb-4",
+                "Logs"
+            }
+
+            // Filters
+            div {
+                class: "flex items-center gap-3",
+                select {
+                    class: "px-3 py-2 bg-slate-800 border border-slate-700 rounded-lg text-sm text-white focus:outline-none focus:border-blue-500",
+                    value: "{min_level}",
+                    onchange: move |e| {
+                        min_level.set(e.value());
+                        load();
+                    },
+                    option { value: "ERROR", "Errors" }
+                    option { value: "WARN", "Warnings and above" }
+                    option { value: "INFO", "Info and above" }
+                    option { value: "DEBUG", "Debug and above" }
+                }
+                input {
+                    class: "flex-1 px-3 py-2 bg-slate-800 border border-slate-700 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                    placeholder: "Filter by text or module...",
+                    value: "{search}",
+                    oninput: move |e| search.set(e.value()),
+                    oncompositionstart: move |_| ime.start(),
+                    oncompositionend: move |_| ime.end(),
+                    onkeydown: move |e| {
+                        if ime.is_enter(&e) {
+                            load();
+                        }
+                    },
+                }
+                button {
+                    class: "px-3 py-2 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors disabled:opacity-50",
+                    disabled: is_loading(),
+                    onclick: move |_| load(),
+                    if is_loading() { "Loading..." } else { "Refresh" }
+                }
+            }
+
+            p {
+                class: "text-xs text-slate-500",
+                "Newest first. Log files are kept for 7 days in ./data/logs/."
+            }
+
+            // Entries
+            div {
+                class: "bg-slate-950 border border-slate-800 rounded-lg font-mono text-xs divide-y divide-slate-800 max-h-[65vh] overflow-y-auto",
+                if entries().is_empty() {
+                    div { class: "p-4 text-slate-500", "No log entries match the current filter." }
+                }
+                for (index, entry) in entries().into_iter().enumerate() {
+                    div {
+                        key: "{index}",
+                        class: "px-3 py-1.5 flex gap-3",
+                        span { class: "text-slate-500 whitespace-nowrap", {entry.timestamp.get(11..19).unwrap_or(&entry.timestamp).to_string()} }
+                        span {
+                            class: match entry.level.as_str() {
+                                "ERROR" => "text-red-400 w-12 flex-shrink-0",
+                                "WARN" => "text-yellow-400 w-12 flex-shrink-0",
+                                "INFO" => "text-blue-400 w-12 flex-shrink-0",
+                                _ => "text-slate-500 w-12 flex-shrink-0",
+                            },
+                            "{entry.level}"
+                        }
+                        span {
+                            class: "text-slate-200 break-all",
+                            title: "{entry.target}",
+                            "{entry.message}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Number of recent requests shown in the Privacy tab
+const NETWORK_AUDIT_VIEW_LIMIT: usize = 500;
+
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        b if b < 1024 => format!("{} B", b),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+/// Outbound network requests, by domain and one by one
+#[component]
+fn NetworkAuditSettings() -> Element {
+    let mut requests: Signal<Vec<NetworkRequest>> = use_signal(Vec::new);
+    let mut feature: Signal<Option<NetworkFeature>> = use_signal(|| None);
+    let mut is_loading: Signal<bool> = use_signal(|| false);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    let mut load = move || {
+        is_loading.set(true);
+        let selected = feature();
+        spawn(async move {
+            match get_network_audit(selected, NETWORK_AUDIT_VIEW_LIMIT).await {
+                Ok(loaded) => {
+                    requests.set(loaded);
+                    error.set(None);
+                }
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+            is_loading.set(false);
+        });
+    };
+
+    use_effect(move || load());
+
+    let clear = move |_| {
+        spawn(async move {
+            match clear_network_audit().await {
+                Ok(()) => requests.set(Vec::new()),
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+        });
+    };
+
+    let summaries = summarize_by_domain(&requests());
+
+    rsx! {
+        div {
+            class: "max-w-4xl space-y-4",
+
+            h2 {
+                class: "text-lg font-semibold text-white mb-4",
+                "Privacy"
+            }
+            p {
+                class: "text-sm text-slate-400",
+                "Every request iDoris sends over the network is listed here with the feature that sent it. Only the domain and sizes are recorded, never the page, query or content. Model downloads from Hugging Face are not listed."
+            }
+
+            // Filters
+            div {
+                class: "flex items-center gap-3",
+                select {
+                    class: "flex-1 px-3 py-2 bg-slate-800 border border-slate-700 rounded-lg text-sm text-white focus:outline-none focus:border-blue-500",
+                    value: feature().map(|f| f.id()).unwrap_or(""),
+                    onchange: move |e| {
+                        feature.set(NetworkFeature::from_id(&e.value()));
+                        load();
+                    },
+                    option { value: "", "All features" }
+                    for f in NetworkFeature::all() {
+                        option { value: f.id(), {f.display_name()} }
+                    }
+                }
+                button {
+                    class: "px-3 py-2 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors disabled:opacity-50",
+                    disabled: is_loading(),
+                    onclick: move |_| load(),
+                    if is_loading() { "Loading..." } else { "Refresh" }
+                }
+                button {
+                    class: "px-3 py-2 text-sm bg-red-600/20 hover:bg-red-600/30 text-red-400 rounded-lg transition-colors disabled:opacity-50",
+                    disabled: requests().is_empty(),
+                    onclick: clear,
+                    "Clear log"
+                }
+            }
+
+            if let Some(message) = error() {
+                p { class: "text-sm text-red-400", "{message}" }
+            }
+
+            if requests().is_empty() {
                 div {
-                    class: "flex items-start gap-3",
-                    svg {
-                        class: "w-5 h-5 text-yellow-400 mt-0.5",
-                        fill: "none",
-                        stroke: "currentColor",
-                        stroke_width: "2",
-                        view_box: "0 0 24 24",
-                        path {
-                            stroke_linecap: "round",
-                            stroke_linejoin: "round",
-                            d: "M12 9v2m0 4h.01m-6.938 4h13.856c1.54 0 2.502-1.667 1.732-3L13.732 4c-.77-1.333-2.694-1.333-3.464 0L3.34 16c-.77 1.333.192 3 1.732 3z"
+                    class: "p-4 bg-slate-800 rounded-lg text-sm text-slate-500",
+                    "No outbound requests recorded."
+                }
+            } else {
+                // Per-domain summary
+                div {
+                    class: "bg-slate-800 rounded-lg divide-y divide-slate-700",
+                    for summary in summaries {
+                        div {
+                            key: "{summary.domain}",
+                            class: "px-4 py-2 flex items-center gap-3 text-sm",
+                            span { class: "flex-1 text-white font-mono truncate", "{summary.domain}" }
+                            span {
+                                class: "text-xs text-slate-400 truncate",
+                                {summary.features.iter().map(|f| f.display_name()).collect::<Vec<_>>().join(", ")}
+                            }
+                            span { class: "text-xs text-slate-400 whitespace-nowrap", "{summary.requests} requests" }
+                            span {
+                                class: "text-xs text-slate-500 whitespace-nowrap",
+                                "↑ {format_bytes(summary.bytes_sent)} ↓ {format_bytes(summary.bytes_received)}"
+                            }
                         }
                     }
-                    div {
-                        class: "text-sm text-yellow-200",
-                        p { "Database files are stored locally in the project directory." }
-                        p { class: "mt-1 text-yellow-300/70", "Do not delete the ./data/ or ./db/ folders to preserve your chat history and context." }
+                }
+
+                // Recent requests
+                div {
+                    class: "bg-slate-950 border border-slate-800 rounded-lg font-mono text-xs divide-y divide-slate-800 max-h-[50vh] overflow-y-auto",
+                    for (index, request) in requests().into_iter().enumerate() {
+                        div {
+                            key: "{index}",
+                            class: "px-3 py-1.5 flex gap-3",
+                            span {
+                                class: "text-slate-500 whitespace-nowrap",
+                                {request.timestamp.with_timezone(&chrono::Local).format("%b %d %H:%M:%S").to_string()}
+                            }
+                            span { class: "text-blue-400 w-12 flex-shrink-0", "{request.method}" }
+                            span { class: "text-slate-200 flex-1 break-all", "{request.domain}" }
+                            span { class: "text-slate-500 whitespace-nowrap", {request.feature.display_name()} }
+                            match (&request.error, request.status) {
+                                (Some(e), _) => rsx! { span { class: "text-red-400 whitespace-nowrap", title: "{e}", "failed" } },
+                                (None, Some(status)) if status >= 400 => rsx! { span { class: "text-yellow-400", "{status}" } },
+                                (None, Some(status)) => rsx! { span { class: "text-green-400", "{status}" } },
+                                (None, None) => rsx! { span { class: "text-green-400", "done" } },
+                            }
+                        }
                     }
                 }
             }
@@ -1270,3 +3879,433 @@ fn AboutSettings() -> Element {
         }
     }
 }
+
+/// Outgoing webhook configuration
+#[component]
+fn WebhookSettings() -> Element {
+    let mut webhooks: Signal<Vec<Webhook>> = use_signal(Vec::new);
+    let mut name: Signal<String> = use_signal(String::new);
+    let mut url: Signal<String> = use_signal(String::new);
+    let mut secret: Signal<String> = use_signal(String::new);
+    let mut events: Signal<Vec<WebhookEvent>> = use_signal(|| WebhookEvent::all().to_vec());
+    let mut status: Signal<Option<(bool, String)>> = use_signal(|| None);
+
+    use_effect(move || {
+        spawn(async move {
+            match get_webhooks().await {
+                Ok(loaded) => webhooks.set(loaded),
+                Err(e) => tracing::error!("Error loading webhooks: {:?}", e),
+            }
+        });
+    });
+
+    // Persists the list, reporting errors inline
+    let persist = move |updated: Vec<Webhook>| {
+        spawn(async move {
+            match save_webhooks(updated.clone()).await {
+                Ok(_) => webhooks.set(updated),
+                Err(e) => status.set(Some((false, error_message(&e)))),
+            }
+        });
+    };
+
+    let current = webhooks();
+
+    rsx! {
+        div {
+            class: "max-w-2xl space-y-6",
+
+            h2 {
+                class: "text-lg font-semibold text-white mb-4",
+                "Webhooks"
+            }
+            p {
+                class: "text-sm text-slate-400",
+                "POST a JSON payload to your own automation (n8n, Zapier-style tools, scripts) when jobs finish."
+            }
+
+            // Configured webhooks
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                h3 {
+                    class: "text-sm font-medium text-slate-300",
+                    "Configured"
+                }
+                if current.is_empty() {
+                    p { class: "text-sm text-slate-500", "No webhooks yet." }
+                }
+                for webhook in current.clone() {
+                    {
+                        let toggle_id = webhook.id;
+                        let delete_id = webhook.id;
+                        let test_target = webhook.clone();
+                        let events_label = webhook.events.iter().map(|e| e.display_name()).collect::<Vec<_>>().join(", ");
+                        rsx! {
+                            div {
+                                key: "{webhook.id}",
+                                class: "p-3 bg-slate-700/50 rounded-lg space-y-1",
+                                div {
+                                    class: "flex items-center gap-2",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: webhook.enabled,
+                                        onchange: move |_| {
+                                            let mut updated = webhooks();
+                                            if let Some(w) = updated.iter_mut().find(|w| w.id == toggle_id) {
+                                                w.enabled = !w.enabled;
+                                            }
+                                            persist(updated);
+                                        },
+                                    }
+                                    span { class: "text-sm font-medium text-white", "{webhook.name}" }
+                                    div {
+                                        class: "ml-auto flex items-center gap-2",
+                                        button {
+                                            class: "px-2 py-1 text-xs bg-slate-600 hover:bg-slate-500 rounded transition-colors",
+                                            onclick: move |_| {
+                                                let target = test_target.clone();
+                                                status.set(None);
+                                                spawn(async move {
+                                                    match test_webhook(target).await {
+                                                        Ok(msg) => status.set(Some((true, msg))),
+                                                        Err(e) => status.set(Some((false, error_message(&e)))),
+                                                    }
+                                                });
+                                            },
+                                            "Test"
+                                        }
+                                        button {
+                                            class: "px-2 py-1 text-xs text-red-400 hover:bg-red-900/30 rounded transition-colors",
+                                            onclick: move |_| {
+                                                let updated: Vec<Webhook> = webhooks().into_iter().filter(|w| w.id != delete_id).collect();
+                                                persist(updated);
+                                            },
+                                            "Delete"
+                                        }
+                                    }
+                                }
+                                p { class: "text-xs text-slate-400 font-mono truncate", "{webhook.url}" }
+                                p { class: "text-xs text-slate-500", "{events_label}" }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Add webhook
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                h3 {
+                    class: "text-sm font-medium text-slate-300",
+                    "Add Webhook"
+                }
+                input {
+                    class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                    placeholder: "Name",
+                    value: "{name}",
+                    oninput: move |e| name.set(e.value()),
+                }
+                input {
+                    class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                    placeholder: "URL, e.g. http://localhost:5678/webhook/idoris",
+                    value: "{url}",
+                    oninput: move |e| url.set(e.value()),
+                }
+                input {
+                    class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                    placeholder: "Signing secret (optional)",
+                    value: "{secret}",
+                    oninput: move |e| secret.set(e.value()),
+                }
+                div {
+                    class: "space-y-1",
+                    for event in WebhookEvent::all().iter().copied() {
+                        label {
+                            key: "{event.id()}",
+                            class: "flex items-center gap-2 text-sm text-slate-300",
+                            input {
+                                r#type: "checkbox",
+                                checked: events.read().contains(&event),
+                                onchange: move |_| {
+                                    let mut selected = events.write();
+                                    if let Some(pos) = selected.iter().position(|e| *e == event) {
+                                        selected.remove(pos);
+                                    } else {
+                                        selected.push(event);
+                                    }
+                                },
+                            }
+                            "{event.display_name()}"
+                        }
+                    }
+                }
+                div {
+                    class: "flex justify-end",
+                    button {
+                        class: "px-3 py-1.5 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors disabled:opacity-50",
+                        disabled: name().trim().is_empty() || url().trim().is_empty() || events.read().is_empty(),
+                        onclick: move |_| {
+                            let mut webhook = Webhook::new(name().trim().to_string(), url().trim().to_string(), events());
+                            let key = secret().trim().to_string();
+                            webhook.secret = (!key.is_empty()).then_some(key);
+                            let mut updated = webhooks();
+                            updated.push(webhook);
+                            persist(updated);
+                            name.set(String::new());
+                            url.set(String::new());
+                            secret.set(String::new());
+                            status.set(None);
+                        },
+                        "Add"
+                    }
+                }
+            }
+
+            if let Some((ok, msg)) = status() {
+                p {
+                    class: if ok { "text-xs text-green-400" } else { "text-xs text-red-400" },
+                    "{msg}"
+                }
+            }
+        }
+    }
+}
+
+/// Review and delete long-term memories
+#[component]
+fn MemorySettings() -> Element {
+    let mut memories: Signal<Vec<MemoryEntry>> = use_signal(Vec::new);
+    let mut is_loading: Signal<bool> = use_signal(|| true);
+
+    use_effect(move || {
+        spawn(async move {
+            match get_memories().await {
+                Ok(loaded) => memories.set(loaded),
+                Err(e) => tracing::error!("Error loading memories: {:?}", e),
+            }
+            is_loading.set(false);
+        });
+    });
+
+    let current = memories();
+
+    rsx! {
+        div {
+            class: "max-w-2xl space-y-6",
+
+            div {
+                class: "flex items-center justify-between mb-4",
+                h2 {
+                    class: "text-lg font-semibold text-white",
+                    "Memory"
+                }
+                if !current.is_empty() {
+                    button {
+                        class: "px-3 py-1.5 text-sm text-red-400 hover:bg-red-900/30 rounded-lg transition-colors",
+                        onclick: move |_| {
+                            spawn(async move {
+                                match clear_memories().await {
+                                    Ok(_) => memories.set(Vec::new()),
+                                    Err(e) => tracing::error!("Error clearing memories: {:?}", e),
+                                }
+                            });
+                        },
+                        "Forget everything"
+                    }
+                }
+            }
+            p {
+                class: "text-sm text-slate-400",
+                "Facts the assistant learned about you in conversations. Relevant ones are added to new prompts."
+            }
+
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-2",
+                if is_loading() {
+                    p { class: "text-sm text-slate-500", "Loading..." }
+                } else if current.is_empty() {
+                    p { class: "text-sm text-slate-500", "Nothing remembered yet." }
+                }
+                for memory in current {
+                    {
+                        let id = memory.id;
+                        let learned = memory.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string();
+                        rsx! {
+                            div {
+                                key: "{memory.id}",
+                                class: "flex items-start gap-3 py-2 border-b border-slate-700 last:border-0",
+                                div {
+                                    class: "flex-1",
+                                    p { class: "text-sm text-white", "{memory.content}" }
+                                    p { class: "text-xs text-slate-500", "Learned {learned}" }
+                                }
+                                button {
+                                    class: "px-2 py-1 text-xs text-red-400 hover:bg-red-900/30 rounded transition-colors",
+                                    onclick: move |_| {
+                                        spawn(async move {
+                                            match delete_memory(id.to_string()).await {
+                                                Ok(_) => memories.write().retain(|m| m.id != id),
+                                                Err(e) => tracing::error!("Error deleting memory: {:?}", e),
+                                            }
+                                        });
+                                    },
+                                    "Delete"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Configure the SearxNG or Brave backend used for web grounding
+#[component]
+fn WebSearchSettings() -> Element {
+    let mut config: Signal<WebSearchConfig> = use_signal(WebSearchConfig::default);
+    let mut status: Signal<Option<(bool, String)>> = use_signal(|| None);
+    let mut test_query: Signal<String> = use_signal(|| "Rust programming language".to_string());
+    let mut test_results: Signal<Vec<WebSearchResult>> = use_signal(Vec::new);
+    let mut is_testing = use_signal(|| false);
+
+    use_effect(move || {
+        spawn(async move {
+            match get_web_search_config().await {
+                Ok(loaded) => config.set(loaded),
+                Err(e) => tracing::error!("Error loading web search config: {:?}", e),
+            }
+        });
+    });
+
+    let current = config();
+
+    rsx! {
+        div {
+            class: "max-w-2xl space-y-6",
+
+            h2 {
+                class: "text-lg font-semibold text-white mb-4",
+                "Web Search"
+            }
+            p {
+                class: "text-sm text-slate-400",
+                "Optionally ground chat answers in web results. Enable \"Search Web\" in the chat toolbar to use it; results are cited as [1], [2], ..."
+            }
+
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                label {
+                    class: "block text-sm font-medium text-slate-300",
+                    "Provider"
+                }
+                select {
+                    class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white focus:outline-none focus:border-blue-500",
+                    value: "{current.provider.id()}",
+                    onchange: move |e| config.write().provider = WebSearchProvider::from_id(&e.value()),
+                    for provider in WebSearchProvider::all().iter().copied() {
+                        option {
+                            key: "{provider.id()}",
+                            value: "{provider.id()}",
+                            selected: provider == current.provider,
+                            "{provider.display_name()}"
+                        }
+                    }
+                }
+
+                if current.provider == WebSearchProvider::Searxng {
+                    input {
+                        class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                        placeholder: "SearxNG URL, e.g. http://localhost:8888",
+                        value: "{current.searxng_url}",
+                        oninput: move |e| config.write().searxng_url = e.value(),
+                    }
+                    p {
+                        class: "text-xs text-slate-500",
+                        "The instance must allow the JSON output format (search.formats in settings.yml)."
+                    }
+                }
+                if current.provider == WebSearchProvider::Brave {
+                    input {
+                        r#type: "password",
+                        class: "w-full px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                        placeholder: "Brave Search API key",
+                        value: "{current.brave_api_key}",
+                        oninput: move |e| config.write().brave_api_key = e.value(),
+                    }
+                }
+
+                div {
+                    class: "flex justify-end",
+                    button {
+                        class: "px-3 py-1.5 text-sm bg-blue-600 hover:bg-blue-700 rounded-lg transition-colors",
+                        onclick: move |_| {
+                            let updated = config();
+                            spawn(async move {
+                                match save_web_search_config(updated).await {
+                                    Ok(_) => status.set(Some((true, "Saved".to_string()))),
+                                    Err(e) => status.set(Some((false, error_message(&e)))),
+                                }
+                            });
+                        },
+                        "Save"
+                    }
+                }
+            }
+
+            // Try the saved configuration
+            div {
+                class: "bg-slate-800 rounded-lg p-4 space-y-3",
+                h3 {
+                    class: "text-sm font-medium text-slate-300",
+                    "Test Search"
+                }
+                div {
+                    class: "flex gap-2",
+                    input {
+                        class: "flex-1 px-3 py-2 bg-slate-700 border border-slate-600 rounded-lg text-sm text-white placeholder-slate-500 focus:outline-none focus:border-blue-500",
+                        value: "{test_query}",
+                        oninput: move |e| test_query.set(e.value()),
+                    }
+                    button {
+                        class: "px-3 py-1.5 text-sm bg-slate-600 hover:bg-slate-500 rounded-lg transition-colors disabled:opacity-50",
+                        disabled: is_testing() || test_query().trim().is_empty(),
+                        onclick: move |_| {
+                            is_testing.set(true);
+                            status.set(None);
+                            spawn(async move {
+                                match search_web(test_query()).await {
+                                    Ok(results) => {
+                                        if results.is_empty() {
+                                            status.set(Some((false, "No results".to_string())));
+                                        }
+                                        test_results.set(results);
+                                    }
+                                    Err(e) => status.set(Some((false, error_message(&e)))),
+                                }
+                                is_testing.set(false);
+                            });
+                        },
+                        if is_testing() { "Searching..." } else { "Search" }
+                    }
+                }
+                for (i, result) in test_results().into_iter().enumerate() {
+                    div {
+                        key: "{result.url}",
+                        class: "p-3 bg-slate-700/50 rounded-lg space-y-1",
+                        p { class: "text-sm font-medium text-white", "[{i + 1}] {result.title}" }
+                        p { class: "text-xs text-slate-400 font-mono truncate", "{result.url}" }
+                        p { class: "text-xs text-slate-300", "{result.snippet}" }
+                    }
+                }
+            }
+
+            if let Some((ok, msg)) = status() {
+                p {
+                    class: if ok { "text-xs text-green-400" } else { "text-xs text-red-400" },
+                    "{msg}"
+                }
+            }
+        }
+    }
+}
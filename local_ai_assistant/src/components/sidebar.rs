@@ -1,8 +1,25 @@
 //! Sidebar Component for Session Management
 
 use dioxus::prelude::*;
-use crate::models::Session;
+use dioxus::html::input_data::keyboard_types::Key;
+use uuid::Uuid;
+use crate::models::{Profile, Session, tag_counts};
+use crate::server_functions::{
+    set_session_archived, set_session_folder, set_session_pinned, share_session_html, delete_session,
+    get_profiles, get_active_profile, create_profile, switch_profile,
+    start_session_tagging, is_session_tagging_running, get_sessions,
+};
 use super::ActivePanel;
+use super::download::save_text_file;
+use super::status_bar::sleep_ms;
+use super::ime::use_ime_state;
+use super::i18n::t;
+
+/// Tag filters shown before "more"
+const VISIBLE_TAGS: usize = 8;
+
+/// How often a running tagging job is checked, in milliseconds
+const TAGGING_POLL_MS: u32 = 2000;
 
 #[component]
 pub fn Sidebar(
@@ -13,16 +30,55 @@ pub fn Sidebar(
     on_select_session: EventHandler<Session>,
     on_toggle_settings: EventHandler<()>,
     on_select_panel: EventHandler<ActivePanel>,
+    on_switch_profile: EventHandler<Profile>,
     sidebar_collapsed: Signal<bool>,
 ) -> Element {
+    // Session currently being dragged onto a folder
+    let dragged_session: Signal<Option<Uuid>> = use_signal(|| None);
+    let mut show_archived: Signal<bool> = use_signal(|| false);
+    // Some(..) while the "new folder" input is open
+    let mut new_folder_name: Signal<Option<String>> = use_signal(|| None);
+    let folder_ime = use_ime_state();
+    // Folders created in the UI that have no sessions yet
+    let mut extra_folders: Signal<Vec<String>> = use_signal(Vec::new);
+    // Only sessions with this tag or topic are listed
+    let mut tag_filter: Signal<Option<String>> = use_signal(|| None);
+    let mut show_all_tags: Signal<bool> = use_signal(|| false);
+    let mut is_tagging: Signal<bool> = use_signal(|| false);
+
     if sidebar_collapsed() {
         return rsx! {};
     }
 
+    let all_sessions = sessions();
+    let tags = tag_counts(&all_sessions);
+    let visible_tags = if show_all_tags() { tags.len() } else { VISIBLE_TAGS.min(tags.len()) };
+    let filter = tag_filter();
+    let (archived, active): (Vec<Session>, Vec<Session>) = all_sessions
+        .into_iter()
+        .filter(|s| filter.as_deref().is_none_or(|tag| s.matches_tag(tag)))
+        .partition(|s| s.archived);
+    let pinned: Vec<Session> = active.iter().filter(|s| s.pinned).cloned().collect();
+    let unfiled: Vec<Session> = active.iter()
+        .filter(|s| !s.pinned && s.folder.is_none())
+        .cloned()
+        .collect();
+
+    let mut folders: Vec<String> = active.iter()
+        .filter_map(|s| s.folder.clone())
+        .chain(extra_folders.read().iter().filter(|_| filter.is_none()).cloned())
+        .collect();
+    folders.sort();
+    folders.dedup();
+
+    let pinned_or_filed_empty = pinned.is_empty() && folders.is_empty();
+
     rsx! {
         aside {
             class: "w-64 bg-gray-800 border-r border-gray-700 flex flex-col",
 
+            ProfileSwitcher { on_switch: on_switch_profile }
+
             // New chat button
             div {
                 class: "p-4",
@@ -41,34 +97,184 @@ pub fn Sidebar(
                             d: "M12 4v16m8-8H4"
                         }
                     }
-                    span { "New Chat" }
+                    span { {t("sidebar.new_chat")} }
                 }
             }
 
-            // Session list
+            // Session list: pinned, folders, unfiled, then archived
             div {
                 class: "flex-1 overflow-y-auto px-2",
-                for session in sessions() {
+
+                // Tag and topic filters from the tagging job
+                if !tags.is_empty() || is_tagging() {
+                    div {
+                        class: "mb-2 px-1",
+                        div {
+                            class: "flex items-center text-xs text-slate-500 uppercase font-semibold mb-1",
+                            span { {t("sidebar.topics")} }
+                            button {
+                                class: "ml-auto normal-case font-normal hover:text-slate-300 transition-colors disabled:opacity-50",
+                                title: t("sidebar.retag_hint"),
+                                disabled: is_tagging(),
+                                onclick: move |_| {
+                                    is_tagging.set(true);
+                                    spawn(async move {
+                                        tag_sessions(sessions, true).await;
+                                        is_tagging.set(false);
+                                    });
+                                },
+                                if is_tagging() { {t("sidebar.tagging")} } else { {t("sidebar.retag")} }
+                            }
+                        }
+                        div {
+                            class: "flex flex-wrap gap-1",
+                            for (tag, count) in tags.iter().take(visible_tags).cloned() {
+                                {
+                                    let selected = tag_filter().as_deref() == Some(tag.as_str());
+                                    let label = tag.clone();
+                                    rsx! {
+                                        button {
+                                            key: "tag-{label}",
+                                            class: if selected {
+                                                "px-2 py-0.5 text-xs rounded-full bg-blue-600 text-white"
+                                            } else {
+                                                "px-2 py-0.5 text-xs rounded-full bg-gray-700 text-slate-300 hover:bg-gray-600 transition-colors"
+                                            },
+                                            onclick: move |_| tag_filter.set(if selected { None } else { Some(tag.clone()) }),
+                                            "{label} "
+                                            span { class: "opacity-60", "{count}" }
+                                        }
+                                    }
+                                }
+                            }
+                            if tags.len() > VISIBLE_TAGS {
+                                button {
+                                    class: "px-2 py-0.5 text-xs text-slate-500 hover:text-slate-300",
+                                    onclick: move |_| show_all_tags.set(!show_all_tags()),
+                                    if show_all_tags() { {t("sidebar.less")} } else { {t("sidebar.more")} }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if !pinned.is_empty() {
+                    div {
+                        class: "text-xs text-slate-500 uppercase font-semibold mt-1 mb-1 px-1",
+                        {t("sidebar.pinned")}
+                    }
+                    for session in pinned {
+                        { render_session_item(session, current_session, sessions, dragged_session, on_select_session, on_new_session) }
+                    }
+                }
+
+                // Folders double as drop targets
+                for folder in folders {
                     {
-                        let is_active = current_session().map(|s| s.id == session.id).unwrap_or(false);
-                        let session_clone = session.clone();
+                        let folder_sessions: Vec<Session> = active.iter()
+                            .filter(|s| !s.pinned && s.folder.as_deref() == Some(folder.as_str()))
+                            .cloned()
+                            .collect();
+                        let drop_folder = folder.clone();
                         rsx! {
-                            button {
-                                key: "{session.id}",
-                                class: if is_active {
-                                    "w-full text-left p-3 rounded-lg mb-1 bg-gray-700"
-                                } else {
-                                    "w-full text-left p-3 rounded-lg mb-1 hover:bg-gray-700 transition-colors"
+                            div {
+                                key: "folder-{folder}",
+                                class: "mt-2",
+                                ondragover: move |e| e.prevent_default(),
+                                ondrop: move |e| {
+                                    e.prevent_default();
+                                    move_dragged_session(sessions, dragged_session, Some(drop_folder.clone()));
                                 },
-                                onclick: move |_| on_select_session.call(session_clone.clone()),
                                 div {
-                                    class: "truncate font-medium text-slate-100",
-                                    "{session.title}"
+                                    class: "flex items-center gap-2 text-xs text-slate-400 font-semibold px-1 py-1 rounded hover:bg-gray-700/50",
+                                    svg {
+                                        class: "w-4 h-4",
+                                        fill: "none",
+                                        stroke: "currentColor",
+                                        stroke_width: "2",
+                                        view_box: "0 0 24 24",
+                                        path {
+                                            stroke_linecap: "round",
+                                            stroke_linejoin: "round",
+                                            d: "M3 7a2 2 0 012-2h4l2 2h8a2 2 0 012 2v8a2 2 0 01-2 2H5a2 2 0 01-2-2V7z"
+                                        }
+                                    }
+                                    span { class: "truncate", "{folder}" }
+                                    span { class: "ml-auto text-slate-500", "{folder_sessions.len()}" }
                                 }
                                 div {
-                                    class: "text-xs text-slate-400 mt-1",
-                                    {session.created_at.format("%m/%d %H:%M").to_string()}
+                                    class: "pl-2",
+                                    for session in folder_sessions {
+                                        { render_session_item(session, current_session, sessions, dragged_session, on_select_session, on_new_session) }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // New folder input
+                if let Some(name) = new_folder_name() {
+                    input {
+                        class: "w-full mt-2 px-2 py-1 text-sm bg-gray-900 border border-gray-600 rounded focus:outline-none focus:border-blue-500",
+                        placeholder: t("sidebar.folder_name"),
+                        value: "{name}",
+                        autofocus: true,
+                        oninput: move |e| new_folder_name.set(Some(e.value())),
+                        oncompositionstart: move |_| folder_ime.start(),
+                        oncompositionend: move |_| folder_ime.end(),
+                        onkeydown: move |e| {
+                            if folder_ime.is_enter(&e) {
+                                let name = new_folder_name().unwrap_or_default().trim().to_string();
+                                if !name.is_empty() && !extra_folders.read().contains(&name) {
+                                    extra_folders.write().push(name);
                                 }
+                                new_folder_name.set(None);
+                            } else if e.key() == Key::Escape {
+                                new_folder_name.set(None);
+                            }
+                        },
+                    }
+                } else {
+                    button {
+                        class: "w-full mt-2 mb-1 px-1 text-left text-xs text-slate-500 hover:text-slate-300 transition-colors",
+                        onclick: move |_| new_folder_name.set(Some(String::new())),
+                        "+ New folder"
+                    }
+                }
+
+                // Unfiled sessions; dropping here removes a session from its folder
+                div {
+                    class: "mt-2 min-h-[2rem]",
+                    ondragover: move |e| e.prevent_default(),
+                    ondrop: move |e| {
+                        e.prevent_default();
+                        move_dragged_session(sessions, dragged_session, None);
+                    },
+                    if !pinned_or_filed_empty {
+                        div {
+                            class: "text-xs text-slate-500 uppercase font-semibold mb-1 px-1",
+                            {t("sidebar.chats")}
+                        }
+                    }
+                    for session in unfiled {
+                        { render_session_item(session, current_session, sessions, dragged_session, on_select_session, on_new_session) }
+                    }
+                }
+
+                // Archived sessions, collapsed by default
+                if !archived.is_empty() {
+                    button {
+                        class: "w-full mt-3 mb-1 px-1 flex items-center text-xs text-slate-500 uppercase font-semibold hover:text-slate-300 transition-colors",
+                        onclick: move |_| show_archived.set(!show_archived()),
+                        span { if show_archived() { "▾ " } else { "▸ " } {t("sidebar.archived")} }
+                        span { class: "ml-auto", "{archived.len()}" }
+                    }
+                    if show_archived() {
+                        div {
+                            class: "opacity-70",
+                            for session in archived {
+                                { render_session_item(session, current_session, sessions, dragged_session, on_select_session, on_new_session) }
                             }
                         }
                     }
@@ -80,7 +286,7 @@ pub fn Sidebar(
                 class: "p-3 border-t border-gray-700",
                 div {
                     class: "text-xs text-slate-500 uppercase font-semibold mb-2 px-1",
-                    "Panels"
+                    {t("sidebar.panels")}
                 }
 
                 // Chat panel button
@@ -103,7 +309,7 @@ pub fn Sidebar(
                             d: "M8 12h.01M12 12h.01M16 12h.01M21 12c0 4.418-4.03 8-9 8a9.863 9.863 0 01-4.255-.949L3 20l1.395-3.72C3.512 15.042 3 13.574 3 12c0-4.418 4.03-8 9-8s9 3.582 9 8z"
                         }
                     }
-                    span { "Chat" }
+                    span { {t("panel.chat")} }
                 }
 
                 // Image Generation panel button
@@ -126,7 +332,7 @@ pub fn Sidebar(
                             d: "M4 16l4.586-4.586a2 2 0 012.828 0L16 16m-2-2l1.586-1.586a2 2 0 012.828 0L20 14m-6-6h.01M6 20h12a2 2 0 002-2V6a2 2 0 00-2-2H6a2 2 0 00-2 2v12a2 2 0 002 2z"
                         }
                     }
-                    span { "Image Gen" }
+                    span { {t("panel.image_gen")} }
                 }
 
                 // TTS panel button
@@ -149,7 +355,30 @@ pub fn Sidebar(
                             d: "M15.536 8.464a5 5 0 010 7.072m2.828-9.9a9 9 0 010 12.728M5.586 15H4a1 1 0 01-1-1v-4a1 1 0 011-1h1.586l4.707-4.707C10.923 3.663 12 4.109 12 5v14c0 .891-1.077 1.337-1.707.707L5.586 15z"
                         }
                     }
-                    span { "Text to Speech" }
+                    span { {t("panel.tts")} }
+                }
+
+                // Voice conversation panel button
+                button {
+                    class: if matches!(active_panel(), ActivePanel::Voice) {
+                        "w-full py-2 px-3 bg-teal-600 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    } else {
+                        "w-full py-2 px-3 hover:bg-slate-700 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    },
+                    onclick: move |_| on_select_panel.call(ActivePanel::Voice),
+                    svg {
+                        class: "w-5 h-5",
+                        fill: "none",
+                        stroke: "currentColor",
+                        stroke_width: "2",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M19 11a7 7 0 01-7 7m0 0a7 7 0 01-7-7m7 7v4m0 0H8m4 0h4m-4-8a3 3 0 01-3-3V5a3 3 0 116 0v6a3 3 0 01-3 3z"
+                        }
+                    }
+                    span { {t("panel.voice")} }
                 }
 
                 // Content Editor panel button
@@ -172,7 +401,53 @@ pub fn Sidebar(
                             d: "M11 5H6a2 2 0 00-2 2v11a2 2 0 002 2h11a2 2 0 002-2v-5m-1.414-9.414a2 2 0 112.828 2.828L11.828 15H9v-2.828l8.586-8.586z"
                         }
                     }
-                    span { "Content Editor" }
+                    span { {t("panel.content_editor")} }
+                }
+
+                // Content Pipeline panel button
+                button {
+                    class: if matches!(active_panel(), ActivePanel::ContentPipeline) {
+                        "w-full py-2 px-3 bg-orange-600 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    } else {
+                        "w-full py-2 px-3 hover:bg-slate-700 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    },
+                    onclick: move |_| on_select_panel.call(ActivePanel::ContentPipeline),
+                    svg {
+                        class: "w-5 h-5",
+                        fill: "none",
+                        stroke: "currentColor",
+                        stroke_width: "2",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M4 6h16M4 12h10M4 18h6m10-3l-3 3-2-2"
+                        }
+                    }
+                    span { {t("panel.content_pipeline")} }
+                }
+
+                // Content calendar panel button
+                button {
+                    class: if matches!(active_panel(), ActivePanel::Calendar) {
+                        "w-full py-2 px-3 bg-orange-600 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    } else {
+                        "w-full py-2 px-3 hover:bg-slate-700 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    },
+                    onclick: move |_| on_select_panel.call(ActivePanel::Calendar),
+                    svg {
+                        class: "w-5 h-5",
+                        fill: "none",
+                        stroke: "currentColor",
+                        stroke_width: "2",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M8 7V3m8 4V3m-9 8h10M5 21h14a2 2 0 002-2V7a2 2 0 00-2-2H5a2 2 0 00-2 2v12a2 2 0 002 2z"
+                        }
+                    }
+                    span { {t("panel.calendar")} }
                 }
 
                 // Video Gen panel button
@@ -195,9 +470,101 @@ pub fn Sidebar(
                             d: "M15 10l4.553-2.276A1 1 0 0121 8.618v6.764a1 1 0 01-1.447.894L15 14M5 18h8a2 2 0 002-2V8a2 2 0 00-2-2H5a2 2 0 00-2 2v8a2 2 0 002 2z"
                         }
                     }
-                    span { "Video Generation" }
+                    span { {t("panel.video_gen")} }
                     span { class: "text-xs text-purple-400 ml-auto", "AI" }
                 }
+
+                // Model comparison panel button
+                button {
+                    class: if matches!(active_panel(), ActivePanel::Compare) {
+                        "w-full py-2 px-3 bg-slate-600 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    } else {
+                        "w-full py-2 px-3 hover:bg-slate-700 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    },
+                    onclick: move |_| on_select_panel.call(ActivePanel::Compare),
+                    svg {
+                        class: "w-5 h-5 text-slate-400",
+                        fill: "none",
+                        stroke: "currentColor",
+                        stroke_width: "2",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M9 17V7m0 10a2 2 0 01-2 2H5a2 2 0 01-2-2V7a2 2 0 012-2h2a2 2 0 012 2m0 10a2 2 0 002 2h2a2 2 0 002-2M9 7a2 2 0 012-2h2a2 2 0 012 2m0 10V7m0 10a2 2 0 002 2h2a2 2 0 002-2V7a2 2 0 00-2-2h-2a2 2 0 00-2 2"
+                        }
+                    }
+                    span { {t("panel.compare")} }
+                }
+
+                // Asset library panel button
+                button {
+                    class: if matches!(active_panel(), ActivePanel::Assets) {
+                        "w-full py-2 px-3 bg-slate-600 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    } else {
+                        "w-full py-2 px-3 hover:bg-slate-700 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    },
+                    onclick: move |_| on_select_panel.call(ActivePanel::Assets),
+                    svg {
+                        class: "w-5 h-5 text-slate-400",
+                        fill: "none",
+                        stroke: "currentColor",
+                        stroke_width: "2",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M4 6a2 2 0 012-2h12a2 2 0 012 2v12a2 2 0 01-2 2H6a2 2 0 01-2-2V6zm4 10l3-3 2 2 3-4 2 5"
+                        }
+                    }
+                    span { {t("panel.assets")} }
+                }
+
+                // Background jobs panel button
+                button {
+                    class: if matches!(active_panel(), ActivePanel::Jobs) {
+                        "w-full py-2 px-3 bg-slate-600 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    } else {
+                        "w-full py-2 px-3 hover:bg-slate-700 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    },
+                    onclick: move |_| on_select_panel.call(ActivePanel::Jobs),
+                    svg {
+                        class: "w-5 h-5 text-slate-400",
+                        fill: "none",
+                        stroke: "currentColor",
+                        stroke_width: "2",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M4 6h16M4 10h16M4 14h10M4 18h7"
+                        }
+                    }
+                    span { {t("panel.jobs")} }
+                }
+
+                // Trash panel button
+                button {
+                    class: if matches!(active_panel(), ActivePanel::Trash) {
+                        "w-full py-2 px-3 bg-slate-600 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    } else {
+                        "w-full py-2 px-3 hover:bg-slate-700 rounded-lg flex items-center gap-3 transition-colors mb-2"
+                    },
+                    onclick: move |_| on_select_panel.call(ActivePanel::Trash),
+                    svg {
+                        class: "w-5 h-5 text-slate-400",
+                        fill: "none",
+                        stroke: "currentColor",
+                        stroke_width: "2",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M19 7l-.867 12.142A2 2 0 0116.138 21H7.862a2 2 0 01-1.995-1.858L5 7m5 4v6m4-6v6m1-10V4a1 1 0 00-1-1h-4a1 1 0 00-1 1v3M4 7h16"
+                        }
+                    }
+                    span { {t("panel.trash")} }
+                }
             }
 
             // Footer with settings button
@@ -227,7 +594,7 @@ pub fn Sidebar(
                     }
                     span {
                         class: "text-slate-400",
-                        "Settings"
+                        {t("settings.title")}
                     }
                 }
 
@@ -239,3 +606,314 @@ pub fn Sidebar(
         }
     }
 }
+
+/// Profile selector with inline creation of new profiles
+#[component]
+fn ProfileSwitcher(on_switch: EventHandler<Profile>) -> Element {
+    let mut profiles: Signal<Vec<Profile>> = use_signal(Vec::new);
+    let mut active_id: Signal<String> = use_signal(String::new);
+    // Some(..) while the "new profile" input is open
+    let mut new_profile_name: Signal<Option<String>> = use_signal(|| None);
+    let ime = use_ime_state();
+    let mut is_switching: Signal<bool> = use_signal(|| false);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(list) = get_profiles().await {
+                profiles.set(list);
+            }
+            if let Ok(active) = get_active_profile().await {
+                active_id.set(active.id);
+            }
+        });
+    });
+
+    let mut do_switch = move |id: String| {
+        if id == active_id() || is_switching() {
+            return;
+        }
+        is_switching.set(true);
+        spawn(async move {
+            match switch_profile(id).await {
+                Ok(profile) => {
+                    active_id.set(profile.id.clone());
+                    on_switch.call(profile);
+                }
+                Err(e) => tracing::error!("Error switching profile: {:?}", e),
+            }
+            is_switching.set(false);
+        });
+    };
+
+    let mut submit_new_profile = move || {
+        let name = new_profile_name().unwrap_or_default().trim().to_string();
+        new_profile_name.set(None);
+        if name.is_empty() {
+            return;
+        }
+        spawn(async move {
+            match create_profile(name).await {
+                Ok(profile) => {
+                    let id = profile.id.clone();
+                    profiles.write().push(profile);
+                    do_switch(id);
+                }
+                Err(e) => tracing::error!("Error creating profile: {:?}", e),
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "px-4 pt-4 flex items-center gap-2",
+            svg {
+                class: "w-4 h-4 text-slate-400 shrink-0",
+                fill: "none",
+                stroke: "currentColor",
+                stroke_width: "2",
+                view_box: "0 0 24 24",
+                path {
+                    stroke_linecap: "round",
+                    stroke_linejoin: "round",
+                    d: "M16 7a4 4 0 11-8 0 4 4 0 018 0zM12 14a7 7 0 00-7 7h14a7 7 0 00-7-7z"
+                }
+            }
+            if let Some(name) = new_profile_name() {
+                input {
+                    class: "flex-1 min-w-0 px-2 py-1 text-sm bg-gray-700 border border-gray-600 rounded text-white focus:outline-none focus:border-blue-500",
+                    placeholder: t("sidebar.profile_name"),
+                    value: "{name}",
+                    autofocus: true,
+                    oninput: move |e| new_profile_name.set(Some(e.value())),
+                    oncompositionstart: move |_| ime.start(),
+                    oncompositionend: move |_| ime.end(),
+                    onkeydown: move |e| {
+                        if ime.is_enter(&e) {
+                            submit_new_profile();
+                        } else if e.key() == Key::Escape {
+                            new_profile_name.set(None);
+                        }
+                    },
+                    onblur: move |_| submit_new_profile(),
+                }
+            } else {
+                select {
+                    class: "flex-1 min-w-0 px-2 py-1 text-sm bg-gray-700 border border-gray-600 rounded text-white focus:outline-none focus:border-blue-500 disabled:opacity-50",
+                    value: "{active_id}",
+                    disabled: is_switching(),
+                    onchange: move |e| do_switch(e.value()),
+                    for profile in profiles() {
+                        option {
+                            key: "{profile.id}",
+                            value: "{profile.id}",
+                            selected: profile.id == active_id(),
+                            "{profile.name}"
+                        }
+                    }
+                }
+                button {
+                    class: "p-1 rounded text-slate-400 hover:text-white hover:bg-gray-700 transition-colors",
+                    title: t("sidebar.new_profile"),
+                    onclick: move |_| new_profile_name.set(Some(String::new())),
+                    "+"
+                }
+            }
+        }
+    }
+}
+
+/// Renders a single draggable session row with pin, archive and delete
+/// actions. Deleting the open session starts a new chat.
+fn render_session_item(
+    session: Session,
+    current_session: Signal<Option<Session>>,
+    mut sessions: Signal<Vec<Session>>,
+    mut dragged_session: Signal<Option<Uuid>>,
+    on_select_session: EventHandler<Session>,
+    on_new_session: EventHandler<()>,
+) -> Element {
+    let is_active = current_session().map(|s| s.id == session.id).unwrap_or(false);
+    let session_id = session.id;
+    let pinned = session.pinned;
+    let archived = session.archived;
+    let session_clone = session.clone();
+
+    rsx! {
+        div {
+            key: "{session.id}",
+            class: if is_active {
+                "group w-full text-left p-3 rounded-lg mb-1 bg-gray-700 cursor-pointer"
+            } else {
+                "group w-full text-left p-3 rounded-lg mb-1 hover:bg-gray-700 transition-colors cursor-pointer"
+            },
+            draggable: "true",
+            ondragstart: move |_| dragged_session.set(Some(session_id)),
+            ondragend: move |_| dragged_session.set(None),
+            onclick: move |_| on_select_session.call(session_clone.clone()),
+
+            div {
+                class: "flex items-center gap-1",
+                if pinned {
+                    span { class: "text-xs text-yellow-400", "★" }
+                }
+                div {
+                    class: "truncate font-medium text-slate-100 flex-1",
+                    "{session.title}"
+                }
+
+                // Row actions, shown on hover
+                div {
+                    class: "hidden group-hover:flex items-center gap-1",
+                    if !archived {
+                        button {
+                            class: "p-1 rounded text-xs text-slate-400 hover:text-yellow-400 hover:bg-gray-600",
+                            title: if pinned { t("sidebar.unpin") } else { t("sidebar.pin") },
+                            onclick: move |e| {
+                                e.stop_propagation();
+                                update_session(sessions, session_id, |s| s.pinned = !pinned);
+                                spawn(async move {
+                                    if let Err(e) = set_session_pinned(session_id.to_string(), !pinned).await {
+                                        tracing::error!("Error pinning session: {:?}", e);
+                                    }
+                                });
+                            },
+                            if pinned { "☆" } else { "★" }
+                        }
+                    }
+                    button {
+                        class: "p-1 rounded text-xs text-slate-400 hover:text-slate-100 hover:bg-gray-600",
+                        title: if archived { t("sidebar.restore") } else { t("sidebar.archive") },
+                        onclick: move |e| {
+                            e.stop_propagation();
+                            update_session(sessions, session_id, |s| {
+                                s.archived = !archived;
+                                if s.archived {
+                                    s.pinned = false;
+                                }
+                            });
+                            spawn(async move {
+                                if let Err(e) = set_session_archived(session_id.to_string(), !archived).await {
+                                    tracing::error!("Error archiving session: {:?}", e);
+                                }
+                            });
+                        },
+                        if archived { "↺" } else { "⤓" }
+                    }
+                    button {
+                        class: "p-1 rounded text-xs text-slate-400 hover:text-slate-100 hover:bg-gray-600",
+                        title: t("sidebar.share_html"),
+                        onclick: {
+                            let title = session.title.clone();
+                            move |e: MouseEvent| {
+                                e.stop_propagation();
+                                let title = title.clone();
+                                spawn(async move {
+                                    download_session_html(session_id, &title).await;
+                                });
+                            }
+                        },
+                        "⇪"
+                    }
+                    button {
+                        class: "p-1 rounded text-xs text-slate-400 hover:text-red-400 hover:bg-gray-600",
+                        title: t("sidebar.move_to_trash"),
+                        onclick: move |e| {
+                            e.stop_propagation();
+                            sessions.write().retain(|s| s.id != session_id);
+                            if is_active {
+                                on_new_session.call(());
+                            }
+                            spawn(async move {
+                                if let Err(e) = delete_session(session_id.to_string()).await {
+                                    tracing::error!("Error deleting session: {:?}", e);
+                                }
+                            });
+                        },
+                        "🗑"
+                    }
+                }
+            }
+            div {
+                class: "text-xs text-slate-400 mt-1",
+                {session.created_at.format("%m/%d %H:%M").to_string()}
+            }
+        }
+    }
+}
+
+/// Renders the session as a standalone HTML file and saves it through the
+/// browser's download
+async fn download_session_html(session_id: Uuid, title: &str) {
+    let html = match share_session_html(session_id.to_string()).await {
+        Ok(html) => html,
+        Err(e) => {
+            tracing::error!("Error sharing session: {:?}", e);
+            return;
+        }
+    };
+
+    let stem: String = title.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let file_name = format!("{}.html", if stem.is_empty() { "chat" } else { &stem });
+    save_text_file(&file_name, "text/html", html);
+}
+
+/// Moves the session being dragged into `folder` locally and on the server
+fn move_dragged_session(
+    sessions: Signal<Vec<Session>>,
+    mut dragged_session: Signal<Option<Uuid>>,
+    folder: Option<String>,
+) {
+    let Some(session_id) = dragged_session() else {
+        return;
+    };
+    dragged_session.set(None);
+
+    let local_folder = folder.clone();
+    update_session(sessions, session_id, move |s| s.folder = local_folder.clone());
+    spawn(async move {
+        if let Err(e) = set_session_folder(session_id.to_string(), folder).await {
+            tracing::error!("Error moving session: {:?}", e);
+        }
+    });
+}
+
+/// Applies an in-place change to a session in the shared sessions list
+fn update_session(mut sessions: Signal<Vec<Session>>, session_id: Uuid, change: impl FnOnce(&mut Session)) {
+    let mut sessions_list = sessions.read().clone();
+    if let Some(session) = sessions_list.iter_mut().find(|s| s.id == session_id) {
+        change(session);
+        sessions.set(sessions_list);
+    }
+}
+
+/// Runs the session tagging job and applies the new tags and topics once it
+/// has finished. Without `retag_all`, only untagged sessions are tagged.
+pub async fn tag_sessions(mut sessions: Signal<Vec<Session>>, retag_all: bool) {
+    if let Err(e) = start_session_tagging(retag_all).await {
+        tracing::error!("Error starting session tagging: {:?}", e);
+        return;
+    }
+    loop {
+        sleep_ms(TAGGING_POLL_MS).await;
+        if !is_session_tagging_running().await.unwrap_or(false) {
+            break;
+        }
+    }
+    match get_sessions().await {
+        Ok(loaded) => {
+            for session in sessions.write().iter_mut() {
+                if let Some(updated) = loaded.iter().find(|s| s.id == session.id) {
+                    session.tags = updated.tags.clone();
+                    session.topic = updated.topic.clone();
+                }
+            }
+        }
+        Err(e) => tracing::error!("Error reloading sessions: {:?}", e),
+    }
+}
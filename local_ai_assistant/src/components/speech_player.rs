@@ -0,0 +1,203 @@
+//! Speech Player Component
+//!
+//! Reads queued assistant messages aloud, one sentence at a time. The
+//! sentence after the current one is generated while it plays so playback
+//! runs without gaps.
+
+use std::collections::HashMap;
+use dioxus::prelude::*;
+use uuid::Uuid;
+
+use crate::models::{AppSettings, PlaybackQueue, SpeechOutput, PLAYBACK_SPEEDS, error_message};
+use crate::server_functions::generate_tts;
+
+const AUDIO_ID: &str = "speech-player-audio";
+
+/// Runs a method on the player's `<audio>` element
+fn control_audio(script: &str) {
+    let _ = document::eval(&format!("document.getElementById('{}')?.{}", AUDIO_ID, script));
+}
+
+/// Applies the output device, volume and playback rate to an `<audio>`
+/// element. The device is matched by name against the browser's outputs,
+/// which needs a browser that supports choosing one; otherwise the system
+/// default is used.
+pub fn apply_speech_output(element_id: &str, output: &SpeechOutput, rate: f32) {
+    let eval = document::eval(
+        r#"
+        const [id, volume, rate, device] = await dioxus.recv();
+        const element = document.getElementById(id);
+        if (!element) return;
+        element.volume = Math.min(Math.max(volume, 0), 1);
+        element.playbackRate = rate;
+        if (!element.setSinkId) return;
+        let sinkId = "";
+        if (device) {
+            const outputs = (await navigator.mediaDevices.enumerateDevices()).filter((d) => d.kind === "audiooutput" && d.label);
+            const match = outputs.find((d) => d.label === device) || outputs.find((d) => d.label.includes(device) || device.includes(d.label));
+            if (match) sinkId = match.deviceId;
+        }
+        if (element.sinkId !== sinkId) await element.setSinkId(sinkId).catch(() => {});
+        "#,
+    );
+    let _ = eval.send((element_id.to_string(), output.volume, rate, output.device.clone().unwrap_or_default()));
+}
+
+/// Player bar shown while the queue has messages
+#[component]
+pub fn SpeechPlayer(queue: Signal<PlaybackQueue>, settings: Signal<AppSettings>) -> Element {
+    // Generated audio per (message, sentence) with the text it says, and
+    // the sentence being generated
+    let mut audio: Signal<HashMap<(Uuid, usize), (String, String)>> = use_signal(HashMap::new);
+    let mut generating: Signal<Option<(Uuid, usize)>> = use_signal(|| None);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    let current = use_memo(move || queue.read().current());
+    let upcoming = use_memo(move || queue.read().upcoming());
+
+    // Generates the current sentence, then the next one, dropping audio
+    // already played. The engine takes one request at a time.
+    use_effect(move || {
+        let wanted: Vec<_> = current().into_iter().chain(upcoming()).collect();
+        // Audio is kept only while it matches the sentence
+        audio.write().retain(|key, (said, _)| wanted.iter().any(|(id, i, sentence)| (*id, *i) == *key && said == sentence));
+        if generating.read().is_some() {
+            return;
+        }
+        let Some((id, index, sentence)) = wanted.into_iter().find(|(id, i, _)| !audio.peek().contains_key(&(*id, *i))) else {
+            return;
+        };
+        let key = (id, index);
+        generating.set(Some(key));
+        let engine = settings.peek().speech_engine.clone();
+        spawn(async move {
+            match generate_tts(sentence.clone(), engine, 1.0, false).await {
+                Ok(url) => {
+                    audio.write().insert(key, (sentence, url));
+                }
+                Err(e) => {
+                    // Stop rather than retry; the engine is likely missing
+                    error.set(Some(format!("Speech failed: {}", error_message(&e))));
+                    queue.write().clear();
+                }
+            }
+            generating.set(None);
+        });
+    });
+
+    let source = use_memo(move || {
+        let (id, index, sentence) = current()?;
+        audio.read().get(&(id, index)).filter(|(said, _)| *said == sentence).map(|(_, url)| url.clone())
+    });
+
+    // Keeps the element in step with the pause button and output settings
+    use_effect(move || {
+        let paused = queue.read().paused;
+        let output = settings.read().speech_output.clone();
+        if source().is_none() {
+            return;
+        }
+        apply_speech_output(AUDIO_ID, &output, output.speed);
+        control_audio(if paused { "pause()" } else { "play()" });
+    });
+
+    let Some((_, _, sentence)) = current() else {
+        return match error() {
+            Some(message) => rsx! {
+                p {
+                    class: "px-4 py-1 text-xs text-red-300 cursor-pointer",
+                    onclick: move |_| error.set(None),
+                    "{message}"
+                }
+            },
+            None => rsx! {},
+        };
+    };
+    let paused = queue.read().paused;
+    let speed = settings.read().speech_output.speed;
+    let waiting = queue.read().items.len().saturating_sub(1);
+
+    rsx! {
+        div {
+            class: "border-t border-slate-700/50 bg-slate-900/80 px-4 py-2",
+            div {
+                class: "max-w-3xl mx-auto flex items-center gap-2 text-sm",
+                button {
+                    class: "w-8 h-8 rounded-full bg-emerald-600 hover:bg-emerald-500 text-white",
+                    title: if paused { "Resume" } else { "Pause" },
+                    onclick: move |_| {
+                        let mut q = queue.write();
+                        q.paused = !q.paused;
+                    },
+                    if paused { "▶" } else { "⏸" }
+                }
+                button {
+                    class: "px-2 py-1 rounded bg-slate-700 hover:bg-slate-600 text-slate-200",
+                    title: "Skip to the next message",
+                    onclick: move |_| queue.write().skip(),
+                    "⏭"
+                }
+                button {
+                    class: "px-2 py-1 rounded bg-slate-700 hover:bg-slate-600 text-slate-200",
+                    title: "Stop and clear the queue",
+                    onclick: move |_| {
+                        control_audio("pause()");
+                        queue.write().clear();
+                    },
+                    "⏹"
+                }
+                p {
+                    class: "flex-1 min-w-0 truncate text-slate-300",
+                    title: "{sentence}",
+                    if source().is_none() { span { class: "text-slate-500 mr-1", "Generating…" } }
+                    "{sentence}"
+                }
+                if waiting > 0 {
+                    span { class: "text-xs text-slate-500", "+{waiting} queued" }
+                }
+                select {
+                    class: "px-1 py-1 bg-slate-800 border border-slate-600 rounded text-xs text-slate-200",
+                    title: "Playback speed",
+                    value: "{speed}",
+                    onchange: move |e: FormEvent| {
+                        if let Ok(speed) = e.value().parse() {
+                            settings.write().speech_output.speed = speed;
+                        }
+                    },
+                    for rate in PLAYBACK_SPEEDS {
+                        option { value: "{rate}", selected: rate == speed, "{rate}×" }
+                    }
+                }
+                select {
+                    class: "px-1 py-1 bg-slate-800 border border-slate-600 rounded text-xs text-slate-200",
+                    title: "Voice engine",
+                    value: "{settings.read().speech_engine}",
+                    onchange: move |e: FormEvent| settings.write().speech_engine = e.value(),
+                    option { value: "system", "System" }
+                    option { value: "kokoro", "Kokoro" }
+                    option { value: "vibevoice", "VibeVoice" }
+                }
+            }
+            if let Some(message) = error() {
+                p {
+                    class: "max-w-3xl mx-auto mt-1 text-xs text-red-300 cursor-pointer",
+                    onclick: move |_| error.set(None),
+                    "{message}"
+                }
+            }
+            if let Some(src) = source() {
+                audio {
+                    id: AUDIO_ID,
+                    class: "hidden",
+                    src: "{src}",
+                    autoplay: !paused,
+                    onplay: move |_| {
+                        let output = settings.peek().speech_output.clone();
+                        apply_speech_output(AUDIO_ID, &output, output.speed);
+                    },
+                    onended: move |_| queue.write().advance(),
+                }
+            }
+        }
+    }
+}
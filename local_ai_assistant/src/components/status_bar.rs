@@ -0,0 +1,88 @@
+//! Status Bar Component
+//!
+//! Compact resource monitor shown at the bottom of the main content area.
+//! Also tells the user when the server can't be reached, e.g. when the
+//! installed web app opens from its offline cache.
+
+use dioxus::prelude::*;
+use crate::models::SystemStats;
+use crate::server_functions::get_system_stats;
+
+/// How often the status bar refreshes, in milliseconds
+const REFRESH_INTERVAL_MS: u32 = 5000;
+
+#[component]
+pub fn StatusBar() -> Element {
+    let mut stats: Signal<Option<SystemStats>> = use_signal(|| None);
+    let mut reachable = use_signal(|| true);
+
+    // Poll stats for as long as the status bar is mounted
+    use_effect(move || {
+        spawn(async move {
+            loop {
+                match get_system_stats().await {
+                    Ok(latest) => {
+                        stats.set(Some(latest));
+                        reachable.set(true);
+                    }
+                    Err(_) => reachable.set(false),
+                }
+                sleep_ms(REFRESH_INTERVAL_MS).await;
+            }
+        });
+    });
+
+    if !reachable() {
+        return rsx! {
+            footer {
+                class: "h-7 border-t border-gray-700 flex items-center gap-2 px-4 text-xs text-yellow-400",
+                span { class: "w-2 h-2 rounded-full bg-yellow-400" }
+                "Can't reach the iDoris server. Make sure it is running; retrying..."
+            }
+        };
+    }
+
+    let Some(current) = stats() else {
+        return rsx! {};
+    };
+
+    rsx! {
+        footer {
+            class: "h-7 border-t border-gray-700 flex items-center gap-4 px-4 text-xs text-slate-500",
+            if let Some(mem) = current.process_memory_mb {
+                span { title: "Server process memory", "RAM {format_mb(mem)}" }
+            }
+            if let Some(gpu) = current.gpu_memory_used_mb {
+                span { title: "GPU memory in use", "GPU {format_mb(gpu)}" }
+            }
+            span { title: "Data directory size", "Data {format_mb(current.data_dir_size_mb)}" }
+            if current.queued_generations > 0 {
+                span { title: "Generation requests waiting for the model", "{current.queued_generations} queued" }
+            }
+            if let Some(tps) = current.last_tokens_per_sec {
+                span { class: "ml-auto", title: "Last generation throughput", "{tps:.1} tok/s" }
+            }
+        }
+    }
+}
+
+/// Formats a size in MB using GB above 1024 MB
+pub fn format_mb(size_mb: u64) -> String {
+    if size_mb < 1024 {
+        format!("{} MB", size_mb)
+    } else {
+        format!("{:.1} GB", size_mb as f64 / 1024.0)
+    }
+}
+
+/// Platform-appropriate async sleep
+pub async fn sleep_ms(ms: u32) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        gloo_timers::future::TimeoutFuture::new(ms).await;
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        tokio::time::sleep(std::time::Duration::from_millis(ms as u64)).await;
+    }
+}
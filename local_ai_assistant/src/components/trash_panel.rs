@@ -0,0 +1,173 @@
+//! Trash Panel Component
+//!
+//! Deleted chats, messages, context documents and assets, with restore and
+//! purge actions. Items are purged for good after 30 days.
+
+use dioxus::prelude::*;
+use crate::models::{Session, TrashItem, TrashKind, TRASH_RETENTION_DAYS, error_message};
+use crate::server_functions::{get_trash, restore_trash_item, purge_trash_item, empty_trash, get_sessions};
+
+#[component]
+pub fn TrashPanel(sessions: Signal<Vec<Session>>) -> Element {
+    let mut items: Signal<Vec<TrashItem>> = use_signal(Vec::new);
+    let mut loaded = use_signal(|| false);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+    // Asks again before emptying the trash
+    let mut confirm_empty = use_signal(|| false);
+
+    let refresh = move || {
+        spawn(async move {
+            match get_trash().await {
+                Ok(latest) => items.set(latest),
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+            loaded.set(true);
+        });
+    };
+
+    use_effect(move || refresh());
+
+    // Restored chats reappear in the sidebar
+    let reload_sessions = move || {
+        spawn(async move {
+            if let Ok(latest) = get_sessions().await {
+                sessions.set(latest);
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "flex-1 overflow-y-auto p-6",
+            div {
+                class: "max-w-4xl mx-auto space-y-4",
+
+                div {
+                    class: "flex items-center justify-between",
+                    div {
+                        h2 { class: "text-lg font-semibold text-white", "Trash" }
+                        p {
+                            class: "text-sm text-slate-400",
+                            "Deleted items are kept for {TRASH_RETENTION_DAYS} days, then removed for good."
+                        }
+                    }
+                    if !items().is_empty() {
+                        if confirm_empty() {
+                            div {
+                                class: "flex items-center gap-2",
+                                span { class: "text-sm text-slate-300", "Delete everything for good?" }
+                                button {
+                                    class: "px-3 py-1.5 text-sm bg-red-600 hover:bg-red-700 text-white rounded-lg transition-colors",
+                                    onclick: move |_| {
+                                        confirm_empty.set(false);
+                                        spawn(async move {
+                                            match empty_trash().await {
+                                                Ok(_) => error.set(None),
+                                                Err(e) => error.set(Some(error_message(&e))),
+                                            }
+                                            refresh();
+                                        });
+                                    },
+                                    "Empty trash"
+                                }
+                                button {
+                                    class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                                    onclick: move |_| confirm_empty.set(false),
+                                    "Cancel"
+                                }
+                            }
+                        } else {
+                            button {
+                                class: "px-3 py-1.5 text-sm bg-slate-700 hover:bg-slate-600 rounded-lg transition-colors",
+                                onclick: move |_| confirm_empty.set(true),
+                                "Empty trash"
+                            }
+                        }
+                    }
+                }
+
+                if let Some(message) = error() {
+                    p { class: "text-sm text-red-400", "{message}" }
+                }
+
+                if loaded() && items().is_empty() {
+                    p { class: "text-sm text-slate-500", "The trash is empty." }
+                }
+
+                for item in items() {
+                    TrashRow {
+                        key: "{item.id}",
+                        item,
+                        on_changed: move |result: Result<Option<TrashKind>, String>| {
+                            match result {
+                                Ok(restored) => {
+                                    error.set(None);
+                                    if restored == Some(TrashKind::Session) {
+                                        reload_sessions();
+                                    }
+                                }
+                                Err(e) => error.set(Some(e)),
+                            }
+                            refresh();
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// One deleted item. `on_changed` gets the kind of a restored item, or
+/// `None` after a purge.
+#[component]
+fn TrashRow(item: TrashItem, on_changed: EventHandler<Result<Option<TrashKind>, String>>) -> Element {
+    let deleted = item.deleted_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+    let days_left = item.days_left(chrono::Utc::now());
+    let kind = item.kind;
+
+    rsx! {
+        div {
+            class: "bg-slate-800 rounded-lg p-4 flex items-center gap-3",
+            span { title: "{kind.display_name()}", "{kind.icon()}" }
+            div {
+                class: "flex-1 min-w-0",
+                p { class: "text-sm text-white truncate", "{item.title}" }
+                p {
+                    class: "text-xs text-slate-500 truncate",
+                    "{kind.display_name()}"
+                    if let Some(detail) = &item.detail {
+                        " · {detail}"
+                    }
+                    " · deleted {deleted} · {days_left} days left"
+                }
+            }
+            button {
+                class: "px-3 py-1 text-xs bg-slate-600 hover:bg-slate-500 rounded transition-colors",
+                onclick: {
+                    let id = item.id.clone();
+                    move |_| {
+                        let id = id.clone();
+                        spawn(async move {
+                            on_changed.call(restore_trash_item(kind, id).await.map(|_| Some(kind)).map_err(error_message));
+                        });
+                    }
+                },
+                "Restore"
+            }
+            button {
+                class: "px-2 py-1 text-xs text-slate-400 hover:text-red-400 transition-colors",
+                title: "Delete for good",
+                onclick: {
+                    let id = item.id.clone();
+                    move |_| {
+                        let id = id.clone();
+                        spawn(async move {
+                            on_changed.call(purge_trash_item(kind, id).await.map(|_| None).map_err(error_message));
+                        });
+                    }
+                },
+                "Delete"
+            }
+        }
+    }
+}
@@ -4,19 +4,27 @@
 
 use dioxus::prelude::*;
 
+use crate::models::{AppSettings, Feature};
 use crate::server_functions::generate_tts;
+use super::speech_player::apply_speech_output;
+use super::{ErrorNotice, MissingTools, use_feature_available};
+
+const AUDIO_ID: &str = "tts-panel-audio";
 
 /// TTS Panel component for testing text-to-speech
 #[component]
 pub fn TtsPanel(
+    settings: Signal<AppSettings>,
     on_open_settings: EventHandler<()>,
 ) -> Element {
     let mut input_text = use_signal(|| "Hello, welcome to the Local AI Assistant!".to_string());
     let mut is_generating = use_signal(|| false);
+    let speech_found = use_feature_available(Feature::Speech);
     let mut error_message: Signal<Option<String>> = use_signal(|| None);
     let mut audio_url: Signal<Option<String>> = use_signal(|| None);
     let mut selected_engine = use_signal(|| "system".to_string());
-    let mut speed = use_signal(|| 1.0f32);
+    // Saved, so read-aloud in chat uses the same speed
+    let speed = use_memo(move || settings.read().speech_output.speed);
 
     // Handle TTS generation
     let handle_generate = move |_| {
@@ -34,13 +42,13 @@ pub fn TtsPanel(
         audio_url.set(None);
 
         spawn(async move {
-            match generate_tts(text, engine, spd).await {
+            match generate_tts(text, engine, spd, true).await {
                 Ok(url) => {
                     audio_url.set(Some(url));
                     is_generating.set(false);
                 }
                 Err(e) => {
-                    error_message.set(Some(format!("Generation failed: {:?}", e)));
+                    error_message.set(Some(format!("Generation failed: {}", e)));
                     is_generating.set(false);
                 }
             }
@@ -64,6 +72,8 @@ pub fn TtsPanel(
                 }
             }
 
+            MissingTools { feature: Feature::Speech }
+
             // Engine selection
             div {
                 class: "mb-4",
@@ -124,7 +134,7 @@ pub fn TtsPanel(
                     value: "{speed}",
                     oninput: move |e| {
                         if let Ok(val) = e.value().parse::<f32>() {
-                            speed.set(val);
+                            settings.write().speech_output.speed = val;
                         }
                     }
                 }
@@ -151,12 +161,12 @@ pub fn TtsPanel(
 
             // Generate button
             button {
-                class: if is_generating() {
+                class: if is_generating() || !speech_found {
                     "w-full py-3 px-6 bg-slate-600 text-slate-400 rounded-lg cursor-not-allowed"
                 } else {
                     "w-full py-3 px-6 bg-green-600 hover:bg-green-700 text-white rounded-lg transition-colors font-medium"
                 },
-                disabled: is_generating(),
+                disabled: is_generating() || !speech_found,
                 onclick: handle_generate,
                 if is_generating() {
                     div {
@@ -189,8 +199,8 @@ pub fn TtsPanel(
             // Error message
             if let Some(err) = error_message() {
                 div {
-                    class: "mt-4 p-3 bg-red-900/50 border border-red-700 rounded-lg text-red-300 text-sm",
-                    "{err}"
+                    class: "mt-4",
+                    ErrorNotice { error: err, on_dismiss: move |_| error_message.set(None) }
                 }
             }
 
@@ -203,10 +213,13 @@ pub fn TtsPanel(
                         "Generated Audio"
                     }
                     audio {
+                        id: AUDIO_ID,
                         class: "w-full",
                         controls: true,
                         autoplay: true,
-                        src: "{url}"
+                        src: "{url}",
+                        // Speed is already part of the generated audio
+                        onplay: move |_| apply_speech_output(AUDIO_ID, &settings.peek().speech_output, 1.0),
                     }
                 }
             }
@@ -1,10 +1,12 @@
 use dioxus::prelude::*;
 use crate::server_functions::{
     VideoGenForm, VideoResponse, VideoProviderInfo,
-    get_available_video_providers, estimate_video_cost, generate_video
+    get_available_video_providers, estimate_video_cost, generate_video,
+    get_content_jobs, add_package_video_clip,
 };
-use crate::models::{VideoProvider, VideoModel, VideoQuality};
+use crate::models::{VideoProvider, VideoModel, VideoQuality, VideoPreset, validate_video_config, error_message};
 use js_sys::eval;
+use super::ErrorNotice;
 
 #[derive(Clone, PartialEq, Props)]
 pub struct VideoGenPanelProps {
@@ -51,7 +53,7 @@ pub fn VideoGenPanel(props: VideoGenPanelProps) -> Element {
     };
 
     // Generate    // Generate video
-    let handle_generate = move |_| {
+    let mut handle_generate = move || {
         if is_generating() {
             return;
         }
@@ -61,6 +63,10 @@ pub fn VideoGenPanel(props: VideoGenPanelProps) -> Element {
             error_msg.set(Some("Please enter a video description".to_string()));
             return;
         }
+        if let Err(e) = validate_video_config(&current_form.provider, &current_form.config()) {
+            error_msg.set(Some(e));
+            return;
+        }
 
         is_generating.set(true);
         error_msg.set(None);
@@ -100,8 +106,12 @@ pub fn VideoGenPanel(props: VideoGenPanelProps) -> Element {
 
                 // Error Message
                 if let Some(error) = error_msg() {
-                    div { class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-4",
-                        {error}
+                    div { class: "mb-4",
+                        ErrorNotice {
+                            error: error,
+                            on_retry: move |_| handle_generate(),
+                            on_dismiss: move |_| error_msg.set(None),
+                        }
                     }
                 }
 
@@ -188,6 +198,40 @@ pub fn VideoGenPanel(props: VideoGenPanelProps) -> Element {
                         }
                     }
 
+                    // Format presets
+                    div {
+                        label { class: "block text-sm font-medium text-gray-700 mb-1", "Format" }
+                        div { class: "flex flex-wrap gap-2",
+                            for preset in VideoPreset::all().iter().copied() {
+                                button {
+                                    key: "{preset.display_name()}",
+                                    class: if VideoPreset::matching(form.read().width, form.read().height) == Some(preset) {
+                                        "px-3 py-1.5 text-sm rounded-md border border-blue-500 bg-blue-50 text-blue-700"
+                                    } else {
+                                        "px-3 py-1.5 text-sm rounded-md border border-gray-300 text-gray-700 hover:bg-gray-50"
+                                    },
+                                    onclick: move |_| {
+                                        form.write().apply_preset(preset);
+                                        estimate_cost(());
+                                    },
+                                    "{preset.display_name()}"
+                                }
+                            }
+                        }
+                        {
+                            let current = form.read();
+                            let config = current.config();
+                            match validate_video_config(&current.provider, &config) {
+                                Ok(()) => rsx! {
+                                    p { class: "text-xs text-gray-500 mt-1",
+                                        "{config.width}×{config.height}, {config.duration_seconds}s at {config.fps} fps"
+                                    }
+                                },
+                                Err(e) => rsx! { p { class: "text-xs text-amber-600 mt-1", "{e}" } },
+                            }
+                        }
+                    }
+
                     // Advanced Settings
                     div { class: "space-y-4",
                         button {
@@ -330,7 +374,7 @@ pub fn VideoGenPanel(props: VideoGenPanelProps) -> Element {
                 // Generate Button
                 div { class: "mt-6 flex justify-center",
                     button {
-                        onclick: handle_generate,
+                        onclick: move |_| handle_generate(),
                         disabled: is_generating(),
                         class: "px-8 py-3 bg-blue-600 text-white rounded-lg hover:bg-blue-700 disabled:bg-gray-400 disabled:cursor-not-allowed transition-colors font-medium",
                         if is_generating() {
@@ -391,6 +435,7 @@ pub fn VideoGenPanel(props: VideoGenPanelProps) -> Element {
                                     max_width: "640",
                                     class: "rounded-lg shadow-md bg-black",
                                     crossorigin: "anonymous",
+                                    poster: result.thumbnail_url.clone().unwrap_or_default(),
                                     source { 
                                         src: result.video_url.clone(), 
                                         r#type: "video/mp4" 
@@ -403,6 +448,12 @@ pub fn VideoGenPanel(props: VideoGenPanelProps) -> Element {
                                     "Note: If video doesn't play due to CORS/403, use the download button or open URL directly."
                                 }
 
+                                AddToPackage {
+                                    video_url: result.video_url.clone(),
+                                    thumbnail_url: result.thumbnail_url.clone(),
+                                    label: form.read().prompt.clone(),
+                                }
+
                                 // Download Button  
                                 div { class: "flex gap-2",
                                     a {
@@ -428,4 +479,55 @@ pub fn VideoGenPanel(props: VideoGenPanelProps) -> Element {
             }
         }
     }
-}
\ No newline at end of file
+}
+/// Saves the generated video, with its thumbnail, to a content package's
+/// video timeline
+#[component]
+fn AddToPackage(video_url: String, thumbnail_url: Option<String>, label: String) -> Element {
+    let jobs = use_resource(|| async move { get_content_jobs().await.unwrap_or_default() });
+    let mut selected = use_signal(String::new);
+    let mut is_adding = use_signal(|| false);
+    let mut status = use_signal::<Option<Result<String, String>>>(|| None);
+
+    let jobs = jobs().unwrap_or_default();
+    if jobs.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "flex flex-wrap items-center gap-2",
+            select {
+                class: "px-3 py-2 border border-gray-300 rounded-md text-sm",
+                onchange: move |e| selected.set(e.value()),
+                option { value: "", "Choose a content package..." }
+                for job in jobs.iter() {
+                    option {
+                        key: "{job.progress.job_id}",
+                        value: "{job.progress.job_id}",
+                        selected: selected() == job.progress.job_id.to_string(),
+                        "{job.progress.topic}"
+                    }
+                }
+            }
+            button {
+                class: "inline-flex items-center px-4 py-2 bg-slate-600 text-white rounded-lg hover:bg-slate-700 transition-colors disabled:opacity-50",
+                disabled: selected().is_empty() || is_adding(),
+                onclick: move |_| {
+                    let (video_url, thumbnail_url, label) = (video_url.clone(), thumbnail_url.clone(), label.clone());
+                    is_adding.set(true);
+                    spawn(async move {
+                        let result = add_package_video_clip(selected(), video_url, label, thumbnail_url).await;
+                        status.set(Some(result.map(|_| "Added to the package's video timeline".to_string()).map_err(error_message)));
+                        is_adding.set(false);
+                    });
+                },
+                if is_adding() { "Adding..." } else { "Add to Package" }
+            }
+            match status() {
+                Some(Ok(msg)) => rsx! { span { class: "text-sm text-green-600", "{msg}" } },
+                Some(Err(msg)) => rsx! { span { class: "text-sm text-red-600", "{msg}" } },
+                None => rsx! {},
+            }
+        }
+    }
+}
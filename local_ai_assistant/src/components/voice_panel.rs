@@ -0,0 +1,350 @@
+//! Voice Panel Component
+//!
+//! Hands-free conversation: speech is recorded in the browser, transcribed
+//! locally with Whisper, answered by the chat model and read back sentence
+//! by sentence while the reply is still streaming. Recording is push-to-talk
+//! or starts and stops on its own when speech is detected.
+
+use dioxus::prelude::*;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::{AppSettings, CaptureMode, Feature, PlaybackQueue, VoiceTurn, take_sentences, voice_prompt, error_message};
+use crate::server_functions::{get_response, transcribe_speech};
+use super::{MissingTools, SpeechPlayer, use_feature_available};
+
+/// Microphone level above which hands-free mode starts recording
+const VAD_THRESHOLD: f32 = 0.04;
+/// Silence that ends an utterance in hands-free mode
+const VAD_SILENCE_MS: u32 = 900;
+/// Recordings shorter than this are dropped as noise in hands-free mode
+const VAD_MIN_SPEECH_MS: u32 = 400;
+
+/// What the conversation is waiting on
+#[derive(Clone, Copy, PartialEq)]
+enum VoiceStatus {
+    Idle,
+    Recording,
+    Transcribing,
+    Thinking,
+}
+
+/// Message from the recorder script
+#[derive(Deserialize)]
+struct RecorderEvent {
+    kind: String,
+    #[serde(default)]
+    level: f32,
+    #[serde(default)]
+    audio: Vec<u8>,
+    #[serde(default)]
+    error: String,
+}
+
+/// Sets up `window.idorisVoice` to open the microphone, record utterances
+/// and detect speech, reporting levels and recordings back to the app
+fn recorder_script() -> String {
+    format!(
+        r#"
+        const send = (event) => dioxus.send(event);
+        let stream, context, timer, recorder, chunks = [], startedAt = 0;
+        let handsFree = false, muted = false, lastSpeech = 0, lastLevel = 0;
+        const recording = () => recorder && recorder.state === "recording";
+        const start = () => {{
+            if (!stream || recording()) return;
+            recorder = new MediaRecorder(stream);
+            chunks = [];
+            recorder.ondataavailable = (e) => chunks.push(e.data);
+            recorder.onstop = async () => {{
+                if (handsFree && Date.now() - startedAt < {min_ms}) return;
+                const buffer = await new Blob(chunks).arrayBuffer();
+                send({{ kind: "audio", audio: Array.from(new Uint8Array(buffer)) }});
+            }};
+            startedAt = Date.now();
+            recorder.start();
+            send({{ kind: "recording" }});
+        }};
+        const stop = () => {{ if (recording()) recorder.stop(); }};
+        const open = async () => {{
+            if (stream) return;
+            try {{
+                stream = await navigator.mediaDevices.getUserMedia({{ audio: true }});
+            }} catch (e) {{
+                send({{ kind: "error", error: "Microphone unavailable: " + e.message }});
+                return;
+            }}
+            context = new AudioContext();
+            const analyser = context.createAnalyser();
+            analyser.fftSize = 1024;
+            context.createMediaStreamSource(stream).connect(analyser);
+            const samples = new Uint8Array(analyser.fftSize);
+            timer = setInterval(() => {{
+                analyser.getByteTimeDomainData(samples);
+                let sum = 0;
+                for (const s of samples) {{ const x = (s - 128) / 128; sum += x * x; }}
+                const level = Math.sqrt(sum / samples.length);
+                const now = Date.now();
+                if (now - lastLevel > 100) {{ lastLevel = now; send({{ kind: "level", level }}); }}
+                if (!handsFree || muted) return;
+                if (level > {threshold}) {{
+                    lastSpeech = now;
+                    start();
+                }} else if (recording() && now - lastSpeech > {silence_ms}) {{
+                    stop();
+                }}
+            }}, 30);
+        }};
+        window.idorisVoice = {{
+            open,
+            start: async () => {{ await open(); start(); }},
+            stop,
+            setHandsFree: async (on) => {{ handsFree = on; if (on) await open(); else stop(); }},
+            setMuted: (on) => {{ muted = on; if (on && handsFree) stop(); }},
+            close: () => {{
+                handsFree = false;
+                stop();
+                clearInterval(timer);
+                stream?.getTracks().forEach((track) => track.stop());
+                context?.close();
+                stream = undefined;
+            }},
+        }};
+        open();
+        await new Promise(() => {{}});
+        "#,
+        min_ms = VAD_MIN_SPEECH_MS,
+        threshold = VAD_THRESHOLD,
+        silence_ms = VAD_SILENCE_MS,
+    )
+}
+
+/// Calls a method of the recorder script
+fn recorder(call: &str) {
+    let _ = document::eval(&format!("window.idorisVoice?.{}", call));
+}
+
+#[component]
+pub fn VoicePanel(settings: Signal<AppSettings>) -> Element {
+    let mut turns: Signal<Vec<VoiceTurn>> = use_signal(Vec::new);
+    let mut status = use_signal(|| VoiceStatus::Idle);
+    let mut mode = use_signal(CaptureMode::default);
+    let mut level = use_signal(|| 0.0f32);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+    let mut playback: Signal<PlaybackQueue> = use_signal(PlaybackQueue::default);
+    // The model keeps its own chat per conversation, apart from chat sessions
+    let mut conversation: Signal<Uuid> = use_signal(Uuid::new_v4);
+    let mut cancel = use_signal(|| false);
+    let transcription_found = use_feature_available(Feature::Transcription);
+
+    // Answers a recording: transcribe, stream the reply and queue each
+    // sentence for speech as soon as it is complete
+    let mut respond = move |audio: Vec<u8>| {
+        spawn(async move {
+            status.set(VoiceStatus::Transcribing);
+            let text = match transcribe_speech(audio).await {
+                Ok(text) if !text.trim().is_empty() => text,
+                Ok(_) => {
+                    status.set(VoiceStatus::Idle);
+                    return;
+                }
+                Err(e) => {
+                    error.set(Some(error_message(&e)));
+                    status.set(VoiceStatus::Idle);
+                    return;
+                }
+            };
+            let first_turn = turns.read().is_empty();
+            turns.write().push(VoiceTurn::user(text.clone()));
+            let reply = VoiceTurn::assistant();
+            let reply_id = reply.id;
+            turns.write().push(reply);
+            status.set(VoiceStatus::Thinking);
+            cancel.set(false);
+
+            let mut pending = String::new();
+            match get_response(voice_prompt(&text, first_turn), conversation().to_string()).await {
+                Ok(mut stream) => {
+                    while let Some(Ok(chunk)) = stream.next().await {
+                        if cancel() {
+                            break;
+                        }
+                        if let Some(turn) = turns.write().iter_mut().find(|t| t.id == reply_id) {
+                            turn.text.push_str(&chunk);
+                        }
+                        pending.push_str(&chunk);
+                        playback.write().push_sentences(reply_id, take_sentences(&mut pending));
+                    }
+                    if !cancel() {
+                        pending.push('\n');
+                        playback.write().push_sentences(reply_id, take_sentences(&mut pending));
+                    }
+                }
+                Err(e) => error.set(Some(format!("The model could not answer: {}", error_message(&e)))),
+            }
+            status.set(VoiceStatus::Idle);
+        });
+    };
+
+    // Listens to the recorder script for as long as the panel is open
+    use_effect(move || {
+        spawn(async move {
+            let mut events = document::eval(&recorder_script());
+            while let Ok(event) = events.recv::<RecorderEvent>().await {
+                match event.kind.as_str() {
+                    "level" => level.set(event.level),
+                    "recording" => {
+                        error.set(None);
+                        status.set(VoiceStatus::Recording);
+                    }
+                    "audio" => respond(event.audio),
+                    "error" => {
+                        error.set(Some(event.error));
+                        status.set(VoiceStatus::Idle);
+                    }
+                    _ => {}
+                }
+            }
+        });
+    });
+
+    // Releases the microphone when the panel closes
+    use_drop(move || recorder("close()"));
+
+    // Hands-free mode stops listening while the assistant is busy or
+    // speaking, so it does not answer itself
+    use_effect(move || {
+        let busy = matches!(status(), VoiceStatus::Transcribing | VoiceStatus::Thinking);
+        let speaking = !playback.read().items.is_empty();
+        recorder(&format!("setMuted({})", busy || speaking));
+    });
+
+    use_effect(move || {
+        recorder(&format!("setHandsFree({})", mode() == CaptureMode::VoiceActivity));
+    });
+
+    let stop = move |_| {
+        cancel.set(true);
+        playback.write().clear();
+        recorder("stop()");
+    };
+
+    let new_conversation = move |_| {
+        cancel.set(true);
+        playback.write().clear();
+        turns.write().clear();
+        conversation.set(Uuid::new_v4());
+    };
+
+    let status_text = match status() {
+        VoiceStatus::Idle if !playback.read().items.is_empty() => "Speaking…",
+        VoiceStatus::Idle if mode() == CaptureMode::VoiceActivity => "Listening — just start talking",
+        VoiceStatus::Idle => "Hold the button and speak",
+        VoiceStatus::Recording => "Recording…",
+        VoiceStatus::Transcribing => "Transcribing…",
+        VoiceStatus::Thinking => "Thinking…",
+    };
+    let meter = (level() * 400.0).min(100.0);
+    let speaking_id = playback.read().items.first().map(|item| item.message_id);
+
+    rsx! {
+        div {
+            class: "flex-1 flex flex-col h-full bg-gradient-to-b from-slate-900 via-slate-800 to-slate-900",
+
+            // Transcript
+            div {
+                class: "flex-1 overflow-y-auto",
+                div {
+                    class: "max-w-3xl mx-auto px-4 py-6 space-y-3",
+                    if turns.read().is_empty() {
+                        p {
+                            class: "text-center text-slate-500 mt-20",
+                            "Talk to the assistant. Your words are transcribed locally and the answer is read back to you."
+                        }
+                    }
+                    for turn in turns() {
+                        div {
+                            key: "{turn.id}",
+                            class: if turn.from_user { "flex justify-end" } else { "flex justify-start" },
+                            div {
+                                class: "max-w-[85%] px-4 py-2 rounded-2xl whitespace-pre-wrap",
+                                class: if turn.from_user {
+                                    "bg-gradient-to-br from-blue-500 to-indigo-600 text-white rounded-tr-sm"
+                                } else if speaking_id == Some(turn.id) {
+                                    "bg-slate-700/50 text-slate-100 rounded-tl-sm ring-1 ring-emerald-500/60"
+                                } else {
+                                    "bg-slate-700/50 text-slate-100 rounded-tl-sm"
+                                },
+                                if turn.text.is_empty() { "…" } else { "{turn.text}" }
+                            }
+                        }
+                    }
+                }
+            }
+
+            SpeechPlayer { queue: playback, settings: settings }
+
+            // Controls
+            div {
+                class: "border-t border-slate-700/50 px-4 py-4",
+                div {
+                    class: "max-w-3xl mx-auto flex flex-col items-center gap-3",
+                    MissingTools { feature: Feature::Transcription }
+                    if let Some(message) = error() {
+                        p { class: "text-sm text-red-300", "{message}" }
+                    }
+                    p { class: "text-sm text-slate-400", "{status_text}" }
+                    div {
+                        class: "w-48 h-1.5 rounded-full bg-slate-700 overflow-hidden",
+                        div { class: "h-full bg-emerald-500 transition-all", style: "width: {meter}%" }
+                    }
+                    div {
+                        class: "flex items-center gap-3",
+                        if mode() == CaptureMode::PushToTalk {
+                            button {
+                                class: if status() == VoiceStatus::Recording {
+                                    "w-16 h-16 rounded-full bg-red-600 text-white text-2xl shadow-lg"
+                                } else {
+                                    "w-16 h-16 rounded-full bg-emerald-600 hover:bg-emerald-500 text-white text-2xl shadow-lg disabled:opacity-50"
+                                },
+                                title: "Hold to talk",
+                                disabled: !transcription_found || matches!(status(), VoiceStatus::Transcribing | VoiceStatus::Thinking),
+                                onmousedown: move |_| {
+                                    playback.write().clear();
+                                    recorder("start()");
+                                },
+                                onmouseup: move |_| recorder("stop()"),
+                                onmouseleave: move |_| recorder("stop()"),
+                                ontouchstart: move |_| {
+                                    playback.write().clear();
+                                    recorder("start()");
+                                },
+                                ontouchend: move |_| recorder("stop()"),
+                                "🎙"
+                            }
+                        }
+                        button {
+                            class: "px-3 py-2 rounded-lg bg-slate-700 hover:bg-slate-600 text-sm text-slate-200",
+                            onclick: stop,
+                            "Stop"
+                        }
+                        button {
+                            class: "px-3 py-2 rounded-lg bg-slate-700 hover:bg-slate-600 text-sm text-slate-200",
+                            onclick: new_conversation,
+                            "New conversation"
+                        }
+                    }
+                    div {
+                        class: "flex gap-1 text-xs",
+                        for option in [CaptureMode::PushToTalk, CaptureMode::VoiceActivity] {
+                            button {
+                                class: if mode() == option { "px-3 py-1 rounded-full bg-emerald-600 text-white" } else { "px-3 py-1 rounded-full bg-slate-700 text-slate-300 hover:bg-slate-600" },
+                                onclick: move |_| mode.set(option),
+                                "{option.display_name()}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
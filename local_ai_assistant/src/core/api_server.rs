@@ -0,0 +1,351 @@
+//! Local HTTP API
+//!
+//! Opt-in REST/SSE endpoints served from the same process as the UI, so
+//! editors and scripts can use the local model and knowledge base:
+//!
+//! - `GET  /v1/models`           - the loaded chat model
+//! - `POST /v1/chat/completions` - OpenAI-compatible chat, `stream: true` for SSE
+//! - `POST /v1/rag/search`       - raw top-k chunks from the vector store
+//! - `POST /v1/capture`          - file text or a URL into the knowledge base
+//!
+//! Enable with `IDORIS_API_ENABLED=true`. Every request must send
+//! `Authorization: Bearer <token>`; the token comes from `IDORIS_API_TOKEN`
+//! or is generated once and stored in `./data/api_token`.
+
+use std::convert::Infallible;
+use std::path::PathBuf;
+use axum::extract::Request;
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::models::RetrievedChunk;
+
+/// Default generation length when the client does not send `max_tokens`
+const DEFAULT_MAX_TOKENS: u32 = 600;
+/// Default number of chunks for RAG search
+const DEFAULT_TOP_K: usize = 5;
+/// Largest accepted `top_k`
+const MAX_TOP_K: usize = 50;
+
+/// Returns the API router if the API is enabled
+pub fn router() -> Option<Router> {
+    let enabled = std::env::var("IDORIS_API_ENABLED")
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+
+    let token = match load_or_create_token() {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::error!("HTTP API disabled: could not load API token: {}", e);
+            return None;
+        }
+    };
+    tracing::info!("HTTP API enabled at /v1 (token in {:?} or IDORIS_API_TOKEN)", token_path());
+
+    Some(
+        Router::new()
+            .route("/v1/models", get(list_models))
+            .route("/v1/chat/completions", post(chat_completions))
+            .route("/v1/rag/search", post(rag_search))
+            .route("/v1/capture", post(capture))
+            .layer(middleware::from_fn(move |req: Request, next: Next| {
+                let token = token.clone();
+                async move { require_token(&token, req, next).await }
+            })),
+    )
+}
+
+fn token_path() -> PathBuf {
+    crate::storage::database::get_data_dir().join("api_token")
+}
+
+/// Reads the token from the environment or the token file, generating one on first use
+fn load_or_create_token() -> Result<String, String> {
+    if let Ok(token) = std::env::var("IDORIS_API_TOKEN") {
+        if !token.trim().is_empty() {
+            return Ok(token.trim().to_string());
+        }
+    }
+
+    let path = token_path();
+    if let Ok(token) = std::fs::read_to_string(&path) {
+        if !token.trim().is_empty() {
+            return Ok(token.trim().to_string());
+        }
+    }
+
+    let token = format!("idoris-{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, &token).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    }
+    tracing::info!("Generated new API token in {:?}", path);
+    Ok(token)
+}
+
+async fn require_token(token: &str, req: Request, next: Next) -> Response {
+    let provided = req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+
+    if !tokens_match(provided.trim(), token) {
+        return api_error(StatusCode::UNAUTHORIZED, "Invalid or missing API token");
+    }
+    next.run(req).await
+}
+
+/// Constant-time token comparison
+pub(crate) fn tokens_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// OpenAI-style error body
+fn api_error(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({
+        "error": { "message": message, "type": "invalid_request_error" }
+    }))).into_response()
+}
+
+// ============================================================================
+// Models
+// ============================================================================
+
+async fn list_models() -> Json<serde_json::Value> {
+    let model = super::llm::get_current_model_id_sync();
+    Json(serde_json::json!({
+        "object": "list",
+        "data": [{ "id": model, "object": "model", "owned_by": "local" }]
+    }))
+}
+
+// ============================================================================
+// Chat completions
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ApiMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionRequest {
+    messages: Vec<ApiMessage>,
+    #[serde(default)]
+    stream: bool,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+    /// Extension: prepend context from the knowledge base to the last user message
+    #[serde(default)]
+    rag: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct RagSearchResponse {
+    results: Vec<RetrievedChunk>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RagSearchRequest {
+    query: String,
+    top_k: Option<usize>,
+}
+
+async fn chat_completions(Json(request): Json<ChatCompletionRequest>) -> Response {
+    if !super::llm::is_initialized() {
+        return api_error(StatusCode::SERVICE_UNAVAILABLE, "Model not initialized");
+    }
+
+    let (system_prompt, mut prompt) = match build_prompt(&request.messages) {
+        Some(parts) => parts,
+        None => return api_error(StatusCode::BAD_REQUEST, "messages must end with a user message"),
+    };
+
+    if request.rag {
+        let last_user = request.messages.iter().rev().find(|m| m.role == "user").map(|m| m.content.clone()).unwrap_or_default();
+        match super::vector_store::query(&last_user).await {
+            Ok(documents) if !documents.is_empty() => {
+                let context = documents.iter()
+                    .map(|d| format!("Title: {}\n{}", d.title, d.body))
+                    .collect::<Vec<_>>()
+                    .join("\n---\n");
+                prompt = format!("Use the following context to answer.\n\n{}\n\n{}", context, prompt);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("API RAG lookup failed: {}", e),
+        }
+    }
+
+    let rx = match super::llm::stream_standalone(
+        prompt,
+        system_prompt,
+        request.temperature.unwrap_or(0.7),
+        request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+    ) {
+        Ok(rx) => rx,
+        Err(e) => return api_error(StatusCode::SERVICE_UNAVAILABLE, &e),
+    };
+
+    let id = format!("chatcmpl-{}", uuid::Uuid::new_v4().simple());
+    let model = super::llm::get_current_model_id_sync();
+    let created = chrono::Utc::now().timestamp();
+
+    if request.stream {
+        let chunk = {
+            let (id, model) = (id.clone(), model.clone());
+            move |delta: serde_json::Value, finish_reason: Option<&str>| {
+                serde_json::json!({
+                    "id": id,
+                    "object": "chat.completion.chunk",
+                    "created": created,
+                    "model": model,
+                    "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }]
+                }).to_string()
+            }
+        };
+        let first = chunk(serde_json::json!({ "role": "assistant" }), None);
+        let last = chunk(serde_json::json!({}), Some("stop"));
+        let tokens = rx.map(move |token| chunk(serde_json::json!({ "content": token }), None));
+
+        let events = futures::stream::once(async move { first })
+            .chain(tokens)
+            .chain(futures::stream::iter([last, "[DONE]".to_string()]))
+            .map(|data| Ok::<_, Infallible>(Event::default().data(data)));
+        return Sse::new(events).into_response();
+    }
+
+    let content: String = rx.collect::<Vec<_>>().await.concat();
+    Json(serde_json::json!({
+        "id": id,
+        "object": "chat.completion",
+        "created": created,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop"
+        }]
+    })).into_response()
+}
+
+/// Splits OpenAI-style messages into a system prompt and a single prompt.
+///
+/// The model runs a fresh chat per request, so earlier turns are folded into
+/// a transcript ahead of the final user message. Returns None if the
+/// conversation does not end with a user message.
+fn build_prompt(messages: &[ApiMessage]) -> Option<(Option<String>, String)> {
+    let (last, history) = messages.split_last()?;
+    if last.role != "user" {
+        return None;
+    }
+
+    let system: Vec<&str> = messages.iter()
+        .filter(|m| m.role == "system")
+        .map(|m| m.content.as_str())
+        .collect();
+    let system_prompt = (!system.is_empty()).then(|| system.join("\n\n"));
+
+    let transcript: Vec<String> = history.iter()
+        .filter(|m| m.role == "user" || m.role == "assistant")
+        .map(|m| format!("{}: {}", if m.role == "user" { "User" } else { "Assistant" }, m.content))
+        .collect();
+
+    let prompt = if transcript.is_empty() {
+        last.content.clone()
+    } else {
+        format!("Conversation so far:\n{}\n\nUser: {}", transcript.join("\n"), last.content)
+    };
+    Some((system_prompt, prompt))
+}
+
+// ============================================================================
+// RAG search
+// ============================================================================
+
+async fn rag_search(Json(request): Json<RagSearchRequest>) -> Response {
+    if request.query.trim().is_empty() {
+        return api_error(StatusCode::BAD_REQUEST, "query must not be empty");
+    }
+
+    let top_k = request.top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, MAX_TOP_K);
+    match super::vector_store::search_raw(&request.query, top_k).await {
+        Ok(results) => Json(RagSearchResponse { results }).into_response(),
+        Err(e) => api_error(StatusCode::SERVICE_UNAVAILABLE, &e),
+    }
+}
+
+// ============================================================================
+// Quick capture
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct CaptureRequest {
+    /// Text or a URL to fetch
+    content: String,
+    collection: Option<String>,
+}
+
+async fn capture(Json(request): Json<CaptureRequest>) -> Response {
+    match super::quick_capture::capture(&request.content, request.collection.as_deref()).await {
+        Ok(captured) => Json(captured).into_response(),
+        Err(e) => api_error(StatusCode::BAD_REQUEST, &e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(role: &str, content: &str) -> ApiMessage {
+        ApiMessage { role: role.to_string(), content: content.to_string() }
+    }
+
+    #[test]
+    fn test_build_prompt_single_turn() {
+        let (system, prompt) = build_prompt(&[msg("system", "Be brief"), msg("user", "Hi")]).unwrap();
+        assert_eq!(system.as_deref(), Some("Be brief"));
+        assert_eq!(prompt, "Hi");
+    }
+
+    #[test]
+    fn test_build_prompt_folds_history() {
+        let (system, prompt) = build_prompt(&[
+            msg("user", "What is Rust?"),
+            msg("assistant", "A language."),
+            msg("user", "Who made it?"),
+        ]).unwrap();
+        assert!(system.is_none());
+        assert!(prompt.contains("User: What is Rust?\nAssistant: A language."));
+        assert!(prompt.ends_with("User: Who made it?"));
+    }
+
+    #[test]
+    fn test_build_prompt_requires_trailing_user_message() {
+        assert!(build_prompt(&[]).is_none());
+        assert!(build_prompt(&[msg("user", "Hi"), msg("assistant", "Hello")]).is_none());
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("abc", "abc"));
+        assert!(!tokens_match("abd", "abc"));
+        assert!(!tokens_match("", "abc"));
+    }
+}
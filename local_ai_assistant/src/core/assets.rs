@@ -0,0 +1,248 @@
+//! Asset Library
+//!
+//! One index of all generated media, kept in SQLite. Features register
+//! files as they create them; media that had no folder of its own (TTS
+//! audio, chat tool output, generated videos) is saved to the `library`
+//! asset folder. Files created before the library existed are imported the
+//! first time it is opened, and entries whose file is gone are dropped.
+//!
+//! Registering never fails the feature that created the file: errors are
+//! logged and the media is simply missing from the library.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use uuid::Uuid;
+
+use crate::models::{Asset, AssetFilter, AssetKind, AssetOrigin, AttachmentMode, ChatAttachment, asset_markdown};
+use crate::storage::database;
+
+/// Width of image previews in the library
+const PREVIEW_WIDTH: u32 = 320;
+
+/// Largest file sent to the browser for playback or embedding
+const MAX_INLINE_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Profiles whose existing files have been imported in this run
+static IMPORTED: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Folder for media that no feature keeps a folder for
+pub fn library_dir() -> PathBuf {
+    super::profile::asset_dir("library")
+}
+
+/// Adds a file to the library
+pub async fn register(path: &Path, prompt: &str, origin: AssetOrigin, origin_id: Option<String>) {
+    if let Err(e) = try_register(path, prompt, origin, origin_id).await {
+        tracing::warn!("Could not add {:?} to the asset library: {}", path, e);
+    }
+}
+
+async fn try_register(path: &Path, prompt: &str, origin: AssetOrigin, origin_id: Option<String>) -> Result<bool, String> {
+    let path = path.canonicalize().map_err(|e| e.to_string())?;
+    let path_text = path.display().to_string();
+    let kind = AssetKind::from_path(&path_text).ok_or("not a media file")?;
+    let mut asset = Asset::new(kind, path_text, prompt.trim().to_string(), origin);
+    asset.origin_id = origin_id;
+    asset.size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    database::save_asset(&asset).await.map_err(|e| e.to_string())
+}
+
+/// Saves generated media that has no other home to the library folder and
+/// registers it
+pub async fn save(data: &[u8], extension: &str, prompt: &str, origin: AssetOrigin) -> Result<PathBuf, String> {
+    let path = library_dir().join(format!("{}-{}.{}", origin.id(), Uuid::new_v4(), extension));
+    std::fs::write(&path, data).map_err(|e| e.to_string())?;
+    register(&path, prompt, origin, None).await;
+    Ok(path)
+}
+
+/// Downloads a generated video with its thumbnail into the library in the
+/// background, since provider links expire
+pub fn save_video_in_background(url: String, thumbnail_url: Option<String>, prompt: String) {
+    tokio::spawn(async move {
+        match super::video_assembly::save_clip(&url, &library_dir()).await {
+            Ok(path) => {
+                super::video_assembly::save_thumbnail(&path, thumbnail_url.as_deref()).await;
+                register(&path, &prompt, AssetOrigin::VideoPanel, None).await;
+            }
+            Err(e) => tracing::warn!("Could not save the generated video to the library: {}", e),
+        }
+    });
+}
+
+/// Library entries matching the filter, newest first
+pub async fn list(filter: &AssetFilter) -> Result<Vec<Asset>, String> {
+    let profile = super::profile::active_profile_id();
+    let first_open = IMPORTED.lock().map_err(|_| "Failed to lock library")?.insert(profile);
+    if first_open {
+        import_existing().await;
+    }
+
+    let (present, missing): (Vec<Asset>, Vec<Asset>) = database::get_assets().await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .partition(|a| Path::new(&a.path).exists());
+    if !missing.is_empty() {
+        let ids: Vec<Uuid> = missing.iter().map(|a| a.id).collect();
+        database::delete_assets(&ids).await.map_err(|e| e.to_string())?;
+    }
+    Ok(present.into_iter().filter(|a| filter.matches(a)).collect())
+}
+
+/// Registers media already in the asset folders. Files written by content
+/// packages keep their package as origin.
+pub async fn import_existing() -> usize {
+    let mut count = 0;
+    let folders = [
+        (super::profile::asset_dir("images"), AssetOrigin::ImagePanel),
+        (super::profile::asset_dir("content_packages"), AssetOrigin::ContentPackage),
+        (library_dir(), AssetOrigin::Imported),
+    ];
+    for (root, origin) in folders {
+        for file in media_files(&root) {
+            // Exported sites are copies of the package's own files
+            if file.components().any(|c| c.as_os_str() == "site") {
+                continue;
+            }
+            let origin_id = (origin == AssetOrigin::ContentPackage)
+                .then(|| file.strip_prefix(&root).ok()?.components().next()?.as_os_str().to_str().map(str::to_string))
+                .flatten();
+            if matches!(try_register(&file, "", origin, origin_id).await, Ok(true)) {
+                count += 1;
+            }
+        }
+    }
+    if count > 0 {
+        tracing::info!("Imported {} existing files into the asset library", count);
+    }
+    count
+}
+
+/// Media files under a folder, except video thumbnails
+fn media_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.is_dir() {
+                dirs.push(path);
+            } else if AssetKind::from_path(&path.display().to_string()).is_some() && !is_video_thumbnail(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+/// Thumbnails are saved next to their video with the same name
+fn is_video_thumbnail(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "jpg") && path.with_extension("mp4").exists()
+}
+
+async fn get(id: Uuid) -> Result<Asset, String> {
+    database::get_assets().await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|a| a.id == id)
+        .ok_or_else(|| "Asset not found".to_string())
+}
+
+/// Small preview as a data URL: a scaled-down image, or a video's thumbnail
+pub async fn preview(id: Uuid) -> Result<Option<String>, String> {
+    let asset = get(id).await?;
+    let source = match asset.kind {
+        AssetKind::Image => PathBuf::from(&asset.path),
+        AssetKind::Video => PathBuf::from(&asset.path).with_extension("jpg"),
+        AssetKind::Audio => return Ok(None),
+    };
+    if !source.exists() {
+        return Ok(None);
+    }
+    tokio::task::spawn_blocking(move || {
+        let image = image::open(&source).map_err(|e| e.to_string())?;
+        let image = if image.width() > PREVIEW_WIDTH { image.resize(PREVIEW_WIDTH, PREVIEW_WIDTH * 4, image::imageops::FilterType::Triangle) } else { image };
+        let mut jpeg = Vec::new();
+        image.to_rgb8()
+            .write_to(&mut std::io::Cursor::new(&mut jpeg), image::ImageFormat::Jpeg)
+            .map_err(|e| e.to_string())?;
+        Ok(Some(data_url("image/jpeg", &jpeg)))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// The whole file as a data URL, for playback or embedding
+pub async fn media(id: Uuid) -> Result<String, String> {
+    let asset = get(id).await?;
+    if asset.size_bytes > MAX_INLINE_BYTES {
+        return Err(format!("{} is too large to open here; find it at {}", asset.file_name(), asset.path));
+    }
+    let data = std::fs::read(&asset.path).map_err(|e| format!("Failed to read {}: {}", asset.path, e))?;
+    Ok(data_url(mime_type(&asset.path), &data))
+}
+
+/// Markdown to insert the asset into an article
+pub async fn article_snippet(id: Uuid) -> Result<String, String> {
+    let asset = get(id).await?;
+    let data_url = match asset.kind {
+        AssetKind::Image => Some(media(id).await?),
+        _ => None,
+    };
+    Ok(asset_markdown(&asset, data_url.as_deref()))
+}
+
+/// The asset as a chat attachment. The chat model reads text, so it gets
+/// the asset's description rather than the media.
+pub async fn attachment(id: Uuid) -> Result<ChatAttachment, String> {
+    let asset = get(id).await?;
+    Ok(ChatAttachment {
+        id: Uuid::new_v4(),
+        name: asset.file_name().to_string(),
+        size_bytes: asset.size_bytes,
+        mode: AttachmentMode::Inline,
+        content: asset.describe(),
+        ingested: false,
+    })
+}
+
+pub async fn set_tags(id: Uuid, tags: &[String]) -> Result<(), String> {
+    database::set_asset_tags(id, tags).await.map_err(|e| e.to_string())
+}
+
+/// Moves an asset to the trash; the file is deleted when it is purged.
+/// Content package files stay, as the package still uses them.
+pub async fn delete(id: Uuid) -> Result<(), String> {
+    let asset = get(id).await?;
+    if asset.origin == AssetOrigin::ContentPackage {
+        return Err("This file belongs to a content package; remove it there".to_string());
+    }
+    database::trash_asset(id).await.map_err(|e| e.to_string())
+}
+
+fn mime_type(path: &str) -> &'static str {
+    let extension = path.rsplit_once('.').map(|(_, e)| e.to_lowercase()).unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        "aiff" => "audio/aiff",
+        "m4a" => "audio/mp4",
+        "ogg" => "audio/ogg",
+        "flac" => "audio/flac",
+        "mov" => "video/quicktime",
+        "webm" => "video/webm",
+        "mkv" => "video/x-matroska",
+        _ => "video/mp4",
+    }
+}
+
+fn data_url(mime: &str, data: &[u8]) -> String {
+    use base64::Engine;
+    format!("data:{};base64,{}", mime, base64::engine::general_purpose::STANDARD.encode(data))
+}
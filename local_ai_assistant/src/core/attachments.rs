@@ -0,0 +1,213 @@
+//! Chat Attachments
+//!
+//! Turns a file dropped onto the chat into context for the next message.
+//! Text is extracted with the document loader, or read as UTF-8 for plain
+//! text formats it doesn't know. Short texts are inlined; longer ones are
+//! summarized chunk by chunk and the partial summaries combined. The
+//! extracted text is kept in the `attachments` data folder so the file can
+//! later be added to the knowledge base, encrypted like the chat history
+//! when history encryption is on. Files dropped onto the Context tab
+//! go to the knowledge base directly with [`upload`]. Images are refused,
+//! as the chat model reads text only.
+
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::models::{
+    split_into_chunks, AttachmentMode, CapturedDocument, ChatAttachment, MAX_ATTACHMENT_BYTES, MAX_INLINE_CHARS,
+};
+use super::{encryption, llm, quick_capture, vector_store};
+
+/// Text summarized in one model call
+const SUMMARY_CHUNK_CHARS: usize = 6_000;
+
+/// Chunks summarized per file; the rest of a very long file is left out
+const MAX_SUMMARY_CHUNKS: usize = 24;
+
+/// Token budget for a partial summary and for the combined one
+const CHUNK_SUMMARY_TOKENS: u32 = 300;
+const FINAL_SUMMARY_TOKENS: u32 = 800;
+
+fn attachments_dir() -> PathBuf {
+    crate::storage::database::get_data_dir().join("attachments")
+}
+
+fn text_path(id: Uuid) -> PathBuf {
+    attachments_dir().join(format!("{}.txt", id))
+}
+
+/// Extracts the text of an uploaded file and inlines or summarizes it
+pub async fn prepare(name: &str, data: Vec<u8>) -> Result<ChatAttachment, String> {
+    let size_bytes = data.len() as u64;
+    let (name, text) = read_upload(name, data).await?;
+    let id = Uuid::new_v4();
+    std::fs::write(text_path(id), encryption::seal(&text)?).map_err(|e| e.to_string())?;
+
+    let (mode, content) = if text.chars().count() <= MAX_INLINE_CHARS {
+        (AttachmentMode::Inline, text)
+    } else {
+        (AttachmentMode::Summary, summarize(&name, &text).await?)
+    };
+    tracing::info!("Attached {} ({} bytes, {:?})", name, size_bytes, mode);
+
+    Ok(ChatAttachment { id, name, size_bytes, mode, content, ingested: false })
+}
+
+/// Adds an uploaded file's text straight to the knowledge base
+pub async fn upload(name: &str, data: Vec<u8>) -> Result<CapturedDocument, String> {
+    let (name, text) = read_upload(name, data).await?;
+    add_to_knowledge_base(&name, &text).await
+}
+
+/// Checks an uploaded file and extracts its text, returning the cleaned
+/// file name with it
+async fn read_upload(name: &str, data: Vec<u8>) -> Result<(String, String), String> {
+    if data.is_empty() {
+        return Err("The file is empty".to_string());
+    }
+    if data.len() > MAX_ATTACHMENT_BYTES {
+        return Err(format!("Files up to {} MB can be attached", MAX_ATTACHMENT_BYTES / (1024 * 1024)));
+    }
+    // The chat model reads text only; text that happens to start like an
+    // image header is still valid UTF-8
+    if std::str::from_utf8(&data).is_err() && image::guess_format(&data).is_ok() {
+        return Err("Images can't be read by the chat model; paste them into Image Generation to use them as a source image".to_string());
+    }
+
+    let name = Path::new(name)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .filter(|n| !n.starts_with('.'))
+        .ok_or("Invalid file name")?
+        .to_string();
+    let dir = attachments_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let file_path = dir.join(format!("{}-{}", Uuid::new_v4(), name));
+    std::fs::write(&file_path, &data).map_err(|e| e.to_string())?;
+
+    let text = extract_text(&file_path, data).await;
+    let _ = std::fs::remove_file(&file_path);
+    let text = text?;
+    if text.trim().is_empty() {
+        return Err(format!("No text found in {}", name));
+    }
+    Ok((name, text))
+}
+
+/// Text via the document loader (PDF, HTML, ...), falling back to UTF-8
+async fn extract_text(path: &Path, data: Vec<u8>) -> Result<String, String> {
+    if let Ok(document) = vector_store::parse_document_file(path.to_path_buf()).await {
+        return Ok(document.body().to_string());
+    }
+    match String::from_utf8(data) {
+        Ok(text) if !text.contains('\0') => Ok(text),
+        _ => Err("Only text files and documents can be attached".to_string()),
+    }
+}
+
+/// Summarizes long text part by part, then combines the partial summaries
+pub(crate) async fn summarize(name: &str, text: &str) -> Result<String, String> {
+    let mut chunks = split_into_chunks(text, SUMMARY_CHUNK_CHARS);
+    let truncated = chunks.len() > MAX_SUMMARY_CHUNKS;
+    chunks.truncate(MAX_SUMMARY_CHUNKS);
+
+    let total = chunks.len();
+    let mut partials = Vec::with_capacity(total);
+    for (i, chunk) in chunks.iter().enumerate() {
+        tracing::debug!("Summarizing {} part {}/{}", name, i + 1, total);
+        let prompt = format!(
+            "Summarize part {} of {} of the file \"{}\". Keep names, numbers and key facts. \
+Reply with the summary only.\n\n{}",
+            i + 1, total, name, chunk
+        );
+        let summary = llm::get_oneshot_response(prompt, CHUNK_SUMMARY_TOKENS).await?;
+        partials.push(summary.trim().to_string());
+    }
+
+    let mut summary = if partials.len() == 1 {
+        partials.remove(0)
+    } else {
+        let prompt = format!(
+            "Combine these summaries of consecutive parts of the file \"{}\" into one summary \
+of the whole file. Keep names, numbers and key facts. Reply with the summary only.\n\n{}",
+            name,
+            partials.join("\n\n")
+        );
+        llm::get_oneshot_response(prompt, FINAL_SUMMARY_TOKENS).await?.trim().to_string()
+    };
+    if truncated {
+        summary.push_str(&format!("\n\n(Only the first {} parts of the file were summarized.)", MAX_SUMMARY_CHUNKS));
+    }
+    Ok(summary)
+}
+
+/// Adds an attachment's full text to the knowledge base
+pub async fn ingest(id: Uuid, name: &str) -> Result<CapturedDocument, String> {
+    let text = std::fs::read_to_string(text_path(id))
+        .map_err(|_| "The attachment is no longer available".to_string())?;
+    add_to_knowledge_base(name, &encryption::open(&text)?).await
+}
+
+/// Writes every saved attachment text, rewritten with `recode`, to a file
+/// next to it, for [`apply_recoded_texts`] to move into place once the
+/// history is rewritten too. Nothing is staged if any file fails.
+pub(crate) fn stage_recoded_texts(recode: &dyn Fn(&str) -> Result<String, String>) -> Result<Vec<PathBuf>, String> {
+    let Ok(entries) = std::fs::read_dir(attachments_dir()) else {
+        return Ok(Vec::new());
+    };
+    let mut staged = Vec::new();
+    let result = entries.flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .filter(|path| path.file_stem().and_then(|s| s.to_str()).is_some_and(|s| Uuid::parse_str(s).is_ok()))
+        .try_for_each(|path| {
+            let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            let recoded = path.with_extension("txt.recoded");
+            std::fs::write(&recoded, recode(&text)?).map_err(|e| e.to_string())?;
+            staged.push(recoded);
+            Ok(())
+        });
+    if let Err(e) = result {
+        discard_recoded_texts(staged);
+        return Err(e);
+    }
+    Ok(staged)
+}
+
+/// Replaces the attachment texts with their staged versions
+pub(crate) fn apply_recoded_texts(staged: Vec<PathBuf>) {
+    for path in staged {
+        if let Err(e) = std::fs::rename(&path, path.with_extension("")) {
+            tracing::error!("Failed to replace {} with its re-encrypted text: {}", path.display(), e);
+        }
+    }
+}
+
+pub(crate) fn discard_recoded_texts(staged: Vec<PathBuf>) {
+    for path in staged {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Saves a file's text in the context folder and indexes it
+async fn add_to_knowledge_base(name: &str, text: &str) -> Result<CapturedDocument, String> {
+    let dir = vector_store::get_context_folder();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let title = Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or(name).to_string();
+    let filename = quick_capture::unique_filename(&dir, &title);
+    // The vector store uses the first line as the document title
+    let content = format!("# {}\n\n{}\n", name, text.trim());
+    std::fs::write(dir.join(&filename), &content).map_err(|e| e.to_string())?;
+    tracing::info!("Added {} to the knowledge base as {}", name, filename);
+
+    let indexed = match vector_store::index_document(&format!("# {}", name), &content).await {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!("{} saved but not indexed yet: {}", name, e);
+            false
+        }
+    };
+
+    Ok(CapturedDocument { title: name.to_string(), filename, collection: None, indexed })
+}
@@ -0,0 +1,92 @@
+//! Capability Detection
+//!
+//! Looks for the optional command line tools and models once at startup,
+//! so panels can explain what to install instead of failing when used.
+//! Programs are found on the PATH without running them; `detect` looks
+//! again after the user installs something.
+
+use std::path::PathBuf;
+use std::sync::RwLock;
+use once_cell::sync::Lazy;
+
+use crate::models::{Capabilities, Tool, ToolStatus};
+use super::{transcription, tts, upscale};
+
+static DETECTED: Lazy<RwLock<Option<Capabilities>>> = Lazy::new(|| RwLock::new(None));
+
+/// Looks for every tool, remembering and logging what was found
+pub fn detect() -> Capabilities {
+    let capabilities = Capabilities {
+        tools: Tool::all().iter().map(|tool| probe(*tool)).collect(),
+        mock: super::mock::is_enabled(),
+    };
+    let missing: Vec<&str> = capabilities.tools.iter()
+        .filter(|status| !status.available)
+        .map(|status| status.tool.name())
+        .collect();
+    if missing.is_empty() {
+        tracing::info!("All optional tools found");
+    } else {
+        tracing::info!("Optional tools not found: {}", missing.join(", "));
+    }
+    if let Ok(mut detected) = DETECTED.write() {
+        *detected = Some(capabilities.clone());
+    }
+    capabilities
+}
+
+/// The tools found at startup, looking for them on first use
+pub fn current() -> Capabilities {
+    if let Some(capabilities) = DETECTED.read().ok().and_then(|detected| detected.clone()) {
+        return capabilities;
+    }
+    detect()
+}
+
+fn probe(tool: Tool) -> ToolStatus {
+    let (available, detail) = match tool {
+        Tool::Mflux => on_path("mflux-generate"),
+        // Video assembly measures clips with ffprobe, which ships with FFmpeg
+        Tool::Ffmpeg => match (find_program("ffmpeg"), find_program("ffprobe")) {
+            (Some(path), Some(_)) => (true, Some(path.display().to_string())),
+            (Some(_), None) => (false, Some("ffprobe not found".to_string())),
+            (None, _) => (false, None),
+        },
+        Tool::Whisper => match find_program(&transcription::whisper_bin()) {
+            Some(path) => {
+                let model = transcription::whisper_model();
+                if model.exists() {
+                    (true, Some(path.display().to_string()))
+                } else {
+                    (false, Some(format!("Model not found at {}", model.display())))
+                }
+            }
+            None => (false, None),
+        },
+        Tool::YtDlp => on_path("yt-dlp"),
+        Tool::VibeVoice => (tts::is_vibevoice_available(), None),
+        Tool::MlxAudio => (tts::is_mlx_audio_available(), None),
+        Tool::SystemTts => on_path("say"),
+        Tool::RealEsrgan => on_path(&upscale::upscaler_bin()),
+        Tool::Graphviz => on_path("dot"),
+        Tool::Mermaid => on_path("mmdc"),
+    };
+    ToolStatus { tool, available, detail }
+}
+
+fn on_path(program: &str) -> (bool, Option<String>) {
+    match find_program(program) {
+        Some(path) => (true, Some(path.display().to_string())),
+        None => (false, None),
+    }
+}
+
+/// Where a program is, looked up on the PATH unless it is given as a path
+fn find_program(program: &str) -> Option<PathBuf> {
+    if program.contains(std::path::MAIN_SEPARATOR) {
+        let path = PathBuf::from(program);
+        return path.is_file().then_some(path);
+    }
+    let dirs = std::env::var_os("PATH")?;
+    std::env::split_paths(&dirs).map(|dir| dir.join(program)).find(|path| path.is_file())
+}
@@ -0,0 +1,322 @@
+//! Code Sandbox
+//!
+//! Runs Python and Rust snippets written by the model in a restricted
+//! subprocess: a throwaway working directory, a cleared environment, a hard
+//! timeout and no network access. The snippet can only write to its working
+//! directory and can't read the user's home, apart from the Rust toolchain.
+//! Isolation uses `sandbox-exec` on macOS and bubblewrap (`bwrap`) on Linux;
+//! if neither is available, execution is refused rather than run
+//! unsandboxed.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use tokio::process::Command;
+
+/// Wall-clock limit for compiling or running a snippet
+const RUN_TIMEOUT: Duration = Duration::from_secs(10);
+/// Output beyond this is cut off before it reaches the model
+const MAX_OUTPUT_BYTES: usize = 16 * 1024;
+/// macOS sandbox profile denying network access, writes outside the
+/// working directory and reads of the home outside the toolchain
+const MACOS_PROFILE: &str = r#"(version 1)
+(allow default)
+(deny network*)
+(deny file-write*)
+(allow file-write* (subpath (param "WORKDIR")) (literal "/dev/null") (literal "/dev/tty"))
+(deny file-read* (subpath (param "HOME")))
+(allow file-read* (subpath (param "WORKDIR")) (subpath (param "RUSTUP_HOME")) (subpath (param "CARGO_HOME")))"#;
+
+/// The user's home and the Rust toolchain directories, which may sit in it
+struct HostPaths {
+    home: PathBuf,
+    rustup_home: PathBuf,
+    cargo_home: PathBuf,
+}
+
+static HOST_PATHS: Lazy<HostPaths> = Lazy::new(|| {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/var/empty"));
+    let dir = |var: &str, default: &str| {
+        let path = std::env::var_os(var).map(PathBuf::from).unwrap_or_else(|| home.join(default));
+        path.canonicalize().unwrap_or(path)
+    };
+    HostPaths {
+        rustup_home: dir("RUSTUP_HOME", ".rustup"),
+        cargo_home: dir("CARGO_HOME", ".cargo"),
+        home: home.canonicalize().unwrap_or(home),
+    }
+});
+
+/// Probed once, since it spawns a process
+static JAIL: Lazy<Option<Jail>> = Lazy::new(probe_jail);
+
+/// Languages the sandbox can run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeLanguage {
+    Python,
+    Rust,
+}
+
+impl CodeLanguage {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "python" | "python3" | "py" => Some(CodeLanguage::Python),
+            "rust" | "rs" => Some(CodeLanguage::Rust),
+            _ => None,
+        }
+    }
+}
+
+/// Result of running a snippet
+#[derive(Debug)]
+pub struct ExecutionOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// None if the process was killed
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration: Duration,
+}
+
+impl ExecutionOutput {
+    /// Compact report fed back to the model and shown in chat
+    pub fn summary(&self) -> String {
+        let mut report = String::new();
+        if self.timed_out {
+            report.push_str(&format!("Timed out after {}s\n", RUN_TIMEOUT.as_secs()));
+        } else {
+            report.push_str(&format!("Exit code: {}\n", self.exit_code.map(|c| c.to_string()).unwrap_or_else(|| "killed".into())));
+        }
+        if !self.stdout.is_empty() {
+            report.push_str(&format!("stdout:\n{}\n", self.stdout.trim_end()));
+        }
+        if !self.stderr.is_empty() {
+            report.push_str(&format!("stderr:\n{}\n", self.stderr.trim_end()));
+        }
+        report.trim_end().to_string()
+    }
+}
+
+/// Whether snippets can be run sandboxed on this machine
+pub fn is_available() -> bool {
+    JAIL.is_some()
+}
+
+/// Runs a snippet and captures its output
+pub async fn run(language: CodeLanguage, code: &str) -> Result<ExecutionOutput, String> {
+    let jail = JAIL.ok_or("No sandbox available (needs sandbox-exec or bwrap)")?;
+
+    let workdir = std::env::temp_dir().join(format!("idoris-sandbox-{}", uuid::Uuid::new_v4().simple()));
+    std::fs::create_dir_all(&workdir).map_err(|e| e.to_string())?;
+    // The sandbox rules match resolved paths (/tmp is a symlink on macOS)
+    let workdir = workdir.canonicalize().map_err(|e| e.to_string())?;
+    // Remove the working directory however the run ends
+    let _cleanup = scopeguard::guard(workdir.clone(), |dir| {
+        let _ = std::fs::remove_dir_all(dir);
+    });
+
+    let started = Instant::now();
+    let output = match language {
+        CodeLanguage::Python => {
+            let script = workdir.join("main.py");
+            std::fs::write(&script, code).map_err(|e| e.to_string())?;
+            run_jailed(jail, &workdir, "python3", &["-I".into(), script.display().to_string()], RUN_TIMEOUT).await?
+        }
+        CodeLanguage::Rust => {
+            let source = workdir.join("main.rs");
+            let binary = workdir.join("main");
+            std::fs::write(&source, code).map_err(|e| e.to_string())?;
+            let args = vec![
+                "--edition".to_string(), "2021".into(),
+                "-o".into(), binary.display().to_string(),
+                source.display().to_string(),
+            ];
+            let compiled = run_jailed(jail, &workdir, "rustc", &args, RUN_TIMEOUT).await?;
+            if compiled.timed_out || compiled.exit_code != Some(0) {
+                return Ok(ExecutionOutput { duration: started.elapsed(), ..compiled });
+            }
+            let remaining = RUN_TIMEOUT.saturating_sub(started.elapsed()).max(Duration::from_secs(1));
+            run_jailed(jail, &workdir, &binary.display().to_string(), &[], remaining).await?
+        }
+    };
+
+    tracing::info!("Sandbox ran {:?} snippet in {:?} (exit {:?}, timed out: {})", language, output.duration, output.exit_code, output.timed_out);
+    Ok(ExecutionOutput { duration: started.elapsed(), ..output })
+}
+
+/// Program that confines the child
+#[derive(Clone, Copy, Debug)]
+enum Jail {
+    SandboxExec,
+    Bubblewrap,
+}
+
+impl Jail {
+    fn program(self) -> &'static str {
+        match self {
+            Jail::SandboxExec => "sandbox-exec",
+            Jail::Bubblewrap => "bwrap",
+        }
+    }
+
+    /// Arguments placed before the confined program
+    fn args(self, workdir: &Path) -> Vec<String> {
+        let paths = &*HOST_PATHS;
+        let workdir = workdir.display().to_string();
+        match self {
+            Jail::SandboxExec => {
+                let mut args = Vec::new();
+                for (name, value) in [
+                    ("WORKDIR", workdir),
+                    ("HOME", paths.home.display().to_string()),
+                    ("RUSTUP_HOME", paths.rustup_home.display().to_string()),
+                    ("CARGO_HOME", paths.cargo_home.display().to_string()),
+                ] {
+                    args.push("-D".to_string());
+                    args.push(format!("{}={}", name, value));
+                }
+                args.extend(["-p".to_string(), MACOS_PROFILE.to_string()]);
+                args
+            }
+            Jail::Bubblewrap => {
+                let home = paths.home.display().to_string();
+                let rustup = paths.rustup_home.display().to_string();
+                let cargo = paths.cargo_home.display().to_string();
+                // Later mounts go on top of earlier ones: a read-only root,
+                // then the home hidden, then the toolchain and workdir back
+                [
+                    "--ro-bind", "/", "/",
+                    "--dev", "/dev",
+                    "--proc", "/proc",
+                    "--tmpfs", "/tmp",
+                    "--tmpfs", home.as_str(),
+                    "--ro-bind-try", rustup.as_str(), rustup.as_str(),
+                    "--ro-bind-try", cargo.as_str(), cargo.as_str(),
+                    "--bind", workdir.as_str(), workdir.as_str(),
+                    "--chdir", workdir.as_str(),
+                    "--unshare-net",
+                    "--unshare-pid",
+                    "--new-session",
+                    "--die-with-parent",
+                    "--",
+                ].iter().map(|arg| arg.to_string()).collect()
+            }
+        }
+    }
+}
+
+fn probe_jail() -> Option<Jail> {
+    let workdir = std::env::temp_dir();
+    let workdir = workdir.canonicalize().unwrap_or(workdir);
+    let works = |jail: Jail| {
+        std::process::Command::new(jail.program())
+            .args(jail.args(&workdir))
+            .arg("true")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+    };
+
+    if cfg!(target_os = "macos") && works(Jail::SandboxExec) {
+        Some(Jail::SandboxExec)
+    } else if cfg!(target_os = "linux") && works(Jail::Bubblewrap) {
+        Some(Jail::Bubblewrap)
+    } else {
+        None
+    }
+}
+
+async fn run_jailed(jail: Jail, workdir: &Path, program: &str, args: &[String], timeout: Duration) -> Result<ExecutionOutput, String> {
+    let paths = &*HOST_PATHS;
+    let mut command = Command::new(jail.program());
+    command
+        .args(jail.args(workdir))
+        .arg(program)
+        .args(args)
+        .current_dir(workdir)
+        .env_clear()
+        .env("PATH", std::env::var("PATH").unwrap_or_default())
+        .env("HOME", workdir)
+        .env("TMPDIR", workdir)
+        // rustup finds its toolchains through these rather than HOME
+        .env("RUSTUP_HOME", &paths.rustup_home)
+        .env("CARGO_HOME", &paths.cargo_home)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let started = Instant::now();
+    let child = command.spawn().map_err(|e| format!("Failed to start {}: {}", program, e))?;
+
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(result) => {
+            let output = result.map_err(|e| e.to_string())?;
+            Ok(ExecutionOutput {
+                stdout: truncate_output(&output.stdout, workdir),
+                stderr: truncate_output(&output.stderr, workdir),
+                exit_code: output.status.code(),
+                timed_out: false,
+                duration: started.elapsed(),
+            })
+        }
+        // Dropping the future kills the child (kill_on_drop)
+        Err(_) => Ok(ExecutionOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+            exit_code: None,
+            timed_out: true,
+            duration: started.elapsed(),
+        }),
+    }
+}
+
+/// Lossy UTF-8, capped, with the temporary path hidden
+fn truncate_output(bytes: &[u8], workdir: &Path) -> String {
+    let text = String::from_utf8_lossy(bytes).replace(&workdir.display().to_string(), ".");
+    if text.len() <= MAX_OUTPUT_BYTES {
+        return text;
+    }
+    let mut end = MAX_OUTPUT_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n... (output truncated)", &text[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_language_from_name() {
+        assert_eq!(CodeLanguage::from_name("Python"), Some(CodeLanguage::Python));
+        assert_eq!(CodeLanguage::from_name("rs"), Some(CodeLanguage::Rust));
+        assert_eq!(CodeLanguage::from_name("bash"), None);
+    }
+
+    #[test]
+    fn test_truncate_output_hides_workdir_and_caps_length() {
+        let workdir = PathBuf::from("/tmp/idoris-sandbox-x");
+        assert_eq!(truncate_output(b"/tmp/idoris-sandbox-x/main.py:1", &workdir), "./main.py:1");
+
+        let long = vec![b'a'; MAX_OUTPUT_BYTES + 10];
+        assert!(truncate_output(&long, &workdir).ends_with("(output truncated)"));
+    }
+
+    #[test]
+    fn test_summary() {
+        let output = ExecutionOutput {
+            stdout: "4\n".into(),
+            stderr: String::new(),
+            exit_code: Some(0),
+            timed_out: false,
+            duration: Duration::from_millis(5),
+        };
+        assert_eq!(output.summary(), "Exit code: 0\nstdout:\n4");
+    }
+}
@@ -0,0 +1,311 @@
+//! Codebase Ingestion
+//!
+//! Indexes a local source repository for "chat with my codebase". Files are
+//! walked with `.gitignore` rules applied, parsed with tree-sitter where a
+//! grammar is bundled (Rust, Python, JavaScript, TypeScript, Go) and split
+//! at function, type and block boundaries; other languages are split at
+//! top-level lines. Each chunk is indexed under a `name/path:start-end`
+//! citation, which retrieval turns back into a link to the file and line.
+
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser};
+use uuid::Uuid;
+
+use crate::models::{
+    chunk_line_ranges, heuristic_units, parse_citation, CodeChunk, CodeReference, Codebase, JobKind,
+    CODE_CHUNK_LINES, MAX_CODE_CHUNK_LINES,
+};
+use crate::storage::database;
+use super::jobs::{JobHandle, JobSpec};
+use super::vector_store;
+
+/// Source files larger than this are skipped, as they are usually generated
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+
+/// Files indexed per codebase at most
+const MAX_FILES: usize = 5_000;
+
+/// File extensions indexed, with their code fence language
+const LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "rust"), ("py", "python"), ("js", "javascript"), ("jsx", "jsx"), ("mjs", "javascript"),
+    ("cjs", "javascript"), ("ts", "typescript"), ("tsx", "tsx"), ("go", "go"), ("java", "java"),
+    ("kt", "kotlin"), ("swift", "swift"), ("c", "c"), ("h", "c"), ("cc", "cpp"), ("cpp", "cpp"),
+    ("hpp", "cpp"), ("cs", "csharp"), ("rb", "ruby"), ("php", "php"), ("scala", "scala"),
+    ("sh", "bash"), ("sql", "sql"), ("toml", "toml"), ("yaml", "yaml"), ("yml", "yaml"), ("md", "markdown"),
+];
+
+/// Codebases of the active profile
+pub async fn list() -> Result<Vec<Codebase>, String> {
+    database::get_codebases().await.map_err(|e| e.to_string())
+}
+
+/// Registers a repository and indexes it. The name defaults to the folder
+/// name and must be unique.
+pub async fn add(root: &str, name: Option<&str>) -> Result<Codebase, String> {
+    let root = std::fs::canonicalize(root.trim()).map_err(|e| format!("Folder not found: {}", e))?;
+    if !root.is_dir() {
+        return Err("Not a folder".to_string());
+    }
+    let name = sanitize_name(name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| {
+        root.file_name().and_then(|n| n.to_str()).unwrap_or("codebase")
+    }));
+    let existing = list().await?;
+    if existing.iter().any(|c| c.name == name) {
+        return Err(format!("A codebase named {} already exists", name));
+    }
+    if existing.iter().any(|c| Path::new(&c.root) == root) {
+        return Err("This folder is already indexed".to_string());
+    }
+
+    let codebase = Codebase {
+        id: Uuid::new_v4(),
+        name,
+        root: root.to_string_lossy().to_string(),
+        file_count: 0,
+        chunk_count: 0,
+        indexed_at: None,
+        created_at: chrono::Utc::now(),
+    };
+    database::save_codebase(&codebase).await.map_err(|e| e.to_string())?;
+    reindex(codebase.id).await
+}
+
+/// Indexes a codebase again, picking up changed and removed files
+pub async fn reindex(id: Uuid) -> Result<Codebase, String> {
+    let codebase = find(id).await?;
+    JobSpec::new(JobKind::Indexing, format!("Index codebase {}", codebase.name))
+        .run(move |job| index(job, codebase))
+        .await
+}
+
+/// Removes a codebase and its chunks from the index
+pub async fn remove(id: Uuid) -> Result<(), String> {
+    let codebase = find(id).await?;
+    let prefix = format!("{}/", codebase.name);
+    if vector_store::is_initialized() {
+        vector_store::remove_documents(|title| title.starts_with(&prefix)).await?;
+    }
+    database::delete_codebase(id).await.map_err(|e| e.to_string())
+}
+
+async fn find(id: Uuid) -> Result<Codebase, String> {
+    list().await?.into_iter().find(|c| c.id == id).ok_or("Codebase not found".to_string())
+}
+
+async fn index(job: JobHandle, mut codebase: Codebase) -> Result<Codebase, String> {
+    job.log(format!("Reading {}", codebase.root));
+    let root = codebase.root.clone();
+    let files = tokio::task::spawn_blocking(move || source_files(Path::new(&root)))
+        .await
+        .map_err(|e| e.to_string())??;
+    job.log(format!("{} source files", files.len()));
+
+    let prefix = format!("{}/", codebase.name);
+    let removed = vector_store::remove_documents(|title| title.starts_with(&prefix)).await?;
+    if removed > 0 {
+        job.log(format!("Removed {} chunks from the previous index", removed));
+    }
+
+    let mut chunk_count = 0;
+    for (i, relative) in files.iter().enumerate() {
+        let path = Path::new(&codebase.root).join(relative);
+        let Ok(source) = std::fs::read_to_string(&path) else { continue };
+        for chunk in chunk_file(relative, &source) {
+            vector_store::index_document(&chunk.citation(&codebase.name), &chunk.document_body(&codebase.name)).await?;
+            chunk_count += 1;
+        }
+        job.set_progress((i + 1) as f32 / files.len() as f32);
+    }
+    job.log(format!("Indexed {} chunks", chunk_count));
+
+    codebase.file_count = files.len();
+    codebase.chunk_count = chunk_count;
+    codebase.indexed_at = Some(chrono::Utc::now());
+    database::save_codebase(&codebase).await.map_err(|e| e.to_string())?;
+    Ok(codebase)
+}
+
+/// Chunks of every codebase of the active profile as documents, for a full
+/// rebuild of the index
+pub async fn index_documents() -> Vec<kalosm::language::Document> {
+    let codebases = match list().await {
+        Ok(codebases) => codebases,
+        Err(e) => {
+            tracing::warn!("Codebases not indexed: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut documents = Vec::new();
+    for codebase in codebases {
+        let root = codebase.root.clone();
+        let files = match tokio::task::spawn_blocking(move || source_files(Path::new(&root))).await {
+            Ok(Ok(files)) => files,
+            Ok(Err(e)) => {
+                tracing::warn!("Skipping codebase {}: {}", codebase.name, e);
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("Skipping codebase {}: {}", codebase.name, e);
+                continue;
+            }
+        };
+        for relative in files {
+            let Ok(source) = std::fs::read_to_string(Path::new(&codebase.root).join(&relative)) else { continue };
+            for chunk in chunk_file(&relative, &source) {
+                documents.push(kalosm::language::Document::from_parts(
+                    chunk.citation(&codebase.name),
+                    chunk.document_body(&codebase.name),
+                ));
+            }
+        }
+    }
+    documents
+}
+
+/// Turns retrieved document titles that are code citations into links to
+/// the file and line
+pub async fn references_for(titles: &[String]) -> Vec<CodeReference> {
+    let citations: Vec<_> = titles.iter().filter_map(|t| parse_citation(t).map(|c| (t, c))).collect();
+    if citations.is_empty() {
+        return Vec::new();
+    }
+    let codebases = list().await.unwrap_or_default();
+    citations.into_iter()
+        .filter_map(|(title, (name, path, start, _))| {
+            let codebase = codebases.iter().find(|c| c.name == name)?;
+            Some(CodeReference {
+                label: title.clone(),
+                file: Path::new(&codebase.root).join(path).to_string_lossy().to_string(),
+                line: start,
+            })
+        })
+        .collect()
+}
+
+/// Indexable files below `root`, relative to it with `/` separators.
+/// `.gitignore`, `.ignore` and hidden files are skipped.
+fn source_files(root: &Path) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    for entry in ignore::WalkBuilder::new(root).build() {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        let is_file = entry.file_type().is_some_and(|t| t.is_file());
+        let small = entry.metadata().is_ok_and(|m| m.len() <= MAX_FILE_BYTES);
+        if !is_file || !small || language_of(path).is_none() {
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(root) else { continue };
+        files.push(relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"));
+        if files.len() >= MAX_FILES {
+            tracing::warn!("Codebase {:?} has more than {} files; the rest are skipped", root, MAX_FILES);
+            break;
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn language_of(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    LANGUAGES.iter().find(|(ext, _)| *ext == extension).map(|(_, lang)| *lang)
+}
+
+/// Bundled tree-sitter grammar for a code fence language
+fn grammar(language: &str) -> Option<Language> {
+    Some(match language {
+        "rust" => tree_sitter_rust::LANGUAGE.into(),
+        "python" => tree_sitter_python::LANGUAGE.into(),
+        "javascript" | "jsx" => tree_sitter_javascript::LANGUAGE.into(),
+        "typescript" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        "go" => tree_sitter_go::LANGUAGE.into(),
+        _ => return None,
+    })
+}
+
+/// Splits a source file into chunks at syntax boundaries
+fn chunk_file(relative: &str, source: &str) -> Vec<CodeChunk> {
+    let Some(language) = language_of(Path::new(relative)) else { return Vec::new() };
+    let lines: Vec<&str> = source.lines().collect();
+    if lines.iter().all(|l| l.trim().is_empty()) {
+        return Vec::new();
+    }
+
+    let units = grammar(language)
+        .and_then(|grammar| syntax_units(grammar, source))
+        .unwrap_or_else(|| heuristic_units(source));
+    chunk_line_ranges(&units, lines.len(), CODE_CHUNK_LINES, MAX_CODE_CHUNK_LINES)
+        .into_iter()
+        .filter(|&(start, end)| lines[start..=end].iter().any(|l| !l.trim().is_empty()))
+        .map(|(start, end)| CodeChunk {
+            path: relative.to_string(),
+            start_line: start + 1,
+            end_line: end + 1,
+            language: language.to_string(),
+            text: lines[start..=end].join("\n"),
+        })
+        .collect()
+}
+
+/// Top-level items of the parsed file, descending into items too long for
+/// one chunk (e.g. an `impl` block or class) to split between their members
+fn syntax_units(grammar: Language, source: &str) -> Option<Vec<(usize, usize)>> {
+    let mut parser = Parser::new();
+    parser.set_language(&grammar).ok()?;
+    let tree = parser.parse(source, None)?;
+    let mut units = Vec::new();
+    collect_units(tree.root_node(), &mut units);
+    Some(units)
+}
+
+fn collect_units(node: Node, units: &mut Vec<(usize, usize)>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let (start, end) = (child.start_position().row, child.end_position().row);
+        if end + 1 - start > CODE_CHUNK_LINES && child.named_child_count() > 0 {
+            collect_units(child, units);
+        } else {
+            units.push((start, end));
+        }
+    }
+}
+
+/// Citation-safe codebase name
+fn sanitize_name(name: &str) -> String {
+    let name: String = name.trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '-' })
+        .collect();
+    let name = name.trim_matches(['-', '.']);
+    if name.is_empty() { "codebase".to_string() } else { name.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("my repo/v2"), "my-repo-v2");
+        assert_eq!(sanitize_name(".."), "codebase");
+    }
+
+    #[test]
+    fn test_chunk_file_splits_at_functions() {
+        let function = |name: &str| format!("fn {}() {{\n{}}}\n", name, "    let x = 1;\n".repeat(30));
+        let source = format!("use std::io;\n\n{}\n{}\n{}", function("a"), function("b"), function("c"));
+        let chunks = chunk_file("src/lib.rs", &source);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].start_line, 1);
+        assert!(chunks[1].text.starts_with("fn b()"));
+        assert_eq!(chunks[2].end_line, source.lines().count());
+        assert_eq!(chunks[0].citation("repo"), format!("repo/src/lib.rs:1-{}", chunks[0].end_line));
+    }
+
+    #[test]
+    fn test_chunk_file_skips_unknown_files() {
+        assert!(chunk_file("image.png", "binary").is_empty());
+        assert!(chunk_file("notes.md", "\n\n").is_empty());
+    }
+}
@@ -7,17 +7,21 @@ use std::env;
 
 /// Validates all required environment variables
 pub fn validate_env_config() -> Result<()> {
-    println!("Validating environment configuration...");
+    tracing::info!("Validating environment configuration...");
     
     // Check for model paths (optional but recommended)
     if env::var("HF_HOME").is_err() && env::var("TRANSFORMERS_CACHE").is_err() {
-        println!("Info: HF_HOME or TRANSFORMERS_CACHE not set. Models will use default cache location.");
+        tracing::info!("Info: HF_HOME or TRANSFORMERS_CACHE not set. Models will use default cache location.");
     }
     
+    if super::mock::is_enabled() {
+        tracing::warn!("IDORIS_MOCK_PROVIDERS is set: models and remote services are replaced by mock providers");
+    }
+
     // Validate API keys for external services (all optional)
     validate_optional_api_keys();
     
-    println!("✅ Environment configuration validated");
+    tracing::info!("✅ Environment configuration validated");
     Ok(())
 }
 
@@ -25,17 +29,17 @@ pub fn validate_env_config() -> Result<()> {
 fn validate_optional_api_keys() {
     // ByteDance/Jimeng keys
     if env::var("Access_Key_ID").is_err() && env::var("JIMENG_ACCESS_KEY").is_err() {
-        println!("Info: ByteDance/Jimeng API keys not configured. Video generation with ByteDance will not be available.");
+        tracing::info!("Info: ByteDance/Jimeng API keys not configured. Video generation with ByteDance will not be available.");
     }
     
     // Together.ai
     if env::var("TOGETHER_API_KEY").is_err() {
-        println!("Info: Together.ai API key not configured.");
+        tracing::info!("Info: Together.ai API key not configured.");
     }
     
     // Replicate
     if env::var("REPLICATE_API_TOKEN").is_err() {
-        println!("Info: Replicate API token not configured.");
+        tracing::info!("Info: Replicate API token not configured.");
     }
 }
 
@@ -66,7 +70,7 @@ pub fn validate_api_key_with_fallbacks(preferred: &str, fallbacks: &[&str]) -> R
     // Try fallbacks
     for key in fallbacks {
         if let Ok(val) = validate_api_key(key) {
-            println!("Using {} (fallback for {})", key, preferred);
+            tracing::info!("Using {} (fallback for {})", key, preferred);
             return Ok(val);
         }
     }
@@ -0,0 +1,872 @@
+//! Content Pipeline
+//!
+//! Turns a topic into a full content package by running composable stages
+//! (research → outline → draft → images → SEO pass → social posts) as a
+//! background job. After a checkpoint stage the job pauses until the user
+//! approves (and optionally edits) the artifacts; failed jobs can be resumed
+//! from the stage that failed. Generated images can be processed into
+//! web-ready copies, the article can be narrated with TTS, video clips can
+//! be joined into one narrated, subtitled video, and finished packages
+//! export as a static site.
+//!
+//! Every job is persisted to `content_jobs/<id>.json` in the data directory
+//! whenever it changes, including partially drafted sections, so jobs that were running
+//! when the app closed continue from their last completed step on restart.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use uuid::Uuid;
+
+use crate::models::{
+    ArticleExtras, ContentPackage, ContentTranslation, FaqEntry, GenerationProgress, GenerationStage, ImageEdit, JobKind, JobState, NarrationAsset,
+    NotificationLevel, PackageImage, PipelineDefinition, PipelineJob, ResearchBrief, ResearchSource, ResearchSourceKind,
+    SeoMetadata, SocialPost, MAX_RESEARCH_SOURCES, MAX_SOURCE_CHARS, parse_article_extras, parse_source_summary,
+    UpscaleFactor, VideoClip, AssembledVideo, SubtitleOptions, SubtitleSource, SubtitleTrack, fit_cues, replace_upscaled,
+    script_cues,
+};
+use crate::models::content_template::parse_outline;
+use kalosm::language::{Parse, Schema};
+use super::jobs::{JobHandle, JobSpec};
+use super::{llm, notifications};
+
+/// Maximum tokens for short answers (SEO, prompts, posts)
+const SHORT_MAX_LENGTH: u32 = 300;
+/// Maximum tokens for research notes and drafted sections
+const LONG_MAX_LENGTH: u32 = 900;
+/// Platforms social posts are written for
+const SOCIAL_PLATFORMS: &[&str] = &["Twitter/X", "LinkedIn", "小红书"];
+
+/// How often a job checks whether the chat model has loaded
+const MODEL_WAIT_INTERVAL: Duration = Duration::from_secs(2);
+const WAITING_FOR_MODEL: &str = "Waiting for the model to load";
+
+static JOBS: Lazy<Mutex<HashMap<Uuid, PipelineJob>>> = Lazy::new(|| Mutex::new(load_jobs()));
+static RESTORED: AtomicBool = AtomicBool::new(false);
+
+fn jobs_dir() -> PathBuf {
+    crate::storage::database::get_data_dir().join("content_jobs")
+}
+
+/// Reads persisted jobs, skipping unreadable files
+fn load_jobs() -> HashMap<Uuid, PipelineJob> {
+    let Ok(entries) = std::fs::read_dir(jobs_dir()) else {
+        return HashMap::new();
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| {
+            let content = std::fs::read_to_string(e.path()).ok()?;
+            match serde_json::from_str::<PipelineJob>(&content) {
+                Ok(job) => Some((job.progress.job_id, job)),
+                Err(err) => {
+                    tracing::warn!("Skipping unreadable content job {:?}: {}", e.path(), err);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Writes the job atomically so a crash never leaves a truncated file
+fn persist(job: &PipelineJob) {
+    let dir = jobs_dir();
+    let result = std::fs::create_dir_all(&dir)
+        .and_then(|_| serde_json::to_vec_pretty(job).map_err(std::io::Error::other))
+        .and_then(|content| {
+            let tmp = dir.join(format!("{}.json.tmp", job.progress.job_id));
+            std::fs::write(&tmp, content)?;
+            std::fs::rename(tmp, dir.join(format!("{}.json", job.progress.job_id)))
+        });
+    if let Err(e) = result {
+        tracing::warn!("Failed to persist content job {}: {}", job.progress.job_id, e);
+    }
+}
+
+/// Continues jobs that were running when the app last closed. Call once at startup.
+pub fn restore_jobs() {
+    if RESTORED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let interrupted: Vec<Uuid> = JOBS.lock()
+        .map(|jobs| jobs.values().filter(|j| j.progress.state == JobState::Running).map(|j| j.progress.job_id).collect())
+        .unwrap_or_default();
+    for job_id in interrupted {
+        tracing::info!("Resuming content pipeline {} after restart", job_id);
+        let _ = update_job(job_id, |job| set_state(job, JobState::Running, "Resuming after restart"));
+        spawn_drive(job_id);
+    }
+}
+
+/// All jobs, newest first
+pub fn list_jobs() -> Vec<PipelineJob> {
+    let mut jobs: Vec<PipelineJob> = JOBS.lock().map(|j| j.values().cloned().collect()).unwrap_or_default();
+    jobs.sort_by(|a, b| b.progress.updated_at.cmp(&a.progress.updated_at));
+    jobs
+}
+
+pub fn get_job(job_id: Uuid) -> Option<PipelineJob> {
+    JOBS.lock().ok()?.get(&job_id).cloned()
+}
+
+/// Starts a pipeline for the topic in the background
+pub fn start(topic: String, pipeline: PipelineDefinition) -> Result<Uuid, String> {
+    let topic = topic.trim().to_string();
+    if topic.is_empty() {
+        return Err("Topic must not be empty".to_string());
+    }
+    if pipeline.stages.is_empty() {
+        return Err("Select at least one stage".to_string());
+    }
+
+    let progress = GenerationProgress::new(topic.clone(), pipeline);
+    let job_id = progress.job_id;
+    let package = ContentPackage { topic, ..Default::default() };
+    let job = PipelineJob { progress, package };
+    persist(&job);
+    JOBS.lock().map_err(|_| "Failed to lock jobs")?.insert(job_id, job);
+
+    spawn_drive(job_id);
+    Ok(job_id)
+}
+
+/// Approves the stage the job is waiting on, replacing the artifacts with
+/// the user's edits if given, and continues the pipeline
+pub fn approve(job_id: Uuid, edited: Option<ContentPackage>) -> Result<(), String> {
+    update_job(job_id, |job| {
+        if job.progress.state != JobState::AwaitingApproval {
+            return Err("Job is not waiting for approval".to_string());
+        }
+        if let Some(package) = edited {
+            job.package = package;
+        }
+        if let Some(stage) = job.progress.current.take() {
+            job.progress.completed.push(stage);
+        }
+        set_state(job, JobState::Running, "Approved");
+        Ok(())
+    })??;
+
+    spawn_drive(job_id);
+    Ok(())
+}
+
+/// Retries a failed job from the stage that failed
+pub fn resume(job_id: Uuid) -> Result<(), String> {
+    update_job(job_id, |job| {
+        if !matches!(job.progress.state, JobState::Failed(_)) {
+            return Err("Only failed jobs can be resumed".to_string());
+        }
+        set_state(job, JobState::Running, "Resuming");
+        Ok(())
+    })??;
+
+    spawn_drive(job_id);
+    Ok(())
+}
+
+/// Removes a job that is not running, with its saved state
+pub fn delete_job(job_id: Uuid) -> Result<(), String> {
+    let mut jobs = JOBS.lock().map_err(|_| "Failed to lock jobs")?;
+    match jobs.get(&job_id) {
+        Some(job) if job.progress.state == JobState::Running => return Err("Job is still running".to_string()),
+        Some(_) => {}
+        None => return Err("Job not found".to_string()),
+    }
+    jobs.remove(&job_id);
+    let _ = std::fs::remove_file(jobs_dir().join(format!("{}.json", job_id)));
+    Ok(())
+}
+
+/// Crops, resizes and compresses a package image into an export-ready copy
+/// saved next to the original
+pub fn process_package_image(job_id: Uuid, index: usize, edit: &ImageEdit) -> Result<PackageImage, String> {
+    let job = get_job(job_id).ok_or("Job not found")?;
+    if job.progress.state == JobState::Running && job.progress.current == Some(GenerationStage::Images) {
+        return Err("Images are still being generated".to_string());
+    }
+    let image = job.package.images.get(index).ok_or("Image not found")?;
+    let source = PathBuf::from(image.path.as_ref().ok_or("Image has not been generated")?);
+    let stem = source.file_stem().and_then(|s| s.to_str()).unwrap_or("image");
+    let output = source.with_file_name(format!("{}-export.{}", stem, edit.format.extension()));
+
+    let processed = super::image_processing::process_image(&source, &output, edit)?;
+    update_job(job_id, |job| {
+        let image = job.package.images.get_mut(index).ok_or("Image not found")?;
+        image.processed = Some(processed);
+        Ok(image.clone())
+    })?
+}
+
+/// Saves an upscaled copy of a package image next to the original and
+/// records it on the image
+pub async fn upscale_package_image(job_id: Uuid, index: usize, factor: UpscaleFactor, face_fix: bool) -> Result<PackageImage, String> {
+    let job = get_job(job_id).ok_or("Job not found")?;
+    if job.progress.state == JobState::Running && job.progress.current == Some(GenerationStage::Images) {
+        return Err("Images are still being generated".to_string());
+    }
+    let image = job.package.images.get(index).ok_or("Image not found")?;
+    let source = PathBuf::from(image.path.as_ref().ok_or("Image has not been generated")?);
+
+    let upscaled = super::upscale::upscale_image(&source, factor, face_fix).await?;
+    register_asset(job_id, std::path::Path::new(&upscaled.path), &image.prompt).await;
+    update_job(job_id, |job| {
+        let image = job.package.images.get_mut(index).ok_or("Image not found")?;
+        replace_upscaled(&mut image.upscaled, upscaled);
+        Ok(image.clone())
+    })?
+}
+
+/// Reads the drafted article aloud with the best available TTS engine and
+/// stores the recording with the package's other assets
+pub async fn narrate(job_id: Uuid) -> Result<NarrationAsset, String> {
+    use super::tts::{generate_speech, get_available_engines, TtsEngine, TtsSettings};
+
+    let job = get_job(job_id).ok_or("Job not found")?;
+    if job.package.sections.is_empty() {
+        return Err("Draft the article before narrating it".to_string());
+    }
+
+    let engine = get_available_engines().into_iter().next().unwrap_or(TtsEngine::System);
+    let audio = generate_speech(TtsSettings::new(&job.package.narration_text()).with_engine(engine.clone())).await?;
+
+    let dir = super::profile::asset_dir("content_packages").join(job_id.to_string());
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let file = dir.join(format!("narration.{}", audio.format));
+    std::fs::write(&file, &audio.data).map_err(|e| e.to_string())?;
+    register_asset(job_id, &file, &job.package.topic).await;
+
+    let narration = NarrationAsset {
+        path: file.display().to_string(),
+        engine: engine.display_name().to_string(),
+        format: audio.format,
+        duration_ms: audio.duration_ms,
+    };
+    update_job(job_id, |job| job.package.narration = Some(narration.clone()))?;
+    Ok(narration)
+}
+
+/// Saves a video clip and its thumbnail with the package and appends it to
+/// the timeline. `source` is a URL (e.g. from video generation) or a local
+/// file; `thumbnail_url` is the provider's preview image, if any.
+pub async fn add_video_clip(job_id: Uuid, source: &str, label: &str, thumbnail_url: Option<&str>) -> Result<VideoClip, String> {
+    get_job(job_id).ok_or("Job not found")?;
+    let source = source.trim();
+    if source.is_empty() {
+        return Err("Enter a video URL or file path".to_string());
+    }
+    let dir = super::profile::asset_dir("content_packages").join(job_id.to_string()).join("clips");
+    let path = super::video_assembly::save_clip(source, &dir).await?;
+
+    let label = if label.trim().is_empty() { source } else { label.trim() };
+    let mut clip = VideoClip::new(path.display().to_string(), label.to_string());
+    clip.thumbnail = super::video_assembly::save_thumbnail(&path, thumbnail_url).await.map(|t| t.display().to_string());
+    register_asset(job_id, &path, &clip.label).await;
+    update_job(job_id, |job| job.package.video_clips.push(clip.clone()))?;
+    Ok(clip)
+}
+
+/// Replaces the timeline after clips were reordered, removed or titled.
+/// Files of removed clips are deleted.
+pub fn set_video_clips(job_id: Uuid, clips: Vec<VideoClip>) -> Result<(), String> {
+    let job = get_job(job_id).ok_or("Job not found")?;
+    let mut known = job.package.video_clips;
+    // Only clips already saved with the package can be kept
+    if let Some(unknown) = clips.iter().find(|c| !known.iter().any(|k| k.id == c.id)) {
+        return Err(format!("Unknown clip: {}", unknown.label));
+    }
+    let clips: Vec<VideoClip> = clips.into_iter()
+        .map(|c| {
+            let saved = known.iter().find(|k| k.id == c.id).cloned();
+            let (path, thumbnail) = saved.map(|k| (k.path, k.thumbnail)).unwrap_or_default();
+            let title = c.title.filter(|t| !t.trim().is_empty());
+            VideoClip { path, thumbnail, title, ..c }
+        })
+        .collect();
+    known.retain(|k| !clips.iter().any(|c| c.id == k.id));
+    for removed in known {
+        let _ = std::fs::remove_file(&removed.path);
+        if let Some(thumbnail) = removed.thumbnail {
+            let _ = std::fs::remove_file(thumbnail);
+        }
+    }
+    update_job(job_id, |job| job.package.video_clips = clips)
+}
+
+/// Joins the timeline's clips into `video.mp4`, with the narration as its
+/// soundtrack when the article has been narrated, and adds subtitles when
+/// the package's subtitle options ask for them
+pub async fn assemble_video(job_id: Uuid) -> Result<AssembledVideo, String> {
+    let job = get_job(job_id).ok_or("Job not found")?;
+    let narration = job.package.narration.as_ref().map(|n| PathBuf::from(&n.path));
+    let output = super::profile::asset_dir("content_packages").join(job_id.to_string()).join("video.mp4");
+
+    let mut video = super::video_assembly::assemble(&job.package.video_clips, narration.as_deref(), &output).await?;
+    let options = job.package.subtitle_options.clone();
+    let subtitles = if options.enabled {
+        Some(make_subtitles(&job.package, &options, &output).await)
+    } else {
+        None
+    };
+    // The video is kept even when its subtitles fail
+    video.subtitles = subtitles.clone().and_then(Result::ok);
+    video.thumbnail = super::video_assembly::save_thumbnail(&output, None).await.map(|t| t.display().to_string());
+    register_asset(job_id, &output, &job.package.topic).await;
+    update_job(job_id, |job| job.package.video = Some(video.clone()))?;
+    match subtitles {
+        Some(Err(e)) => Err(format!("The video was assembled, but subtitles failed: {}", e)),
+        _ => Ok(video),
+    }
+}
+
+/// Adds a file saved with a package to the asset library
+async fn register_asset(job_id: Uuid, path: &std::path::Path, prompt: &str) {
+    super::assets::register(path, prompt, crate::models::AssetOrigin::ContentPackage, Some(job_id.to_string())).await;
+}
+
+async fn make_subtitles(package: &ContentPackage, options: &SubtitleOptions, video: &std::path::Path) -> Result<SubtitleTrack, String> {
+    let cues = match options.source {
+        SubtitleSource::Script => {
+            let narration = package.narration.as_ref()
+                .ok_or("Subtitles from the script are timed by the narration; narrate the article first")?;
+            script_cues(&package.narration_text(), narration.duration_ms as u64)
+        }
+        SubtitleSource::Transcription => {
+            let work_dir = std::env::temp_dir().join(format!("idoris-subtitles-{}", Uuid::new_v4()));
+            std::fs::create_dir_all(&work_dir).map_err(|e| e.to_string())?;
+            let segments = super::transcription::transcribe_audio(video, &work_dir).await;
+            let _ = std::fs::remove_dir_all(&work_dir);
+            fit_cues(&segments?)
+        }
+    };
+    super::video_assembly::write_subtitles(&cues, options.source, video, options.burn_in).await
+}
+
+/// A package video thumbnail as a data URL. Only thumbnails saved with the
+/// package can be read.
+pub fn video_thumbnail(job_id: Uuid, path: &str) -> Result<String, String> {
+    use base64::Engine;
+
+    let dir = super::profile::asset_dir("content_packages").join(job_id.to_string());
+    let dir = dir.canonicalize().map_err(|_| "Package folder not found")?;
+    let file = PathBuf::from(path).canonicalize().map_err(|_| "Thumbnail not found")?;
+    if !file.starts_with(&dir) || file.extension().is_none_or(|e| e != "jpg") {
+        return Err("Not a thumbnail of this package".to_string());
+    }
+    let data = std::fs::read(&file).map_err(|e| e.to_string())?;
+    Ok(format!("data:image/jpeg;base64,{}", base64::engine::general_purpose::STANDARD.encode(data)))
+}
+
+/// Saves edited SEO metadata
+pub fn set_seo(job_id: Uuid, seo: SeoMetadata) -> Result<(), String> {
+    update_job(job_id, |job| job.package.seo = Some(seo))
+}
+
+/// Saves edits to the key takeaways and FAQ and whether they are included
+pub fn set_extras(job_id: Uuid, extras: ArticleExtras) -> Result<(), String> {
+    update_job(job_id, |job| job.package.extras = extras)
+}
+
+/// Saves how subtitles are made the next time the video is assembled
+pub fn set_subtitle_options(job_id: Uuid, options: SubtitleOptions) -> Result<(), String> {
+    update_job(job_id, |job| job.package.subtitle_options = options)
+}
+
+/// Writes the package as a static site (`index.html` plus its images,
+/// narration and video) and returns the folder it was written to
+pub fn export_site(job_id: Uuid) -> Result<PathBuf, String> {
+    let job = get_job(job_id).ok_or("Job not found")?;
+    let dir = super::profile::asset_dir("content_packages").join(job_id.to_string()).join("site");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let assets = job.package.images.iter()
+        .filter_map(|image| image.export_path())
+        .chain(job.package.narration.as_ref().map(|n| n.path.as_str()))
+        .chain(job.package.video.iter().flat_map(|v| std::iter::once(v.path.as_str()).chain(v.thumbnail.as_deref())))
+        .chain(job.package.video.iter().flat_map(|v| &v.subtitles).flat_map(|s| [s.srt_path.as_str(), s.vtt_path.as_str()]));
+    for asset in assets {
+        let source = PathBuf::from(asset);
+        let Some(name) = source.file_name() else { continue };
+        std::fs::copy(&source, dir.join(name)).map_err(|e| format!("Failed to copy {}: {}", asset, e))?;
+    }
+    std::fs::write(dir.join("index.html"), job.package.to_html()).map_err(|e| e.to_string())?;
+
+    Ok(dir)
+}
+
+/// Runs the pipeline in the background as a registered job. Cancelling it
+/// marks the pipeline failed, so it can be resumed or retried later.
+fn spawn_drive(job_id: Uuid) {
+    let topic = get_job(job_id).map(|job| job.progress.topic).unwrap_or_default();
+    JobSpec::new(JobKind::ContentPipeline, topic)
+        .with_retry(move || resume(job_id))
+        .with_on_cancel(move || {
+            update_job(job_id, |job| set_state(job, JobState::Failed("Cancelled".to_string()), "Cancelled"))
+        })
+        .spawn(move |handle| async move {
+            drive(job_id, handle).await;
+            match get_job(job_id).map(|job| job.progress.state) {
+                Some(JobState::Failed(e)) => Err(e),
+                Some(JobState::AwaitingApproval) => {
+                    handle.log("Waiting for approval; approving continues it as a new job");
+                    Ok(())
+                }
+                _ => Ok(()),
+            }
+        });
+}
+
+/// Runs stages until the pipeline completes, pauses or fails
+async fn drive(job_id: Uuid, handle: JobHandle) {
+    loop {
+        let Some(job) = get_job(job_id) else { return };
+
+        // Jobs restored at startup wait for the UI to load the model
+        if !llm::is_initialized() {
+            if job.progress.message != WAITING_FOR_MODEL {
+                let _ = update_job(job_id, |job| set_state(job, JobState::Running, WAITING_FOR_MODEL));
+            }
+            tokio::time::sleep(MODEL_WAIT_INTERVAL).await;
+            continue;
+        }
+
+        let Some(stage) = job.progress.next_stage() else {
+            let _ = update_job(job_id, |job| {
+                job.progress.current = None;
+                set_state(job, JobState::Completed, "Package ready");
+            });
+            tracing::info!("Content pipeline {} completed", job_id);
+            notifications::success("Content package ready", job.progress.topic.clone());
+            return;
+        };
+
+        let _ = update_job(job_id, |job| {
+            job.progress.current = Some(stage);
+            set_state(job, JobState::Running, &format!("{}...", stage.display_name()));
+        });
+        handle.set_progress(job.progress.percent() as f32 / 100.0);
+        handle.log(format!("{}...", stage.display_name()));
+        tracing::info!("Content pipeline {}: running {}", job_id, stage.display_name());
+
+        let mut package = job.package.clone();
+        let result = run_stage(stage, job_id, &mut package).await;
+
+        let paused = update_job(job_id, |job| match result {
+            Ok(()) => {
+                job.package = package;
+                if job.progress.pipeline.checkpoints.contains(&stage) {
+                    set_state(job, JobState::AwaitingApproval, &format!("Review the {} and approve to continue", stage.display_name()));
+                    notifications::notify(
+                        NotificationLevel::Info,
+                        "Content pipeline needs review",
+                        format!("{}: {}", job.progress.topic, job.progress.message),
+                    );
+                    true
+                } else {
+                    job.progress.completed.push(stage);
+                    job.progress.current = None;
+                    false
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Content pipeline {} failed at {}: {}", job_id, stage.display_name(), e);
+                notifications::error(
+                    format!("Content pipeline failed at {}", stage.display_name()),
+                    format!("{}: {}", job.progress.topic, e),
+                );
+                set_state(job, JobState::Failed(e), &format!("{} failed", stage.display_name()));
+                true
+            }
+        });
+
+        if paused.unwrap_or(true) {
+            return;
+        }
+    }
+}
+
+/// Applies a change to a job and persists it
+fn update_job<T>(job_id: Uuid, f: impl FnOnce(&mut PipelineJob) -> T) -> Result<T, String> {
+    let mut jobs = JOBS.lock().map_err(|_| "Failed to lock jobs")?;
+    let job = jobs.get_mut(&job_id).ok_or("Job not found")?;
+    let result = f(job);
+    persist(job);
+    Ok(result)
+}
+
+/// Saves partial artifacts of the running stage so a restart can pick up from them
+fn checkpoint(job_id: Uuid, package: &ContentPackage) {
+    let _ = update_job(job_id, |job| {
+        job.package = package.clone();
+        job.progress.updated_at = chrono::Utc::now();
+    });
+}
+
+fn set_state(job: &mut PipelineJob, state: JobState, message: &str) {
+    job.progress.state = state;
+    job.progress.message = message.to_string();
+    job.progress.updated_at = chrono::Utc::now();
+}
+
+// ============================================================================
+// Stages
+// ============================================================================
+
+async fn run_stage(stage: GenerationStage, job_id: Uuid, package: &mut ContentPackage) -> Result<(), String> {
+    match stage {
+        GenerationStage::Research => research(job_id, package).await,
+        GenerationStage::Outline => outline(package).await,
+        GenerationStage::Draft => draft(job_id, package).await,
+        GenerationStage::Extras => extras(package).await,
+        GenerationStage::Images => images(job_id, package).await,
+        GenerationStage::Seo => seo(package).await,
+        GenerationStage::SocialPosts => social_posts(job_id, package).await,
+    }
+}
+
+/// Reads the named pages, web search hits (if configured), matching feed
+/// entries and knowledge base documents, summarizes each and merges their
+/// key points into the research brief. Without any sources, the notes
+/// come from the model alone.
+async fn research(job_id: Uuid, package: &mut ContentPackage) -> Result<(), String> {
+    let research_sources = get_job(job_id).map(|job| job.progress.pipeline.research_sources).unwrap_or_default();
+    let mut gathered = super::content_source::gather_research(&package.topic, &research_sources, MAX_RESEARCH_SOURCES).await;
+    if super::vector_store::is_initialized() && gathered.len() < MAX_RESEARCH_SOURCES {
+        if let Ok(documents) = super::vector_store::query(&package.topic).await {
+            let room = MAX_RESEARCH_SOURCES - gathered.len();
+            gathered.extend(documents.into_iter().take(room).map(|d| {
+                let source = ResearchSource {
+                    kind: ResearchSourceKind::KnowledgeBase,
+                    title: d.title,
+                    url: None,
+                    summary: String::new(),
+                    key_points: Vec::new(),
+                    error: None,
+                };
+                (source, d.body.chars().take(MAX_SOURCE_CHARS).collect())
+            }));
+        }
+    }
+
+    if gathered.is_empty() {
+        package.research_brief = None;
+        let prompt = format!("List the key facts, angles and questions a writer should cover in an article about \"{}\". Use short bullet points.", package.topic);
+        package.research_notes = llm::get_oneshot_response(prompt, LONG_MAX_LENGTH).await?.trim().to_string();
+        return Ok(());
+    }
+
+    let mut sources = Vec::new();
+    for (mut source, text) in gathered {
+        if !text.trim().is_empty() {
+            let prompt = format!(
+                "Summarize this source for research on \"{}\". First write one sentence saying what it is about, then list its key facts and claims relevant to the topic as short bullet points starting with \"- \".\n\nTitle: {}\n\n{}",
+                package.topic, source.title, text
+            );
+            match llm::get_oneshot_response(prompt, SHORT_MAX_LENGTH).await {
+                Ok(reply) => (source.summary, source.key_points) = parse_source_summary(&reply),
+                Err(e) => source.error = Some(e),
+            }
+        }
+        sources.push(source);
+        // Shows the sources read so far while the rest are summarized
+        package.research_brief = Some(ResearchBrief::new(package.topic.clone(), sources.clone()));
+        checkpoint(job_id, package);
+    }
+
+    let brief = ResearchBrief::new(package.topic.clone(), sources);
+    if brief.key_points.is_empty() {
+        return Err("None of the research sources could be read".to_string());
+    }
+    package.research_notes = brief.notes();
+    package.research_brief = Some(brief);
+    Ok(())
+}
+
+/// Article outline as generated with constrained decoding
+#[derive(Parse, Schema, Clone, Debug)]
+pub struct StructuredOutline {
+    /// 4-6 sections in reading order
+    pub sections: Vec<StructuredSection>,
+}
+
+#[derive(Parse, Schema, Clone, Debug)]
+pub struct StructuredSection {
+    pub title: String,
+    /// What the section should cover, in one or two sentences
+    pub description: String,
+}
+
+impl StructuredOutline {
+    /// (title, description) pairs, without sections the model left blank
+    pub fn into_sections(self) -> Vec<(String, String)> {
+        self.sections.into_iter()
+            .map(|s| (s.title.trim().to_string(), s.description.trim().to_string()))
+            .filter(|(title, _)| !title.is_empty())
+            .collect()
+    }
+}
+
+/// Key takeaways and FAQ as generated with constrained decoding
+#[derive(Parse, Schema, Clone, Debug)]
+struct StructuredExtras {
+    /// 3-5 one-sentence takeaways
+    key_takeaways: Vec<String>,
+    /// 3-5 questions a reader might still have, answered from the article
+    faq: Vec<StructuredFaq>,
+}
+
+#[derive(Parse, Schema, Clone, Debug)]
+struct StructuredFaq {
+    question: String,
+    /// One to three sentences
+    answer: String,
+}
+
+async fn outline(package: &mut ContentPackage) -> Result<(), String> {
+    let request = format!(
+        "Generate an article outline for: \"{}\"\n\nResearch notes:\n{}\n\nCreate 4-6 sections.",
+        package.topic, package.research_notes
+    );
+
+    let sections = match llm::generate_structured::<StructuredOutline>(
+        format!("{}\nGive each section a title and a brief description of what it covers.", request),
+        None,
+        SHORT_MAX_LENGTH * 2,
+    ).await {
+        Ok(outline) => outline.into_sections(),
+        Err(e) => {
+            tracing::warn!("Falling back to a text outline: {}", e);
+            let prompt = format!(
+                "{} Format each as:\n## Section Title\nBrief description of what the section covers.\n\nOnly output the sections.",
+                request
+            );
+            parse_outline(&llm::get_oneshot_response(prompt, SHORT_MAX_LENGTH * 2).await?)
+        }
+    };
+    if sections.is_empty() {
+        return Err("The model did not return an outline".to_string());
+    }
+    package.outline = sections;
+    Ok(())
+}
+
+/// Drafts each outline section, keeping sections already drafted before a restart
+async fn draft(job_id: Uuid, package: &mut ContentPackage) -> Result<(), String> {
+    if package.outline.is_empty() {
+        return Err("Outline is empty".to_string());
+    }
+
+    let drafted = drafted_sections(package);
+    package.sections.truncate(drafted);
+
+    for (title, brief) in package.outline.clone().into_iter().skip(drafted) {
+        let prompt = format!(
+            "Write the section \"{}\" of an article titled \"{}\".\nIt should cover: {}\n\nResearch notes:\n{}\n\n\
+Write 2-4 well-structured paragraphs. Do not repeat the section title.",
+            title, package.topic, brief, package.research_notes
+        );
+        let text = llm::get_oneshot_response(prompt, LONG_MAX_LENGTH).await?;
+        package.sections.push((title, text.trim().to_string()));
+        checkpoint(job_id, package);
+    }
+    Ok(())
+}
+
+/// Number of leading sections already drafted for the current outline
+fn drafted_sections(package: &ContentPackage) -> usize {
+    package.sections.iter()
+        .zip(&package.outline)
+        .take_while(|((drafted, _), (planned, _))| drafted == planned)
+        .count()
+}
+
+/// Key takeaways and an FAQ drawn from the drafted article, both included
+/// in the article until the user leaves them out
+async fn extras(package: &mut ContentPackage) -> Result<(), String> {
+    if package.sections.is_empty() {
+        return Err("There is no draft to summarize".to_string());
+    }
+
+    let article: String = package.sections.iter()
+        .map(|(title, text)| format!("## {}\n{}", title, text))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+        .chars()
+        .take(4000)
+        .collect();
+    let request = format!(
+        "Write the key takeaways of this article about \"{}\" and an FAQ answering questions a reader might still have, \
+using only what the article says.\n\n{}",
+        package.topic, article
+    );
+
+    let (key_takeaways, faq) = match llm::generate_structured::<StructuredExtras>(request.clone(), None, LONG_MAX_LENGTH).await {
+        Ok(extras) => (
+            extras.key_takeaways.into_iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect(),
+            extras.faq.into_iter()
+                .map(|f| FaqEntry { question: f.question.trim().to_string(), answer: f.answer.trim().to_string() })
+                .filter(|f| !f.question.is_empty() && !f.answer.is_empty())
+                .collect(),
+        ),
+        Err(e) => {
+            tracing::warn!("Falling back to text takeaways and FAQ: {}", e);
+            let prompt = format!(
+                "{}\n\nReply with 3-5 takeaways as \"- \" bullets, then 3-5 questions, each as a \"Q: \" line followed by an \"A: \" line.",
+                request
+            );
+            parse_article_extras(&llm::get_oneshot_response(prompt, LONG_MAX_LENGTH).await?)
+        }
+    };
+    let extras = ArticleExtras { key_takeaways, faq, include_takeaways: true, include_faq: true };
+    if extras.is_empty() {
+        return Err("The model did not return takeaways or questions".to_string());
+    }
+    package.extras = extras;
+    Ok(())
+}
+
+/// Header image: the prompt is always written, the image only if MFLUX is available
+async fn images(job_id: Uuid, package: &mut ContentPackage) -> Result<(), String> {
+    // Keep an image that was saved before a restart
+    if package.images.iter().any(|image| image.path.as_ref().is_some_and(|p| std::path::Path::new(p).exists())) {
+        return Ok(());
+    }
+
+    let summary: String = package.sections.iter()
+        .map(|(_, text)| text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .chars()
+        .take(500)
+        .collect();
+    let prompt = llm::get_oneshot_response(
+        format!("Write a one-sentence image prompt for a header illustration of an article titled \"{}\".\n\n{}", package.topic, summary),
+        SHORT_MAX_LENGTH,
+    ).await?.trim().to_string();
+
+    let path = if super::image_gen::is_mflux_available() {
+        let image = super::image_gen::generate_image(super::image_gen::ImageGenSettings::new(&prompt)).await?;
+        let dir = super::profile::asset_dir("content_packages").join(job_id.to_string());
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let file = dir.join(format!("header.{}", image.format));
+        std::fs::write(&file, &image.data).map_err(|e| e.to_string())?;
+        register_asset(job_id, &file, &prompt).await;
+        Some(file.display().to_string())
+    } else {
+        None
+    };
+
+    package.images = vec![PackageImage { caption: "Header".to_string(), prompt, path, processed: None, upscaled: Vec::new() }];
+    Ok(())
+}
+
+/// Search and sharing metadata, with the saved defaults filled in
+async fn seo(package: &mut ContentPackage) -> Result<(), String> {
+    package.seo = Some(super::seo::generate(package).await?);
+    Ok(())
+}
+
+async fn social_posts(job_id: Uuid, package: &mut ContentPackage) -> Result<(), String> {
+    let summary = package.seo.as_ref()
+        .map(|seo| seo.meta_description.clone())
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| package.sections.first().map(|(_, text)| text.chars().take(400).collect()).unwrap_or_default());
+
+    for platform in SOCIAL_PLATFORMS {
+        if package.social_posts.iter().any(|post| post.platform == *platform) {
+            continue;
+        }
+        let prompt = format!(
+            "Write one {} post promoting an article titled \"{}\".\nSummary: {}\n\nMatch the platform's tone and length. Only output the post.",
+            platform, package.topic, summary
+        );
+        let text = llm::get_oneshot_response(prompt, SHORT_MAX_LENGTH).await?;
+        package.social_posts.push(SocialPost { platform: platform.to_string(), text: text.trim().to_string() });
+        checkpoint(job_id, package);
+    }
+    Ok(())
+}
+
+/// Translates the article's title and sections into the language, which
+/// may be a name ("French") or a code ("ja")
+pub async fn translate(package: &ContentPackage, language: &str) -> Result<ContentTranslation, String> {
+    let language = language.trim();
+    if language.is_empty() {
+        return Err("Language must not be empty".to_string());
+    }
+    if package.sections.is_empty() {
+        return Err("Nothing to translate".to_string());
+    }
+
+    let title = package.seo.as_ref().map(|seo| seo.title.clone()).filter(|t| !t.is_empty()).unwrap_or_else(|| package.topic.clone());
+    let prompt = format!("Translate this article title into {}. Only output the translation.\n\n{}", language, title);
+    let translated_title = llm::get_oneshot_response(prompt, SHORT_MAX_LENGTH).await?.trim().trim_matches('"').to_string();
+
+    let mut sections = Vec::with_capacity(package.sections.len());
+    for (heading, text) in &package.sections {
+        let prompt = format!(
+            "Translate this Markdown article section into {}. Keep the \"## \" heading line and the Markdown formatting. \
+Only output the translation.\n\n## {}\n\n{}",
+            language, heading, text
+        );
+        let response = llm::get_oneshot_response(prompt, LONG_MAX_LENGTH).await?;
+        sections.push(parse_translated_section(&response, heading));
+    }
+
+    tracing::info!("Translated \"{}\" into {}", title, language);
+    Ok(ContentTranslation {
+        language: language.to_string(),
+        title: if translated_title.is_empty() { title } else { translated_title },
+        sections,
+    })
+}
+
+/// Splits a translated section into heading and text, keeping the original
+/// heading if the model dropped it
+fn parse_translated_section(response: &str, original_heading: &str) -> (String, String) {
+    let response = response.trim();
+    match response.strip_prefix("## ") {
+        Some(rest) => {
+            let (heading, text) = rest.split_once('\n').unwrap_or((rest, ""));
+            (heading.trim().to_string(), text.trim().to_string())
+        }
+        None => (original_heading.to_string(), response.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drafted_sections_resume_point() {
+        let mut package = ContentPackage {
+            outline: vec![("Intro".into(), String::new()), ("Body".into(), String::new()), ("End".into(), String::new())],
+            sections: vec![("Intro".into(), "text".into()), ("Body".into(), "text".into())],
+            ..Default::default()
+        };
+        assert_eq!(drafted_sections(&package), 2);
+
+        // An edited outline invalidates sections drafted for the old one
+        package.outline[1].0 = "Details".into();
+        assert_eq!(drafted_sections(&package), 1);
+    }
+
+    #[test]
+    fn test_parse_translated_section() {
+        assert_eq!(
+            parse_translated_section("## Introduction\n\nBonjour le monde.", "Intro"),
+            ("Introduction".to_string(), "Bonjour le monde.".to_string())
+        );
+        assert_eq!(
+            parse_translated_section("Bonjour le monde.", "Intro"),
+            ("Intro".to_string(), "Bonjour le monde.".to_string())
+        );
+    }
+}
@@ -5,11 +5,16 @@
 //! - Web pages (article extraction)
 //! - Local files (txt, md)
 //!
+//! It also gathers the sources the content pipeline's research stage reads:
+//! pages the user named, web search hits and feed entries about the topic.
+//!
 //! Phase 2.4: Content Workflow
 
 use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+#[cfg(feature = "server")]
+use crate::models::{ResearchSource, ResearchSourceKind, ResearchSources, MAX_SOURCE_CHARS, topic_relevance};
 
 /// Content source types
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -136,7 +141,11 @@ pub struct FeedEntry {
 pub async fn fetch_rss_feed(url: &str) -> Result<Vec<FeedEntry>, String> {
     use feed_rs::parser;
 
-    let response = reqwest::get(url)
+    if super::mock::is_enabled() {
+        return Ok(super::mock::rss_entries(url));
+    }
+
+    let response = super::http::get(crate::models::NetworkFeature::ContentSources, url)
         .await
         .map_err(|e| format!("Failed to fetch RSS feed: {}", e))?;
 
@@ -165,7 +174,11 @@ pub async fn fetch_rss_feed(url: &str) -> Result<Vec<FeedEntry>, String> {
 /// Extract article content from a URL using readability
 #[cfg(feature = "server")]
 pub async fn extract_article(url: &str) -> Result<Article, String> {
-    let response = reqwest::get(url)
+    if super::mock::is_enabled() {
+        return Ok(super::mock::article(url));
+    }
+
+    let response = super::http::get(crate::models::NetworkFeature::ContentSources, url)
         .await
         .map_err(|e| format!("Failed to fetch URL: {}", e))?;
 
@@ -204,6 +217,66 @@ pub fn read_local_file(path: &PathBuf) -> Result<Article, String> {
     Ok(article)
 }
 
+/// Finds up to `limit` sources about the topic and reads them: the given
+/// pages first, then web search hits (when configured), then the feed
+/// entries that mention the topic most. Each source comes with its text,
+/// or with an error and the search or feed snippet when it couldn't be read.
+#[cfg(feature = "server")]
+pub async fn gather_research(topic: &str, sources: &ResearchSources, limit: usize) -> Vec<(ResearchSource, String)> {
+    // (kind, title, url, snippet)
+    let mut candidates: Vec<(ResearchSourceKind, String, String, String)> = sources.urls.iter()
+        .map(|url| url.trim())
+        .filter(|url| !url.is_empty())
+        .map(|url| (ResearchSourceKind::Url, url.to_string(), url.to_string(), String::new()))
+        .collect();
+
+    if super::web_search::is_enabled() {
+        match super::web_search::search(topic, super::web_search::DEFAULT_RESULT_COUNT).await {
+            Ok(results) => candidates.extend(results.into_iter().map(|r| (ResearchSourceKind::WebSearch, r.title, r.url, r.snippet))),
+            Err(e) => tracing::warn!("Research web search failed: {}", e),
+        }
+    }
+
+    let mut entries: Vec<(usize, FeedEntry)> = Vec::new();
+    for feed in sources.feeds.iter().map(|f| f.trim()).filter(|f| !f.is_empty()) {
+        match fetch_rss_feed(feed).await {
+            Ok(feed_entries) => entries.extend(feed_entries.into_iter().filter_map(|entry| {
+                let text = format!("{} {}", entry.title, entry.summary.as_deref().unwrap_or_default());
+                let relevance = topic_relevance(topic, &text);
+                (relevance > 0 && !entry.url.is_empty()).then_some((relevance, entry))
+            })),
+            Err(e) => tracing::warn!("Research feed {} failed: {}", feed, e),
+        }
+    }
+    entries.sort_by_key(|(relevance, _)| std::cmp::Reverse(*relevance));
+    candidates.extend(entries.into_iter().map(|(_, entry)| {
+        (ResearchSourceKind::Feed, entry.title, entry.url, entry.summary.unwrap_or_default())
+    }));
+
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|(_, _, url, _)| seen.insert(url.trim_end_matches('/').to_string()));
+    candidates.truncate(limit);
+
+    let mut gathered = Vec::new();
+    for (kind, title, url, snippet) in candidates {
+        let mut source = ResearchSource { kind, title, url: Some(url.clone()), summary: String::new(), key_points: Vec::new(), error: None };
+        let text = match extract_article(&url).await {
+            Ok(article) => {
+                if !article.title.trim().is_empty() {
+                    source.title = article.title;
+                }
+                article.content
+            }
+            Err(e) => {
+                source.error = Some(e);
+                snippet
+            }
+        };
+        gathered.push((source, text.chars().take(MAX_SOURCE_CHARS).collect()));
+    }
+    gathered
+}
+
 /// Source manager for handling multiple content sources
 #[derive(Default)]
 pub struct SourceManager {
@@ -0,0 +1,214 @@
+//! System Diagnostics
+//!
+//! Samples process memory, GPU/Metal memory, disk usage of the data
+//! directory and HuggingFace cache, and LLM throughput.
+//!
+//! All probes are best-effort: a probe that is unsupported on the current
+//! platform returns `None` instead of an error.
+
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+
+use crate::models::SystemStats;
+use super::model_manager::ModelManager;
+
+/// Directory sizes are expensive to compute, so they are refreshed at most this often
+const DISK_USAGE_TTL: Duration = Duration::from_secs(60);
+
+/// Running LLM throughput counters
+#[derive(Default)]
+struct Throughput {
+    last_tokens_per_sec: Option<f32>,
+    total_tokens: u64,
+    total_secs: f64,
+}
+
+static THROUGHPUT: Lazy<Mutex<Throughput>> = Lazy::new(|| Mutex::new(Throughput::default()));
+
+/// Cached (data_dir_mb, hf_cache_mb) with the time they were measured
+static DISK_USAGE: Lazy<Mutex<Option<(Instant, u64, u64)>>> = Lazy::new(|| Mutex::new(None));
+
+/// Records a finished LLM generation for throughput statistics
+pub fn record_generation(tokens: usize, elapsed: Duration) {
+    let secs = elapsed.as_secs_f64();
+    if tokens == 0 || secs <= 0.0 {
+        return;
+    }
+
+    if let Ok(mut stats) = THROUGHPUT.lock() {
+        stats.last_tokens_per_sec = Some((tokens as f64 / secs) as f32);
+        stats.total_tokens += tokens as u64;
+        stats.total_secs += secs;
+    }
+}
+
+/// Collects a full resource usage snapshot. Blocking - call from a blocking task.
+pub fn collect_stats() -> SystemStats {
+    let (gpu_memory_used_mb, gpu_memory_total_mb, gpu_name) = gpu_memory()
+        .map(|(used, total, name)| (Some(used), total, Some(name)))
+        .unwrap_or((None, None, None));
+    let total_memory_mb = total_memory_mb();
+    let (data_dir_size_mb, hf_cache_size_mb) = disk_usage();
+
+    let (last_tokens_per_sec, avg_tokens_per_sec, total_tokens_generated) = THROUGHPUT
+        .lock()
+        .map(|t| {
+            let avg = (t.total_secs > 0.0).then(|| (t.total_tokens as f64 / t.total_secs) as f32);
+            (t.last_tokens_per_sec, avg, t.total_tokens)
+        })
+        .unwrap_or((None, None, 0));
+
+    SystemStats {
+        process_memory_mb: process_memory_mb(),
+        total_memory_mb,
+        gpu_memory_used_mb,
+        // Apple Silicon GPUs share system memory
+        gpu_memory_total_mb: gpu_memory_total_mb.or(if cfg!(target_os = "macos") { total_memory_mb } else { None }),
+        gpu_name,
+        data_dir_size_mb,
+        hf_cache_size_mb,
+        last_tokens_per_sec,
+        avg_tokens_per_sec,
+        total_tokens_generated,
+        queued_generations: super::llm_queue::status().waiting.len(),
+        sampled_at: Some(chrono::Utc::now()),
+    }
+}
+
+/// Resident set size of this process in MB
+fn process_memory_mb() -> Option<u64> {
+    if let Ok(status) = std::fs::read_to_string("/proc/self/status") {
+        return parse_kb_field(&status, "VmRSS:").map(|kb| kb / 1024);
+    }
+
+    // macOS and other Unixes: ask ps for the RSS in KB
+    let output = Command::new("ps")
+        .args(["-o", "rss=", "-p", &std::process::id().to_string()])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|kb| kb / 1024)
+}
+
+/// Total physical memory in MB
+fn total_memory_mb() -> Option<u64> {
+    if let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") {
+        return parse_kb_field(&meminfo, "MemTotal:").map(|kb| kb / 1024);
+    }
+
+    let output = Command::new("sysctl").args(["-n", "hw.memsize"]).output().ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|bytes| bytes / (1024 * 1024))
+}
+
+/// GPU memory as (used_mb, total_mb, name)
+fn gpu_memory() -> Option<(u64, Option<u64>, String)> {
+    // NVIDIA
+    if let Ok(output) = Command::new("nvidia-smi")
+        .args(["--query-gpu=name,memory.used,memory.total", "--format=csv,noheader,nounits"])
+        .output()
+    {
+        if output.status.success() {
+            if let Some(parsed) = parse_nvidia_smi(&String::from_utf8_lossy(&output.stdout)) {
+                return Some(parsed);
+            }
+        }
+    }
+
+    // Apple Silicon (Metal): the accelerator reports its in-use system memory
+    if cfg!(target_os = "macos") {
+        let output = Command::new("ioreg")
+            .args(["-r", "-d", "1", "-w", "0", "-c", "IOAccelerator"])
+            .output()
+            .ok()?;
+        let used_bytes = parse_ioreg_number(&String::from_utf8_lossy(&output.stdout), "In use system memory")?;
+        return Some((used_bytes / (1024 * 1024), None, "Apple GPU (Metal)".to_string()));
+    }
+
+    None
+}
+
+/// Sizes of the data directory and HF cache in MB, cached for [`DISK_USAGE_TTL`]
+fn disk_usage() -> (u64, u64) {
+    if let Ok(cache) = DISK_USAGE.lock() {
+        if let Some((at, data_mb, hf_mb)) = *cache {
+            if at.elapsed() < DISK_USAGE_TTL {
+                return (data_mb, hf_mb);
+            }
+        }
+    }
+
+    let data_mb = ModelManager::calculate_dir_size(&crate::storage::database::get_data_dir())
+        .unwrap_or(0) / (1024 * 1024);
+    let hf_mb = ModelManager::calculate_dir_size(&ModelManager::get_cache_dir())
+        .unwrap_or(0) / (1024 * 1024);
+
+    if let Ok(mut cache) = DISK_USAGE.lock() {
+        *cache = Some((Instant::now(), data_mb, hf_mb));
+    }
+    (data_mb, hf_mb)
+}
+
+/// Parses a "Key:   12345 kB" line from /proc files
+fn parse_kb_field(content: &str, key: &str) -> Option<u64> {
+    content.lines()
+        .find(|line| line.starts_with(key))?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+/// Parses the first line of `nvidia-smi --query-gpu=name,memory.used,memory.total`
+fn parse_nvidia_smi(output: &str) -> Option<(u64, Option<u64>, String)> {
+    let line = output.lines().next()?;
+    let mut parts = line.split(',').map(str::trim);
+    let name = parts.next()?.to_string();
+    let used = parts.next()?.parse().ok()?;
+    let total = parts.next().and_then(|t| t.parse().ok());
+    Some((used, total, name))
+}
+
+/// Extracts `"<key>"=<number>` from ioreg output
+fn parse_ioreg_number(output: &str, key: &str) -> Option<u64> {
+    let needle = format!("\"{}\"=", key);
+    let start = output.find(&needle)? + needle.len();
+    output[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kb_field() {
+        let status = "Name:\tlocal_ai\nVmRSS:\t  204800 kB\nThreads:\t12\n";
+        assert_eq!(parse_kb_field(status, "VmRSS:"), Some(204800));
+        assert_eq!(parse_kb_field(status, "VmSwap:"), None);
+    }
+
+    #[test]
+    fn test_parse_nvidia_smi() {
+        let parsed = parse_nvidia_smi("NVIDIA GeForce RTX 4090, 5120, 24564\n").unwrap();
+        assert_eq!(parsed, (5120, Some(24564), "NVIDIA GeForce RTX 4090".to_string()));
+    }
+
+    #[test]
+    fn test_parse_ioreg_number() {
+        let output = r#""PerformanceStatistics" = {"Alloc system memory"=123,"In use system memory"=1073741824}"#;
+        assert_eq!(parse_ioreg_number(output, "In use system memory"), Some(1073741824));
+    }
+}
@@ -0,0 +1,143 @@
+//! Diagram Rendering
+//!
+//! Renders Mermaid and Graphviz code blocks with the locally installed
+//! command line tools: `mmdc` from `@mermaid-js/mermaid-cli` and `dot` from
+//! Graphviz. When a tool is missing or the source doesn't parse, the caller
+//! shows the code block instead. Rendered SVGs are cached in memory, since
+//! a message is rendered again whenever the chat view updates. The page
+//! shows them as images, so scripts, event handlers and links a diagram's
+//! source puts into the SVG never run.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use tokio::process::Command;
+
+use crate::models::DiagramKind;
+
+/// Longest a renderer may run; Mermaid starts a headless browser
+const RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest diagram source accepted
+const MAX_SOURCE_BYTES: usize = 64 * 1024;
+
+/// Rendered SVGs kept in memory
+const MAX_CACHED: usize = 100;
+
+static SVG_CACHE: Lazy<Mutex<HashMap<u64, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Output format
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiagramFormat {
+    Svg,
+    Png,
+}
+
+impl DiagramFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            DiagramFormat::Svg => "svg",
+            DiagramFormat::Png => "png",
+        }
+    }
+}
+
+/// The diagram as an SVG document
+pub async fn render_svg(kind: DiagramKind, source: &str) -> Result<String, String> {
+    let key = cache_key(kind, source);
+    if let Some(svg) = SVG_CACHE.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+        return Ok(svg);
+    }
+
+    let svg = String::from_utf8(render(kind, source, DiagramFormat::Svg).await?)
+        .map_err(|_| "The renderer produced an invalid SVG".to_string())?;
+
+    if let Ok(mut cache) = SVG_CACHE.lock() {
+        if cache.len() >= MAX_CACHED {
+            cache.clear();
+        }
+        cache.insert(key, svg.clone());
+    }
+    Ok(svg)
+}
+
+/// The diagram as a PNG image, for articles
+pub async fn render_png(kind: DiagramKind, source: &str) -> Result<Vec<u8>, String> {
+    render(kind, source, DiagramFormat::Png).await
+}
+
+async fn render(kind: DiagramKind, source: &str, format: DiagramFormat) -> Result<Vec<u8>, String> {
+    if source.trim().is_empty() {
+        return Err("The diagram is empty".to_string());
+    }
+    if source.len() > MAX_SOURCE_BYTES {
+        return Err("The diagram is too large to render".to_string());
+    }
+    // Both tools work on files, so the source and image go through a
+    // temporary folder
+    let dir = std::env::temp_dir().join(format!("idoris-diagram-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let input = dir.join(format!("diagram.{}", kind.lang()));
+    let output = dir.join(format!("diagram.{}", format.extension()));
+    let result = run_renderer(kind, format, &input, &output, source).await;
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+async fn run_renderer(kind: DiagramKind, format: DiagramFormat, input: &Path, output: &Path, source: &str) -> Result<Vec<u8>, String> {
+    std::fs::write(input, source).map_err(|e| e.to_string())?;
+
+    let mut command = match kind {
+        DiagramKind::Graphviz => {
+            let mut c = Command::new("dot");
+            c.arg(format!("-T{}", format.extension())).arg(input).arg("-o").arg(output);
+            c
+        }
+        DiagramKind::Mermaid => {
+            let mut c = Command::new("mmdc");
+            c.arg("-i").arg(input).arg("-o").arg(output).args(["-b", "white", "--quiet"]);
+            c
+        }
+    };
+    let child = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|_| match kind {
+            DiagramKind::Graphviz => "Graphviz is not installed (the `dot` command was not found)".to_string(),
+            DiagramKind::Mermaid => "Mermaid CLI is not installed (the `mmdc` command was not found)".to_string(),
+        })?;
+
+    let result = tokio::time::timeout(RENDER_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| "Rendering the diagram timed out".to_string())?
+        .map_err(|e| e.to_string())?;
+    if !result.status.success() {
+        let fallback = format!("{} could not render the diagram", kind.display_name());
+        return Err(first_line(&result.stderr, &fallback));
+    }
+    std::fs::read(output).map_err(|e| e.to_string())
+}
+
+fn cache_key(kind: DiagramKind, source: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    kind.lang().hash(&mut hasher);
+    source.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// First line of a tool's error output
+fn first_line(stderr: &[u8], fallback: &str) -> String {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| fallback.to_string())
+}
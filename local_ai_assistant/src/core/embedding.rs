@@ -1,45 +1,340 @@
 //! Embedding Model Implementation
 //!
-//! This module provides functionality for text embedding generation using the BERT model.
-//! It manages a singleton embedding model instance and offers methods to convert text
-//! into numerical vector representations for semantic search and comparison.
+//! This module provides functionality for text embedding generation.
+//! The backend is configurable: a local BERT-family model run through kalosm,
+//! or any OpenAI-compatible `/v1/embeddings` endpoint. The same embedder is
+//! used for direct embedding calls and by the vector store for indexing.
+//!
+//! The configuration is persisted to `./data/embedding.json`. Changing the
+//! backend changes the vector space, so the vector store must re-embed its
+//! documents (see [`super::vector_store::reembed_documents`]) and memories
+//! their facts (see [`super::memory::reembed_memories`]).
+//!
+//! Texts are sent to the model in batches of the configured size. Computed
+//! vectors are cached by content hash (see [`super::embedding_cache`]),
+//! so re-indexing only embeds chunks that are new or have changed.
 
-use std::sync::Mutex;
-use kalosm::language::Bert;
-use tokio::sync::OnceCell;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use kalosm::language::{Bert, BertSource, Embedder, EmbedderExt, Embedding, EmbeddingInput};
+use once_cell::sync::Lazy;
+use tokio::sync::RwLock;
 
-/// Global singleton for the BERT embedding model
-/// Uses OnceCell and Mutex for thread-safe access and initialization
-pub static EMBEDDING_MODEL: OnceCell<Mutex<Bert>> = OnceCell::const_new();
+use crate::models::{EmbeddingBackend, EmbeddingConfig, LocalEmbeddingModel, NetworkFeature};
+use super::error::IDorisError;
 
-/// Initializes the BERT embedding model
-///
-/// This function:
-/// 1. Checks if the model is already initialized
-/// 2. If not, creates a new Bert model instance
-/// 3. Stores the model in the global singleton
+/// Global embedder instance, replaced when the backend changes
+static EMBEDDER: Lazy<RwLock<Option<ConfiguredEmbedder>>> = Lazy::new(|| RwLock::new(None));
+
+/// Active configuration, loaded lazily from disk
+static CONFIG: Lazy<Mutex<EmbeddingConfig>> = Lazy::new(|| Mutex::new(load_config()));
+
+/// Embedder selected by the user configuration
+#[derive(Clone)]
+pub enum ConfiguredEmbedder {
+    Local(Bert, LocalEmbeddingModel),
+    Remote(Arc<RemoteEmbedder>),
+    /// Deterministic vectors from [`super::mock`]
+    Mock,
+}
+
+/// Client for an OpenAI-compatible embeddings endpoint
+pub struct RemoteEmbedder {
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    /// Backend fingerprint, used as the cache key
+    fingerprint: String,
+}
+
+impl RemoteEmbedder {
+    fn new(base_url: &str, model: &str, api_key_env: Option<&str>) -> Self {
+        let backend = EmbeddingBackend::OpenAiCompatible {
+            base_url: base_url.to_string(),
+            model: model.to_string(),
+            api_key_env: api_key_env.map(str::to_string),
+        };
+        Self {
+            fingerprint: backend.fingerprint(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: api_key_env.and_then(|key| std::env::var(key).ok()),
+        }
+    }
+
+    /// Embeds several texts with a single request
+    async fn embed_many(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, IDorisError> {
+        let mut request = super::http::client()
+            .post(format!("{}/embeddings", self.base_url))
+            .json(&serde_json::json!({ "model": self.model, "input": texts }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = super::http::send(NetworkFeature::Embeddings, request).await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(IDorisError::ApiError(format!("{} {}", status, body)));
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        parse_embeddings_response(&body)
+    }
+}
+
+impl ConfiguredEmbedder {
+    /// Identifies the vector space for caching; `None` for mock vectors,
+    /// which are cheap and must not pollute the cache
+    fn fingerprint(&self) -> Option<String> {
+        match self {
+            ConfiguredEmbedder::Local(_, model) => Some(EmbeddingBackend::Local(*model).fingerprint()),
+            ConfiguredEmbedder::Remote(remote) => Some(remote.fingerprint.clone()),
+            ConfiguredEmbedder::Mock => None,
+        }
+    }
+
+    /// Runs the model on every input, one batch at a time
+    async fn embed_uncached(&self, inputs: Vec<EmbeddingInput>) -> Result<Vec<Embedding>, IDorisError> {
+        in_batches(inputs, get_config().batch_size, |batch| self.embed_batch_uncached(batch)).await
+    }
+
+    /// Runs the model on one batch with a single call
+    async fn embed_batch_uncached(&self, inputs: Vec<EmbeddingInput>) -> Result<Vec<Embedding>, IDorisError> {
+        match self {
+            ConfiguredEmbedder::Local(bert, _) => bert.embed_vec_for(inputs)
+                .await
+                .map_err(|e| IDorisError::ModelError(e.to_string())),
+            ConfiguredEmbedder::Remote(remote) => {
+                let texts = inputs.into_iter().map(|i| i.text).collect();
+                Ok(remote.embed_many(texts).await?.into_iter().map(Embedding::from).collect())
+            }
+            ConfiguredEmbedder::Mock => Ok(inputs.iter().map(|i| Embedding::from(super::mock::embedding(&i.text))).collect()),
+        }
+    }
+}
+
+/// Calls `embed` on consecutive slices of at most `batch_size` items and
+/// concatenates the results in order
+async fn in_batches<T, R, E, F, Fut>(items: Vec<T>, batch_size: usize, mut embed: F) -> Result<Vec<R>, E>
+where
+    F: FnMut(Vec<T>) -> Fut,
+    Fut: Future<Output = Result<Vec<R>, E>>,
+{
+    let batch_size = batch_size.max(1);
+    let mut results = Vec::with_capacity(items.len());
+    let mut items = items.into_iter().peekable();
+    while items.peek().is_some() {
+        let batch: Vec<T> = items.by_ref().take(batch_size).collect();
+        results.extend(embed(batch).await?);
+    }
+    Ok(results)
+}
+
+impl Embedder for ConfiguredEmbedder {
+    type Error = IDorisError;
+
+    async fn embed_for(&self, input: EmbeddingInput) -> Result<Embedding, Self::Error> {
+        self.embed_vec_for(vec![input])
+            .await?
+            .pop()
+            .ok_or_else(|| IDorisError::ApiError("Empty embeddings response".to_string()))
+    }
+
+    async fn embed_vec_for(&self, inputs: Vec<EmbeddingInput>) -> Result<Vec<Embedding>, Self::Error> {
+        let Some(fingerprint) = self.fingerprint() else {
+            return self.embed_uncached(inputs).await;
+        };
+
+        // Queries and documents may be embedded differently, so each variant
+        // gets its own cache key
+        let keys: Vec<String> = inputs.iter().map(|i| format!("{}|{:?}", fingerprint, i.variant)).collect();
+        let mut vectors: Vec<Option<Vec<f32>>> = vec![None; inputs.len()];
+        for key in unique(&keys) {
+            let indices: Vec<usize> = (0..inputs.len()).filter(|&i| keys[i] == key).collect();
+            let texts: Vec<&str> = indices.iter().map(|&i| inputs[i].text.as_str()).collect();
+            for (&i, cached) in indices.iter().zip(super::embedding_cache::get_many(&key, &texts)) {
+                vectors[i] = cached;
+            }
+        }
+
+        let missing: Vec<usize> = (0..inputs.len()).filter(|&i| vectors[i].is_none()).collect();
+        if missing.is_empty() {
+            tracing::debug!(cached = inputs.len(), "Embeddings served from cache");
+        } else {
+            let computed = self.embed_uncached(missing.iter().map(|&i| inputs[i].clone()).collect()).await?;
+            if computed.len() != missing.len() {
+                return Err(IDorisError::ApiError(format!(
+                    "Expected {} embeddings, got {}", missing.len(), computed.len()
+                )));
+            }
+            for (&i, embedding) in missing.iter().zip(computed) {
+                vectors[i] = Some(embedding.vector().to_vec());
+            }
+            for key in unique(&keys) {
+                let entries: Vec<(&str, &[f32])> = missing.iter()
+                    .filter(|&&i| keys[i] == key)
+                    .filter_map(|&i| vectors[i].as_deref().map(|v| (inputs[i].text.as_str(), v)))
+                    .collect();
+                super::embedding_cache::put_many(&key, &entries);
+            }
+            tracing::debug!(cached = inputs.len() - missing.len(), embedded = missing.len(), "Embeddings computed");
+        }
+
+        Ok(vectors.into_iter().map(|v| Embedding::from(v.unwrap_or_default())).collect())
+    }
+}
+
+/// Distinct keys in first-seen order
+fn unique(keys: &[String]) -> Vec<String> {
+    let mut seen: Vec<String> = Vec::new();
+    for key in keys {
+        if !seen.contains(key) {
+            seen.push(key.clone());
+        }
+    }
+    seen
+}
+
+/// Path of the persisted configuration file
+fn config_path() -> PathBuf {
+    crate::storage::database::get_data_dir().join("embedding.json")
+}
+
+/// Reads the configuration file, falling back to the default local model
+fn load_config() -> EmbeddingConfig {
+    std::fs::read_to_string(config_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Writes the configuration file
+fn save_config(config: &EmbeddingConfig) -> Result<(), String> {
+    let path = config_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Returns the active embedding configuration
+pub fn get_config() -> EmbeddingConfig {
+    CONFIG.lock().map(|c| c.clone()).unwrap_or_default()
+}
+
+/// Maps a local model choice to its kalosm source
+fn bert_source(model: LocalEmbeddingModel) -> BertSource {
+    match model {
+        LocalEmbeddingModel::ArcticExtraSmall => BertSource::snowflake_arctic_embed_extra_small(),
+        LocalEmbeddingModel::ArcticSmall => BertSource::snowflake_arctic_embed_small(),
+        LocalEmbeddingModel::ArcticMedium => BertSource::snowflake_arctic_embed_medium(),
+        LocalEmbeddingModel::ArcticLarge => BertSource::snowflake_arctic_embed_large(),
+    }
+}
+
+/// Builds (and for local models, downloads/loads) the embedder for a backend
+async fn build_embedder(backend: &EmbeddingBackend) -> Result<ConfiguredEmbedder, String> {
+    if super::mock::is_enabled() {
+        tracing::info!("Using mock embeddings instead of {}", backend.display_name());
+        return Ok(ConfiguredEmbedder::Mock);
+    }
+
+    match backend {
+        EmbeddingBackend::Local(model) => {
+            tracing::info!("Loading local embedding model {}...", model.id());
+            let bert = Bert::builder()
+                .with_source(bert_source(*model))
+                .build()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(ConfiguredEmbedder::Local(bert, *model))
+        }
+        EmbeddingBackend::OpenAiCompatible { base_url, model, api_key_env } => {
+            tracing::info!("Using remote embedding endpoint {} ({})", base_url, model);
+            Ok(ConfiguredEmbedder::Remote(Arc::new(RemoteEmbedder::new(base_url, model, api_key_env.as_deref()))))
+        }
+    }
+}
+
+/// Initializes the configured embedding model
 ///
 /// The embedding model is used to convert text into vector representations
 /// that capture semantic meaning, which enables similarity-based searches.
 ///
 /// Returns Ok(()) on success or an error message on failure
 pub async fn init_embedding_model() -> Result<(), String> {
-    if EMBEDDING_MODEL.get().is_none() {
-        println!("Initializing embedding model...");
-        let bert = Bert::new().await.map_err(|e| e.to_string())?;
-        println!("Embedding model loaded successfully");
-        EMBEDDING_MODEL.set(Mutex::new(bert))
-            .map_err(|_| "Couldn't set embedding model".to_string())?;
+    if is_initialized() {
+        return Ok(());
     }
+
+    let config = get_config();
+    tracing::info!("Initializing embedding model: {}", config.backend.display_name());
+    let embedder = build_embedder(&config.backend).await?;
+    *EMBEDDER.write().await = Some(embedder);
+    tracing::info!("Embedding model loaded successfully");
     Ok(())
 }
 
-/// Converts input text into vector embeddings
+/// Switches to a different embedding backend.
 ///
-/// This function:
-/// 1. Accesses the global embedding model
-/// 2. Generates vector embeddings for the provided text
-/// 3. Returns the vector representation
+/// The new embedder is built and probed before it replaces the current one,
+/// so a misconfigured endpoint leaves the previous backend active.
+///
+/// Returns `true` when the vector space changed and indexed documents must
+/// be re-embedded.
+pub async fn set_backend(backend: EmbeddingBackend) -> Result<bool, String> {
+    let previous = get_config();
+
+    let embedder = build_embedder(&backend).await?;
+    let dimensions = embedder.embed("dimension probe")
+        .await
+        .map_err(|e| format!("Embedding backend check failed: {}", e))?
+        .vector()
+        .len();
+
+    let changed = previous.backend.fingerprint() != backend.fingerprint()
+        || previous.dimensions.is_some_and(|d| d != dimensions);
+
+    let config = EmbeddingConfig { backend, dimensions: Some(dimensions), batch_size: previous.batch_size };
+    save_config(&config)?;
+    if let Ok(mut guard) = CONFIG.lock() {
+        *guard = config;
+    }
+    *EMBEDDER.write().await = Some(embedder);
+
+    tracing::info!(dimensions, changed, "Embedding backend updated");
+    Ok(changed)
+}
+
+/// Sets how many texts are sent to the model per call
+pub fn set_batch_size(batch_size: usize) -> Result<(), String> {
+    if batch_size == 0 {
+        return Err("Batch size must be at least 1".to_string());
+    }
+    let config = EmbeddingConfig { batch_size, ..get_config() };
+    save_config(&config)?;
+    if let Ok(mut guard) = CONFIG.lock() {
+        *guard = config;
+    }
+    tracing::info!(batch_size, "Embedding batch size updated");
+    Ok(())
+}
+
+/// Returns a handle to the active embedder
+pub async fn current_embedder() -> Result<ConfiguredEmbedder, String> {
+    if !is_initialized() {
+        init_embedding_model().await?;
+    }
+    EMBEDDER.read()
+        .await
+        .clone()
+        .ok_or_else(|| "Embedding model not initialized".to_string())
+}
+
+/// Converts input text into vector embeddings
 ///
 /// The generated embeddings capture the semantic meaning of the text
 /// and can be used for similarity comparisons and semantic search.
@@ -50,23 +345,18 @@ pub async fn init_embedding_model() -> Result<(), String> {
 /// # Returns
 /// * `Result<Vec<f32>, String>` - The embedding vector or an error message
 pub async fn embed_text(text: &str) -> Result<Vec<f32>, String> {
-    use kalosm::language::EmbedderExt;
-    let embedding_model = EMBEDDING_MODEL
-        .get()
-        .ok_or("Embedding model not initialized")?
-        .lock()
-        .map_err(|_| "Error locking embedding model")?;
-
-    let embeddings = embedding_model.embed(text)
+    let embedder = current_embedder().await?;
+    let embedding = embedder.embed(text)
         .await
         .map_err(|e| e.to_string())?;
-    println!("Embedding generated for text: {:?}", embeddings.vector().to_vec());
-    Ok(embeddings.vector().to_vec())
+    tracing::debug!("Embedding generated ({} dimensions)", embedding.vector().len());
+    Ok(embedding.vector().to_vec())
 }
 
-/// Check if the embedding model is initialized
+/// Check if the embedding model is initialized; reads as not ready while a
+/// newly loaded model is being stored
 pub fn is_initialized() -> bool {
-    EMBEDDING_MODEL.get().is_some()
+    EMBEDDER.try_read().map(|e| e.is_some()).unwrap_or(false)
 }
 
 /// Wrapper function for async init
@@ -74,11 +364,97 @@ pub async fn init_model() -> Result<(), anyhow::Error> {
     init_embedding_model().await.map_err(|e| anyhow::anyhow!(e))
 }
 
-/// Generate embeddings for multiple texts
+/// Generate embeddings for multiple texts, batched per the configured size
 pub async fn embed_batch(texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
-    let mut embeddings = Vec::with_capacity(texts.len());
-    for text in texts {
-        embeddings.push(embed_text(text).await?);
+    let embedder = current_embedder().await?;
+    let embeddings = embedder.embed_vec(texts.to_vec())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(embeddings.iter().map(|e| e.vector().to_vec()).collect())
+}
+
+/// Extracts vectors from an OpenAI embeddings response, ordered by `index`
+fn parse_embeddings_response(body: &serde_json::Value) -> Result<Vec<Vec<f32>>, IDorisError> {
+    let data = body.get("data")
+        .and_then(|d| d.as_array())
+        .ok_or_else(|| IDorisError::ApiError("Embeddings response has no data".to_string()))?;
+
+    let mut indexed: Vec<(usize, Vec<f32>)> = data.iter()
+        .enumerate()
+        .map(|(position, item)| {
+            let index = item.get("index").and_then(|i| i.as_u64()).map(|i| i as usize).unwrap_or(position);
+            let vector = item.get("embedding")
+                .and_then(|e| e.as_array())
+                .map(|values| values.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect())
+                .unwrap_or_default();
+            (index, vector)
+        })
+        .collect();
+    indexed.sort_by_key(|(index, _)| *index);
+
+    Ok(indexed.into_iter().map(|(_, vector)| vector).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_embeddings_response_orders_by_index() {
+        let body = serde_json::json!({
+            "data": [
+                { "index": 1, "embedding": [0.5, 0.25] },
+                { "index": 0, "embedding": [1.0, 0.0] }
+            ]
+        });
+        let vectors = parse_embeddings_response(&body).unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.5, 0.25]]);
+        assert!(parse_embeddings_response(&serde_json::json!({})).is_err());
+    }
+
+    /// Stand-in for a model call: a fixed cost per call (kernel launch or
+    /// HTTP round trip) plus a small cost per text
+    async fn simulated_model(batch: Vec<usize>, calls: &std::cell::Cell<usize>) -> Result<Vec<f32>, String> {
+        calls.set(calls.get() + 1);
+        std::thread::sleep(std::time::Duration::from_millis(2) + std::time::Duration::from_micros(20) * batch.len() as u32);
+        Ok(batch.into_iter().map(|i| i as f32).collect())
+    }
+
+    #[test]
+    fn test_batching_throughput() {
+        // A few-hundred-chunk document
+        let chunks: Vec<usize> = (0..300).collect();
+
+        let run = |batch_size: usize| {
+            let calls = std::cell::Cell::new(0);
+            let started = std::time::Instant::now();
+            let vectors = futures::executor::block_on(
+                in_batches(chunks.clone(), batch_size, |batch| simulated_model(batch, &calls))
+            ).unwrap();
+            (vectors, calls.get(), started.elapsed())
+        };
+
+        let (one_by_one, single_calls, single_time) = run(1);
+        let (batched, batched_calls, batched_time) = run(32);
+
+        assert_eq!(one_by_one, batched);
+        assert_eq!(batched, (0..300).map(|i| i as f32).collect::<Vec<_>>());
+        assert_eq!((single_calls, batched_calls), (300, 10));
+        assert!(
+            batched_time * 5 < single_time,
+            "batched {:?} vs one-by-one {:?}", batched_time, single_time
+        );
+    }
+
+    #[test]
+    fn test_in_batches_stops_on_error() {
+        let result: Result<Vec<usize>, String> = futures::executor::block_on(in_batches(
+            (0..10).collect(),
+            4,
+            |batch: Vec<usize>| async move {
+                if batch.contains(&5) { Err("model failed".to_string()) } else { Ok(batch) }
+            },
+        ));
+        assert_eq!(result, Err("model failed".to_string()));
     }
-    Ok(embeddings)
 }
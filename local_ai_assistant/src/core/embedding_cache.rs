@@ -0,0 +1,123 @@
+//! Embedding Cache
+//!
+//! Embeddings keyed by the embedding model and a SHA-256 hash of the text,
+//! persisted to `./data/embedding_cache.db`. Re-indexing a stable corpus then
+//! only runs the model for chunks that are new or changed; everything else
+//! is read back from the cache.
+//!
+//! The cache is an optimisation only: if it cannot be opened or read,
+//! embeddings are computed as usual.
+
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use rusqlite::{Connection, OptionalExtension};
+use sha2::{Digest, Sha256};
+
+/// Oldest entries beyond this are dropped when the cache is opened
+const MAX_ENTRIES: usize = 200_000;
+
+static CACHE: Lazy<Option<Mutex<Connection>>> = Lazy::new(|| match open() {
+    Ok(conn) => Some(Mutex::new(conn)),
+    Err(e) => {
+        tracing::warn!("Embedding cache unavailable: {}", e);
+        None
+    }
+});
+
+fn open() -> rusqlite::Result<Connection> {
+    let path = crate::storage::database::get_data_dir().join("embedding_cache.db");
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).ok();
+    }
+    let conn = Connection::open(path)?;
+    init_schema(&conn)?;
+    Ok(conn)
+}
+
+fn init_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS embeddings (
+            model TEXT NOT NULL,
+            hash TEXT NOT NULL,
+            vector BLOB NOT NULL,
+            PRIMARY KEY (model, hash)
+        );",
+    )?;
+    conn.execute(
+        "DELETE FROM embeddings WHERE rowid IN (
+            SELECT rowid FROM embeddings ORDER BY rowid DESC LIMIT -1 OFFSET ?1
+        )",
+        [MAX_ENTRIES as i64],
+    )?;
+    Ok(())
+}
+
+/// Hash identifying a text's content
+fn content_hash(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+/// Cached vectors for the texts, `None` where the text has not been embedded
+/// with `model` before
+pub fn get_many(model: &str, texts: &[&str]) -> Vec<Option<Vec<f32>>> {
+    match CACHE.as_ref().and_then(|c| c.lock().ok()) {
+        Some(conn) => lookup(&conn, model, texts),
+        None => vec![None; texts.len()],
+    }
+}
+
+/// Stores freshly computed vectors
+pub fn put_many(model: &str, entries: &[(&str, &[f32])]) {
+    let Some(mut conn) = CACHE.as_ref().and_then(|c| c.lock().ok()) else {
+        return;
+    };
+    if let Err(e) = store(&mut conn, model, entries) {
+        tracing::warn!("Failed to cache embeddings: {}", e);
+    }
+}
+
+fn lookup(conn: &Connection, model: &str, texts: &[&str]) -> Vec<Option<Vec<f32>>> {
+    let Ok(mut stmt) = conn.prepare_cached("SELECT vector FROM embeddings WHERE model = ?1 AND hash = ?2") else {
+        return vec![None; texts.len()];
+    };
+    texts.iter()
+        .map(|text| {
+            stmt.query_row(rusqlite::params![model, content_hash(text)], |row| row.get::<_, Vec<u8>>(0))
+                .optional()
+                .ok()
+                .flatten()
+                .map(|blob| blob.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect())
+        })
+        .collect()
+}
+
+fn store(conn: &mut Connection, model: &str, entries: &[(&str, &[f32])]) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare_cached("INSERT OR REPLACE INTO embeddings (model, hash, vector) VALUES (?1, ?2, ?3)")?;
+        for (text, vector) in entries {
+            let blob: Vec<u8> = vector.iter().flat_map(|v| v.to_le_bytes()).collect();
+            stmt.execute(rusqlite::params![model, content_hash(text), blob])?;
+        }
+    }
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_is_keyed_by_model_and_content() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        store(&mut conn, "local:a", &[("hello", &[1.0, -0.5]), ("world", &[0.25, 0.0])]).unwrap();
+
+        assert_eq!(
+            lookup(&conn, "local:a", &["world", "hello", "new"]),
+            vec![Some(vec![0.25, 0.0]), Some(vec![1.0, -0.5]), None]
+        );
+        assert_eq!(lookup(&conn, "local:b", &["hello"]), vec![None]);
+    }
+}
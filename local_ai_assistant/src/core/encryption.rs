@@ -0,0 +1,252 @@
+//! Chat History Encryption
+//!
+//! Optional AES-256-GCM encryption of chat titles, summaries, tags, topics,
+//! messages with their attachments and feedback comments, memories and
+//! model comparisons in SQLite. Memory embeddings, the knowledge base and
+//! settings stay in plain text. The key is derived from a passphrase with
+//! Argon2id and only kept in memory, so encrypted history is locked on
+//! every start until the passphrase is entered. The database keeps the salt
+//! and a sealed check value to tell a wrong passphrase from a right one.
+//!
+//! Encrypted values are stored as `enc1:` followed by the base64 nonce and
+//! ciphertext; plain values are read as they are, so turning encryption on
+//! or off rewrites the history in a single transaction.
+
+use std::sync::RwLock;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::Engine;
+use once_cell::sync::Lazy;
+
+use crate::models::{EncryptionStatus, validate_passphrase};
+use crate::storage::database;
+
+/// Prefix of encrypted values
+const PREFIX: &str = "enc1:";
+/// Length of the AES-GCM nonce stored in front of the ciphertext
+const NONCE_LEN: usize = 12;
+/// Text sealed with the key to check a passphrase
+const VERIFIER_TEXT: &str = "idoris-history";
+const LOCKED: &str = "Chat history is locked; enter the passphrase to unlock it";
+
+/// Salt and passphrase check, stored in the `encryption` table
+#[derive(Clone, Debug)]
+pub struct EncryptionConfig {
+    /// Argon2 salt, base64
+    pub salt: String,
+    /// `VERIFIER_TEXT` sealed with the key
+    pub verifier: String,
+}
+
+#[derive(Default)]
+struct State {
+    enabled: bool,
+    cipher: Option<Aes256Gcm>,
+}
+
+static STATE: Lazy<RwLock<State>> = Lazy::new(|| RwLock::new(State::default()));
+
+/// Called when the database opens, before anything is read
+pub fn set_enabled(enabled: bool) {
+    if let Ok(mut state) = STATE.write() {
+        state.enabled = enabled;
+    }
+}
+
+pub fn status() -> EncryptionStatus {
+    STATE.read()
+        .map(|state| EncryptionStatus { enabled: state.enabled, unlocked: state.cipher.is_some() })
+        .unwrap_or_default()
+}
+
+pub fn ensure_unlocked() -> Result<(), String> {
+    if status().is_locked() {
+        return Err(LOCKED.to_string());
+    }
+    Ok(())
+}
+
+/// Encrypts a value for storage when encryption is on
+pub fn seal(text: &str) -> Result<String, String> {
+    let state = STATE.read().map_err(|_| "Failed to lock encryption state")?;
+    match (&state.cipher, state.enabled) {
+        (Some(cipher), true) => seal_with(cipher, text),
+        (None, true) => Err(LOCKED.to_string()),
+        (_, false) => Ok(text.to_string()),
+    }
+}
+
+/// Decrypts a stored value; plain values are returned as they are
+pub fn open(text: &str) -> Result<String, String> {
+    if !text.starts_with(PREFIX) {
+        return Ok(text.to_string());
+    }
+    let state = STATE.read().map_err(|_| "Failed to lock encryption state")?;
+    match (&state.cipher, state.enabled) {
+        (Some(cipher), _) => open_with(cipher, text),
+        (None, true) => Err(LOCKED.to_string()),
+        // Plain text that happens to look encrypted
+        (None, false) => Ok(text.to_string()),
+    }
+}
+
+/// Loads the key for this run
+pub async fn unlock(passphrase: &str) -> Result<(), String> {
+    let config = database::get_encryption_config().await
+        .map_err(|e| e.to_string())?
+        .ok_or("Chat history is not encrypted")?;
+    let cipher = verified_cipher(passphrase, &config).await?;
+    set_state(true, Some(cipher));
+    tracing::info!("Chat history unlocked");
+    Ok(())
+}
+
+/// Forgets the key until the passphrase is entered again
+pub fn lock() {
+    if let Ok(mut state) = STATE.write() {
+        if state.enabled {
+            state.cipher = None;
+            tracing::info!("Chat history locked");
+        }
+    }
+}
+
+/// Encrypts all chat history with a key derived from a new passphrase.
+/// Returns the number of sessions and messages encrypted.
+pub async fn enable(passphrase: &str, confirmation: &str) -> Result<usize, String> {
+    if status().enabled {
+        return Err("Chat history is already encrypted".to_string());
+    }
+    validate_passphrase(passphrase, confirmation)?;
+    let (cipher, config) = new_key(passphrase).await?;
+    let count = database::recode_history(
+        &|text| seal_with(&cipher, text),
+        Some(&config),
+        || set_state(true, Some(cipher.clone())),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    tracing::info!("Encrypted {} sessions and messages", count);
+    Ok(count)
+}
+
+/// Changes the passphrase and re-encrypts all chat history with a fresh key
+pub async fn change_passphrase(current: &str, passphrase: &str, confirmation: &str) -> Result<usize, String> {
+    let config = database::get_encryption_config().await
+        .map_err(|e| e.to_string())?
+        .ok_or("Chat history is not encrypted")?;
+    let old = verified_cipher(current, &config).await?;
+    validate_passphrase(passphrase, confirmation)?;
+    let (cipher, config) = new_key(passphrase).await?;
+    let count = database::recode_history(
+        &|text| seal_with(&cipher, &open_with(&old, text)?),
+        Some(&config),
+        || set_state(true, Some(cipher.clone())),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    tracing::info!("Rotated the history key for {} sessions and messages", count);
+    Ok(count)
+}
+
+/// Decrypts all chat history and turns encryption off
+pub async fn disable(passphrase: &str) -> Result<usize, String> {
+    let config = database::get_encryption_config().await
+        .map_err(|e| e.to_string())?
+        .ok_or("Chat history is not encrypted")?;
+    let cipher = verified_cipher(passphrase, &config).await?;
+    let count = database::recode_history(&|text| open_with(&cipher, text), None, || set_state(false, None))
+        .await
+        .map_err(|e| e.to_string())?;
+    tracing::info!("Decrypted {} sessions and messages", count);
+    Ok(count)
+}
+
+fn set_state(enabled: bool, cipher: Option<Aes256Gcm>) {
+    if let Ok(mut state) = STATE.write() {
+        *state = State { enabled, cipher };
+    }
+}
+
+/// Derives a key with a fresh salt
+async fn new_key(passphrase: &str) -> Result<(Aes256Gcm, EncryptionConfig), String> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = derive_cipher(passphrase, salt.to_vec()).await?;
+    let config = EncryptionConfig {
+        salt: base64::engine::general_purpose::STANDARD.encode(salt),
+        verifier: seal_with(&cipher, VERIFIER_TEXT)?,
+    };
+    Ok((cipher, config))
+}
+
+/// Derives the key and checks it against the stored verifier
+async fn verified_cipher(passphrase: &str, config: &EncryptionConfig) -> Result<Aes256Gcm, String> {
+    let salt = base64::engine::general_purpose::STANDARD
+        .decode(&config.salt)
+        .map_err(|_| "The stored encryption salt is damaged")?;
+    let cipher = derive_cipher(passphrase, salt).await?;
+    match open_with(&cipher, &config.verifier) {
+        Ok(text) if text == VERIFIER_TEXT => Ok(cipher),
+        _ => Err("Wrong passphrase".to_string()),
+    }
+}
+
+/// Argon2id takes a moment on purpose, so it runs off the async runtime
+async fn derive_cipher(passphrase: &str, salt: Vec<u8>) -> Result<Aes256Gcm, String> {
+    let passphrase = passphrase.to_string();
+    tokio::task::spawn_blocking(move || {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| format!("Failed to derive the key: {}", e))?;
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn seal_with(cipher: &Aes256Gcm, text: &str) -> Result<String, String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, text.as_bytes()).map_err(|_| "Failed to encrypt")?;
+    let mut data = nonce.to_vec();
+    data.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", PREFIX, base64::engine::general_purpose::STANDARD.encode(data)))
+}
+
+/// Decrypts a value sealed with the cipher; plain values pass through
+fn open_with(cipher: &Aes256Gcm, text: &str) -> Result<String, String> {
+    let Some(encoded) = text.strip_prefix(PREFIX) else {
+        return Ok(text.to_string());
+    };
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|_| "Encrypted value is damaged")?;
+    if data.len() < NONCE_LEN {
+        return Err("Encrypted value is damaged".to_string());
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let plain = cipher.decrypt(Nonce::from_slice(nonce), ciphertext).map_err(|_| "Failed to decrypt")?;
+    String::from_utf8(plain).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher(byte: u8) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&[byte; 32]))
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let sealed = seal_with(&cipher(1), "Hello").unwrap();
+        assert!(sealed.starts_with(PREFIX));
+        assert_ne!(sealed, seal_with(&cipher(1), "Hello").unwrap());
+        assert_eq!(open_with(&cipher(1), &sealed).unwrap(), "Hello");
+        assert!(open_with(&cipher(2), &sealed).is_err());
+        assert_eq!(open_with(&cipher(1), "plain text").unwrap(), "plain text");
+    }
+}
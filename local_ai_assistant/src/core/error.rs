@@ -4,6 +4,8 @@
 
 use std::fmt;
 
+use crate::models::{AppError, ErrorCategory};
+
 /// Main error type for iDoris application
 #[derive(Debug)]
 pub enum IDorisError {
@@ -157,6 +159,42 @@ impl UserFriendlyError for IDorisError {
     }
 }
 
+/// The category server functions report, with the user-friendly message
+impl From<IDorisError> for AppError {
+    fn from(err: IDorisError) -> Self {
+        let message = err.user_message();
+        let category = match &err {
+            IDorisError::ApiError(msg) => match AppError::parse(msg).category {
+                ErrorCategory::Other => ErrorCategory::Network,
+                category => category,
+            },
+            IDorisError::ModelError(msg) => {
+                if ["not found", "not cached", "not loaded", "not initialized"].iter().any(|s| msg.contains(s)) {
+                    ErrorCategory::ModelNotReady
+                } else {
+                    ErrorCategory::Other
+                }
+            }
+            IDorisError::ConfigError(msg) => {
+                let msg = msg.to_lowercase();
+                if msg.contains("api") || msg.contains("key") || msg.contains("token") {
+                    ErrorCategory::ProviderAuth
+                } else {
+                    ErrorCategory::Other
+                }
+            }
+            IDorisError::DatabaseError(_) | IDorisError::IoError(_) => ErrorCategory::Storage,
+            IDorisError::JsonError(_) => ErrorCategory::Other,
+            IDorisError::HttpError(err) => match err.status().map(|s| s.as_u16()) {
+                Some(401 | 403) => ErrorCategory::ProviderAuth,
+                _ => ErrorCategory::Network,
+            },
+            IDorisError::Other(msg) => AppError::parse(msg).category,
+        };
+        AppError::new(category, message)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,6 +217,17 @@ mod tests {
         assert!(err.user_message().contains("download it from Settings"));
     }
 
+    #[test]
+    fn test_app_error_category() {
+        let err: AppError = IDorisError::ConfigError("TOGETHER_API_KEY not configured".to_string()).into();
+        assert_eq!(err.category, ErrorCategory::ProviderAuth);
+        let err: AppError = IDorisError::ModelError("model not found".to_string()).into();
+        assert_eq!(err.category, ErrorCategory::ModelNotReady);
+        assert!(err.message.contains("Settings > Models"));
+        let err: AppError = IDorisError::DatabaseError("locked".to_string()).into();
+        assert_eq!(err.category, ErrorCategory::Storage);
+    }
+
     #[test]
     fn test_from_string() {
         let err: IDorisError = "test error".into();
@@ -0,0 +1,31 @@
+//! Fine-Tuning Dataset Export
+//!
+//! Collects the sessions that pass a [`FineTuneFilter`] and turns them into
+//! a JSONL dataset for chat fine-tuning.
+
+use crate::models::{FineTuneDataset, FineTuneFilter};
+use crate::storage::database;
+
+/// Builds the dataset from the filtered sessions' history
+pub async fn export_dataset(filter: &FineTuneFilter) -> Result<FineTuneDataset, String> {
+    let profile_id = filter.profile_id.clone().unwrap_or_else(super::profile::active_profile_id);
+    let sessions = database::get_profile_sessions(&profile_id)
+        .await
+        .map_err(|e| format!("Error loading sessions: {}", e))?;
+
+    let mut examples = Vec::new();
+    let mut used_sessions = 0;
+    for session in sessions.iter().filter(|s| filter.matches(s)) {
+        let messages = database::get_session_messages(session.id)
+            .await
+            .map_err(|e| format!("Error loading messages: {}", e))?;
+        let session_examples = filter.examples(&messages);
+        if !session_examples.is_empty() {
+            used_sessions += 1;
+            examples.extend(session_examples);
+        }
+    }
+
+    tracing::info!("Exported {} fine-tuning example(s) from {} session(s)", examples.len(), used_sessions);
+    Ok(FineTuneDataset::new(&examples, used_sessions))
+}
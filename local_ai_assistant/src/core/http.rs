@@ -0,0 +1,242 @@
+//! Shared HTTP Client
+//!
+//! Every outbound request iDoris makes goes through [`send`] or [`get`],
+//! which record the domain, feature, time and body sizes to a network audit
+//! log in the data directory. Paths, queries and bodies are never logged.
+//! The log is JSON lines and rolls over to a single older file once it
+//! passes `MAX_AUDIT_BYTES`. Downloads by external tools are recorded with
+//! [`record_external`]; model downloads by the inference libraries don't
+//! go through this client.
+//!
+//! The client connects through the proxy set in Settings → System, which is
+//! persisted to `proxy.json` in the data directory along with the Hugging
+//! Face mirror used for model downloads.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+
+use crate::models::{NetworkFeature, NetworkRequest, ProxyConfig, DEFAULT_HF_ENDPOINT};
+
+/// Size at which the audit log rolls over
+const MAX_AUDIT_BYTES: u64 = 2 * 1024 * 1024;
+
+static PROXY: Lazy<RwLock<ProxyConfig>> = Lazy::new(|| RwLock::new(load_proxy_config()));
+
+/// Rebuilt when the proxy changes
+static CLIENT: Lazy<RwLock<reqwest::Client>> = Lazy::new(|| RwLock::new(build_client(&proxy_config())));
+
+/// Serializes writes to the audit log
+static AUDIT_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+fn audit_path() -> PathBuf {
+    super::paths::data_dir().join("network_audit.jsonl")
+}
+
+fn previous_audit_path() -> PathBuf {
+    super::paths::data_dir().join("network_audit.1.jsonl")
+}
+
+fn proxy_path() -> PathBuf {
+    super::paths::data_dir().join("proxy.json")
+}
+
+fn load_proxy_config() -> ProxyConfig {
+    std::fs::read_to_string(proxy_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn proxy_config() -> ProxyConfig {
+    PROXY.read().map(|c| c.clone()).unwrap_or_default()
+}
+
+/// Saves the proxy and mirror; requests made from now on use them
+pub fn save_proxy_config(config: ProxyConfig) -> Result<(), String> {
+    let config = ProxyConfig {
+        proxy_url: config.proxy_url.trim().to_string(),
+        no_proxy: config.no_proxy.trim().to_string(),
+        hf_endpoint: config.mirror().unwrap_or_default(),
+    };
+    config.validate()?;
+    let client = try_build_client(&config)?;
+
+    let path = proxy_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    // The proxy address may hold credentials
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    }
+
+    *CLIENT.write().map_err(|_| "Failed to lock the HTTP client")? = client;
+    *PROXY.write().map_err(|_| "Failed to lock the proxy settings")? = config;
+    Ok(())
+}
+
+/// The Hugging Face Hub address: the mirror from settings, else
+/// `HF_ENDPOINT`, else the Hub itself
+pub fn hf_endpoint() -> String {
+    proxy_config().mirror()
+        .or_else(|| {
+            std::env::var("HF_ENDPOINT").ok()
+                .map(|endpoint| endpoint.trim().trim_end_matches('/').to_string())
+                .filter(|endpoint| !endpoint.is_empty())
+        })
+        .unwrap_or_else(|| DEFAULT_HF_ENDPOINT.to_string())
+}
+
+/// Passes the proxy and mirror on to the inference libraries and external
+/// tools, which read them from the environment. Must run at startup,
+/// before other threads start; variables already set are kept.
+pub fn apply_proxy_environment() {
+    let config = proxy_config();
+    let mut vars = Vec::new();
+    if let Some(proxy) = config.proxy() {
+        vars.extend(["HTTPS_PROXY", "HTTP_PROXY", "ALL_PROXY"].map(|name| (name, proxy.to_string())));
+        if !config.no_proxy.is_empty() {
+            vars.push(("NO_PROXY", config.no_proxy.clone()));
+        }
+    }
+    if let Some(mirror) = config.mirror() {
+        vars.push(("HF_ENDPOINT", mirror));
+    }
+    for (name, value) in vars {
+        if std::env::var_os(name).is_none() {
+            std::env::set_var(name, value);
+        }
+    }
+}
+
+fn build_client(config: &ProxyConfig) -> reqwest::Client {
+    try_build_client(config).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring the proxy setting: {}", e);
+        reqwest::Client::new()
+    })
+}
+
+fn try_build_client(config: &ProxyConfig) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(url) = config.proxy() {
+        let proxy = reqwest::Proxy::all(url)
+            .map_err(|e| format!("Invalid proxy address: {}", e))?
+            .no_proxy(reqwest::NoProxy::from_string(&config.no_proxy));
+        builder = builder.proxy(proxy);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// The shared client, for building requests to pass to [`send`]
+pub fn client() -> reqwest::Client {
+    CLIENT.read().map(|c| c.clone()).unwrap_or_default()
+}
+
+/// Sends a request and records it in the audit log
+pub async fn send(feature: NetworkFeature, request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+    let (client, request) = request.build_split();
+    let request = request?;
+    let mut entry = NetworkRequest {
+        timestamp: Utc::now(),
+        feature,
+        method: request.method().to_string(),
+        domain: request.url().host_str().unwrap_or_default().to_string(),
+        bytes_sent: request.body().and_then(|b| b.as_bytes()).map(|b| b.len() as u64).unwrap_or(0),
+        bytes_received: None,
+        status: None,
+        error: None,
+    };
+
+    let result = client.execute(request).await;
+    match &result {
+        Ok(response) => {
+            entry.status = Some(response.status().as_u16());
+            entry.bytes_received = response.content_length();
+        }
+        Err(e) => entry.error = Some(e.to_string()),
+    }
+    record(&entry);
+    result
+}
+
+/// Sends a GET request with the shared client
+pub async fn get(feature: NetworkFeature, url: &str) -> reqwest::Result<reqwest::Response> {
+    send(feature, client().get(url)).await
+}
+
+/// Records a download made by an external tool such as `yt-dlp`, which
+/// uses the network on its own
+pub fn record_external(feature: NetworkFeature, tool: &str, url: &str, bytes_received: Option<u64>, error: Option<String>) {
+    record(&NetworkRequest {
+        timestamp: Utc::now(),
+        feature,
+        method: tool.to_string(),
+        domain: reqwest::Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_string)).unwrap_or_default(),
+        bytes_sent: 0,
+        bytes_received,
+        status: None,
+        error,
+    });
+}
+
+fn record(entry: &NetworkRequest) {
+    if let Err(e) = try_record(entry) {
+        tracing::warn!("Could not write the network audit log: {}", e);
+    }
+}
+
+fn try_record(entry: &NetworkRequest) -> Result<(), String> {
+    let _guard = AUDIT_LOCK.lock().map_err(|_| "Failed to lock the audit log")?;
+    std::fs::create_dir_all(super::paths::data_dir()).map_err(|e| e.to_string())?;
+    let path = audit_path();
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() > MAX_AUDIT_BYTES) {
+        std::fs::rename(&path, previous_audit_path()).map_err(|e| e.to_string())?;
+    }
+    let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| e.to_string())?;
+    writeln!(file, "{}", line).map_err(|e| e.to_string())
+}
+
+/// Recorded requests, newest first, optionally of one feature
+pub fn read_audit(feature: Option<NetworkFeature>, limit: usize) -> Result<Vec<NetworkRequest>, String> {
+    let _guard = AUDIT_LOCK.lock().map_err(|_| "Failed to lock the audit log")?;
+    let mut requests = Vec::new();
+    for path in [audit_path(), previous_audit_path()] {
+        let Ok(content) = std::fs::read_to_string(&path) else { continue };
+        requests.extend(
+            content.lines()
+                .rev()
+                .filter_map(|line| serde_json::from_str::<NetworkRequest>(line).ok())
+                .filter(|r| feature.is_none_or(|f| r.feature == f)),
+        );
+        if requests.len() >= limit {
+            break;
+        }
+    }
+    requests.truncate(limit);
+    Ok(requests)
+}
+
+/// Deletes the audit log
+pub fn clear_audit() -> Result<(), String> {
+    let _guard = AUDIT_LOCK.lock().map_err(|_| "Failed to lock the audit log")?;
+    for path in [audit_path(), previous_audit_path()] {
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to delete {}: {}", path.display(), e)),
+        }
+    }
+    Ok(())
+}
@@ -1,15 +1,25 @@
 //! Image Generation Implementation
 //!
 //! This module provides functionality for generating images from text prompts
-//! using MFLUX (MLX-based Flux model) via subprocess.
+//! using MFLUX (MLX-based Flux model) via subprocess. A long-lived Python
+//! worker keeps the model loaded between images, so only the first image
+//! pays the load time; `mflux-generate` is used when the worker cannot run.
+//! A source image, such as a pasted screenshot, turns a generation into
+//! image-to-image.
 //!
 //! Phase 2.2: Image Generation Support (MFLUX backend)
 
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use once_cell::sync::Lazy;
-use std::process::Command;
-use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+
+use crate::models::{CustomImageModel, LoraWeight, DEFAULT_IMAGE_STRENGTH, IMAGE_ESTIMATE_BYTES, active_loras, lora_args};
+use super::model_manager::ModelManager;
 
 /// Flag to indicate if the model is currently generating
 static IS_GENERATING: AtomicBool = AtomicBool::new(false);
@@ -27,14 +37,16 @@ pub enum MfluxModel {
     Schnell,       // Fast, 4 steps
     Dev,           // Higher quality, 20 steps
     ZImageTurbo,   // Very fast, 9 steps
+    Custom(CustomImageModel),  // Saved on disk, loaded with --path
 }
 
 impl MfluxModel {
-    pub fn name(&self) -> &'static str {
+    pub fn name(&self) -> &str {
         match self {
             MfluxModel::Schnell => "schnell",
             MfluxModel::Dev => "dev",
             MfluxModel::ZImageTurbo => "mlx-community/Z-Image-Turbo",
+            MfluxModel::Custom(model) => model.base_model.name(),
         }
     }
 
@@ -44,6 +56,15 @@ impl MfluxModel {
             MfluxModel::Schnell => None,  // Built-in, no base model needed
             MfluxModel::Dev => None,      // Built-in, no base model needed
             MfluxModel::ZImageTurbo => Some("schnell"),  // Based on schnell
+            MfluxModel::Custom(_) => None,  // --model names the base already
+        }
+    }
+
+    /// Folder of a model saved on disk
+    pub fn local_path(&self) -> Option<&str> {
+        match self {
+            MfluxModel::Custom(model) => Some(&model.path),
+            _ => None,
         }
     }
 
@@ -52,18 +73,48 @@ impl MfluxModel {
             MfluxModel::Schnell => 4,
             MfluxModel::Dev => 20,
             MfluxModel::ZImageTurbo => 9,
+            MfluxModel::Custom(model) => model.steps,
         }
     }
 
-    pub fn display_name(&self) -> &'static str {
+    pub fn display_name(&self) -> &str {
         match self {
             MfluxModel::Schnell => "FLUX.1 Schnell (Fast)",
             MfluxModel::Dev => "FLUX.1 Dev (Quality)",
             MfluxModel::ZImageTurbo => "Z-Image Turbo (Very Fast)",
+            MfluxModel::Custom(model) => &model.name,
         }
     }
 }
 
+/// Checks that a custom model's folder exists before it is registered
+pub fn check_custom_model(model: &CustomImageModel) -> Result<(), String> {
+    let path = Path::new(model.path.trim());
+    if !path.is_dir() {
+        return Err(format!("Model folder not found: {}", path.display()));
+    }
+    let has_weights = std::fs::read_dir(path)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .any(|entry| entry.path().is_dir() || entry.path().extension().is_some_and(|ext| ext == "safetensors"));
+    if !has_weights {
+        return Err("The folder has no model weights; save the model with mflux-save first".to_string());
+    }
+    Ok(())
+}
+
+/// Checks that a LoRA weights file exists
+pub fn check_lora(path: &str) -> Result<(), String> {
+    let path = Path::new(path.trim());
+    if path.extension().is_none_or(|ext| ext != "safetensors") {
+        return Err("LoRA weights must be a .safetensors file".to_string());
+    }
+    if !path.is_file() {
+        return Err(format!("LoRA file not found: {}", path.display()));
+    }
+    Ok(())
+}
+
 /// Image generation settings
 #[derive(Clone, Debug)]
 pub struct ImageGenSettings {
@@ -75,6 +126,11 @@ pub struct ImageGenSettings {
     pub model: MfluxModel,
     pub quantize: Option<u8>,  // 4 or 8 bit quantization
     pub seed: Option<u64>,
+    pub loras: Vec<LoraWeight>,
+    /// Starting image for image-to-image
+    pub source_image: Option<PathBuf>,
+    /// How much of the source image is kept, from 0 to 1
+    pub image_strength: f32,
 }
 
 impl Default for ImageGenSettings {
@@ -88,6 +144,9 @@ impl Default for ImageGenSettings {
             model: MfluxModel::Schnell,
             quantize: Some(8),  // 8-bit quantization by default for speed
             seed: None,
+            loras: Vec::new(),
+            source_image: None,
+            image_strength: DEFAULT_IMAGE_STRENGTH,
         }
     }
 }
@@ -130,6 +189,41 @@ impl ImageGenSettings {
         self.seed = Some(seed);
         self
     }
+
+    pub fn with_loras(mut self, loras: Vec<LoraWeight>) -> Self {
+        self.loras = loras;
+        self
+    }
+
+    pub fn with_source_image(mut self, path: PathBuf, strength: f32) -> Self {
+        self.source_image = Some(path);
+        self.image_strength = strength.clamp(0.0, 1.0);
+        self
+    }
+}
+
+fn source_images_dir() -> PathBuf {
+    crate::storage::database::get_data_dir().join("image_sources")
+}
+
+/// Saves an uploaded source image as PNG and returns its ID
+pub fn save_source_image(data: &[u8]) -> Result<String, String> {
+    let image = image::load_from_memory(data).map_err(|_| "The file is not an image".to_string())?;
+    let dir = source_images_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let id = format!("{}.png", uuid::Uuid::new_v4());
+    image.save_with_format(dir.join(&id), image::ImageFormat::Png).map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+/// Path of a source image saved with [`save_source_image`]
+pub fn source_image_path(id: &str) -> Result<PathBuf, String> {
+    let path = source_images_dir().join(id);
+    let valid = Path::new(id).file_name().is_some_and(|name| name == id);
+    if !valid || !path.is_file() {
+        return Err("The source image is no longer available".to_string());
+    }
+    Ok(path)
 }
 
 /// Result of image generation
@@ -139,6 +233,8 @@ pub struct GeneratedImage {
     pub width: u32,
     pub height: u32,
     pub format: String,
+    /// Saved file in the images folder, if the image was written to disk
+    pub path: Option<PathBuf>,
 }
 
 impl GeneratedImage {
@@ -154,7 +250,7 @@ impl GeneratedImage {
 
 /// Check if mflux-generate command is available
 pub fn is_mflux_available() -> bool {
-    Command::new("mflux-generate")
+    super::mock::is_enabled() || Command::new("mflux-generate")
         .arg("--help")
         .output()
         .is_ok()
@@ -162,10 +258,7 @@ pub fn is_mflux_available() -> bool {
 
 /// Get the output directory for generated images
 fn get_output_dir() -> PathBuf {
-    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-    let output_dir = home.join(".local_ai_assistant").join("images");
-    std::fs::create_dir_all(&output_dir).ok();
-    output_dir
+    super::profile::asset_dir("images")
 }
 
 /// Initialize MFLUX (check if available)
@@ -178,7 +271,7 @@ pub async fn init_image_model() -> Result<(), String> {
     }
 
     set_status("Ready (MFLUX)", 0);
-    println!("[ImageGen] MFLUX is available");
+    tracing::info!("[ImageGen] MFLUX is available");
     Ok(())
 }
 
@@ -207,13 +300,231 @@ fn set_status(status: &str, progress: u8) {
         *s = status.to_string();
     }
     GEN_PROGRESS.store(progress, Ordering::SeqCst);
-    println!("[ImageGen] {}: {}%", status, progress);
+    tracing::info!("[ImageGen] {}: {}%", status, progress);
 }
 
-/// Generate an image from a text prompt using MFLUX CLI
-pub async fn generate_image(settings: ImageGenSettings) -> Result<GeneratedImage, String> {
-    use std::time::{SystemTime, UNIX_EPOCH};
+/// Python run by the worker: loads the model once, then answers one JSON
+/// job per stdin line with {"ok": true} or {"error": "..."}
+const WORKER_SCRIPT: &str = r#"
+import json, sys
+
+try:
+    from mflux.config.config import Config
+    from mflux.config.model_config import ModelConfig
+    from mflux.models.flux.variants.txt2img.flux import Flux1
+except ImportError:
+    from mflux import Config, Flux1, ModelConfig
+
+def reply(message):
+    sys.stdout.write(json.dumps(message) + "\n")
+    sys.stdout.flush()
+
+settings = json.loads(sys.argv[1])
+try:
+    if settings["base_model"]:
+        model_config = ModelConfig.from_name(model_name=settings["model"], base_model=settings["base_model"])
+    else:
+        model_config = ModelConfig.from_name(model_name=settings["model"])
+    flux = Flux1(
+        model_config=model_config,
+        quantize=settings["quantize"],
+        local_path=settings["path"],
+        lora_paths=settings["lora_paths"] or None,
+        lora_scales=settings["lora_scales"] or None,
+    )
+except Exception as e:
+    reply({"error": f"Failed to load model: {e}"})
+    sys.exit(1)
+reply({"ready": True})
+
+for line in sys.stdin:
+    if not line.strip():
+        continue
+    try:
+        job = json.loads(line)
+        # Image-to-image when a source image is given
+        source = {"image_path": job["image_path"], "image_strength": job["image_strength"]} if job.get("image_path") else {}
+        config = Config(num_inference_steps=job["steps"], width=job["width"], height=job["height"], **source)
+        image = flux.generate_image(seed=job["seed"], prompt=job["prompt"], config=config)
+        image.save(path=job["output"])
+        reply({"ok": True})
+    except Exception as e:
+        reply({"error": str(e)})
+"#;
+
+/// Set to "false" to run `mflux-generate` for every image instead of
+/// keeping the model loaded
+const WORKER_ENV: &str = "IDORIS_MFLUX_WORKER";
+
+/// Python with MFLUX installed; found from the `mflux-generate` script when
+/// not set
+const PYTHON_ENV: &str = "IDORIS_MFLUX_PYTHON";
+
+/// How long an unused worker keeps its model in memory
+const WORKER_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// A Python process with one model loaded, reused while the model, its
+/// quantization and LoRAs stay the same
+struct MfluxWorker {
+    /// Load settings as JSON, compared to decide whether to restart
+    key: String,
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    last_used: Instant,
+}
+
+#[derive(serde::Deserialize)]
+struct WorkerReply {
+    #[serde(default)]
+    ready: bool,
+    #[serde(default)]
+    ok: bool,
+    error: Option<String>,
+}
 
+static WORKER: Lazy<tokio::sync::Mutex<Option<MfluxWorker>>> = Lazy::new(|| tokio::sync::Mutex::new(None));
+
+impl MfluxWorker {
+    async fn start(key: String) -> Result<Self, String> {
+        let python = mflux_python().ok_or("no Python with MFLUX found")?;
+        let mut child = tokio::process::Command::new(&python)
+            .arg("-c").arg(WORKER_SCRIPT)
+            .arg(&key)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("failed to start {}: {}", python.display(), e))?;
+        let stdin = child.stdin.take().ok_or("worker has no stdin")?;
+        let stdout = BufReader::new(child.stdout.take().ok_or("worker has no stdout")?).lines();
+        let mut worker = Self { key, _child: child, stdin, stdout, last_used: Instant::now() };
+        worker.read_reply().await?;
+        Ok(worker)
+    }
+
+    /// Next reply from the worker, skipping anything else MFLUX prints
+    async fn read_reply(&mut self) -> Result<(), String> {
+        while let Some(line) = self.stdout.next_line().await.map_err(|e| e.to_string())? {
+            let Ok(reply) = serde_json::from_str::<WorkerReply>(&line) else {
+                continue;
+            };
+            if let Some(error) = reply.error {
+                return Err(error);
+            }
+            if reply.ready || reply.ok {
+                return Ok(());
+            }
+        }
+        Err("MFLUX worker exited".to_string())
+    }
+
+    async fn generate(&mut self, job: serde_json::Value) -> Result<(), String> {
+        self.stdin.write_all(format!("{}\n", job).as_bytes()).await.map_err(|e| e.to_string())?;
+        self.stdin.flush().await.map_err(|e| e.to_string())?;
+        let result = self.read_reply().await;
+        self.last_used = Instant::now();
+        result
+    }
+}
+
+fn worker_enabled() -> bool {
+    !matches!(std::env::var(WORKER_ENV).as_deref(), Ok("false") | Ok("0"))
+}
+
+/// uv and pipx install `mflux-generate` as a script whose first line names
+/// the Python of its environment
+fn mflux_python() -> Option<PathBuf> {
+    if let Ok(python) = std::env::var(PYTHON_ENV) {
+        return Some(PathBuf::from(python));
+    }
+    let script = std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join("mflux-generate"))
+        .find(|path| path.is_file())?;
+    let first_line = std::fs::read_to_string(script).ok()?.lines().next()?.to_string();
+    let python = PathBuf::from(first_line.strip_prefix("#!")?.trim());
+    let is_python = python.file_name().is_some_and(|name| name.to_string_lossy().starts_with("python"));
+    (is_python && python.is_file()).then_some(python)
+}
+
+/// Generates one image in the warm worker, starting or restarting it when
+/// the model settings changed. Returns false when no worker can be started,
+/// so the caller falls back to the CLI.
+async fn generate_with_worker(settings: &ImageGenSettings, output_file: &Path) -> Result<bool, String> {
+    let loras = active_loras(&settings.loras);
+    let key = serde_json::json!({
+        "model": settings.model.name(),
+        "base_model": settings.model.base_model(),
+        "path": settings.model.local_path(),
+        "quantize": settings.quantize,
+        "lora_paths": loras.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+        "lora_scales": loras.iter().map(|(_, scale)| scale).collect::<Vec<_>>(),
+    }).to_string();
+
+    let mut worker = WORKER.lock().await;
+    if worker.as_ref().is_some_and(|w| w.key != key) {
+        tracing::info!("[ImageGen] Model settings changed, restarting worker");
+        *worker = None;
+    }
+    if worker.is_none() {
+        set_status(&format!("Loading {}...", settings.model.display_name()), 10);
+        match MfluxWorker::start(key).await {
+            Ok(started) => *worker = Some(started),
+            Err(e) => {
+                tracing::warn!("[ImageGen] Worker unavailable, using mflux-generate: {}", e);
+                return Ok(false);
+            }
+        }
+    }
+
+    let steps = settings.num_steps.unwrap_or(settings.model.default_steps());
+    let seed = settings.seed.unwrap_or_else(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos() as u64
+    });
+    set_status(&format!("Generating with {}...", settings.model.display_name()), 30);
+    tracing::info!("[ImageGen] Worker job: \"{}\" {}x{}, {} steps, seed {}", settings.prompt, settings.width, settings.height, steps, seed);
+
+    let job = serde_json::json!({
+        "prompt": settings.prompt,
+        "width": settings.width,
+        "height": settings.height,
+        "steps": steps,
+        "seed": seed,
+        "output": output_file,
+        "image_path": settings.source_image,
+        "image_strength": settings.image_strength,
+    });
+    let Some(running) = worker.as_mut() else {
+        return Ok(false);
+    };
+    if let Err(e) = running.generate(job).await {
+        // The process may be in a bad state; the next image starts afresh
+        *worker = None;
+        set_status("Generation failed", 0);
+        return Err(format!("MFLUX generation failed: {}", e));
+    }
+    drop(worker);
+    unload_when_idle();
+    Ok(true)
+}
+
+/// Stops the worker if no image is generated for `WORKER_IDLE_TIMEOUT`,
+/// freeing the model's memory
+fn unload_when_idle() {
+    tokio::spawn(async {
+        tokio::time::sleep(WORKER_IDLE_TIMEOUT).await;
+        let mut worker = WORKER.lock().await;
+        if worker.as_ref().is_some_and(|w| w.last_used.elapsed() >= WORKER_IDLE_TIMEOUT) {
+            tracing::info!("[ImageGen] Unloading idle model");
+            *worker = None;
+        }
+    });
+}
+
+/// Generate an image from a text prompt using MFLUX, in a warm worker when
+/// possible
+pub async fn generate_image(settings: ImageGenSettings) -> Result<GeneratedImage, String> {
     // Check if already generating
     if IS_GENERATING.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
         return Err("Image generation is already in progress".to_string());
@@ -225,9 +536,13 @@ pub async fn generate_image(settings: ImageGenSettings) -> Result<GeneratedImage
         set_status("Ready", 0);
     });
 
+    if super::mock::is_enabled() {
+        return super::mock::image(&settings);
+    }
+
     set_status("Starting generation...", 5);
-    println!("[ImageGen] Prompt: {}", settings.prompt);
-    println!("[ImageGen] Model: {}", settings.model.display_name());
+    tracing::info!("[ImageGen] Prompt: {}", settings.prompt);
+    tracing::info!("[ImageGen] Model: {}", settings.model.display_name());
 
     // Check if MFLUX is available
     if !is_mflux_available() {
@@ -235,6 +550,21 @@ pub async fn generate_image(settings: ImageGenSettings) -> Result<GeneratedImage
         return Err("MFLUX not installed. Install with: uv tool install mflux".to_string());
     }
 
+    // Files may have moved since they were added
+    if let MfluxModel::Custom(model) = &settings.model {
+        check_custom_model(model)?;
+    }
+    for lora in settings.loras.iter().filter(|l| l.enabled) {
+        check_lora(&lora.path)?;
+        tracing::info!("[ImageGen] LoRA: {} at {:.2}", lora.path, lora.scale);
+    }
+    if let Some(source) = &settings.source_image {
+        tracing::info!("[ImageGen] Source image: {} at strength {:.2}", source.display(), settings.image_strength);
+    }
+
+    let output_dir = get_output_dir();
+    ModelManager::check_disk_space(&output_dir, IMAGE_ESTIMATE_BYTES, "generating an image").await.map_err(|e| e.to_string())?;
+
     set_status("Preparing MFLUX...", 10);
 
     // Generate unique output filename
@@ -242,9 +572,42 @@ pub async fn generate_image(settings: ImageGenSettings) -> Result<GeneratedImage
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis();
-    let output_dir = get_output_dir();
     let output_file = output_dir.join(format!("image_{}.png", timestamp));
 
+    // A warm worker skips loading the model; the CLI is the fallback
+    let warm = worker_enabled() && generate_with_worker(&settings, &output_file).await?;
+    if !warm {
+        run_cli(&settings, &output_file)?;
+    }
+
+    set_status("Reading generated image...", 90);
+
+    // Read the generated image
+    let png_bytes = std::fs::read(&output_file).map_err(|e| {
+        set_status(&format!("Failed: {}", e), 0);
+        format!("Failed to read generated image: {}", e)
+    })?;
+
+    // Get image dimensions using image crate
+    let img = image::load_from_memory(&png_bytes).map_err(|e| {
+        set_status(&format!("Failed: {}", e), 0);
+        format!("Failed to parse image: {}", e)
+    })?;
+
+    set_status("Complete!", 100);
+    tracing::info!("[ImageGen] Image generated successfully! Size: {} bytes", png_bytes.len());
+
+    Ok(GeneratedImage {
+        data: png_bytes,
+        width: img.width(),
+        height: img.height(),
+        format: "png".to_string(),
+        path: Some(output_file),
+    })
+}
+
+/// Runs `mflux-generate` for one image, loading the model from scratch
+fn run_cli(settings: &ImageGenSettings, output_file: &Path) -> Result<(), String> {
     // Build mflux-generate command
     let mut cmd = Command::new("mflux-generate");
 
@@ -256,11 +619,19 @@ pub async fn generate_image(settings: ImageGenSettings) -> Result<GeneratedImage
         cmd.arg("--base-model").arg(base);
     }
 
+    // Local model folder for registered custom models
+    if let Some(path) = settings.model.local_path() {
+        cmd.arg("--path").arg(path);
+    }
+
+    // LoRA weights, stacked in order
+    cmd.args(lora_args(&settings.loras));
+
     // Prompt
     cmd.arg("--prompt").arg(&settings.prompt);
 
     // Output path
-    cmd.arg("--output").arg(&output_file);
+    cmd.arg("--output").arg(output_file);
 
     // Image dimensions
     cmd.arg("--width").arg(settings.width.to_string());
@@ -280,8 +651,14 @@ pub async fn generate_image(settings: ImageGenSettings) -> Result<GeneratedImage
         cmd.arg("--seed").arg(seed.to_string());
     }
 
+    // Source image for image-to-image
+    if let Some(source) = &settings.source_image {
+        cmd.arg("--image-path").arg(source);
+        cmd.arg("--image-strength").arg(settings.image_strength.to_string());
+    }
+
     set_status(&format!("Generating with {}...", settings.model.display_name()), 20);
-    println!("[ImageGen] Running: mflux-generate --model {} --prompt \"{}\" --width {} --height {} --steps {}",
+    tracing::info!("[ImageGen] Running: mflux-generate --model {} --prompt \"{}\" --width {} --height {} --steps {}",
         settings.model.name(),
         settings.prompt,
         settings.width,
@@ -298,38 +675,8 @@ pub async fn generate_image(settings: ImageGenSettings) -> Result<GeneratedImage
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         set_status("Generation failed", 0);
-        eprintln!("[ImageGen] MFLUX stderr: {}", stderr);
+        tracing::warn!("[ImageGen] MFLUX stderr: {}", stderr);
         return Err(format!("MFLUX generation failed: {}", stderr));
     }
-
-    set_status("Reading generated image...", 90);
-
-    // Read the generated image
-    let png_bytes = std::fs::read(&output_file).map_err(|e| {
-        set_status(&format!("Failed: {}", e), 0);
-        format!("Failed to read generated image: {}", e)
-    })?;
-
-    // Get image dimensions using image crate
-    let img = image::load_from_memory(&png_bytes).map_err(|e| {
-        set_status(&format!("Failed: {}", e), 0);
-        format!("Failed to parse image: {}", e)
-    })?;
-
-    set_status("Complete!", 100);
-    println!("[ImageGen] Image generated successfully! Size: {} bytes", png_bytes.len());
-
-    Ok(GeneratedImage {
-        data: png_bytes,
-        width: img.width(),
-        height: img.height(),
-        format: "png".to_string(),
-    })
-}
-
-/// Generate an image and return as base64 encoded string
-pub async fn generate_image_base64(prompt: &str) -> Result<String, String> {
-    let settings = ImageGenSettings::new(prompt);
-    let image = generate_image(settings).await?;
-    Ok(image.to_data_url())
+    Ok(())
 }
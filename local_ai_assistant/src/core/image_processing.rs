@@ -0,0 +1,92 @@
+//! Image Processing
+//!
+//! Crops, resizes and re-encodes generated images into web-ready copies for
+//! export. JPEG output is compressed in steps until it fits the size target
+//! or reaches the lowest acceptable quality.
+
+use std::path::Path;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType as PngFilter, PngEncoder};
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::DynamicImage;
+
+use crate::models::{CropRect, ImageEdit, ImageOutputFormat, ProcessedImage};
+
+/// JPEG quality is never lowered below this to meet a size target
+const MIN_JPEG_QUALITY: u8 = 40;
+const JPEG_QUALITY_STEP: u8 = 10;
+
+/// Applies the edit to the source image and writes the result to `output`
+pub fn process_image(source: &Path, output: &Path, edit: &ImageEdit) -> Result<ProcessedImage, String> {
+    if edit.width == 0 || edit.height == 0 {
+        return Err("Output width and height must be greater than zero".to_string());
+    }
+
+    let original = image::open(source).map_err(|e| format!("Failed to open image: {}", e))?;
+    let resized = crop(&original, &edit.crop)?.resize_to_fill(edit.width, edit.height, FilterType::Lanczos3);
+
+    let target = edit.max_kb.map(|kb| kb as usize * 1024);
+    let (data, quality) = match edit.format {
+        ImageOutputFormat::Jpeg => {
+            let mut quality = edit.quality.clamp(1, 100);
+            let mut data = encode_jpeg(&resized, quality)?;
+            while target.is_some_and(|t| data.len() > t) && quality > MIN_JPEG_QUALITY {
+                quality = quality.saturating_sub(JPEG_QUALITY_STEP).max(MIN_JPEG_QUALITY);
+                data = encode_jpeg(&resized, quality)?;
+            }
+            (data, Some(quality))
+        }
+        ImageOutputFormat::WebP => {
+            let mut data = Vec::new();
+            resized.to_rgba8()
+                .write_with_encoder(WebPEncoder::new_lossless(&mut data))
+                .map_err(|e| format!("Failed to encode WebP: {}", e))?;
+            (data, None)
+        }
+        ImageOutputFormat::Png => {
+            let mut data = Vec::new();
+            resized.write_with_encoder(PngEncoder::new_with_quality(&mut data, CompressionType::Best, PngFilter::Adaptive))
+                .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+            (data, None)
+        }
+    };
+
+    if let Some(dir) = output.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(output, &data).map_err(|e| format!("Failed to write image: {}", e))?;
+
+    Ok(ProcessedImage {
+        path: output.display().to_string(),
+        edit: edit.clone(),
+        width: resized.width(),
+        height: resized.height(),
+        size_bytes: data.len() as u64,
+        quality,
+    })
+}
+
+/// Cuts out the crop region, given as fractions of the image size
+fn crop(image: &DynamicImage, rect: &CropRect) -> Result<DynamicImage, String> {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let x = (rect.x.clamp(0.0, 1.0) * width) as u32;
+    let y = (rect.y.clamp(0.0, 1.0) * height) as u32;
+    let crop_width = ((rect.width.clamp(0.0, 1.0) * width) as u32).min(image.width() - x);
+    let crop_height = ((rect.height.clamp(0.0, 1.0) * height) as u32).min(image.height() - y);
+
+    if crop_width == 0 || crop_height == 0 {
+        return Err("Crop region is empty".to_string());
+    }
+    Ok(image.crop_imm(x, y, crop_width, crop_height))
+}
+
+fn encode_jpeg(image: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    // JPEG has no alpha channel
+    image.to_rgb8()
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut data, quality))
+        .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+    Ok(data)
+}
@@ -0,0 +1,125 @@
+//! Background Indexing
+//!
+//! Ingests the context folder into the vector store as a background job so
+//! large folders no longer block the request that started them. Jobs queue
+//! behind each other and report per-document progress, which the Context
+//! settings tab polls. Each run is also listed in the job registry, where it
+//! can be cancelled or retried.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use uuid::Uuid;
+
+use crate::models::{IndexStage, IndexingJob, JobKind};
+use super::jobs::{JobHandle, JobSpec};
+use super::{notifications, vector_store};
+
+/// Finished jobs kept for display
+const MAX_JOBS: usize = 10;
+
+/// Recent jobs, oldest first
+static JOBS: Lazy<Mutex<Vec<IndexingJob>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Held by the running job, so queued jobs run one at a time in order
+static WORKER: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+/// Queues every document in the context folder for indexing
+pub fn start_reload() -> Result<IndexingJob, String> {
+    if !vector_store::is_initialized() {
+        return Err("Vector store not initialized. Please restart the application.".to_string());
+    }
+
+    let folder = vector_store::get_context_folder();
+    let paths = vector_store::context_document_paths()?;
+    let job = IndexingJob::new(paths.iter().map(|p| display_name(&folder, p)).collect());
+
+    let mut jobs = JOBS.lock().map_err(|_| "Failed to lock indexing jobs")?;
+    jobs.push(job.clone());
+    while jobs.len() > MAX_JOBS {
+        match jobs.iter().position(|j| !j.is_running()) {
+            Some(index) => { jobs.remove(index); }
+            None => break,
+        }
+    }
+    drop(jobs);
+
+    tracing::info!(job = %job.id, documents = paths.len(), "Indexing job queued");
+    let job_id = job.id;
+    JobSpec::new(JobKind::Indexing, format!("Index {} documents", paths.len()))
+        .with_retry(|| start_reload().map(|_| ()))
+        .with_on_cancel(move || {
+            update(job_id, |job| job.finished_at = Some(chrono::Utc::now()));
+            Ok(())
+        })
+        .spawn(move |handle| run(job_id, paths, handle));
+    Ok(job)
+}
+
+/// Recent jobs, newest first
+pub fn list_jobs() -> Vec<IndexingJob> {
+    let mut jobs = JOBS.lock().map(|j| j.clone()).unwrap_or_default();
+    jobs.reverse();
+    jobs
+}
+
+/// Indexes the documents; fails when any document failed
+async fn run(job_id: Uuid, paths: Vec<PathBuf>, handle: JobHandle) -> Result<(), String> {
+    let _worker = WORKER.lock().await;
+    let total = paths.len();
+    let folder = vector_store::get_context_folder();
+
+    for (index, path) in paths.into_iter().enumerate() {
+        let name = display_name(&folder, &path);
+        handle.set_progress(index as f32 / total as f32);
+        let document = match vector_store::parse_document_file(path.clone()).await {
+            Ok(document) => document,
+            Err(e) => {
+                tracing::warn!("Failed to parse {:?}: {}", path, e);
+                handle.log(format!("{}: {}", name, e));
+                update(job_id, |job| job.fail(index, e));
+                continue;
+            }
+        };
+        update(job_id, |job| job.set_stage(index, IndexStage::Parsed));
+
+        update(job_id, |job| job.set_stage(index, IndexStage::Embedding));
+        match vector_store::store_document(document).await {
+            Ok(()) => {
+                handle.log(format!("{}: stored", name));
+                update(job_id, |job| job.set_stage(index, IndexStage::Stored));
+            }
+            Err(e) => {
+                tracing::warn!("Failed to index {:?}: {}", path, e);
+                handle.log(format!("{}: {}", name, e));
+                update(job_id, |job| job.fail(index, e));
+            }
+        }
+    }
+
+    let mut result = Ok(());
+    update(job_id, |job| {
+        job.finished_at = Some(chrono::Utc::now());
+        tracing::info!(job = %job.id, "Indexing job finished: {}", job.summary());
+        if job.count(IndexStage::Failed) > 0 {
+            notifications::error("Indexing finished with errors", job.summary());
+            result = Err(job.summary());
+        } else {
+            notifications::success("Indexing finished", job.summary());
+        }
+    });
+    result
+}
+
+fn update(job_id: Uuid, f: impl FnOnce(&mut IndexingJob)) {
+    if let Ok(mut jobs) = JOBS.lock() {
+        if let Some(job) = jobs.iter_mut().find(|j| j.id == job_id) {
+            f(job);
+        }
+    }
+}
+
+/// Path relative to the context folder, for display
+fn display_name(folder: &Path, path: &Path) -> String {
+    path.strip_prefix(folder).unwrap_or(path).to_string_lossy().to_string()
+}
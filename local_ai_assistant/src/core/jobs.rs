@@ -0,0 +1,248 @@
+//! Job Registry
+//!
+//! Shared registry of background work: model downloads, indexing, video
+//! generation and content pipelines. Each task runs as a tokio task the
+//! registry can abort, reports progress and log lines through a
+//! [`JobHandle`], and may come with a retry action. The Jobs panel lists,
+//! cancels and retries them.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use tokio::task::{AbortHandle, JoinHandle};
+use uuid::Uuid;
+
+use crate::models::{BackgroundJob, JobKind, JobStatus};
+
+/// Finished jobs kept for display
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// Log lines kept per job
+const MAX_LOG_LINES: usize = 200;
+
+/// Retry or cancel hook supplied by the code that started a job
+pub type JobAction = Arc<dyn Fn() -> Result<(), String> + Send + Sync>;
+
+struct Entry {
+    job: BackgroundJob,
+    abort: Option<AbortHandle>,
+    retry: Option<JobAction>,
+    on_cancel: Option<JobAction>,
+}
+
+/// Jobs, oldest first
+static JOBS: Lazy<Mutex<Vec<Entry>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Describes a job before it starts
+pub struct JobSpec {
+    kind: JobKind,
+    title: String,
+    retry: Option<JobAction>,
+    on_cancel: Option<JobAction>,
+}
+
+impl JobSpec {
+    pub fn new(kind: JobKind, title: impl Into<String>) -> Self {
+        Self { kind, title: title.into(), retry: None, on_cancel: None }
+    }
+
+    /// How to start the work again after it failed or was cancelled
+    pub fn with_retry(mut self, retry: impl Fn() -> Result<(), String> + Send + Sync + 'static) -> Self {
+        self.retry = Some(Arc::new(retry));
+        self
+    }
+
+    /// Cleanup to run when the user cancels, after the task is aborted
+    pub fn with_on_cancel(mut self, on_cancel: impl Fn() -> Result<(), String> + Send + Sync + 'static) -> Self {
+        self.on_cancel = Some(Arc::new(on_cancel));
+        self
+    }
+
+    /// Registers the job and runs `task` in the background
+    pub fn spawn<T, F, Fut>(self, task: F) -> JoinHandle<Result<T, String>>
+    where
+        T: Send + 'static,
+        F: FnOnce(JobHandle) -> Fut,
+        Fut: Future<Output = Result<T, String>> + Send + 'static,
+    {
+        let job = BackgroundJob::new(self.kind, self.title, self.retry.is_some());
+        let handle = JobHandle { id: job.id };
+        tracing::info!(job = %job.id, "{} started: {}", job.kind.display_name(), job.title);
+
+        if let Ok(mut jobs) = JOBS.lock() {
+            jobs.push(Entry { job, abort: None, retry: self.retry, on_cancel: self.on_cancel });
+            prune(&mut jobs);
+        }
+
+        let future = task(handle);
+        let join = tokio::spawn(async move {
+            let result = future.await;
+            handle.finish(match &result {
+                Ok(_) => JobStatus::Completed,
+                Err(e) => JobStatus::Failed(e.clone()),
+            });
+            result
+        });
+        update(handle.id, |entry| entry.abort = Some(join.abort_handle()));
+        join
+    }
+
+    /// Runs the job in the background and waits for its result
+    pub async fn run<T, F, Fut>(self, task: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(JobHandle) -> Fut,
+        Fut: Future<Output = Result<T, String>> + Send + 'static,
+    {
+        match self.spawn(task).await {
+            Ok(result) => result,
+            Err(e) if e.is_cancelled() => Err("Cancelled".to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// Lets a running task report on itself
+#[derive(Clone, Copy)]
+pub struct JobHandle {
+    id: Uuid,
+}
+
+impl JobHandle {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Fraction done, from 0.0 to 1.0
+    pub fn set_progress(&self, fraction: f32) {
+        update(self.id, |entry| entry.job.progress = Some(fraction.clamp(0.0, 1.0)));
+    }
+
+    pub fn log(&self, line: impl Into<String>) {
+        let line = line.into();
+        update(self.id, |entry| {
+            let logs = &mut entry.job.logs;
+            logs.push(format!("{} {}", chrono::Local::now().format("%H:%M:%S"), line));
+            if logs.len() > MAX_LOG_LINES {
+                logs.remove(0);
+            }
+        });
+    }
+
+    fn finish(&self, status: JobStatus) {
+        update(self.id, |entry| {
+            entry.job.finish(status);
+            entry.abort = None;
+            tracing::info!(job = %entry.job.id, "{} {}: {}", entry.job.kind.display_name(), entry.job.status.label().to_lowercase(), entry.job.title);
+        });
+    }
+}
+
+/// All jobs, newest first
+pub fn list() -> Vec<BackgroundJob> {
+    JOBS.lock().map(|jobs| jobs.iter().rev().map(|e| e.job.clone()).collect()).unwrap_or_default()
+}
+
+/// Stops a running job
+pub fn cancel(job_id: Uuid) -> Result<(), String> {
+    let on_cancel = {
+        let mut jobs = JOBS.lock().map_err(|_| "Failed to lock jobs")?;
+        let entry = jobs.iter_mut().find(|e| e.job.id == job_id).ok_or("Job not found")?;
+        if entry.job.status.is_finished() {
+            return Err("Job has already finished".to_string());
+        }
+        if let Some(abort) = entry.abort.take() {
+            abort.abort();
+        }
+        entry.job.finish(JobStatus::Cancelled);
+        tracing::info!(job = %job_id, "{} cancelled: {}", entry.job.kind.display_name(), entry.job.title);
+        entry.on_cancel.clone()
+    };
+    match on_cancel {
+        Some(on_cancel) => on_cancel(),
+        None => Ok(()),
+    }
+}
+
+/// Starts a failed or cancelled job again. The new run shows up as a new
+/// job; the old one is removed from the list.
+pub fn retry(job_id: Uuid) -> Result<(), String> {
+    let retry = {
+        let jobs = JOBS.lock().map_err(|_| "Failed to lock jobs")?;
+        let entry = jobs.iter().find(|e| e.job.id == job_id).ok_or("Job not found")?;
+        if !entry.job.can_retry() {
+            return Err("This job can't be retried".to_string());
+        }
+        entry.retry.clone().ok_or("This job can't be retried")?
+    };
+    retry()?;
+    if let Ok(mut jobs) = JOBS.lock() {
+        jobs.retain(|e| e.job.id != job_id);
+    }
+    Ok(())
+}
+
+/// Removes finished jobs from the list
+pub fn clear_finished() {
+    if let Ok(mut jobs) = JOBS.lock() {
+        jobs.retain(|e| !e.job.status.is_finished());
+    }
+}
+
+fn update(job_id: Uuid, f: impl FnOnce(&mut Entry)) {
+    if let Ok(mut jobs) = JOBS.lock() {
+        if let Some(entry) = jobs.iter_mut().find(|e| e.job.id == job_id) {
+            f(entry);
+        }
+    }
+}
+
+/// Drops the oldest finished jobs beyond the limit
+fn prune(jobs: &mut Vec<Entry>) {
+    let mut excess = jobs.iter().filter(|e| e.job.status.is_finished()).count().saturating_sub(MAX_FINISHED_JOBS);
+    jobs.retain(|e| {
+        if excess > 0 && e.job.status.is_finished() {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_job_reports_and_finishes() {
+        let result = JobSpec::new(JobKind::Indexing, "test job")
+            .run(|job| async move {
+                job.log("working");
+                job.set_progress(0.5);
+                Ok::<_, String>(42)
+            })
+            .await;
+        assert_eq!(result, Ok(42));
+
+        let job = list().into_iter().find(|j| j.title == "test job").unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+        assert_eq!(job.progress, Some(1.0));
+        assert!(job.logs[0].ends_with("working"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_aborts_task() {
+        let join = JobSpec::new(JobKind::Video, "slow job")
+            .spawn(|_| async {
+                tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+                Ok::<_, String>(())
+            });
+        let id = list().into_iter().find(|j| j.title == "slow job").unwrap().id;
+
+        cancel(id).unwrap();
+        assert!(join.await.unwrap_err().is_cancelled());
+        let job = list().into_iter().find(|j| j.id == id).unwrap();
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert!(!job.can_retry());
+    }
+}
@@ -0,0 +1,274 @@
+//! Local Network Access
+//!
+//! Lets other devices on the same network, such as a phone, use the
+//! assistant. When enabled, the server listens on all interfaces (from the
+//! next start) and every request outside the `/v1` API, which has its own
+//! bearer token, needs a session cookie obtained by entering the access PIN
+//! at `/login`. Repeated wrong PINs lock the login for a minute.
+//!
+//! The configuration is persisted to `lan_access.json` in the data
+//! directory.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use axum::extract::{ConnectInfo, Form, FromRequest, Request};
+use axum::http::{header, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::models::{LanAccessConfig, LanAccessInfo};
+
+const SESSION_COOKIE: &str = "idoris_session";
+/// How long a signed-in device stays signed in
+const SESSION_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// Wrong PINs allowed before the login locks
+const MAX_FAILED_LOGINS: u32 = 5;
+const LOGIN_LOCKOUT: Duration = Duration::from_secs(60);
+const LOGIN_PATH: &str = "/login";
+/// Port the server listens on unless `PORT` is set
+const DEFAULT_PORT: u16 = 8080;
+
+static CONFIG: Lazy<RwLock<LanAccessConfig>> = Lazy::new(|| RwLock::new(load_config()));
+
+/// Whether the server was started listening on all interfaces
+static LISTENING: AtomicBool = AtomicBool::new(false);
+
+/// Session token → expiry
+static SESSIONS: Lazy<Mutex<HashMap<String, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Failed logins since the last success, and when the lockout ends
+static FAILED_LOGINS: Mutex<(u32, Option<Instant>)> = Mutex::new((0, None));
+
+fn config_path() -> PathBuf {
+    crate::storage::database::get_data_dir().join("lan_access.json")
+}
+
+fn load_config() -> LanAccessConfig {
+    std::fs::read_to_string(config_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn config() -> LanAccessConfig {
+    CONFIG.read().map(|c| c.clone()).unwrap_or_default()
+}
+
+/// Saves the settings. Changing the PIN signs every device out.
+pub fn save_config(config: LanAccessConfig) -> Result<(), String> {
+    let config = LanAccessConfig { pin: config.pin.trim().to_string(), ..config };
+    config.validate()?;
+
+    let path = config_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    }
+
+    let mut current = CONFIG.write().map_err(|_| "Failed to lock network access settings")?;
+    if current.pin != config.pin {
+        if let Ok(mut sessions) = SESSIONS.lock() {
+            sessions.clear();
+        }
+    }
+    *current = config;
+    Ok(())
+}
+
+/// Makes the server listen on all interfaces when network access is
+/// enabled. Must run before the server starts; an address already set
+/// through `IP` (e.g. `dx serve --addr`) is kept.
+pub fn apply_bind_address() {
+    if !config().enabled {
+        return;
+    }
+    if std::env::var("IP").is_err() {
+        std::env::set_var("IP", "0.0.0.0");
+    }
+    let listening = std::env::var("IP").is_ok_and(|ip| ip.parse::<IpAddr>().is_ok_and(|ip| !ip.is_loopback()));
+    LISTENING.store(listening, Ordering::SeqCst);
+    if listening {
+        tracing::info!("Network access enabled: other devices sign in with the access PIN");
+    }
+}
+
+/// Current state with the address and QR code to open from another device
+pub fn info() -> LanAccessInfo {
+    let config = config();
+    let url = config.enabled.then(lan_url).flatten();
+    let qr_svg = url.as_deref().and_then(qr_svg);
+    LanAccessInfo { config, listening: LISTENING.load(Ordering::SeqCst), url, qr_svg }
+}
+
+/// This computer's address on the local network
+fn lan_url() -> Option<String> {
+    // Connecting a UDP socket sends nothing but picks the outgoing interface
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.168.0.1:80").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    let port = std::env::var("PORT").ok().and_then(|p| p.parse().ok()).unwrap_or(DEFAULT_PORT);
+    Some(format!("http://{}", SocketAddr::new(ip, port)))
+}
+
+fn qr_svg(url: &str) -> Option<String> {
+    let code = qrcode::QrCode::new(url.as_bytes()).ok()?;
+    Some(code.render::<qrcode::render::svg::Color>().min_dimensions(200, 200).build())
+}
+
+/// Middleware requiring a signed-in session for requests from other
+/// devices. When the peer address isn't known every request must sign in.
+pub async fn require_session(req: Request, next: Next) -> Response {
+    if !LISTENING.load(Ordering::SeqCst) && !config().enabled {
+        return next.run(req).await;
+    }
+
+    let from_this_computer = req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .is_some_and(|ConnectInfo(addr)| addr.ip().is_loopback());
+    let path = req.uri().path();
+    if from_this_computer || path.starts_with("/v1/") {
+        return next.run(req).await;
+    }
+
+    if path == LOGIN_PATH {
+        return match *req.method() {
+            Method::POST => login(req).await,
+            _ => login_page(None).into_response(),
+        };
+    }
+
+    if session_cookie(&req).is_some_and(|token| is_valid_session(&token)) {
+        return next.run(req).await;
+    }
+
+    let wants_page = req.method() == Method::GET && req.headers()
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"));
+    if wants_page {
+        Redirect::to(LOGIN_PATH).into_response()
+    } else {
+        (StatusCode::UNAUTHORIZED, "Sign in with the access PIN").into_response()
+    }
+}
+
+#[derive(Deserialize)]
+struct LoginForm {
+    pin: String,
+}
+
+async fn login(req: Request) -> Response {
+    if let Some(wait) = lockout_remaining() {
+        return login_page(Some(&format!("Too many attempts. Try again in {} seconds.", wait.as_secs() + 1)))
+            .into_response();
+    }
+
+    let Ok(Form(form)) = Form::<LoginForm>::from_request(req, &()).await else {
+        return login_page(Some("Enter the access PIN")).into_response();
+    };
+
+    let expected = config().pin;
+    if expected.is_empty() || !super::api_server::tokens_match(form.pin.trim(), &expected) {
+        record_failed_login();
+        return login_page(Some("Wrong PIN")).into_response();
+    }
+
+    if let Ok(mut failed) = FAILED_LOGINS.lock() {
+        *failed = (0, None);
+    }
+    let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    if let Ok(mut sessions) = SESSIONS.lock() {
+        let now = Instant::now();
+        sessions.retain(|_, expires| *expires > now);
+        sessions.insert(token.clone(), now + SESSION_TTL);
+    }
+    tracing::info!("A device signed in over the network");
+
+    let cookie = format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}",
+        SESSION_COOKIE, token, SESSION_TTL.as_secs()
+    );
+    ([(header::SET_COOKIE, cookie)], Redirect::to("/")).into_response()
+}
+
+fn session_cookie(req: &Request) -> Option<String> {
+    req.headers()
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value.to_string())
+}
+
+fn is_valid_session(token: &str) -> bool {
+    SESSIONS.lock().is_ok_and(|sessions| sessions.get(token).is_some_and(|expires| *expires > Instant::now()))
+}
+
+fn record_failed_login() {
+    if let Ok(mut failed) = FAILED_LOGINS.lock() {
+        failed.0 += 1;
+        if failed.0 >= MAX_FAILED_LOGINS {
+            *failed = (0, Some(Instant::now() + LOGIN_LOCKOUT));
+            tracing::warn!("Network login locked after {} wrong PINs", MAX_FAILED_LOGINS);
+        }
+    }
+}
+
+fn lockout_remaining() -> Option<Duration> {
+    let until = FAILED_LOGINS.lock().ok()?.1?;
+    until.checked_duration_since(Instant::now())
+}
+
+fn login_page(error: Option<&str>) -> Html<String> {
+    let error = error.map(|e| format!(r#"<p class="error">{}</p>"#, e)).unwrap_or_default();
+    Html(format!(r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1">
+<title>iDoris | Sign in</title>
+<style>
+body {{ margin: 0; min-height: 100vh; display: flex; align-items: center; justify-content: center; background: #0f172a; color: #fff; font-family: system-ui, sans-serif; }}
+form {{ background: #1e293b; padding: 2rem; border-radius: .75rem; width: 18rem; }}
+h1 {{ font-size: 1.25rem; margin: 0 0 1rem; }}
+input, button {{ box-sizing: border-box; width: 100%; padding: .75rem; border-radius: .5rem; border: 1px solid #475569; font-size: 1rem; }}
+input {{ background: #334155; color: #fff; margin-bottom: 1rem; }}
+button {{ background: #2563eb; color: #fff; border: none; }}
+.error {{ color: #f87171; font-size: .875rem; }}
+</style></head>
+<body><form method="post" action="{LOGIN_PATH}">
+<h1>iDoris</h1>
+{error}
+<input name="pin" type="password" inputmode="numeric" autocomplete="current-password" placeholder="Access PIN" autofocus>
+<button type="submit">Sign in</button>
+</form></body></html>"#))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    #[test]
+    fn test_session_cookie_among_others() {
+        let req = Request::builder()
+            .header(header::COOKIE, "theme=dark; idoris_session=abc123; lang=en")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(session_cookie(&req).as_deref(), Some("abc123"));
+
+        let req = Request::builder().header(header::COOKIE, "theme=dark").body(Body::empty()).unwrap();
+        assert_eq!(session_cookie(&req), None);
+    }
+}
@@ -5,15 +5,24 @@
 //! generating responses, streaming text output, and resetting conversation state.
 //!
 //! Phase 2.1+: True runtime model switching with hybrid architecture.
-//! - Uses OnceCell<Mutex<Chat<Llama>>> for stream compatibility
 //! - Uses Lazy<Mutex<Option<Llama>>> for model storage
-//! - Supports runtime model switching by reinitializing both
-
-use std::sync::Mutex;
+//! - Supports runtime model switching by reloading the model
+//! - Keeps a chat per conversation, keyed by session ID, so sessions never
+//!   share context and follow-up turns reuse their KV cache. A conversation's
+//!   chat is rebuilt from its stored messages when the session is opened.
+//! - Prompts outside a conversation run in a fresh chat
+//! - Runs one generation at a time through [`llm_queue`](super::llm_queue)
+
+use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
 use kalosm::language::{Chat, ChatModelExt, IntoChatMessage, Llama};
-use once_cell::sync::{Lazy, OnceCell};
+use once_cell::sync::Lazy;
 use futures::channel::mpsc;
+use uuid::Uuid;
+
+use crate::models::{ChatMessage, GenerationLimits, RequestKind, SessionOverrides, DEFAULT_TEMPERATURE};
+use super::llm_queue;
+use super::stream_guard::{StopReason, StreamGuard};
 
 #[cfg(feature = "server")]
 use super::model_manager::ModelManager;
@@ -21,8 +30,37 @@ use super::model_manager::ModelManager;
 /// Global storage for the Llama model
 static LLAMA_MODEL: Lazy<Mutex<Option<Llama>>> = Lazy::new(|| Mutex::new(None));
 
-/// Global storage for the chat session - uses OnceCell for stream compatibility
-pub static CHAT_SESSION: OnceCell<Mutex<Chat<Llama>>> = OnceCell::new();
+/// A conversation's chat
+struct SessionChat {
+    chat: Arc<Mutex<Chat<Llama>>>,
+    /// Earlier messages of a rebuilt chat, replayed with its next prompt
+    replay: Option<String>,
+}
+
+/// Chats of recent conversations, least recently used first. Each keeps its
+/// KV cache, so a follow-up turn only processes the new message.
+static SESSION_CHATS: Lazy<Mutex<Vec<(Uuid, SessionChat)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Conversations whose last response hit the token cap, so it can be
+/// continued
+static TRUNCATED_SESSIONS: Lazy<Mutex<Vec<Uuid>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Conversations kept warm; each holds its KV cache in memory
+const MAX_SESSION_CHATS: usize = 4;
+/// History replayed into a conversation's chat after a restart or eviction
+const HISTORY_REPLAY_CHARS: usize = 6000;
+
+/// A second model loaded for side-by-side comparisons, with its ID. Kept
+/// until another model is compared, so repeated runs don't reload it.
+static COMPARE_MODEL: Lazy<Mutex<Option<(String, Llama)>>> = Lazy::new(|| Mutex::new(None));
+
+/// A model chosen for individual sessions instead of the active one, with
+/// its ID
+static SESSION_MODEL: Lazy<Mutex<Option<(String, Llama)>>> = Lazy::new(|| Mutex::new(None));
+
+/// The small routing model used for quick prompts such as condensing a
+/// follow-up question, with its ID, if one is configured
+static ROUTING_MODEL: Lazy<Mutex<Option<(String, Llama)>>> = Lazy::new(|| Mutex::new(None));
 
 /// Current model ID
 static CURRENT_MODEL_ID: Lazy<Mutex<String>> = Lazy::new(|| Mutex::new(DEFAULT_MODEL_ID.to_string()));
@@ -45,11 +83,17 @@ pub async fn init_chat_model() -> Result<(), String> {
 /// # Arguments
 /// * `model_id` - The ID of the model to load
 pub async fn init_chat_model_with_id(model_id: &str) -> Result<(), String> {
+    if super::mock::is_enabled() {
+        tracing::info!("Using the mock echo model instead of {}", model_id);
+        *CURRENT_MODEL_ID.lock().unwrap() = model_id.to_string();
+        return Ok(());
+    }
+
     // Check if already initialized with the same model
-    if CHAT_SESSION.get().is_some() {
+    if model_loaded() {
         let current = CURRENT_MODEL_ID.lock().unwrap();
         if *current == model_id {
-            println!("Model {} is already initialized", model_id);
+            tracing::info!("Model {} is already initialized", model_id);
             return Ok(());
         }
     }
@@ -60,7 +104,7 @@ pub async fn init_chat_model_with_id(model_id: &str) -> Result<(), String> {
 
 /// Internal function to load a model
 async fn load_model(model_id: &str) -> Result<(), String> {
-    println!("Initializing chat model: {}...", model_id);
+    tracing::info!("Initializing chat model: {}...", model_id);
 
     // Convert model_id to HuggingFace format if needed
     let hf_model_id = convert_to_hf_model_id(model_id);
@@ -69,7 +113,7 @@ async fn load_model(model_id: &str) -> Result<(), String> {
     {
         // Pre-download model using hf if not cached
         if let Err(e) = ModelManager::ensure_model_cached(&hf_model_id).await {
-            eprintln!("Warning: Failed to ensure model is cached: {}", e);
+            tracing::warn!("Warning: Failed to ensure model is cached: {}", e);
         }
     }
 
@@ -80,14 +124,11 @@ async fn load_model(model_id: &str) -> Result<(), String> {
         .build()
         .await
         .map_err(|e| {
-            eprintln!("Error building model: {}", e);
+            tracing::error!("Error building model: {}", e);
             e.to_string()
         })?;
 
-    println!("Model {} loaded successfully!", model_id);
-
-    // Create chat session
-    let chat = llama.chat();
+    tracing::info!("Model {} loaded successfully!", model_id);
 
     // Store model
     {
@@ -100,10 +141,8 @@ async fn load_model(model_id: &str) -> Result<(), String> {
         let mut id_guard = CURRENT_MODEL_ID.lock().unwrap();
         *id_guard = model_id.to_string();
     }
-
-    // Initialize chat session (only if not already set)
-    // Note: OnceCell can only be set once, so we need to handle this carefully
-    let _ = CHAT_SESSION.set(Mutex::new(chat));
+    // Cached conversations belong to the previous model
+    clear_session_chats();
 
     Ok(())
 }
@@ -136,8 +175,8 @@ pub fn get_current_model_id_sync() -> String {
 
 /// Switch to a different model at runtime
 ///
-/// Note: Due to OnceCell limitations, this requires a server restart.
-/// This function will update the model ID but actual switching happens on restart.
+/// Conversation chats belong to the old model and are rebuilt from their
+/// stored messages on their next turn.
 pub async fn switch_model(model_id: &str) -> Result<(), String> {
     // Check if switching is already in progress
     if MODEL_SWITCHING.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
@@ -149,97 +188,225 @@ pub async fn switch_model(model_id: &str) -> Result<(), String> {
         MODEL_SWITCHING.store(false, Ordering::SeqCst);
     });
 
+    if super::mock::is_enabled() {
+        *CURRENT_MODEL_ID.lock().unwrap() = model_id.to_string();
+        return Ok(());
+    }
+
     // Check if already using the requested model
     let current_id = get_current_model_id_sync();
     if current_id == model_id {
-        println!("Model {} is already loaded", model_id);
+        tracing::info!("Model {} is already loaded", model_id);
         return Ok(());
     }
 
     // Verify model ID is valid
     get_model_source(model_id)?;
 
-    println!("Switching to model {}...", model_id);
+    tracing::info!("Switching to model {}...", model_id);
 
-    // If chat session is not yet initialized, we can do a full switch
-    if CHAT_SESSION.get().is_none() {
-        return load_model(model_id).await;
-    }
+    load_model(model_id).await?;
 
-    // For now, we need to reload the model manually
-    // This will create a new chat session but cannot replace the OnceCell
-    println!("Loading new model {}...", model_id);
+    tracing::info!("Successfully switched to model {}", model_id);
+    Ok(())
+}
 
-    let source = get_model_source(model_id)?;
-    let llama = Llama::builder()
-        .with_source(source)
-        .build()
-        .await
-        .map_err(|e| e.to_string())?;
+/// Check if model switching is supported
+pub fn is_model_switching_supported() -> bool {
+    true
+}
 
-    // Create new chat session
-    let new_chat = llama.chat();
+/// Check if model switching is in progress
+pub fn is_model_switching() -> bool {
+    MODEL_SWITCHING.load(Ordering::SeqCst)
+}
 
-    // Store new model
-    {
-        let mut model_guard = LLAMA_MODEL.lock().unwrap();
-        *model_guard = Some(llama);
+/// Streams the reply to a prompt outside any conversation, from a fresh
+/// chat that keeps no history
+pub fn try_get_stream(prompt: &str) -> Result<mpsc::UnboundedReceiver<String>, String> {
+    let max_length = super::profile::load_settings().generation.max_output_tokens;
+    stream_standalone(prompt.to_string(), None, DEFAULT_TEMPERATURE, max_length)
+}
+
+/// Whether a conversation's chat is cached, so its history need not be
+/// loaded for [`try_get_session_stream`]
+pub fn has_session_chat(session_id: Uuid) -> bool {
+    SESSION_CHATS.lock().is_ok_and(|chats| chats.iter().any(|(id, _)| *id == session_id))
+}
+
+/// Builds a conversation's chat when its session is opened, unless it is
+/// cached. `history` (the conversation's stored messages) is replayed with
+/// the next prompt so the model keeps the context. The session's
+/// `overrides` pick the model and persona; a model other than the active
+/// one is loaded next to it.
+pub async fn open_session_chat(
+    session_id: Uuid,
+    history: &[ChatMessage],
+    overrides: &SessionOverrides,
+) -> Result<(), String> {
+    if super::mock::is_enabled() || has_session_chat(session_id) {
+        return Ok(());
+    }
+    if MODEL_SWITCHING.load(Ordering::SeqCst) {
+        return Err("Model switching in progress, please wait".to_string());
     }
 
-    // Update model ID
-    {
-        let mut id_guard = CURRENT_MODEL_ID.lock().unwrap();
-        *id_guard = model_id.to_string();
+    // Loaded before taking the cache lock, which must not be held across
+    // the load
+    let llama = match overrides.model_id.as_deref().map(convert_from_hf_model_id) {
+        Some(model_id) if model_id != get_current_model_id_sync() => load_side_model(&SESSION_MODEL, &model_id).await?,
+        _ => {
+            let model_guard = LLAMA_MODEL.lock().map_err(|_| "Failed to lock model")?;
+            model_guard.as_ref().ok_or("Model not initialized")?.clone()
+        }
+    };
+    let chat = match overrides.persona.system_prompt() {
+        Some(system) => llama.chat().with_system_prompt(system),
+        None => llama.chat(),
+    };
+    let replay = Some(ChatMessage::history_preamble(history, HISTORY_REPLAY_CHARS)).filter(|r| !r.is_empty());
+    cache_session_chat(session_id, SessionChat { chat: Arc::new(Mutex::new(chat)), replay })
+}
+
+/// Adds a conversation's chat to the cache, evicting the least recently
+/// used one when it is full; a chat already cached is kept
+fn cache_session_chat(session_id: Uuid, entry: SessionChat) -> Result<(), String> {
+    let mut chats = SESSION_CHATS.lock().map_err(|_| "Failed to lock chat cache")?;
+    if chats.iter().any(|(id, _)| *id == session_id) {
+        return Ok(());
+    }
+    if chats.len() >= MAX_SESSION_CHATS {
+        chats.remove(0);
+    }
+    chats.push((session_id, entry));
+    Ok(())
+}
+
+/// Streams the reply to the next turn of a conversation, reusing the
+/// conversation's chat and KV cache from earlier turns.
+///
+/// When the chat is not cached, it is rebuilt as in [`open_session_chat`]
+/// from `history` and the session's `overrides`. Overrides also set the
+/// temperature.
+pub async fn try_get_session_stream(
+    session_id: Uuid,
+    prompt: &str,
+    history: &[ChatMessage],
+    overrides: &SessionOverrides,
+) -> Result<mpsc::UnboundedReceiver<String>, String> {
+    if MODEL_SWITCHING.load(Ordering::SeqCst) {
+        return Err("Model switching in progress, please wait".to_string());
     }
 
-    // Replace the chat session content (Mutex allows this)
-    if let Some(chat_mutex) = CHAT_SESSION.get() {
-        let mut chat_guard = chat_mutex.lock().unwrap();
-        *chat_guard = new_chat;
+    if super::mock::is_enabled() {
+        return Ok(super::mock::echo_stream(prompt));
     }
 
-    println!("Successfully switched to model {}", model_id);
-    Ok(())
+    open_session_chat(session_id, history, overrides).await?;
+
+    let mut chats = SESSION_CHATS.lock().map_err(|_| "Failed to lock chat cache")?;
+    let index = chats.iter()
+        .position(|(id, _)| *id == session_id)
+        .ok_or("The conversation was closed while it was being opened, please try again")?;
+    // Move to the most recently used end
+    let entry = chats.remove(index);
+    let chat = entry.1.chat.clone();
+    chats.push(entry);
+    drop(chats);
+
+    Ok(stream_from_chat(chat, prompt.to_string(), session_id, overrides.temperature()))
 }
 
-/// Check if model switching is supported
-pub fn is_model_switching_supported() -> bool {
-    true
+/// Starts a conversation over: its chat is replaced by an empty one, so the
+/// next turn neither reuses nor replays earlier messages
+pub fn reset_session_chat(session_id: Uuid, overrides: &SessionOverrides) -> Result<(), String> {
+    forget_session_chat(session_id);
+    set_truncated(session_id, false);
+    if super::mock::is_enabled() {
+        return Ok(());
+    }
+
+    // A session model that isn't loaded is picked up on the next turn
+    let llama = match overrides.model_id.as_deref().map(convert_from_hf_model_id) {
+        Some(model_id) if model_id != get_current_model_id_sync() => {
+            let slot = SESSION_MODEL.lock().map_err(|_| "Failed to lock model")?;
+            match slot.as_ref() {
+                Some((id, llama)) if *id == model_id => llama.clone(),
+                _ => return Ok(()),
+            }
+        }
+        _ => {
+            let model_guard = LLAMA_MODEL.lock().map_err(|_| "Failed to lock model")?;
+            model_guard.as_ref().ok_or("Model not initialized")?.clone()
+        }
+    };
+    let chat = match overrides.persona.system_prompt() {
+        Some(system) => llama.chat().with_system_prompt(system),
+        None => llama.chat(),
+    };
+    cache_session_chat(session_id, SessionChat { chat: Arc::new(Mutex::new(chat)), replay: None })
 }
 
-/// Check if model switching is in progress
-pub fn is_model_switching() -> bool {
-    MODEL_SWITCHING.load(Ordering::SeqCst)
+/// Takes the history a rebuilt conversation chat replays with its next
+/// prompt
+fn take_replay(session_id: Uuid) -> Option<String> {
+    let mut chats = SESSION_CHATS.lock().ok()?;
+    chats.iter_mut().find(|(id, _)| *id == session_id)?.1.replay.take()
 }
 
-/// Creates a stream for generating text responses from the language model
-///
-/// This version uses a channel-based approach to avoid lifetime issues with MutexGuard.
-///
-/// # Parameters
-/// * `prompt` - The user's input message
-///
-/// # Returns
-/// * `Result<impl Stream<Item=String>, &'static str>` - A text generation stream or an error
-pub fn try_get_stream(prompt: &str) -> Result<mpsc::UnboundedReceiver<String>, &'static str> {
-    use kalosm::language::GenerationParameters;
-    use futures::StreamExt;
+/// Drops a conversation's cached chat, so its next turn starts a new one
+/// with the session's current model and persona
+pub fn forget_session_chat(session_id: Uuid) {
+    if let Ok(mut chats) = SESSION_CHATS.lock() {
+        chats.retain(|(id, _)| *id != session_id);
+    }
+}
 
-    // Check if switching is in progress
-    if MODEL_SWITCHING.load(Ordering::SeqCst) {
-        return Err("Model switching in progress, please wait");
+/// Whether the last response streamed in a conversation stopped at the
+/// token cap rather than where the model finished
+pub fn was_truncated(session_id: Uuid) -> bool {
+    TRUNCATED_SESSIONS.lock().is_ok_and(|sessions| sessions.contains(&session_id))
+}
+
+fn set_truncated(session_id: Uuid, truncated: bool) {
+    if let Ok(mut sessions) = TRUNCATED_SESSIONS.lock() {
+        sessions.retain(|id| *id != session_id);
+        if truncated {
+            sessions.push(session_id);
+        }
     }
+}
 
-    let chat_mutex = CHAT_SESSION.get().ok_or("Chat session not initialized")?;
+/// Drops every cached conversation chat
+fn clear_session_chats() {
+    if let Ok(mut chats) = SESSION_CHATS.lock() {
+        chats.clear();
+    }
+}
+
+/// Streams the reply to `prompt` from a conversation's chat, holding the
+/// chat's lock on a generation thread until the reply is complete. The
+/// thread waits for its turn in the queue first. Whether the reply was cut
+/// off is recorded before the stream closes.
+fn stream_from_chat(
+    chat_mutex: Arc<Mutex<Chat<Llama>>>,
+    prompt_owned: String,
+    session_id: Uuid,
+    temperature: f32,
+) -> mpsc::UnboundedReceiver<String> {
+    use kalosm::language::GenerationParameters;
 
     // Create channel for streaming tokens
     let (tx, rx) = mpsc::unbounded();
-
-    // Clone prompt to move into async block
-    let prompt_owned = prompt.to_string();
+    let limits = super::profile::load_settings().generation;
+    let ticket = llm_queue::enqueue(RequestKind::Chat, Some(session_id.to_string()));
 
     // Spawn task to handle streaming within the mutex lock
     std::thread::spawn(move || {
+        if !ticket.wait_turn(|| tx.is_closed()) {
+            return;
+        }
+
         // Lock the chat session within the thread
         let mut chat = match chat_mutex.lock() {
             Ok(guard) => guard,
@@ -248,13 +415,17 @@ pub fn try_get_stream(prompt: &str) -> Result<mpsc::UnboundedReceiver<String>, &
                 return;
             }
         };
+        let prompt_owned = match take_replay(session_id) {
+            Some(replay) => format!("{}{}", replay, prompt_owned),
+            None => prompt_owned,
+        };
 
         // Create the stream while holding the lock
         let mut stream = chat.add_message(prompt_owned.into_chat_message())
             .with_sampler(GenerationParameters::default()
-                .with_temperature(0.7)
+                .with_temperature(temperature)
                 .with_top_p(0.9)
-                .with_max_length(600)
+                .with_max_length(limits.max_output_tokens)
             );
 
         // Use a runtime to poll the stream
@@ -263,26 +434,22 @@ pub fn try_get_stream(prompt: &str) -> Result<mpsc::UnboundedReceiver<String>, &
             .build()
             .unwrap();
 
-        rt.block_on(async {
-            while let Some(token) = stream.next().await {
-                if tx.unbounded_send(token).is_err() {
-                    break;
-                }
-            }
-        });
+        let stop = rt.block_on(forward_guarded(&mut stream, &tx, limits));
+        set_truncated(session_id, stop == Some(StopReason::TokenLimit));
     });
 
-    Ok(rx)
+    rx
 }
 
 /// Generates a complete response for the provided prompt.
 ///
 /// This function waits for the full response to be generated and returns it as a String.
-/// Useful for non-streaming contexts like outline generation.
+/// Useful for non-streaming contexts like outline generation. Runs in a fresh
+/// chat, so no conversation's history is involved.
 pub async fn get_llm_response(prompt: String, _options: Option<()>) -> Result<String, String> {
     use futures::StreamExt;
 
-    let mut rx = try_get_stream(&prompt).map_err(|e| e.to_string())?;
+    let mut rx = try_get_stream(&prompt)?;
     let mut response = String::new();
 
     while let Some(token) = rx.next().await {
@@ -292,30 +459,371 @@ pub async fn get_llm_response(prompt: String, _options: Option<()>) -> Result<St
     Ok(response)
 }
 
-/// Resets the chat session to start a new conversation
+/// Generates a short, standalone response with a low temperature and a
+/// length cap, suitable for housekeeping tasks such as session titling.
+pub async fn get_oneshot_response(prompt: String, max_length: u32) -> Result<String, String> {
+    use futures::StreamExt;
+
+    let mut rx = stream_standalone(prompt, None, 0.3, max_length)?;
+    let mut response = String::new();
+    while let Some(token) = rx.next().await {
+        response.push_str(&token);
+    }
+
+    Ok(response)
+}
+
+/// Maximum tool calls per answer before the model must reply
+#[cfg(feature = "server")]
+const MAX_TOOL_ROUNDS: usize = 4;
+
+/// Answers a prompt, letting the model call the given tools.
 ///
-/// # Returns
-/// * `Result<(), String>` - Success or an error message
-pub async fn reset_chat() -> Result<(), String> {
-    // Get the model
-    let model_guard = LLAMA_MODEL.lock().map_err(|_| "Failed to lock model")?;
-    let llama = model_guard.as_ref().ok_or("Model not initialized")?;
+/// Each round asks a fresh chat, with the tool descriptions and a
+/// transcript of earlier calls and their results, for the next tool call
+/// with constrained decoding. Once the model picks no tool or the round
+/// limit is reached, the answer is generated as free text. Without
+/// constrained decoding (mock providers), calls are parsed from the text.
+#[cfg(feature = "server")]
+pub async fn generate_with_tools(
+    prompt: String,
+    tools: &[super::tools::Tool],
+) -> Result<crate::models::ToolRun, String> {
+    use futures::StreamExt;
+    use super::tools::{self, Tool};
+    use crate::models::{ToolInvocation, ToolRun};
+
+    let call_prompt = tools::call_prompt(tools);
+    let system_prompt = tools::system_prompt(tools);
+    let mut run = ToolRun::default();
+    let mut transcript = String::new();
+
+    for round in 0..=MAX_TOOL_ROUNDS {
+        let round_prompt = if transcript.is_empty() {
+            prompt.clone()
+        } else if round == MAX_TOOL_ROUNDS {
+            format!("{}\n\n{}\nAnswer the user now without calling more tools.", prompt, transcript)
+        } else {
+            format!("{}\n\n{}", prompt, transcript)
+        };
+
+        let call = if round == MAX_TOOL_ROUNDS {
+            None
+        } else {
+            match generate_structured::<tools::StructuredToolCall>(round_prompt.clone(), Some(call_prompt.clone()), 600).await {
+                Ok(structured) => structured.into_call(),
+                Err(e) => {
+                    tracing::warn!("Falling back to text tool calls: {}", e);
+                    let mut rx = stream_standalone(round_prompt.clone(), Some(system_prompt.clone()), 0.3, 600)?;
+                    let mut output = String::new();
+                    while let Some(token) = rx.next().await {
+                        output.push_str(&token);
+                    }
+                    match tools::parse_tool_call(&output) {
+                        Some(call) => Some(call),
+                        None => {
+                            run.answer = output.trim().to_string();
+                            return Ok(run);
+                        }
+                    }
+                }
+            }
+        };
 
-    // Create new chat session
-    let new_chat = llama.chat();
+        let Some(call) = call else {
+            run.answer = get_oneshot_response(round_prompt, 600).await?.trim().to_string();
+            return Ok(run);
+        };
 
-    // Replace the chat session
-    if let Some(chat_mutex) = CHAT_SESSION.get() {
-        let mut chat_guard = chat_mutex.lock().map_err(|_| "Failed to lock chat session")?;
-        *chat_guard = new_chat;
+        let outcome = match Tool::from_name(&call.name).filter(|t| tools.contains(t)) {
+            Some(tool) => tool.execute(&call.arguments).await,
+            None => Err(format!("Unknown tool '{}'", call.name)),
+        };
+        tracing::info!("Tool call {} -> {}", call.name, if outcome.is_ok() { "ok" } else { "error" });
+
+        let invocation = match outcome {
+            Ok(tool_output) => ToolInvocation {
+                name: call.name,
+                arguments: call.arguments.to_string(),
+                result: tool_output.text,
+                media: tool_output.media,
+                is_error: false,
+            },
+            Err(e) => ToolInvocation {
+                name: call.name,
+                arguments: call.arguments.to_string(),
+                result: format!("Error: {}", e),
+                media: None,
+                is_error: true,
+            },
+        };
+        transcript.push_str(&format!(
+            "<tool_call>{{\"name\": \"{}\", \"arguments\": {}}}</tool_call>\n<tool_result>{}</tool_result>\n",
+            invocation.name, invocation.arguments, invocation.result
+        ));
+        run.invocations.push(invocation);
     }
 
-    Ok(())
+    Ok(run)
+}
+
+/// Streams a response from a fresh chat that does not share history with
+/// the UI conversation. Used for one-shot prompts and the HTTP API.
+pub fn stream_standalone(
+    prompt: String,
+    system_prompt: Option<String>,
+    temperature: f32,
+    max_length: u32,
+) -> Result<mpsc::UnboundedReceiver<String>, String> {
+    use kalosm::language::GenerationParameters;
+
+    if MODEL_SWITCHING.load(Ordering::SeqCst) {
+        return Err("Model switching in progress, please wait".to_string());
+    }
+
+    if super::mock::is_enabled() {
+        return Ok(super::mock::echo_stream(&prompt));
+    }
+
+    let llama = {
+        let model_guard = LLAMA_MODEL.lock().map_err(|_| "Failed to lock model")?;
+        model_guard.as_ref().ok_or("Model not initialized")?.clone()
+    };
+
+    Ok(spawn_standalone(RequestKind::OneShot, llama, prompt, system_prompt, temperature, max_length))
+}
+
+/// Streams a response to `prompt` from the given model in a fresh chat, for
+/// comparing models side by side. The active model is used as is; any other
+/// model is loaded next to it, which needs memory for both.
+pub async fn stream_with_model(
+    model_id: &str,
+    prompt: String,
+    temperature: f32,
+) -> Result<mpsc::UnboundedReceiver<String>, String> {
+    if MODEL_SWITCHING.load(Ordering::SeqCst) {
+        return Err("Model switching in progress, please wait".to_string());
+    }
+
+    if super::mock::is_enabled() {
+        return Ok(super::mock::echo_stream(&prompt));
+    }
+
+    // The model list uses HuggingFace IDs
+    let model_id = convert_from_hf_model_id(model_id);
+    let llama = if model_id == get_current_model_id_sync() {
+        let model_guard = LLAMA_MODEL.lock().map_err(|_| "Failed to lock model")?;
+        model_guard.as_ref().ok_or("Model not initialized")?.clone()
+    } else {
+        load_side_model(&COMPARE_MODEL, &model_id).await?
+    };
+
+    let max_length = super::profile::load_settings().generation.max_output_tokens;
+    Ok(spawn_standalone(RequestKind::Comparison, llama, prompt, None, temperature, max_length))
+}
+
+/// Generates a short response with the routing model set in settings,
+/// outside the chat session. Falls back to the chat model when no routing
+/// model is set or it is the chat model itself.
+pub async fn get_routing_response(prompt: String, max_length: u32) -> Result<String, String> {
+    use futures::StreamExt;
+
+    let routing_model = super::profile::load_settings().routing_model
+        .map(|id| convert_from_hf_model_id(&id))
+        .filter(|id| *id != get_current_model_id_sync());
+    let Some(model_id) = routing_model else {
+        return get_oneshot_response(prompt, max_length).await;
+    };
+    if MODEL_SWITCHING.load(Ordering::SeqCst) {
+        return Err("Model switching in progress, please wait".to_string());
+    }
+    if super::mock::is_enabled() {
+        return get_oneshot_response(prompt, max_length).await;
+    }
+
+    let llama = load_side_model(&ROUTING_MODEL, &model_id).await?;
+    let mut rx = spawn_standalone(RequestKind::Routing, llama, prompt, None, 0.2, max_length);
+    let mut response = String::new();
+    while let Some(token) = rx.next().await {
+        response.push_str(&token);
+    }
+    Ok(response)
+}
+
+/// The model with the given ID from a side slot (comparison or routing),
+/// loading it into the slot if needed
+async fn load_side_model(slot: &Mutex<Option<(String, Llama)>>, model_id: &str) -> Result<Llama, String> {
+    if let Some((id, llama)) = slot.lock().map_err(|_| "Failed to lock model")?.as_ref() {
+        if id == model_id {
+            return Ok(llama.clone());
+        }
+    }
+
+    let source = get_model_source(model_id)?;
+    #[cfg(feature = "server")]
+    {
+        if let Err(e) = ModelManager::ensure_model_cached(&convert_to_hf_model_id(model_id)).await {
+            tracing::warn!("Warning: Failed to ensure model is cached: {}", e);
+        }
+    }
+
+    tracing::info!("Loading {} next to the chat model...", model_id);
+    let llama = Llama::builder()
+        .with_source(source)
+        .build()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    *slot.lock().map_err(|_| "Failed to lock model")? = Some((model_id.to_string(), llama.clone()));
+    Ok(llama)
+}
+
+/// Runs a fresh chat on its own thread once it is the request's turn, and
+/// streams its output
+fn spawn_standalone(
+    kind: RequestKind,
+    llama: Llama,
+    prompt: String,
+    system_prompt: Option<String>,
+    temperature: f32,
+    max_length: u32,
+) -> mpsc::UnboundedReceiver<String> {
+    use kalosm::language::GenerationParameters;
+
+    let mut limits = super::profile::load_settings().generation;
+    limits.max_output_tokens = limits.max_output_tokens.min(max_length);
+
+    let (tx, rx) = mpsc::unbounded();
+    let ticket = llm_queue::enqueue(kind, None);
+
+    std::thread::spawn(move || {
+        if !ticket.wait_turn(|| tx.is_closed()) {
+            return;
+        }
+        let mut chat = match system_prompt {
+            Some(system) => llama.chat().with_system_prompt(system),
+            None => llama.chat(),
+        };
+        let mut stream = chat.add_message(prompt.into_chat_message())
+            .with_sampler(GenerationParameters::default()
+                .with_temperature(temperature)
+                .with_max_length(limits.max_output_tokens)
+            );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(forward_guarded(&mut stream, &tx, limits));
+    });
+
+    rx
+}
+
+/// Generates a value of type `T` from a fresh chat. Sampling is constrained
+/// to `T`'s JSON schema, so the model cannot produce output that fails to
+/// parse. Not available with mock providers; callers fall back to parsing
+/// free text.
+pub async fn generate_structured<T>(
+    prompt: String,
+    system_prompt: Option<String>,
+    max_length: u32,
+) -> Result<T, String>
+where
+    T: kalosm::language::Parse + kalosm::language::Schema + Clone + Send + Sync + 'static,
+{
+    use kalosm::language::GenerationParameters;
+
+    if MODEL_SWITCHING.load(Ordering::SeqCst) {
+        return Err("Model switching in progress, please wait".to_string());
+    }
+
+    if super::mock::is_enabled() {
+        return Err("Structured output is not available with mock providers".to_string());
+    }
+
+    let llama = {
+        let model_guard = LLAMA_MODEL.lock().map_err(|_| "Failed to lock model")?;
+        model_guard.as_ref().ok_or("Model not initialized")?.clone()
+    };
+
+    let (tx, rx) = futures::channel::oneshot::channel();
+    let ticket = llm_queue::enqueue(RequestKind::Structured, None);
+
+    std::thread::spawn(move || {
+        if !ticket.wait_turn(|| tx.is_canceled()) {
+            return;
+        }
+        let mut chat = match system_prompt {
+            Some(system) => llama.chat().with_system_prompt(system),
+            None => llama.chat(),
+        };
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let started = std::time::Instant::now();
+        let result = rt.block_on(async {
+            chat.add_message(prompt.into_chat_message())
+                .with_sampler(GenerationParameters::default()
+                    .with_temperature(0.3)
+                    .with_max_length(max_length)
+                )
+                .typed::<T>()
+                .await
+                .map_err(|e| format!("Structured generation failed: {}", e))
+        });
+        tracing::debug!("Structured generation took {:?}", started.elapsed());
+        let _ = tx.send(result);
+    });
+
+    rx.await.map_err(|_| "Generation thread stopped".to_string())?
+}
+
+/// Passes tokens to the receiver through the stop-sequence, repetition and
+/// length safeguards, and records the generation stats. Returns why the
+/// stream was stopped early, if it was.
+async fn forward_guarded(
+    stream: &mut (impl futures::Stream<Item = String> + Unpin),
+    tx: &mpsc::UnboundedSender<String>,
+    limits: GenerationLimits,
+) -> Option<StopReason> {
+    use futures::StreamExt;
+
+    let started = std::time::Instant::now();
+    let mut token_count = 0usize;
+    let mut guard = StreamGuard::new(limits);
+    let mut stopped = None;
+    while let Some(token) = stream.next().await {
+        token_count += 1;
+        let (text, stop) = guard.push(&token);
+        if !text.is_empty() && tx.unbounded_send(text).is_err() {
+            break;
+        }
+        if let Some(reason) = stop {
+            tracing::info!("Stopped generation after {} tokens: {:?}", token_count, reason);
+            stopped = Some(reason);
+            break;
+        }
+    }
+    let rest = guard.finish();
+    if !rest.is_empty() {
+        let _ = tx.unbounded_send(rest);
+    }
+    super::diagnostics::record_generation(token_count, started.elapsed());
+    stopped
 }
 
 /// Check if the model is initialized
 pub fn is_initialized() -> bool {
-    CHAT_SESSION.get().is_some()
+    super::mock::is_enabled() || model_loaded()
+}
+
+fn model_loaded() -> bool {
+    LLAMA_MODEL.lock().is_ok_and(|model| model.is_some())
 }
 
 /// Check if the model is initialized (async version)
@@ -333,3 +841,14 @@ fn convert_to_hf_model_id(model_id: &str) -> String {
         _ => model_id.to_string(),
     }
 }
+
+/// Convert a HuggingFace model ID to the short ID used here
+fn convert_from_hf_model_id(model_id: &str) -> String {
+    match model_id {
+        "Qwen/Qwen2.5-1.5B-Instruct" => "qwen-2.5-1.5b".to_string(),
+        "Qwen/Qwen2.5-3B-Instruct" => "qwen-2.5-3b".to_string(),
+        "Qwen/Qwen2.5-7B-Instruct" => "qwen-2.5-7b".to_string(),
+        "meta-llama/Llama-3.2-3B-Instruct" => "llama-3.2-3b".to_string(),
+        _ => model_id.to_string(),
+    }
+}
@@ -0,0 +1,94 @@
+//! LLM Request Queue
+//!
+//! Serializes generation on the shared model: every request takes a
+//! [`Ticket`] and its generation thread waits for its turn before running.
+//! Turns are handed out by [`GenerationQueue`], which alternates between
+//! kinds of request. Dropping a ticket, when the request finishes or its
+//! receiver goes away, passes the turn on.
+
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use uuid::Uuid;
+
+use crate::models::{GenerationQueue, QueueStatus, QueuedRequest, RequestKind};
+
+/// How often a waiting request checks whether its caller is still there
+const CANCEL_CHECK: Duration = Duration::from_millis(500);
+
+static QUEUE: Lazy<Mutex<GenerationQueue>> = Lazy::new(|| Mutex::new(GenerationQueue::default()));
+static TURN: Condvar = Condvar::new();
+
+/// The queue, even if a thread panicked while holding it: every change
+/// leaves it consistent, and refusing it would stall all generation
+fn lock_queue() -> MutexGuard<'static, GenerationQueue> {
+    QUEUE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A place in the queue, released when dropped
+pub struct Ticket {
+    id: Uuid,
+    kind: RequestKind,
+}
+
+/// Queues a generation request
+pub fn enqueue(kind: RequestKind, owner: Option<String>) -> Ticket {
+    let request = QueuedRequest::new(kind, owner);
+    let id = request.id;
+    let mut queue = lock_queue();
+    queue.push(request);
+    if let Some(ahead) = queue.status().position(id).filter(|ahead| *ahead > 0) {
+        tracing::info!("LLM request {} ({}) queued behind {} others", id, kind.label(), ahead);
+    }
+    Ticket { id, kind }
+}
+
+/// The running and waiting requests
+pub fn status() -> QueueStatus {
+    lock_queue().status()
+}
+
+impl Ticket {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Blocks until the request may generate. Returns false when `cancelled`
+    /// reports the caller gave up while waiting.
+    pub fn wait_turn(&self, cancelled: impl Fn() -> bool) -> bool {
+        let started = std::time::Instant::now();
+        let mut queue = lock_queue();
+        loop {
+            if !queue.is_running(self.id) {
+                if let Some(next) = queue.start_next() {
+                    // Wake the request whose turn it is
+                    if next != self.id {
+                        TURN.notify_all();
+                    }
+                }
+            }
+            if queue.is_running(self.id) {
+                let waited = started.elapsed();
+                if waited >= CANCEL_CHECK {
+                    tracing::info!("LLM request {} ({}) started after waiting {:.1}s", self.id, self.kind.label(), waited.as_secs_f32());
+                }
+                return true;
+            }
+            if cancelled() {
+                tracing::info!("LLM request {} ({}) cancelled while queued", self.id, self.kind.label());
+                return false;
+            }
+            queue = TURN.wait_timeout(queue, CANCEL_CHECK).unwrap_or_else(PoisonError::into_inner).0;
+        }
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        let mut queue = lock_queue();
+        queue.remove(self.id);
+        queue.start_next();
+        drop(queue);
+        TURN.notify_all();
+    }
+}
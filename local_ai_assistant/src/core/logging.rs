@@ -0,0 +1,155 @@
+//! Structured Logging
+//!
+//! Configures `tracing` to write human-readable output to stdout and JSON
+//! lines to daily-rotated files under `./data/logs`, and reads those files
+//! back for the in-app log viewer.
+//!
+//! The filter defaults to `info` and can be overridden with `RUST_LOG`.
+
+use std::fs;
+use std::path::PathBuf;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+use crate::models::LogEntry;
+
+/// Number of daily log files kept on disk
+const MAX_LOG_FILES: usize = 7;
+
+/// Log file name prefix; files are named `idoris.YYYY-MM-DD.log`
+const LOG_FILE_PREFIX: &str = "idoris";
+
+/// Directory holding the rotated log files
+pub fn get_log_dir() -> PathBuf {
+    crate::storage::database::get_data_dir().join("logs")
+}
+
+/// Installs the global subscriber. Must run before `dioxus::launch` so that
+/// Dioxus keeps it instead of installing its own logger.
+pub fn init() -> anyhow::Result<()> {
+    let log_dir = get_log_dir();
+    fs::create_dir_all(&log_dir)?;
+
+    let file_appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(&log_dir)?;
+
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new("info"));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_target(false))
+        .with(fmt::layer().json().with_ansi(false).with_writer(file_appender))
+        .try_init()?;
+
+    tracing::info!(log_dir = %log_dir.display(), "Logging initialized");
+    Ok(())
+}
+
+/// Reads the most recent log entries, newest first.
+///
+/// * `min_level` - only include entries at this level or more severe
+/// * `search` - case-insensitive substring match on message and target
+/// * `limit` - maximum number of entries returned
+pub fn read_recent(min_level: Option<&str>, search: Option<&str>, limit: usize) -> anyhow::Result<Vec<LogEntry>> {
+    let log_dir = get_log_dir();
+    if !log_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<PathBuf> = fs::read_dir(&log_dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.starts_with(LOG_FILE_PREFIX))
+            .unwrap_or(false))
+        .collect();
+    // Date-stamped names sort chronologically; newest first
+    files.sort();
+    files.reverse();
+
+    let min_rank = min_level.map(level_rank).unwrap_or(usize::MAX);
+    let search = search.map(str::to_lowercase).filter(|s| !s.is_empty());
+
+    let mut entries = Vec::new();
+    for file in files {
+        let content = fs::read_to_string(&file)?;
+        for line in content.lines().rev() {
+            let Some(entry) = parse_line(line) else { continue };
+            if level_rank(&entry.level) > min_rank {
+                continue;
+            }
+            if let Some(needle) = &search {
+                let haystack = format!("{} {}", entry.message, entry.target).to_lowercase();
+                if !haystack.contains(needle) {
+                    continue;
+                }
+            }
+            entries.push(entry);
+            if entries.len() >= limit {
+                return Ok(entries);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Severity rank, lower is more severe
+fn level_rank(level: &str) -> usize {
+    match level.to_uppercase().as_str() {
+        "ERROR" => 0,
+        "WARN" => 1,
+        "INFO" => 2,
+        "DEBUG" => 3,
+        _ => 4,
+    }
+}
+
+/// Parses one JSON line written by the file layer
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    let fields = value.get("fields")?.as_object()?;
+
+    let mut message = fields.get("message")
+        .and_then(|m| m.as_str())
+        .unwrap_or_default()
+        .to_string();
+    // Append structured fields as key=value
+    for (key, field) in fields.iter().filter(|(k, _)| k.as_str() != "message") {
+        let rendered = field.as_str().map(str::to_string).unwrap_or_else(|| field.to_string());
+        message.push_str(&format!(" {}={}", key, rendered));
+    }
+
+    Some(LogEntry {
+        timestamp: value.get("timestamp")?.as_str()?.to_string(),
+        level: value.get("level")?.as_str()?.to_string(),
+        target: value.get("target").and_then(|t| t.as_str()).unwrap_or_default().to_string(),
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = r#"{"timestamp":"2025-01-01T10:00:00.000Z","level":"WARN","fields":{"message":"Model load slow","model":"qwen-2.5-7b"},"target":"local_ai_assistant::core::llm"}"#;
+        let entry = parse_line(line).unwrap();
+        assert_eq!(entry.level, "WARN");
+        assert_eq!(entry.message, "Model load slow model=qwen-2.5-7b");
+        assert_eq!(entry.target, "local_ai_assistant::core::llm");
+        assert!(parse_line("not json").is_none());
+    }
+
+    #[test]
+    fn test_level_rank() {
+        assert!(level_rank("ERROR") < level_rank("warn"));
+        assert!(level_rank("INFO") < level_rank("DEBUG"));
+    }
+}
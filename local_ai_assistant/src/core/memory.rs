@@ -0,0 +1,177 @@
+//! Long-term Conversation Memory
+//!
+//! Extracts stable facts and preferences about the user from chat exchanges
+//! ("I use Rust and Dioxus", "my blog is X"), stores them with embeddings in
+//! SQLite, and recalls the most relevant ones for new prompts.
+
+use uuid::Uuid;
+
+use crate::models::MemoryEntry;
+use crate::storage::database;
+use super::{embedding, llm};
+
+/// Maximum tokens for the fact extraction response
+const EXTRACTION_MAX_LENGTH: u32 = 200;
+/// New facts this similar to an existing memory are treated as duplicates
+const DUPLICATE_THRESHOLD: f32 = 0.9;
+/// Minimum similarity for a memory to be injected into a prompt
+const RECALL_THRESHOLD: f32 = 0.45;
+/// Maximum memories injected into a prompt
+pub const MAX_RECALLED: usize = 5;
+
+const EXTRACTION_PROMPT: &str = "Extract stable, long-term facts or preferences about the user from the message below \
+(for example their profession, tools they use, projects, or how they like answers). \
+Ignore questions, one-off requests and anything about the assistant. \
+Write each fact as a short third-person sentence on its own line starting with \"- \". \
+If there are none, reply with NONE.\n\nUser message:\n";
+
+/// Cheap pre-check so the LLM is only asked when the user talks about themselves
+pub fn may_contain_personal_facts(message: &str) -> bool {
+    let lower = format!(" {} ", message.to_lowercase());
+    [" i ", " i'm ", " i am ", " my ", " i've ", " mine ", " me ", "我"]
+        .iter()
+        .any(|marker| lower.contains(marker))
+}
+
+/// Extracts facts from a user message and stores the new ones
+pub async fn remember_exchange(session_id: Option<Uuid>, user_message: &str) -> Result<Vec<MemoryEntry>, String> {
+    if !may_contain_personal_facts(user_message) {
+        return Ok(Vec::new());
+    }
+
+    let response = llm::get_oneshot_response(format!("{}{}", EXTRACTION_PROMPT, user_message), EXTRACTION_MAX_LENGTH).await?;
+    let facts = parse_facts(&response);
+    if facts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut known = database::get_memories_with_embeddings().await.map_err(|e| e.to_string())?;
+    let mut saved = Vec::new();
+    for fact in facts {
+        let vector = embedding::embed_text(&fact).await?;
+        if known.iter().any(|(_, v)| cosine_similarity(v, &vector) >= DUPLICATE_THRESHOLD) {
+            tracing::debug!("Skipping duplicate memory: {}", fact);
+            continue;
+        }
+
+        let entry = MemoryEntry::new(fact, session_id);
+        database::save_memory(&entry, &vector).await.map_err(|e| e.to_string())?;
+        tracing::info!("Remembered: {}", entry.content);
+        known.push((entry.clone(), vector));
+        saved.push(entry);
+    }
+
+    Ok(saved)
+}
+
+/// Memories most relevant to the query, best first
+pub async fn recall(query: &str, limit: usize) -> Result<Vec<MemoryEntry>, String> {
+    let memories = database::get_memories_with_embeddings().await.map_err(|e| e.to_string())?;
+    if memories.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let query_vector = embedding::embed_text(query).await?;
+    let mut scored: Vec<(f32, MemoryEntry)> = memories
+        .into_iter()
+        .map(|(entry, v)| (cosine_similarity(&v, &query_vector), entry))
+        .filter(|(score, _)| *score >= RECALL_THRESHOLD)
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    Ok(scored.into_iter().take(limit).map(|(_, entry)| entry).collect())
+}
+
+/// Re-embeds the memories of every profile with the current embedding
+/// backend. Used after the embedding model changes, since vectors from
+/// different models are not comparable. Returns the number re-embedded.
+pub async fn reembed_memories() -> Result<usize, String> {
+    let memories = database::get_all_memory_contents().await.map_err(|e| e.to_string())?;
+    if memories.is_empty() {
+        return Ok(0);
+    }
+
+    let contents: Vec<String> = memories.iter().map(|(_, content)| content.clone()).collect();
+    let vectors = embedding::embed_batch(&contents).await?;
+    if vectors.len() != memories.len() {
+        return Err(format!("Expected {} embeddings but got {}", memories.len(), vectors.len()));
+    }
+    let embeddings: Vec<_> = memories.into_iter().map(|(id, _)| id).zip(vectors).collect();
+    database::set_memory_embeddings(&embeddings).await.map_err(|e| e.to_string())?;
+
+    tracing::info!("Re-embedded {} memories", embeddings.len());
+    Ok(embeddings.len())
+}
+
+/// Formats recalled memories as a prompt preamble
+pub fn format_for_prompt(memories: &[MemoryEntry]) -> String {
+    if memories.is_empty() {
+        return String::new();
+    }
+    let facts: Vec<String> = memories.iter().map(|m| format!("- {}", m.content)).collect();
+    format!("Known facts about the user (use them when relevant):\n{}\n\n", facts.join("\n"))
+}
+
+/// Parses the "- fact" lines of an extraction response
+fn parse_facts(response: &str) -> Vec<String> {
+    if response.trim().eq_ignore_ascii_case("none") {
+        return Vec::new();
+    }
+
+    response
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("- ").or_else(|| line.trim().strip_prefix("* ")))
+        .map(|fact| fact.trim().to_string())
+        .filter(|fact| !fact.is_empty() && !fact.eq_ignore_ascii_case("none"))
+        .collect()
+}
+
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_facts() {
+        let response = "- The user writes Rust.\n- The user's blog is example.com\nsome chatter";
+        assert_eq!(parse_facts(response), vec!["The user writes Rust.", "The user's blog is example.com"]);
+        assert!(parse_facts("NONE").is_empty());
+        assert!(parse_facts("- none").is_empty());
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_may_contain_personal_facts() {
+        assert!(may_contain_personal_facts("I use Rust and Dioxus"));
+        assert!(may_contain_personal_facts("My blog is at example.com"));
+        assert!(may_contain_personal_facts("我是一名程序员"));
+        assert!(!may_contain_personal_facts("What is the capital of France?"));
+    }
+
+    #[test]
+    fn test_format_for_prompt() {
+        assert_eq!(format_for_prompt(&[]), "");
+        let prompt = format_for_prompt(&[MemoryEntry::new("The user likes Rust.".into(), None)]);
+        assert!(prompt.contains("- The user likes Rust."));
+    }
+}
@@ -0,0 +1,191 @@
+//! Mock Providers
+//!
+//! Deterministic stand-ins for every model and remote service, so the UI and
+//! server functions can be exercised in CI or by contributors without
+//! downloading models or paying for API calls. Enable with
+//! `IDORIS_MOCK_PROVIDERS=true`:
+//!
+//! - LLM: an echo model that streams back a fixed reply built from the prompt
+//! - Embeddings: hashed bag-of-words vectors
+//! - Image generation: a solid-colour PNG derived from the prompt
+//! - TTS: silent WAV audio whose length follows the text
+//! - Speech recognition: a fixed sentence naming the recording's size
+//! - Video generation: a completed job with a placeholder URL
+//! - RSS and article extraction: canned entries for any URL
+//!
+//! The same input always produces the same output.
+
+use futures::channel::mpsc;
+use once_cell::sync::Lazy;
+
+use super::content_source::{Article, FeedEntry};
+use super::image_gen::{GeneratedImage, ImageGenSettings};
+use super::tts::GeneratedAudio;
+use super::video_gen::{VideoRequest, VideoResponse, VideoStatus};
+
+/// Dimensions of mock embedding vectors
+pub const EMBEDDING_DIMENSIONS: usize = 64;
+/// Characters of the prompt echoed back by the mock LLM
+const ECHO_LENGTH: usize = 200;
+const AUDIO_SAMPLE_RATE: u32 = 8000;
+/// Mock narration length per word, capped so long articles stay small
+const AUDIO_MS_PER_WORD: u32 = 300;
+const AUDIO_MAX_MS: u32 = 30_000;
+
+static ENABLED: Lazy<bool> = Lazy::new(|| {
+    std::env::var("IDORIS_MOCK_PROVIDERS")
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+});
+
+/// Whether mock providers replace the real ones for this process
+pub fn is_enabled() -> bool {
+    *ENABLED
+}
+
+/// FNV-1a, stable across platforms and Rust versions
+fn hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |h, b| (h ^ u64::from(b)).wrapping_mul(0x100000001b3))
+}
+
+/// The mock LLM's full reply to a prompt
+pub fn echo_reply(prompt: &str) -> String {
+    let prompt = prompt.split_whitespace().collect::<Vec<_>>().join(" ");
+    let echoed: String = prompt.chars().take(ECHO_LENGTH).collect();
+    format!("[mock] {}", echoed)
+}
+
+/// Streams the echo reply word by word, like a real model
+pub fn echo_stream(prompt: &str) -> mpsc::UnboundedReceiver<String> {
+    let (tx, rx) = mpsc::unbounded();
+    for (i, word) in echo_reply(prompt).split(' ').enumerate() {
+        let token = if i == 0 { word.to_string() } else { format!(" {}", word) };
+        let _ = tx.unbounded_send(token);
+    }
+    rx
+}
+
+/// Hashed bag-of-words vector, normalised to unit length
+pub fn embedding(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0f32; EMBEDDING_DIMENSIONS];
+    for word in text.split_whitespace() {
+        let h = hash(&word.to_lowercase());
+        let sign = if h & 1 == 0 { 1.0 } else { -1.0 };
+        vector[(h >> 1) as usize % EMBEDDING_DIMENSIONS] += sign;
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+/// Solid-colour PNG at the requested size
+pub fn image(settings: &ImageGenSettings) -> Result<GeneratedImage, String> {
+    let [r, g, b, ..] = hash(&settings.prompt).to_le_bytes();
+    let pixels = image::RgbImage::from_pixel(settings.width.max(1), settings.height.max(1), image::Rgb([r, g, b]));
+
+    let mut data = Vec::new();
+    pixels.write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode mock image: {}", e))?;
+
+    Ok(GeneratedImage {
+        data,
+        width: pixels.width(),
+        height: pixels.height(),
+        format: "png".to_string(),
+        path: None,
+    })
+}
+
+/// Silent 16-bit mono WAV
+pub fn speech(text: &str) -> GeneratedAudio {
+    let duration_ms = (text.split_whitespace().count() as u32 * AUDIO_MS_PER_WORD).clamp(AUDIO_MS_PER_WORD, AUDIO_MAX_MS);
+    let samples = AUDIO_SAMPLE_RATE * duration_ms / 1000;
+    let data_len = samples * 2;
+
+    let mut data = Vec::with_capacity(44 + data_len as usize);
+    data.extend_from_slice(b"RIFF");
+    data.extend_from_slice(&(36 + data_len).to_le_bytes());
+    data.extend_from_slice(b"WAVEfmt ");
+    data.extend_from_slice(&16u32.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    data.extend_from_slice(&1u16.to_le_bytes()); // mono
+    data.extend_from_slice(&AUDIO_SAMPLE_RATE.to_le_bytes());
+    data.extend_from_slice(&(AUDIO_SAMPLE_RATE * 2).to_le_bytes());
+    data.extend_from_slice(&2u16.to_le_bytes());
+    data.extend_from_slice(&16u16.to_le_bytes());
+    data.extend_from_slice(b"data");
+    data.extend_from_slice(&data_len.to_le_bytes());
+    data.resize(44 + data_len as usize, 0);
+
+    GeneratedAudio {
+        data,
+        sample_rate: AUDIO_SAMPLE_RATE,
+        format: "wav".to_string(),
+        duration_ms,
+    }
+}
+
+/// What the mock recognizer "hears" in a recording
+pub fn transcript(audio: &[u8]) -> String {
+    format!("Mock question from {} bytes of audio.", audio.len())
+}
+
+/// A completed video job; nothing is downloaded from the placeholder URL
+pub fn video(request: &VideoRequest, cost_estimate: f64) -> VideoResponse {
+    let id = format!("mock-{:016x}", hash(&request.prompt));
+    VideoResponse {
+        video_url: format!("mock://video/{}.mp4", id),
+        thumbnail_url: None,
+        generation_id: id,
+        duration_seconds: request.config.duration_seconds,
+        cost_estimate,
+        status: VideoStatus::Completed,
+    }
+}
+
+/// Canned feed entries, the same for every feed URL
+pub fn rss_entries(feed_url: &str) -> Vec<FeedEntry> {
+    (1..=3)
+        .map(|i| FeedEntry {
+            id: format!("mock-entry-{}", i),
+            title: format!("Mock article {}", i),
+            url: format!("https://example.com/mock/{}", i),
+            summary: Some(format!("Summary of mock article {} from {}", i, feed_url)),
+            published_at: None,
+        })
+        .collect()
+}
+
+/// A canned article for any URL
+pub fn article(url: &str) -> Article {
+    Article::new(
+        "mock",
+        "Mock article",
+        "Local models keep your data on your machine. They trade some quality for privacy and \
+         predictable costs. This text is returned for every URL while mock providers are enabled.",
+    )
+    .with_url(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outputs_are_deterministic() {
+        assert_eq!(echo_reply("Hello\n  world"), "[mock] Hello world");
+        assert_eq!(embedding("Rust is fast"), embedding("rust IS fast"));
+        assert_ne!(embedding("Rust is fast"), embedding("Python is slow"));
+        assert!((embedding("one two three").iter().map(|v| v * v).sum::<f32>() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_speech_is_valid_wav() {
+        let audio = speech("four words of text");
+        assert_eq!(audio.duration_ms, 4 * AUDIO_MS_PER_WORD);
+        assert_eq!(&audio.data[..4], b"RIFF");
+        assert_eq!(audio.data.len(), 44 + (AUDIO_SAMPLE_RATE * audio.duration_ms / 1000 * 2) as usize);
+    }
+}
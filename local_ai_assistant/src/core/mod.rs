@@ -1,24 +1,146 @@
 //! Core Services Module
 //!
-//! Low-level services for LLM inference, embedding, vector storage, image generation, TTS, video generation, and content sources.
+//! Low-level services behind the server functions: inference, retrieval, media generation, content pipelines and storage.
 
 pub mod error;
 pub mod config;
 pub mod llm;
+pub mod llm_queue;
 pub mod embedding;
+pub mod embedding_cache;
 pub mod vector_store;
 
+#[cfg(feature = "server")]
+pub mod jobs;
+
+#[cfg(feature = "server")]
+pub mod indexing;
+
+#[cfg(feature = "server")]
+pub mod paths;
+
+#[cfg(feature = "server")]
+pub mod http;
+
 #[cfg(feature = "server")]
 pub mod model_manager;
 
 #[cfg(feature = "server")]
 pub mod image_gen;
 
+#[cfg(feature = "server")]
+pub mod image_processing;
+
+#[cfg(feature = "server")]
+pub mod upscale;
+
 #[cfg(feature = "server")]
 pub mod tts;
 
 #[cfg(feature = "server")]
 pub mod video_gen;
 
+#[cfg(feature = "server")]
+pub mod video_assembly;
+
+#[cfg(feature = "server")]
+pub mod assets;
+
+#[cfg(feature = "server")]
+pub mod trash;
+
+#[cfg(feature = "server")]
+pub mod encryption;
+
 #[cfg(feature = "server")]
 pub mod content_source;
+
+#[cfg(feature = "server")]
+pub mod diagnostics;
+
+#[cfg(feature = "server")]
+pub mod logging;
+
+#[cfg(feature = "server")]
+pub mod profile;
+
+#[cfg(feature = "server")]
+pub mod api_server;
+
+#[cfg(feature = "server")]
+pub mod lan_access;
+
+#[cfg(feature = "server")]
+pub mod webhooks;
+
+#[cfg(feature = "server")]
+pub mod notifications;
+
+#[cfg(feature = "server")]
+pub mod quick_capture;
+
+#[cfg(feature = "server")]
+pub mod attachments;
+
+#[cfg(feature = "server")]
+pub mod session_summary;
+
+#[cfg(feature = "server")]
+pub mod session_tags;
+
+#[cfg(feature = "server")]
+pub mod transcription;
+
+#[cfg(feature = "server")]
+pub mod youtube;
+
+#[cfg(feature = "server")]
+pub mod codebase;
+
+#[cfg(feature = "server")]
+pub mod query_expansion;
+
+#[cfg(feature = "server")]
+pub mod diagrams;
+
+#[cfg(feature = "server")]
+pub mod memory;
+
+#[cfg(feature = "server")]
+pub mod fine_tune;
+
+#[cfg(feature = "server")]
+pub mod tools;
+
+#[cfg(feature = "server")]
+pub mod web_search;
+
+#[cfg(feature = "server")]
+pub mod code_sandbox;
+
+#[cfg(feature = "server")]
+pub mod content_generator;
+
+#[cfg(feature = "server")]
+pub mod seo;
+
+#[cfg(feature = "server")]
+pub mod publishing;
+
+#[cfg(feature = "server")]
+pub mod proofreader;
+
+#[cfg(feature = "server")]
+pub mod readability;
+
+#[cfg(feature = "server")]
+pub mod similarity_check;
+
+#[cfg(feature = "server")]
+pub mod mock;
+
+#[cfg(feature = "server")]
+pub mod capabilities;
+
+#[cfg(feature = "server")]
+pub mod stream_guard;
@@ -1,14 +1,40 @@
 //! Model Manager
 //!
 //! Manages HuggingFace model downloads, caching, and switching.
-//! Uses huggingface-cli for model management.
+//! Models are downloaded file by file from the Hub into the standard
+//! Hugging Face cache layout. Interrupted files resume with range requests,
+//! failed attempts are retried, and every file is checked against the size
+//! and checksum the Hub lists before it is used. [`ModelManager::check_disk_space`]
+//! is the preflight check for free space shared with the media generators.
 
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::fs;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command as AsyncCommand;
 use anyhow::{Result, Context};
-use crate::models::{ModelInfo, CacheInfo, get_available_models};
+use sha2::{Digest, Sha256};
+use crate::models::{
+    ModelInfo, CacheInfo, NetworkFeature, RepoFile, RepoListing, ResumeAction, SpaceCheck, get_available_models,
+    check_space, format_bytes, parse_df_available, resume_action, retry_delay, DISK_RESERVE_BYTES, MAX_DOWNLOAD_ATTEMPTS,
+};
+use super::http;
+
+/// Bytes downloaded between progress reports
+const PROGRESS_STEP: u64 = 4 * 1024 * 1024;
+
+/// How long a download may go without receiving data before it is retried
+const STALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Reported while a model downloads
+pub enum DownloadEvent {
+    /// Bytes of the whole model fetched so far
+    Progress { done: u64, total: u64 },
+    /// A file failed and will be tried again
+    Retrying(String),
+    /// The download fits, but leaves little disk space
+    LowDiskSpace(String),
+}
 
 /// Model Manager for handling HuggingFace models
 pub struct ModelManager {
@@ -23,7 +49,7 @@ impl ModelManager {
     }
 
     /// Get HuggingFace cache directory
-    fn get_cache_dir() -> PathBuf {
+    pub(crate) fn get_cache_dir() -> PathBuf {
         // Use the symlinked cache directory
         dirs::home_dir()
             .unwrap_or_else(|| PathBuf::from("~"))
@@ -43,13 +69,30 @@ impl ModelManager {
         Ok(get_available_models())
     }
 
+    /// Cache folder of a model
+    fn model_cache_dir(model_id: &str) -> PathBuf {
+        Self::get_cache_dir().join(format!("models--{}", model_id.replace('/', "--")))
+    }
+
+    /// Whether a model finished downloading. The revision is recorded once
+    /// every file is in place, and partial files are left while one is
+    /// still missing.
+    fn is_fully_cached(model_cache_dir: &Path) -> bool {
+        if !model_cache_dir.join("refs/main").exists() {
+            return false;
+        }
+        let Ok(blobs) = fs::read_dir(model_cache_dir.join("blobs")) else {
+            return true;
+        };
+        !blobs.filter_map(Result::ok)
+            .any(|entry| entry.path().extension().is_some_and(|ext| ext == "incomplete"))
+    }
+
     /// Check if model is cached and update cache status
     pub async fn check_cached_status(models: &mut [ModelInfo]) -> Result<()> {
-        let cache_dir = Self::get_cache_dir();
-
         for model in models.iter_mut() {
-            let model_cache_dir = cache_dir.join(format!("models--{}", model.id.replace('/', "--")));
-            model.is_cached = model_cache_dir.exists();
+            let model_cache_dir = Self::model_cache_dir(&model.id);
+            model.is_cached = Self::is_fully_cached(&model_cache_dir);
             model.cache_path = if model.is_cached {
                 Some(model_cache_dir)
             } else {
@@ -60,50 +103,69 @@ impl ModelManager {
         Ok(())
     }
 
-    /// Download a model using huggingface-cli
-    /// Download a model using hf command
+    /// Download a model from the Hub
     pub async fn download_model(model_id: &str) -> Result<String> {
-        println!("Downloading model: {}", model_id);
-
-        // Check if hf command is available
-        let output = Command::new("which")
-            .arg("hf")
-            .output();
+        Self::download_model_with_progress(model_id, |_| {}).await
+    }
 
-        if !output.map_or(false, |o| o.status.success()) {
-            return Err(anyhow::anyhow!(
-                "hf command not found. Please install huggingface_hub: pip install -U huggingface_hub"
-            ));
+    /// Download a model from the Hub, reporting progress and retries.
+    /// Files already in the cache are skipped, so a cancelled or failed
+    /// download continues where it stopped when started again.
+    pub async fn download_model_with_progress(model_id: &str, on_event: impl Fn(DownloadEvent) + Send + Sync) -> Result<String> {
+        tracing::info!("Downloading model: {}", model_id);
+
+        let listing = list_repo_files(model_id).await?;
+        let model_cache_dir = Self::model_cache_dir(model_id);
+        let blobs_dir = model_cache_dir.join("blobs");
+        let snapshot_dir = model_cache_dir.join("snapshots").join(&listing.commit);
+
+        // Only what is still missing needs room
+        let needed: u64 = listing.files.iter()
+            .filter(|file| !fs::metadata(blobs_dir.join(file.etag())).is_ok_and(|m| m.len() == file.size))
+            .map(|file| file.size.saturating_sub(partial_len(&partial_path(&blobs_dir.join(file.etag())))))
+            .sum();
+        if let Some(warning) = Self::check_disk_space(&blobs_dir, needed, &format!("downloading {}", model_id)).await? {
+            on_event(DownloadEvent::LowDiskSpace(warning));
         }
 
-        // Use hf to download
-        let output = AsyncCommand::new("hf")
-            .arg("download")
-            .arg(model_id)
-            .output()
-            .await
-            .context("Failed to execute hf download")?;
-
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("Failed to download model {}: {}", model_id, error));
+        fs::create_dir_all(&blobs_dir).context("Failed to create the model cache directory")?;
+
+        let total = listing.total_size();
+        let mut done = 0u64;
+        let mut fetched = 0usize;
+        for file in &listing.files {
+            let blob = blobs_dir.join(file.etag());
+            if !fs::metadata(&blob).is_ok_and(|m| m.len() == file.size) {
+                let before = done;
+                download_file(model_id, &listing.commit, file, &blob, |bytes| {
+                    on_event(DownloadEvent::Progress { done: before + bytes, total });
+                }, &on_event).await?;
+                fetched += 1;
+            }
+            done += file.size;
+            on_event(DownloadEvent::Progress { done, total });
+            link_snapshot(&snapshot_dir, &file.path, &blob)?;
         }
 
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        Ok(output_str.to_string())
+        // Recorded last, so an interrupted download is not taken as cached
+        let refs_dir = model_cache_dir.join("refs");
+        fs::create_dir_all(&refs_dir)?;
+        fs::write(refs_dir.join("main"), &listing.commit)?;
+
+        Ok(format!(
+            "Downloaded {} of {} files ({} MB) for {}",
+            fetched, listing.files.len(), total / (1024 * 1024), model_id
+        ))
     }
 
     /// Check if model is cached, download if not
     pub async fn ensure_model_cached(model_id: &str) -> Result<()> {
-        let cache_dir = Self::get_cache_dir();
-        let model_cache_dir = cache_dir.join(format!("models--{}", model_id.replace('/', "--")));
-
-        if !model_cache_dir.exists() {
-            println!("Model {} not found in cache, downloading...", model_id);
+        if !Self::is_fully_cached(&Self::model_cache_dir(model_id)) {
+            tracing::info!("Model {} not found in cache, downloading...", model_id);
             Self::download_model(model_id).await?;
-            println!("Model {} downloaded successfully", model_id);
+            tracing::info!("Model {} downloaded successfully", model_id);
         } else {
-            println!("Model {} found in cache", model_id);
+            tracing::info!("Model {} found in cache", model_id);
         }
 
         Ok(())
@@ -111,13 +173,12 @@ impl ModelManager {
 
     /// Delete a model from cache
     pub async fn delete_model(model_id: &str) -> Result<()> {
-        let cache_dir = Self::get_cache_dir();
-        let model_cache_dir = cache_dir.join(format!("models--{}", model_id.replace('/', "--")));
+        let model_cache_dir = Self::model_cache_dir(model_id);
 
         if model_cache_dir.exists() {
             fs::remove_dir_all(&model_cache_dir)
                 .context("Failed to delete model cache directory")?;
-            println!("Model {} deleted from cache", model_id);
+            tracing::info!("Model {} deleted from cache", model_id);
         }
 
         Ok(())
@@ -157,7 +218,7 @@ impl ModelManager {
     }
 
     /// Calculate directory size recursively
-    fn calculate_dir_size(path: &Path) -> Result<u64> {
+    pub(crate) fn calculate_dir_size(path: &Path) -> Result<u64> {
         let mut total_size = 0u64;
 
         if path.is_dir() {
@@ -176,6 +237,44 @@ impl ModelManager {
         Ok(total_size)
     }
 
+    /// Free bytes on the disk holding `dir`, from `df`; None where that
+    /// can't be measured
+    pub async fn available_space(dir: &Path) -> Option<u64> {
+        // The directory may not exist yet
+        let existing = dir.ancestors().find(|path| path.exists())?;
+        let output = AsyncCommand::new("df").arg("-Pk").arg(existing).output().await.ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_df_available(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Checks there is room to write about `needed` bytes into `dir` before
+    /// starting `purpose`, e.g. "generating an image". Fails when less than
+    /// the reserve would be left free, and returns a warning, also logged,
+    /// when little would be left. Passes when space can't be measured.
+    pub async fn check_disk_space(dir: &Path, needed: u64, purpose: &str) -> Result<Option<String>> {
+        let Some(available) = Self::available_space(dir).await else {
+            tracing::debug!("Could not measure free disk space at {}", dir.display());
+            return Ok(None);
+        };
+        match check_space(needed, available) {
+            SpaceCheck::Enough => Ok(None),
+            SpaceCheck::Low { available } => {
+                let warning = format!(
+                    "Disk space is low: {} needs about {}, leaving {} free on the disk holding {}",
+                    purpose, format_bytes(needed), format_bytes(available.saturating_sub(needed)), dir.display()
+                );
+                tracing::warn!("{}", warning);
+                Ok(Some(warning))
+            }
+            SpaceCheck::Insufficient { available } => Err(anyhow::anyhow!(
+                "Not enough disk space for {}: it needs about {} and {} is kept free, but only {} is free on the disk holding {}. Free up space or choose another storage location.",
+                purpose, format_bytes(needed), format_bytes(DISK_RESERVE_BYTES), format_bytes(available), dir.display()
+            )),
+        }
+    }
+
     /// Move cache to external disk
     pub async fn move_cache_to_external() -> Result<()> {
         let old_cache = dirs::home_dir()
@@ -184,7 +283,7 @@ impl ModelManager {
         let new_cache = PathBuf::from("/Volumes/UltraDisk/Dev2/.cache/huggingface");
 
         if !old_cache.exists() {
-            println!("No existing cache to move");
+            tracing::info!("No existing cache to move");
             return Ok(());
         }
 
@@ -192,7 +291,7 @@ impl ModelManager {
         fs::create_dir_all(&new_cache)?;
 
         // Move contents
-        println!("Moving cache to external disk...");
+        tracing::info!("Moving cache to external disk...");
         let output = AsyncCommand::new("rsync")
             .arg("-avh")
             .arg("--progress")
@@ -216,12 +315,191 @@ impl ModelManager {
             std::os::unix::fs::symlink(&new_cache, &old_cache)?;
         }
 
-        println!("Cache moved successfully to: {}", new_cache.display());
+        tracing::info!("Cache moved successfully to: {}", new_cache.display());
         Ok(())
     }
 }
 
 
+/// Adds the `HF_TOKEN` access token, needed for gated models
+fn with_token(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match std::env::var("HF_TOKEN") {
+        Ok(token) if !token.is_empty() => request.bearer_auth(token),
+        _ => request,
+    }
+}
+
+/// The files of the model's main revision
+async fn list_repo_files(model_id: &str) -> Result<RepoListing> {
+    let url = format!("{}/api/models/{}/revision/main?blobs=true", http::hf_endpoint(), model_id);
+    let response = http::send(NetworkFeature::ModelDownloads, with_token(http::client().get(&url)))
+        .await
+        .context("Failed to reach Hugging Face")?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!("Failed to list the files of {}: HTTP {}", model_id, response.status()));
+    }
+    let json: serde_json::Value = response.json().await.context("Failed to read the model's file list")?;
+    RepoListing::from_json(&json).map_err(anyhow::Error::msg)
+}
+
+/// Where a blob is written while it downloads, named like the hf tools do
+fn partial_path(blob: &Path) -> PathBuf {
+    let mut name = blob.file_name().unwrap_or_default().to_os_string();
+    name.push(".incomplete");
+    blob.with_file_name(name)
+}
+
+fn partial_len(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Downloads one file to its blob, retrying and resuming until it arrives
+/// intact. Failures only count toward the limit while no bytes arrive.
+async fn download_file(
+    model_id: &str,
+    commit: &str,
+    file: &RepoFile,
+    blob: &Path,
+    on_bytes: impl Fn(u64),
+    on_event: &impl Fn(DownloadEvent),
+) -> Result<()> {
+    let partial = partial_path(blob);
+    let url = format!("{}/{}/resolve/{}/{}", http::hf_endpoint(), model_id, commit, file.path);
+    let mut failures = 0;
+    loop {
+        let before = partial_len(&partial);
+        let result = match fetch_into(&url, &partial, file.size, &on_bytes).await {
+            Ok(()) => verify(&partial, file).await.inspect_err(|_| {
+                // A corrupt file can't be resumed
+                let _ = fs::remove_file(&partial);
+            }),
+            Err(e) => Err(e),
+        };
+        let error = match result {
+            Ok(()) => {
+                fs::rename(&partial, blob).context("Failed to move the downloaded file into the cache")?;
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+
+        if partial_len(&partial) > before {
+            failures = 0;
+        }
+        failures += 1;
+        if failures >= MAX_DOWNLOAD_ATTEMPTS {
+            return Err(anyhow::anyhow!("Failed to download {} after {} attempts: {}", file.path, failures, error));
+        }
+        let delay = retry_delay(failures);
+        let message = format!("{} failed ({}), retrying in {}s", file.path, error, delay.as_secs());
+        tracing::warn!("Model {}: {}", model_id, message);
+        on_event(DownloadEvent::Retrying(message));
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Fetches the rest of a file into its partial file
+async fn fetch_into(url: &str, partial: &Path, size: u64, on_bytes: &impl Fn(u64)) -> Result<()> {
+    let mut have = partial_len(partial);
+    if have > size {
+        fs::remove_file(partial)?;
+        have = 0;
+    }
+    if have == size && partial.exists() {
+        return Ok(());
+    }
+
+    let mut request = with_token(http::client().get(url));
+    if have > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", have));
+    }
+    let mut response = http::send(NetworkFeature::ModelDownloads, request).await?;
+    let content_range = response.headers()
+        .get(reqwest::header::CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let mut out = match resume_action(have, response.status().as_u16(), content_range.as_deref()).map_err(anyhow::Error::msg)? {
+        ResumeAction::Complete => return Ok(()),
+        ResumeAction::Restart => {
+            have = 0;
+            tokio::fs::File::create(partial).await?
+        }
+        ResumeAction::Append => tokio::fs::OpenOptions::new().append(true).open(partial).await?,
+    };
+
+    let mut reported = have;
+    loop {
+        let chunk = match tokio::time::timeout(STALL_TIMEOUT, response.chunk()).await {
+            Ok(chunk) => chunk?,
+            Err(_) => {
+                // Keep what arrived so the retry resumes after it
+                out.flush().await?;
+                return Err(anyhow::anyhow!("no data received for {}s", STALL_TIMEOUT.as_secs()));
+            }
+        };
+        let Some(chunk) = chunk else { break };
+        out.write_all(&chunk).await?;
+        have += chunk.len() as u64;
+        if have - reported >= PROGRESS_STEP {
+            on_bytes(have);
+            reported = have;
+        }
+    }
+    out.flush().await?;
+    on_bytes(have);
+
+    if have != size {
+        return Err(anyhow::anyhow!("the connection closed after {} of {} bytes", have, size));
+    }
+    Ok(())
+}
+
+/// Checks a finished file against the size and checksum the Hub lists
+async fn verify(partial: &Path, file: &RepoFile) -> Result<()> {
+    let size = partial_len(partial);
+    if size != file.size {
+        return Err(anyhow::anyhow!("expected {} bytes but got {}", file.size, size));
+    }
+    let Some(expected) = file.sha256.clone() else {
+        return Ok(());
+    };
+    let path = partial.to_path_buf();
+    let actual = tokio::task::spawn_blocking(move || -> Result<String> {
+        let mut reader = fs::File::open(&path)?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut reader, &mut hasher)?;
+        Ok(hex::encode(hasher.finalize()))
+    })
+    .await??;
+    if !actual.eq_ignore_ascii_case(&expected) {
+        return Err(anyhow::anyhow!("checksum mismatch"));
+    }
+    Ok(())
+}
+
+/// Points the snapshot's copy of a file at its blob
+fn link_snapshot(snapshot_dir: &Path, file_path: &str, blob: &Path) -> Result<()> {
+    let link = snapshot_dir.join(file_path);
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::symlink_metadata(&link).is_ok() {
+        fs::remove_file(&link)?;
+    }
+
+    #[cfg(unix)]
+    {
+        // Relative like the hf tools, so the cache can be moved
+        let depth = file_path.matches('/').count() + 2;
+        let target = PathBuf::from("../".repeat(depth)).join("blobs").join(blob.file_name().unwrap_or_default());
+        std::os::unix::fs::symlink(target, &link)?;
+    }
+    #[cfg(not(unix))]
+    {
+        fs::copy(blob, &link)?;
+    }
+    Ok(())
+}
 
 #[cfg(test)]
 mod tests {
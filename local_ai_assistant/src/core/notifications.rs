@@ -0,0 +1,69 @@
+//! Notifications
+//!
+//! In-memory queue of job completion and failure messages. Background jobs
+//! push to it; the UI polls it for toasts and the notification center, and
+//! the desktop app turns new entries into OS notifications.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use crate::models::{Notification, NotificationLevel};
+
+/// Notifications kept for the history
+const MAX_NOTIFICATIONS: usize = 100;
+
+/// Recent notifications, oldest first, and the next id
+static QUEUE: Lazy<Mutex<(VecDeque<Notification>, u64)>> = Lazy::new(|| Mutex::new((VecDeque::new(), 1)));
+
+pub fn notify(level: NotificationLevel, title: impl Into<String>, message: impl Into<String>) {
+    let Ok(mut queue) = QUEUE.lock() else { return };
+    let (notifications, next_id) = &mut *queue;
+    let notification = Notification::new(*next_id, level, title, message);
+    *next_id += 1;
+    tracing::info!("Notification: {} - {}", notification.title, notification.message);
+    notifications.push_back(notification);
+    while notifications.len() > MAX_NOTIFICATIONS {
+        notifications.pop_front();
+    }
+}
+
+pub fn success(title: impl Into<String>, message: impl Into<String>) {
+    notify(NotificationLevel::Success, title, message);
+}
+
+pub fn error(title: impl Into<String>, message: impl Into<String>) {
+    notify(NotificationLevel::Error, title, message);
+}
+
+/// Recent notifications, newest first
+pub fn list() -> Vec<Notification> {
+    QUEUE.lock().map(|q| q.0.iter().rev().cloned().collect()).unwrap_or_default()
+}
+
+pub fn mark_all_read() {
+    if let Ok(mut queue) = QUEUE.lock() {
+        queue.0.iter_mut().for_each(|n| n.read = true);
+    }
+}
+
+pub fn clear() {
+    if let Ok(mut queue) = QUEUE.lock() {
+        queue.0.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_increase_and_history_is_capped() {
+        for i in 0..MAX_NOTIFICATIONS + 5 {
+            notify(NotificationLevel::Info, "Job", i.to_string());
+        }
+        let list = list();
+        assert!(list.len() <= MAX_NOTIFICATIONS);
+        assert!(list.windows(2).all(|pair| pair[0].id > pair[1].id));
+    }
+}
@@ -0,0 +1,272 @@
+//! Storage Locations
+//!
+//! Resolves where the data, vector index, context documents and generated
+//! output live. Each location comes from, in order: its environment variable
+//! (see [`StorageLocation::env_var`]), the Settings > System override, or the
+//! per-OS data directory (`~/.local/share/iDoris` on Linux,
+//! `~/Library/Application Support/iDoris` on macOS, `%APPDATA%\iDoris` on
+//! Windows). Overrides are saved to `paths.json` in the per-OS config
+//! directory, since the data directory itself can move.
+//!
+//! [`migrate`] runs at startup and moves data from the locations used last
+//! time, or from the old `./data`, `./db` and `./context` folders next to the
+//! project and `~/.local_ai_assistant`, to the current ones.
+
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{StorageLocation, StorageLocationInfo, StoragePaths};
+
+/// Name of the app's folder inside the per-OS directories
+const APP_DIR_NAME: &str = "iDoris";
+
+/// Contents of `paths.json`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct PathsFile {
+    /// Directories set in the settings UI
+    #[serde(default)]
+    overrides: StoragePaths,
+    /// Directories resolved at the last start, to detect moves
+    #[serde(default)]
+    in_use: Option<StoragePaths>,
+}
+
+static PATHS: Lazy<RwLock<PathsFile>> = Lazy::new(|| RwLock::new(load()));
+
+fn paths_file() -> PathBuf {
+    dirs::config_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_DIR_NAME)
+        .join("paths.json")
+}
+
+fn load() -> PathsFile {
+    std::fs::read_to_string(paths_file())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(file: &PathsFile) -> Result<(), String> {
+    let path = paths_file();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Expands a leading `~` to the home directory
+fn expand(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') || rest.starts_with('\\') => {
+            home.join(rest.trim_start_matches(['/', '\\']))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+fn env_override(location: StorageLocation) -> Option<PathBuf> {
+    std::env::var(location.env_var())
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| expand(v.trim()))
+}
+
+/// Directory in effect for a location
+pub fn resolve(location: StorageLocation) -> PathBuf {
+    if let Some(path) = env_override(location) {
+        return path;
+    }
+    let configured = PATHS.read().ok().and_then(|p| p.overrides.get(location).map(expand));
+    configured.unwrap_or_else(|| match location.default_subdir() {
+        Some(subdir) => resolve(StorageLocation::Data).join(subdir),
+        None => dirs::data_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(APP_DIR_NAME),
+    })
+}
+
+pub fn data_dir() -> PathBuf {
+    resolve(StorageLocation::Data)
+}
+
+pub fn index_dir() -> PathBuf {
+    resolve(StorageLocation::Index)
+}
+
+pub fn context_dir() -> PathBuf {
+    resolve(StorageLocation::Context)
+}
+
+pub fn output_dir() -> PathBuf {
+    resolve(StorageLocation::Output)
+}
+
+/// Every location with the directory in effect
+pub fn locations() -> Vec<StorageLocationInfo> {
+    StorageLocation::all()
+        .into_iter()
+        .map(|location| StorageLocationInfo {
+            location,
+            path: resolve(location).display().to_string(),
+            from_env: env_override(location).is_some(),
+        })
+        .collect()
+}
+
+/// Directories set in the settings UI
+pub fn overrides() -> StoragePaths {
+    PATHS.read().map(|p| p.overrides.clone()).unwrap_or_default()
+}
+
+/// Saves new overrides. Open databases and indexes keep their files until
+/// the app restarts, when [`migrate`] moves existing data over.
+pub fn set_overrides(overrides: StoragePaths) -> Result<(), String> {
+    for location in StorageLocation::all() {
+        if let Some(path) = overrides.get(location) {
+            if !expand(path).is_absolute() {
+                return Err(format!("{} must be an absolute path: {}", location.display_name(), path));
+            }
+        }
+    }
+
+    let mut file = PATHS.write().map_err(|_| "Failed to lock storage paths")?;
+    let updated = PathsFile { overrides, in_use: file.in_use.clone() };
+    save(&updated)?;
+    *file = updated;
+    Ok(())
+}
+
+/// The project folder the old relative paths pointed into
+fn legacy_project_root() -> PathBuf {
+    let mut path = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("."));
+    for _ in 0..10 {
+        if path.join("Cargo.toml").exists() {
+            return path;
+        }
+        match path.parent() {
+            Some(parent) => path = parent.to_path_buf(),
+            None => break,
+        }
+    }
+    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+/// Where a location lived before configurable paths existed
+fn legacy_location(location: StorageLocation) -> PathBuf {
+    match location {
+        StorageLocation::Data => legacy_project_root().join("data"),
+        StorageLocation::Index => legacy_project_root().join("db"),
+        StorageLocation::Context => legacy_project_root().join("context"),
+        StorageLocation::Output => dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".local_ai_assistant"),
+    }
+}
+
+/// Moves data left in previous locations to the current ones. Runs before
+/// anything opens a file, so it uses stderr rather than the log.
+pub fn migrate() {
+    let previous = PATHS.read().ok().and_then(|p| p.in_use.clone());
+    let mut in_use = StoragePaths::default();
+
+    // Data first: the other locations default to folders inside it
+    for location in StorageLocation::all() {
+        let current = resolve(location);
+        let old = match previous.as_ref().and_then(|p| p.get(location)) {
+            Some(path) => PathBuf::from(path),
+            None => legacy_location(location),
+        };
+        if let Err(e) = move_dir(&old, &current) {
+            eprintln!("Could not move {} from {:?} to {:?}: {}", location.display_name(), old, current, e);
+        }
+        in_use.set(location, Some(current.display().to_string()));
+    }
+
+    if let Ok(mut file) = PATHS.write() {
+        file.in_use = Some(in_use);
+        if let Err(e) = save(&file) {
+            eprintln!("Could not save {:?}: {}", paths_file(), e);
+        }
+    }
+}
+
+/// Moves `from` to `to` unless there is nothing to move or `to` already has
+/// data. Falls back to copying (keeping the original) across file systems.
+fn move_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    if from == to || !from.is_dir() || is_non_empty_dir(to) || to.starts_with(from) {
+        return Ok(());
+    }
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if to.is_dir() {
+        std::fs::remove_dir(to)?;
+    }
+
+    if std::fs::rename(from, to).is_ok() {
+        eprintln!("Moved {:?} to {:?}", from, to);
+        return Ok(());
+    }
+    copy_dir(from, to)?;
+    eprintln!("Copied {:?} to {:?}; the old folder can be deleted", from, to);
+    Ok(())
+}
+
+fn is_non_empty_dir(path: &Path) -> bool {
+    std::fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_some())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_dir_keeps_existing_data() {
+        let root = std::env::temp_dir().join(format!("idoris-paths-{}", uuid::Uuid::new_v4()));
+        let old = root.join("old");
+        let new = root.join("nested").join("new");
+        std::fs::create_dir_all(old.join("logs")).unwrap();
+        std::fs::write(old.join("logs").join("a.log"), "a").unwrap();
+
+        move_dir(&old, &new).unwrap();
+        assert!(!old.exists());
+        assert_eq!(std::fs::read_to_string(new.join("logs").join("a.log")).unwrap(), "a");
+
+        // A second source never overwrites data already at the target
+        std::fs::create_dir_all(&old).unwrap();
+        std::fs::write(old.join("b.txt"), "b").unwrap();
+        move_dir(&old, &new).unwrap();
+        assert!(old.join("b.txt").exists());
+        assert!(!new.join("b.txt").exists());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand("~/notes"), home.join("notes"));
+        assert_eq!(expand("/srv/idoris"), PathBuf::from("/srv/idoris"));
+        assert_eq!(expand("~other/x"), PathBuf::from("~other/x"));
+    }
+}
@@ -0,0 +1,232 @@
+//! User Profiles
+//!
+//! Lightweight, password-less workspaces. The profile list and the active
+//! profile are persisted to `./data/profiles.json`. Every profile other than
+//! the default one keeps its files under `./data/profiles/<id>/`; the default
+//! profile uses the original locations so existing installs keep their data.
+//!
+//! Per-profile scoping:
+//! - sessions: `sessions.profile_id` column in the SQLite database
+//! - knowledge base: the profile's context folder, re-indexed on switch
+//! - settings: `settings.json` in the profile directory
+//! - generated assets: `profiles/<id>/` in the output directory
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{AppSettings, Profile, DEFAULT_PROFILE_ID};
+
+/// Persisted profile registry
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ProfileRegistry {
+    active: String,
+    profiles: Vec<Profile>,
+}
+
+impl Default for ProfileRegistry {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE_ID.to_string(),
+            profiles: vec![Profile::default_profile()],
+        }
+    }
+}
+
+static REGISTRY: Lazy<Mutex<ProfileRegistry>> = Lazy::new(|| Mutex::new(load_registry()));
+
+/// Path of the registry file
+fn registry_path() -> PathBuf {
+    crate::storage::database::get_data_dir().join("profiles.json")
+}
+
+/// Reads the registry, always making sure the default profile exists
+fn load_registry() -> ProfileRegistry {
+    let mut registry: ProfileRegistry = std::fs::read_to_string(registry_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    if !registry.profiles.iter().any(|p| p.is_default()) {
+        registry.profiles.insert(0, Profile::default_profile());
+    }
+    if !registry.profiles.iter().any(|p| p.id == registry.active) {
+        registry.active = DEFAULT_PROFILE_ID.to_string();
+    }
+    registry
+}
+
+fn save_registry(registry: &ProfileRegistry) -> Result<(), String> {
+    let path = registry_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(registry).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// Id of the active profile
+pub fn active_profile_id() -> String {
+    REGISTRY.lock().map(|r| r.active.clone()).unwrap_or_else(|_| DEFAULT_PROFILE_ID.to_string())
+}
+
+/// The active profile
+pub fn active_profile() -> Profile {
+    REGISTRY.lock().ok()
+        .and_then(|registry| registry.profiles.iter().find(|p| p.id == registry.active).cloned())
+        .unwrap_or_else(Profile::default_profile)
+}
+
+/// All profiles, default first
+pub fn list_profiles() -> Vec<Profile> {
+    REGISTRY.lock().map(|r| r.profiles.clone()).unwrap_or_default()
+}
+
+/// Creates a profile from a display name
+pub fn create_profile(name: &str) -> Result<Profile, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+
+    let mut registry = REGISTRY.lock().map_err(|e| e.to_string())?;
+    if registry.profiles.iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+        return Err(format!("A profile named '{}' already exists", name));
+    }
+
+    let base = profile_id_from_name(name);
+    let mut id = base.clone();
+    let mut n = 2;
+    while registry.profiles.iter().any(|p| p.id == id) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+
+    let profile = Profile {
+        id,
+        name: name.to_string(),
+        created_at: chrono::Utc::now(),
+    };
+    std::fs::create_dir_all(context_folder_for(&profile.id)).map_err(|e| e.to_string())?;
+
+    registry.profiles.push(profile.clone());
+    save_registry(&registry)?;
+    tracing::info!("Created profile '{}' ({})", profile.name, profile.id);
+    Ok(profile)
+}
+
+/// Makes a profile active. Returns false if it already was.
+pub fn set_active_profile(id: &str) -> Result<bool, String> {
+    let mut registry = REGISTRY.lock().map_err(|e| e.to_string())?;
+    if !registry.profiles.iter().any(|p| p.id == id) {
+        return Err(format!("Unknown profile: {}", id));
+    }
+    if registry.active == id {
+        return Ok(false);
+    }
+
+    registry.active = id.to_string();
+    save_registry(&registry)?;
+    tracing::info!("Switched to profile {}", id);
+    Ok(true)
+}
+
+/// Directory-safe id derived from a display name
+fn profile_id_from_name(name: &str) -> String {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() || slug == DEFAULT_PROFILE_ID {
+        format!("profile-{}", &uuid::Uuid::new_v4().simple().to_string()[..8])
+    } else {
+        slug
+    }
+}
+
+/// Data directory of a profile
+fn profile_dir(id: &str) -> PathBuf {
+    let data_dir = crate::storage::database::get_data_dir();
+    if id == DEFAULT_PROFILE_ID {
+        data_dir
+    } else {
+        data_dir.join("profiles").join(id)
+    }
+}
+
+fn context_folder_for(id: &str) -> PathBuf {
+    profile_dir(id).join("context")
+}
+
+/// Context folder of the active profile, or None for the default profile
+/// (which keeps using the vector store's original folder)
+pub fn context_folder() -> Option<PathBuf> {
+    let id = active_profile_id();
+    (id != DEFAULT_PROFILE_ID).then(|| context_folder_for(&id))
+}
+
+/// Directory for generated assets of the given kind (e.g. "images")
+pub fn asset_dir(kind: &str) -> PathBuf {
+    let base = super::paths::output_dir();
+    let id = active_profile_id();
+    let dir = if id == DEFAULT_PROFILE_ID {
+        base.join(kind)
+    } else {
+        base.join("profiles").join(id).join(kind)
+    };
+    std::fs::create_dir_all(&dir).ok();
+    dir
+}
+
+fn settings_path() -> PathBuf {
+    profile_dir(&active_profile_id()).join("settings.json")
+}
+
+/// Settings of the active profile
+pub fn load_settings() -> AppSettings {
+    std::fs::read_to_string(settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Saves settings for the active profile
+pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
+    let path = settings_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_id_from_name() {
+        assert_eq!(profile_id_from_name("Alice"), "alice");
+        assert_eq!(profile_id_from_name("  Bob's Work  "), "bob-s-work");
+        assert_eq!(profile_id_from_name("小明"), "小明");
+    }
+
+    #[test]
+    fn test_profile_id_never_collides_with_default() {
+        assert!(profile_id_from_name("Default").starts_with("profile-"));
+        assert!(profile_id_from_name("../..").starts_with("profile-"));
+    }
+
+    #[test]
+    fn test_default_profile_uses_original_locations() {
+        assert_eq!(profile_dir(DEFAULT_PROFILE_ID), crate::storage::database::get_data_dir());
+        assert!(profile_dir("alice").ends_with("profiles/alice"));
+    }
+}
@@ -0,0 +1,98 @@
+//! Proofreader
+//!
+//! Local grammar and style pass over draft sections. The model reports typos,
+//! grammar, passive voice and style issues as JSON; overly long sentences are
+//! found without the model. Issues whose excerpt is not in the text are
+//! dropped, so every remaining issue can be highlighted and fixed in place.
+
+use serde_json::Value;
+
+use crate::models::{find_long_sentences, IssueKind, ProofreadIssue, SectionReview};
+use super::llm;
+
+/// Maximum tokens for the issue list
+const MAX_LENGTH: u32 = 700;
+/// Issues reported by the model beyond this are ignored
+const MAX_MODEL_ISSUES: usize = 20;
+
+/// Proofreads a section and scores it
+pub async fn proofread(text: &str) -> Result<SectionReview, String> {
+    if text.trim().is_empty() {
+        return Err("Section is empty".to_string());
+    }
+
+    let prompt = format!(
+        r#"Proofread the text below. Find typos, grammar mistakes, passive voice and awkward style.
+
+Reply with only a JSON array, one object per issue:
+[{{"kind": "typo" | "grammar" | "passive_voice" | "style", "excerpt": "<exact text from the input>", "suggestion": "<replacement text>", "explanation": "<short reason>"}}]
+
+Keep each excerpt short and copied exactly. Reply with [] if there are no issues.
+
+Text:
+{}"#,
+        text
+    );
+    let response = llm::get_oneshot_response(prompt, MAX_LENGTH).await?;
+
+    let mut issues = parse_issues(&response, text);
+    issues.extend(find_long_sentences(text));
+    tracing::info!("Proofreading found {} issues", issues.len());
+
+    Ok(SectionReview::new(issues, text))
+}
+
+/// Parses the model's JSON array, keeping issues that point at real text
+fn parse_issues(response: &str, text: &str) -> Vec<ProofreadIssue> {
+    let (Some(start), Some(end)) = (response.find('['), response.rfind(']')) else {
+        return Vec::new();
+    };
+    let Ok(Value::Array(items)) = serde_json::from_str::<Value>(&response[start..=end.max(start)]) else {
+        return Vec::new();
+    };
+
+    items.into_iter()
+        .filter_map(|item| {
+            let field = |name: &str| item.get(name).and_then(Value::as_str).unwrap_or_default().trim().to_string();
+            let kind = match field("kind").as_str() {
+                "typo" | "spelling" => IssueKind::Typo,
+                "grammar" => IssueKind::Grammar,
+                "passive_voice" | "passive" => IssueKind::PassiveVoice,
+                _ => IssueKind::Style,
+            };
+            let issue = ProofreadIssue {
+                kind,
+                excerpt: field("excerpt"),
+                suggestion: field("suggestion"),
+                explanation: field("explanation"),
+            };
+            (!issue.excerpt.is_empty() && text.contains(&issue.excerpt) && issue.excerpt != issue.suggestion).then_some(issue)
+        })
+        .take(MAX_MODEL_ISSUES)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_issues_drops_invented_excerpts() {
+        let text = "I recieve the report. It was written by the team.";
+        let response = r#"Here you go:
+[{"kind": "typo", "excerpt": "recieve", "suggestion": "receive", "explanation": "Misspelling"},
+ {"kind": "passive_voice", "excerpt": "was written by the team", "suggestion": "the team wrote it"},
+ {"kind": "grammar", "excerpt": "not in the text", "suggestion": "x"}]"#;
+
+        let issues = parse_issues(response, text);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].kind, IssueKind::Typo);
+        assert_eq!(issues[1].kind, IssueKind::PassiveVoice);
+        assert!(issues[1].explanation.is_empty());
+    }
+
+    #[test]
+    fn test_parse_issues_without_json() {
+        assert!(parse_issues("No issues found.", "text").is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+//! Scheduled Publishing
+//!
+//! Publishes content calendar entries when their date comes. A scheduler
+//! started with the server checks once a minute for scheduled entries that
+//! are due and posts their content package to WordPress through its REST
+//! API with an application password. Each attempt runs as a background
+//! job; the result is stored on the entry, announced as a notification and
+//! sent to `job.completed` webhooks. The WordPress connection is persisted
+//! to `./data/wordpress.json`.
+//!
+//! Only the article text is published; images stay in the package.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::models::{JobKind, NetworkFeature, Publication, PublishPlatform, WebhookEvent, WordPressConfig};
+use crate::storage::database;
+use super::jobs::JobSpec;
+
+/// How often the scheduler looks for due entries
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Per-request timeout for publishing
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(30);
+
+static SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
+
+fn config_path() -> PathBuf {
+    database::get_data_dir().join("wordpress.json")
+}
+
+/// WordPress connection, empty if none was saved
+pub fn load_config() -> WordPressConfig {
+    std::fs::read_to_string(config_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_config(config: &WordPressConfig) -> Result<(), String> {
+    let site_url = config.site_url.trim();
+    if !site_url.is_empty() && !site_url.starts_with("http://") && !site_url.starts_with("https://") {
+        return Err("Site URL must start with http:// or https://".to_string());
+    }
+
+    let path = config_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// Starts checking for due entries in the background. Call once at startup.
+pub fn start_scheduler() {
+    if SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tokio::spawn(async {
+        loop {
+            if let Err(e) = publish_due().await {
+                tracing::warn!("Publishing scheduler check failed: {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Publishes every scheduled entry whose date has passed
+async fn publish_due() -> Result<(), String> {
+    if !database::is_initialized() {
+        database::init().await.map_err(|e| e.to_string())?;
+    }
+
+    let now = chrono::Utc::now();
+    let due: Vec<Publication> = database::get_scheduled_publications()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|p| p.is_due(now))
+        .collect();
+    for publication in due {
+        let _ = publish(publication).await;
+    }
+    Ok(())
+}
+
+/// Publishes an entry now and records the result on it. Returns the URL
+/// of the published post.
+pub async fn publish(publication: Publication) -> Result<String, String> {
+    let id = publication.id;
+    let title = publication.title.clone();
+    let platform = publication.platform;
+    let result = JobSpec::new(JobKind::Publishing, format!("Publish \"{}\"", title))
+        .run(move |job| async move {
+            job.log(format!("Publishing to {}", platform.display_name()));
+            let url = match platform {
+                PublishPlatform::WordPress => post_to_wordpress(&publication).await?,
+                other => return Err(format!("{} posts have to be published by hand", other.display_name())),
+            };
+            job.log(format!("Published at {}", url));
+            Ok(url)
+        })
+        .await;
+
+    if let Err(e) = database::set_publication_result(id, &result).await {
+        tracing::warn!("Failed to record publishing result for {}: {}", id, e);
+    }
+    match &result {
+        Ok(url) => {
+            super::notifications::success("Post published", format!("\"{}\" is live at {}", title, url));
+            super::webhooks::fire(WebhookEvent::ScheduledJobCompleted, serde_json::json!({
+                "kind": "publication",
+                "id": id.to_string(),
+                "title": title,
+                "platform": platform.id(),
+                "url": url,
+            }));
+        }
+        Err(e) => super::notifications::error("Publishing failed", format!("\"{}\": {}", title, e)),
+    }
+    result
+}
+
+/// Creates a published post from the entry's content package and returns
+/// its address
+async fn post_to_wordpress(publication: &Publication) -> Result<String, String> {
+    let config = load_config();
+    if !config.is_configured() {
+        return Err("WordPress is not connected; add the site in the content calendar".to_string());
+    }
+    let job_id = publication.job_id
+        .ok_or("Pick the content package to publish")?;
+    let package = super::content_generator::get_job(job_id)
+        .ok_or("The content package no longer exists")?
+        .package;
+    if package.sections.is_empty() {
+        return Err("The content package has no drafted sections yet".to_string());
+    }
+    super::seo::check(&package)?;
+
+    let seo = package.seo.clone().unwrap_or_default();
+    let title = if seo.title.trim().is_empty() { &publication.title } else { &seo.title };
+    let mut post = serde_json::json!({
+        "title": title,
+        "content": package.sections_html(),
+        "status": "publish",
+        "excerpt": seo.meta_description,
+    });
+    if !seo.slug.trim().is_empty() {
+        post["slug"] = serde_json::Value::String(seo.slug.clone());
+    }
+
+    let request = super::http::client()
+        .post(config.posts_endpoint())
+        .basic_auth(config.username.trim(), Some(config.app_password.trim()))
+        .timeout(PUBLISH_TIMEOUT)
+        .json(&post);
+    let response = super::http::send(NetworkFeature::Publishing, request)
+        .await
+        .map_err(|e| format!("WordPress request failed: {}", e))?;
+
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    if !status.is_success() {
+        let message = body["message"].as_str().unwrap_or("no details");
+        return Err(format!("WordPress returned {}: {}", status, message));
+    }
+    body["link"].as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "WordPress did not return the post's address".to_string())
+}
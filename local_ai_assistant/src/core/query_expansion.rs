@@ -0,0 +1,37 @@
+//! Query Expansion
+//!
+//! Short follow-up questions ("and its price?") embed poorly. Before
+//! retrieval the recent conversation and the question are condensed into a
+//! standalone search query by the routing model, or, with expansion on, the
+//! model rewrites the question or writes a hypothetical answer to embed
+//! instead (HyDE), which sits closer to the wording of the documents that
+//! answer it.
+
+use crate::models::{clean_search_query, condense_prompt, QueryExpansion};
+use super::llm;
+
+/// Most tokens of a rewritten query or hypothetical answer
+const EXPANSION_TOKENS: u32 = 160;
+
+/// The text to embed for `question`. `history` holds earlier messages as
+/// "role: text" and is ignored unless `conversational` is set. Returns the
+/// question itself when there is nothing to do or the model fails.
+pub async fn expand(question: &str, history: &[String], expansion: QueryExpansion, conversational: bool) -> String {
+    let history = if conversational { history } else { &[] };
+    let (prompt, reply_kind) = match expansion.prompt(question, history) {
+        Some(prompt) => (prompt, expansion),
+        None if !history.is_empty() => (condense_prompt(question, history), QueryExpansion::Rewrite),
+        None => return question.to_string(),
+    };
+    match llm::get_routing_response(prompt, EXPANSION_TOKENS).await {
+        Ok(reply) => {
+            let query = clean_search_query(&reply, reply_kind, question);
+            tracing::info!("Retrieval query ({}): {}", expansion.display_name(), query);
+            query
+        }
+        Err(e) => {
+            tracing::warn!("Query expansion failed, searching for the question as asked: {}", e);
+            question.to_string()
+        }
+    }
+}
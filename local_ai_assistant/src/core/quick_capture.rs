@@ -0,0 +1,160 @@
+//! Quick Capture
+//!
+//! Files a piece of text or a URL into the knowledge base in one step: URLs
+//! are fetched and reduced to their readable article, a title is extracted,
+//! and the result is written to the active profile's context folder (or a
+//! collection subfolder) and added to the live index.
+
+use std::path::PathBuf;
+
+use crate::models::CapturedDocument;
+use super::vector_store;
+
+/// Longest title derived from plain text
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Collections are subfolders of the context folder
+pub fn list_collections() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(vector_store::get_context_folder()) else {
+        return Vec::new();
+    };
+
+    let mut collections: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    collections.sort();
+    collections
+}
+
+/// Captures clipboard text or a shared URL into the knowledge base
+pub async fn capture(input: &str, collection: Option<&str>) -> Result<CapturedDocument, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Nothing to capture".to_string());
+    }
+
+    let (title, body) = if is_url(input) {
+        let article = super::content_source::extract_article(input).await?;
+        let title = if article.title.trim().is_empty() { extract_title(&article.content) } else { article.title.trim().to_string() };
+        (title, format!("{}\n\nSource: {}", article.content.trim(), input))
+    } else {
+        (extract_title(input), input.to_string())
+    };
+
+    let dir = collection_dir(collection)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let filename = unique_filename(&dir, &title);
+    // The vector store uses the first line as the document title
+    let content = format!("# {}\n\n{}\n", title, body);
+    std::fs::write(dir.join(&filename), &content).map_err(|e| e.to_string())?;
+    tracing::info!("Captured '{}' into {:?}", title, dir.join(&filename));
+
+    let indexed = match vector_store::index_document(&format!("# {}", title), &content).await {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!("Captured document saved but not indexed yet: {}", e);
+            false
+        }
+    };
+
+    Ok(CapturedDocument {
+        title,
+        filename,
+        collection: collection.map(str::to_string).filter(|c| !c.is_empty()),
+        indexed,
+    })
+}
+
+/// Folder for a collection, or the context folder itself for none
+pub fn collection_dir(collection: Option<&str>) -> Result<PathBuf, String> {
+    let root = vector_store::get_context_folder();
+    match collection.map(str::trim).filter(|c| !c.is_empty()) {
+        None => Ok(root),
+        Some(name) if name.contains(['/', '\\']) || name.starts_with('.') => {
+            Err(format!("Invalid collection name: {}", name))
+        }
+        Some(name) => Ok(root.join(name)),
+    }
+}
+
+fn is_url(input: &str) -> bool {
+    (input.starts_with("http://") || input.starts_with("https://")) && !input.contains(char::is_whitespace)
+}
+
+/// First markdown heading, or the first non-empty line, shortened
+fn extract_title(text: &str) -> String {
+    let line = text.lines()
+        .map(str::trim)
+        .find(|l| l.starts_with('#'))
+        .or_else(|| text.lines().map(str::trim).find(|l| !l.is_empty()))
+        .unwrap_or("Untitled");
+    let line = line.trim_start_matches('#').trim();
+
+    if line.chars().count() <= MAX_TITLE_CHARS {
+        return if line.is_empty() { "Untitled".to_string() } else { line.to_string() };
+    }
+
+    let cut: String = line.chars().take(MAX_TITLE_CHARS).collect();
+    // Prefer breaking at a word boundary
+    match cut.rfind(' ') {
+        Some(pos) if pos > MAX_TITLE_CHARS / 2 => format!("{}…", &cut[..pos]),
+        _ => format!("{}…", cut),
+    }
+}
+
+/// File name derived from the title that does not overwrite existing files
+pub(crate) fn unique_filename(dir: &std::path::Path, title: &str) -> String {
+    let stem: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .take(60)
+        .collect();
+    let stem = stem.trim_matches('_');
+    let stem = if stem.is_empty() { "capture" } else { stem };
+
+    let mut filename = format!("{}.md", stem);
+    let mut n = 2;
+    while dir.join(&filename).exists() {
+        filename = format!("{}_{}.md", stem, n);
+        n += 1;
+    }
+    filename
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_title_prefers_heading() {
+        assert_eq!(extract_title("intro text\n# Real Title\nbody"), "Real Title");
+        assert_eq!(extract_title("\n\n  First line  \nsecond"), "First line");
+        assert_eq!(extract_title(""), "Untitled");
+    }
+
+    #[test]
+    fn test_extract_title_truncates_long_lines() {
+        let long = "word ".repeat(40);
+        let title = extract_title(&long);
+        assert!(title.ends_with('…'));
+        assert!(title.chars().count() <= MAX_TITLE_CHARS + 1);
+    }
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("https://example.com/post"));
+        assert!(!is_url("see https://example.com for details"));
+        assert!(!is_url("example.com"));
+    }
+
+    #[test]
+    fn test_collection_dir_rejects_traversal() {
+        assert!(collection_dir(Some("../secrets")).is_err());
+        assert!(collection_dir(Some(".hidden")).is_err());
+        assert!(collection_dir(Some("reading")).unwrap().ends_with("reading"));
+    }
+}
@@ -0,0 +1,63 @@
+//! Readability Rewriting
+//!
+//! Rewrites a draft section towards a target Flesch-Kincaid grade level.
+//! Each pass asks the model to simplify or elevate the prose depending on
+//! where the current grade lies, then measures the result again; passes
+//! continue until the grade is within a level of the target or the attempts
+//! run out, keeping the closest version.
+
+use crate::models::{grade_level, ReadabilityRewrite};
+use super::llm;
+
+/// Maximum tokens for a rewritten section
+const MAX_LENGTH: u32 = 1200;
+/// Rewrites tried before settling for the closest one
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Rewrites the text until it reads at the target grade, returning the
+/// closest version; text already on target is returned unchanged
+pub async fn rewrite_to_grade(text: &str, target: f32) -> Result<ReadabilityRewrite, String> {
+    let Some(grade_before) = grade_level(text) else {
+        return Err("The section is too short, or not in a language grade levels can be measured for".to_string());
+    };
+
+    let mut best = ReadabilityRewrite {
+        original: text.to_string(),
+        text: text.to_string(),
+        target,
+        grade_before: Some(grade_before),
+        grade_after: Some(grade_before),
+        attempts: 0,
+    };
+    let mut current = (text.to_string(), grade_before);
+    while best.attempts < MAX_ATTEMPTS && !best.reached_target() {
+        best.attempts += 1;
+        let (draft, grade) = &current;
+        let direction = if *grade > target {
+            "Simplify it: use shorter sentences and plainer, shorter words, and explain jargon."
+        } else {
+            "Elevate it: use richer vocabulary, more precise terms and longer, more complex sentences."
+        };
+        let prompt = format!(
+            "Rewrite the text below for a reading grade level of about {:.0} (it currently reads at {:.1}). {} \
+Keep every fact, the Markdown formatting and any image lines unchanged. Only output the rewritten text.\n\n{}",
+            target, grade, direction, draft
+        );
+        let rewritten = llm::get_oneshot_response(prompt, MAX_LENGTH).await?.trim().to_string();
+        let Some(new_grade) = grade_level(&rewritten).filter(|_| !rewritten.is_empty()) else {
+            continue;
+        };
+        tracing::debug!("Readability pass {}: grade {:.1} -> {:.1} (target {})", best.attempts, grade, new_grade, target);
+
+        if (new_grade - target).abs() < best.grade_after.map_or(f32::MAX, |g| (g - target).abs()) {
+            best.text = rewritten.clone();
+            best.grade_after = Some(new_grade);
+        }
+        current = (rewritten, new_grade);
+    }
+
+    if !best.reached_target() {
+        tracing::info!("Readability rewrite did not reach grade {} in {} attempts", target, best.attempts);
+    }
+    Ok(best)
+}
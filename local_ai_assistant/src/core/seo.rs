@@ -0,0 +1,318 @@
+//! SEO Metadata
+//!
+//! Generates a content package's search metadata (title, description,
+//! keywords and slug) with the LLM and fills in the saved defaults for the
+//! author, site and Twitter account, from which the exported page's Open
+//! Graph, Twitter Card and schema.org Article tags are built. Packages are
+//! checked for the fields those tags need before they are published. The
+//! defaults are persisted to `./data/seo.json`.
+//!
+//! Keyword research takes frequent terms from knowledge base passages about
+//! the topic and keywords the LLM brainstorms, embeds them locally, groups
+//! related ones, and scores each against the draft's sections.
+
+use std::path::PathBuf;
+
+use kalosm::language::{Parse, Schema};
+
+use crate::models::{
+    ContentPackage, KeywordCluster, KeywordReport, KeywordSource, KeywordSuggestion, SeoDefaults, SeoMetadata,
+    candidate_terms, parse_keyword_lines,
+};
+use crate::storage::database;
+use super::{embedding, llm, vector_store};
+
+/// Token budget for generating the metadata
+const MAX_LENGTH: u32 = 300;
+/// Knowledge base passages read for keyword research
+const CORPUS_PASSAGES: usize = 12;
+/// Terms taken from the knowledge base passages
+const CORPUS_TERMS: usize = 15;
+/// Similarity at which keywords join the same cluster
+const CLUSTER_SIMILARITY: f32 = 0.75;
+/// Uncovered clusters a section is suggested for
+const MAX_SECTION_SUGGESTIONS: usize = 5;
+
+fn config_path() -> PathBuf {
+    database::get_data_dir().join("seo.json")
+}
+
+/// Saved defaults, empty if none were saved
+pub fn load_defaults() -> SeoDefaults {
+    std::fs::read_to_string(config_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_defaults(defaults: &SeoDefaults) -> Result<(), String> {
+    let site_url = defaults.site_url.trim();
+    if !site_url.is_empty() && !site_url.starts_with("http://") && !site_url.starts_with("https://") {
+        return Err("Site URL must start with http:// or https://".to_string());
+    }
+
+    let path = config_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(defaults).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// SEO metadata as generated with constrained decoding
+#[derive(Parse, Schema, Clone, Debug)]
+struct StructuredSeo {
+    /// Under 60 characters
+    title: String,
+    /// Under 155 characters
+    description: String,
+    /// 5-8 keywords
+    keywords: Vec<String>,
+}
+
+/// Metadata for the drafted article, with the saved defaults filled in
+pub async fn generate(package: &ContentPackage) -> Result<SeoMetadata, String> {
+    let article: String = package.sections.iter()
+        .map(|(title, text)| format!("## {}\n{}", title, text))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+        .chars()
+        .take(2000)
+        .collect();
+    let request = format!("Write SEO metadata for this article about \"{}\".\n\n{}", package.topic, article);
+
+    let mut seo = match llm::generate_structured::<StructuredSeo>(request.clone(), None, MAX_LENGTH).await {
+        Ok(seo) => seo_metadata(seo, &package.topic),
+        Err(e) => {
+            tracing::warn!("Falling back to text SEO metadata: {}", e);
+            let prompt = format!(
+                "{}\n\nReply with exactly these lines:\nTitle: <under 60 characters>\nDescription: <under 155 characters>\nKeywords: <5-8 comma separated keywords>",
+                request
+            );
+            parse_seo(&llm::get_oneshot_response(prompt, MAX_LENGTH).await?, &package.topic)
+        }
+    };
+    // Keep what the user entered when the stage runs again
+    if let Some(previous) = &package.seo {
+        seo.author = previous.author.clone();
+        seo.canonical_url = previous.canonical_url.clone();
+        seo.site_name = previous.site_name.clone();
+        seo.twitter_handle = previous.twitter_handle.clone();
+    }
+    load_defaults().apply(&mut seo);
+    seo.published_at = Some(chrono::Utc::now());
+    Ok(seo)
+}
+
+/// Fails with the missing fields if the package's metadata is incomplete
+pub fn check(package: &ContentPackage) -> Result<(), String> {
+    let Some(seo) = &package.seo else {
+        return Err("The package has no SEO metadata yet; run its SEO stage first".to_string());
+    };
+    let missing = seo.missing_fields();
+    if !missing.is_empty() {
+        return Err(format!("SEO metadata is missing: {}", missing.join(", ")));
+    }
+    Ok(())
+}
+
+/// Keyword clusters for the topic, scored by how well the draft covers
+/// them, with a section suggested for each cluster it misses
+pub async fn suggest_keywords(package: &ContentPackage) -> Result<KeywordReport, String> {
+    if package.sections.is_empty() {
+        return Err("Draft the article first; keywords are scored against it".to_string());
+    }
+
+    let passages: Vec<String> = if vector_store::is_initialized() {
+        vector_store::search_raw(&package.topic, CORPUS_PASSAGES)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Keyword research could not search the knowledge base: {}", e);
+                Vec::new()
+            })
+            .into_iter()
+            .map(|chunk| chunk.text)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut terms: Vec<(String, KeywordSource)> = candidate_terms(&passages, CORPUS_TERMS)
+        .into_iter()
+        .map(|term| (term, KeywordSource::Corpus))
+        .collect();
+
+    let prompt = format!(
+        "List 10-15 search keywords and short phrases people use when looking for articles about \"{}\". \
+One per line, without numbering or explanations.",
+        package.topic
+    );
+    for keyword in parse_keyword_lines(&llm::get_oneshot_response(prompt, MAX_LENGTH).await?) {
+        match terms.iter_mut().find(|(term, _)| *term == keyword) {
+            Some((_, source)) => *source = KeywordSource::Both,
+            None => terms.push((keyword, KeywordSource::Brainstorm)),
+        }
+    }
+    if terms.is_empty() {
+        return Err("No keywords found for the topic".to_string());
+    }
+
+    let term_texts: Vec<String> = terms.iter().map(|(term, _)| term.clone()).collect();
+    let term_vectors = embedding::embed_batch(&term_texts).await?;
+    let section_texts: Vec<String> = package.sections.iter()
+        .map(|(title, text)| format!("{}\n{}", title, text))
+        .collect();
+    let section_vectors = embedding::embed_batch(&section_texts).await?;
+    let article = section_texts.join("\n").to_lowercase();
+
+    let assignments = super::session_tags::cluster(&term_vectors, CLUSTER_SIMILARITY);
+    let mut clusters: Vec<KeywordCluster> = Vec::new();
+    for (((term, source), vector), index) in terms.into_iter().zip(&term_vectors).zip(assignments) {
+        let coverage = section_vectors.iter()
+            .map(|section| super::memory::cosine_similarity(vector, section))
+            .fold(0.0, f32::max);
+        let keyword = KeywordSuggestion { mentioned: article.contains(&term), term, source, coverage };
+        if index == clusters.len() {
+            clusters.push(KeywordCluster { label: keyword.term.clone(), keywords: Vec::new(), suggested_section: None });
+        }
+        let cluster = &mut clusters[index];
+        if keyword.term.chars().count() < cluster.label.chars().count() {
+            cluster.label = keyword.term.clone();
+        }
+        cluster.keywords.push(keyword);
+    }
+    clusters.sort_by(|a, b| a.is_covered().cmp(&b.is_covered()).then(b.keywords.len().cmp(&a.keywords.len())));
+
+    suggest_sections(package, &mut clusters).await;
+    tracing::info!("Keyword research for \"{}\": {} clusters from {} passages", package.topic, clusters.len(), passages.len());
+    Ok(KeywordReport { topic: package.topic.clone(), clusters, passages_read: passages.len() })
+}
+
+/// Asks for one section title per uncovered cluster; clusters keep no
+/// suggestion if the request fails
+async fn suggest_sections(package: &ContentPackage, clusters: &mut [KeywordCluster]) {
+    let uncovered: Vec<&mut KeywordCluster> = clusters.iter_mut()
+        .filter(|c| !c.is_covered())
+        .take(MAX_SECTION_SUGGESTIONS)
+        .collect();
+    if uncovered.is_empty() {
+        return;
+    }
+
+    let sections = package.sections.iter().map(|(title, _)| format!("- {}", title)).collect::<Vec<_>>().join("\n");
+    let groups = uncovered.iter()
+        .enumerate()
+        .map(|(i, c)| format!("{}. {}", i + 1, c.keywords.iter().map(|k| k.term.as_str()).collect::<Vec<_>>().join(", ")))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let prompt = format!(
+        "An article about \"{}\" has these sections:\n{}\n\nIt does not cover these keyword groups:\n{}\n\n\
+Suggest one new section title for each group. Reply with one numbered line per group, like \"1. Title\".",
+        package.topic, sections, groups
+    );
+    let reply = match llm::get_oneshot_response(prompt, MAX_LENGTH).await {
+        Ok(reply) => reply,
+        Err(e) => {
+            tracing::warn!("Could not suggest sections for uncovered keywords: {}", e);
+            return;
+        }
+    };
+    let titles: Vec<String> = reply.lines()
+        .filter_map(|line| line.trim().split_once(". "))
+        .filter(|(n, _)| n.chars().all(|c| c.is_ascii_digit()))
+        .map(|(_, title)| title.trim().trim_matches(['"', '*']).trim().to_string())
+        .filter(|title| !title.is_empty())
+        .collect();
+    for (cluster, title) in uncovered.into_iter().zip(titles) {
+        cluster.suggested_section = Some(title);
+    }
+}
+
+fn seo_metadata(seo: StructuredSeo, topic: &str) -> SeoMetadata {
+    let title = Some(seo.title.trim().to_string()).filter(|t| !t.is_empty()).unwrap_or_else(|| topic.to_string());
+    SeoMetadata {
+        slug: slugify(&title),
+        meta_description: seo.description.trim().to_string(),
+        keywords: seo.keywords.iter().map(|k| k.trim().to_string()).filter(|k| !k.is_empty()).collect(),
+        title,
+        ..Default::default()
+    }
+}
+
+fn parse_seo(response: &str, topic: &str) -> SeoMetadata {
+    let field = |name: &str| {
+        response.lines()
+            .find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                key.trim().eq_ignore_ascii_case(name).then(|| value.trim().trim_matches('"').to_string())
+            })
+            .unwrap_or_default()
+    };
+
+    let title = Some(field("title")).filter(|t| !t.is_empty()).unwrap_or_else(|| topic.to_string());
+    SeoMetadata {
+        slug: slugify(&title),
+        meta_description: field("description"),
+        keywords: field("keywords")
+            .split([',', '，'])
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .collect(),
+        title,
+        ..Default::default()
+    }
+}
+
+fn slugify(text: &str) -> String {
+    let slug: String = text
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_seo() {
+        let seo = parse_seo("Title: Rust for Web Apps\nDescription: \"Build fast apps.\"\nKeywords: rust, dioxus,  wasm", "Rust");
+        assert_eq!(seo.title, "Rust for Web Apps");
+        assert_eq!(seo.slug, "rust-for-web-apps");
+        assert_eq!(seo.meta_description, "Build fast apps.");
+        assert_eq!(seo.keywords, vec!["rust", "dioxus", "wasm"]);
+    }
+
+    #[test]
+    fn test_structured_seo_metadata() {
+        let seo = seo_metadata(StructuredSeo {
+            title: " Rust for Web Apps ".into(),
+            description: "Build fast apps.".into(),
+            keywords: vec!["rust".into(), " ".into(), " wasm".into()],
+        }, "Rust");
+        assert_eq!(seo.slug, "rust-for-web-apps");
+        assert_eq!(seo.keywords, vec!["rust", "wasm"]);
+        assert_eq!(seo_metadata(StructuredSeo { title: String::new(), description: String::new(), keywords: Vec::new() }, "Local AI").title, "Local AI");
+    }
+
+    #[test]
+    fn test_parse_seo_falls_back_to_topic() {
+        let seo = parse_seo("nothing useful", "Local AI");
+        assert_eq!(seo.title, "Local AI");
+        assert_eq!(seo.slug, "local-ai");
+        assert!(seo.keywords.is_empty());
+    }
+
+    #[test]
+    fn test_check() {
+        let mut package = ContentPackage::default();
+        assert!(check(&package).is_err());
+
+        package.seo = Some(parse_seo("Title: Rust\nDescription: Fast apps.", "Rust"));
+        assert_eq!(check(&package), Err("SEO metadata is missing: author".to_string()));
+
+        package.seo.as_mut().unwrap().author = "Ada".into();
+        assert_eq!(check(&package), Ok(()));
+    }
+}
@@ -0,0 +1,112 @@
+//! Session Summaries
+//!
+//! Condenses a whole conversation into a short summary with key takeaways
+//! and action items, stored with the session. Long conversations are first
+//! condensed part by part, like long attachments.
+
+use kalosm::language::{Parse, Schema};
+use uuid::Uuid;
+
+use crate::models::{split_into_chunks, ChatRole, SessionSummary};
+use crate::storage::database;
+use super::llm;
+
+/// Transcript condensed in one model call
+const TRANSCRIPT_CHUNK_CHARS: usize = 6_000;
+
+/// Transcript parts condensed; older parts of a very long conversation are
+/// left out
+const MAX_TRANSCRIPT_CHUNKS: usize = 16;
+
+/// Token budget for the notes on one part and for the summary
+const CHUNK_NOTES_TOKENS: u32 = 300;
+const SUMMARY_TOKENS: u32 = 700;
+
+/// Summary as generated with constrained decoding
+#[derive(Parse, Schema, Clone, Debug)]
+struct StructuredSummary {
+    /// Two to four sentences
+    summary: String,
+    /// Up to five key takeaways
+    takeaways: Vec<String>,
+    /// Follow-up tasks, empty if there are none
+    action_items: Vec<String>,
+}
+
+/// Summarizes a session and stores the summary with it
+pub async fn summarize(session_id: Uuid) -> Result<SessionSummary, String> {
+    let messages = database::get_session_messages(session_id).await.map_err(|e| e.to_string())?;
+    let transcript = messages
+        .iter()
+        .filter(|m| m.role != ChatRole::System && !m.content.trim().is_empty())
+        .map(|m| format!("{}: {}", m.role, m.content.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if transcript.is_empty() {
+        return Err("The session has no messages to summarize".to_string());
+    }
+
+    let conversation = condense(&transcript).await?;
+    let request = format!(
+        "Summarize this conversation in the language it is written in. Give a short summary, \
+the key takeaways, and any action items the user should follow up on.\n\n{}",
+        conversation
+    );
+
+    let summary = match llm::generate_structured::<StructuredSummary>(request.clone(), None, SUMMARY_TOKENS).await {
+        Ok(structured) => SessionSummary {
+            summary: structured.summary.trim().to_string(),
+            takeaways: clean_items(structured.takeaways),
+            action_items: clean_items(structured.action_items),
+            message_count: messages.len(),
+            created_at: chrono::Utc::now(),
+        },
+        Err(e) => {
+            tracing::warn!("Falling back to a text session summary: {}", e);
+            let prompt = format!(
+                "{}\n\nReply in exactly this format:\nSummary: <two to four sentences>\nTakeaways:\n- <takeaway>\nAction items:\n- <action item, or None>",
+                request
+            );
+            SessionSummary::parse(&llm::get_oneshot_response(prompt, SUMMARY_TOKENS).await?, messages.len())
+        }
+    };
+    if summary.summary.is_empty() {
+        return Err("The model did not return a summary".to_string());
+    }
+
+    database::set_session_summary(session_id, Some(&summary)).await.map_err(|e| e.to_string())?;
+    tracing::info!("Summarized session {} ({} messages)", session_id, messages.len());
+    Ok(summary)
+}
+
+/// The transcript, or notes on each of its parts if it is too long for one
+/// prompt
+async fn condense(transcript: &str) -> Result<String, String> {
+    let mut chunks = split_into_chunks(transcript, TRANSCRIPT_CHUNK_CHARS);
+    if chunks.len() == 1 {
+        return Ok(chunks.remove(0));
+    }
+    // Later turns usually hold the conclusions, so the oldest parts go first
+    let skip = chunks.len().saturating_sub(MAX_TRANSCRIPT_CHUNKS);
+
+    let total = chunks.len() - skip;
+    let mut notes = Vec::with_capacity(total);
+    for (i, chunk) in chunks.iter().skip(skip).enumerate() {
+        tracing::debug!("Condensing conversation part {}/{}", i + 1, total);
+        let prompt = format!(
+            "Write brief notes on part {} of {} of a conversation. Keep decisions, facts, open \
+questions and tasks. Reply with the notes only.\n\n{}",
+            i + 1, total, chunk
+        );
+        notes.push(llm::get_oneshot_response(prompt, CHUNK_NOTES_TOKENS).await?.trim().to_string());
+    }
+    Ok(notes.join("\n\n"))
+}
+
+fn clean_items(items: Vec<String>) -> Vec<String> {
+    items
+        .into_iter()
+        .map(|item| item.trim().trim_start_matches(['-', '*', '•']).trim().to_string())
+        .filter(|item| !item.is_empty() && !item.eq_ignore_ascii_case("none"))
+        .collect()
+}
@@ -0,0 +1,233 @@
+//! Session Tagging
+//!
+//! Background job that gives sessions a few topic tags with the LLM, then
+//! embeds every tagged session and groups similar ones into topic clusters,
+//! so the sidebar can filter hundreds of sessions by tag or topic.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use uuid::Uuid;
+
+use crate::models::{parse_tags, JobKind, Session, MAX_SESSION_TAGS};
+use crate::storage::database;
+use super::jobs::{JobHandle, JobSpec};
+use super::memory::cosine_similarity;
+use super::{embedding, llm};
+
+/// Token budget for a session's tags
+const TAGS_MAX_LENGTH: u32 = 40;
+/// Conversation text the tags are based on
+const TAG_SOURCE_CHARS: usize = 2_000;
+/// Sessions need at least this many messages to be tagged
+const MIN_MESSAGES: usize = 2;
+/// Minimum similarity for a session to join a topic cluster
+const CLUSTER_THRESHOLD: f32 = 0.75;
+
+/// Set while a tagging job runs, so only one runs at a time
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Starts the tagging job. Only untagged sessions are tagged unless
+/// `retag_all` is set; topics are always recomputed.
+pub fn start(retag_all: bool) -> Result<(), String> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("Session tagging is already running".to_string());
+    }
+    let title = if retag_all { "Retag all sessions" } else { "Tag new sessions" };
+    JobSpec::new(JobKind::SessionTagging, title)
+        .with_retry(move || start(retag_all))
+        .spawn(move |handle| {
+            // Cleared however the task ends, including when it is aborted
+            let running = scopeguard::guard((), |_| RUNNING.store(false, Ordering::SeqCst));
+            async move {
+                let _running = running;
+                run(retag_all, handle).await
+            }
+        });
+    Ok(())
+}
+
+/// True while a tagging job runs
+pub fn is_running() -> bool {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+/// Starts the job if some sessions have not been tagged yet
+pub async fn start_if_needed() -> Result<(), String> {
+    let sessions = database::get_all_sessions().await.map_err(|e| e.to_string())?;
+    if sessions.iter().any(|s| s.tags.is_empty()) && !is_running() {
+        start(false)?;
+    }
+    Ok(())
+}
+
+async fn run(retag_all: bool, handle: JobHandle) -> Result<(), String> {
+    let mut sessions = database::get_all_sessions().await.map_err(|e| e.to_string())?;
+    let pending: Vec<usize> = (0..sessions.len())
+        .filter(|&i| retag_all || sessions[i].tags.is_empty())
+        .collect();
+    handle.log(format!("{} of {} sessions to tag", pending.len(), sessions.len()));
+
+    for (done, &i) in pending.iter().enumerate() {
+        let session = &mut sessions[i];
+        match tag_session(session).await {
+            Ok(Some(tags)) => {
+                handle.log(format!("{}: {}", session.title, tags.join(", ")));
+                database::set_session_tags(session.id, &tags).await.map_err(|e| e.to_string())?;
+                session.tags = tags;
+            }
+            Ok(None) => {}
+            Err(e) => handle.log(format!("{}: {}", session.title, e)),
+        }
+        // Clustering is the last step
+        handle.set_progress((done + 1) as f32 / (pending.len() + 1) as f32);
+    }
+
+    let topics = cluster_topics(&sessions, &handle).await;
+    let clusters = {
+        let mut labels: Vec<&str> = topics.iter().filter_map(|(_, topic)| topic.as_deref()).collect();
+        labels.sort_unstable();
+        labels.dedup();
+        labels.len()
+    };
+    database::set_session_topics(&topics).await.map_err(|e| e.to_string())?;
+    handle.log(format!("Grouped sessions into {} topics", clusters));
+    Ok(())
+}
+
+/// New tags for a session, or None if it is too short to tag
+async fn tag_session(session: &Session) -> Result<Option<Vec<String>>, String> {
+    let messages = database::get_session_messages(session.id).await.map_err(|e| e.to_string())?;
+    if messages.len() < MIN_MESSAGES {
+        return Ok(None);
+    }
+    let conversation: String = messages
+        .iter()
+        .map(|m| format!("{}: {}\n", m.role, m.content.trim()))
+        .collect::<String>()
+        .chars()
+        .take(TAG_SOURCE_CHARS)
+        .collect();
+
+    let prompt = format!(
+        "List 1 to {} short topic tags for this conversation, one or two lowercase words each, \
+in English. Reply with the tags only, comma separated.\n\nTitle: {}\n\n{}",
+        MAX_SESSION_TAGS, session.title, conversation
+    );
+    let tags = parse_tags(&llm::get_oneshot_response(prompt, TAGS_MAX_LENGTH).await?);
+    if tags.is_empty() {
+        return Err("The model returned no tags".to_string());
+    }
+    Ok(Some(tags))
+}
+
+/// Topic of every session: clusters of two or more similar tagged sessions
+/// are labelled with their most common tag
+async fn cluster_topics(sessions: &[Session], handle: &JobHandle) -> Vec<(Uuid, Option<String>)> {
+    let tagged: Vec<&Session> = sessions.iter().filter(|s| !s.tags.is_empty()).collect();
+    let mut topics: Vec<(Uuid, Option<String>)> = sessions.iter().map(|s| (s.id, None)).collect();
+    if tagged.len() < 2 {
+        return topics;
+    }
+
+    let texts: Vec<String> = tagged
+        .iter()
+        .map(|s| {
+            let summary = s.summary.as_ref().map(|summary| summary.summary.as_str()).unwrap_or_default();
+            format!("{}. Tags: {}. {}", s.title, s.tags.join(", "), summary)
+        })
+        .collect();
+    let vectors = match embedding::embed_batch(&texts).await {
+        Ok(vectors) => vectors,
+        Err(e) => {
+            // Without embeddings the tags still work as filters
+            handle.log(format!("Skipping topic clusters: {}", e));
+            return topics;
+        }
+    };
+
+    let assignments = cluster(&vectors, CLUSTER_THRESHOLD);
+    let cluster_count = assignments.iter().copied().max().map_or(0, |max| max + 1);
+    for cluster_id in 0..cluster_count {
+        let members: Vec<&Session> = tagged
+            .iter()
+            .zip(&assignments)
+            .filter(|(_, &c)| c == cluster_id)
+            .map(|(s, _)| *s)
+            .collect();
+        if members.len() < 2 {
+            continue;
+        }
+        let label = most_common_tag(&members);
+        for member in members {
+            if let Some(topic) = topics.iter_mut().find(|(id, _)| *id == member.id) {
+                topic.1 = label.clone();
+            }
+        }
+    }
+    topics
+}
+
+/// Greedy clustering: each vector joins the most similar cluster centroid
+/// at or above the threshold, or starts a new cluster. Returns the cluster
+/// index of every vector.
+pub(crate) fn cluster(vectors: &[Vec<f32>], threshold: f32) -> Vec<usize> {
+    let mut centroids: Vec<(Vec<f32>, usize)> = Vec::new();
+    let mut assignments = Vec::with_capacity(vectors.len());
+
+    for vector in vectors {
+        let best = centroids
+            .iter()
+            .enumerate()
+            .map(|(i, (centroid, _))| (i, cosine_similarity(centroid, vector)))
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((i, _)) => {
+                let (centroid, count) = &mut centroids[i];
+                *count += 1;
+                for (c, v) in centroid.iter_mut().zip(vector) {
+                    *c += (v - *c) / *count as f32;
+                }
+                assignments.push(i);
+            }
+            None => {
+                centroids.push((vector.clone(), 1));
+                assignments.push(centroids.len() - 1);
+            }
+        }
+    }
+    assignments
+}
+
+/// The tag most sessions share, alphabetically first on ties
+fn most_common_tag(sessions: &[&Session]) -> Option<String> {
+    let mut tags: Vec<&String> = sessions.iter().flat_map(|s| s.tags.iter()).collect();
+    tags.sort_unstable();
+    tags.dedup();
+    tags.into_iter()
+        .map(|tag| (sessions.iter().filter(|s| s.tags.contains(tag)).count(), tag))
+        .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(a.1)))
+        .map(|(_, tag)| tag.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cluster_groups_similar_vectors() {
+        let vectors = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.95, 0.1], vec![0.1, 0.9], vec![-1.0, 0.0]];
+        assert_eq!(cluster(&vectors, 0.8), vec![0, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_most_common_tag() {
+        let session = |tags: &[&str]| {
+            let mut s = Session::new("s".into());
+            s.tags = tags.iter().map(|t| t.to_string()).collect();
+            s
+        };
+        let (a, b, c) = (session(&["rust", "web"]), session(&["web", "css"]), session(&["rust", "async"]));
+        assert_eq!(most_common_tag(&[&a, &b, &c]).as_deref(), Some("rust"));
+        assert_eq!(most_common_tag(&[]), None);
+    }
+}
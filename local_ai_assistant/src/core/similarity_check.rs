@@ -0,0 +1,66 @@
+//! Similarity Check
+//!
+//! Flags draft passages that closely parrot a document in the knowledge
+//! base. Each paragraph is embedded and compared against the vector store;
+//! paragraphs whose best match scores above the threshold are reported with
+//! the matching source.
+
+use crate::models::{SimilarityMatch, SimilarityReport};
+use super::vector_store;
+
+/// Similarity at or above which a passage is flagged. Much stricter than the
+/// chat retrieval threshold: related is fine, near-copies are not.
+pub const FLAG_THRESHOLD: f32 = 0.85;
+/// Paragraphs shorter than this are too generic to compare
+const MIN_PASSAGE_WORDS: usize = 12;
+
+/// Scans (section title, text) pairs against the knowledge base
+pub async fn check(sections: &[(String, String)]) -> Result<SimilarityReport, String> {
+    if !vector_store::is_initialized() {
+        return Err("Knowledge base not initialized".to_string());
+    }
+
+    let mut report = SimilarityReport { threshold: FLAG_THRESHOLD, ..Default::default() };
+    for (section, text) in sections {
+        for passage in split_passages(text) {
+            report.passages_checked += 1;
+            let Some(best) = vector_store::search_raw(&passage, 1).await?.into_iter().next() else {
+                continue;
+            };
+            if best.score >= FLAG_THRESHOLD {
+                report.matches.push(SimilarityMatch {
+                    section: section.clone(),
+                    passage,
+                    source_title: best.title,
+                    source_text: best.text,
+                    score: best.score,
+                });
+            }
+        }
+    }
+
+    report.matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    tracing::info!("Similarity check: {} of {} passages flagged", report.matches.len(), report.passages_checked);
+    Ok(report)
+}
+
+/// Paragraphs long enough to be worth comparing
+fn split_passages(text: &str) -> Vec<String> {
+    text.split("\n\n")
+        .map(|p| p.trim())
+        .filter(|p| !p.starts_with("![") && p.split_whitespace().count() >= MIN_PASSAGE_WORDS)
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_passages_skips_short_and_images() {
+        let long = "This paragraph has more than enough words in it to be compared against sources.";
+        let text = format!("Short line.\n\n{}\n\n![Generated Image](data:image/png;base64,abc)", long);
+        assert_eq!(split_passages(&text), vec![long.to_string()]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Streaming Safeguards
+//!
+//! Local models sometimes loop on the same sentence or ramble past the
+//! point of being useful. [`StreamGuard`] sits between the model's token
+//! stream and the receiver: it cuts the output at a stop sequence, halts
+//! when the tail of the text keeps repeating, and enforces the token cap.
+
+use crate::models::GenerationLimits;
+
+/// Longest repeating unit looked for, in characters
+const MAX_REPEAT_UNIT: usize = 200;
+/// A loop must repeat at least this often...
+const MIN_REPEATS: usize = 4;
+/// ...and cover at least this many characters, so short legitimate runs
+/// such as `----` or `0, 0, 0` do not count
+const MIN_REPEAT_SPAN: usize = 120;
+
+/// Why a guarded stream ended early
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    StopSequence,
+    Repetition,
+    TokenLimit,
+}
+
+/// Filters one response's tokens
+pub struct StreamGuard {
+    limits: GenerationLimits,
+    /// Text passed on so far
+    text: String,
+    /// Text held back because it may be the start of a stop sequence
+    pending: String,
+    tokens: u32,
+}
+
+impl StreamGuard {
+    pub fn new(limits: GenerationLimits) -> Self {
+        let stop_sequences = limits.stop_sequences.iter()
+            .filter(|s| !s.is_empty())
+            .cloned()
+            .collect();
+        Self {
+            limits: GenerationLimits { stop_sequences, ..limits },
+            text: String::new(),
+            pending: String::new(),
+            tokens: 0,
+        }
+    }
+
+    /// Takes the next token and returns the text that may be passed on, and
+    /// whether generation should stop
+    pub fn push(&mut self, token: &str) -> (String, Option<StopReason>) {
+        self.tokens += 1;
+        self.pending.push_str(token);
+
+        let stop_at = self.limits.stop_sequences.iter()
+            .filter_map(|s| self.pending.find(s.as_str()))
+            .min();
+        if let Some(index) = stop_at {
+            self.pending.truncate(index);
+            return (self.flush(), Some(StopReason::StopSequence));
+        }
+
+        let held = self.limits.stop_sequences.iter()
+            .map(|s| partial_match_len(&self.pending, s))
+            .max()
+            .unwrap_or(0);
+        let ready = self.pending.drain(..self.pending.len() - held).collect::<String>();
+        self.text.push_str(&ready);
+
+        if self.limits.stop_on_repetition && is_looping(&self.text) {
+            return (ready, Some(StopReason::Repetition));
+        }
+        if self.tokens >= self.limits.max_output_tokens {
+            let mut out = ready;
+            out.push_str(&self.flush());
+            return (out, Some(StopReason::TokenLimit));
+        }
+        (ready, None)
+    }
+
+    /// Text still held back when the stream ends on its own
+    pub fn finish(mut self) -> String {
+        self.flush()
+    }
+
+    fn flush(&mut self) -> String {
+        let rest = std::mem::take(&mut self.pending);
+        self.text.push_str(&rest);
+        rest
+    }
+}
+
+/// Length of the longest end of `text` that starts `stop`
+fn partial_match_len(text: &str, stop: &str) -> usize {
+    (1..stop.len().min(text.len() + 1))
+        .rev()
+        .find(|&n| stop.is_char_boundary(n) && text.ends_with(&stop[..n]))
+        .unwrap_or(0)
+}
+
+/// Whether the text ends in the same chunk repeated over and over
+fn is_looping(text: &str) -> bool {
+    let tail: Vec<char> = text.chars().rev().take(MAX_REPEAT_UNIT * MIN_REPEATS).collect();
+
+    (1..=MAX_REPEAT_UNIT).any(|unit| {
+        let repeats = MIN_REPEATS.max(MIN_REPEAT_SPAN.div_ceil(unit));
+        let span = unit * repeats;
+        span <= tail.len() && (unit..span).all(|i| tail[i] == tail[i % unit])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(limits: GenerationLimits, tokens: &[&str]) -> (String, Option<StopReason>) {
+        let mut guard = StreamGuard::new(limits);
+        let mut out = String::new();
+        for token in tokens {
+            let (text, stop) = guard.push(token);
+            out.push_str(&text);
+            if stop.is_some() {
+                return (out, stop);
+            }
+        }
+        out.push_str(&guard.finish());
+        (out, None)
+    }
+
+    #[test]
+    fn test_stop_sequence_across_tokens() {
+        let limits = GenerationLimits { stop_sequences: vec!["<|end|>".to_string()], ..Default::default() };
+        assert_eq!(
+            run(limits.clone(), &["Hello", " world<|", "end|> ignored"]),
+            ("Hello world".to_string(), Some(StopReason::StopSequence))
+        );
+        // A partial match that does not complete is passed on
+        assert_eq!(run(limits, &["a <", "b"]), ("a <b".to_string(), None));
+    }
+
+    #[test]
+    fn test_repetition_halts_stream() {
+        let sentence = "The answer is forty-two. ";
+        let tokens = vec![sentence; 20];
+        let (text, stop) = run(GenerationLimits::default(), &tokens);
+        assert_eq!(stop, Some(StopReason::Repetition));
+        assert!(text.len() < sentence.len() * 10);
+
+        let off = GenerationLimits { stop_on_repetition: false, ..Default::default() };
+        assert_eq!(run(off, &tokens).1, None);
+
+        assert!(!is_looping("| --- | --- | --- |"));
+        assert!(!is_looping("Rust is fast. Rust is safe. Rust is fun. Rust is here."));
+    }
+
+    #[test]
+    fn test_token_limit() {
+        let limits = GenerationLimits { max_output_tokens: 3, ..Default::default() };
+        assert_eq!(run(limits, &["a", "b", "c", "d"]), ("abc".to_string(), Some(StopReason::TokenLimit)));
+    }
+}
@@ -0,0 +1,434 @@
+//! LLM Tools
+//!
+//! Server-side tools the chat model can call, described to the model with
+//! JSON schemas. Each step the model picks a tool and its arguments with
+//! constrained decoding ([`StructuredToolCall`]), or "none" to answer.
+//! Where constrained decoding is not available it replies with a
+//! `<tool_call>{"name": ..., "arguments": {...}}</tool_call>` block instead.
+//! The loop that executes calls and feeds results back lives in
+//! [`super::llm::generate_with_tools`].
+
+use kalosm::language::{Parse, Schema};
+use serde_json::{json, Value};
+
+/// Tools available to the model
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    Calculator,
+    RagLookup,
+    GenerateImage,
+    TextToSpeech,
+    WebSearch,
+    RunCode,
+}
+
+/// A tool request parsed from model output
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToolCall {
+    pub name: String,
+    pub arguments: Value,
+}
+
+/// A tool call as generated with constrained decoding
+#[derive(Parse, Schema, Clone, Debug)]
+pub struct StructuredToolCall {
+    /// Name of the tool to call, or "none" to answer without a tool
+    pub name: String,
+    pub arguments: Vec<StructuredArgument>,
+}
+
+#[derive(Parse, Schema, Clone, Debug)]
+pub struct StructuredArgument {
+    pub name: String,
+    pub value: String,
+}
+
+impl StructuredToolCall {
+    /// The requested call, None when the model chose to answer
+    pub fn into_call(self) -> Option<ToolCall> {
+        let name = self.name.trim();
+        if name.is_empty() || name.eq_ignore_ascii_case("none") {
+            return None;
+        }
+        let arguments = self.arguments.into_iter()
+            .map(|argument| (argument.name.trim().to_string(), Value::String(argument.value)))
+            .collect::<serde_json::Map<_, _>>();
+        Some(ToolCall { name: name.to_string(), arguments: Value::Object(arguments) })
+    }
+}
+
+/// Result of executing a tool
+pub struct ToolOutput {
+    /// Text fed back to the model
+    pub text: String,
+    /// Data URL of generated media
+    pub media: Option<String>,
+}
+
+impl ToolOutput {
+    fn text(text: impl Into<String>) -> Self {
+        Self { text: text.into(), media: None }
+    }
+}
+
+impl Tool {
+    pub fn all() -> &'static [Tool] {
+        &[Tool::Calculator, Tool::RagLookup, Tool::GenerateImage, Tool::TextToSpeech, Tool::WebSearch, Tool::RunCode]
+    }
+
+    /// Tools whose backends are usable on this machine.
+    ///
+    /// Code execution is only offered when the session opted in.
+    pub fn available(allow_code_execution: bool) -> Vec<Tool> {
+        Self::all().iter()
+            .copied()
+            .filter(|t| *t != Tool::RunCode || allow_code_execution)
+            .filter(|t| t.is_available())
+            .collect()
+    }
+
+    fn is_available(&self) -> bool {
+        match self {
+            Tool::Calculator => true,
+            Tool::RagLookup => super::vector_store::is_initialized(),
+            Tool::GenerateImage => super::image_gen::is_mflux_available(),
+            Tool::TextToSpeech => !super::tts::get_available_engines().is_empty(),
+            Tool::WebSearch => super::web_search::is_enabled(),
+            Tool::RunCode => super::code_sandbox::is_available(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Calculator => "calculator",
+            Tool::RagLookup => "search_knowledge_base",
+            Tool::GenerateImage => "generate_image",
+            Tool::TextToSpeech => "text_to_speech",
+            Tool::WebSearch => "web_search",
+            Tool::RunCode => "run_code",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Tool> {
+        Self::all().iter().copied().find(|t| t.name() == name)
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Tool::Calculator => "Evaluate an arithmetic expression with + - * / ^ and parentheses.",
+            Tool::RagLookup => "Search the user's local knowledge base documents.",
+            Tool::GenerateImage => "Generate an image from a text description.",
+            Tool::TextToSpeech => "Read text aloud and return an audio clip.",
+            Tool::WebSearch => "Search the web for current information. Cite results by their [number].",
+            Tool::RunCode => "Run a short Python or Rust program without network access and return its output. Print the values you need.",
+        }
+    }
+
+    /// JSON schema of the arguments object
+    fn parameters(&self) -> Value {
+        let single = |name: &str, description: &str| json!({
+            "type": "object",
+            "properties": { name: { "type": "string", "description": description } },
+            "required": [name]
+        });
+        match self {
+            Tool::Calculator => single("expression", "Expression to evaluate, e.g. (3 + 4) * 2"),
+            Tool::RagLookup => single("query", "What to look up"),
+            Tool::GenerateImage => single("prompt", "Description of the image"),
+            Tool::TextToSpeech => single("text", "Text to speak"),
+            Tool::WebSearch => single("query", "Search query"),
+            Tool::RunCode => json!({
+                "type": "object",
+                "properties": {
+                    "language": { "type": "string", "enum": ["python", "rust"] },
+                    "code": { "type": "string", "description": "Complete program; Rust needs a main function" }
+                },
+                "required": ["language", "code"]
+            }),
+        }
+    }
+
+    /// Runs the tool with the model-provided arguments
+    pub async fn execute(&self, arguments: &Value) -> Result<ToolOutput, String> {
+        let arg = |key: &str| -> Result<String, String> {
+            arguments.get(key)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .filter(|s| !s.trim().is_empty())
+                .ok_or_else(|| format!("Missing argument '{}'", key))
+        };
+
+        match self {
+            Tool::Calculator => {
+                let value = calculator::evaluate(&arg("expression")?)?;
+                Ok(ToolOutput::text(calculator::format_number(value)))
+            }
+            Tool::RagLookup => {
+                let documents = super::vector_store::query(&arg("query")?).await?;
+                if documents.is_empty() {
+                    return Ok(ToolOutput::text("No matching documents."));
+                }
+                let text = documents.iter()
+                    .map(|d| format!("[{}] {}", d.title, d.body))
+                    .collect::<Vec<_>>()
+                    .join("\n---\n");
+                Ok(ToolOutput::text(text))
+            }
+            Tool::GenerateImage => {
+                let prompt = arg("prompt")?;
+                let image = super::image_gen::generate_image(super::image_gen::ImageGenSettings::new(&prompt)).await?;
+                if let Some(path) = &image.path {
+                    super::assets::register(path, &prompt, crate::models::AssetOrigin::Chat, None).await;
+                }
+                Ok(ToolOutput {
+                    text: format!("Generated a {}x{} image. It is shown to the user.", image.width, image.height),
+                    media: Some(image.to_data_url()),
+                })
+            }
+            Tool::TextToSpeech => {
+                let text = arg("text")?;
+                let audio = super::tts::speak_text(&text).await?;
+                if let Err(e) = super::assets::save(&audio.data, &audio.format, &text, crate::models::AssetOrigin::Chat).await {
+                    tracing::warn!("Could not save generated audio to the asset library: {}", e);
+                }
+                Ok(ToolOutput {
+                    text: format!("Generated {:.1}s of audio. It is shown to the user.", audio.duration_ms as f32 / 1000.0),
+                    media: Some(audio.to_data_url()),
+                })
+            }
+            Tool::WebSearch => {
+                let results = super::web_search::search(&arg("query")?, super::web_search::DEFAULT_RESULT_COUNT).await?;
+                if results.is_empty() {
+                    return Ok(ToolOutput::text("No results."));
+                }
+                Ok(ToolOutput::text(crate::models::format_web_results(&results)))
+            }
+            Tool::RunCode => {
+                let language = super::code_sandbox::CodeLanguage::from_name(&arg("language")?)
+                    .ok_or("Only python and rust are supported")?;
+                let output = super::code_sandbox::run(language, &arg("code")?).await?;
+                Ok(ToolOutput::text(output.summary()))
+            }
+        }
+    }
+}
+
+fn tool_specs(tools: &[Tool]) -> String {
+    tools.iter()
+        .map(|t| json!({
+            "name": t.name(),
+            "description": t.description(),
+            "parameters": t.parameters(),
+        }).to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// System prompt for choosing a tool with constrained decoding
+pub fn call_prompt(tools: &[Tool]) -> String {
+    format!(
+        "You can call these tools:\n{}\n\n\
+Decide whether a tool is needed to answer the user. If it is, give the tool's name and \
+its arguments by parameter name. If not, or if the results so far are enough, give the name \"none\".",
+        tool_specs(tools)
+    )
+}
+
+/// System prompt describing the tools and the text call format
+pub fn system_prompt(tools: &[Tool]) -> String {
+    format!(
+        "You can call these tools:\n{}\n\n\
+To call a tool, reply with ONLY this block and nothing else:\n\
+<tool_call>{{\"name\": \"tool_name\", \"arguments\": {{...}}}}</tool_call>\n\
+You will receive the result and can then call another tool or answer. \
+Only call a tool when it is needed; otherwise answer directly.",
+        tool_specs(tools)
+    )
+}
+
+/// Extracts a tool call from model output, if the model made one
+pub fn parse_tool_call(output: &str) -> Option<ToolCall> {
+    let start = output.find("<tool_call>")? + "<tool_call>".len();
+    let rest = &output[start..];
+    let body = rest.find("</tool_call>").map(|end| &rest[..end]).unwrap_or(rest);
+
+    // Tolerate code fences and trailing chatter inside the block
+    let json_start = body.find('{')?;
+    let json_end = body.rfind('}')?;
+    let value: Value = serde_json::from_str(body.get(json_start..=json_end)?).ok()?;
+
+    let name = value.get("name")?.as_str()?.to_string();
+    let arguments = match value.get("arguments") {
+        // Some models send the arguments as a JSON string
+        Some(Value::String(s)) => serde_json::from_str(s).unwrap_or_else(|_| json!({})),
+        Some(v) => v.clone(),
+        None => json!({}),
+    };
+    Some(ToolCall { name, arguments })
+}
+
+/// Small arithmetic evaluator for the calculator tool
+mod calculator {
+    /// Evaluates + - * / ^ with parentheses and unary minus
+    pub fn evaluate(expression: &str) -> Result<f64, String> {
+        let tokens: Vec<char> = expression.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut parser = Parser { tokens, pos: 0 };
+        let value = parser.expression()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(format!("Unexpected '{}' in expression", parser.tokens[parser.pos]));
+        }
+        if !value.is_finite() {
+            return Err("Result is not a finite number".to_string());
+        }
+        Ok(value)
+    }
+
+    /// Prints integers without a trailing ".0"
+    pub fn format_number(value: f64) -> String {
+        if value.fract() == 0.0 && value.abs() < 1e15 {
+            format!("{}", value as i64)
+        } else {
+            format!("{}", value)
+        }
+    }
+
+    struct Parser {
+        tokens: Vec<char>,
+        pos: usize,
+    }
+
+    impl Parser {
+        fn peek(&self) -> Option<char> {
+            self.tokens.get(self.pos).copied()
+        }
+
+        fn expression(&mut self) -> Result<f64, String> {
+            let mut value = self.term()?;
+            while let Some(op @ ('+' | '-')) = self.peek() {
+                self.pos += 1;
+                let rhs = self.term()?;
+                value = if op == '+' { value + rhs } else { value - rhs };
+            }
+            Ok(value)
+        }
+
+        fn term(&mut self) -> Result<f64, String> {
+            let mut value = self.unary()?;
+            while let Some(op @ ('*' | '/')) = self.peek() {
+                self.pos += 1;
+                let rhs = self.unary()?;
+                if op == '/' && rhs == 0.0 {
+                    return Err("Division by zero".to_string());
+                }
+                value = if op == '*' { value * rhs } else { value / rhs };
+            }
+            Ok(value)
+        }
+
+        // Binds looser than ^, so -2^2 is -(2^2)
+        fn unary(&mut self) -> Result<f64, String> {
+            if self.peek() == Some('-') {
+                self.pos += 1;
+                return Ok(-self.unary()?);
+            }
+            self.power()
+        }
+
+        fn power(&mut self) -> Result<f64, String> {
+            let base = self.primary()?;
+            if self.peek() == Some('^') {
+                self.pos += 1;
+                // Right-associative; the exponent may be negative, as in 2^-1
+                let exponent = self.unary()?;
+                return Ok(base.powf(exponent));
+            }
+            Ok(base)
+        }
+
+        fn primary(&mut self) -> Result<f64, String> {
+            match self.peek() {
+                Some('(') => {
+                    self.pos += 1;
+                    let value = self.expression()?;
+                    if self.peek() != Some(')') {
+                        return Err("Missing closing parenthesis".to_string());
+                    }
+                    self.pos += 1;
+                    Ok(value)
+                }
+                Some(c) if c.is_ascii_digit() || c == '.' => {
+                    let start = self.pos;
+                    while matches!(self.peek(), Some(c) if c.is_ascii_digit() || c == '.') {
+                        self.pos += 1;
+                    }
+                    let number: String = self.tokens[start..self.pos].iter().collect();
+                    number.parse().map_err(|_| format!("Invalid number '{}'", number))
+                }
+                Some(c) => Err(format!("Unexpected '{}' in expression", c)),
+                None => Err("Unexpected end of expression".to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculator() {
+        assert_eq!(calculator::evaluate("1 + 2 * 3").unwrap(), 7.0);
+        assert_eq!(calculator::evaluate("(1 + 2) * 3").unwrap(), 9.0);
+        assert_eq!(calculator::evaluate("2 ^ 3 ^ 2").unwrap(), 512.0);
+        assert_eq!(calculator::evaluate("-4 + 10 / 4").unwrap(), -1.5);
+        assert_eq!(calculator::evaluate("-2^2").unwrap(), -4.0);
+        assert_eq!(calculator::evaluate("(-2)^2").unwrap(), 4.0);
+        assert_eq!(calculator::evaluate("2^-1").unwrap(), 0.5);
+        assert!(calculator::evaluate("1 / 0").is_err());
+        assert!(calculator::evaluate("2 +").is_err());
+        assert!(calculator::evaluate("abc").is_err());
+        assert_eq!(calculator::format_number(42.0), "42");
+        assert_eq!(calculator::format_number(2.5), "2.5");
+    }
+
+    #[test]
+    fn test_parse_tool_call() {
+        let call = parse_tool_call(r#"<tool_call>{"name": "calculator", "arguments": {"expression": "2+2"}}</tool_call>"#).unwrap();
+        assert_eq!(call.name, "calculator");
+        assert_eq!(call.arguments["expression"], "2+2");
+    }
+
+    #[test]
+    fn test_parse_tool_call_tolerates_fences_and_string_arguments() {
+        let output = "Sure.\n<tool_call>\n```json\n{\"name\": \"calculator\", \"arguments\": \"{\\\"expression\\\": \\\"1\\\"}\"}\n```\n</tool_call>";
+        let call = parse_tool_call(output).unwrap();
+        assert_eq!(call.arguments["expression"], "1");
+    }
+
+    #[test]
+    fn test_structured_tool_call() {
+        let structured = StructuredToolCall {
+            name: " calculator ".to_string(),
+            arguments: vec![StructuredArgument { name: "expression".to_string(), value: "2+2".to_string() }],
+        };
+        let call = structured.into_call().unwrap();
+        assert_eq!(call.name, "calculator");
+        assert_eq!(call.arguments["expression"], "2+2");
+
+        let answer = StructuredToolCall { name: "None".to_string(), arguments: Vec::new() };
+        assert!(answer.into_call().is_none());
+    }
+
+    #[test]
+    fn test_parse_tool_call_none_for_plain_answer() {
+        assert!(parse_tool_call("The answer is 4.").is_none());
+        assert!(parse_tool_call("<tool_call>not json</tool_call>").is_none());
+    }
+
+    #[test]
+    fn test_tool_names_round_trip() {
+        for tool in Tool::all() {
+            assert_eq!(Tool::from_name(tool.name()), Some(*tool));
+        }
+    }
+}
@@ -0,0 +1,321 @@
+//! Audio Transcription
+//!
+//! Adds audio files and podcast episodes to the knowledge base. The audio is
+//! converted with `ffmpeg` and transcribed locally by whisper.cpp
+//! (`whisper-cli`); the timestamped transcript is written to the context
+//! folder as Markdown and indexed, so answers can cite the minute mark of
+//! the source. Runs as a background job, since an hour of audio takes a
+//! while to transcribe. Short microphone recordings for voice mode are
+//! transcribed directly.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::models::{JobKind, NetworkFeature, TranscriptSegment, MAX_UTTERANCE_BYTES, transcript_markdown};
+use super::jobs::{JobHandle, JobSpec};
+use super::{quick_capture, vector_store};
+
+/// Largest audio file accepted, uploaded or downloaded
+pub const MAX_AUDIO_BYTES: usize = 500 * 1024 * 1024;
+
+/// whisper.cpp command, overridable for custom builds
+const WHISPER_BIN_ENV: &str = "IDORIS_WHISPER_BIN";
+const DEFAULT_WHISPER_BIN: &str = "whisper-cli";
+
+/// Path of the ggml Whisper model, overridable
+const WHISPER_MODEL_ENV: &str = "IDORIS_WHISPER_MODEL";
+const DEFAULT_WHISPER_MODEL: &str = "ggml-base.bin";
+
+/// Audio to transcribe
+#[derive(Clone, Debug)]
+enum AudioSource {
+    /// An uploaded file saved in the audio folder, with its original name
+    File { path: PathBuf, name: String },
+    /// An audio file URL, or a podcast feed whose latest episode is used
+    Url(String),
+}
+
+impl AudioSource {
+    fn label(&self) -> &str {
+        match self {
+            AudioSource::File { name, .. } => name,
+            AudioSource::Url(url) => url,
+        }
+    }
+}
+
+/// Uploaded audio waiting to be transcribed, kept until it is indexed so a
+/// failed job can be retried
+fn audio_dir() -> PathBuf {
+    super::paths::data_dir().join("audio")
+}
+
+pub(crate) fn whisper_model() -> PathBuf {
+    std::env::var(WHISPER_MODEL_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| super::paths::data_dir().join("whisper").join(DEFAULT_WHISPER_MODEL))
+}
+
+pub(crate) fn whisper_bin() -> String {
+    std::env::var(WHISPER_BIN_ENV).unwrap_or_else(|_| DEFAULT_WHISPER_BIN.to_string())
+}
+
+/// Saves an uploaded audio file and starts transcribing it
+pub fn start_file(name: &str, data: Vec<u8>, collection: Option<String>) -> Result<(), String> {
+    if data.is_empty() {
+        return Err("The file is empty".to_string());
+    }
+    if data.len() > MAX_AUDIO_BYTES {
+        return Err(format!("Audio files up to {} MB can be added", MAX_AUDIO_BYTES / (1024 * 1024)));
+    }
+    let name = Path::new(name)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .filter(|n| !n.starts_with('.'))
+        .ok_or("Invalid file name")?
+        .to_string();
+    quick_capture::collection_dir(collection.as_deref())?;
+
+    let dir = audio_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}-{}", Uuid::new_v4(), name));
+    std::fs::write(&path, &data).map_err(|e| e.to_string())?;
+    start(AudioSource::File { path, name }, collection)
+}
+
+/// Starts transcribing an audio URL or the latest episode of a podcast feed
+pub fn start_url(url: &str, collection: Option<String>) -> Result<(), String> {
+    let url = url.trim();
+    reqwest::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    quick_capture::collection_dir(collection.as_deref())?;
+    start(AudioSource::Url(url.to_string()), collection)
+}
+
+fn start(source: AudioSource, collection: Option<String>) -> Result<(), String> {
+    let retry_source = source.clone();
+    let retry_collection = collection.clone();
+    let title = format!("Transcribe {}", source.label());
+    JobSpec::new(JobKind::Transcription, title)
+        .with_retry(move || start(retry_source.clone(), retry_collection.clone()))
+        .spawn(move |handle| run(handle, source, collection));
+    Ok(())
+}
+
+async fn run(job: JobHandle, source: AudioSource, collection: Option<String>) -> Result<(), String> {
+    let work_dir = std::env::temp_dir().join(format!("idoris-transcribe-{}", job.id()));
+    std::fs::create_dir_all(&work_dir).map_err(|e| e.to_string())?;
+    let result = transcribe_and_index(&job, &source, collection.as_deref(), &work_dir).await;
+    let _ = std::fs::remove_dir_all(&work_dir);
+
+    if result.is_ok() {
+        if let AudioSource::File { path, .. } = &source {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    result
+}
+
+async fn transcribe_and_index(job: &JobHandle, source: &AudioSource, collection: Option<&str>, work_dir: &Path) -> Result<(), String> {
+    let (audio, title, source_label) = match source {
+        AudioSource::File { path, name } => {
+            let title = Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or(name).to_string();
+            (path.clone(), title, name.clone())
+        }
+        AudioSource::Url(url) => {
+            job.log(format!("Downloading {}", url));
+            let (path, title) = download(url, work_dir).await?;
+            (path, title, url.clone())
+        }
+    };
+    job.set_progress(0.2);
+
+    job.log("Transcribing with Whisper");
+    let segments = transcribe_audio(&audio, work_dir).await?;
+    job.log(format!("Transcribed {} segments", segments.len()));
+    job.set_progress(0.9);
+
+    let dir = quick_capture::collection_dir(collection)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let filename = quick_capture::unique_filename(&dir, &title);
+    let content = transcript_markdown(&title, &source_label, &segments);
+    std::fs::write(dir.join(&filename), &content).map_err(|e| e.to_string())?;
+    job.log(format!("Saved transcript as {}", filename));
+
+    match vector_store::index_document(&format!("# {}", title), &content).await {
+        Ok(()) => job.log("Added to the index"),
+        Err(e) => job.log(format!("Saved but not indexed yet: {}", e)),
+    }
+    job.set_progress(1.0);
+    Ok(())
+}
+
+/// Downloads the audio, resolving a podcast feed to its latest episode.
+/// Returns the file and a title for the transcript.
+async fn download(url: &str, work_dir: &Path) -> Result<(PathBuf, String), String> {
+    let response = super::http::get(NetworkFeature::Transcription, url).await.map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    let is_feed = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|t| t.contains("xml") || t.contains("rss"));
+
+    if is_feed {
+        let bytes = response.bytes().await.map_err(|e| format!("Failed to read feed: {}", e))?;
+        let feed = feed_rs::parser::parse(&bytes[..]).map_err(|e| format!("Failed to parse podcast feed: {}", e))?;
+        let (episode_url, title) = feed.entries.iter()
+            .find_map(|entry| {
+                let enclosure = entry.media.iter().flat_map(|m| m.content.iter()).find_map(|c| c.url.clone())?;
+                let title = entry.title.as_ref().map(|t| t.content.clone()).unwrap_or_else(|| "Podcast episode".to_string());
+                Some((enclosure.to_string(), title))
+            })
+            .ok_or("The feed has no episode with audio")?;
+        let response = super::http::get(NetworkFeature::Transcription, &episode_url).await.map_err(|e| format!("Failed to fetch {}: {}", episode_url, e))?;
+        return Ok((save_response(response, work_dir).await?, title));
+    }
+
+    let title = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.path_segments()?.next_back().map(str::to_string))
+        .and_then(|name| Path::new(&name).file_stem().and_then(|s| s.to_str()).map(str::to_string))
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "Audio".to_string());
+    Ok((save_response(response, work_dir).await?, title))
+}
+
+/// Streams a download to disk, up to [`MAX_AUDIO_BYTES`]
+async fn save_response(mut response: reqwest::Response, work_dir: &Path) -> Result<PathBuf, String> {
+    if !response.status().is_success() {
+        return Err(format!("Download failed with status {}", response.status()));
+    }
+    let path = work_dir.join("download");
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Download failed: {}", e))? {
+        if data.len() + chunk.len() > MAX_AUDIO_BYTES {
+            return Err(format!("Audio files up to {} MB can be added", MAX_AUDIO_BYTES / (1024 * 1024)));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    std::fs::write(&path, &data).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Transcribes a short recording from the microphone, such as a spoken
+/// question in voice mode. Runs directly rather than as a job.
+pub async fn transcribe_utterance(data: Vec<u8>) -> Result<String, String> {
+    if data.is_empty() {
+        return Err("No audio was recorded".to_string());
+    }
+    if data.len() > MAX_UTTERANCE_BYTES {
+        return Err("The recording is too long".to_string());
+    }
+    if super::mock::is_enabled() {
+        return Ok(super::mock::transcript(&data));
+    }
+
+    let work_dir = std::env::temp_dir().join(format!("idoris-voice-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&work_dir).map_err(|e| e.to_string())?;
+    let result = async {
+        let audio = work_dir.join("utterance.webm");
+        std::fs::write(&audio, &data).map_err(|e| e.to_string())?;
+        transcribe_audio(&audio, &work_dir).await
+    }.await;
+    let _ = std::fs::remove_dir_all(&work_dir);
+
+    let text = result?.iter().map(|s| s.text.trim()).collect::<Vec<_>>().join(" ");
+    Ok(text.trim().to_string())
+}
+
+/// Converts and transcribes an audio file, using `work_dir` for
+/// intermediate files
+pub(crate) async fn transcribe_audio(audio: &Path, work_dir: &Path) -> Result<Vec<TranscriptSegment>, String> {
+    let wav = work_dir.join("audio.wav");
+    convert_to_wav(audio, &wav).await?;
+    let segments = transcribe(&wav, work_dir).await?;
+    if segments.is_empty() {
+        return Err("No speech found in the audio".to_string());
+    }
+    Ok(segments)
+}
+
+/// 16 kHz mono WAV, the input whisper.cpp expects
+async fn convert_to_wav(input: &Path, output: &Path) -> Result<(), String> {
+    let result = Command::new("ffmpeg")
+        .args(["-nostdin", "-y", "-i"])
+        .arg(input)
+        .args(["-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"])
+        .arg(output)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|_| "ffmpeg is not installed (the `ffmpeg` command was not found)".to_string())?;
+    if !result.status.success() {
+        return Err("ffmpeg could not read the audio file".to_string());
+    }
+    Ok(())
+}
+
+async fn transcribe(wav: &Path, work_dir: &Path) -> Result<Vec<TranscriptSegment>, String> {
+    let model = whisper_model();
+    if !model.exists() {
+        return Err(format!(
+            "Whisper model not found at {:?}. Download a ggml model from whisper.cpp or set {}.",
+            model, WHISPER_MODEL_ENV
+        ));
+    }
+    let bin = whisper_bin();
+    let output = work_dir.join("transcript");
+
+    let result = Command::new(&bin)
+        .arg("-m").arg(&model)
+        .arg("-f").arg(wav)
+        .args(["-l", "auto", "-oj", "-np"])
+        .arg("-of").arg(&output)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|_| format!("whisper.cpp is not installed (the `{}` command was not found)", bin))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        let message = stderr.lines().map(str::trim).rfind(|l| !l.is_empty()).unwrap_or("Transcription failed");
+        return Err(message.to_string());
+    }
+
+    let json = std::fs::read_to_string(output.with_extension("json")).map_err(|e| e.to_string())?;
+    parse_whisper_json(&json)
+}
+
+/// Segments from whisper.cpp's `-oj` output
+fn parse_whisper_json(json: &str) -> Result<Vec<TranscriptSegment>, String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| format!("Invalid transcript: {}", e))?;
+    let segments = value.get("transcription").and_then(|t| t.as_array()).ok_or("Invalid transcript: no segments")?;
+    Ok(segments.iter().filter_map(|segment| {
+        let offsets = segment.get("offsets")?;
+        Some(TranscriptSegment {
+            start_ms: offsets.get("from")?.as_u64()?,
+            end_ms: offsets.get("to")?.as_u64()?,
+            text: segment.get("text")?.as_str()?.trim().to_string(),
+        })
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_whisper_json() {
+        let json = r#"{"transcription": [
+            {"timestamps": {"from": "00:00:00,000", "to": "00:00:04,200"}, "offsets": {"from": 0, "to": 4200}, "text": " Welcome to the show."},
+            {"offsets": {"from": 4200}, "text": "missing end"}
+        ]}"#;
+        let segments = parse_whisper_json(json).unwrap();
+        assert_eq!(segments, vec![TranscriptSegment { start_ms: 0, end_ms: 4200, text: "Welcome to the show.".to_string() }]);
+        assert!(parse_whisper_json("{}").is_err());
+    }
+}
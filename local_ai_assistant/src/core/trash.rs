@@ -0,0 +1,151 @@
+//! Trash
+//!
+//! Deleting a session, message, context document or asset moves it here.
+//! Items can be restored for `TRASH_RETENTION_DAYS`, after which they are
+//! purged when the trash is next opened. Rows stay in SQLite with a
+//! `deleted_at` time; context documents are moved to the `trash` folder in
+//! the data directory and leave the index when it is next reloaded.
+
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::models::{AssetKind, TrashItem, TrashKind};
+use crate::storage::database;
+
+/// Folder holding deleted context documents
+fn trash_dir() -> PathBuf {
+    super::paths::data_dir().join("trash").join(super::profile::active_profile_id())
+}
+
+/// Everything in the trash, after purging items past their retention
+pub async fn list() -> Result<Vec<TrashItem>, String> {
+    purge_expired().await?;
+    database::get_trash().await.map_err(|e| e.to_string())
+}
+
+/// Moves a file of the context folder to the trash
+pub async fn trash_document(filename: &str) -> Result<(), String> {
+    let source = document_path(filename)?;
+    if !source.is_file() {
+        return Err(format!("{} does not exist", filename));
+    }
+    let dir = trash_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let id = Uuid::new_v4();
+    let target = dir.join(format!("{}-{}", id, filename));
+    move_file(&source, &target)?;
+    if let Err(e) = database::save_trashed_document(id, filename, &target.display().to_string()).await {
+        // Put the file back rather than lose track of it
+        let _ = move_file(&target, &source);
+        return Err(e.to_string());
+    }
+    tracing::info!("Moved context document to the trash: {}", filename);
+    Ok(())
+}
+
+/// Puts an item back where it was deleted from
+pub async fn restore(kind: TrashKind, id: &str) -> Result<(), String> {
+    match kind {
+        TrashKind::Document => {
+            let item = find(kind, id).await?;
+            let target = document_path(&item.title)?;
+            if target.exists() {
+                return Err(format!("A document named {} already exists; rename or delete it first", item.title));
+            }
+            let trashed = PathBuf::from(item.path.as_deref().ok_or("Trashed file is missing")?);
+            move_file(&trashed, &target)?;
+            database::delete_trashed_document(id).await.map_err(|e| e.to_string())
+        }
+        _ => database::restore_trashed(kind, id).await.map_err(|e| e.to_string()),
+    }
+}
+
+/// Deletes an item for good, with its file
+pub async fn purge(kind: TrashKind, id: &str) -> Result<(), String> {
+    let item = find(kind, id).await?;
+    purge_item(&item).await
+}
+
+/// Purges everything in the trash, returning how many items were removed
+pub async fn empty() -> Result<usize, String> {
+    let items = database::get_trash().await.map_err(|e| e.to_string())?;
+    for item in &items {
+        purge_item(item).await?;
+    }
+    Ok(items.len())
+}
+
+/// Purges items deleted more than `TRASH_RETENTION_DAYS` ago
+pub async fn purge_expired() -> Result<usize, String> {
+    let now = Utc::now();
+    let expired: Vec<TrashItem> = database::get_trash().await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|item| item.is_expired(now))
+        .collect();
+    for item in &expired {
+        purge_item(item).await?;
+    }
+    if !expired.is_empty() {
+        tracing::info!("Purged {} expired items from the trash", expired.len());
+    }
+    Ok(expired.len())
+}
+
+async fn purge_item(item: &TrashItem) -> Result<(), String> {
+    match item.kind {
+        TrashKind::Session => {
+            let id = Uuid::parse_str(&item.id).map_err(|_| "Invalid session ID")?;
+            database::delete_session(id).await.map_err(|e| e.to_string())
+        }
+        TrashKind::Message => database::delete_message(&item.id).await.map_err(|e| e.to_string()),
+        TrashKind::Document => {
+            remove_file(item.path.as_deref())?;
+            database::delete_trashed_document(&item.id).await.map_err(|e| e.to_string())
+        }
+        TrashKind::Asset => {
+            let id = Uuid::parse_str(&item.id).map_err(|_| "Invalid asset ID")?;
+            remove_file(item.path.as_deref())?;
+            // Generated videos have their thumbnail next to them
+            if let Some(path) = item.path.as_deref().filter(|p| AssetKind::from_path(p) == Some(AssetKind::Video)) {
+                let _ = std::fs::remove_file(Path::new(path).with_extension("jpg"));
+            }
+            database::delete_assets(&[id]).await.map_err(|e| e.to_string())
+        }
+    }
+}
+
+async fn find(kind: TrashKind, id: &str) -> Result<TrashItem, String> {
+    database::get_trash().await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|item| item.kind == kind && item.id == id)
+        .ok_or_else(|| "Item is no longer in the trash".to_string())
+}
+
+/// Path of a file directly in the context folder
+fn document_path(filename: &str) -> Result<PathBuf, String> {
+    if filename.is_empty() || filename.contains("..") || filename.contains(['/', '\\']) {
+        return Err("Invalid filename".to_string());
+    }
+    Ok(super::vector_store::get_context_folder().join(filename))
+}
+
+/// Renames a file, copying it when the folders are on different drives
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to).map_err(|e| format!("Failed to move {:?}: {}", from, e))?;
+    std::fs::remove_file(from).map_err(|e| format!("Failed to remove {:?}: {}", from, e))
+}
+
+fn remove_file(path: Option<&str>) -> Result<(), String> {
+    let Some(path) = path else { return Ok(()) };
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to delete {}: {}", path, e)),
+    }
+}
@@ -12,6 +12,9 @@ use once_cell::sync::Lazy;
 use std::process::Command;
 use std::path::PathBuf;
 
+use crate::models::{AudioOutputDevice, PITCH_SAMPLE_RATE, SPEECH_ESTIMATE_BYTES, parse_speech_markup, pitch_filter, render_for_say, render_plain};
+use super::model_manager::ModelManager;
+
 /// TTS Engine type
 #[derive(Clone, Debug, PartialEq, Default)]
 pub enum TtsEngine {
@@ -78,6 +81,11 @@ impl TtsSettings {
         self.speed = speed;
         self
     }
+
+    pub fn with_pitch(mut self, pitch: f32) -> Self {
+        self.pitch = pitch;
+        self
+    }
 }
 
 /// Generated audio result
@@ -125,7 +133,7 @@ fn set_status(status: &str, progress: u8) {
         *s = status.to_string();
     }
     GEN_PROGRESS.store(progress, Ordering::SeqCst);
-    println!("[TTS] {}: {}%", status, progress);
+    tracing::info!("[TTS] {}: {}%", status, progress);
 }
 
 /// Get the model directory path
@@ -424,10 +432,18 @@ pub async fn generate_speech(settings: TtsSettings) -> Result<GeneratedAudio, St
         set_status("Ready", 0);
     });
 
+    if super::mock::is_enabled() {
+        return Ok(super::mock::speech(&settings.text));
+    }
+
+    // Engines write their audio to the temporary folder
+    ModelManager::check_disk_space(&std::env::temp_dir(), SPEECH_ESTIMATE_BYTES, "generating speech").await.map_err(|e| e.to_string())?;
+
     set_status("Starting TTS generation...", 1);
-    println!("[TTS] Text: {} ({})", &settings.text[..settings.text.len().min(50)], settings.engine.display_name());
+    tracing::info!("[TTS] Text: {} ({})", settings.text.chars().take(50).collect::<String>(), settings.engine.display_name());
+    let settings = TtsSettings { text: prepare_text(&settings.text, &settings.engine), ..settings };
 
-    match settings.engine {
+    let audio = match settings.engine {
         TtsEngine::VibeVoice => {
             if !is_vibevoice_available() {
                 return Err("VibeVoice model not downloaded. Please download from Settings.".to_string());
@@ -441,9 +457,88 @@ pub async fn generate_speech(settings: TtsSettings) -> Result<GeneratedAudio, St
         TtsEngine::System => {
             generate_system_tts(&settings.text, settings.speed).await
         }
+    }?;
+    Ok(shift_pitch(audio, settings.pitch).await)
+}
+
+/// Applies the pronunciation lexicon and markup, rendered for the engine:
+/// `say` takes pauses and emphasis as embedded commands, other engines get
+/// plain text
+fn prepare_text(text: &str, engine: &TtsEngine) -> String {
+    let lexicon = super::profile::load_settings().pronunciations;
+    let parts = parse_speech_markup(text, &lexicon);
+    match engine {
+        TtsEngine::System => render_for_say(&parts),
+        _ => render_plain(&parts),
     }
 }
 
+/// Changes the pitch of generated speech with ffmpeg, keeping its length.
+/// Returns the audio unchanged if ffmpeg is missing or fails.
+async fn shift_pitch(audio: GeneratedAudio, pitch: f32) -> GeneratedAudio {
+    let Some(filter) = pitch_filter(pitch) else {
+        return audio;
+    };
+    set_status("Adjusting pitch...", 90);
+    let work_dir = std::env::temp_dir().join(format!("idoris-pitch-{}", uuid::Uuid::new_v4()));
+    let result = async {
+        std::fs::create_dir_all(&work_dir).map_err(|e| e.to_string())?;
+        let input = work_dir.join(format!("input.{}", audio.format));
+        let output = work_dir.join("output.wav");
+        std::fs::write(&input, &audio.data).map_err(|e| e.to_string())?;
+        let status = tokio::process::Command::new("ffmpeg")
+            .args(["-nostdin", "-y", "-i"])
+            .arg(&input)
+            .args(["-af", &filter])
+            .arg(&output)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .status()
+            .await
+            .map_err(|_| "ffmpeg is not installed".to_string())?;
+        if !status.success() {
+            return Err("ffmpeg could not change the pitch".to_string());
+        }
+        std::fs::read(&output).map_err(|e| e.to_string())
+    }.await;
+    let _ = std::fs::remove_dir_all(&work_dir);
+
+    match result {
+        Ok(data) => GeneratedAudio {
+            data,
+            sample_rate: PITCH_SAMPLE_RATE,
+            format: "wav".to_string(),
+            duration_ms: audio.duration_ms,
+        },
+        Err(e) => {
+            tracing::warn!("[TTS] Pitch unchanged: {}", e);
+            audio
+        }
+    }
+}
+
+/// Audio output devices of the machine running the server, default first
+pub fn output_devices() -> Vec<AudioOutputDevice> {
+    use cpal::traits::{DeviceTrait, HostTrait};
+
+    let host = cpal::default_host();
+    let default = host.default_output_device().and_then(|d| d.name().ok());
+    let mut devices: Vec<AudioOutputDevice> = match host.output_devices() {
+        Ok(devices) => devices
+            .filter_map(|d| d.name().ok())
+            .map(|name| AudioOutputDevice { is_default: default.as_ref() == Some(&name), name })
+            .collect(),
+        Err(e) => {
+            tracing::warn!("[TTS] Could not list output devices: {}", e);
+            Vec::new()
+        }
+    };
+    devices.sort_by_key(|d| !d.is_default);
+    devices.dedup_by(|a, b| a.name == b.name);
+    devices
+}
+
 /// Quick TTS using default settings
 pub async fn speak_text(text: &str) -> Result<GeneratedAudio, String> {
     let engines = get_available_engines();
@@ -0,0 +1,139 @@
+//! Image Upscaling
+//!
+//! Makes 2× and 4× copies of generated images with Real-ESRGAN
+//! (`realesrgan-ncnn-vulkan`), optionally restoring faces with GFPGAN
+//! first. Without Real-ESRGAN the image is resampled instead, which is
+//! larger but no sharper. Copies are saved as PNG next to the original.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use image::imageops::FilterType;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::models::{UpscaleFactor, UpscaleMethod, UpscaledImage, upscaled_file_name};
+
+/// Real-ESRGAN command, overridable for custom builds
+const UPSCALER_BIN_ENV: &str = "IDORIS_UPSCALER_BIN";
+const DEFAULT_UPSCALER_BIN: &str = "realesrgan-ncnn-vulkan";
+
+/// Real-ESRGAN model; it always upscales 4×, 2× copies are scaled down
+const UPSCALER_MODEL: &str = "realesrgan-x4plus";
+
+/// GFPGAN's `inference_gfpgan.py`, needed for face fixing
+const GFPGAN_SCRIPT_ENV: &str = "IDORIS_GFPGAN_SCRIPT";
+
+/// Longest side an upscaled copy may have
+const MAX_UPSCALED_SIDE: u32 = 8192;
+
+/// Saves an upscaled copy of `source` next to it
+pub async fn upscale_image(source: &Path, factor: UpscaleFactor, face_fix: bool) -> Result<UpscaledImage, String> {
+    let (width, height) = image::image_dimensions(source).map_err(|e| format!("Failed to open image: {}", e))?;
+    let (target_width, target_height) = (width * factor.scale(), height * factor.scale());
+    if target_width.max(target_height) > MAX_UPSCALED_SIDE {
+        return Err(format!("{} would be larger than {} pixels; use a smaller factor", factor.label(), MAX_UPSCALED_SIDE));
+    }
+
+    let stem = source.file_stem().and_then(|s| s.to_str()).unwrap_or("image");
+    let output = source.with_file_name(upscaled_file_name(stem, factor, face_fix));
+    let work_dir = std::env::temp_dir().join(format!("idoris-upscale-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&work_dir).map_err(|e| e.to_string())?;
+
+    let result = run(source, &output, &work_dir, (target_width, target_height), face_fix).await;
+    let _ = std::fs::remove_dir_all(&work_dir);
+    let method = result?;
+
+    let (width, height) = image::image_dimensions(&output).map_err(|e| e.to_string())?;
+    Ok(UpscaledImage { path: output.display().to_string(), factor, face_fix, method, width, height })
+}
+
+async fn run(source: &Path, output: &Path, work_dir: &Path, size: (u32, u32), face_fix: bool) -> Result<UpscaleMethod, String> {
+    let mock = super::mock::is_enabled();
+    let input = if face_fix && !mock { restore_faces(source, work_dir).await? } else { source.to_path_buf() };
+
+    let enlarged = work_dir.join("enlarged.png");
+    let method = if !mock && real_esrgan(&input, &enlarged).await? {
+        UpscaleMethod::RealEsrgan
+    } else {
+        UpscaleMethod::Resample
+    };
+    let from = if method == UpscaleMethod::RealEsrgan { enlarged } else { input };
+
+    // Real-ESRGAN's 4× output is brought to the requested size
+    let output = output.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let image = image::open(&from).map_err(|e| format!("Failed to open image: {}", e))?;
+        let image = if (image.width(), image.height()) == size {
+            image
+        } else {
+            image.resize_exact(size.0, size.1, FilterType::Lanczos3)
+        };
+        image.save(&output).map_err(|e| format!("Failed to write image: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Upscaling panicked: {}", e))??;
+    Ok(method)
+}
+
+pub(crate) fn upscaler_bin() -> String {
+    std::env::var(UPSCALER_BIN_ENV).unwrap_or_else(|_| DEFAULT_UPSCALER_BIN.to_string())
+}
+
+/// Runs Real-ESRGAN, returning false when it is not installed
+async fn real_esrgan(input: &Path, output: &Path) -> Result<bool, String> {
+    let bin = upscaler_bin();
+    let result = Command::new(&bin)
+        .arg("-i").arg(input)
+        .arg("-o").arg(output)
+        .args(["-s", "4", "-n", UPSCALER_MODEL, "-f", "png"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await;
+    let result = match result {
+        Ok(result) => result,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::info!("{} not found, resampling instead", bin);
+            return Ok(false);
+        }
+        Err(e) => return Err(format!("Failed to run {}: {}", bin, e)),
+    };
+    if !result.status.success() || !output.exists() {
+        return Err(last_line(&result.stderr, "Real-ESRGAN failed"));
+    }
+    Ok(true)
+}
+
+/// Restores faces with GFPGAN, returning the restored image
+async fn restore_faces(source: &Path, work_dir: &Path) -> Result<PathBuf, String> {
+    let script = std::env::var(GFPGAN_SCRIPT_ENV)
+        .map_err(|_| format!("Fixing faces needs GFPGAN; set {} to its inference_gfpgan.py", GFPGAN_SCRIPT_ENV))?;
+    let result = Command::new("python3")
+        .arg(&script)
+        .arg("-i").arg(source)
+        .arg("-o").arg(work_dir)
+        .args(["-v", "1.4", "-s", "1", "--bg_upsampler", "none", "--ext", "png"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run GFPGAN: {}", e))?;
+    if !result.status.success() {
+        return Err(last_line(&result.stderr, "GFPGAN failed"));
+    }
+
+    let stem = source.file_stem().and_then(|s| s.to_str()).unwrap_or("image");
+    let restored = work_dir.join("restored_imgs").join(format!("{}.png", stem));
+    if restored.exists() {
+        Ok(restored)
+    } else {
+        Err("GFPGAN did not produce an image".to_string())
+    }
+}
+
+fn last_line(stderr: &[u8], fallback: &str) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    stderr.lines().map(str::trim).rfind(|l| !l.is_empty()).unwrap_or(fallback).to_string()
+}
@@ -10,8 +10,16 @@ use kalosm::language::Embedding;
 use tokio::sync::{Mutex, OnceCell};
 use surrealdb::Surreal;
 use surrealdb::engine::local::{Db, SurrealKv};
-use crate::models::Document as SimpleDocument;
-use std::path::PathBuf;
+use crate::models::{parse_citation, recency_weight, Document as SimpleDocument, DocumentChunk, DocumentDetails, DocumentMetadata, RetrievalOptions, RetrievedChunk, SourceType, VectorStoreStats, DEFAULT_MIN_SCORE};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use once_cell::sync::Lazy;
+use super::embedding::{self, ConfiguredEmbedder};
+
+/// Document table indexed with the user-configured embedding model
+type IndexTable = DocumentTable<Db, Document, ConfiguredEmbedder>;
 
 /// Global singleton for the database connection
 /// Uses OnceCell and Mutex for thread-safe access and initialization
@@ -19,7 +27,33 @@ static DB_CONN: OnceCell<Mutex<Option<Surreal<Db>>>> = OnceCell::const_new();
 
 /// Global singleton for the document table
 /// Stores documents with embedding-based search capabilities
-static DOCUMENT_TABLE: OnceCell<Mutex<Option<DocumentTable<Db>>>> = OnceCell::const_new();
+static DOCUMENT_TABLE: OnceCell<Mutex<Option<IndexTable>>> = OnceCell::const_new();
+
+/// Embedding backend fingerprint and vector size the current index was built with
+static INDEX_EMBEDDING: OnceCell<Mutex<Option<(String, usize)>>> = OnceCell::const_new();
+
+/// Document title and source type by file, with the modification time they
+/// were read at, for filtering search results by metadata
+static FILE_INFO_CACHE: Lazy<std::sync::Mutex<HashMap<PathBuf, (SystemTime, String, SourceType)>>> = Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Record id of a document in the index
+type DocumentId = surrealdb::sql::Id;
+
+/// An indexed document with its chunks, kept so single chunks can be
+/// removed from or re-added to the index
+struct IndexedDocument {
+    /// None once every chunk was removed
+    id: Option<DocumentId>,
+    document: Document,
+    /// Byte range of each chunk, with its embeddings while it is indexed
+    chunks: Vec<(Range<usize>, Option<Vec<Embedding>>)>,
+}
+
+/// Indexed documents by title
+static INDEXED_DOCUMENTS: Lazy<Mutex<HashMap<String, IndexedDocument>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Time of the last successful (re)index
+static LAST_INDEXED_AT: std::sync::Mutex<Option<chrono::DateTime<chrono::Utc>>> = std::sync::Mutex::new(None);
 
 /// Constants for database configuration
 const NAMESPACE: &str = "test";
@@ -30,36 +64,29 @@ const TABLE_NAME: &str = "documents";
 /// Search more results initially to allow for filtering
 const SEARCH_RESULTS_COUNT: usize = 10;
 /// Minimum similarity threshold (BERT distance) - filter out low quality matches
-const SIMILARITY_THRESHOLD: f32 = 0.5;
-/// Maximum results to return after filtering
-const MAX_RESULTS: usize = 5;
-
-/// Get the project root directory
-fn get_project_root() -> PathBuf {
-    // Fallback to the local_ai_assistant project directory
-    let fallback = PathBuf::from("/Volumes/UltraDisk/Dev2/crypto-projects/AI-test/local_ai_assistant");
-    if fallback.exists() {
-        return fallback;
-    }
-    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
-}
-
-/// Get database paths based on project root
+const SIMILARITY_THRESHOLD: f32 = DEFAULT_MIN_SCORE;
+/// Searched per requested result when only some documents are searched,
+/// since hits from the others are dropped
+const FILTER_OVERSAMPLING: usize = 4;
+/// Most results searched for one query
+const MAX_SEARCH_RESULTS: usize = 100;
+
+/// Directory holding the index files
 fn get_db_path() -> PathBuf {
-    get_project_root().join("db")
+    super::paths::index_dir()
 }
 
 fn get_db_file() -> PathBuf {
-    get_project_root().join("db/temp.db")
+    get_db_path().join("temp.db")
 }
 
 fn get_embeddings_file() -> PathBuf {
-    get_project_root().join("db/embeddings.db")
+    get_db_path().join("embeddings.db")
 }
 
-/// Get the context folder path - public for use by other modules
+/// Get the context folder of the active profile - public for use by other modules
 pub fn get_context_folder() -> PathBuf {
-    get_project_root().join("context")
+    super::profile::context_folder().unwrap_or_else(super::paths::context_dir)
 }
 
 /// Establishes a connection to the database and initializes the document table
@@ -76,7 +103,7 @@ pub async fn connect_to_database() -> Result<(), String> {
     {
         let dt_guard = DOCUMENT_TABLE.get().unwrap().lock().await;
         if dt_guard.is_some() {
-            println!("Vector store already initialized, skipping...");
+            tracing::info!("Vector store already initialized, skipping...");
             return Ok(());
         }
     }
@@ -99,7 +126,7 @@ pub async fn connect_to_database() -> Result<(), String> {
     // Add documents to the database
     add_documents().await?;
 
-    println!("Database connection setup completed successfully");
+    tracing::info!("Database connection setup completed successfully");
     Ok(())
 }
 
@@ -107,19 +134,26 @@ pub async fn connect_to_database() -> Result<(), String> {
 async fn initialize_globals() {
     DB_CONN.get_or_init(|| async { Mutex::new(None) }).await;
     DOCUMENT_TABLE.get_or_init(|| async { Mutex::new(None) }).await;
+    INDEX_EMBEDDING.get_or_init(|| async { Mutex::new(None) }).await;
 }
 
 /// Cleans up existing database files
 fn cleanup_database_files() -> Result<(), String> {
-    let db_path = get_db_path();
-    if db_path.exists() {
-        std::fs::remove_dir_all(&db_path).map_err(|e| {
-            eprintln!("Error removing existing database: {}", e);
+    // Only the index's own files: the directory is user-configurable
+    for path in [get_db_file(), get_embeddings_file()] {
+        let removed = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else if path.exists() {
+            std::fs::remove_file(&path)
+        } else {
+            tracing::info!("No existing database found at {:?}, creating a new one", path);
+            continue;
+        };
+        removed.map_err(|e| {
+            tracing::error!("Error removing existing database: {}", e);
             e.to_string()
         })?;
-        println!("Removed existing database files at {:?}", db_path);
-    } else {
-        println!("No existing database found at {:?}, creating a new one", db_path);
+        tracing::info!("Removed existing database files at {:?}", path);
     }
     Ok(())
 }
@@ -127,11 +161,11 @@ fn cleanup_database_files() -> Result<(), String> {
 /// Creates a new database connection
 async fn create_database_connection() -> Result<Surreal<Db>, String> {
     let db_file = get_db_file();
-    println!("Connecting to the database at {:?}...", db_file);
+    tracing::info!("Connecting to the database at {:?}...", db_file);
     let db = Surreal::new::<SurrealKv>(db_file)
         .await
         .map_err(|e| e.to_string())?;
-    println!("Database connected successfully");
+    tracing::info!("Database connected successfully");
     Ok(db)
 }
 
@@ -140,30 +174,44 @@ async fn configure_database(db: &Surreal<Db>) -> Result<(), String> {
     db.use_ns(NAMESPACE).use_db(DATABASE)
         .await
         .map_err(|e| {
-            eprintln!("Error using namespace and database: {}", e);
+            tracing::error!("Error using namespace and database: {}", e);
             e.to_string()
         })
 }
 
-/// Creates the document table with semantic chunking
-async fn create_document_table(db: &Surreal<Db>) -> Result<DocumentTable<Db>, String> {
+/// Creates the document table with semantic chunking, embedding with the
+/// configured backend
+async fn create_document_table(db: &Surreal<Db>) -> Result<IndexTable, String> {
     let embeddings_file = get_embeddings_file();
-    println!("Creating document table with embeddings at {:?}...", embeddings_file);
+    tracing::info!("Creating document table with embeddings at {:?}...", embeddings_file);
+
+    let embedder = embedding::current_embedder().await?;
+    let dimensions = embedder.embed("dimension probe")
+        .await
+        .map_err(|e| format!("Embedding model unavailable: {}", e))?
+        .vector()
+        .len();
+    let fingerprint = embedding::get_config().backend.fingerprint();
+    tracing::info!("Indexing with {} ({} dimensions)", fingerprint, dimensions);
+
     let dt = db.document_table_builder(TABLE_NAME)
+        .with_embedding_model(embedder)
         .with_chunker(SemanticChunker::default())
         .at(embeddings_file)
         .build::<Document>()
         .await
         .map_err(|e| {
-            eprintln!("Error creating document table: {}", e);
+            tracing::error!("Error creating document table: {}", e);
             e.to_string()
         })?;
-    println!("Document table created successfully");
+    tracing::info!("Document table created successfully");
+
+    *INDEX_EMBEDDING.get().unwrap().lock().await = Some((fingerprint, dimensions));
     Ok(dt)
 }
 
 /// Stores database connections in the global singletons
-async fn store_connections(db: Surreal<Db>, dt: DocumentTable<Db>) {
+async fn store_connections(db: Surreal<Db>, dt: IndexTable) {
     {
         let mut db_guard = DB_CONN.get().unwrap().lock().await;
         *db_guard = Some(db);
@@ -179,13 +227,13 @@ async fn store_connections(db: Surreal<Db>, dt: DocumentTable<Db>) {
 ///
 /// Returns Ok(()) on success or an error message on failure
 async fn add_documents() -> Result<(), String> {
-    println!("Adding documents to the table...");
+    tracing::info!("Adding documents to the table...");
 
     // Check if context folder exists
     let context_path = get_context_folder();
-    println!("Using context folder: {:?}", context_path);
+    tracing::info!("Using context folder: {:?}", context_path);
     if !context_path.exists() {
-        println!("Context folder does not exist, creating it...");
+        tracing::info!("Context folder does not exist, creating it...");
         std::fs::create_dir_all(&context_path).map_err(|e| e.to_string())?;
         // Create a sample document
         std::fs::write(context_path.join("sample.md"), "# Sample Document\n\nThis is a sample document for RAG testing.")
@@ -199,13 +247,15 @@ async fn add_documents() -> Result<(), String> {
     // Get document table reference
     let table = get_document_table().await?;
 
-    // Process documents
-    let documents = process_documents(raw_documents).await?;
+    // Process documents, and the chunks of the profile's codebases
+    let mut documents = process_documents(raw_documents).await?;
+    documents.extend(super::codebase::index_documents().await);
 
     // Insert documents into table
     insert_documents(&table, documents).await?;
+    mark_indexed();
 
-    println!("All documents added successfully");
+    tracing::info!("All documents added successfully");
     Ok(())
 }
 
@@ -230,26 +280,46 @@ async fn process_documents(document_folder: DocumentFolder) -> Result<Vec<Docume
         })
 }
 
-/// Inserts multiple documents into the document table
-async fn insert_documents(table: &DocumentTable<Db>, documents: Vec<Document>) -> Result<(), String> {
+/// Inserts multiple documents into the document table. Each document's
+/// chunks are embedded in batches by the configured embedder.
+async fn insert_documents(table: &IndexTable, documents: Vec<Document>) -> Result<(), String> {
+    let started = std::time::Instant::now();
+    let count = documents.len();
     for document in documents {
         insert_single_document(table, document).await?;
     }
+    tracing::info!(
+        documents = count,
+        batch_size = embedding::get_config().batch_size,
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "Documents indexed"
+    );
     Ok(())
 }
 
-/// Inserts a single document into the document table
-async fn insert_single_document(table: &DocumentTable<Db>, document: Document) -> Result<(), String> {
-    table.insert(document).await
+/// Inserts a single document into the document table, remembering its
+/// chunks for the document viewer
+async fn insert_single_document(table: &IndexTable, document: Document) -> Result<(), String> {
+    let chunks = table.chunker().chunk(&document, table.embedding_model()).await
+        .map_err(|e| {
+            tracing::error!("Error chunking document: {}", e);
+            e.to_string()
+        })?;
+    let ranges = chunks.iter().map(|c| (c.byte_range.clone(), Some(c.embeddings.clone()))).collect();
+    let id = table.table().insert(chunks, document.clone()).await
         .map_err(|e| {
-            eprintln!("Error adding document: {}", e);
+            tracing::error!("Error adding document: {}", e);
             e.to_string()
         })?;
+    INDEXED_DOCUMENTS.lock().await.insert(
+        document.title().to_string(),
+        IndexedDocument { id: Some(id), document, chunks: ranges },
+    );
     Ok(())
 }
 
 /// Gets a reference to the document table from the global singleton
-async fn get_document_table() -> Result<impl std::ops::Deref<Target = DocumentTable<Db>> + 'static, String> {
+async fn get_document_table() -> Result<impl std::ops::Deref<Target = IndexTable> + 'static, String> {
     let document_table_mutex_ref = DOCUMENT_TABLE
         .get()
         .ok_or("Document table not initialized")?;
@@ -272,55 +342,210 @@ async fn get_document_table() -> Result<impl std::ops::Deref<Target = DocumentTa
 /// # Returns
 /// * `Result<Vec<SimpleDocument>, String>` - A vector of matching document results or an error
 pub async fn query(query: &str) -> Result<Vec<SimpleDocument>, String> {
+    query_with(query, &RetrievalOptions::default()).await
+}
+
+/// Like [`query`], with the collection, metadata filter, result count and
+/// score threshold chosen for one chat message. Scores in collections with
+/// a recency half-life are weighted by document age.
+pub async fn query_with(query: &str, options: &RetrievalOptions) -> Result<Vec<SimpleDocument>, String> {
+    let half_lives = super::profile::load_settings().recency_half_life_days;
+    let metadata = if options.is_filtered() || !half_lives.is_empty() {
+        Some(document_metadata().await?)
+    } else {
+        None
+    };
+    let allowed_titles = match &metadata {
+        Some(metadata) if options.is_filtered() => Some(matching_titles(metadata, options)?),
+        _ => None,
+    };
+    let weights = match &metadata {
+        Some(metadata) => recency_weights(metadata, &half_lives),
+        None => HashMap::new(),
+    };
+
     // Get document table
     let table = get_document_table().await?;
 
     // Create embedding from query
     let query_embed = create_embedding_from_query(&table, query).await?;
+    check_dimensions(query_embed.vector().len()).await?;
 
     // Perform semantic search
-    let results = perform_semantic_search(&table, query_embed).await?;
+    let results = perform_semantic_search(&table, query_embed, options, allowed_titles.as_ref(), &weights).await?;
 
     // Convert results to SimpleDocument
     Ok(convert_search_results(results))
 }
 
+/// Titles of the documents the options allow searching, as stored in the
+/// index
+fn matching_titles(metadata: &HashMap<String, DocumentMetadata>, options: &RetrievalOptions) -> Result<HashSet<String>, String> {
+    if let Some(name) = &options.collection {
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(format!("Invalid collection name: {}", name));
+        }
+        if !metadata.values().any(|m| m.collection.as_deref() == Some(name.as_str())) {
+            return Err(format!("Collection not found: {}", name));
+        }
+    }
+    Ok(metadata.iter()
+        .filter(|(_, metadata)| options.matches(metadata))
+        .map(|(title, _)| title.clone())
+        .collect())
+}
+
+/// Score weights by title for documents in collections with a recency
+/// half-life
+fn recency_weights(metadata: &HashMap<String, DocumentMetadata>, half_lives: &BTreeMap<String, u32>) -> HashMap<String, f32> {
+    let now = chrono::Utc::now();
+    metadata.iter()
+        .filter_map(|(title, metadata)| {
+            let half_life = *half_lives.get(metadata.collection.as_ref()?)?;
+            let age_days = (now - metadata.added_at).num_seconds() as f32 / 86_400.0;
+            Some((title.clone(), recency_weight(age_days, half_life)))
+        })
+        .collect()
+}
+
+/// Metadata of every searchable document by title: the context folder's
+/// files, then the chunks of the profile's codebases
+pub async fn document_metadata() -> Result<HashMap<String, DocumentMetadata>, String> {
+    let root = get_context_folder();
+    let tags = crate::storage::database::get_document_tags().await.unwrap_or_default();
+    let mut metadata = HashMap::new();
+    for path in context_document_paths()? {
+        if let Some((title, entry)) = file_metadata(&root, &path, &tags).await {
+            metadata.insert(title, entry);
+        }
+    }
+
+    let code_titles: Vec<String> = INDEXED_DOCUMENTS.lock().await
+        .keys()
+        .filter(|title| parse_citation(title).is_some())
+        .cloned()
+        .collect();
+    if !code_titles.is_empty() {
+        let codebases = super::codebase::list().await.unwrap_or_default();
+        for title in code_titles {
+            let Some((name, ..)) = parse_citation(&title) else { continue };
+            let Some(codebase) = codebases.iter().find(|c| c.name == name) else { continue };
+            let code_metadata = DocumentMetadata {
+                source_type: SourceType::Code,
+                tags: Vec::new(),
+                added_at: codebase.indexed_at.unwrap_or(codebase.created_at),
+                collection: Some(codebase.name.clone()),
+            };
+            metadata.insert(title, code_metadata);
+        }
+    }
+    Ok(metadata)
+}
+
+/// Title and metadata of a file in the context folder. Titles and source
+/// types are cached until the file changes.
+async fn file_metadata(root: &Path, path: &Path, tags: &HashMap<String, Vec<String>>) -> Option<(String, DocumentMetadata)> {
+    let file = std::fs::metadata(path).ok()?;
+    let modified = file.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    let cached = FILE_INFO_CACHE.lock().ok()
+        .and_then(|cache| cache.get(path).filter(|(at, ..)| *at == modified).map(|(_, title, source)| (title.clone(), *source)));
+    let (title, source_type) = match cached {
+        Some(info) => info,
+        None => match parse_document_file(path.to_path_buf()).await {
+            Ok(document) => {
+                let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                let info = (document.title().to_string(), SourceType::detect(filename, document.body()));
+                if let Ok(mut cache) = FILE_INFO_CACHE.lock() {
+                    cache.insert(path.to_path_buf(), (modified, info.0.clone(), info.1));
+                }
+                info
+            }
+            Err(e) => {
+                tracing::warn!("Skipping {:?} in metadata filtering: {}", path, e);
+                return None;
+            }
+        },
+    };
+
+    let relative = relative_path(root, path)?;
+    let collection = relative.split_once('/').map(|(collection, _)| collection.to_string());
+    let added_at = file.created().unwrap_or(modified);
+    Some((title, DocumentMetadata {
+        source_type,
+        tags: tags.get(&relative).cloned().unwrap_or_default(),
+        added_at: added_at.into(),
+        collection,
+    }))
+}
+
+/// Path of a file below the context folder with `/` separators, the key
+/// document tags are stored under
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    Some(relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"))
+}
+
 /// Creates an embedding vector from the query text
 async fn create_embedding_from_query(
-    table: &DocumentTable<Db>,
+    table: &IndexTable,
     query: &str
 ) -> Result<Embedding, String> {
     table.embedding_model().embed(query).await.map_err(|e| {
-        eprintln!("Error creating embedding: {}", e);
+        tracing::error!("Error creating embedding: {}", e);
         e.to_string()
     })
 }
 
+/// Rejects queries embedded with a different model than the index
+async fn check_dimensions(query_dimensions: usize) -> Result<(), String> {
+    let index = INDEX_EMBEDDING.get().ok_or("Vector store not initialized")?.lock().await.clone();
+    match index {
+        Some((fingerprint, dimensions)) if dimensions != query_dimensions
+            || fingerprint != embedding::get_config().backend.fingerprint() => Err(format!(
+                "The embedding model changed since the index was built ({} dims vs {} dims). Re-embed documents in Settings > Database.",
+                dimensions, query_dimensions
+            )),
+        _ => Ok(()),
+    }
+}
+
 /// Performs semantic search using the embedding vector
 /// Returns filtered results based on similarity threshold
 async fn perform_semantic_search(
-    table: &DocumentTable<Db>,
-    query_embed: Embedding
+    table: &IndexTable,
+    query_embed: Embedding,
+    options: &RetrievalOptions,
+    allowed_titles: Option<&HashSet<String>>,
+    weights: &HashMap<String, f32>,
 ) -> Result<Vec<EmbeddingIndexedTableSearchResult<Document>>, String> {
+    // Weighted hits can overtake ones further down the raw ranking
+    let per_result = if allowed_titles.is_some() || !weights.is_empty() { FILTER_OVERSAMPLING } else { 2 };
     let results = table.search(query_embed)
-        .with_results(SEARCH_RESULTS_COUNT)
+        .with_results((options.top_k * per_result).clamp(SEARCH_RESULTS_COUNT, MAX_SEARCH_RESULTS))
         .await
         .map_err(|e| e.to_string())?;
 
-    // Filter by similarity threshold and take top results
-    let filtered: Vec<_> = results
+    // Filter by similarity threshold and metadata, rank by recency-weighted
+    // score and take top results
+    let mut filtered: Vec<_> = results
         .into_iter()
         .filter(|doc| {
-            let passes = doc.distance >= SIMILARITY_THRESHOLD;
-            println!("RAG result: score={:.3}, passes_threshold={}, title='{}'",
+            let passes = doc.distance >= options.min_score;
+            tracing::debug!("RAG result: score={:.3}, passes_threshold={}, title='{}'",
                 doc.distance, passes, doc.record.title().chars().take(50).collect::<String>());
-            passes
+            passes && allowed_titles.is_none_or(|titles| titles.contains(doc.record.title()))
         })
-        .take(MAX_RESULTS)
         .collect();
+    if !weights.is_empty() {
+        for doc in filtered.iter_mut() {
+            doc.distance *= weights.get(doc.record.title()).copied().unwrap_or(1.0);
+        }
+        filtered.sort_by(|a, b| b.distance.total_cmp(&a.distance));
+    }
+    filtered.truncate(options.top_k);
 
-    println!("RAG search: {} results after filtering (threshold={}, max={})",
-        filtered.len(), SIMILARITY_THRESHOLD, MAX_RESULTS);
+    tracing::info!("RAG search: {} results after filtering (threshold={}, max={}, collection={:?}, filter={:?})",
+        filtered.len(), options.min_score, options.top_k, options.collection, options.filter);
 
     Ok(filtered)
 }
@@ -348,39 +573,301 @@ pub fn is_initialized() -> bool {
     DOCUMENT_TABLE.get().is_some()
 }
 
-/// Reload documents from context folder into existing table
-/// This adds new documents without rebuilding the entire database
-pub async fn reload_documents() -> Result<String, String> {
-    // Check if table is initialized
+/// Files in the context folder the document loader can read, sorted by path
+pub fn context_document_paths() -> Result<Vec<PathBuf>, String> {
+    let context_path = get_context_folder();
+    if !context_path.exists() {
+        return Err(format!("Context folder not found: {:?}", context_path));
+    }
+    document_paths_in(context_path)
+}
+
+/// Readable documents in a folder and its subfolders, sorted by path
+fn document_paths_in(folder: PathBuf) -> Result<Vec<PathBuf>, String> {
+    let mut paths = Vec::new();
+    let mut dirs = vec![folder];
+    while let Some(dir) = dirs.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|e| format!("Error reading {:?}: {}", dir, e))?;
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.is_dir() {
+                dirs.push(path);
+            } else if FsDocument::try_from(path.clone()).is_ok() {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Reads and converts one file into a document, titled by its first line
+pub async fn parse_document_file(path: PathBuf) -> Result<Document, String> {
+    let doc = FsDocument::try_from(path)
+        .map_err(|e| format!("Unsupported document: {}", e))?
+        .into_document()
+        .await
+        .map_err(|e| format!("Error processing document: {}", e))?;
+    let title = doc.body().lines().next().unwrap_or("Unknown").to_string();
+    Ok(Document::from_parts(title, doc.body().to_string()))
+}
+
+/// Chunks, embeds and stores a parsed document in the live index
+pub async fn store_document(document: Document) -> Result<(), String> {
     if !is_initialized() {
         return Err("Vector store not initialized. Please restart the application.".to_string());
     }
+    let table = get_document_table().await?;
+    insert_single_document(&table, document).await?;
+    mark_indexed();
+    Ok(())
+}
 
-    println!("Reloading documents from context folder...");
+/// Rebuilds the index from the context folder with the current embedding
+/// backend. Used after the embedding model changes, since vectors from
+/// different models are not comparable.
+pub async fn reembed_documents() -> Result<String, String> {
+    tracing::info!("Re-embedding documents with {}", embedding::get_config().backend.display_name());
+    rebuild_index().await?;
 
-    // Get context folder path
-    let context_path = get_context_folder();
-    if !context_path.exists() {
-        return Err(format!("Context folder not found: {:?}", context_path));
+    let msg = "Documents re-embedded with the new model".to_string();
+    tracing::info!("{}", msg);
+    Ok(msg)
+}
+
+/// Rebuilds the index from the active profile's context folder. Called when
+/// switching profiles so each profile searches only its own knowledge base.
+pub async fn switch_profile_collection() -> Result<String, String> {
+    tracing::info!("Indexing context folder {:?}", get_context_folder());
+    rebuild_index().await?;
+    Ok("Knowledge base switched".to_string())
+}
+
+/// Drops the current index and re-creates it from the context folder
+async fn rebuild_index() -> Result<(), String> {
+    initialize_globals().await;
+
+    // Drop the old table and connection so the files can be removed
+    *DOCUMENT_TABLE.get().unwrap().lock().await = None;
+    *DB_CONN.get().unwrap().lock().await = None;
+    *INDEX_EMBEDDING.get().unwrap().lock().await = None;
+    INDEXED_DOCUMENTS.lock().await.clear();
+
+    cleanup_database_files()?;
+    let db = create_database_connection().await?;
+    configure_database(&db).await?;
+    let dt = create_document_table(&db).await?;
+    store_connections(db, dt).await;
+    add_documents().await
+}
+
+/// Records the current time as the last index time
+fn mark_indexed() {
+    if let Ok(mut at) = LAST_INDEXED_AT.lock() {
+        *at = Some(chrono::Utc::now());
     }
+}
 
-    // Get document table
+/// Collects document/chunk counts, on-disk size and index metadata
+pub async fn get_stats() -> Result<VectorStoreStats, String> {
+    let index_size_bytes = super::model_manager::ModelManager::calculate_dir_size(&get_db_path())
+        .unwrap_or(0);
+    let last_indexed_at = LAST_INDEXED_AT.lock().ok().and_then(|at| *at);
+    let dimensions = match INDEX_EMBEDDING.get() {
+        Some(index) => index.lock().await.as_ref().map(|(_, d)| *d),
+        None => None,
+    };
+
+    let Some(db_mutex) = DB_CONN.get() else {
+        return Ok(VectorStoreStats { index_size_bytes, last_indexed_at, dimensions, ..Default::default() });
+    };
+    let db_guard = db_mutex.lock().await;
+    let Some(db) = db_guard.as_ref() else {
+        return Ok(VectorStoreStats { index_size_bytes, last_indexed_at, dimensions, ..Default::default() });
+    };
+
+    let document_count = count_rows(db, TABLE_NAME).await?.unwrap_or(0);
+
+    // kalosm stores chunk embeddings in companion tables named after the document table
+    let mut chunk_count = None;
+    for table in list_tables(db).await? {
+        if table != TABLE_NAME && table.starts_with(TABLE_NAME) {
+            if let Some(count) = count_rows(db, &table).await? {
+                *chunk_count.get_or_insert(0) += count;
+            }
+        }
+    }
+
+    Ok(VectorStoreStats {
+        document_count,
+        chunk_count,
+        index_size_bytes,
+        last_indexed_at,
+        dimensions,
+    })
+}
+
+/// Names of all tables in the current SurrealDB database
+async fn list_tables(db: &Surreal<Db>) -> Result<Vec<String>, String> {
+    let mut response = db.query("INFO FOR DB").await.map_err(|e| e.to_string())?;
+    let info: Option<serde_json::Value> = response.take(0).map_err(|e| e.to_string())?;
+    Ok(info
+        .and_then(|v| v.get("tables").and_then(|t| t.as_object()).map(|t| t.keys().cloned().collect()))
+        .unwrap_or_default())
+}
+
+/// Row count of a table, None if the table does not exist
+async fn count_rows(db: &Surreal<Db>, table: &str) -> Result<Option<usize>, String> {
+    let mut response = db
+        .query("SELECT count() AS count FROM type::table($table) GROUP ALL")
+        .bind(("table", table.to_string()))
+        .await
+        .map_err(|e| e.to_string())?;
+    let count: Option<usize> = response.take("count").map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+/// Runs a query and returns the raw top-k chunks with scores, without the
+/// similarity threshold applied to chat retrieval
+pub async fn search_raw(query: &str, top_k: usize) -> Result<Vec<RetrievedChunk>, String> {
     let table = get_document_table().await?;
+    let query_embed = create_embedding_from_query(&table, query).await?;
+    check_dimensions(query_embed.vector().len()).await?;
 
-    // Load and process documents
-    let context_folder_str = context_path.to_string_lossy().to_string();
-    let raw_documents = load_documents_from_folder(&context_folder_str)?;
-    let documents = process_documents(raw_documents).await?;
+    let results = table.search(query_embed)
+        .with_results(top_k)
+        .await
+        .map_err(|e| e.to_string())?;
 
-    // Insert documents (this will add new documents to the existing table)
-    let doc_count = documents.len();
-    for document in documents {
-        if let Err(e) = insert_single_document(&table, document).await {
-            eprintln!("Warning: Failed to insert document: {}", e);
+    Ok(results.into_iter().map(|hit| {
+        let body = hit.record.body();
+        let text = body.get(hit.byte_range.clone()).unwrap_or(body).to_string();
+        RetrievedChunk {
+            title: hit.record.title().to_string(),
+            text,
+            score: hit.distance,
+            passes_threshold: hit.distance >= SIMILARITY_THRESHOLD,
+        }
+    }).collect())
+}
+
+/// Adds a single document to the live index without reloading the folder
+pub async fn index_document(title: &str, body: &str) -> Result<(), String> {
+    let table = get_document_table().await?;
+    insert_single_document(&table, Document::from_parts(title.to_string(), body.to_string())).await?;
+    mark_indexed();
+    Ok(())
+}
+
+/// Removes every indexed document whose title matches, returning how many
+/// were removed
+pub async fn remove_documents(matches: impl Fn(&str) -> bool) -> Result<usize, String> {
+    let table = get_document_table().await?;
+    let mut indexed = INDEXED_DOCUMENTS.lock().await;
+    let titles: Vec<String> = indexed.keys().filter(|title| matches(title)).cloned().collect();
+    for title in &titles {
+        if let Some(id) = indexed.remove(title).and_then(|entry| entry.id) {
+            table.delete(id).await.map_err(|e| e.to_string())?;
         }
     }
+    Ok(titles.len())
+}
 
-    let msg = format!("Loaded {} documents from context folder", doc_count);
-    println!("{}", msg);
-    Ok(msg)
+/// Stored text and chunks of a file in the context folder
+pub async fn document_details(filename: &str) -> Result<DocumentDetails, String> {
+    let path = context_file(filename)?;
+    let document = parse_document_file(path).await?;
+    let details = match INDEXED_DOCUMENTS.lock().await.get(document.title()) {
+        Some(entry) => details_of(filename, entry),
+        None => DocumentDetails {
+            filename: filename.to_string(),
+            title: document.title().to_string(),
+            body: document.body().to_string(),
+            chunks: Vec::new(),
+            metadata: None,
+        },
+    };
+    Ok(with_metadata(details).await)
+}
+
+/// Sets the tags of a file in the context folder
+pub async fn set_document_tags(filename: &str, tags: &[String]) -> Result<DocumentDetails, String> {
+    context_file(filename)?;
+    crate::storage::database::set_document_tags(filename, tags).await.map_err(|e| e.to_string())?;
+    document_details(filename).await
+}
+
+/// Removes one chunk of a document from the index; the rest stay searchable
+pub async fn remove_chunk(filename: &str, index: usize) -> Result<DocumentDetails, String> {
+    Ok(with_metadata(update_chunk(filename, index, false).await?).await)
+}
+
+/// Embeds one chunk of a document again with the current embedding model
+pub async fn reembed_chunk(filename: &str, index: usize) -> Result<DocumentDetails, String> {
+    Ok(with_metadata(update_chunk(filename, index, true).await?).await)
+}
+
+/// Embeds or drops one chunk and stores the document again with its
+/// embedded chunks
+async fn update_chunk(filename: &str, index: usize, embed: bool) -> Result<DocumentDetails, String> {
+    let path = context_file(filename)?;
+    let title = parse_document_file(path).await?.title().to_string();
+    let table = get_document_table().await?;
+    let mut indexed = INDEXED_DOCUMENTS.lock().await;
+    let entry = indexed.get_mut(&title).ok_or("Document is not indexed yet. Reload the database first.")?;
+    let range = entry.chunks.get(index).map(|(range, _)| range.clone()).ok_or("Chunk not found")?;
+
+    entry.chunks[index].1 = if embed {
+        let text = entry.document.body().get(range).unwrap_or_default();
+        let embedding = table.embedding_model().embed(text).await.map_err(|e| e.to_string())?;
+        check_dimensions(embedding.vector().len()).await?;
+        Some(vec![embedding])
+    } else {
+        None
+    };
+
+    if let Some(id) = entry.id.take() {
+        table.delete(id).await.map_err(|e| e.to_string())?;
+    }
+    let chunks: Vec<Chunk> = entry.chunks.iter()
+        .filter_map(|(range, embeddings)| embeddings.clone().map(|embeddings| Chunk { byte_range: range.clone(), embeddings }))
+        .collect();
+    if !chunks.is_empty() {
+        let id = table.table().insert(chunks, entry.document.clone()).await.map_err(|e| e.to_string())?;
+        entry.id = Some(id);
+    }
+    mark_indexed();
+    Ok(details_of(filename, entry))
+}
+
+/// Path of a file directly in the context folder
+fn context_file(filename: &str) -> Result<PathBuf, String> {
+    if filename.is_empty() || filename.contains(['/', '\\']) || filename.contains("..") {
+        return Err(format!("Invalid filename: {}", filename));
+    }
+    Ok(get_context_folder().join(filename))
+}
+
+/// Adds the file's metadata to the details of a top-level context file
+async fn with_metadata(mut details: DocumentDetails) -> DocumentDetails {
+    let root = get_context_folder();
+    let tags = crate::storage::database::get_document_tags().await.unwrap_or_default();
+    details.metadata = file_metadata(&root, &root.join(&details.filename), &tags).await.map(|(_, metadata)| metadata);
+    details
+}
+
+fn details_of(filename: &str, entry: &IndexedDocument) -> DocumentDetails {
+    let body = entry.document.body();
+    DocumentDetails {
+        filename: filename.to_string(),
+        title: entry.document.title().to_string(),
+        body: body.to_string(),
+        chunks: entry.chunks.iter().enumerate().map(|(index, (range, embeddings))| DocumentChunk {
+            index,
+            start: range.start,
+            end: range.end,
+            text: body.get(range.clone()).unwrap_or_default().to_string(),
+            embedded: embeddings.is_some(),
+        }).collect(),
+        metadata: None,
+    }
 }
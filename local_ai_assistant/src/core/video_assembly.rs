@@ -0,0 +1,311 @@
+//! Video Assembly
+//!
+//! Joins a content package's clips into one MP4 with `ffmpeg`. Each clip is
+//! scaled into a common frame, title cards are drawn before the clips that
+//! have one, and the narration becomes the soundtrack. When the narration
+//! runs longer than the clips, the last frame is held until it ends.
+//! Subtitles are written next to the video and can be burned into it.
+//! Every clip and assembled video gets a JPEG thumbnail beside it.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::models::{
+    AssembledVideo, NetworkFeature, SubtitleSource, SubtitleTrack, TranscriptSegment, VideoClip, THUMBNAIL_WIDTH, TITLE_CARD_SECONDS,
+    VIDEO_ESTIMATE_BYTES, VIDEO_FPS, VIDEO_SIZE, concat_list, fit_filter, thumbnail_time, to_srt, to_vtt, wrap_title,
+};
+use super::model_manager::ModelManager;
+
+/// Largest clip accepted, downloaded or copied
+pub const MAX_CLIP_BYTES: usize = 500 * 1024 * 1024;
+
+/// Largest thumbnail image downloaded from a provider
+const MAX_THUMBNAIL_BYTES: usize = 10 * 1024 * 1024;
+
+/// Saves a clip into `dir` so it outlives the provider's temporary URL.
+/// `source` is an http(s) URL or a video file on this machine.
+pub async fn save_clip(source: &str, dir: &Path) -> Result<PathBuf, String> {
+    // A local file is copied at its size; a download is taken as a typical clip
+    let needed = std::fs::metadata(source).map(|m| m.len()).unwrap_or(VIDEO_ESTIMATE_BYTES);
+    ModelManager::check_disk_space(dir, needed, "saving the video clip").await.map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.mp4", Uuid::new_v4()));
+
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let mut response = super::http::get(NetworkFeature::VideoGeneration, source).await.map_err(|e| format!("Failed to fetch the clip: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Download failed with status {}", response.status()));
+        }
+        let mut data = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| format!("Download failed: {}", e))? {
+            if data.len() + chunk.len() > MAX_CLIP_BYTES {
+                return Err(format!("Clips up to {} MB can be added", MAX_CLIP_BYTES / (1024 * 1024)));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        std::fs::write(&path, &data).map_err(|e| e.to_string())?;
+    } else {
+        let file = Path::new(source);
+        let size = std::fs::metadata(file).map_err(|_| format!("Video file not found: {}", source))?.len();
+        if size as usize > MAX_CLIP_BYTES {
+            return Err(format!("Clips up to {} MB can be added", MAX_CLIP_BYTES / (1024 * 1024)));
+        }
+        std::fs::copy(file, &path).map_err(|e| format!("Failed to copy the clip: {}", e))?;
+    }
+
+    // Catch pages and broken downloads now rather than at assembly
+    if let Err(e) = probe_duration(&path).await {
+        let _ = std::fs::remove_file(&path);
+        return Err(format!("Not a playable video: {}", e));
+    }
+    Ok(path)
+}
+
+/// Joins the clips in order and writes the video to `output`
+pub async fn assemble(clips: &[VideoClip], narration: Option<&Path>, output: &Path) -> Result<AssembledVideo, String> {
+    if clips.is_empty() {
+        return Err("Add clips to the timeline first".to_string());
+    }
+    // Parts are re-encoded in a temporary folder, then joined into the
+    // output, each about the size of the clips
+    let needed: u64 = clips.iter()
+        .map(|clip| Path::new(&clip.path))
+        .chain(narration)
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|m| m.len())
+        .sum();
+    for dir in [std::env::temp_dir(), output.parent().map(Path::to_path_buf).unwrap_or_default()] {
+        ModelManager::check_disk_space(&dir, needed, "assembling the video").await.map_err(|e| e.to_string())?;
+    }
+    let work_dir = std::env::temp_dir().join(format!("idoris-video-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&work_dir).map_err(|e| e.to_string())?;
+    let result = assemble_in(clips, narration, output, &work_dir).await;
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result
+}
+
+async fn assemble_in(clips: &[VideoClip], narration: Option<&Path>, output: &Path, work_dir: &Path) -> Result<AssembledVideo, String> {
+    // Every part is re-encoded to the same size, rate and codec so they can
+    // be joined without another encode
+    let mut parts = Vec::new();
+    for (i, clip) in clips.iter().enumerate() {
+        if let Some(title) = clip.title.as_deref().map(wrap_title).filter(|t| !t.is_empty()) {
+            let card = format!("card-{}.mp4", i);
+            render_title_card(&title, &work_dir.join(&card), work_dir).await?;
+            parts.push(card);
+        }
+        let part = format!("part-{}.mp4", i);
+        run_ffmpeg(work_dir, |cmd| {
+            cmd.arg("-i").arg(&clip.path)
+                .args(["-an", "-vf", &fit_filter()])
+                .args(["-c:v", "libx264", "-preset", "veryfast", "-crf", "20"])
+                .arg(&part);
+        })
+        .await
+        .map_err(|e| format!("Clip {} could not be converted: {}", i + 1, e))?;
+        parts.push(part);
+    }
+
+    std::fs::write(work_dir.join("parts.txt"), concat_list(&parts)).map_err(|e| e.to_string())?;
+    let joined = work_dir.join("joined.mp4");
+    run_ffmpeg(work_dir, |cmd| {
+        cmd.args(["-f", "concat", "-safe", "0", "-i", "parts.txt", "-c", "copy"]).arg(&joined);
+    })
+    .await?;
+    let video_seconds = probe_duration(&joined).await?;
+
+    if let Some(dir) = output.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let duration = match narration {
+        Some(audio) => {
+            let audio_seconds = probe_duration(audio).await?;
+            let hold = audio_seconds - video_seconds;
+            run_ffmpeg(work_dir, |cmd| {
+                cmd.arg("-i").arg(&joined).arg("-i").arg(audio).args(["-map", "0:v:0", "-map", "1:a:0"]);
+                if hold > 0.05 {
+                    cmd.args(["-vf", &format!("tpad=stop_mode=clone:stop_duration={:.2}", hold)])
+                        .args(["-c:v", "libx264", "-preset", "veryfast", "-crf", "20"]);
+                } else {
+                    cmd.args(["-c:v", "copy"]);
+                }
+                cmd.args(["-c:a", "aac", "-b:a", "160k", "-movflags", "+faststart"]).arg(output);
+            })
+            .await?;
+            video_seconds.max(audio_seconds)
+        }
+        None => {
+            run_ffmpeg(work_dir, |cmd| {
+                cmd.arg("-i").arg(&joined).args(["-c", "copy", "-movflags", "+faststart"]).arg(output);
+            })
+            .await?;
+            video_seconds
+        }
+    };
+
+    Ok(AssembledVideo {
+        path: output.display().to_string(),
+        duration_ms: (duration * 1000.0) as u32,
+        clip_count: clips.len(),
+        with_narration: narration.is_some(),
+        subtitles: None,
+        thumbnail: None,
+    })
+}
+
+/// Saves a thumbnail next to the video (`clip.mp4` → `clip.jpg`), from the
+/// provider's thumbnail when there is one, otherwise from a frame of the
+/// video. Thumbnails are only a preview, so failures are logged and `None`
+/// returned.
+pub async fn save_thumbnail(video: &Path, provider_url: Option<&str>) -> Option<PathBuf> {
+    let output = video.with_extension("jpg");
+    if let Some(url) = provider_url.filter(|u| u.starts_with("http://") || u.starts_with("https://")) {
+        match download_thumbnail(url, &output).await {
+            Ok(()) => return Some(output),
+            Err(e) => tracing::warn!("Provider thumbnail unavailable, using a video frame: {}", e),
+        }
+    }
+    match extract_thumbnail(video, &output).await {
+        Ok(()) => Some(output),
+        Err(e) => {
+            tracing::warn!("Could not make a thumbnail for {:?}: {}", video, e);
+            None
+        }
+    }
+}
+
+/// Grabs one frame of the video, scaled to `THUMBNAIL_WIDTH`
+async fn extract_thumbnail(video: &Path, output: &Path) -> Result<(), String> {
+    let at = thumbnail_time(probe_duration(video).await?);
+    let dir = output.parent().ok_or("Invalid thumbnail path")?;
+    run_ffmpeg(dir, |cmd| {
+        cmd.args(["-ss", &format!("{:.2}", at)])
+            .arg("-i").arg(video)
+            .args(["-frames:v", "1", "-vf", &format!("scale={}:-2", THUMBNAIL_WIDTH), "-q:v", "4"])
+            .arg(output);
+    })
+    .await
+}
+
+/// Downloads the provider's thumbnail and re-encodes it to the usual size,
+/// which also checks that it is an image
+async fn download_thumbnail(url: &str, output: &Path) -> Result<(), String> {
+    let response = super::http::get(NetworkFeature::VideoGeneration, url).await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("status {}", response.status()));
+    }
+    let data = response.bytes().await.map_err(|e| e.to_string())?;
+    if data.len() > MAX_THUMBNAIL_BYTES {
+        return Err("thumbnail is too large".to_string());
+    }
+    let output = output.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let image = image::load_from_memory(&data).map_err(|e| e.to_string())?;
+        let height = (THUMBNAIL_WIDTH as f64 * image.height() as f64 / image.width().max(1) as f64).round() as u32;
+        image.thumbnail(THUMBNAIL_WIDTH, height.max(1)).to_rgb8().save(&output).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Writes `subtitles.srt` and `subtitles.vtt` next to the video and, with
+/// `burn_in`, draws the subtitles into the video itself
+pub async fn write_subtitles(
+    cues: &[TranscriptSegment],
+    source: SubtitleSource,
+    video: &Path,
+    burn_in: bool,
+) -> Result<SubtitleTrack, String> {
+    if cues.is_empty() {
+        return Err("There is no text for subtitles".to_string());
+    }
+    let dir = video.parent().ok_or("Invalid video path")?;
+    let srt = dir.join("subtitles.srt");
+    let vtt = dir.join("subtitles.vtt");
+    std::fs::write(&srt, to_srt(cues)).map_err(|e| e.to_string())?;
+    std::fs::write(&vtt, to_vtt(cues)).map_err(|e| e.to_string())?;
+
+    if burn_in {
+        // Run in the video's folder so the filter needs no path escaping
+        let burned = dir.join("video-subtitled.mp4");
+        run_ffmpeg(dir, |cmd| {
+            cmd.arg("-i").arg(video)
+                .args(["-vf", "subtitles=subtitles.srt:force_style='FontSize=22,Outline=2'"])
+                .args(["-c:v", "libx264", "-preset", "veryfast", "-crf", "20", "-c:a", "copy", "-movflags", "+faststart"])
+                .arg(&burned);
+        })
+        .await
+        .map_err(|e| format!("Subtitles could not be burned in: {}", e))?;
+        std::fs::rename(&burned, video).map_err(|e| e.to_string())?;
+    }
+
+    Ok(SubtitleTrack {
+        srt_path: srt.display().to_string(),
+        vtt_path: vtt.display().to_string(),
+        source,
+        cue_count: cues.len(),
+        burned_in: burn_in,
+    })
+}
+
+/// White text centred on black. The text is read from a file so it needs
+/// no escaping in the filter.
+async fn render_title_card(title: &str, output: &Path, work_dir: &Path) -> Result<(), String> {
+    let text_file = output.with_extension("txt");
+    std::fs::write(&text_file, title).map_err(|e| e.to_string())?;
+    let (w, h) = VIDEO_SIZE;
+    let source = format!("color=c=black:s={}x{}:r={}:d={}", w, h, VIDEO_FPS, TITLE_CARD_SECONDS);
+    let text = format!(
+        "drawtext=textfile='{}':expansion=none:fontcolor=white:fontsize={}:line_spacing=12:x=(w-text_w)/2:y=(h-text_h)/2,format=yuv420p",
+        text_file.file_name().and_then(|n| n.to_str()).unwrap_or_default(),
+        h / 12,
+    );
+    run_ffmpeg(work_dir, |cmd| {
+        cmd.args(["-f", "lavfi", "-i", &source, "-vf", &text])
+            .args(["-c:v", "libx264", "-preset", "veryfast", "-crf", "20"])
+            .arg(output);
+    })
+    .await
+    .map_err(|e| format!("Title card could not be drawn: {}", e))
+}
+
+/// Runs ffmpeg in `work_dir` with the arguments added by `args`
+async fn run_ffmpeg(work_dir: &Path, args: impl FnOnce(&mut Command)) -> Result<(), String> {
+    let mut cmd = Command::new("ffmpeg");
+    cmd.current_dir(work_dir).args(["-nostdin", "-y", "-loglevel", "error"]);
+    args(&mut cmd);
+    let result = cmd
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|_| "ffmpeg is not installed (the `ffmpeg` command was not found)".to_string())?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        let message = stderr.lines().map(str::trim).rfind(|l| !l.is_empty()).unwrap_or("ffmpeg failed");
+        return Err(message.to_string());
+    }
+    Ok(())
+}
+
+/// Length of a media file in seconds
+async fn probe_duration(file: &Path) -> Result<f64, String> {
+    let result = Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "format=duration", "-of", "default=noprint_wrappers=1:nokey=1"])
+        .arg(file)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|_| "ffprobe is not installed (it comes with ffmpeg)".to_string())?;
+    String::from_utf8_lossy(&result.stdout)
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|seconds| *seconds > 0.0)
+        .ok_or_else(|| "could not read its length".to_string())
+}
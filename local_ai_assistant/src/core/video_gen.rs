@@ -3,7 +3,7 @@
 
 use std::time::Duration;
 use std::collections::BTreeMap;
-use crate::models::{VideoProvider, VideoModel, VideoConfig, VideoQuality};
+use crate::models::{NetworkFeature, VideoProvider, VideoModel, VideoConfig, VideoQuality};
 use hmac::{Hmac, Mac};
 use sha2::{Sha256, Digest};
 use hex;
@@ -261,6 +261,9 @@ impl VideoGenerator {
 
     pub async fn generate_video(&self, request: VideoRequest) -> Result<VideoResponse, anyhow::Error> {
         let cost_estimate = self.estimate_cost(&request);
+        if super::mock::is_enabled() {
+            return Ok(super::mock::video(&request, cost_estimate));
+        }
 
         match request.provider {
             VideoProvider::OpenRouter => self.generate_with_openrouter(request, cost_estimate).await,
@@ -301,16 +304,15 @@ impl VideoGenerator {
             api_request["seed"] = serde_json::Value::Number(seed.into());
         }
 
-        let client = reqwest::Client::new();
-        let response = client
+        let client = super::http::client();
+        let http_request = client
             .post(&format!("{}/video/generations", config.base_url))
             .header("Authorization", format!("Bearer {}", config.api_key))
             .header("HTTP-Referer", "https://idoris.local")
             .header("X-Title", "iDoris Content Creator")
             .json(&api_request)
-            .timeout(config.timeout)
-            .send()
-            .await?;
+            .timeout(config.timeout);
+        let response = super::http::send(NetworkFeature::VideoGeneration, http_request).await?;
 
         if !response.status().is_success() {
             let error_text = response.text().await?;
@@ -341,16 +343,16 @@ impl VideoGenerator {
     }
 
     async fn generate_with_bytedance(&self, request: VideoRequest, cost_estimate: f64) -> Result<VideoResponse, anyhow::Error> {
-        println!("Starting generate_with_bytedance...");
+        tracing::info!("Starting generate_with_bytedance...");
         let config = self.configs.get(&VideoProvider::ByteDance)
             .ok_or_else(|| anyhow::anyhow!("ByteDance config not found"))?;
 
         if config.access_key_id.is_empty() || config.secret_access_key.is_empty() {
-            println!("Error: ByteDance keys missing");
+            tracing::error!("Error: ByteDance keys missing");
             return Err(anyhow::anyhow!("ByteDance Access Key ID or Secret Access Key not configured. Please set Access_Key_ID and Secret_Access_Key in .env file."));
         }
 
-        let client = reqwest::Client::new();
+        let client = super::http::client();
         let region = "cn-north-1";
         let service = "cv";
         let host = "visual.volcengineapi.com";
@@ -375,14 +377,14 @@ impl VideoGenerator {
             "req_key": "jimeng_t2v_v30_1080p", // Video 3.0
             "prompt": request.prompt,
             "seed": seed,
-            "frames": 121, // Default to 5s (24*5+1)
-            "aspect_ratio": "16:9" // Default
+            "frames": if request.config.duration_seconds >= 10 { 241 } else { 121 }, // 24 fps plus one frame
+            "aspect_ratio": crate::models::aspect_ratio_label(request.config.width, request.config.height).unwrap_or("16:9")
         });
         let payload = req_body.to_string();
-        println!("Request Payload: {}", payload);
+        tracing::debug!("Request Payload: {}", payload);
 
         // Debug: Print keys (masked)
-        println!("Using AccessKey: {}...", &config.access_key_id.chars().take(4).collect::<String>());
+        tracing::debug!("Using AccessKey: {}...", &config.access_key_id.chars().take(4).collect::<String>());
 
         // Prepare headers for signature
         // NOTE: We do NOT include X-Content-Sha256 in the headers map passed to signing, 
@@ -405,29 +407,28 @@ impl VideoGenerator {
             service,
             &date_iso
         );
-        println!("Generated Authorization: {}", auth);
+        tracing::debug!("Generated Authorization: {}", auth);
 
-        let submit_resp = client.post(format!("https://{}?{}", host, query))
+        let submit_request = client.post(format!("https://{}?{}", host, query))
             .header("Authorization", auth)
             .header("Content-Type", "application/json")
             .header("Host", host)
             .header("X-Date", date_iso)
             // .header("X-Content-Sha256", ...) // Not sending this header either
-            .body(payload)
-            .send()
-            .await?;
+            .body(payload);
+        let submit_resp = super::http::send(NetworkFeature::VideoGeneration, submit_request).await?;
 
         let status = submit_resp.status();
-        println!("Submit Response Status: {}", status);
+        tracing::info!("Submit Response Status: {}", status);
 
         if !status.is_success() {
             let error_text = submit_resp.text().await?;
-            println!("Submit Response Error Body: {}", error_text);
+            tracing::error!("Submit Response Error Body: {}", error_text);
             return Err(anyhow::anyhow!("ByteDance Submit Task Error: status={}, body={}", status, error_text));
         }
 
         let submit_data: serde_json::Value = submit_resp.json().await?;
-        println!("Submit Response JSON: {:?}", submit_data);
+        tracing::debug!("Submit Response JSON: {:?}", submit_data);
 
         if submit_data["code"].as_i64().unwrap_or(0) != 10000 {
              return Err(anyhow::anyhow!("ByteDance Submit Failed: {}", submit_data["message"]));
@@ -436,7 +437,7 @@ impl VideoGenerator {
         let task_id = submit_data["data"]["task_id"].as_str()
             .ok_or_else(|| anyhow::anyhow!("Task ID not found in response"))?
             .to_string();
-        println!("Task ID received: {}", task_id);
+        tracing::info!("Task ID received: {}", task_id);
 
         // 2. Poll Result
         let action_poll = "CVSync2AsyncGetResult";
@@ -479,20 +480,19 @@ impl VideoGenerator {
                 &date_iso_poll
             );
 
-            let poll_resp = client.post(format!("https://{}?{}", host, query_poll))
+            let poll_request = client.post(format!("https://{}?{}", host, query_poll))
                 .header("Authorization", auth_poll)
                 .header("Content-Type", "application/json")
                 .header("Host", host)
                 .header("X-Date", date_iso_poll)
-                .body(payload_poll)
-                .send()
-                .await;
+                .body(payload_poll);
+            let poll_resp = super::http::send(NetworkFeature::VideoGeneration, poll_request).await;
 
             match poll_resp {
                 Ok(resp) => {
                     if !resp.status().is_success() {
                         let err = resp.text().await.unwrap_or_default();
-                        println!("Poll Error ({}): {}", attempts, err);
+                        tracing::warn!("Poll Error ({}): {}", attempts, err);
                         continue; 
                     }
 
@@ -501,7 +501,7 @@ impl VideoGenerator {
                              // println!("Poll Data: {:?}", data); // Verbose, maybe comment out later
                              if data["code"].as_i64().unwrap_or(0) == 10000 {
                                  let status = data["data"]["status"].as_str().unwrap_or("unknown");
-                                 println!("Poll Status: {}", status);
+                                 tracing::debug!("Poll Status: {}", status);
                                  if status == "done" || status == "success" {
                                      let video_url = data["data"]["video_url"].as_str().unwrap_or("").to_string();
                                      return Ok(VideoResponse {
@@ -517,10 +517,10 @@ impl VideoGenerator {
                                  }
                              }
                         },
-                        Err(e) => println!("Poll JSON parse error: {}", e),
+                        Err(e) => tracing::error!("Poll JSON parse error: {}", e),
                     }
                 },
-                Err(e) => println!("Poll Request error: {}", e),
+                Err(e) => tracing::error!("Poll Request error: {}", e),
             }
         }
     }
@@ -543,15 +543,14 @@ impl VideoGenerator {
             }
         });
 
-        let client = reqwest::Client::new();
-        let response = client
+        let client = super::http::client();
+        let http_request = client
             .post(&format!("{}/services/aigc/text2video/video-synthesis", config.base_url))
             .header("Authorization", format!("Bearer {}", config.api_key))
             .header("X-DashScope-SSE", "disable")
             .json(&api_request)
-            .timeout(config.timeout)
-            .send()
-            .await?;
+            .timeout(config.timeout);
+        let response = super::http::send(NetworkFeature::VideoGeneration, http_request).await?;
 
         if !response.status().is_success() {
             let error_text = response.text().await?;
@@ -580,14 +579,13 @@ impl VideoGenerator {
         }
 
         // 首先获取 access_token
-        let client = reqwest::Client::new();
-        let token_response = client
+        let client = super::http::client();
+        let http_request = client
             .get(&format!(
                 "https://aip.baidubce.com/oauth/2.0/token?grant_type=client_credentials&client_id={}",
                 config.api_key
-            ))
-            .send()
-            .await?;
+            ));
+        let token_response = super::http::send(NetworkFeature::VideoGeneration, http_request).await?;
 
         let token_data: serde_json::Value = token_response.json().await?;
         let access_token = token_data["access_token"].as_str().ok_or_else(|| anyhow::anyhow!("Failed to get access token"))?;
@@ -600,15 +598,14 @@ impl VideoGenerator {
             "video_duration": request.config.duration_seconds,
         });
 
-        let response = client
+        let http_request = client
             .post(&format!(
                 "{}/wenxinworkshop/video/generation/v1?access_token={}",
                 config.base_url, access_token
             ))
             .json(&api_request)
-            .timeout(config.timeout)
-            .send()
-            .await?;
+            .timeout(config.timeout);
+        let response = super::http::send(NetworkFeature::VideoGeneration, http_request).await?;
 
         if !response.status().is_success() {
             let error_text = response.text().await?;
@@ -0,0 +1,165 @@
+//! Web Search
+//!
+//! Optional web search through a self-hosted SearxNG instance or the Brave
+//! Search API. Results ground chat answers with numbered citations and back
+//! the `web_search` tool. Configuration is persisted to
+//! `./data/web_search.json`.
+
+use std::path::PathBuf;
+use std::time::Duration;
+use serde_json::Value;
+
+use crate::models::{NetworkFeature, WebSearchConfig, WebSearchProvider, WebSearchResult};
+
+/// Default number of results used for grounding
+pub const DEFAULT_RESULT_COUNT: usize = 5;
+/// Per-request timeout for search backends
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(15);
+const BRAVE_ENDPOINT: &str = "https://api.search.brave.com/res/v1/web/search";
+
+fn config_path() -> PathBuf {
+    crate::storage::database::get_data_dir().join("web_search.json")
+}
+
+/// Current configuration, disabled if none was saved
+pub fn load_config() -> WebSearchConfig {
+    std::fs::read_to_string(config_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_config(config: &WebSearchConfig) -> Result<(), String> {
+    if config.provider == WebSearchProvider::Searxng
+        && !config.searxng_url.starts_with("http://")
+        && !config.searxng_url.starts_with("https://")
+    {
+        return Err("SearxNG URL must start with http:// or https://".to_string());
+    }
+
+    let path = config_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+pub fn is_enabled() -> bool {
+    load_config().is_configured()
+}
+
+/// Searches the web with the configured provider
+pub async fn search(query: &str, limit: usize) -> Result<Vec<WebSearchResult>, String> {
+    let config = load_config();
+    if !config.is_configured() {
+        return Err("Web search is not configured".to_string());
+    }
+
+    let client = super::http::client();
+    let count = limit.to_string();
+    let request = match config.provider {
+        WebSearchProvider::Searxng => client
+            .get(format!("{}/search", config.searxng_url.trim_end_matches('/')))
+            .query(&[("q", query), ("format", "json")]),
+        WebSearchProvider::Brave => client
+            .get(BRAVE_ENDPOINT)
+            .query(&[("q", query), ("count", count.as_str())])
+            .header("Accept", "application/json")
+            .header("X-Subscription-Token", config.brave_api_key.trim()),
+        WebSearchProvider::Disabled => unreachable!("checked by is_configured"),
+    };
+    let response = super::http::send(NetworkFeature::WebSearch, request.timeout(SEARCH_TIMEOUT))
+        .await
+        .map_err(|e| format!("Web search request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("{} returned {}", config.provider.display_name(), status));
+    }
+
+    let body: Value = response.json().await.map_err(|e| format!("Invalid search response: {}", e))?;
+    let mut results = match config.provider {
+        WebSearchProvider::Brave => parse_brave(&body),
+        _ => parse_searxng(&body),
+    };
+    results.truncate(limit);
+    tracing::info!("Web search for '{}' returned {} results", query, results.len());
+    Ok(results)
+}
+
+fn parse_searxng(body: &Value) -> Vec<WebSearchResult> {
+    parse_results(body.get("results"), "content")
+}
+
+fn parse_brave(body: &Value) -> Vec<WebSearchResult> {
+    parse_results(body.get("web").and_then(|w| w.get("results")), "description")
+}
+
+fn parse_results(results: Option<&Value>, snippet_field: &str) -> Vec<WebSearchResult> {
+    results
+        .and_then(Value::as_array)
+        .map(|items| {
+            items.iter()
+                .filter_map(|item| {
+                    let url = item.get("url")?.as_str()?.to_string();
+                    let title = item.get("title").and_then(Value::as_str).unwrap_or(&url).to_string();
+                    let snippet = item.get(snippet_field).and_then(Value::as_str).unwrap_or_default();
+                    Some(WebSearchResult { title: strip_tags(&title), url, snippet: strip_tags(snippet) })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Removes inline highlight markup such as `<strong>` from snippets
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_searxng() {
+        let body = json!({ "results": [
+            { "title": "Rust", "url": "https://rust-lang.org", "content": "A language" },
+            { "title": "No URL" }
+        ]});
+        let results = parse_searxng(&body);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].snippet, "A language");
+    }
+
+    #[test]
+    fn test_parse_brave_strips_markup() {
+        let body = json!({ "web": { "results": [
+            { "title": "Dioxus", "url": "https://dioxuslabs.com", "description": "<strong>Fullstack</strong> apps" }
+        ]}});
+        let results = parse_brave(&body);
+        assert_eq!(results[0].snippet, "Fullstack apps");
+        assert!(parse_brave(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_is_configured() {
+        let mut config = WebSearchConfig::default();
+        assert!(!config.is_configured());
+        config.provider = WebSearchProvider::Brave;
+        assert!(!config.is_configured());
+        config.brave_api_key = "key".into();
+        assert!(config.is_configured());
+    }
+}
@@ -0,0 +1,142 @@
+//! Outgoing Webhooks
+//!
+//! Delivers a JSON payload to every enabled webhook subscribed to an event.
+//! Webhooks are persisted to `./data/webhooks.json`. Delivery runs in the
+//! background and never fails the job that triggered it.
+//!
+//! Payload:
+//! ```json
+//! { "event": "video.completed", "timestamp": "2025-01-01T00:00:00Z", "data": { ... } }
+//! ```
+//! If a webhook has a secret, the hex HMAC-SHA256 of the body is sent in
+//! the `X-IDoris-Signature` header.
+
+use std::path::PathBuf;
+use std::time::Duration;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::models::{NetworkFeature, Webhook, WebhookEvent};
+
+/// Per-request timeout for webhook delivery
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn config_path() -> PathBuf {
+    crate::storage::database::get_data_dir().join("webhooks.json")
+}
+
+/// Configured webhooks
+pub fn list() -> Vec<Webhook> {
+    std::fs::read_to_string(config_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Replaces the configured webhooks
+pub fn save(webhooks: &[Webhook]) -> Result<(), String> {
+    for webhook in webhooks {
+        if !webhook.url.starts_with("http://") && !webhook.url.starts_with("https://") {
+            return Err(format!("Webhook '{}' must use an http(s) URL", webhook.name));
+        }
+    }
+
+    let path = config_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(webhooks).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// Sends the event to all subscribed webhooks in the background
+pub fn fire(event: WebhookEvent, data: serde_json::Value) {
+    let targets: Vec<Webhook> = list().into_iter().filter(|w| w.subscribes_to(event)).collect();
+    if targets.is_empty() {
+        return;
+    }
+
+    let body = build_payload(event, data).to_string();
+    tokio::spawn(async move {
+        for webhook in targets {
+            if let Err(e) = deliver(&webhook, &body).await {
+                tracing::warn!("Webhook '{}' failed for {}: {}", webhook.name, event.id(), e);
+            }
+        }
+    });
+}
+
+/// Sends a sample payload to one webhook and reports the result
+pub async fn send_test(webhook: &Webhook) -> Result<String, String> {
+    let body = build_payload(
+        *webhook.events.first().unwrap_or(&WebhookEvent::VideoCompleted),
+        serde_json::json!({ "test": true }),
+    ).to_string();
+    deliver(webhook, &body).await
+}
+
+async fn deliver(webhook: &Webhook, body: &str) -> Result<String, String> {
+    let mut request = super::http::client()
+        .post(&webhook.url)
+        .timeout(DELIVERY_TIMEOUT)
+        .header("Content-Type", "application/json")
+        .body(body.to_string());
+    if let Some(secret) = webhook.secret.as_deref().filter(|s| !s.is_empty()) {
+        request = request.header("X-IDoris-Signature", sign(secret, body));
+    }
+
+    let response = super::http::send(NetworkFeature::Webhooks, request).await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    if status.is_success() {
+        tracing::info!("Webhook '{}' delivered ({})", webhook.name, status);
+        Ok(format!("Delivered ({})", status))
+    } else {
+        Err(format!("Endpoint returned {}", status))
+    }
+}
+
+fn build_payload(event: WebhookEvent, data: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "event": event.id(),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "data": data,
+    })
+}
+
+/// Hex-encoded HMAC-SHA256 of the body
+fn sign(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_payload() {
+        let payload = build_payload(WebhookEvent::ContentExported, serde_json::json!({ "title": "Post" }));
+        assert_eq!(payload["event"], "content.exported");
+        assert_eq!(payload["data"]["title"], "Post");
+        assert!(payload["timestamp"].is_string());
+    }
+
+    #[test]
+    fn test_sign_matches_known_vector() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_subscribes_only_when_enabled() {
+        let mut webhook = Webhook::new("n8n".into(), "http://localhost:5678".into(), vec![WebhookEvent::VideoCompleted]);
+        assert!(webhook.subscribes_to(WebhookEvent::VideoCompleted));
+        assert!(!webhook.subscribes_to(WebhookEvent::ContentExported));
+        webhook.enabled = false;
+        assert!(!webhook.subscribes_to(WebhookEvent::VideoCompleted));
+    }
+}
@@ -0,0 +1,246 @@
+//! YouTube Ingestion
+//!
+//! Adds a YouTube video to the knowledge base as two context documents: a
+//! summary and the full timestamped transcript. The transcript comes from
+//! the video's captions, preferring ones written by a person over
+//! auto-generated ones; videos without captions are downloaded with
+//! `yt-dlp` and transcribed locally with Whisper.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+
+use crate::models::{JobKind, NetworkFeature, TranscriptSegment, VideoTranscript, transcript_markdown, youtube_video_id};
+use super::jobs::{JobHandle, JobSpec};
+use super::{attachments, quick_capture, transcription, vector_store};
+
+/// Details read from a video's watch page
+struct VideoPage {
+    title: String,
+    /// Caption track URL, if the video has captions
+    captions_url: Option<String>,
+}
+
+/// Fetches or transcribes the video's transcript, summarizes it and adds
+/// both to the knowledge base. Tracked in the Jobs panel.
+pub async fn ingest(url: &str, collection: Option<String>) -> Result<VideoTranscript, String> {
+    let video_id = youtube_video_id(url).ok_or("Not a YouTube video link")?;
+    quick_capture::collection_dir(collection.as_deref())?;
+
+    JobSpec::new(JobKind::Transcription, format!("YouTube video {}", video_id))
+        .run(move |job| run(job, video_id, collection))
+        .await
+}
+
+async fn run(job: JobHandle, video_id: String, collection: Option<String>) -> Result<VideoTranscript, String> {
+    let url = format!("https://www.youtube.com/watch?v={}", video_id);
+    job.log("Reading video page");
+    let page = fetch_video_page(&url).await?;
+    job.set_progress(0.1);
+
+    let captions = match &page.captions_url {
+        Some(captions_url) => {
+            job.log("Downloading captions");
+            match fetch_captions(captions_url).await {
+                Ok(segments) if !segments.is_empty() => Some(segments),
+                Ok(_) => None,
+                Err(e) => {
+                    job.log(format!("Captions unavailable: {}", e));
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+    let transcribed_locally = captions.is_none();
+    let segments = match captions {
+        Some(segments) => segments,
+        None => {
+            job.log("No captions, transcribing the audio locally");
+            transcribe_locally(&url, &job).await?
+        }
+    };
+    job.set_progress(0.5);
+
+    job.log("Summarizing transcript");
+    let text = segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ");
+    let summary = attachments::summarize(&page.title, &text).await?;
+    job.set_progress(0.9);
+
+    let dir = quick_capture::collection_dir(collection.as_deref())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    // The vector store uses the first line as the document title
+    let summary_title = format!("{} (summary)", page.title);
+    let summary_filename = quick_capture::unique_filename(&dir, &summary_title);
+    let summary_content = format!("# {}\n\nSummary of the YouTube video {}\n\n{}\n", summary_title, url, summary);
+    std::fs::write(dir.join(&summary_filename), &summary_content).map_err(|e| e.to_string())?;
+
+    let transcript_title = format!("{} (transcript)", page.title);
+    let transcript_filename = quick_capture::unique_filename(&dir, &transcript_title);
+    let transcript_content = transcript_markdown(&transcript_title, &url, &segments);
+    std::fs::write(dir.join(&transcript_filename), &transcript_content).map_err(|e| e.to_string())?;
+    job.log(format!("Saved {} and {}", summary_filename, transcript_filename));
+
+    let mut indexed = true;
+    for (title, content) in [(&summary_title, &summary_content), (&transcript_title, &transcript_content)] {
+        if let Err(e) = vector_store::index_document(&format!("# {}", title), content).await {
+            job.log(format!("Saved but not indexed yet: {}", e));
+            indexed = false;
+        }
+    }
+    job.set_progress(1.0);
+
+    Ok(VideoTranscript {
+        video_id,
+        title: page.title,
+        url,
+        summary,
+        summary_filename,
+        transcript_filename,
+        transcribed_locally,
+        indexed,
+    })
+}
+
+/// Reads the title and caption tracks from the player data embedded in the
+/// watch page
+async fn fetch_video_page(url: &str) -> Result<VideoPage, String> {
+    let request = super::http::client()
+        .get(url)
+        .header(reqwest::header::ACCEPT_LANGUAGE, "en");
+    let html = super::http::send(NetworkFeature::YouTube, request)
+        .await
+        .map_err(|e| format!("Failed to fetch the video page: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read the video page: {}", e))?;
+    parse_video_page(&html)
+}
+
+fn parse_video_page(html: &str) -> Result<VideoPage, String> {
+    let start = html.find("ytInitialPlayerResponse = ")
+        .map(|i| i + "ytInitialPlayerResponse = ".len())
+        .ok_or("The video page has no player data; the video may be private or removed")?;
+    // Reads the one JSON object that follows, ignoring the script after it
+    let player: serde_json::Value = serde_json::Deserializer::from_str(&html[start..])
+        .into_iter()
+        .next()
+        .ok_or("Invalid player data")?
+        .map_err(|e| format!("Invalid player data: {}", e))?;
+
+    let title = player.pointer("/videoDetails/title")
+        .and_then(|t| t.as_str())
+        .unwrap_or("YouTube video")
+        .to_string();
+    let tracks = player.pointer("/captions/playerCaptionsTracklistRenderer/captionTracks")
+        .and_then(|t| t.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let is_generated = |track: &serde_json::Value| track.get("kind").and_then(|k| k.as_str()) == Some("asr");
+    let captions_url = tracks.iter()
+        .find(|t| !is_generated(t))
+        .or_else(|| tracks.first())
+        .and_then(|t| t.get("baseUrl")?.as_str())
+        .map(|base| format!("{}&fmt=json3", base));
+
+    Ok(VideoPage { title, captions_url })
+}
+
+async fn fetch_captions(url: &str) -> Result<Vec<TranscriptSegment>, String> {
+    let json = super::http::get(NetworkFeature::YouTube, url)
+        .await
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    parse_json3_captions(&json)
+}
+
+/// Segments from YouTube's `json3` caption format
+fn parse_json3_captions(json: &str) -> Result<Vec<TranscriptSegment>, String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| format!("Invalid captions: {}", e))?;
+    let events = value.get("events").and_then(|e| e.as_array()).ok_or("Invalid captions: no events")?;
+    Ok(events.iter().filter_map(|event| {
+        let start_ms = event.get("tStartMs")?.as_u64()?;
+        let duration_ms = event.get("dDurationMs").and_then(|d| d.as_u64()).unwrap_or(0);
+        let text: String = event.get("segs")?.as_array()?
+            .iter()
+            .filter_map(|seg| seg.get("utf8")?.as_str())
+            .collect();
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        (!text.is_empty()).then_some(TranscriptSegment { start_ms, end_ms: start_ms + duration_ms, text })
+    }).collect())
+}
+
+/// Downloads the audio track with `yt-dlp` and transcribes it with Whisper
+async fn transcribe_locally(url: &str, job: &JobHandle) -> Result<Vec<TranscriptSegment>, String> {
+    let work_dir = std::env::temp_dir().join(format!("idoris-youtube-{}", job.id()));
+    std::fs::create_dir_all(&work_dir).map_err(|e| e.to_string())?;
+    let result = async {
+        let audio = download_audio(url, &work_dir).await?;
+        job.log("Transcribing with Whisper");
+        transcription::transcribe_audio(&audio, &work_dir).await
+    }.await;
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result
+}
+
+async fn download_audio(url: &str, work_dir: &Path) -> Result<PathBuf, String> {
+    let result = Command::new("yt-dlp")
+        .args(["--no-playlist", "-f", "bestaudio", "-o"])
+        .arg(work_dir.join("audio.%(ext)s"))
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|_| "The video has no captions and yt-dlp is not installed to download its audio".to_string())?;
+    let audio = std::fs::read_dir(work_dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| p.file_stem().and_then(|s| s.to_str()) == Some("audio"));
+    let size = audio.as_ref().and_then(|p| std::fs::metadata(p).ok()).map(|m| m.len());
+    let error = (!result.status.success()).then(|| "download failed".to_string());
+    super::http::record_external(NetworkFeature::YouTube, "yt-dlp", url, size, error);
+    if !result.status.success() {
+        return Err("yt-dlp could not download the video's audio".to_string());
+    }
+    audio.ok_or("yt-dlp did not produce an audio file".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_video_page_prefers_manual_captions() {
+        let html = r#"<script>var ytInitialPlayerResponse = {"videoDetails": {"title": "Talk"},
+            "captions": {"playerCaptionsTracklistRenderer": {"captionTracks": [
+                {"baseUrl": "https://yt/asr", "kind": "asr"},
+                {"baseUrl": "https://yt/en"}
+            ]}}};var meta = {};</script>"#;
+        let page = parse_video_page(html).unwrap();
+        assert_eq!(page.title, "Talk");
+        assert_eq!(page.captions_url.as_deref(), Some("https://yt/en&fmt=json3"));
+
+        let html = r#"ytInitialPlayerResponse = {"videoDetails": {"title": "No captions"}};"#;
+        assert_eq!(parse_video_page(html).unwrap().captions_url, None);
+        assert!(parse_video_page("<html></html>").is_err());
+    }
+
+    #[test]
+    fn test_parse_json3_captions() {
+        let json = r#"{"events": [
+            {"tStartMs": 0, "dDurationMs": 2000, "segs": [{"utf8": "Hello"}, {"utf8": " world"}]},
+            {"tStartMs": 2000, "segs": [{"utf8": "\n"}]},
+            {"tStartMs": 65000, "dDurationMs": 1500, "segs": [{"utf8": "Next"}]}
+        ]}"#;
+        let segments = parse_json3_captions(json).unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0], TranscriptSegment { start_ms: 0, end_ms: 2000, text: "Hello world".to_string() });
+        assert_eq!(segments[1].start_ms, 65000);
+    }
+}
@@ -0,0 +1,125 @@
+//! Desktop Shell
+//!
+//! Runs the app as a desktop window with a system tray icon. Closing the
+//! window only hides it, so the server and scheduled jobs keep running; the
+//! tray menu brings it back, starts a new chat or opens quick capture, and
+//! quits. "Start at login" registers the app with the OS (launch agent on
+//! macOS, registry on Windows, autostart entry on Linux) to start hidden in
+//! the tray.
+
+use dioxus::prelude::*;
+use dioxus::desktop::{use_tray_menu_event_handler, window, Config, WindowBuilder, WindowCloseBehaviour};
+use dioxus::desktop::trayicon::init_tray_icon;
+use dioxus::desktop::trayicon::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use auto_launch::{AutoLaunch, AutoLaunchBuilder};
+use crate::models::Notification;
+
+const APP_NAME: &str = "iDoris";
+
+/// Passed when started at login, to start hidden in the tray
+const HIDDEN_ARG: &str = "--hidden";
+
+const MENU_OPEN: &str = "open";
+const MENU_NEW_CHAT: &str = "new_chat";
+const MENU_QUICK_CAPTURE: &str = "quick_capture";
+const MENU_START_AT_LOGIN: &str = "start_at_login";
+const MENU_QUIT: &str = "quit";
+
+/// Window configuration for the desktop app
+pub fn config() -> Config {
+    let hidden = std::env::args().any(|arg| arg == HIDDEN_ARG);
+    Config::new()
+        .with_window(WindowBuilder::new().with_title(APP_NAME).with_visible(!hidden))
+        .with_close_behaviour(WindowCloseBehaviour::LastWindowHides)
+}
+
+/// Installs the tray icon and handles its menu. `on_new_chat` starts a new
+/// chat; `quick_capture` opens the quick capture dialog.
+pub fn use_tray(mut on_new_chat: impl FnMut() + 'static, mut quick_capture: Signal<bool>) {
+    let start_at_login = use_hook(|| {
+        let start_at_login = CheckMenuItem::with_id(MENU_START_AT_LOGIN, "Start at login", true, is_start_at_login(), None);
+        let menu = Menu::new();
+        let items = menu.append_items(&[
+            &MenuItem::with_id(MENU_OPEN, "Open iDoris", true, None),
+            &PredefinedMenuItem::separator(),
+            &MenuItem::with_id(MENU_NEW_CHAT, "New chat", true, None),
+            &MenuItem::with_id(MENU_QUICK_CAPTURE, "Quick capture", true, None),
+            &PredefinedMenuItem::separator(),
+            &start_at_login,
+            &PredefinedMenuItem::separator(),
+            &MenuItem::with_id(MENU_QUIT, "Quit iDoris", true, None),
+        ]);
+        if let Err(e) = items {
+            tracing::error!("Failed to build tray menu: {}", e);
+        }
+        init_tray_icon(menu, None);
+        start_at_login
+    });
+
+    use_tray_menu_event_handler(move |event| {
+        match event.id.0.as_str() {
+            MENU_OPEN => show_window(),
+            MENU_NEW_CHAT => {
+                show_window();
+                on_new_chat();
+            }
+            MENU_QUICK_CAPTURE => {
+                show_window();
+                quick_capture.set(true);
+            }
+            MENU_START_AT_LOGIN => {
+                // The menu item has already toggled itself
+                let enabled = start_at_login.is_checked();
+                if let Err(e) = set_start_at_login(enabled) {
+                    tracing::error!("Failed to change start at login: {}", e);
+                    start_at_login.set_checked(!enabled);
+                }
+            }
+            MENU_QUIT => std::process::exit(0),
+            _ => {}
+        }
+    });
+}
+
+fn show_window() {
+    let window = window();
+    window.set_visible(true);
+    window.set_minimized(false);
+    window.set_focus();
+}
+
+fn auto_launch() -> Result<AutoLaunch, String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    AutoLaunchBuilder::new()
+        .set_app_name(APP_NAME)
+        .set_app_path(&exe.to_string_lossy())
+        .set_args(&[HIDDEN_ARG])
+        .set_use_launch_agent(true)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Whether the app is registered to start at login
+pub fn is_start_at_login() -> bool {
+    auto_launch().and_then(|a| a.is_enabled().map_err(|e| e.to_string())).unwrap_or(false)
+}
+
+pub fn set_start_at_login(enabled: bool) -> Result<(), String> {
+    let auto_launch = auto_launch()?;
+    let result = if enabled { auto_launch.enable() } else { auto_launch.disable() };
+    result.map_err(|e| e.to_string())?;
+    tracing::info!("Start at login {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+/// Shows a notification through the OS notification center
+pub fn notify(notification: &Notification) {
+    let result = notify_rust::Notification::new()
+        .appname(APP_NAME)
+        .summary(&notification.title)
+        .body(&notification.message)
+        .show();
+    if let Err(e) = result {
+        tracing::warn!("Failed to show desktop notification: {}", e);
+    }
+}
@@ -16,28 +16,88 @@ mod storage;
 
 mod server_functions;
 
+#[cfg(feature = "cli")]
+mod cli;
+
+#[cfg(feature = "desktop")]
+mod desktop;
+
 /// Static resources used by the application
 /// Favicon that will appear in the browser tab
 const FAVICON: Asset = asset!("/assets/favicon.ico");
+/// Tailwind stylesheet compiled from `tailwind.css` at build time
+const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 
 /// Main function that launches the Dioxus application
 fn main() {
+    // Storage locations may be set in .env, and data from old locations is
+    // moved before anything opens it
+    #[cfg(feature = "server")]
+    let env_loaded = dotenv::dotenv();
+    #[cfg(feature = "server")]
+    core::paths::migrate();
+
+    // Headless commands run and exit without starting the app
+    #[cfg(feature = "cli")]
+    if let Some(command) = cli::parse_args(std::env::args().skip(1)) {
+        match command {
+            Ok(command) => std::process::exit(cli::run(command)),
+            Err(usage) => {
+                eprintln!("{}", usage);
+                std::process::exit(2);
+            }
+        }
+    }
+
     #[cfg(feature = "server")]
     {
-        println!("Server starting...");
-        // Load .env file if it exists
-        if let Err(e) = dotenv::dotenv() {
-            println!("Note: .env file not found or could not be loaded: {}", e);
+        // Structured logging to stdout and <data dir>/logs
+        if let Err(e) = core::logging::init() {
+            eprintln!("Failed to initialize file logging: {}", e);
+        }
+
+        tracing::info!("Server starting...");
+        tracing::info!("Data directory: {:?}", core::paths::data_dir());
+        if let Err(e) = env_loaded {
+            tracing::info!("Note: .env file not found or could not be loaded: {}", e);
         } else {
-            println!("✅ .env loaded");
+            tracing::info!("✅ .env loaded");
         }
         
         // Validate configuration
         if let Err(e) = core::config::validate_env_config() {
-            eprintln!("❌ Configuration validation failed: {}", e);
-            eprintln!("   The application will continue, but some features may not work.");
+            tracing::error!("❌ Configuration validation failed: {}", e);
+            tracing::warn!("   The application will continue, but some features may not work.");
         }
+
+        // Listen on the network when access from other devices is enabled
+        core::lan_access::apply_bind_address();
+
+        // Hand the proxy and Hugging Face mirror to libraries and tools
+        core::http::apply_proxy_environment();
+
+        // Serve the app, plus the opt-in /v1 API when IDORIS_API_ENABLED is set
+        dioxus::serve(|| async move {
+            // Continue content pipeline jobs interrupted by the last shutdown
+            core::content_generator::restore_jobs();
+            // Find the optional tools so panels can say what to install
+            tokio::task::spawn_blocking(core::capabilities::detect);
+            // Publish scheduled content calendar entries when they are due
+            core::publishing::start_scheduler();
+
+            let mut router = dioxus::server::router(App);
+            if let Some(api) = core::api_server::router() {
+                router = router.merge(api);
+            }
+            Ok(router.layer(axum::middleware::from_fn(core::lan_access::require_session)))
+        });
     }
+
+    // Desktop window with a tray icon; closing it keeps the app running
+    #[cfg(all(feature = "desktop", not(feature = "server")))]
+    dioxus::LaunchBuilder::new().with_cfg(desktop::config()).launch(App);
+
+    #[cfg(not(any(feature = "server", feature = "desktop")))]
     dioxus::launch(App);
 }
 
@@ -45,7 +105,8 @@ fn main() {
 ///
 /// This component defines the basic structure of the HTML document,
 /// including:
-/// - Links to resources such as favicon and CSS styles
+/// - Links to resources such as favicon and the bundled Tailwind stylesheet
+/// - The web app manifest and service worker for installing as a PWA
 /// - Page body with dark background
 /// - The main App component that handles the interface
 #[component]
@@ -53,12 +114,20 @@ fn App() -> Element {
     rsx! {
         document::Link { rel: "icon", href: FAVICON }
         document::Title { "iDoris | Your Local AI Assistant" }
-        // Use Tailwind CDN for complete class support
-        script { src: "https://cdn.tailwindcss.com" }
+        // Bundled Tailwind output - served locally, no CDN required
+        document::Stylesheet { href: TAILWIND_CSS }
+        // Installable as a PWA; the service worker serves the cached shell
+        // when the server can't be reached
+        document::Link { rel: "manifest", href: "/manifest.webmanifest" }
+        document::Link { rel: "apple-touch-icon", href: "/icon-192.png" }
+        document::Meta { name: "theme-color", content: "#0f172a" }
         // Also set title via script for better compatibility
         script {
             "document.title = 'iDoris | Your Local AI Assistant';"
         }
+        script {
+            "if ('serviceWorker' in navigator && location.protocol.startsWith('http')) navigator.serviceWorker.register('/sw.js');"
+        }
         body {
             class: "bg-slate-900 text-white",
             components::App {}
@@ -0,0 +1,211 @@
+//! Application Errors
+//!
+//! Server functions report failures as an [`AppError`]: a category the UI
+//! can act on and the message for the user. The category travels to the
+//! client as a short tag in front of the server function's error message,
+//! e.g. `[network] The request timed out`, and is parsed back from the error
+//! text. Errors without a tag are categorized from their wording.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Prefix the server function transport puts before error messages
+const SERVER_FN_PREFIX: &str = "error running server function:";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// A model is not downloaded, loaded or initialized yet
+    ModelNotReady,
+    /// A provider's API key is missing or was rejected
+    ProviderAuth,
+    /// A service could not be reached, timed out or is rate limiting
+    Network,
+    /// Reading or writing the database or files failed
+    Storage,
+    Other,
+}
+
+/// What the UI offers to get past an error
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecoveryAction {
+    OpenModelSettings,
+    /// The log lists the provider keys found missing at startup
+    ViewLogs,
+    OpenStorageSettings,
+    Retry,
+}
+
+impl RecoveryAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RecoveryAction::OpenModelSettings => "Open model settings",
+            RecoveryAction::ViewLogs => "View logs",
+            RecoveryAction::OpenStorageSettings => "Open storage settings",
+            RecoveryAction::Retry => "Try again",
+        }
+    }
+}
+
+impl ErrorCategory {
+    pub fn all() -> &'static [ErrorCategory] {
+        &[
+            ErrorCategory::ModelNotReady,
+            ErrorCategory::ProviderAuth,
+            ErrorCategory::Network,
+            ErrorCategory::Storage,
+            ErrorCategory::Other,
+        ]
+    }
+
+    /// Tag the category is sent as
+    pub fn tag(&self) -> &'static str {
+        match self {
+            ErrorCategory::ModelNotReady => "model_not_ready",
+            ErrorCategory::ProviderAuth => "provider_auth",
+            ErrorCategory::Network => "network",
+            ErrorCategory::Storage => "storage",
+            ErrorCategory::Other => "other",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            ErrorCategory::ModelNotReady => "Model not ready",
+            ErrorCategory::ProviderAuth => "Provider rejected the request",
+            ErrorCategory::Network => "Connection problem",
+            ErrorCategory::Storage => "Storage problem",
+            ErrorCategory::Other => "Something went wrong",
+        }
+    }
+
+    /// What the user can do about it
+    pub fn hint(&self) -> &'static str {
+        match self {
+            ErrorCategory::ModelNotReady => "Download or load the model in Settings, or wait for it to finish loading.",
+            ErrorCategory::ProviderAuth => "Check the provider's API key in your .env file, then restart the app.",
+            ErrorCategory::Network => "Check your internet connection or the service's address and try again.",
+            ErrorCategory::Storage => "Check the data folder's free space and permissions, and that history is unlocked.",
+            ErrorCategory::Other => "",
+        }
+    }
+
+    /// The recovery action offered, if any; retrying needs a retry handler
+    pub fn recovery(&self) -> Option<RecoveryAction> {
+        match self {
+            ErrorCategory::ModelNotReady => Some(RecoveryAction::OpenModelSettings),
+            ErrorCategory::ProviderAuth => Some(RecoveryAction::ViewLogs),
+            ErrorCategory::Network => Some(RecoveryAction::Retry),
+            ErrorCategory::Storage => Some(RecoveryAction::OpenStorageSettings),
+            ErrorCategory::Other => None,
+        }
+    }
+
+    /// Category the wording of an untagged error suggests
+    fn classify(message: &str) -> Self {
+        let text = message.to_lowercase();
+        let any = |words: &[&str]| words.iter().any(|w| text.contains(w));
+        if any(&["401", "403", "unauthorized", "forbidden", "api key", "api_key", "api token", "authentication", "invalid token"]) {
+            ErrorCategory::ProviderAuth
+        } else if text.contains("model") && any(&["not initialized", "not loaded", "not found", "not downloaded", "not ready", "still loading"]) {
+            ErrorCategory::ModelNotReady
+        } else if any(&["timed out", "timeout", "connection", "connect to", "network", "unreachable", "dns", "error sending request", "rate limit", "429"]) {
+            ErrorCategory::Network
+        } else if any(&["database", "sqlite", "surreal", "vector store", "disk", "no space", "permission denied", "i/o error", "history is locked"]) {
+            ErrorCategory::Storage
+        } else {
+            ErrorCategory::Other
+        }
+    }
+}
+
+/// A categorized failure; shown as `[tag] message`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppError {
+    pub category: ErrorCategory,
+    pub message: String,
+}
+
+impl AppError {
+    pub fn new(category: ErrorCategory, message: impl Into<String>) -> Self {
+        Self { category, message: message.into() }
+    }
+
+    pub fn model_not_ready(message: impl Into<String>) -> Self {
+        Self::new(ErrorCategory::ModelNotReady, message)
+    }
+
+    pub fn provider_auth(message: impl Into<String>) -> Self {
+        Self::new(ErrorCategory::ProviderAuth, message)
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::new(ErrorCategory::Network, message)
+    }
+
+    pub fn storage(message: impl Into<String>) -> Self {
+        Self::new(ErrorCategory::Storage, message)
+    }
+
+    /// The error in the text, which may be a tagged message wrapped in a
+    /// server function error and context such as "Failed to save: ";
+    /// untagged text is categorized from its wording
+    pub fn parse(text: &str) -> Self {
+        let tagged = ErrorCategory::all().iter().find_map(|category| {
+            let tag = format!("[{}] ", category.tag());
+            text.find(&tag).map(|at| (*category, at, at + tag.len()))
+        });
+        let Some((category, start, end)) = tagged else {
+            let message = strip_server_prefix(text);
+            return Self::new(ErrorCategory::classify(&message), message);
+        };
+        let context = strip_server_prefix(&text[..start]);
+        let message = text[end..].trim();
+        let message = if context.is_empty() { message.to_string() } else { format!("{} {}", context, message) };
+        Self::new(category, message)
+    }
+}
+
+fn strip_server_prefix(text: &str) -> String {
+    text.replace(SERVER_FN_PREFIX, "").trim().replace("  ", " ")
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.category.tag(), self.message)
+    }
+}
+
+/// The user-facing message in an error's text, without its category tag
+pub fn error_message(error: impl fmt::Display) -> String {
+    AppError::parse(&error.to_string()).message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tagged() {
+        let error = AppError::network("The request timed out");
+        assert_eq!(error.to_string(), "[network] The request timed out");
+        assert_eq!(AppError::parse(&error.to_string()), error);
+
+        let wrapped = format!("Failed to translate: error running server function: {}", AppError::storage("Disk full"));
+        let parsed = AppError::parse(&wrapped);
+        assert_eq!(parsed.category, ErrorCategory::Storage);
+        assert_eq!(parsed.message, "Failed to translate: Disk full");
+        assert_eq!(error_message(&wrapped), "Failed to translate: Disk full");
+    }
+
+    #[test]
+    fn test_classify_untagged() {
+        assert_eq!(AppError::parse("Model not initialized").category, ErrorCategory::ModelNotReady);
+        assert_eq!(AppError::parse("HTTP 401 Unauthorized").category, ErrorCategory::ProviderAuth);
+        assert_eq!(AppError::parse("TOGETHER_API_KEY not configured").category, ErrorCategory::ProviderAuth);
+        assert_eq!(AppError::parse("error sending request for url").category, ErrorCategory::Network);
+        assert_eq!(AppError::parse("Database not initialized").category, ErrorCategory::Storage);
+        assert_eq!(AppError::parse("Invalid asset ID").category, ErrorCategory::Other);
+        assert_eq!(ErrorCategory::Other.recovery(), None);
+    }
+}
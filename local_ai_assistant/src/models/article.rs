@@ -0,0 +1,107 @@
+//! Article Model
+//!
+//! The canonical shape of a written article: a title and its headed
+//! sections. The content editor, pipeline packages and translations each
+//! keep their own state around the text, but convert to an `Article` for
+//! rendering and export, so Markdown, HTML, narration and the platform
+//! format profiles are written once.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArticleSection {
+    pub title: String,
+    /// Markdown text of the section, without its heading
+    pub body: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Article {
+    pub title: String,
+    pub sections: Vec<ArticleSection>,
+}
+
+impl Article {
+    /// Article from (section title, text) pairs
+    pub fn new<I, T, B>(title: impl Into<String>, sections: I) -> Self
+    where
+        I: IntoIterator<Item = (T, B)>,
+        T: Into<String>,
+        B: Into<String>,
+    {
+        Self {
+            title: title.into(),
+            sections: sections.into_iter()
+                .map(|(title, body)| ArticleSection { title: title.into(), body: body.into() })
+                .collect(),
+        }
+    }
+
+    /// (section title, text) pairs, as packages and translations store them
+    pub fn section_pairs(&self) -> Vec<(String, String)> {
+        self.sections.iter().map(|s| (s.title.clone(), s.body.clone())).collect()
+    }
+
+    /// The article as Markdown, with the title as the top heading
+    pub fn to_markdown(&self) -> String {
+        format!("# {}\n\n{}", self.title, self.sections_markdown())
+    }
+
+    /// The sections as Markdown, each under a second-level heading
+    pub fn sections_markdown(&self) -> String {
+        let mut md = String::new();
+        for section in &self.sections {
+            md.push_str(&format!("## {}\n\n{}\n\n", section.title, section.body));
+        }
+        md
+    }
+
+    /// The sections as HTML, without the title
+    pub fn sections_html(&self) -> String {
+        comrak::markdown_to_html(&self.sections_markdown(), &comrak::Options::default())
+    }
+
+    /// The article as plain text for reading aloud, without Markdown markup
+    pub fn narration_text(&self) -> String {
+        let mut text = format!("{}.\n\n", self.title.trim_end_matches('.'));
+        for section in &self.sections {
+            text.push_str(&format!("{}.\n\n", section.title.trim_end_matches('.')));
+            for line in section.body.lines().map(str::trim) {
+                // Images have nothing to read
+                if line.is_empty() || line.starts_with("![") {
+                    continue;
+                }
+                let line = line.trim_start_matches(['#', '>', '-', '*', ' ']).replace(['*', '_', '`'], "");
+                text.push_str(&line);
+                text.push('\n');
+            }
+            text.push('\n');
+        }
+        text.trim_end().to_string()
+    }
+
+    /// Words in the section text, not counting headings
+    pub fn word_count(&self) -> usize {
+        self.sections.iter().map(|s| s.body.split_whitespace().count()).sum()
+    }
+
+    pub fn has_images(&self) -> bool {
+        self.sections.iter().any(|s| s.body.contains("!["))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_article_rendering() {
+        let article = Article::new("Rust & WASM", [("Intro", "**Fast** code.\n\n![chart](c.png)"), ("Setup", "Install it")]);
+        assert_eq!(article.to_markdown(), "# Rust & WASM\n\n## Intro\n\n**Fast** code.\n\n![chart](c.png)\n\n## Setup\n\nInstall it\n\n");
+        assert!(article.sections_html().starts_with("<h2>Intro</h2>"));
+        assert_eq!(article.narration_text(), "Rust & WASM.\n\nIntro.\n\nFast code.\n\nSetup.\n\nInstall it");
+        assert_eq!(article.word_count(), 5);
+        assert!(article.has_images());
+        assert_eq!(Article::new("Rust & WASM", article.section_pairs()), article);
+    }
+}
@@ -0,0 +1,255 @@
+//! Asset Library Model
+//!
+//! Generated images, audio and video from every feature, indexed in one
+//! library with the prompt that made them, where they came from and tags.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Kind of media
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Image,
+    Audio,
+    Video,
+}
+
+impl AssetKind {
+    pub fn all() -> &'static [AssetKind] {
+        &[AssetKind::Image, AssetKind::Audio, AssetKind::Video]
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            AssetKind::Image => "image",
+            AssetKind::Audio => "audio",
+            AssetKind::Video => "video",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        AssetKind::all().iter().copied().find(|k| k.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AssetKind::Image => "Images",
+            AssetKind::Audio => "Audio",
+            AssetKind::Video => "Video",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            AssetKind::Image => "🖼",
+            AssetKind::Audio => "🔊",
+            AssetKind::Video => "🎬",
+        }
+    }
+
+    /// Kind of a media file by its extension, `None` for other files
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = path.rsplit_once('.')?.1.to_lowercase();
+        match extension.as_str() {
+            "png" | "jpg" | "jpeg" | "webp" | "gif" => Some(AssetKind::Image),
+            "wav" | "mp3" | "aiff" | "m4a" | "ogg" | "flac" => Some(AssetKind::Audio),
+            "mp4" | "mov" | "webm" | "mkv" => Some(AssetKind::Video),
+            _ => None,
+        }
+    }
+}
+
+/// Feature that created an asset
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetOrigin {
+    /// Tools called during a chat
+    Chat,
+    ImagePanel,
+    TtsPanel,
+    VideoPanel,
+    ContentEditor,
+    ContentPackage,
+    /// Found on disk when the library was first opened
+    Imported,
+}
+
+impl AssetOrigin {
+    pub fn all() -> &'static [AssetOrigin] {
+        &[
+            AssetOrigin::Chat,
+            AssetOrigin::ImagePanel,
+            AssetOrigin::TtsPanel,
+            AssetOrigin::VideoPanel,
+            AssetOrigin::ContentEditor,
+            AssetOrigin::ContentPackage,
+            AssetOrigin::Imported,
+        ]
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            AssetOrigin::Chat => "chat",
+            AssetOrigin::ImagePanel => "image_panel",
+            AssetOrigin::TtsPanel => "tts_panel",
+            AssetOrigin::VideoPanel => "video_panel",
+            AssetOrigin::ContentEditor => "content_editor",
+            AssetOrigin::ContentPackage => "content_package",
+            AssetOrigin::Imported => "imported",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        AssetOrigin::all().iter().copied().find(|o| o.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AssetOrigin::Chat => "Chat",
+            AssetOrigin::ImagePanel => "Image Gen",
+            AssetOrigin::TtsPanel => "Text to Speech",
+            AssetOrigin::VideoPanel => "Video Gen",
+            AssetOrigin::ContentEditor => "Content Editor",
+            AssetOrigin::ContentPackage => "Content package",
+            AssetOrigin::Imported => "Imported",
+        }
+    }
+}
+
+/// A media file in the library
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Asset {
+    pub id: Uuid,
+    pub kind: AssetKind,
+    pub path: String,
+    /// Prompt or text the media was generated from
+    pub prompt: String,
+    pub origin: AssetOrigin,
+    /// The session, job or package it belongs to, if any
+    pub origin_id: Option<String>,
+    pub tags: Vec<String>,
+    pub size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Asset {
+    pub fn new(kind: AssetKind, path: String, prompt: String, origin: AssetOrigin) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            kind,
+            path,
+            prompt,
+            origin,
+            origin_id: None,
+            tags: Vec::new(),
+            size_bytes: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit(['/', '\\']).next().unwrap_or(&self.path)
+    }
+
+    /// Prompt shortened for lists, or the file name without one
+    pub fn title(&self) -> String {
+        let prompt = self.prompt.trim();
+        if prompt.is_empty() {
+            return self.file_name().to_string();
+        }
+        let mut title: String = prompt.chars().take(60).collect();
+        if prompt.chars().count() > 60 {
+            title.push('…');
+        }
+        title
+    }
+
+    /// Plain-text description given to the chat model when the asset is
+    /// attached to a message
+    pub fn describe(&self) -> String {
+        let mut text = format!("{} from {}", self.kind.id(), self.origin.display_name());
+        if !self.prompt.trim().is_empty() {
+            text.push_str(&format!(", generated from: {}", self.prompt.trim()));
+        }
+        if !self.tags.is_empty() {
+            text.push_str(&format!("\nTags: {}", self.tags.join(", ")));
+        }
+        text.push_str(&format!("\nFile: {}\nCreated: {}", self.path, self.created_at.format("%Y-%m-%d %H:%M")));
+        text
+    }
+}
+
+/// Search and filters of the library view
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AssetFilter {
+    /// Words that must all appear in the prompt, file name or tags
+    pub query: String,
+    pub kind: Option<AssetKind>,
+    pub origin: Option<AssetOrigin>,
+    pub tag: Option<String>,
+}
+
+impl AssetFilter {
+    pub fn matches(&self, asset: &Asset) -> bool {
+        if self.kind.is_some_and(|k| k != asset.kind) || self.origin.is_some_and(|o| o != asset.origin) {
+            return false;
+        }
+        if self.tag.as_ref().is_some_and(|tag| !asset.tags.contains(tag)) {
+            return false;
+        }
+        let haystack = format!("{} {} {}", asset.prompt, asset.file_name(), asset.tags.join(" ")).to_lowercase();
+        self.query.to_lowercase().split_whitespace().all(|word| haystack.contains(word))
+    }
+}
+
+/// Markdown for an asset inserted into an article. Images are embedded as
+/// the given data URL, like other images in the editor; audio and video are
+/// linked by file.
+pub fn asset_markdown(asset: &Asset, image_data_url: Option<&str>) -> String {
+    let label = asset.title().replace(['[', ']'], "");
+    match (asset.kind, image_data_url) {
+        (AssetKind::Image, Some(data_url)) => format!("![{}]({})", label, data_url),
+        _ => format!("[{} {}](<{}>)", asset.kind.icon(), label, asset.path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(kind: AssetKind, prompt: &str, tags: &[&str]) -> Asset {
+        let mut asset = Asset::new(kind, format!("/out/{}.png", prompt.len()), prompt.to_string(), AssetOrigin::ImagePanel);
+        asset.tags = tags.iter().map(|t| t.to_string()).collect();
+        asset
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let cat = asset(AssetKind::Image, "A white cat in the Garden", &["pets"]);
+        assert!(AssetFilter::default().matches(&cat));
+        assert!(AssetFilter { query: "garden CAT".into(), ..Default::default() }.matches(&cat));
+        assert!(AssetFilter { query: "pets".into(), ..Default::default() }.matches(&cat));
+        assert!(!AssetFilter { query: "dog".into(), ..Default::default() }.matches(&cat));
+        assert!(!AssetFilter { kind: Some(AssetKind::Audio), ..Default::default() }.matches(&cat));
+        assert!(!AssetFilter { origin: Some(AssetOrigin::Chat), ..Default::default() }.matches(&cat));
+        assert!(AssetFilter { tag: Some("pets".into()), ..Default::default() }.matches(&cat));
+        assert!(!AssetFilter { tag: Some("work".into()), ..Default::default() }.matches(&cat));
+    }
+
+    #[test]
+    fn test_kind_and_markdown() {
+        assert_eq!(AssetKind::from_path("/a/b/header@2x.PNG"), Some(AssetKind::Image));
+        assert_eq!(AssetKind::from_path("narration.aiff"), Some(AssetKind::Audio));
+        assert_eq!(AssetKind::from_path("subtitles.vtt"), None);
+        assert_eq!(AssetOrigin::from_id("content_package"), Some(AssetOrigin::ContentPackage));
+
+        let image = asset(AssetKind::Image, "A [red] fox", &[]);
+        assert_eq!(asset_markdown(&image, Some("data:image/png;base64,AA")), "![A red fox](data:image/png;base64,AA)");
+        let mut clip = asset(AssetKind::Video, "", &[]);
+        clip.path = "/out/my clip.mp4".into();
+        assert_eq!(asset_markdown(&clip, None), "[🎬 my clip.mp4](</out/my clip.mp4>)");
+        assert!(clip.describe().starts_with("video from Image Gen\nFile: /out/my clip.mp4"));
+    }
+}
@@ -0,0 +1,120 @@
+//! Chat Attachment Model
+//!
+//! Files dropped onto the chat. Small text files travel with the message in
+//! full; larger ones are replaced by a summary built chunk by chunk.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Largest file accepted as an attachment
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Longest text inlined into the prompt as is
+pub const MAX_INLINE_CHARS: usize = 12_000;
+
+/// How an attachment is passed to the model
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AttachmentMode {
+    /// The full text
+    Inline,
+    /// A summary of the text
+    Summary,
+}
+
+/// A file attached to a user message
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChatAttachment {
+    pub id: Uuid,
+    pub name: String,
+    pub size_bytes: u64,
+    pub mode: AttachmentMode,
+    /// The file's text, or its summary
+    pub content: String,
+    /// Whether the file was added to the knowledge base
+    #[serde(default)]
+    pub ingested: bool,
+}
+
+impl ChatAttachment {
+    /// Context block placed before the user's message
+    pub fn to_prompt(&self) -> String {
+        let label = match self.mode {
+            AttachmentMode::Inline => "ATTACHED FILE",
+            AttachmentMode::Summary => "SUMMARY OF ATTACHED FILE",
+        };
+        format!("=== {}: {} ===\n{}\n=== END {} ===\n\n", label, self.name, self.content.trim(), label)
+    }
+
+    /// Context blocks for all attachments of a message
+    pub fn preamble(attachments: &[ChatAttachment]) -> String {
+        attachments.iter().map(ChatAttachment::to_prompt).collect()
+    }
+}
+
+/// Splits text into chunks of at most `max_chars`, breaking between
+/// paragraphs, then lines, where possible
+pub fn split_into_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        let line_chars = line.chars().count();
+        if !current.is_empty() && current.chars().count() + line_chars + 1 > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if line_chars > max_chars {
+            // A single overlong line is cut wherever it has to be
+            let chars: Vec<char> = line.chars().collect();
+            for piece in chars.chunks(max_chars) {
+                chunks.push(piece.iter().collect());
+            }
+            continue;
+        }
+        if line.trim().is_empty() && current.chars().count() > max_chars / 2 {
+            chunks.push(std::mem::take(&mut current));
+            continue;
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks.retain(|c| !c.trim().is_empty());
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_respect_limit_and_keep_text() {
+        let text = (0..200).map(|i| format!("Line number {}", i)).collect::<Vec<_>>().join("\n");
+        let chunks = split_into_chunks(&text, 300);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 300));
+        assert_eq!(chunks.join("\n"), text);
+
+        let long_line = "x".repeat(650);
+        assert_eq!(split_into_chunks(&long_line, 300).len(), 3);
+        assert!(split_into_chunks("\n\n", 300).is_empty());
+    }
+
+    #[test]
+    fn test_prompt_labels_summaries() {
+        let attachment = ChatAttachment {
+            id: Uuid::new_v4(),
+            name: "notes.txt".to_string(),
+            size_bytes: 5,
+            mode: AttachmentMode::Summary,
+            content: "short\n".to_string(),
+            ingested: false,
+        };
+        assert_eq!(
+            attachment.to_prompt(),
+            "=== SUMMARY OF ATTACHED FILE: notes.txt ===\nshort\n=== END SUMMARY OF ATTACHED FILE ===\n\n"
+        );
+    }
+}
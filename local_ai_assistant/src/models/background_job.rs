@@ -0,0 +1,137 @@
+//! Background Job Model
+//!
+//! A task tracked by the shared job registry (model downloads, indexing,
+//! video generation, content pipelines, session tagging, audio transcription,
+//! scheduled publishing),
+//! as listed in the Jobs panel.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobKind {
+    ModelDownload,
+    Indexing,
+    Video,
+    ContentPipeline,
+    SessionTagging,
+    Transcription,
+    Publishing,
+}
+
+impl JobKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            JobKind::ModelDownload => "Model download",
+            JobKind::Indexing => "Indexing",
+            JobKind::Video => "Video",
+            JobKind::ContentPipeline => "Content pipeline",
+            JobKind::SessionTagging => "Session tagging",
+            JobKind::Transcription => "Transcription",
+            JobKind::Publishing => "Publishing",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            JobKind::ModelDownload => "⬇️",
+            JobKind::Indexing => "📚",
+            JobKind::Video => "🎬",
+            JobKind::ContentPipeline => "📝",
+            JobKind::SessionTagging => "🏷️",
+            JobKind::Transcription => "🎙️",
+            JobKind::Publishing => "📅",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed(String),
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobStatus::Running => "Running",
+            JobStatus::Completed => "Completed",
+            JobStatus::Failed(_) => "Failed",
+            JobStatus::Cancelled => "Cancelled",
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, JobStatus::Running)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BackgroundJob {
+    pub id: Uuid,
+    pub kind: JobKind,
+    pub title: String,
+    pub status: JobStatus,
+    /// Fraction done from 0.0 to 1.0, when the task can tell
+    pub progress: Option<f32>,
+    pub logs: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Whether the job can be started again after it failed or was cancelled
+    pub retryable: bool,
+}
+
+impl BackgroundJob {
+    pub fn new(kind: JobKind, title: impl Into<String>, retryable: bool) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            kind,
+            title: title.into(),
+            status: JobStatus::Running,
+            progress: None,
+            logs: Vec::new(),
+            started_at: Utc::now(),
+            finished_at: None,
+            retryable,
+        }
+    }
+
+    pub fn can_retry(&self) -> bool {
+        self.retryable && matches!(self.status, JobStatus::Failed(_) | JobStatus::Cancelled)
+    }
+
+    /// Records the outcome. A job that was cancelled stays cancelled.
+    pub fn finish(&mut self, status: JobStatus) {
+        if self.status.is_finished() {
+            return;
+        }
+        if status == JobStatus::Completed {
+            self.progress = Some(1.0);
+        }
+        self.status = status;
+        self.finished_at = Some(Utc::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancelled_job_stays_cancelled() {
+        let mut job = BackgroundJob::new(JobKind::Video, "cat", true);
+        assert!(!job.can_retry());
+        job.finish(JobStatus::Cancelled);
+        job.finish(JobStatus::Failed("aborted".into()));
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert!(job.can_retry());
+
+        let mut job = BackgroundJob::new(JobKind::Indexing, "docs", false);
+        job.finish(JobStatus::Completed);
+        assert_eq!(job.progress, Some(1.0));
+        assert!(!job.can_retry());
+    }
+}
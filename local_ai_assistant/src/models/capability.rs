@@ -0,0 +1,191 @@
+//! Capability Models
+//!
+//! The optional command line tools and models some features rely on, what
+//! was found on this machine, and how to install what is missing. Panels
+//! whose feature can't work show the install steps instead of failing when
+//! used.
+
+use serde::{Deserialize, Serialize};
+
+/// An external tool or model detected at startup
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tool {
+    Mflux,
+    Ffmpeg,
+    Whisper,
+    YtDlp,
+    VibeVoice,
+    MlxAudio,
+    SystemTts,
+    RealEsrgan,
+    Graphviz,
+    Mermaid,
+}
+
+impl Tool {
+    pub fn all() -> &'static [Tool] {
+        &[
+            Tool::Mflux,
+            Tool::Ffmpeg,
+            Tool::Whisper,
+            Tool::YtDlp,
+            Tool::VibeVoice,
+            Tool::MlxAudio,
+            Tool::SystemTts,
+            Tool::RealEsrgan,
+            Tool::Graphviz,
+            Tool::Mermaid,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Mflux => "MFLUX",
+            Tool::Ffmpeg => "FFmpeg",
+            Tool::Whisper => "whisper.cpp",
+            Tool::YtDlp => "yt-dlp",
+            Tool::VibeVoice => "VibeVoice",
+            Tool::MlxAudio => "mlx-audio (Kokoro)",
+            Tool::SystemTts => "System voice (say)",
+            Tool::RealEsrgan => "Real-ESRGAN",
+            Tool::Graphviz => "Graphviz",
+            Tool::Mermaid => "Mermaid CLI",
+        }
+    }
+
+    /// What the tool is used for
+    pub fn purpose(&self) -> &'static str {
+        match self {
+            Tool::Mflux => "Image generation",
+            Tool::Ffmpeg => "Video assembly, audio conversion and transcription",
+            Tool::Whisper => "Transcription of audio, podcasts and voice questions",
+            Tool::YtDlp => "Transcribing YouTube videos that have no captions",
+            Tool::VibeVoice => "High quality speech",
+            Tool::MlxAudio => "Kokoro speech",
+            Tool::SystemTts => "Built-in speech",
+            Tool::RealEsrgan => "Sharper image upscaling; images are resampled without it",
+            Tool::Graphviz => "Graphviz diagrams in chat",
+            Tool::Mermaid => "Mermaid diagrams in chat",
+        }
+    }
+
+    /// How to install it
+    pub fn install_hint(&self) -> &'static str {
+        match self {
+            Tool::Mflux => "uv tool install mflux",
+            Tool::Ffmpeg => "brew install ffmpeg",
+            Tool::Whisper => "brew install whisper-cpp, then download a ggml model (e.g. ggml-base.bin) to the data folder's whisper directory or set IDORIS_WHISPER_MODEL",
+            Tool::YtDlp => "brew install yt-dlp",
+            Tool::VibeVoice => "Download microsoft/VibeVoice-Realtime-0.5B to ~/models/VibeVoice-Realtime-0.5B",
+            Tool::MlxAudio => "pip install mlx-audio",
+            Tool::SystemTts => "Included with macOS",
+            Tool::RealEsrgan => "Put realesrgan-ncnn-vulkan from the Real-ESRGAN releases on your PATH, or set IDORIS_UPSCALER_BIN",
+            Tool::Graphviz => "brew install graphviz",
+            Tool::Mermaid => "npm install -g @mermaid-js/mermaid-cli",
+        }
+    }
+}
+
+/// A feature that needs external tools
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Feature {
+    ImageGeneration,
+    Speech,
+    VideoAssembly,
+    Transcription,
+    Diagrams,
+}
+
+impl Feature {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feature::ImageGeneration => "Image generation",
+            Feature::Speech => "Text to speech",
+            Feature::VideoAssembly => "Video assembly",
+            Feature::Transcription => "Transcription",
+            Feature::Diagrams => "Diagram rendering",
+        }
+    }
+
+    /// Tools the feature uses, and whether it needs all of them or any one
+    pub fn requirements(&self) -> (&'static [Tool], bool) {
+        match self {
+            Feature::ImageGeneration => (&[Tool::Mflux], true),
+            Feature::Speech => (&[Tool::VibeVoice, Tool::MlxAudio, Tool::SystemTts], false),
+            Feature::VideoAssembly => (&[Tool::Ffmpeg], true),
+            Feature::Transcription => (&[Tool::Ffmpeg, Tool::Whisper], true),
+            Feature::Diagrams => (&[Tool::Graphviz, Tool::Mermaid], false),
+        }
+    }
+
+    /// Whether mock providers stand in for the feature's tools
+    pub fn mocked(&self) -> bool {
+        matches!(self, Feature::ImageGeneration | Feature::Speech | Feature::Transcription)
+    }
+}
+
+/// Whether a tool was found, with where or why not
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ToolStatus {
+    pub tool: Tool,
+    pub available: bool,
+    pub detail: Option<String>,
+}
+
+/// The tools found on this machine
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub tools: Vec<ToolStatus>,
+    /// Mock providers are enabled
+    pub mock: bool,
+}
+
+impl Capabilities {
+    pub fn has(&self, tool: Tool) -> bool {
+        self.tools.iter().any(|s| s.tool == tool && s.available)
+    }
+
+    /// Tools to install before the feature works; empty when it works
+    pub fn missing_for(&self, feature: Feature) -> Vec<Tool> {
+        if self.mock && feature.mocked() {
+            return Vec::new();
+        }
+        let (tools, needs_all) = feature.requirements();
+        let missing: Vec<Tool> = tools.iter().copied().filter(|tool| !self.has(*tool)).collect();
+        if needs_all || missing.len() == tools.len() { missing } else { Vec::new() }
+    }
+
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.missing_for(feature).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(tools: &[Tool]) -> Capabilities {
+        Capabilities {
+            tools: Tool::all().iter()
+                .map(|tool| ToolStatus { tool: *tool, available: tools.contains(tool), detail: None })
+                .collect(),
+            mock: false,
+        }
+    }
+
+    #[test]
+    fn test_missing_for() {
+        let caps = found(&[Tool::Ffmpeg, Tool::SystemTts]);
+        assert!(caps.supports(Feature::VideoAssembly));
+        assert!(caps.supports(Feature::Speech));
+        assert_eq!(caps.missing_for(Feature::Transcription), vec![Tool::Whisper]);
+        assert_eq!(caps.missing_for(Feature::Diagrams), vec![Tool::Graphviz, Tool::Mermaid]);
+        assert_eq!(caps.missing_for(Feature::ImageGeneration), vec![Tool::Mflux]);
+        assert!(found(&[Tool::Mermaid]).supports(Feature::Diagrams));
+
+        let mock = Capabilities { mock: true, ..found(&[]) };
+        assert!(mock.supports(Feature::ImageGeneration));
+        assert!(!mock.supports(Feature::VideoAssembly));
+    }
+}
@@ -3,6 +3,17 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use super::ChatAttachment;
+use super::math::{extract_math, restore_math};
+use comrak::{markdown_to_html_with_plugins, ExtensionOptions, Plugins, RenderOptions, RenderPlugins};
+use comrak::plugins::syntect::SyntectAdapterBuilder;
+
+/// Headings of the blocks appended below an answer
+pub const SOURCES_HEADING: &str = "\n\n**Sources**\n";
+pub const CODE_REFERENCES_HEADING: &str = "\n\n**Code references**\n";
+
+/// End of a cut-off answer quoted back to the model to continue it
+const CONTINUATION_TAIL_CHARS: usize = 1500;
 
 /// Represents a chat message in a conversation
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -12,6 +23,15 @@ pub struct ChatMessage {
     pub role: ChatRole,
     pub content: String,
     pub created_at: DateTime<Utc>,
+    /// The user's rating, on assistant messages only
+    #[serde(default)]
+    pub feedback: Option<MessageFeedback>,
+    /// Files attached to a user message
+    #[serde(default)]
+    pub attachments: Vec<ChatAttachment>,
+    /// Timing of the response, on assistant messages only
+    #[serde(default)]
+    pub generation: Option<GenerationStats>,
 }
 
 impl ChatMessage {
@@ -22,6 +42,9 @@ impl ChatMessage {
             role,
             content,
             created_at: Utc::now(),
+            feedback: None,
+            attachments: Vec::new(),
+            generation: None,
         }
     }
 
@@ -36,6 +59,236 @@ impl ChatMessage {
     pub fn system(session_id: Uuid, content: String) -> Self {
         Self::new(session_id, ChatRole::System, content)
     }
+
+    /// Earlier turns of a conversation as a transcript to prepend to the next
+    /// prompt, newest turns kept when it exceeds `max_chars`. A trailing
+    /// user message is the turn being answered and is left out.
+    pub fn history_preamble(history: &[ChatMessage], max_chars: usize) -> String {
+        let history = match history.last() {
+            Some(last) if last.role == ChatRole::User => &history[..history.len() - 1],
+            _ => history,
+        };
+
+        let mut lines = Vec::new();
+        let mut used = 0;
+        for message in history.iter().rev().filter(|m| m.role != ChatRole::System) {
+            let content = message.content.trim();
+            if content.is_empty() {
+                continue;
+            }
+            let line = format!("{}: {}", message.role, content);
+            used += line.chars().count();
+            if used > max_chars {
+                break;
+            }
+            lines.push(line);
+        }
+        if lines.is_empty() {
+            return String::new();
+        }
+
+        lines.reverse();
+        format!("=== CONVERSATION SO FAR ===\n{}\n=== END CONVERSATION ===\n\n", lines.join("\n\n"))
+    }
+
+    /// Appends the continuation of a cut-off answer, before the sources and
+    /// code references added below it
+    pub fn insert_continuation(&mut self, text: &str) {
+        let end = [SOURCES_HEADING, CODE_REFERENCES_HEADING].iter()
+            .filter_map(|heading| self.content.find(heading))
+            .min()
+            .unwrap_or(self.content.len());
+        self.content.insert_str(end, text);
+    }
+
+    /// The Markdown content as HTML; see [`render_markdown`]
+    pub fn content_html(&self, render_math: bool) -> String {
+        render_markdown(&self.content, render_math)
+    }
+}
+
+/// Prompt asking the model to carry on with an answer that hit the token
+/// cap, quoting its end so the model picks up mid-sentence
+pub fn continuation_prompt(answer: &str) -> String {
+    let answer = [SOURCES_HEADING, CODE_REFERENCES_HEADING].iter()
+        .filter_map(|heading| answer.find(heading))
+        .min()
+        .map_or(answer, |end| &answer[..end]);
+    let chars = answer.chars().count();
+    let tail: String = answer.chars().skip(chars.saturating_sub(CONTINUATION_TAIL_CHARS)).collect();
+    format!(
+        "Your previous answer was cut off. It ended with:\n\n{}\n\nContinue exactly where it stopped, without repeating any of it or adding an introduction.",
+        tail
+    )
+}
+
+/// Chat Markdown as HTML, with code blocks syntax-highlighted through inline
+/// styles and, if `render_math` is set, LaTeX math as MathML
+pub fn render_markdown(markdown: &str, render_math: bool) -> String {
+    if markdown.is_empty() {
+        return String::new();
+    }
+
+    // Configure syntax highlighter with dark theme
+    let syntect_adapter = SyntectAdapterBuilder::new()
+        .theme("base16-ocean.dark")
+        .build();
+
+    let plugins = Plugins::builder()
+        .render(
+            RenderPlugins::builder()
+                .codefence_syntax_highlighter(&syntect_adapter)
+                .build()
+        ).build();
+
+    let extension_options = ExtensionOptions::builder()
+        .strikethrough(true)
+        .tagfilter(true)
+        .autolink(true)
+        .table(true)
+        .build();
+
+    let render_options = RenderOptions::builder()
+        .hardbreaks(true)
+        .github_pre_lang(true)
+        .build();
+
+    let options = comrak::Options {
+        extension: extension_options,
+        render: render_options,
+        ..Default::default()
+    };
+
+    if !render_math {
+        return markdown_to_html_with_plugins(markdown, &options, &plugins);
+    }
+    let (markdown, math) = extract_math(markdown);
+    restore_math(&markdown_to_html_with_plugins(&markdown, &options, &plugins), &math)
+}
+
+/// Thumbs up or down on an assistant response
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackRating {
+    Up,
+    Down,
+}
+
+impl FeedbackRating {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedbackRating::Up => "up",
+            FeedbackRating::Down => "down",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "up" => Some(FeedbackRating::Up),
+            "down" => Some(FeedbackRating::Down),
+            _ => None,
+        }
+    }
+}
+
+/// A rating with an optional note on what was good or bad
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MessageFeedback {
+    pub rating: FeedbackRating,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MessageFeedback {
+    pub fn new(rating: FeedbackRating, comment: Option<String>) -> Self {
+        Self {
+            rating,
+            comment: comment.map(|c| c.trim().to_string()).filter(|c| !c.is_empty()),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// How fast a response was generated
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GenerationStats {
+    /// Model that wrote the response
+    pub model: String,
+    /// Tokens streamed; 0 when the response arrived at once
+    pub tokens: u32,
+    /// From sending the prompt to the first token
+    pub first_token_ms: Option<u64>,
+    /// From sending the prompt to the last token
+    pub total_ms: u64,
+    /// The response stopped at the token cap and can be continued
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+impl GenerationStats {
+    /// Decoding speed, measured after the first token so prompt processing
+    /// doesn't count
+    pub fn tokens_per_second(&self) -> Option<f64> {
+        if self.tokens < 2 {
+            return None;
+        }
+        let first = self.first_token_ms.unwrap_or(0);
+        let decode_ms = self.total_ms.saturating_sub(first);
+        if decode_ms == 0 {
+            return None;
+        }
+        Some((self.tokens - 1) as f64 * 1000.0 / decode_ms as f64)
+    }
+
+    /// Short form for the message footer, e.g. "23.4 tok/s · 8.1s"
+    pub fn summary(&self) -> String {
+        let seconds = format!("{:.1}s", self.total_ms as f64 / 1000.0);
+        match self.tokens_per_second() {
+            Some(rate) => format!("{:.1} tok/s · {}", rate, seconds),
+            None => seconds,
+        }
+    }
+}
+
+/// A rated response with the prompt it answered, one line of the feedback
+/// export
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FeedbackRecord {
+    pub session_id: Uuid,
+    pub message_id: Uuid,
+    pub rating: FeedbackRating,
+    pub comment: Option<String>,
+    /// The user message the response answered
+    pub prompt: String,
+    pub response: String,
+    pub rated_at: DateTime<Utc>,
+}
+
+impl FeedbackRecord {
+    /// Records for every rated assistant message in a conversation, in order
+    pub fn collect(messages: &[ChatMessage]) -> Vec<FeedbackRecord> {
+        messages.iter()
+            .enumerate()
+            .filter(|(_, m)| m.role == ChatRole::Assistant)
+            .filter_map(|(i, m)| {
+                let feedback = m.feedback.as_ref()?;
+                let prompt = messages[..i].iter()
+                    .rev()
+                    .find(|p| p.role == ChatRole::User)
+                    .map(|p| p.content.clone())
+                    .unwrap_or_default();
+                Some(FeedbackRecord {
+                    session_id: m.session_id,
+                    message_id: m.id,
+                    rating: feedback.rating,
+                    comment: feedback.comment.clone(),
+                    prompt,
+                    response: m.content.clone(),
+                    rated_at: feedback.created_at,
+                })
+            })
+            .collect()
+    }
 }
 
 /// Role of a chat message sender
@@ -55,3 +308,71 @@ impl std::fmt::Display for ChatRole {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_summary() {
+        let stats = GenerationStats { model: "m".to_string(), tokens: 101, first_token_ms: Some(500), total_ms: 4_500, truncated: false };
+        assert_eq!(stats.summary(), "25.0 tok/s · 4.5s");
+
+        let tool_run = GenerationStats { tokens: 0, first_token_ms: None, total_ms: 8_100, ..stats };
+        assert_eq!(tool_run.summary(), "8.1s");
+    }
+
+    #[test]
+    fn test_continuation_goes_before_sources() {
+        let mut message = ChatMessage::assistant(Uuid::new_v4(), format!("The first step is{}[1] Guide", SOURCES_HEADING));
+        let prompt = continuation_prompt(&message.content);
+        assert!(prompt.contains("The first step is\n"));
+        assert!(!prompt.contains("Guide"));
+
+        message.insert_continuation(" to install Rust.");
+        assert_eq!(message.content, format!("The first step is to install Rust.{}[1] Guide", SOURCES_HEADING));
+
+        let mut plain = ChatMessage::assistant(Uuid::new_v4(), "Hello".to_string());
+        plain.insert_continuation(" world");
+        assert_eq!(plain.content, "Hello world");
+    }
+
+    #[test]
+    fn test_feedback_records_pair_prompt_and_response() {
+        let session = Uuid::new_v4();
+        let mut answer = ChatMessage::assistant(session, "Paris".to_string());
+        answer.feedback = Some(MessageFeedback::new(FeedbackRating::Down, Some("  too short ".to_string())));
+        let messages = vec![
+            ChatMessage::user(session, "Hi".to_string()),
+            ChatMessage::assistant(session, "Hello!".to_string()),
+            ChatMessage::user(session, "Capital of France?".to_string()),
+            answer.clone(),
+        ];
+
+        let records = FeedbackRecord::collect(&messages);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message_id, answer.id);
+        assert_eq!(records[0].prompt, "Capital of France?");
+        assert_eq!(records[0].comment.as_deref(), Some("too short"));
+        assert_eq!(MessageFeedback::new(FeedbackRating::Up, Some(" ".to_string())).comment, None);
+    }
+
+    #[test]
+    fn test_history_preamble() {
+        let session = Uuid::new_v4();
+        let history = vec![
+            ChatMessage::user(session, "My name is Ann".to_string()),
+            ChatMessage::assistant(session, "Hi Ann!".to_string()),
+            ChatMessage::user(session, "What is my name?".to_string()),
+        ];
+
+        let preamble = ChatMessage::history_preamble(&history, 1000);
+        assert!(preamble.contains("user: My name is Ann\n\nassistant: Hi Ann!\n"));
+        assert!(!preamble.contains("What is my name?"));
+
+        // Only the newest turn fits
+        let preamble = ChatMessage::history_preamble(&history, 20);
+        assert!(preamble.contains("assistant: Hi Ann!") && !preamble.contains("My name is Ann"));
+        assert_eq!(ChatMessage::history_preamble(&history[..1], 1000), "");
+    }
+}
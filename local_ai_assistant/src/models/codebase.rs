@@ -0,0 +1,187 @@
+//! Codebase Model
+//!
+//! A local source repository added to the knowledge base. Files are split
+//! into chunks at function, type and block boundaries; each chunk is
+//! indexed under a `name/path:start-end` citation so answers can link back
+//! to the lines they came from.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Lines a code chunk grows to before a new one is started
+pub const CODE_CHUNK_LINES: usize = 60;
+
+/// Longest chunk; a longer definition is split into windows of this size
+pub const MAX_CODE_CHUNK_LINES: usize = 120;
+
+/// A repository indexed for "chat with my codebase"
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Codebase {
+    pub id: Uuid,
+    /// Short name used in citations, unique per profile
+    pub name: String,
+    /// Absolute path of the repository
+    pub root: String,
+    pub file_count: usize,
+    pub chunk_count: usize,
+    pub indexed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Lines of one source file, stored as a knowledge base document
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CodeChunk {
+    /// Path relative to the repository root, with `/` separators
+    pub path: String,
+    /// First and last line, 1-based and inclusive
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Markdown code fence language, e.g. "rust"
+    pub language: String,
+    pub text: String,
+}
+
+impl CodeChunk {
+    /// Citation and document title, e.g. "idoris/src/main.rs:10-42"
+    pub fn citation(&self, codebase: &str) -> String {
+        format!("{}/{}:{}-{}", codebase, self.path, self.start_line, self.end_line)
+    }
+
+    /// Document body: the citation, then the code in a fenced block
+    pub fn document_body(&self, codebase: &str) -> String {
+        format!("{}\n\n```{}\n{}\n```\n", self.citation(codebase), self.language, self.text.trim_end())
+    }
+}
+
+/// Splits a citation into codebase name, path and line range
+pub fn parse_citation(citation: &str) -> Option<(&str, &str, usize, usize)> {
+    let (location, lines) = citation.rsplit_once(':')?;
+    let (start, end) = lines.split_once('-')?;
+    let (codebase, path) = location.split_once('/')?;
+    if codebase.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some((codebase, path, start.parse().ok()?, end.parse().ok()?))
+}
+
+/// A retrieved code chunk, linked to the file in the editor
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CodeReference {
+    /// The chunk's citation
+    pub label: String,
+    /// Absolute path of the file
+    pub file: String,
+    pub line: usize,
+}
+
+impl CodeReference {
+    /// Opens the file at the line in VS Code
+    pub fn url(&self) -> String {
+        let file = self.file.replace('\\', "/");
+        let file = if file.starts_with('/') { file } else { format!("/{}", file) };
+        format!("vscode://file{}:{}", file, self.line)
+    }
+}
+
+/// Numbered Markdown links to the code an answer drew on
+pub fn format_code_references(references: &[CodeReference]) -> String {
+    references.iter()
+        .enumerate()
+        .map(|(i, r)| format!("{}. [{}]({})", i + 1, r.label, r.url()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Syntax units of a file without a parser: each top-level line (not
+/// indented, not a closing bracket) starts a unit. Rows are 0-based and
+/// inclusive.
+pub fn heuristic_units(source: &str) -> Vec<(usize, usize)> {
+    let lines: Vec<&str> = source.lines().collect();
+    let starts: Vec<usize> = lines.iter()
+        .enumerate()
+        .filter(|(_, line)| {
+            let starts_top_level = line.chars().next().is_some_and(|c| !c.is_whitespace());
+            starts_top_level && !line.starts_with(['}', ')', ']'])
+        })
+        .map(|(row, _)| row)
+        .collect();
+    starts.iter()
+        .enumerate()
+        .map(|(i, &start)| (start, starts.get(i + 1).map_or(lines.len().max(1) - 1, |next| next - 1)))
+        .collect()
+}
+
+/// Groups syntax units into chunks of about `target` lines, cutting only at
+/// the end of a unit. Every line of the file belongs to one chunk; lines
+/// between units go with the unit after them. A chunk longer than `max`
+/// lines is split into windows. Rows are 0-based and inclusive.
+pub fn chunk_line_ranges(units: &[(usize, usize)], line_count: usize, target: usize, max: usize) -> Vec<(usize, usize)> {
+    if line_count == 0 {
+        return Vec::new();
+    }
+    let mut ends: Vec<usize> = units.iter().map(|&(_, end)| end.min(line_count - 1)).collect();
+    ends.sort_unstable();
+    ends.dedup();
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut last_end: Option<usize> = None;
+    for end in ends {
+        if let Some(previous) = last_end {
+            if end + 1 - start > target {
+                ranges.push((start, previous));
+                start = previous + 1;
+            }
+        }
+        last_end = Some(end);
+    }
+    ranges.push((start, line_count - 1));
+
+    ranges.into_iter()
+        .flat_map(|(start, end)| {
+            (start..=end).step_by(max.max(1)).map(move |s| (s, (s + max - 1).min(end)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_citation() {
+        assert_eq!(parse_citation("idoris/src/core/llm.rs:10-42"), Some(("idoris", "src/core/llm.rs", 10, 42)));
+        assert_eq!(parse_citation("Some document title"), None);
+        assert_eq!(parse_citation("/path:1-2"), None);
+    }
+
+    #[test]
+    fn test_code_reference_url() {
+        let reference = CodeReference { label: String::new(), file: "/home/me/repo/src/main.rs".to_string(), line: 7 };
+        assert_eq!(reference.url(), "vscode://file/home/me/repo/src/main.rs:7");
+        let reference = CodeReference { label: String::new(), file: "C:\\repo\\main.rs".to_string(), line: 1 };
+        assert_eq!(reference.url(), "vscode://file/C:/repo/main.rs:1");
+    }
+
+    #[test]
+    fn test_heuristic_units() {
+        let source = "use std::io;\n\nfn a() {\n    1\n}\n\nfn b() {}\n";
+        assert_eq!(heuristic_units(source), vec![(0, 1), (2, 5), (6, 6)]);
+    }
+
+    #[test]
+    fn test_chunk_line_ranges_cuts_at_unit_ends() {
+        // Three 4-line units; two fit in a chunk of 8 lines
+        let units = [(0, 3), (4, 7), (8, 11)];
+        assert_eq!(chunk_line_ranges(&units, 12, 8, 20), vec![(0, 7), (8, 11)]);
+        // Trailing lines without a unit join the last chunk
+        assert_eq!(chunk_line_ranges(&units, 14, 8, 20), vec![(0, 7), (8, 13)]);
+    }
+
+    #[test]
+    fn test_chunk_line_ranges_splits_long_units() {
+        assert_eq!(chunk_line_ranges(&[(0, 24)], 25, 8, 10), vec![(0, 9), (10, 19), (20, 24)]);
+        assert!(chunk_line_ranges(&[], 0, 8, 10).is_empty());
+    }
+}
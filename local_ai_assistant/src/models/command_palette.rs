@@ -0,0 +1,75 @@
+//! Command Palette Model
+//!
+//! Fuzzy matching for the Ctrl/Cmd+K palette that jumps to sessions,
+//! panels, settings pages and actions.
+
+/// Entries listed at once
+pub const MAX_PALETTE_RESULTS: usize = 12;
+
+/// How well `text` matches a typed `query`, higher being better, or None if
+/// it doesn't. The query's characters must appear in order; matches that
+/// follow each other or start a word count for more. Case is ignored.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let query: Vec<char> = query.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut last_match: Option<usize> = None;
+    for (i, c) in text.iter().enumerate() {
+        if *c != query[next] {
+            continue;
+        }
+        score += 1;
+        if last_match.is_some_and(|last| last + 1 == i) {
+            score += 2;
+        }
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 3;
+        }
+        last_match = Some(i);
+        next += 1;
+        if next == query.len() {
+            return Some(score);
+        }
+    }
+    None
+}
+
+/// Indices of the items matching `query`, best first, keeping the original
+/// order among equal scores
+pub fn rank_matches<'a>(query: &str, labels: impl IntoIterator<Item = &'a str>) -> Vec<usize> {
+    let mut scored: Vec<(usize, u32)> = labels.into_iter()
+        .enumerate()
+        .filter_map(|(i, label)| fuzzy_score(query, label).map(|score| (i, score)))
+        .collect();
+    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(i, _)| i).take(MAX_PALETTE_RESULTS).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Anything"), Some(0));
+        assert!(fuzzy_score("img", "Image Generation").is_some());
+        assert!(fuzzy_score("IG", "image generation").is_some());
+        assert!(fuzzy_score("gi", "Image Generation").is_some());
+        assert_eq!(fuzzy_score("xyz", "Image Generation"), None);
+        // Word starts and runs beat scattered letters
+        assert!(fuzzy_score("tts", "TTS") > fuzzy_score("tts", "Trash settings"));
+    }
+
+    #[test]
+    fn test_rank_matches() {
+        let labels = ["Trash", "Text to Speech", "Settings: Appearance"];
+        assert_eq!(rank_matches("text", labels), vec![1]);
+        assert_eq!(rank_matches("", labels), vec![0, 1, 2]);
+        assert_eq!(rank_matches("t", labels)[0], 0);
+    }
+}
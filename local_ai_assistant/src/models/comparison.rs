@@ -0,0 +1,148 @@
+//! Model Comparison Model
+//!
+//! One prompt answered by two models, or one model at two temperatures,
+//! side by side, and the user's vote on which answer was better.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use super::GenerationStats;
+
+/// Model and sampling settings for one side of a comparison
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CompareSide {
+    pub model_id: String,
+    pub temperature: f32,
+}
+
+impl CompareSide {
+    /// Short label, e.g. "qwen-2.5-7b @ 0.7"
+    pub fn label(&self) -> String {
+        format!("{} @ {:.1}", self.model_id, self.temperature)
+    }
+}
+
+/// One side's answer
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CompareAnswer {
+    pub side: CompareSide,
+    pub response: String,
+    #[serde(default)]
+    pub generation: Option<GenerationStats>,
+}
+
+/// Which answer the user preferred
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareVote {
+    Left,
+    Right,
+    Tie,
+}
+
+impl CompareVote {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompareVote::Left => "left",
+            CompareVote::Right => "right",
+            CompareVote::Tie => "tie",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "left" => Some(CompareVote::Left),
+            "right" => Some(CompareVote::Right),
+            "tie" => Some(CompareVote::Tie),
+            _ => None,
+        }
+    }
+}
+
+/// A prompt with both answers, kept for later review
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Comparison {
+    pub id: Uuid,
+    pub prompt: String,
+    pub left: CompareAnswer,
+    pub right: CompareAnswer,
+    pub vote: Option<CompareVote>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Votes for one model and temperature across comparisons
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompareTally {
+    pub label: String,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+}
+
+/// Wins, losses and ties per side label, most wins first
+pub fn tally_votes(comparisons: &[Comparison]) -> Vec<CompareTally> {
+    let mut tallies: Vec<CompareTally> = Vec::new();
+    let mut entry = |label: String| -> usize {
+        match tallies.iter().position(|t| t.label == label) {
+            Some(i) => i,
+            None => {
+                tallies.push(CompareTally { label, ..Default::default() });
+                tallies.len() - 1
+            }
+        }
+    };
+
+    let mut results = Vec::new();
+    for comparison in comparisons {
+        let Some(vote) = comparison.vote else { continue };
+        let left = entry(comparison.left.side.label());
+        let right = entry(comparison.right.side.label());
+        results.push((left, right, vote));
+    }
+    for (left, right, vote) in results {
+        match vote {
+            CompareVote::Left => {
+                tallies[left].wins += 1;
+                tallies[right].losses += 1;
+            }
+            CompareVote::Right => {
+                tallies[right].wins += 1;
+                tallies[left].losses += 1;
+            }
+            CompareVote::Tie => {
+                tallies[left].ties += 1;
+                tallies[right].ties += 1;
+            }
+        }
+    }
+    tallies.sort_by(|a, b| b.wins.cmp(&a.wins).then(a.losses.cmp(&b.losses)));
+    tallies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comparison(left: &str, right: &str, vote: Option<CompareVote>) -> Comparison {
+        let answer = |model: &str| CompareAnswer {
+            side: CompareSide { model_id: model.to_string(), temperature: 0.7 },
+            response: String::new(),
+            generation: None,
+        };
+        Comparison { id: Uuid::new_v4(), prompt: String::new(), left: answer(left), right: answer(right), vote, created_at: Utc::now() }
+    }
+
+    #[test]
+    fn test_tally_votes() {
+        let tallies = tally_votes(&[
+            comparison("a", "b", Some(CompareVote::Right)),
+            comparison("b", "a", Some(CompareVote::Left)),
+            comparison("a", "b", Some(CompareVote::Tie)),
+            comparison("a", "b", None),
+        ]);
+        assert_eq!(tallies.len(), 2);
+        assert_eq!(tallies[0], CompareTally { label: "b @ 0.7".to_string(), wins: 2, losses: 0, ties: 1 });
+        assert_eq!(tallies[1], CompareTally { label: "a @ 0.7".to_string(), wins: 0, losses: 2, ties: 1 });
+    }
+}
@@ -0,0 +1,677 @@
+//! Content Package Models
+//!
+//! A content package is everything produced for one topic by the content
+//! pipeline: research notes and the brief of sources behind them, outline,
+//! drafted article, key takeaways and FAQ, images, SEO metadata and social
+//! posts. Generation runs stage by stage and can pause at checkpoints for
+//! the user to review.
+//! Finished drafts can be translated, with each language kept alongside the
+//! original, images can be cropped, resized and compressed into web-ready
+//! export copies, and the article can be narrated. Finished packages export
+//! as a static HTML page with their assets next to it.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::article::{Article, ArticleSection};
+use super::format_profile::{escape_html, text_data_url, FormatProfile};
+use super::research::{ResearchBrief, ResearchSources};
+use super::seo::{json_ld, SeoMetadata};
+use super::upscale::UpscaledImage;
+use super::subtitles::SubtitleOptions;
+use super::video_timeline::{AssembledVideo, VideoClip};
+
+/// Pipeline stages, in execution order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationStage {
+    Research,
+    Outline,
+    Draft,
+    /// Key takeaways and an FAQ drawn from the draft
+    Extras,
+    Images,
+    Seo,
+    SocialPosts,
+}
+
+impl GenerationStage {
+    pub fn all() -> &'static [GenerationStage] {
+        &[
+            GenerationStage::Research,
+            GenerationStage::Outline,
+            GenerationStage::Draft,
+            GenerationStage::Extras,
+            GenerationStage::Images,
+            GenerationStage::Seo,
+            GenerationStage::SocialPosts,
+        ]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            GenerationStage::Research => "Research",
+            GenerationStage::Outline => "Outline",
+            GenerationStage::Draft => "Draft",
+            GenerationStage::Extras => "FAQ & Takeaways",
+            GenerationStage::Images => "Images",
+            GenerationStage::Seo => "SEO Pass",
+            GenerationStage::SocialPosts => "Social Posts",
+        }
+    }
+}
+
+/// Which stages run and after which ones the pipeline waits for approval
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PipelineDefinition {
+    pub stages: Vec<GenerationStage>,
+    pub checkpoints: Vec<GenerationStage>,
+    /// Feeds and pages read by the research stage
+    #[serde(default)]
+    pub research_sources: ResearchSources,
+}
+
+impl Default for PipelineDefinition {
+    fn default() -> Self {
+        Self {
+            stages: GenerationStage::all().to_vec(),
+            checkpoints: vec![GenerationStage::Outline, GenerationStage::Draft],
+            research_sources: ResearchSources::default(),
+        }
+    }
+}
+
+/// State of a pipeline job
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    /// Paused after a checkpoint stage until the user approves it
+    AwaitingApproval,
+    /// Stopped by an error; resuming retries the failed stage
+    Failed(String),
+    Completed,
+}
+
+/// Per-stage progress of a pipeline job
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenerationProgress {
+    pub job_id: Uuid,
+    pub topic: String,
+    pub pipeline: PipelineDefinition,
+    pub completed: Vec<GenerationStage>,
+    /// Stage being run, awaiting approval or failed
+    pub current: Option<GenerationStage>,
+    pub state: JobState,
+    pub message: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl GenerationProgress {
+    pub fn new(topic: String, pipeline: PipelineDefinition) -> Self {
+        Self {
+            job_id: Uuid::new_v4(),
+            topic,
+            pipeline,
+            completed: Vec::new(),
+            current: None,
+            state: JobState::Running,
+            message: "Queued".to_string(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// First stage of the pipeline that has not completed yet
+    pub fn next_stage(&self) -> Option<GenerationStage> {
+        self.pipeline.stages.iter().copied().find(|s| !self.completed.contains(s))
+    }
+
+    pub fn percent(&self) -> u8 {
+        if self.pipeline.stages.is_empty() {
+            return 100;
+        }
+        (self.completed.len() * 100 / self.pipeline.stages.len()).min(100) as u8
+    }
+}
+
+/// An image generated for the package
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PackageImage {
+    /// Section the image illustrates, or "Header"
+    pub caption: String,
+    pub prompt: String,
+    /// Saved image file, if image generation was available
+    pub path: Option<String>,
+    /// Web-ready copy used on export, if the image has been processed
+    #[serde(default)]
+    pub processed: Option<ProcessedImage>,
+    /// Upscaled copies, smallest first
+    #[serde(default)]
+    pub upscaled: Vec<UpscaledImage>,
+}
+
+impl PackageImage {
+    /// File to export: the processed copy if there is one, else the original
+    pub fn export_path(&self) -> Option<&str> {
+        self.processed.as_ref().map(|p| p.path.as_str()).or(self.path.as_deref())
+    }
+}
+
+/// File format of a processed image
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageOutputFormat {
+    #[default]
+    Jpeg,
+    /// Lossless WebP; quality does not apply
+    WebP,
+    Png,
+}
+
+impl ImageOutputFormat {
+    pub fn all() -> &'static [ImageOutputFormat] {
+        &[ImageOutputFormat::Jpeg, ImageOutputFormat::WebP, ImageOutputFormat::Png]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ImageOutputFormat::Jpeg => "JPEG",
+            ImageOutputFormat::WebP => "WebP",
+            ImageOutputFormat::Png => "PNG",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageOutputFormat::Jpeg => "jpg",
+            ImageOutputFormat::WebP => "webp",
+            ImageOutputFormat::Png => "png",
+        }
+    }
+}
+
+/// Region to keep, as fractions (0.0-1.0) of the original width and height
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CropRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for CropRect {
+    fn default() -> Self {
+        Self { x: 0.0, y: 0.0, width: 1.0, height: 1.0 }
+    }
+}
+
+/// Crop, resize and compression applied to an image before export
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImageEdit {
+    pub crop: CropRect,
+    /// Output size; the cropped image is scaled to cover it, trimming the
+    /// edges that do not fit the aspect ratio
+    pub width: u32,
+    pub height: u32,
+    pub format: ImageOutputFormat,
+    /// JPEG quality (1-100) to start from
+    pub quality: u8,
+    /// Largest file size to aim for; JPEG quality is lowered until it fits
+    pub max_kb: Option<u32>,
+}
+
+impl ImageEdit {
+    /// Header image size and compression suited to an export profile
+    pub fn for_profile(profile: FormatProfile) -> Self {
+        let (width, height) = match profile {
+            // Official account cover ratio is 2.35:1
+            FormatProfile::WeChat => (900, 383),
+            FormatProfile::Medium => (1400, 788),
+            FormatProfile::XThread => (1600, 900),
+            // Open Graph preview size
+            FormatProfile::Markdown => (1200, 630),
+        };
+        Self {
+            crop: CropRect::default(),
+            width,
+            height,
+            format: ImageOutputFormat::Jpeg,
+            quality: 85,
+            max_kb: Some(300),
+        }
+    }
+}
+
+/// An export-ready copy of a package image
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProcessedImage {
+    pub path: String,
+    pub edit: ImageEdit,
+    pub width: u32,
+    pub height: u32,
+    pub size_bytes: u64,
+    /// JPEG quality actually used after meeting the size target
+    pub quality: Option<u8>,
+}
+
+impl ProcessedImage {
+    /// Whether the file is still larger than the edit's size target
+    pub fn over_target(&self) -> bool {
+        self.edit.max_kb.is_some_and(|kb| self.size_bytes > u64::from(kb) * 1024)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SocialPost {
+    pub platform: String,
+    pub text: String,
+}
+
+/// A TTS reading of the finished article
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NarrationAsset {
+    pub path: String,
+    /// TTS engine that produced it
+    pub engine: String,
+    /// Audio container, e.g. "wav" or "aiff"
+    pub format: String,
+    pub duration_ms: u32,
+}
+
+impl NarrationAsset {
+    pub fn mime_type(&self) -> String {
+        match self.format.as_str() {
+            "mp3" => "audio/mpeg".to_string(),
+            other => format!("audio/{}", other),
+        }
+    }
+}
+
+/// The article translated into another language
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContentTranslation {
+    /// Language name (e.g. "French") or code (e.g. "ja")
+    pub language: String,
+    pub title: String,
+    /// (section title, translated text)
+    pub sections: Vec<(String, String)>,
+}
+
+impl ContentTranslation {
+    /// The translated article, titled with the translated title
+    pub fn article(&self) -> Article {
+        Article::new(&self.title, self.sections.iter().cloned())
+    }
+
+    pub fn to_markdown(&self) -> String {
+        self.article().to_markdown()
+    }
+
+    /// Data URL for downloading the translation as a Markdown file
+    pub fn markdown_data_url(&self) -> String {
+        text_data_url("text/markdown", &self.to_markdown())
+    }
+}
+
+/// Artifacts produced by the pipeline
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContentPackage {
+    pub topic: String,
+    pub research_notes: String,
+    /// Sources the research notes were drawn from
+    #[serde(default)]
+    pub research_brief: Option<ResearchBrief>,
+    /// (section title, what it should cover)
+    pub outline: Vec<(String, String)>,
+    /// (section title, drafted text)
+    pub sections: Vec<(String, String)>,
+    pub images: Vec<PackageImage>,
+    pub seo: Option<SeoMetadata>,
+    pub social_posts: Vec<SocialPost>,
+    #[serde(default)]
+    pub translations: Vec<ContentTranslation>,
+    #[serde(default)]
+    pub narration: Option<NarrationAsset>,
+    /// Clips to join into the package video, in play order
+    #[serde(default)]
+    pub video_clips: Vec<VideoClip>,
+    #[serde(default)]
+    pub video: Option<AssembledVideo>,
+    /// How subtitles are made when the video is assembled
+    #[serde(default)]
+    pub subtitle_options: SubtitleOptions,
+    #[serde(default)]
+    pub extras: ArticleExtras,
+}
+
+impl ContentPackage {
+    /// Adds a translation, replacing any earlier one for the same language
+    pub fn set_translation(&mut self, translation: ContentTranslation) {
+        self.translations.retain(|t| !t.language.eq_ignore_ascii_case(&translation.language));
+        self.translations.push(translation);
+    }
+
+    /// Package holding a drafted article, e.g. one written in the editor
+    pub fn from_article(article: &Article) -> Self {
+        Self { topic: article.title.clone(), sections: article.section_pairs(), ..Default::default() }
+    }
+
+    /// The drafted article titled with the topic, with the key takeaways
+    /// before the sections and the FAQ after them, each only when it is
+    /// included
+    pub fn article(&self) -> Article {
+        let mut article = Article::new(&self.topic, self.sections.iter().cloned());
+        if let Some(takeaways) = self.extras.takeaways_section() {
+            article.sections.insert(0, takeaways);
+        }
+        if let Some(faq) = self.extras.faq_section() {
+            article.sections.push(faq);
+        }
+        article
+    }
+
+    /// File name of the first exported image, shared as the cover
+    pub fn cover_image(&self) -> Option<String> {
+        self.images.iter().find_map(|image| image.export_path()).map(file_name)
+    }
+
+    /// The drafted article as Markdown, titled with the topic
+    pub fn to_markdown(&self) -> String {
+        self.article().to_markdown()
+    }
+
+    /// The drafted sections as plain text for reading aloud; the takeaways
+    /// and FAQ repeat the article, so they are not read
+    pub fn narration_text(&self) -> String {
+        Article::new(&self.topic, self.sections.iter().cloned()).narration_text()
+    }
+
+    /// The drafted sections as HTML, without the title
+    pub fn sections_html(&self) -> String {
+        self.article().sections_html()
+    }
+
+    /// Standalone HTML page for the static-site export; images, the
+    /// narration and video are referenced by file name, so they must sit
+    /// next to it
+    pub fn to_html(&self) -> String {
+        let title = self.seo.as_ref().map(|s| s.title.as_str()).filter(|t| !t.is_empty()).unwrap_or(&self.topic);
+        let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>{}</title>\n", escape_html(title)));
+        if let Some(seo) = &self.seo {
+            html.push_str(&format!("<meta name=\"description\" content=\"{}\">\n", escape_html(&seo.meta_description)));
+            html.push_str(&format!("<meta name=\"keywords\" content=\"{}\">\n", escape_html(&seo.keywords.join(", "))));
+            if !seo.canonical_url.trim().is_empty() {
+                html.push_str(&format!("<link rel=\"canonical\" href=\"{}\">\n", escape_html(seo.canonical_url.trim())));
+            }
+            let cover = self.cover_image();
+            html.push_str(&seo.social_meta_tags(cover.as_deref()));
+            html.push_str(&format!("<script type=\"application/ld+json\">{}</script>\n", seo.article_json_ld(cover.as_deref())));
+        }
+        if let Some(schema) = self.extras.faq_json_ld() {
+            html.push_str(&format!("<script type=\"application/ld+json\">{}</script>\n", schema));
+        }
+        html.push_str("</head>\n<body>\n<article>\n");
+        html.push_str(&format!("<h1>{}</h1>\n", escape_html(&self.topic)));
+
+        if let Some(video) = &self.video {
+            // Burned-in subtitles are already in the picture
+            let track = video.subtitles.as_ref()
+                .filter(|s| !s.burned_in)
+                .map(|s| format!("<track kind=\"subtitles\" src=\"{}\" default>", escape_html(&file_name(&s.vtt_path))))
+                .unwrap_or_default();
+            let poster = video.thumbnail.as_ref()
+                .map(|t| format!(" poster=\"{}\"", escape_html(&file_name(t))))
+                .unwrap_or_default();
+            html.push_str(&format!(
+                "<video controls preload=\"metadata\"{} style=\"max-width:100%;\"><source src=\"{}\" type=\"video/mp4\">{}Your browser does not support video playback.</video>\n",
+                poster,
+                escape_html(&file_name(&video.path)),
+                track
+            ));
+        }
+        // A narrated video already carries the narration
+        let narrated_video = self.video.as_ref().is_some_and(|v| v.with_narration);
+        if let Some(narration) = self.narration.as_ref().filter(|_| !narrated_video) {
+            html.push_str(&format!(
+                "<audio controls preload=\"none\"><source src=\"{}\" type=\"{}\">Your browser does not support audio playback.</audio>\n",
+                escape_html(&file_name(&narration.path)),
+                narration.mime_type()
+            ));
+        }
+        for image in &self.images {
+            if let Some(path) = image.export_path() {
+                html.push_str(&format!(
+                    "<img src=\"{}\" alt=\"{}\" style=\"max-width:100%;\">\n",
+                    escape_html(&file_name(path)),
+                    escape_html(&image.caption)
+                ));
+            }
+        }
+
+        html.push_str(&self.sections_html());
+        html.push_str("</article>\n</body>\n</html>\n");
+        html
+    }
+}
+
+/// Last component of a saved asset path
+fn file_name(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// A question and answer of the article's FAQ
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FaqEntry {
+    pub question: String,
+    pub answer: String,
+}
+
+/// Blocks generated from the finished draft, added to the article only
+/// when included
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArticleExtras {
+    pub key_takeaways: Vec<String>,
+    pub faq: Vec<FaqEntry>,
+    #[serde(default)]
+    pub include_takeaways: bool,
+    /// Also adds the FAQ's schema.org markup to the exported page
+    #[serde(default)]
+    pub include_faq: bool,
+}
+
+impl ArticleExtras {
+    pub fn is_empty(&self) -> bool {
+        self.key_takeaways.is_empty() && self.faq.is_empty()
+    }
+
+    fn takeaways_section(&self) -> Option<ArticleSection> {
+        if !self.include_takeaways || self.key_takeaways.is_empty() {
+            return None;
+        }
+        let list = self.key_takeaways.iter().map(|t| format!("- {}", t)).collect::<Vec<_>>().join("\n");
+        Some(ArticleSection { title: "Key takeaways".to_string(), body: list })
+    }
+
+    fn faq_section(&self) -> Option<ArticleSection> {
+        if !self.include_faq || self.faq.is_empty() {
+            return None;
+        }
+        let entries = self.faq.iter()
+            .map(|entry| format!("### {}\n\n{}", entry.question, entry.answer))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        Some(ArticleSection { title: "Frequently asked questions".to_string(), body: entries })
+    }
+
+    /// schema.org FAQPage markup for the page head, when the FAQ is included
+    pub fn faq_json_ld(&self) -> Option<String> {
+        if !self.include_faq || self.faq.is_empty() {
+            return None;
+        }
+        let questions: Vec<serde_json::Value> = self.faq.iter()
+            .map(|entry| serde_json::json!({
+                "@type": "Question",
+                "name": entry.question,
+                "acceptedAnswer": { "@type": "Answer", "text": entry.answer },
+            }))
+            .collect();
+        let schema = serde_json::json!({
+            "@context": "https://schema.org",
+            "@type": "FAQPage",
+            "mainEntity": questions,
+        });
+        Some(json_ld(&schema))
+    }
+}
+
+/// Reads key takeaways and FAQ entries from a plain-text reply laid out as
+/// "Takeaways:" bullets followed by "Q:" and "A:" lines
+pub fn parse_article_extras(reply: &str) -> (Vec<String>, Vec<FaqEntry>) {
+    let mut takeaways = Vec::new();
+    let mut faq: Vec<FaqEntry> = Vec::new();
+    for line in reply.lines().map(str::trim) {
+        if let Some(question) = line.strip_prefix("Q:") {
+            faq.push(FaqEntry { question: question.trim().to_string(), answer: String::new() });
+        } else if let Some(answer) = line.strip_prefix("A:") {
+            if let Some(entry) = faq.last_mut().filter(|e| e.answer.is_empty()) {
+                entry.answer = answer.trim().to_string();
+            }
+        } else if let Some(takeaway) = line.strip_prefix(['-', '*', '•']) {
+            if faq.is_empty() && !takeaway.trim().is_empty() {
+                takeaways.push(takeaway.trim().to_string());
+            }
+        }
+    }
+    faq.retain(|e| !e.question.is_empty() && !e.answer.is_empty());
+    (takeaways, faq)
+}
+
+/// A pipeline job: progress plus the artifacts so far
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PipelineJob {
+    pub progress: GenerationProgress,
+    pub package: ContentPackage,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SubtitleSource, SubtitleTrack};
+
+    #[test]
+    fn test_next_stage_and_percent() {
+        let mut progress = GenerationProgress::new("Rust".into(), PipelineDefinition::default());
+        assert_eq!(progress.next_stage(), Some(GenerationStage::Research));
+        assert_eq!(progress.percent(), 0);
+
+        progress.completed.extend([GenerationStage::Research, GenerationStage::Outline, GenerationStage::Draft]);
+        assert_eq!(progress.next_stage(), Some(GenerationStage::Extras));
+        assert_eq!(progress.percent(), 42);
+
+        progress.completed = GenerationStage::all().to_vec();
+        assert_eq!(progress.next_stage(), None);
+        assert_eq!(progress.percent(), 100);
+    }
+
+    #[test]
+    fn test_translations() {
+        let mut package = ContentPackage::default();
+        let translation = ContentTranslation {
+            language: "French".into(),
+            title: "Bonjour".into(),
+            sections: vec![("Intro".into(), "Salut à tous".into())],
+        };
+        package.set_translation(translation.clone());
+        package.set_translation(ContentTranslation { language: "french".into(), ..translation.clone() });
+        assert_eq!(package.translations.len(), 1);
+
+        assert_eq!(translation.to_markdown(), "# Bonjour\n\n## Intro\n\nSalut à tous\n\n");
+        assert!(translation.markdown_data_url().starts_with("data:text/markdown;charset=utf-8,%23%20Bonjour%0A"));
+    }
+
+    #[test]
+    fn test_narration_and_html_export() {
+        let mut package = ContentPackage {
+            topic: "Rust & WASM".into(),
+            sections: vec![("Intro".into(), "**Fast** code.\n\n![Generated Image](data:image/png;base64,AA)".into())],
+            images: vec![PackageImage {
+                caption: "Header".into(),
+                prompt: String::new(),
+                path: Some("/data/jobs/1/header.png".into()),
+                processed: None,
+                upscaled: Vec::new(),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(package.narration_text(), "Rust & WASM.\n\nIntro.\n\nFast code.");
+
+        package.narration = Some(NarrationAsset {
+            path: "/data/jobs/1/narration.wav".into(),
+            engine: "System TTS".into(),
+            format: "wav".into(),
+            duration_ms: 1000,
+        });
+        let html = package.to_html();
+        assert!(html.contains("<title>Rust &amp; WASM</title>"));
+        assert!(html.contains("<source src=\"narration.wav\" type=\"audio/wav\">"));
+        assert!(html.contains("<img src=\"header.png\""));
+        assert!(html.contains("<strong>Fast</strong>"));
+
+        package.video = Some(AssembledVideo {
+            path: "/data/jobs/1/video.mp4".into(),
+            duration_ms: 9000,
+            clip_count: 2,
+            with_narration: true,
+            subtitles: Some(SubtitleTrack {
+                srt_path: "/data/jobs/1/subtitles.srt".into(),
+                vtt_path: "/data/jobs/1/subtitles.vtt".into(),
+                source: SubtitleSource::Script,
+                cue_count: 4,
+                burned_in: false,
+            }),
+            thumbnail: Some("/data/jobs/1/video.jpg".into()),
+        });
+        let html = package.to_html();
+        assert!(html.contains("<video controls preload=\"metadata\" poster=\"video.jpg\""));
+        assert!(html.contains("<source src=\"video.mp4\" type=\"video/mp4\"><track kind=\"subtitles\" src=\"subtitles.vtt\" default>"));
+        assert!(!html.contains("narration.wav"));
+    }
+
+    #[test]
+    fn test_article_extras() {
+        let (takeaways, faq) = parse_article_extras(
+            "Takeaways:\n- Rust is fast\n* WASM runs anywhere\n\nQ: Is it hard?\nA: A bit at first.\nQ: Unanswered?\n- not a takeaway",
+        );
+        assert_eq!(takeaways, vec!["Rust is fast", "WASM runs anywhere"]);
+        assert_eq!(faq, vec![FaqEntry { question: "Is it hard?".into(), answer: "A bit at first.".into() }]);
+
+        let mut package = ContentPackage {
+            topic: "Rust".into(),
+            sections: vec![("Intro".into(), "Hello.".into())],
+            extras: ArticleExtras {
+                key_takeaways: takeaways,
+                faq: vec![FaqEntry { question: "Why?".into(), answer: "See </script> tags.".into() }],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(package.article().sections.len(), 1);
+        assert!(package.extras.faq_json_ld().is_none());
+
+        package.extras.include_takeaways = true;
+        package.extras.include_faq = true;
+        let sections = package.article().section_pairs();
+        assert_eq!(sections[0], ("Key takeaways".into(), "- Rust is fast\n- WASM runs anywhere".into()));
+        assert_eq!(sections[2], ("Frequently asked questions".into(), "### Why?\n\nSee </script> tags.".into()));
+
+        let schema = package.extras.faq_json_ld().unwrap();
+        assert!(schema.contains("\"@type\":\"FAQPage\""));
+        assert!(!schema.contains("</script>"));
+        assert!(package.to_html().contains("<script type=\"application/ld+json\">"));
+    }
+}
@@ -6,6 +6,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::{Article, ContentPackage, SectionReview};
+
 /// Target platform for content
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
 pub enum Platform {
@@ -362,6 +364,9 @@ pub struct EditorSection {
     pub content: String,
     pub is_generated: bool,
     pub is_expanded: bool,
+    /// Latest proofreading result
+    #[serde(default)]
+    pub review: Option<SectionReview>,
 }
 
 impl EditorSection {
@@ -372,6 +377,7 @@ impl EditorSection {
             content: String::new(),
             is_generated: false,
             is_expanded: true,
+            review: None,
         }
     }
 
@@ -400,27 +406,92 @@ impl EditorContent {
         }
     }
 
+    /// The title and section text, for rendering and export
+    pub fn article(&self) -> Article {
+        Article::new(&self.title, self.sections.iter().map(|s| (&s.title, &s.content)))
+    }
+
     pub fn to_markdown(&self) -> String {
-        let mut md = format!("# {}\n\n", self.title);
+        self.article().to_markdown()
+    }
 
-        for section in &self.sections {
-            md.push_str(&format!("## {}\n\n", section.title));
-            md.push_str(&section.content);
-            md.push_str("\n\n");
+    /// The editor's article as a content package, e.g. for translation
+    pub fn to_package(&self) -> ContentPackage {
+        ContentPackage::from_article(&self.article())
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.article().word_count()
+    }
+}
+
+/// Parses "## Title" headed sections with their descriptions
+pub fn parse_outline(response: &str) -> Vec<(String, String)> {
+    let mut sections = Vec::new();
+    let mut current_title: Option<String> = None;
+    let mut current_content = String::new();
+
+    for line in response.lines() {
+        let trimmed = line.trim();
+
+        if let Some(title) = trimmed.strip_prefix("## ") {
+            if let Some(previous) = current_title.take() {
+                sections.push((previous, current_content.trim().to_string()));
+                current_content.clear();
+            }
+            current_title = Some(title.trim().to_string());
+        } else if current_title.is_some() && !trimmed.is_empty() {
+            if !current_content.is_empty() {
+                current_content.push(' ');
+            }
+            current_content.push_str(trimmed);
         }
+    }
 
-        md
+    if let Some(title) = current_title {
+        sections.push((title, current_content.trim().to_string()));
     }
 
-    pub fn to_html(&self) -> String {
-        let md = self.to_markdown();
-        comrak::markdown_to_html(&md, &comrak::Options::default())
+    sections
+}
+
+/// Sections used when the model's outline cannot be parsed
+pub fn default_outline() -> Vec<(String, String)> {
+    vec![
+        ("Introduction".to_string(), "Write an engaging introduction".to_string()),
+        ("Background".to_string(), "Provide context and background".to_string()),
+        ("Main Content".to_string(), "Elaborate on the main topic".to_string()),
+        ("Conclusion".to_string(), "Summarize key points".to_string()),
+    ]
+}
+
+/// Progress of one section during batched expansion, streamed to the
+/// editor as one JSON object per line
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SectionExpansionEvent {
+    Started { index: usize },
+    /// Generated text so far, sent as it streams in
+    Token { index: usize, text: String },
+    Done { index: usize, content: String },
+    Failed { index: usize, error: String },
+}
+
+impl SectionExpansionEvent {
+    pub fn to_line(&self) -> String {
+        format!("{}\n", serde_json::to_string(self).unwrap_or_default())
     }
 
-    pub fn word_count(&self) -> usize {
-        self.sections.iter()
-            .map(|s| s.content.split_whitespace().count())
-            .sum()
+    /// Takes the complete lines out of a stream buffer and parses them,
+    /// leaving any trailing partial line in place
+    pub fn drain_lines(buffer: &mut String) -> Vec<Self> {
+        let Some(end) = buffer.rfind('\n') else {
+            return Vec::new();
+        };
+        let complete: String = buffer.drain(..=end).collect();
+        complete.lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
     }
 }
 
@@ -457,4 +528,33 @@ mod tests {
         assert!(md.contains("## Introduction"));
         assert!(md.contains("Hello world!"));
     }
+
+    #[test]
+    fn test_parse_outline() {
+        let response = "## Introduction\nThis section introduces the topic.\n\n## Main Content\nThe main points.\nMore detail.\n\n## Conclusion\nWrap up.";
+        let sections = parse_outline(response);
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0].0, "Introduction");
+        assert_eq!(sections[1].1, "The main points. More detail.");
+        assert!(parse_outline("no headings here").is_empty());
+    }
+
+    #[test]
+    fn test_section_expansion_events_survive_chunking() {
+        let stream = [
+            SectionExpansionEvent::Started { index: 1 }.to_line(),
+            SectionExpansionEvent::Token { index: 1, text: "Hel\nlo".into() }.to_line(),
+            SectionExpansionEvent::Done { index: 1, content: "Hello".into() }.to_line(),
+        ].concat();
+        let (first, second) = stream.split_at(20);
+
+        let mut buffer = first.to_string();
+        let mut events = SectionExpansionEvent::drain_lines(&mut buffer);
+        buffer.push_str(second);
+        events.extend(SectionExpansionEvent::drain_lines(&mut buffer));
+
+        assert!(buffer.is_empty());
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1], SectionExpansionEvent::Token { index: 1, text: "Hel\nlo".into() });
+    }
 }
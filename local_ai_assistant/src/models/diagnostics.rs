@@ -0,0 +1,38 @@
+//! System Diagnostics Model
+//!
+//! Snapshot of resource usage reported by the server for the System tab
+//! and the status bar.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Resource usage snapshot. Fields are `None` when the platform does not
+/// expose the value (e.g. no discrete GPU).
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct SystemStats {
+    /// Resident memory of the server process
+    pub process_memory_mb: Option<u64>,
+    /// Total physical memory of the machine
+    pub total_memory_mb: Option<u64>,
+    /// GPU / Metal memory currently in use
+    pub gpu_memory_used_mb: Option<u64>,
+    /// GPU memory available (unified memory on Apple Silicon)
+    pub gpu_memory_total_mb: Option<u64>,
+    /// GPU description, e.g. "NVIDIA GeForce RTX 4090" or "Apple GPU (Metal)"
+    pub gpu_name: Option<String>,
+    /// Size of the ./data directory (sessions, logs, generated assets)
+    pub data_dir_size_mb: u64,
+    /// Size of the HuggingFace model cache
+    pub hf_cache_size_mb: u64,
+    /// Throughput of the most recent LLM generation
+    pub last_tokens_per_sec: Option<f32>,
+    /// Average throughput over all generations since startup
+    pub avg_tokens_per_sec: Option<f32>,
+    /// Total tokens generated since startup
+    pub total_tokens_generated: u64,
+    /// Generation requests waiting for the model
+    #[serde(default)]
+    pub queued_generations: usize,
+    /// When this snapshot was taken
+    pub sampled_at: Option<DateTime<Utc>>,
+}
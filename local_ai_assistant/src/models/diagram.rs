@@ -0,0 +1,148 @@
+//! Diagram Model
+//!
+//! Mermaid and Graphviz code blocks in Markdown, picked out so the UI can
+//! show them as diagrams between the rendered text.
+
+use serde::{Deserialize, Serialize};
+
+/// Diagram language of a fenced code block
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiagramKind {
+    Mermaid,
+    Graphviz,
+}
+
+impl DiagramKind {
+    /// Kind for a code block's info string, e.g. "mermaid" or "dot"
+    pub fn from_lang(lang: &str) -> Option<Self> {
+        match lang.split_whitespace().next()?.to_lowercase().as_str() {
+            "mermaid" => Some(DiagramKind::Mermaid),
+            "dot" | "graphviz" => Some(DiagramKind::Graphviz),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            DiagramKind::Mermaid => "Mermaid",
+            DiagramKind::Graphviz => "Graphviz",
+        }
+    }
+
+    /// Info string to write the diagram back as a code block
+    pub fn lang(&self) -> &'static str {
+        match self {
+            DiagramKind::Mermaid => "mermaid",
+            DiagramKind::Graphviz => "dot",
+        }
+    }
+}
+
+/// A diagram's source code
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiagramBlock {
+    pub kind: DiagramKind,
+    pub source: String,
+}
+
+impl DiagramBlock {
+    /// The diagram as a fenced code block, shown when it can't be rendered
+    pub fn to_markdown(&self) -> String {
+        format!("```{}\n{}\n```\n", self.kind.lang(), self.source)
+    }
+}
+
+/// Part of a Markdown document
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContentSegment {
+    Markdown(String),
+    Diagram(DiagramBlock),
+}
+
+/// Splits Markdown at its closed diagram code blocks. A block still being
+/// written, without its closing fence, stays Markdown.
+pub fn split_diagrams(markdown: &str) -> Vec<ContentSegment> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    // Open fence marker, the diagram kind if it is a diagram, and for
+    // diagrams the block's lines from the opening fence on
+    let mut open: Option<(String, Option<DiagramKind>, String)> = None;
+
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim();
+        match open.take() {
+            Some((marker, kind, mut body)) => {
+                let closes = trimmed.starts_with(marker.as_str()) && trimmed.trim_start_matches(marker.chars().next().unwrap()).is_empty();
+                match (closes, kind) {
+                    (true, Some(kind)) => {
+                        if !text.is_empty() {
+                            segments.push(ContentSegment::Markdown(std::mem::take(&mut text)));
+                        }
+                        let source = body.split_once('\n').map(|(_, source)| source).unwrap_or_default();
+                        segments.push(ContentSegment::Diagram(DiagramBlock { kind, source: source.trim_end().to_string() }));
+                    }
+                    (true, None) => text.push_str(line),
+                    (false, Some(kind)) => {
+                        body.push_str(line);
+                        open = Some((marker, Some(kind), body));
+                    }
+                    (false, None) => {
+                        text.push_str(line);
+                        open = Some((marker, None, body));
+                    }
+                }
+            }
+            None if trimmed.starts_with("```") || trimmed.starts_with("~~~") => {
+                let fence_char = trimmed.chars().next().unwrap();
+                let marker: String = trimmed.chars().take_while(|&c| c == fence_char).collect();
+                let kind = DiagramKind::from_lang(&trimmed[marker.len()..]);
+                let body = match kind {
+                    Some(_) => line.to_string(),
+                    None => {
+                        text.push_str(line);
+                        String::new()
+                    }
+                };
+                open = Some((marker, kind, body));
+            }
+            None => text.push_str(line),
+        }
+    }
+
+    // An unclosed diagram block is shown as the code typed so far
+    if let Some((_, Some(_), body)) = open {
+        text.push_str(&body);
+    }
+    if !text.is_empty() {
+        segments.push(ContentSegment::Markdown(text));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splits_closed_diagram_blocks() {
+        let markdown = "Intro\n\n```mermaid\ngraph TD\n  A --> B\n```\n\nBetween\n```dot\ndigraph { a -> b }\n```\nEnd";
+        let segments = split_diagrams(markdown);
+        assert_eq!(segments.len(), 5);
+        assert_eq!(segments[0], ContentSegment::Markdown("Intro\n\n".to_string()));
+        assert_eq!(
+            segments[1],
+            ContentSegment::Diagram(DiagramBlock { kind: DiagramKind::Mermaid, source: "graph TD\n  A --> B".to_string() })
+        );
+        assert_eq!(segments[3], ContentSegment::Diagram(DiagramBlock { kind: DiagramKind::Graphviz, source: "digraph { a -> b }".to_string() }));
+        assert_eq!(segments[4], ContentSegment::Markdown("End".to_string()));
+    }
+
+    #[test]
+    fn test_keeps_other_and_unclosed_blocks_as_markdown() {
+        let code = "```rust\nlet s = \"```mermaid\";\n```\n";
+        assert_eq!(split_diagrams(code), vec![ContentSegment::Markdown(code.to_string())]);
+
+        let streaming = "Here:\n```mermaid\ngraph TD\n";
+        assert_eq!(split_diagrams(streaming), vec![ContentSegment::Markdown(streaming.to_string())]);
+    }
+}
@@ -0,0 +1,84 @@
+//! Disk Space Models
+//!
+//! Model downloads and media generation check the free space where they
+//! will write before starting. Work that would leave less than
+//! [`DISK_RESERVE_BYTES`] free is refused with a clear message instead of
+//! failing halfway; work that leaves less than [`LOW_DISK_BYTES`] goes
+//! ahead with a warning.
+
+const MB: u64 = 1024 * 1024;
+const GB: u64 = 1024 * MB;
+
+/// Free space always left for the system and other apps
+pub const DISK_RESERVE_BYTES: u64 = GB;
+
+/// Free space left below which a warning is given
+pub const LOW_DISK_BYTES: u64 = 5 * GB;
+
+/// Rough size of a generated image, with the model's scratch files
+pub const IMAGE_ESTIMATE_BYTES: u64 = 50 * MB;
+
+/// Rough size of generated speech and its intermediate files
+pub const SPEECH_ESTIMATE_BYTES: u64 = 100 * MB;
+
+/// Rough size of a generated video clip
+pub const VIDEO_ESTIMATE_BYTES: u64 = 200 * MB;
+
+/// Whether there is room for a write
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpaceCheck {
+    Enough,
+    /// It fits, but leaves little free
+    Low { available: u64 },
+    /// It would leave less than the reserve free
+    Insufficient { available: u64 },
+}
+
+/// Checks whether `needed` bytes fit in `available`
+pub fn check_space(needed: u64, available: u64) -> SpaceCheck {
+    match available.checked_sub(needed) {
+        Some(left) if left >= LOW_DISK_BYTES => SpaceCheck::Enough,
+        Some(left) if left >= DISK_RESERVE_BYTES => SpaceCheck::Low { available },
+        _ => SpaceCheck::Insufficient { available },
+    }
+}
+
+/// Free bytes from the output of `df -Pk <path>`
+pub fn parse_df_available(output: &str) -> Option<u64> {
+    // POSIX format: Filesystem, 1024-blocks, Used, Available, Capacity, Mounted on
+    let line = output.lines().nth(1)?;
+    let kb: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// A size for messages, e.g. "640 MB" or "12.3 GB"
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes >= GB {
+        format!("{:.1} GB", bytes as f64 / GB as f64)
+    } else {
+        format!("{} MB", bytes.div_ceil(MB))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_space() {
+        assert_eq!(check_space(10 * GB, 30 * GB), SpaceCheck::Enough);
+        assert_eq!(check_space(10 * GB, 12 * GB), SpaceCheck::Low { available: 12 * GB });
+        assert_eq!(check_space(10 * GB, 10 * GB + MB), SpaceCheck::Insufficient { available: 10 * GB + MB });
+        assert_eq!(check_space(10 * GB, 2 * GB), SpaceCheck::Insufficient { available: 2 * GB });
+    }
+
+    #[test]
+    fn test_parse_df_available() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n/dev/disk3s5    971350180 612345678 345678901      64% /System/Volumes/Data\n";
+        assert_eq!(parse_df_available(output), Some(345678901 * 1024));
+        assert_eq!(parse_df_available("df: /missing: No such file or directory\n"), None);
+
+        assert_eq!(format_bytes(640 * MB), "640 MB");
+        assert_eq!(format_bytes(GB + GB / 2), "1.5 GB");
+    }
+}
@@ -5,6 +5,7 @@
 //! the database when providing context for conversations.
 
 use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
 
 /// Represents a simplified document search result
 ///
@@ -44,3 +45,411 @@ impl Document {
         self
     }
 }
+
+/// Summary of the vector store contents for the Database settings tab
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct VectorStoreStats {
+    /// Number of source documents indexed
+    pub document_count: usize,
+    /// Number of embedded chunks, if the store exposes it
+    pub chunk_count: Option<usize>,
+    /// Size of the on-disk index (database and embedding files)
+    pub index_size_bytes: u64,
+    /// When documents were last (re)indexed
+    pub last_indexed_at: Option<DateTime<Utc>>,
+    /// Vector size of the current index
+    pub dimensions: Option<usize>,
+}
+
+/// Documents retrieved for a chat message by default
+pub const DEFAULT_TOP_K: usize = 5;
+/// Minimum similarity score of a retrieved document by default
+pub const DEFAULT_MIN_SCORE: f32 = 0.5;
+
+/// How documents are retrieved for a chat message
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RetrievalOptions {
+    /// Only search this collection (context subfolder); None searches all
+    pub collection: Option<String>,
+    /// Documents passed to the model at most
+    pub top_k: usize,
+    /// Minimum similarity score from 0.0 to 1.0
+    pub min_score: f32,
+    /// Source type, tag and date conditions on the documents searched
+    #[serde(default)]
+    pub filter: MetadataFilter,
+}
+
+impl Default for RetrievalOptions {
+    fn default() -> Self {
+        Self { collection: None, top_k: DEFAULT_TOP_K, min_score: DEFAULT_MIN_SCORE, filter: MetadataFilter::default() }
+    }
+}
+
+impl RetrievalOptions {
+    /// Whether a document is searched with these options
+    pub fn matches(&self, metadata: &DocumentMetadata) -> bool {
+        let in_collection = self.collection.is_none() || metadata.collection == self.collection;
+        in_collection && self.filter.matches(metadata)
+    }
+
+    /// Whether only some documents are searched
+    pub fn is_filtered(&self) -> bool {
+        self.collection.is_some() || !self.filter.is_empty()
+    }
+}
+
+/// Where a knowledge base document came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SourceType {
+    Note,
+    WebPage,
+    Pdf,
+    Transcript,
+    Code,
+}
+
+impl SourceType {
+    pub const ALL: [SourceType; 5] = [SourceType::Note, SourceType::WebPage, SourceType::Pdf, SourceType::Transcript, SourceType::Code];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SourceType::Note => "Note",
+            SourceType::WebPage => "Web page",
+            SourceType::Pdf => "PDF",
+            SourceType::Transcript => "Transcript",
+            SourceType::Code => "Code",
+        }
+    }
+
+    /// Source type of a context folder file, from its extension and the
+    /// markers captures and transcripts are written with
+    pub fn detect(filename: &str, body: &str) -> Self {
+        if filename.to_lowercase().ends_with(".pdf") {
+            return SourceType::Pdf;
+        }
+        let head: Vec<&str> = body.lines().take(4).collect();
+        if head.iter().any(|line| line.starts_with("Transcript of ") || line.starts_with("Summary of the YouTube video ")) {
+            SourceType::Transcript
+        } else if body.lines().rev().take(3).any(|line| line.starts_with("Source: http")) {
+            SourceType::WebPage
+        } else {
+            SourceType::Note
+        }
+    }
+}
+
+/// Metadata a document can be filtered by in retrieval
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DocumentMetadata {
+    pub source_type: SourceType,
+    /// Lowercase tags set in the document viewer
+    pub tags: Vec<String>,
+    /// When the file was added, or the codebase last indexed
+    pub added_at: DateTime<Utc>,
+    /// Context subfolder or codebase name
+    pub collection: Option<String>,
+}
+
+/// Conditions on document metadata; an empty filter matches everything
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct MetadataFilter {
+    /// Only these source types; empty allows all
+    pub source_types: Vec<SourceType>,
+    /// Only documents with at least one of these tags; empty allows all
+    pub tags: Vec<String>,
+    pub added_after: Option<DateTime<Utc>>,
+    pub added_before: Option<DateTime<Utc>>,
+}
+
+impl MetadataFilter {
+    pub fn is_empty(&self) -> bool {
+        self.source_types.is_empty() && self.tags.is_empty() && self.added_after.is_none() && self.added_before.is_none()
+    }
+
+    pub fn matches(&self, metadata: &DocumentMetadata) -> bool {
+        (self.source_types.is_empty() || self.source_types.contains(&metadata.source_type))
+            && (self.tags.is_empty() || self.tags.iter().any(|tag| metadata.tags.contains(tag)))
+            && self.added_after.is_none_or(|after| metadata.added_at >= after)
+            && self.added_before.is_none_or(|before| metadata.added_at < before)
+    }
+}
+
+/// Earlier messages given to the model when rewriting a question
+pub const QUERY_HISTORY_MESSAGES: usize = 4;
+
+/// Characters kept of each earlier message in the rewrite prompt
+const QUERY_HISTORY_CHARS: usize = 400;
+
+/// How the question is turned into the text that is embedded for retrieval
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueryExpansion {
+    /// Embed the question as asked
+    #[default]
+    Off,
+    /// Rewrite it into a standalone search query using the conversation
+    Rewrite,
+    /// Embed a short hypothetical answer instead (HyDE)
+    Hypothetical,
+}
+
+impl QueryExpansion {
+    pub const ALL: [QueryExpansion; 3] = [QueryExpansion::Off, QueryExpansion::Rewrite, QueryExpansion::Hypothetical];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            QueryExpansion::Off => "Off",
+            QueryExpansion::Rewrite => "Standalone query",
+            QueryExpansion::Hypothetical => "Hypothetical answer",
+        }
+    }
+
+    /// Prompt asking the model for the search text, None when off.
+    /// `history` holds earlier messages as "role: text", oldest first.
+    pub fn prompt(&self, question: &str, history: &[String]) -> Option<String> {
+        let conversation = conversation_block(history);
+        match self {
+            QueryExpansion::Off => None,
+            QueryExpansion::Rewrite => Some(format!(
+                "{}Rewrite the last question as a standalone search query for a document search. \
+Resolve pronouns and references to the conversation, keep names and key terms, and add likely synonyms. \
+Reply with the query only, on one line.\n\nQuestion: {}",
+                conversation, question
+            )),
+            QueryExpansion::Hypothetical => Some(format!(
+                "{}Write a short passage, two or three sentences, that would answer the last question \
+as it might appear in a reference document. Reply with the passage only.\n\nQuestion: {}",
+                conversation, question
+            )),
+        }
+    }
+}
+
+/// Prompt condensing recent turns and a follow-up question into one search
+/// query, used when the conversation is searched with and expansion is off
+pub fn condense_prompt(question: &str, history: &[String]) -> String {
+    format!(
+        "{}Condense the conversation and the follow-up question into one standalone search query \
+that names what the question is about. Reply with the query only, on one line.\n\nFollow-up question: {}",
+        conversation_block(history), question
+    )
+}
+
+/// Earlier messages, shortened, as a prompt preamble
+fn conversation_block(history: &[String]) -> String {
+    if history.is_empty() {
+        return String::new();
+    }
+    let lines: Vec<String> = history.iter()
+        .map(|message| message.chars().take(QUERY_HISTORY_CHARS).collect())
+        .collect();
+    format!("Conversation so far:\n{}\n\n", lines.join("\n"))
+}
+
+/// The search text from the model's reply: without a label, quotes or
+/// trailing lines for a one-line query. Falls back to the question.
+pub fn clean_search_query(reply: &str, expansion: QueryExpansion, question: &str) -> String {
+    let reply = reply.trim();
+    let reply = match expansion {
+        QueryExpansion::Rewrite => reply.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default(),
+        _ => reply,
+    };
+    let reply = reply
+        .split_once(':')
+        .filter(|(label, _)| ["query", "search query", "passage", "answer"].contains(&label.trim().to_lowercase().as_str()))
+        .map_or(reply, |(_, rest)| rest)
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .trim();
+    if reply.is_empty() { question.to_string() } else { reply.to_string() }
+}
+
+/// Lowest weight recency gives a document's score, however old it is
+pub const RECENCY_FLOOR: f32 = 0.5;
+
+/// Recency half-lives offered per collection, in days
+pub const RECENCY_HALF_LIVES: [u32; 5] = [7, 30, 90, 180, 365];
+
+/// Weight of the similarity score of a document `age_days` old. It starts
+/// at 1.0 and halves its distance to [`RECENCY_FLOOR`] every half-life.
+pub fn recency_weight(age_days: f32, half_life_days: u32) -> f32 {
+    let decay = 0.5f32.powf(age_days.max(0.0) / half_life_days.max(1) as f32);
+    RECENCY_FLOOR + (1.0 - RECENCY_FLOOR) * decay
+}
+
+/// Parses a comma separated tag list into unique lowercase tags
+pub fn parse_document_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for raw in text.split([',', '\n']) {
+        let tag = raw.trim().trim_start_matches('#').trim().to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Start of the day of a `YYYY-MM-DD` date, as entered in a date input
+pub fn parse_filter_date(date: &str) -> Option<DateTime<Utc>> {
+    let date = chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+/// A raw retrieval hit as shown in the retrieval playground
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RetrievedChunk {
+    /// Title of the source document
+    pub title: String,
+    /// Text of the matched chunk
+    pub text: String,
+    /// Similarity score reported by the index
+    pub score: f32,
+    /// Whether the hit passes the similarity threshold used for chat RAG
+    pub passes_threshold: bool,
+}
+
+/// One chunk of an indexed context document
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DocumentChunk {
+    /// Position of the chunk in the document
+    pub index: usize,
+    /// Byte range of the chunk in the document text
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    /// Whether the chunk is embedded in the index and can be retrieved
+    pub embedded: bool,
+}
+
+/// A context document's stored text and its chunks, as shown in the
+/// document viewer
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DocumentDetails {
+    pub filename: String,
+    pub title: String,
+    pub body: String,
+    /// Empty if the document is not in the index yet
+    pub chunks: Vec<DocumentChunk>,
+    pub metadata: Option<DocumentMetadata>,
+}
+
+impl DocumentDetails {
+    /// Number of chunks currently in the index
+    pub fn embedded_count(&self) -> usize {
+        self.chunks.iter().filter(|c| c.embedded).count()
+    }
+}
+
+/// A draft passage that closely matches a knowledge base document
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SimilarityMatch {
+    /// Section the passage is in
+    pub section: String,
+    pub passage: String,
+    /// Title of the matching source document
+    pub source_title: String,
+    /// Text of the matching chunk
+    pub source_text: String,
+    pub score: f32,
+}
+
+/// Result of scanning a draft against the knowledge base
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct SimilarityReport {
+    pub passages_checked: usize,
+    /// Score at or above which a passage is flagged
+    pub threshold: f32,
+    /// Flagged passages, most similar first
+    pub matches: Vec<SimilarityMatch>,
+}
+
+/// Result of a quick capture into the knowledge base
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CapturedDocument {
+    /// Extracted title
+    pub title: String,
+    /// File name written to the context folder
+    pub filename: String,
+    /// Collection (context subfolder) the document was filed under
+    pub collection: Option<String>,
+    /// Whether the document was added to the live index
+    pub indexed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(source_type: SourceType, tags: &[&str], added: &str) -> DocumentMetadata {
+        DocumentMetadata {
+            source_type,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            added_at: parse_filter_date(added).unwrap(),
+            collection: None,
+        }
+    }
+
+    #[test]
+    fn test_source_type_detect() {
+        assert_eq!(SourceType::detect("paper.PDF", ""), SourceType::Pdf);
+        assert_eq!(SourceType::detect("talk.md", "# Talk\n\nTranscript of talk.mp3. Paragraphs..."), SourceType::Transcript);
+        assert_eq!(SourceType::detect("post.md", "# Post\n\nText\n\nSource: https://example.com\n"), SourceType::WebPage);
+        assert_eq!(SourceType::detect("notes.md", "# Notes\n\nText"), SourceType::Note);
+    }
+
+    #[test]
+    fn test_metadata_filter() {
+        let doc = metadata(SourceType::Note, &["api-docs"], "2024-07-02");
+        assert!(MetadataFilter::default().matches(&doc));
+
+        let filter = MetadataFilter {
+            tags: vec!["api-docs".to_string()],
+            added_after: parse_filter_date("2024-06-01"),
+            ..Default::default()
+        };
+        assert!(filter.matches(&doc));
+        assert!(!filter.matches(&metadata(SourceType::Note, &["api-docs"], "2024-05-31")));
+        assert!(!filter.matches(&metadata(SourceType::Note, &["blog"], "2024-07-02")));
+
+        let pdfs = MetadataFilter { source_types: vec![SourceType::Pdf], ..Default::default() };
+        assert!(!pdfs.matches(&doc));
+    }
+
+    #[test]
+    fn test_recency_weight() {
+        assert_eq!(recency_weight(0.0, 30), 1.0);
+        assert_eq!(recency_weight(30.0, 30), 0.75);
+        assert!((recency_weight(3650.0, 30) - RECENCY_FLOOR).abs() < 1e-3);
+        // Documents dated in the future count as new
+        assert_eq!(recency_weight(-5.0, 30), 1.0);
+    }
+
+    #[test]
+    fn test_query_expansion_prompt() {
+        assert_eq!(QueryExpansion::Off.prompt("why?", &[]), None);
+        let prompt = QueryExpansion::Rewrite.prompt("and its price?", &["user: Tell me about the X100".to_string()]).unwrap();
+        assert!(prompt.starts_with("Conversation so far:\nuser: Tell me about the X100\n\n"));
+        assert!(prompt.ends_with("Question: and its price?"));
+
+        let condensed = condense_prompt("what about its pricing?", &["user: What is Acme Cloud?".to_string()]);
+        assert!(condensed.contains("user: What is Acme Cloud?"));
+        assert!(condensed.ends_with("Follow-up question: what about its pricing?"));
+    }
+
+    #[test]
+    fn test_clean_search_query() {
+        let rewrite = QueryExpansion::Rewrite;
+        assert_eq!(clean_search_query("Query: \"X100 camera price\"\nExplanation: ...", rewrite, "q"), "X100 camera price");
+        assert_eq!(clean_search_query("  ", rewrite, "and its price?"), "and its price?");
+        assert_eq!(
+            clean_search_query("The X100 costs $1,399.\nIt shipped in 2023.", QueryExpansion::Hypothetical, "q"),
+            "The X100 costs $1,399.\nIt shipped in 2023."
+        );
+    }
+
+    #[test]
+    fn test_parse_document_tags() {
+        assert_eq!(parse_document_tags("API-docs, #rust,, api-docs"), vec!["api-docs", "rust"]);
+        assert!(parse_document_tags(" , ").is_empty());
+    }
+}
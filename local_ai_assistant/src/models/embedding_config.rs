@@ -0,0 +1,165 @@
+//! Embedding Backend Configuration
+//!
+//! Selects which model turns text into vectors for RAG. Shared between the
+//! settings UI and the server.
+
+use serde::{Deserialize, Serialize};
+
+/// Local BERT-family embedding models run through kalosm
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum LocalEmbeddingModel {
+    ArcticExtraSmall,
+    #[default]
+    ArcticSmall,
+    ArcticMedium,
+    ArcticLarge,
+}
+
+impl LocalEmbeddingModel {
+    pub fn all() -> [LocalEmbeddingModel; 4] {
+        [
+            LocalEmbeddingModel::ArcticExtraSmall,
+            LocalEmbeddingModel::ArcticSmall,
+            LocalEmbeddingModel::ArcticMedium,
+            LocalEmbeddingModel::ArcticLarge,
+        ]
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            LocalEmbeddingModel::ArcticExtraSmall => "snowflake-arctic-embed-xs",
+            LocalEmbeddingModel::ArcticSmall => "snowflake-arctic-embed-s",
+            LocalEmbeddingModel::ArcticMedium => "snowflake-arctic-embed-m",
+            LocalEmbeddingModel::ArcticLarge => "snowflake-arctic-embed-l",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().into_iter().find(|m| m.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            LocalEmbeddingModel::ArcticExtraSmall => "Arctic Embed XS (fastest)",
+            LocalEmbeddingModel::ArcticSmall => "Arctic Embed S (default)",
+            LocalEmbeddingModel::ArcticMedium => "Arctic Embed M",
+            LocalEmbeddingModel::ArcticLarge => "Arctic Embed L (best quality)",
+        }
+    }
+
+    /// Output vector size
+    pub fn dimensions(&self) -> usize {
+        match self {
+            LocalEmbeddingModel::ArcticExtraSmall => 384,
+            LocalEmbeddingModel::ArcticSmall => 384,
+            LocalEmbeddingModel::ArcticMedium => 768,
+            LocalEmbeddingModel::ArcticLarge => 1024,
+        }
+    }
+}
+
+/// Where embeddings are computed
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum EmbeddingBackend {
+    /// A local model run in-process
+    Local(LocalEmbeddingModel),
+    /// Any server implementing the OpenAI `/v1/embeddings` API
+    /// (Ollama, LM Studio, llama.cpp server, OpenAI, ...)
+    OpenAiCompatible {
+        /// Base URL up to and including `/v1`, e.g. `http://localhost:11434/v1`
+        base_url: String,
+        /// Model name sent in the request
+        model: String,
+        /// Name of the environment variable holding the API key, if any
+        api_key_env: Option<String>,
+    },
+}
+
+impl Default for EmbeddingBackend {
+    fn default() -> Self {
+        EmbeddingBackend::Local(LocalEmbeddingModel::default())
+    }
+}
+
+impl EmbeddingBackend {
+    /// Stable identifier; the vector index must be rebuilt when this changes
+    pub fn fingerprint(&self) -> String {
+        match self {
+            EmbeddingBackend::Local(model) => format!("local:{}", model.id()),
+            EmbeddingBackend::OpenAiCompatible { base_url, model, .. } => {
+                format!("openai:{}#{}", base_url.trim_end_matches('/'), model)
+            }
+        }
+    }
+
+    pub fn display_name(&self) -> String {
+        match self {
+            EmbeddingBackend::Local(model) => model.display_name().to_string(),
+            EmbeddingBackend::OpenAiCompatible { model, .. } => format!("{} (remote)", model),
+        }
+    }
+}
+
+/// Texts embedded per model call unless configured otherwise
+pub const DEFAULT_EMBEDDING_BATCH_SIZE: usize = 32;
+
+fn default_batch_size() -> usize {
+    DEFAULT_EMBEDDING_BATCH_SIZE
+}
+
+/// Persisted embedding configuration
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingConfig {
+    pub backend: EmbeddingBackend,
+    /// Vector size measured from the model; filled in by the server
+    #[serde(default)]
+    pub dimensions: Option<usize>,
+    /// Texts sent to the model per call. Larger batches use the GPU (or
+    /// remote endpoint) more efficiently at the cost of memory.
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            backend: EmbeddingBackend::default(),
+            dimensions: None,
+            batch_size: DEFAULT_EMBEDDING_BATCH_SIZE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_ignores_trailing_slash() {
+        let a = EmbeddingBackend::OpenAiCompatible {
+            base_url: "http://localhost:11434/v1/".to_string(),
+            model: "nomic-embed-text".to_string(),
+            api_key_env: None,
+        };
+        let b = EmbeddingBackend::OpenAiCompatible {
+            base_url: "http://localhost:11434/v1".to_string(),
+            model: "nomic-embed-text".to_string(),
+            api_key_env: Some("OPENAI_API_KEY".to_string()),
+        };
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_ne!(a.fingerprint(), EmbeddingBackend::default().fingerprint());
+    }
+
+    #[test]
+    fn test_config_without_batch_size_uses_default() {
+        let config: EmbeddingConfig = serde_json::from_str(r#"{"backend":{"Local":"ArcticSmall"}}"#).unwrap();
+        assert_eq!(config.batch_size, DEFAULT_EMBEDDING_BATCH_SIZE);
+    }
+
+    #[test]
+    fn test_local_model_ids_round_trip() {
+        for model in LocalEmbeddingModel::all() {
+            assert_eq!(LocalEmbeddingModel::from_id(model.id()), Some(model));
+        }
+    }
+}
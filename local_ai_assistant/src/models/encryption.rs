@@ -0,0 +1,54 @@
+//! History Encryption Model
+//!
+//! State of the optional encryption of chat history at rest, shared between
+//! the settings UI, the lock screen and the server.
+
+use serde::{Deserialize, Serialize};
+
+/// Shortest accepted passphrase
+pub const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct EncryptionStatus {
+    /// Chat history is stored encrypted
+    pub enabled: bool,
+    /// The key is loaded for this run
+    pub unlocked: bool,
+}
+
+impl EncryptionStatus {
+    /// Encrypted history that can't be read until the passphrase is entered
+    pub fn is_locked(&self) -> bool {
+        self.enabled && !self.unlocked
+    }
+}
+
+/// Checks a new passphrase and its confirmation
+pub fn validate_passphrase(passphrase: &str, confirmation: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(format!("The passphrase needs at least {} characters", MIN_PASSPHRASE_LENGTH));
+    }
+    if passphrase != confirmation {
+        return Err("The passphrases don't match".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locked_only_when_enabled() {
+        assert!(!EncryptionStatus::default().is_locked());
+        assert!(EncryptionStatus { enabled: true, unlocked: false }.is_locked());
+        assert!(!EncryptionStatus { enabled: true, unlocked: true }.is_locked());
+    }
+
+    #[test]
+    fn test_validate_passphrase() {
+        assert!(validate_passphrase("short", "short").is_err());
+        assert!(validate_passphrase("long enough", "long enough!").is_err());
+        assert!(validate_passphrase("long enough", "long enough").is_ok());
+    }
+}
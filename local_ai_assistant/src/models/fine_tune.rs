@@ -0,0 +1,200 @@
+//! Fine-Tuning Dataset Model
+//!
+//! Converts chat history into the JSONL chat format used by fine-tuning
+//! tools: one `{"messages": [...]}` object per line with system, user and
+//! assistant turns. Responses rated thumbs-down never become training
+//! targets.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use super::{ChatMessage, ChatRole, FeedbackRating, Session};
+
+/// Which sessions and responses go into a dataset
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct FineTuneFilter {
+    /// Sessions to export; empty exports every session that passes the
+    /// other filters
+    #[serde(default)]
+    pub session_ids: Vec<Uuid>,
+    /// Profile whose sessions are exported; `None` uses the active profile
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    /// Only sessions filed in this folder
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Only sessions last updated at or after this time
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// Only sessions created at or before this time
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    /// Only thumbs-up responses become training targets
+    #[serde(default)]
+    pub liked_only: bool,
+    /// System turn prepended to every example
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+}
+
+impl FineTuneFilter {
+    pub fn matches(&self, session: &Session) -> bool {
+        (self.session_ids.is_empty() || self.session_ids.contains(&session.id))
+            && self.folder.as_ref().is_none_or(|f| session.folder.as_ref() == Some(f))
+            && self.from.is_none_or(|from| session.updated_at >= from)
+            && self.to.is_none_or(|to| session.created_at <= to)
+    }
+
+    /// Training examples from one conversation. With `liked_only` every
+    /// thumbs-up response is its own example, ending on that response with
+    /// the conversation before it as context; otherwise the whole
+    /// conversation is one example, without thumbs-down exchanges.
+    pub fn examples(&self, messages: &[ChatMessage]) -> Vec<FineTuneExample> {
+        let mut turns = Vec::new();
+        let mut examples = Vec::new();
+        if let Some(system) = self.system_prompt.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            turns.push(FineTuneTurn::new("system", system));
+        }
+
+        for message in messages {
+            let content = message.content.trim();
+            if content.is_empty() {
+                continue;
+            }
+            match message.role {
+                ChatRole::System => turns.push(FineTuneTurn::new("system", content)),
+                ChatRole::User => turns.push(FineTuneTurn::new("user", content)),
+                ChatRole::Assistant => {
+                    let rating = message.feedback.as_ref().map(|f| f.rating);
+                    if rating == Some(FeedbackRating::Down) {
+                        // Drop the whole exchange so the bad answer is not
+                        // learned and no prompt is left without a reply
+                        if turns.last().is_some_and(|t| t.role == "user") {
+                            turns.pop();
+                        }
+                        continue;
+                    }
+                    turns.push(FineTuneTurn::new("assistant", content));
+                    if self.liked_only && rating == Some(FeedbackRating::Up) {
+                        examples.push(FineTuneExample { messages: turns.clone() });
+                    }
+                }
+            }
+        }
+
+        if !self.liked_only {
+            // An example has to end on the response being learned
+            while turns.last().is_some_and(|t| t.role != "assistant") {
+                turns.pop();
+            }
+            if turns.iter().any(|t| t.role == "user") {
+                examples.push(FineTuneExample { messages: turns });
+            }
+        }
+        examples
+    }
+}
+
+/// One training conversation
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FineTuneExample {
+    pub messages: Vec<FineTuneTurn>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FineTuneTurn {
+    /// `system`, `user` or `assistant`
+    pub role: String,
+    pub content: String,
+}
+
+impl FineTuneTurn {
+    fn new(role: &str, content: &str) -> Self {
+        Self { role: role.to_string(), content: content.to_string() }
+    }
+}
+
+/// A finished dataset
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FineTuneDataset {
+    /// One example per line
+    pub jsonl: String,
+    pub examples: usize,
+    pub sessions: usize,
+}
+
+impl FineTuneDataset {
+    pub fn new(examples: &[FineTuneExample], sessions: usize) -> Self {
+        let jsonl = examples.iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .map(|line| line + "\n")
+            .collect();
+        Self { jsonl, examples: examples.len(), sessions }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MessageFeedback;
+
+    fn rated(session: Uuid, content: &str, rating: FeedbackRating) -> ChatMessage {
+        let mut message = ChatMessage::assistant(session, content.to_string());
+        message.feedback = Some(MessageFeedback::new(rating, None));
+        message
+    }
+
+    fn roles(example: &FineTuneExample) -> Vec<&str> {
+        example.messages.iter().map(|t| t.role.as_str()).collect()
+    }
+
+    fn conversation() -> Vec<ChatMessage> {
+        let session = Uuid::new_v4();
+        vec![
+            ChatMessage::user(session, "Hi".to_string()),
+            ChatMessage::assistant(session, "Hello!".to_string()),
+            ChatMessage::user(session, "2 + 2?".to_string()),
+            rated(session, "5", FeedbackRating::Down),
+            ChatMessage::user(session, "Try again: 2 + 2?".to_string()),
+            rated(session, "4", FeedbackRating::Up),
+            ChatMessage::user(session, "Thanks".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_full_conversation_skips_bad_exchanges() {
+        let filter = FineTuneFilter { system_prompt: Some("Be brief.".to_string()), ..Default::default() };
+        let examples = filter.examples(&conversation());
+
+        assert_eq!(examples.len(), 1);
+        assert_eq!(roles(&examples[0]), ["system", "user", "assistant", "user", "assistant"]);
+        assert!(examples[0].messages.iter().all(|t| t.content != "5" && t.content != "2 + 2?"));
+        assert_eq!(examples[0].messages.last().unwrap().content, "4");
+    }
+
+    #[test]
+    fn test_liked_only_ends_on_liked_response() {
+        let filter = FineTuneFilter { liked_only: true, ..Default::default() };
+        let examples = filter.examples(&conversation());
+
+        assert_eq!(examples.len(), 1);
+        assert_eq!(roles(&examples[0]), ["user", "assistant", "user", "assistant"]);
+
+        let dataset = FineTuneDataset::new(&examples, 1);
+        assert_eq!(dataset.jsonl.lines().count(), 1);
+        assert!(dataset.jsonl.starts_with("{\"messages\":[{\"role\":\"user\""));
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let mut session = Session::new("Notes".to_string());
+        session.folder = Some("work".to_string());
+
+        assert!(FineTuneFilter::default().matches(&session));
+        assert!(FineTuneFilter { folder: Some("work".to_string()), ..Default::default() }.matches(&session));
+        assert!(!FineTuneFilter { folder: Some("home".to_string()), ..Default::default() }.matches(&session));
+        assert!(!FineTuneFilter { session_ids: vec![Uuid::new_v4()], ..Default::default() }.matches(&session));
+        assert!(!FineTuneFilter { from: Some(Utc::now() + chrono::Duration::days(1)), ..Default::default() }.matches(&session));
+    }
+}
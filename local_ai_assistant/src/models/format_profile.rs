@@ -0,0 +1,429 @@
+//! Export Format Profiles
+//!
+//! Each publishing platform wants its own dialect of the article: WeChat
+//! official accounts take inline-styled HTML, Medium imports Markdown with
+//! only two heading levels, and X needs the text cut into a thread of short
+//! posts. A profile turns the canonical Markdown article into one platform's
+//! format at export time and warns where the platform's limits are exceeded.
+
+use serde::{Deserialize, Serialize};
+
+use super::article::Article;
+use super::content_template::Platform;
+
+/// Characters allowed in a single X post
+pub const X_POST_LIMIT: usize = 280;
+/// Threads longer than this are hard to follow and get a warning
+const X_THREAD_MAX_POSTS: usize = 25;
+const WECHAT_TITLE_LIMIT: usize = 64;
+const WECHAT_BODY_LIMIT: usize = 20_000;
+const MEDIUM_TITLE_LIMIT: usize = 100;
+
+/// Target format for an export
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatProfile {
+    /// The canonical article, unchanged
+    #[default]
+    Markdown,
+    WeChat,
+    Medium,
+    XThread,
+}
+
+impl FormatProfile {
+    pub fn all() -> &'static [FormatProfile] {
+        &[
+            FormatProfile::Markdown,
+            FormatProfile::WeChat,
+            FormatProfile::Medium,
+            FormatProfile::XThread,
+        ]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FormatProfile::Markdown => "Markdown",
+            FormatProfile::WeChat => "WeChat Official Account (HTML)",
+            FormatProfile::Medium => "Medium",
+            FormatProfile::XThread => "X Thread",
+        }
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            FormatProfile::Markdown | FormatProfile::Medium => "md",
+            FormatProfile::WeChat => "html",
+            FormatProfile::XThread => "txt",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            FormatProfile::Markdown | FormatProfile::Medium => "text/markdown",
+            FormatProfile::WeChat => "text/html",
+            FormatProfile::XThread => "text/plain",
+        }
+    }
+
+    /// Profile matching an article template's target platform
+    pub fn for_platform(platform: &Platform) -> Self {
+        match platform {
+            Platform::WeChat => FormatProfile::WeChat,
+            Platform::Medium => FormatProfile::Medium,
+            Platform::Twitter => FormatProfile::XThread,
+            _ => FormatProfile::Markdown,
+        }
+    }
+
+    /// Formats the article for this profile
+    pub fn apply(&self, content: &Article) -> FormattedExport {
+        let (text, warnings) = match self {
+            FormatProfile::Markdown => (content.to_markdown(), Vec::new()),
+            FormatProfile::WeChat => format_wechat(content),
+            FormatProfile::Medium => format_medium(content),
+            FormatProfile::XThread => format_x_thread(content),
+        };
+        FormattedExport { profile: *self, text, warnings }
+    }
+}
+
+/// An article formatted for one platform
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FormattedExport {
+    pub profile: FormatProfile,
+    pub text: String,
+    /// Platform limits the article exceeds, or content the platform drops
+    pub warnings: Vec<String>,
+}
+
+impl FormattedExport {
+    /// Data URL for downloading the export as a file
+    pub fn data_url(&self) -> String {
+        text_data_url(self.profile.mime_type(), &self.text)
+    }
+}
+
+/// Percent-encodes text into a UTF-8 data URL
+pub fn text_data_url(mime_type: &str, text: &str) -> String {
+    let encoded: String = text
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    format!("data:{};charset=utf-8,{}", mime_type, encoded)
+}
+
+/// Escapes text for use in HTML content and attribute values
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Inline styles for the tags WeChat renders; its editor strips `<style>`
+/// blocks and classes
+const WECHAT_STYLES: &[(&str, &str)] = &[
+    ("<h1>", "<h1 style=\"font-size:22px;font-weight:bold;margin:24px 0 16px;\">"),
+    ("<h2>", "<h2 style=\"font-size:19px;font-weight:bold;margin:24px 0 12px;border-left:4px solid #07c160;padding-left:8px;\">"),
+    ("<h3>", "<h3 style=\"font-size:17px;font-weight:bold;margin:20px 0 10px;\">"),
+    ("<p>", "<p style=\"margin:0 0 16px;\">"),
+    ("<blockquote>", "<blockquote style=\"margin:16px 0;padding:8px 16px;border-left:3px solid #ddd;color:#666;\">"),
+    ("<pre>", "<pre style=\"background:#f6f8fa;padding:12px;border-radius:4px;overflow-x:auto;font-size:14px;\">"),
+    ("<img ", "<img style=\"max-width:100%;\" "),
+];
+
+fn format_wechat(content: &Article) -> (String, Vec<String>) {
+    let mut warnings = Vec::new();
+    let markdown = content.to_markdown();
+
+    // Outside links are not clickable in official account articles, so they
+    // become numbered references listed at the end
+    let mut references = Vec::new();
+    let mut images = 0;
+    let mut body = rewrite_links(&markdown, |is_image, text, url| {
+        if is_image {
+            images += 1;
+            format!("![{}]({})", text, url)
+        } else {
+            references.push(url.to_string());
+            format!("{}<sup>[{}]</sup>", text, references.len())
+        }
+    });
+    if !references.is_empty() {
+        body.push_str("## References\n\n");
+        for (i, url) in references.iter().enumerate() {
+            body.push_str(&format!("[{}] {}\n\n", i + 1, url));
+        }
+    }
+
+    let mut options = comrak::Options::default();
+    options.render.unsafe_ = true;
+    let mut html = comrak::markdown_to_html(&body, &options);
+    for (tag, styled) in WECHAT_STYLES {
+        html = html.replace(tag, styled);
+    }
+    let html = format!(
+        "<section style=\"font-size:16px;line-height:1.75;color:#333;\">\n{}</section>\n",
+        html
+    );
+
+    let title_len = content.title.chars().count();
+    if title_len > WECHAT_TITLE_LIMIT {
+        warnings.push(format!("Title is {} characters; WeChat allows {}", title_len, WECHAT_TITLE_LIMIT));
+    }
+    let body_len = rewrite_links(&markdown, |is_image, text, _| if is_image { String::new() } else { text.to_string() })
+        .chars()
+        .count();
+    if body_len > WECHAT_BODY_LIMIT {
+        warnings.push(format!("Article is {} characters; WeChat allows {}", body_len, WECHAT_BODY_LIMIT));
+    }
+    if images > 0 {
+        warnings.push(format!("{} image(s) must be uploaded to the WeChat media library before publishing", images));
+    }
+    if !references.is_empty() {
+        warnings.push(format!("{} link(s) moved to the References list; WeChat does not allow outside links", references.len()));
+    }
+
+    (html, warnings)
+}
+
+fn format_medium(content: &Article) -> (String, Vec<String>) {
+    let mut warnings = Vec::new();
+    let mut md = format!("# {}\n\n", content.title);
+    let mut demoted = 0;
+    let mut table_rows = 0;
+
+    for section in &content.sections {
+        md.push_str(&format!("## {}\n\n", section.title));
+        for line in section.body.lines() {
+            let trimmed = line.trim_start();
+            // Medium only has a title and one heading level below it
+            if trimmed.starts_with("###") {
+                demoted += 1;
+                md.push_str(&format!("**{}**\n", trimmed.trim_start_matches('#').trim()));
+            } else {
+                if trimmed.starts_with('|') {
+                    table_rows += 1;
+                }
+                md.push_str(line);
+                md.push('\n');
+            }
+        }
+        md.push('\n');
+    }
+
+    let title_len = content.title.chars().count();
+    if title_len > MEDIUM_TITLE_LIMIT {
+        warnings.push(format!("Title is {} characters; Medium allows {}", title_len, MEDIUM_TITLE_LIMIT));
+    }
+    if demoted > 0 {
+        warnings.push(format!("{} sub-heading(s) turned into bold text; Medium supports two heading levels", demoted));
+    }
+    if table_rows > 0 {
+        warnings.push(format!("Medium does not support tables; {} table row(s) will import as plain text", table_rows));
+    }
+
+    (md, warnings)
+}
+
+fn format_x_thread(content: &Article) -> (String, Vec<String>) {
+    let mut warnings = Vec::new();
+
+    let mut paragraphs = Vec::new();
+    if !content.title.trim().is_empty() {
+        paragraphs.push(content.title.trim().to_string());
+    }
+    for section in &content.sections {
+        let plain = rewrite_links(&section.body, |is_image, text, url| {
+            if is_image { String::new() } else { format!("{} ({})", text, url) }
+        });
+        for paragraph in plain.split("\n\n") {
+            let text = paragraph
+                .lines()
+                .map(|l| l.trim().trim_start_matches('#').trim())
+                .filter(|l| !l.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+                .replace("**", "")
+                .replace("__", "")
+                .replace('`', "");
+            if !text.is_empty() {
+                paragraphs.push(text);
+            }
+        }
+    }
+
+    // Leave room for the " 12/34" counter on each post
+    let posts = pack_posts(&paragraphs, X_POST_LIMIT - 8);
+    let total = posts.len();
+    let thread = posts
+        .iter()
+        .enumerate()
+        .map(|(i, post)| format!("{} {}/{}", post, i + 1, total))
+        .collect::<Vec<_>>()
+        .join("\n\n---\n\n");
+
+    if total > X_THREAD_MAX_POSTS {
+        warnings.push(format!("Thread has {} posts; consider trimming to {} or fewer", total, X_THREAD_MAX_POSTS));
+    }
+    if content.has_images() {
+        warnings.push("Images are left out of the thread; attach them to posts by hand".to_string());
+    }
+
+    (thread, warnings)
+}
+
+/// Packs paragraphs into posts of at most `limit` characters, splitting
+/// long paragraphs at sentence ends and then at word boundaries
+fn pack_posts(paragraphs: &[String], limit: usize) -> Vec<String> {
+    let mut posts = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in paragraphs {
+        let mut separator = "\n\n";
+        for piece in split_to_fit(paragraph, limit) {
+            let joined_len = current.chars().count() + separator.chars().count() + piece.chars().count();
+            if current.is_empty() {
+                current = piece;
+            } else if joined_len <= limit {
+                current.push_str(separator);
+                current.push_str(&piece);
+            } else {
+                posts.push(std::mem::replace(&mut current, piece));
+            }
+            separator = " ";
+        }
+    }
+    if !current.is_empty() {
+        posts.push(current);
+    }
+    posts
+}
+
+/// Sentences of the text, with any sentence longer than `limit` broken up
+fn split_to_fit(text: &str, limit: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut sentence = String::new();
+    for c in text.chars() {
+        sentence.push(c);
+        if matches!(c, '.' | '!' | '?' | '。' | '！' | '？') {
+            pieces.push(std::mem::take(&mut sentence));
+        }
+    }
+    pieces.push(sentence);
+
+    let mut fitted = Vec::new();
+    for piece in pieces.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        if piece.chars().count() <= limit {
+            fitted.push(piece.to_string());
+            continue;
+        }
+        let mut chunk = String::new();
+        for word in piece.split_whitespace() {
+            let chars: Vec<char> = word.chars().collect();
+            for part in chars.chunks(limit) {
+                let part: String = part.iter().collect();
+                if !chunk.is_empty() && chunk.chars().count() + 1 + part.chars().count() > limit {
+                    fitted.push(std::mem::take(&mut chunk));
+                }
+                if !chunk.is_empty() {
+                    chunk.push(' ');
+                }
+                chunk.push_str(&part);
+            }
+        }
+        if !chunk.is_empty() {
+            fitted.push(chunk);
+        }
+    }
+    fitted
+}
+
+/// Replaces each Markdown link and image with `render(is_image, text, url)`
+pub fn rewrite_links(markdown: &str, mut render: impl FnMut(bool, &str, &str) -> String) -> String {
+    let mut out = String::new();
+    let mut rest = markdown;
+
+    while let Some(start) = rest.find('[') {
+        let Some(close) = rest[start..].find("](").map(|i| start + i) else { break };
+        let Some(end) = rest[close + 2..].find(')').map(|i| close + 2 + i) else { break };
+        let text = &rest[start + 1..close];
+        let url = &rest[close + 2..end];
+
+        if text.contains(['[', ']', '\n']) || url.is_empty() || url.contains(char::is_whitespace) {
+            out.push_str(&rest[..=start]);
+            rest = &rest[start + 1..];
+            continue;
+        }
+
+        let is_image = rest[..start].ends_with('!');
+        out.push_str(&rest[..if is_image { start - 1 } else { start }]);
+        out.push_str(&render(is_image, text, url));
+        rest = &rest[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(title: &str, body: &str) -> Article {
+        Article::new(title, [("Intro", body)])
+    }
+
+    #[test]
+    fn test_rewrite_links() {
+        let md = "See [docs](https://a.io) and ![pic](img.png), [not a link] here.";
+        let out = rewrite_links(md, |is_image, text, url| {
+            if is_image { format!("<{}>", url) } else { format!("{}={}", text, url) }
+        });
+        assert_eq!(out, "See docs=https://a.io and <img.png>, [not a link] here.");
+    }
+
+    #[test]
+    fn test_wechat_inlines_styles_and_moves_links() {
+        let export = FormatProfile::WeChat.apply(&article("Hello", "Read [this](https://a.io)."));
+        assert!(export.text.contains("<h2 style="));
+        assert!(export.text.contains("this<sup>[1]</sup>"));
+        assert!(export.text.contains("[1] https://a.io"));
+        assert_eq!(export.warnings.len(), 1);
+
+        let long_title = "t".repeat(WECHAT_TITLE_LIMIT + 1);
+        assert!(FormatProfile::WeChat.apply(&article(&long_title, "Hi")).warnings[0].starts_with("Title is"));
+    }
+
+    #[test]
+    fn test_medium_demotes_subheadings() {
+        let export = FormatProfile::Medium.apply(&article("Hello", "### Deep\ntext\n| a | b |"));
+        assert!(export.text.contains("**Deep**\ntext"));
+        assert_eq!(export.warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_x_thread_respects_post_limit() {
+        let body = "This sentence is exactly long enough to matter. ".repeat(40);
+        let export = FormatProfile::XThread.apply(&article("Thread title", &body));
+        let posts: Vec<&str> = export.text.split("\n\n---\n\n").collect();
+        assert!(posts.len() > 1);
+        assert!(posts.iter().all(|p| p.chars().count() <= X_POST_LIMIT));
+        assert!(posts[0].starts_with("Thread title"));
+        assert!(posts.last().unwrap().ends_with(&format!("{}/{}", posts.len(), posts.len())));
+
+        let word = "x".repeat(X_POST_LIMIT * 2);
+        let export = FormatProfile::XThread.apply(&article("", &word));
+        assert!(export.text.split("\n\n---\n\n").all(|p| p.chars().count() <= X_POST_LIMIT));
+    }
+
+    #[test]
+    fn test_data_url() {
+        let export = FormatProfile::XThread.apply(&article("Hi", ""));
+        assert_eq!(export.data_url(), "data:text/plain;charset=utf-8,Hi%201%2F1");
+    }
+}
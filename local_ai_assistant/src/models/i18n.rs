@@ -0,0 +1,183 @@
+//! Interface Translations
+//!
+//! Key-based translations of the UI text. Each entry lists English,
+//! Chinese, Thai, Spanish, French and German, in that order; an empty
+//! translation falls back to English.
+
+use super::ResponseLanguage;
+
+/// Number of translated languages per entry
+const LANGUAGES: usize = 6;
+
+type Entry = (&'static str, [&'static str; LANGUAGES]);
+
+const TRANSLATIONS: &[Entry] = &[
+    // Sidebar
+    ("sidebar.new_chat", ["New Chat", "新对话", "แชทใหม่", "Nuevo chat", "Nouvelle discussion", "Neuer Chat"]),
+    ("sidebar.topics", ["Topics", "主题", "หัวข้อ", "Temas", "Sujets", "Themen"]),
+    ("sidebar.retag", ["↻ Retag", "↻ 重新标记", "↻ ติดแท็กใหม่", "↻ Reetiquetar", "↻ Réétiqueter", "↻ Neu taggen"]),
+    ("sidebar.retag_hint", [
+        "Tag all sessions again and regroup them into topics",
+        "重新标记所有会话并按主题分组",
+        "ติดแท็กทุกเซสชันอีกครั้งและจัดกลุ่มตามหัวข้อ",
+        "Volver a etiquetar todas las sesiones y agruparlas por temas",
+        "Réétiqueter toutes les sessions et les regrouper par sujet",
+        "Alle Sitzungen neu taggen und nach Themen gruppieren",
+    ]),
+    ("sidebar.tagging", ["Tagging...", "正在标记...", "กำลังติดแท็ก...", "Etiquetando...", "Étiquetage...", "Wird getaggt..."]),
+    ("sidebar.more", ["more", "更多", "เพิ่มเติม", "más", "plus", "mehr"]),
+    ("sidebar.less", ["less", "收起", "น้อยลง", "menos", "moins", "weniger"]),
+    ("sidebar.pinned", ["Pinned", "已置顶", "ปักหมุด", "Fijados", "Épinglées", "Angeheftet"]),
+    ("sidebar.chats", ["Chats", "对话", "แชท", "Chats", "Discussions", "Chats"]),
+    ("sidebar.archived", ["Archived", "已归档", "ที่เก็บถาวร", "Archivados", "Archivées", "Archiviert"]),
+    ("sidebar.folder_name", ["Folder name", "文件夹名称", "ชื่อโฟลเดอร์", "Nombre de la carpeta", "Nom du dossier", "Ordnername"]),
+    ("sidebar.panels", ["Panels", "面板", "แผง", "Paneles", "Panneaux", "Bereiche"]),
+    ("sidebar.new_profile", ["New profile", "新建档案", "โปรไฟล์ใหม่", "Nuevo perfil", "Nouveau profil", "Neues Profil"]),
+    ("sidebar.profile_name", ["Profile name", "档案名称", "ชื่อโปรไฟล์", "Nombre del perfil", "Nom du profil", "Profilname"]),
+    ("sidebar.pin", ["Pin to top", "置顶", "ปักหมุดไว้ด้านบน", "Fijar arriba", "Épingler en haut", "Oben anheften"]),
+    ("sidebar.unpin", ["Unpin", "取消置顶", "เลิกปักหมุด", "Desfijar", "Désépingler", "Lösen"]),
+    ("sidebar.archive", ["Archive", "归档", "เก็บถาวร", "Archivar", "Archiver", "Archivieren"]),
+    ("sidebar.restore", ["Restore", "恢复", "กู้คืน", "Restaurar", "Restaurer", "Wiederherstellen"]),
+    ("sidebar.share_html", ["Share as HTML file", "分享为 HTML 文件", "แชร์เป็นไฟล์ HTML", "Compartir como archivo HTML", "Partager en fichier HTML", "Als HTML-Datei teilen"]),
+    ("sidebar.move_to_trash", ["Move to trash", "移到回收站", "ย้ายไปถังขยะ", "Mover a la papelera", "Mettre à la corbeille", "In den Papierkorb"]),
+
+    // Panels
+    ("panel.chat", ["Chat", "对话", "แชท", "Chat", "Discussion", "Chat"]),
+    ("panel.image_gen", ["Image Gen", "图像生成", "สร้างภาพ", "Imágenes", "Images", "Bilder"]),
+    ("panel.tts", ["Text to Speech", "文字转语音", "แปลงข้อความเป็นเสียง", "Texto a voz", "Synthèse vocale", "Text zu Sprache"]),
+    ("panel.voice", ["Voice", "语音", "เสียงพูด", "Voz", "Voix", "Sprachmodus"]),
+    ("panel.content_editor", ["Content Editor", "内容编辑器", "ตัวแก้ไขเนื้อหา", "Editor de contenido", "Éditeur de contenu", "Inhaltseditor"]),
+    ("panel.content_pipeline", ["Content Pipeline", "内容流水线", "ไปป์ไลน์เนื้อหา", "Flujo de contenido", "Chaîne de contenu", "Inhalts-Pipeline"]),
+    ("panel.calendar", ["Content Calendar", "内容日历", "ปฏิทินเนื้อหา", "Calendario de contenido", "Calendrier éditorial", "Redaktionskalender"]),
+    ("panel.video_gen", ["Video Generation", "视频生成", "สร้างวิดีโอ", "Generación de vídeo", "Génération vidéo", "Videoerstellung"]),
+    ("panel.compare", ["Compare Models", "模型对比", "เปรียบเทียบโมเดล", "Comparar modelos", "Comparer les modèles", "Modelle vergleichen"]),
+    ("panel.assets", ["Assets", "素材库", "คลังไฟล์", "Recursos", "Ressources", "Medien"]),
+    ("panel.jobs", ["Jobs", "任务", "งาน", "Tareas", "Tâches", "Aufgaben"]),
+    ("panel.trash", ["Trash", "回收站", "ถังขยะ", "Papelera", "Corbeille", "Papierkorb"]),
+
+    // Chat input
+    ("chat.placeholder", ["Type your message...", "输入消息...", "พิมพ์ข้อความ...", "Escribe tu mensaje...", "Écrivez votre message...", "Nachricht eingeben..."]),
+    ("chat.initializing", ["Initializing...", "正在初始化...", "กำลังเริ่มต้น...", "Inicializando...", "Initialisation...", "Wird initialisiert..."]),
+    ("chat.thinking", ["AI is thinking...", "AI 正在思考...", "AI กำลังคิด...", "La IA está pensando...", "L'IA réfléchit...", "Die KI denkt nach..."]),
+    ("chat.drop_files", ["Drop files to attach them", "拖放文件以添加附件", "วางไฟล์เพื่อแนบ", "Suelta archivos para adjuntarlos", "Déposez des fichiers pour les joindre", "Dateien zum Anhängen ablegen"]),
+    ("chat.jump_to_latest", ["Jump to latest", "跳到最新", "ไปที่ข้อความล่าสุด", "Ir a lo más reciente", "Aller au plus récent", "Zum Neuesten springen"]),
+    ("chat.attach_files", ["Attach files", "添加附件", "แนบไฟล์", "Adjuntar archivos", "Joindre des fichiers", "Dateien anhängen"]),
+    ("chat.hint_enter", [
+        "Press Enter to send, Shift+Enter for new line. Drop files to attach them.",
+        "按 Enter 发送，Shift+Enter 换行。拖入文件即可添加附件。",
+        "กด Enter เพื่อส่ง, Shift+Enter เพื่อขึ้นบรรทัดใหม่ ลากไฟล์มาวางเพื่อแนบ",
+        "Pulsa Enter para enviar y Shift+Enter para una nueva línea. Arrastra archivos para adjuntarlos.",
+        "Entrée pour envoyer, Maj+Entrée pour un retour à la ligne. Déposez des fichiers pour les joindre.",
+        "Enter zum Senden, Umschalt+Enter für eine neue Zeile. Dateien zum Anhängen hineinziehen.",
+    ]),
+    ("chat.hint_ctrl_enter", [
+        "Press Ctrl+Enter to send, Enter for new line. Drop files to attach them.",
+        "按 Ctrl+Enter 发送，Enter 换行。拖入文件即可添加附件。",
+        "กด Ctrl+Enter เพื่อส่ง, Enter เพื่อขึ้นบรรทัดใหม่ ลากไฟล์มาวางเพื่อแนบ",
+        "Pulsa Ctrl+Enter para enviar y Enter para una nueva línea. Arrastra archivos para adjuntarlos.",
+        "Ctrl+Entrée pour envoyer, Entrée pour un retour à la ligne. Déposez des fichiers pour les joindre.",
+        "Strg+Enter zum Senden, Enter für eine neue Zeile. Dateien zum Anhängen hineinziehen.",
+    ]),
+
+    // Lock screen
+    ("lock.title", ["Chat history is locked", "聊天记录已锁定", "ประวัติแชทถูกล็อก", "El historial de chat está bloqueado", "L'historique des discussions est verrouillé", "Der Chatverlauf ist gesperrt"]),
+    ("lock.prompt", [
+        "Enter your passphrase to open iDoris.",
+        "输入密码短语以打开 iDoris。",
+        "ป้อนรหัสผ่านเพื่อเปิด iDoris",
+        "Introduce tu frase de contraseña para abrir iDoris.",
+        "Saisissez votre phrase secrète pour ouvrir iDoris.",
+        "Gib deine Passphrase ein, um iDoris zu öffnen.",
+    ]),
+    ("lock.passphrase", ["Passphrase", "密码短语", "รหัสผ่าน", "Frase de contraseña", "Phrase secrète", "Passphrase"]),
+    ("lock.unlock", ["Unlock", "解锁", "ปลดล็อก", "Desbloquear", "Déverrouiller", "Entsperren"]),
+    ("lock.unlocking", ["Unlocking...", "正在解锁...", "กำลังปลดล็อก...", "Desbloqueando...", "Déverrouillage...", "Wird entsperrt..."]),
+
+    // Settings
+    ("settings.title", ["Settings", "设置", "การตั้งค่า", "Ajustes", "Paramètres", "Einstellungen"]),
+    ("settings.back", ["Back", "返回", "กลับ", "Volver", "Retour", "Zurück"]),
+    ("settings.tab.models", ["Models", "模型", "โมเดล", "Modelos", "Modèles", "Modelle"]),
+    ("settings.tab.appearance", ["Appearance", "外观", "รูปลักษณ์", "Apariencia", "Apparence", "Darstellung"]),
+    ("settings.tab.language", ["Language", "语言", "ภาษา", "Idioma", "Langue", "Sprache"]),
+    ("settings.tab.audio", ["Audio", "音频", "เสียง", "Audio", "Audio", "Audio"]),
+    ("settings.tab.context", ["Context (RAG)", "知识库 (RAG)", "บริบท (RAG)", "Contexto (RAG)", "Contexte (RAG)", "Kontext (RAG)"]),
+    ("settings.tab.web_search", ["Web Search", "网络搜索", "ค้นหาเว็บ", "Búsqueda web", "Recherche web", "Websuche"]),
+    ("settings.tab.database", ["Database", "数据库", "ฐานข้อมูล", "Base de datos", "Base de données", "Datenbank"]),
+    ("settings.tab.memory", ["Memory", "记忆", "ความจำ", "Memoria", "Mémoire", "Gedächtnis"]),
+    ("settings.tab.system", ["System", "系统", "ระบบ", "Sistema", "Système", "System"]),
+    ("settings.tab.logs", ["Logs", "日志", "บันทึก", "Registros", "Journaux", "Protokolle"]),
+    ("settings.tab.privacy", ["Privacy", "隐私", "ความเป็นส่วนตัว", "Privacidad", "Confidentialité", "Datenschutz"]),
+    ("settings.tab.webhooks", ["Webhooks", "Webhooks", "เว็บฮุก", "Webhooks", "Webhooks", "Webhooks"]),
+    ("settings.tab.about", ["About", "关于", "เกี่ยวกับ", "Acerca de", "À propos", "Über"]),
+    ("settings.language.title", ["Language Settings", "语言设置", "การตั้งค่าภาษา", "Ajustes de idioma", "Paramètres de langue", "Spracheinstellungen"]),
+    ("settings.language.interface", ["Interface Language", "界面语言", "ภาษาของหน้าจอ", "Idioma de la interfaz", "Langue de l'interface", "Sprache der Oberfläche"]),
+    ("settings.language.interface_hint", [
+        "Menus, buttons and labels are shown in this language",
+        "菜单、按钮和标签使用此语言显示",
+        "เมนู ปุ่ม และป้ายกำกับจะแสดงเป็นภาษานี้",
+        "Los menús, botones y etiquetas se muestran en este idioma",
+        "Les menus, boutons et libellés s'affichent dans cette langue",
+        "Menüs, Schaltflächen und Beschriftungen erscheinen in dieser Sprache",
+    ]),
+    ("settings.language.response", ["Response Language", "回复语言", "ภาษาของคำตอบ", "Idioma de respuesta", "Langue des réponses", "Antwortsprache"]),
+    ("settings.language.response_hint", [
+        "The AI will respond in this language",
+        "AI 将使用此语言回复",
+        "AI จะตอบเป็นภาษานี้",
+        "La IA responderá en este idioma",
+        "L'IA répondra dans cette langue",
+        "Die KI antwortet in dieser Sprache",
+    ]),
+    ("settings.language.send", ["Send Messages With", "发送消息快捷键", "ส่งข้อความด้วย", "Enviar mensajes con", "Envoyer les messages avec", "Nachrichten senden mit"]),
+    ("settings.language.send_hint", [
+        "Enter always confirms the candidate while an input method (IME) is composing. Use Ctrl+Enter if you often need new lines.",
+        "输入法（IME）正在输入时，Enter 始终用于确认候选词。如果经常需要换行，请使用 Ctrl+Enter。",
+        "ขณะใช้วิธีป้อนข้อมูล (IME) ปุ่ม Enter จะยืนยันคำที่เลือกเสมอ ใช้ Ctrl+Enter หากต้องขึ้นบรรทัดใหม่บ่อย",
+        "Mientras un método de entrada (IME) está componiendo, Enter siempre confirma el candidato. Usa Ctrl+Enter si necesitas saltos de línea a menudo.",
+        "Pendant la composition avec une méthode de saisie (IME), Entrée valide toujours le candidat. Utilisez Ctrl+Entrée si vous avez souvent besoin de retours à la ligne.",
+        "Während eine Eingabemethode (IME) schreibt, bestätigt Enter immer den Kandidaten. Nutze Strg+Enter, wenn du oft Zeilenumbrüche brauchst.",
+    ]),
+];
+
+fn column(language: &ResponseLanguage) -> usize {
+    match language {
+        ResponseLanguage::English => 0,
+        ResponseLanguage::Chinese => 1,
+        ResponseLanguage::Thai => 2,
+        ResponseLanguage::Spanish => 3,
+        ResponseLanguage::French => 4,
+        ResponseLanguage::German => 5,
+    }
+}
+
+/// UI text for `key` in `language`, falling back to English, or to the key
+/// itself when it has no entry
+pub fn translate(language: &ResponseLanguage, key: &'static str) -> &'static str {
+    match TRANSLATIONS.iter().find(|(k, _)| *k == key) {
+        Some((_, texts)) => match texts[column(language)] {
+            "" => texts[0],
+            text => text,
+        },
+        None => key,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(translate(&ResponseLanguage::English, "sidebar.new_chat"), "New Chat");
+        assert_eq!(translate(&ResponseLanguage::Chinese, "sidebar.new_chat"), "新对话");
+        assert_eq!(translate(&ResponseLanguage::German, "missing.key"), "missing.key");
+    }
+
+    #[test]
+    fn test_translations_complete_and_unique() {
+        for (index, (key, texts)) in TRANSLATIONS.iter().enumerate() {
+            assert!(texts.iter().all(|t| !t.is_empty()), "{} has a missing translation", key);
+            assert!(!TRANSLATIONS[..index].iter().any(|(k, _)| k == key), "{} is listed twice", key);
+        }
+    }
+}
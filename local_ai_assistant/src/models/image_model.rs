@@ -0,0 +1,180 @@
+//! Image Model Customisation Model
+//!
+//! LoRA weights stacked on an MFLUX model, and locally saved models
+//! registered next to the built-in ones.
+
+use serde::{Deserialize, Serialize};
+
+/// Most LoRA weights applied to one image
+pub const MAX_LORAS: usize = 4;
+
+/// Allowed LoRA strengths
+pub const LORA_SCALE_RANGE: (f32, f32) = (0.0, 2.0);
+
+/// Share of an image-to-image source kept unless the user sets it
+pub const DEFAULT_IMAGE_STRENGTH: f32 = 0.4;
+
+/// Prefix of custom model IDs in the model selector, e.g. "custom:My model"
+pub const CUSTOM_MODEL_PREFIX: &str = "custom:";
+
+/// FLUX model a custom model was derived from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageBaseModel {
+    #[default]
+    Schnell,
+    Dev,
+}
+
+impl ImageBaseModel {
+    pub fn all() -> &'static [ImageBaseModel] {
+        &[ImageBaseModel::Schnell, ImageBaseModel::Dev]
+    }
+
+    /// Name passed to `mflux-generate --model`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImageBaseModel::Schnell => "schnell",
+            ImageBaseModel::Dev => "dev",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ImageBaseModel::Schnell => "FLUX.1 Schnell",
+            ImageBaseModel::Dev => "FLUX.1 Dev",
+        }
+    }
+
+    pub fn default_steps(&self) -> u32 {
+        match self {
+            ImageBaseModel::Schnell => 4,
+            ImageBaseModel::Dev => 20,
+        }
+    }
+}
+
+/// LoRA weights file applied on top of the model
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LoraWeight {
+    /// `.safetensors` file on the server
+    pub path: String,
+    /// Strength, 1.0 applies the LoRA as trained
+    pub scale: f32,
+    /// Disabled LoRAs stay in the list but are not applied
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl LoraWeight {
+    pub fn new(path: &str) -> Self {
+        Self { path: path.trim().to_string(), scale: 1.0, enabled: true }
+    }
+
+    /// File name shown in the panel
+    pub fn label(&self) -> &str {
+        self.path.rsplit(['/', '\\']).next().unwrap_or(&self.path)
+    }
+}
+
+/// A model saved on disk (e.g. with `mflux-save`), selectable like the
+/// built-in ones
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CustomImageModel {
+    pub name: String,
+    /// Model folder on the server
+    pub path: String,
+    pub base_model: ImageBaseModel,
+    /// Steps used when the model is selected
+    pub steps: u32,
+}
+
+impl CustomImageModel {
+    /// Value used for the model in the selector and `generate_image`
+    pub fn model_id(&self) -> String {
+        format!("{}{}", CUSTOM_MODEL_PREFIX, self.name)
+    }
+}
+
+/// Name of the custom model a model ID refers to
+pub fn custom_model_name(model_id: &str) -> Option<&str> {
+    model_id.strip_prefix(CUSTOM_MODEL_PREFIX)
+}
+
+/// Checks a custom model before it is registered
+pub fn validate_custom_model(model: &CustomImageModel, registered: &[CustomImageModel]) -> Result<(), String> {
+    if model.name.trim().is_empty() {
+        return Err("Give the model a name".to_string());
+    }
+    if model.path.trim().is_empty() {
+        return Err("Enter the model's folder".to_string());
+    }
+    if !(1..=50).contains(&model.steps) {
+        return Err("Steps must be between 1 and 50".to_string());
+    }
+    if registered.iter().any(|m| m.name.trim().eq_ignore_ascii_case(model.name.trim())) {
+        return Err(format!("A model named \"{}\" is already registered", model.name.trim()));
+    }
+    Ok(())
+}
+
+/// Paths and clamped strengths of the enabled LoRAs, at most `MAX_LORAS`
+pub fn active_loras(loras: &[LoraWeight]) -> Vec<(&str, f32)> {
+    loras.iter()
+        .filter(|l| l.enabled && !l.path.trim().is_empty())
+        .take(MAX_LORAS)
+        .map(|l| (l.path.trim(), l.scale.clamp(LORA_SCALE_RANGE.0, LORA_SCALE_RANGE.1)))
+        .collect()
+}
+
+/// `mflux-generate` arguments for the enabled LoRAs
+pub fn lora_args(loras: &[LoraWeight]) -> Vec<String> {
+    let active = active_loras(loras);
+    if active.is_empty() {
+        return Vec::new();
+    }
+    let mut args = vec!["--lora-paths".to_string()];
+    args.extend(active.iter().map(|(path, _)| path.to_string()));
+    args.push("--lora-scales".to_string());
+    args.extend(active.iter().map(|(_, scale)| format!("{:.2}", scale)));
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(name: &str) -> CustomImageModel {
+        CustomImageModel { name: name.to_string(), path: "/models/x".to_string(), base_model: ImageBaseModel::Dev, steps: 20 }
+    }
+
+    #[test]
+    fn test_lora_args() {
+        assert!(lora_args(&[]).is_empty());
+        let mut off = LoraWeight::new("/l/off.safetensors");
+        off.enabled = false;
+        let mut strong = LoraWeight::new(" /l/style.safetensors ");
+        strong.scale = 3.0;
+        let args = lora_args(&[LoraWeight::new("/l/a.safetensors"), off, strong]);
+        assert_eq!(args, vec!["--lora-paths", "/l/a.safetensors", "/l/style.safetensors", "--lora-scales", "1.00", "2.00"]);
+        assert_eq!(LoraWeight::new("/l/style.safetensors").label(), "style.safetensors");
+    }
+
+    #[test]
+    fn test_custom_models() {
+        let registered = [model("Anime")];
+        assert!(validate_custom_model(&model("Portrait"), &registered).is_ok());
+        assert!(validate_custom_model(&model("anime "), &registered).is_err());
+        assert!(validate_custom_model(&model(" "), &registered).is_err());
+        assert!(validate_custom_model(&CustomImageModel { steps: 0, ..model("Fast") }, &registered).is_err());
+
+        let id = model("Portrait").model_id();
+        assert_eq!(id, "custom:Portrait");
+        assert_eq!(custom_model_name(&id), Some("Portrait"));
+        assert_eq!(custom_model_name("dev"), None);
+    }
+}
@@ -0,0 +1,57 @@
+//! Image Prompt Model
+//!
+//! Turning a rough image idea into a detailed prompt with the local LLM.
+
+/// Longest enhanced prompt requested from the model, in tokens
+pub const ENHANCED_PROMPT_MAX_TOKENS: u32 = 160;
+
+/// Instruction asking the model to enrich a rough image prompt
+pub fn enhance_prompt_instruction(prompt: &str) -> String {
+    format!(
+        r#"Rewrite the following rough idea as a detailed prompt for a text-to-image model.
+Keep the subject and any details the user gave. Add the art style or medium, lighting, colour palette, composition and camera angle, and mood.
+Write one paragraph of comma-separated phrases, in English, under 70 words. Reply with the prompt only.
+
+Idea: {}
+
+Prompt:"#,
+        prompt.trim()
+    )
+}
+
+/// The enhanced prompt from the model's reply: the first paragraph, without
+/// a "Prompt:" label or quotes. Falls back to the original prompt when the
+/// reply is empty.
+pub fn clean_enhanced_prompt(reply: &str, original: &str) -> String {
+    let reply = reply.trim();
+    let reply = reply.strip_prefix("Prompt:")
+        .or_else(|| reply.strip_prefix("prompt:"))
+        .or_else(|| reply.strip_prefix("**Prompt:**"))
+        .unwrap_or(reply)
+        .trim();
+    let paragraph = reply.split("\n\n").next().unwrap_or_default();
+    let cleaned = paragraph.split_whitespace().collect::<Vec<_>>().join(" ");
+    let cleaned = cleaned.trim_matches(|c| c == '"' || c == '“' || c == '”' || c == '`').trim();
+    if cleaned.is_empty() {
+        original.trim().to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_enhanced_prompt() {
+        let reply = "Prompt: \"a small yellow dog playing on a lawn,\n golden hour, shallow depth of field\"\n\nThis prompt adds lighting.";
+        assert_eq!(clean_enhanced_prompt(reply, "dog"), "a small yellow dog playing on a lawn, golden hour, shallow depth of field");
+        assert_eq!(clean_enhanced_prompt("  \n", " dog "), "dog");
+    }
+
+    #[test]
+    fn test_enhance_prompt_instruction() {
+        assert!(enhance_prompt_instruction("  a cat ").contains("Idea: a cat\n"));
+    }
+}
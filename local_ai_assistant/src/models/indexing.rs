@@ -0,0 +1,136 @@
+//! Indexing Job Model
+//!
+//! Progress of a background ingestion run over the context folder. Each
+//! document moves through parsed → chunked and embedded → stored, or ends
+//! up failed with the reason.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Where a document is in the ingestion pipeline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndexStage {
+    Queued,
+    /// Read and converted to text
+    Parsed,
+    /// Split into chunks and embedded. The vector store does both in one
+    /// step, since the semantic chunker places boundaries using embeddings.
+    Embedding,
+    /// Chunks and vectors written to the index
+    Stored,
+    Failed,
+}
+
+impl IndexStage {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            IndexStage::Queued => "Queued",
+            IndexStage::Parsed => "Parsed",
+            IndexStage::Embedding => "Chunking & embedding",
+            IndexStage::Stored => "Stored",
+            IndexStage::Failed => "Failed",
+        }
+    }
+
+    /// Whether the document is done, successfully or not
+    pub fn is_finished(&self) -> bool {
+        matches!(self, IndexStage::Stored | IndexStage::Failed)
+    }
+}
+
+/// One document in an indexing job
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DocumentProgress {
+    /// File name relative to the context folder
+    pub name: String,
+    pub stage: IndexStage,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// A background run over a set of documents
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexingJob {
+    pub id: Uuid,
+    pub started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub documents: Vec<DocumentProgress>,
+}
+
+impl IndexingJob {
+    pub fn new(names: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            started_at: Utc::now(),
+            finished_at: None,
+            documents: names.into_iter()
+                .map(|name| DocumentProgress { name, stage: IndexStage::Queued, error: None })
+                .collect(),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.finished_at.is_none()
+    }
+
+    /// Moves a document to the next stage
+    pub fn set_stage(&mut self, index: usize, stage: IndexStage) {
+        if let Some(document) = self.documents.get_mut(index) {
+            document.stage = stage;
+        }
+    }
+
+    pub fn fail(&mut self, index: usize, error: String) {
+        if let Some(document) = self.documents.get_mut(index) {
+            document.stage = IndexStage::Failed;
+            document.error = Some(error);
+        }
+    }
+
+    pub fn count(&self, stage: IndexStage) -> usize {
+        self.documents.iter().filter(|d| d.stage == stage).count()
+    }
+
+    /// Share of documents that are finished, from 0 to 100
+    pub fn percent(&self) -> u8 {
+        if self.documents.is_empty() {
+            return 100;
+        }
+        let finished = self.documents.iter().filter(|d| d.stage.is_finished()).count();
+        (finished * 100 / self.documents.len()) as u8
+    }
+
+    /// One-line status, e.g. "12 of 40 documents indexed, 1 failed"
+    pub fn summary(&self) -> String {
+        let failed = self.count(IndexStage::Failed);
+        let mut summary = format!("{} of {} documents indexed", self.count(IndexStage::Stored), self.documents.len());
+        if failed > 0 {
+            summary.push_str(&format!(", {} failed", failed));
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_progress() {
+        let mut job = IndexingJob::new(vec!["a.md".to_string(), "b.pdf".to_string(), "c.txt".to_string()]);
+        assert!(job.is_running());
+        assert_eq!(job.percent(), 0);
+
+        job.set_stage(0, IndexStage::Parsed);
+        job.set_stage(0, IndexStage::Stored);
+        job.fail(1, "Unreadable PDF".to_string());
+        job.set_stage(2, IndexStage::Embedding);
+
+        assert_eq!(job.percent(), 66);
+        assert_eq!(job.summary(), "1 of 3 documents indexed, 1 failed");
+        assert_eq!(job.documents[1].error.as_deref(), Some("Unreadable PDF"));
+        assert_eq!(IndexingJob::new(vec![]).percent(), 100);
+    }
+}
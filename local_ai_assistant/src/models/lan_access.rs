@@ -0,0 +1,54 @@
+//! Local Network Access Model
+//!
+//! Settings for using the assistant from other devices on the same network,
+//! such as a phone. Shared between the settings UI and the server.
+
+use serde::{Deserialize, Serialize};
+
+/// Shortest accepted access PIN
+pub const MIN_PIN_LENGTH: usize = 6;
+
+/// Persisted network access settings
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LanAccessConfig {
+    /// Listen on all interfaces instead of only this computer
+    #[serde(default)]
+    pub enabled: bool,
+    /// Access PIN other devices sign in with
+    #[serde(default)]
+    pub pin: String,
+}
+
+impl LanAccessConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enabled && self.pin.trim().chars().count() < MIN_PIN_LENGTH {
+            return Err(format!("The access PIN needs at least {} characters", MIN_PIN_LENGTH));
+        }
+        Ok(())
+    }
+}
+
+/// Network access state for the settings UI
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LanAccessInfo {
+    pub config: LanAccessConfig,
+    /// Whether the server is listening on the network right now; enabling
+    /// access takes effect after a restart
+    pub listening: bool,
+    /// Address to open from another device
+    pub url: Option<String>,
+    /// QR code for `url`, as an SVG document
+    pub qr_svg: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_required_when_enabled() {
+        assert!(LanAccessConfig::default().validate().is_ok());
+        assert!(LanAccessConfig { enabled: true, pin: "12345".to_string() }.validate().is_err());
+        assert!(LanAccessConfig { enabled: true, pin: "123456".to_string() }.validate().is_ok());
+    }
+}
@@ -0,0 +1,183 @@
+//! LLM Request Queue Models
+//!
+//! Generation requests for the shared model run one at a time. Waiting
+//! requests are served fairly: the next one is the oldest request of the
+//! kind served least recently, so a batch of background prompts can't hold
+//! up a chat reply. Each request has an ID, and how many requests run
+//! before it is reported while it waits.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Where a generation request comes from; kinds take turns
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RequestKind {
+    /// A reply in a chat conversation
+    Chat,
+    /// A standalone prompt, e.g. from the content editor, a pipeline job or
+    /// the HTTP API
+    OneShot,
+    /// A model comparison
+    Comparison,
+    /// A quick prompt for the routing model
+    Routing,
+    /// Output constrained to a JSON schema
+    Structured,
+}
+
+impl RequestKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RequestKind::Chat => "Chat",
+            RequestKind::OneShot => "Prompt",
+            RequestKind::Comparison => "Comparison",
+            RequestKind::Routing => "Routing",
+            RequestKind::Structured => "Structured output",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueuedRequest {
+    pub id: Uuid,
+    pub kind: RequestKind,
+    /// Who is waiting for it, e.g. the chat session's ID
+    pub owner: Option<String>,
+    pub queued_at: DateTime<Utc>,
+}
+
+impl QueuedRequest {
+    pub fn new(kind: RequestKind, owner: Option<String>) -> Self {
+        Self { id: Uuid::new_v4(), kind, owner, queued_at: Utc::now() }
+    }
+}
+
+/// The request generating now and those waiting, in the order they will run
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueueStatus {
+    pub running: Option<QueuedRequest>,
+    pub waiting: Vec<QueuedRequest>,
+}
+
+impl QueueStatus {
+    /// Requests that run before the given one; None when it isn't waiting
+    pub fn position(&self, id: Uuid) -> Option<usize> {
+        self.position_where(|request| request.id == id)
+    }
+
+    /// Requests that run before the owner's next one; None when the owner
+    /// has none waiting
+    pub fn position_of_owner(&self, owner: &str) -> Option<usize> {
+        self.position_where(|request| request.owner.as_deref() == Some(owner))
+    }
+
+    fn position_where(&self, matches: impl Fn(&QueuedRequest) -> bool) -> Option<usize> {
+        let ahead = self.waiting.iter().position(matches)?;
+        Some(ahead + usize::from(self.running.is_some()))
+    }
+}
+
+/// Scheduling state of the queue
+#[derive(Debug, Default)]
+pub struct GenerationQueue {
+    running: Option<QueuedRequest>,
+    /// In arrival order
+    waiting: Vec<QueuedRequest>,
+    /// Requests started so far
+    started: u64,
+    /// When each kind last started a request, by start count
+    last_started: HashMap<RequestKind, u64>,
+}
+
+impl GenerationQueue {
+    pub fn push(&mut self, request: QueuedRequest) {
+        self.waiting.push(request);
+    }
+
+    /// Removes a request, whether waiting or running
+    pub fn remove(&mut self, id: Uuid) {
+        self.waiting.retain(|request| request.id != id);
+        if self.is_running(id) {
+            self.running = None;
+        }
+    }
+
+    pub fn is_running(&self, id: Uuid) -> bool {
+        self.running.as_ref().is_some_and(|request| request.id == id)
+    }
+
+    /// Starts the next request when none is running, returning its ID
+    pub fn start_next(&mut self) -> Option<Uuid> {
+        if self.running.is_some() {
+            return None;
+        }
+        let index = self.next_index(&self.last_started)?;
+        let request = self.waiting.remove(index);
+        self.started += 1;
+        self.last_started.insert(request.kind, self.started);
+        let id = request.id;
+        self.running = Some(request);
+        Some(id)
+    }
+
+    /// The oldest waiting request of the kind that started one least
+    /// recently
+    fn next_index(&self, last_started: &HashMap<RequestKind, u64>) -> Option<usize> {
+        self.waiting.iter()
+            .enumerate()
+            .min_by_key(|(index, request)| (last_started.get(&request.kind).copied().unwrap_or(0), *index))
+            .map(|(index, _)| index)
+    }
+
+    pub fn status(&self) -> QueueStatus {
+        // Plays the scheduling forward to list the waiting requests in the
+        // order they will run
+        let mut rest = GenerationQueue {
+            running: None,
+            waiting: self.waiting.clone(),
+            started: self.started,
+            last_started: self.last_started.clone(),
+        };
+        let mut waiting = Vec::with_capacity(rest.waiting.len());
+        while rest.start_next().is_some() {
+            waiting.extend(rest.running.take());
+        }
+        QueueStatus { running: self.running.clone(), waiting }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fair_order() {
+        let mut queue = GenerationQueue::default();
+        let first = QueuedRequest::new(RequestKind::OneShot, None);
+        let batch: Vec<_> = (0..3).map(|_| QueuedRequest::new(RequestKind::OneShot, None)).collect();
+        let chat = QueuedRequest::new(RequestKind::Chat, Some("session".to_string()));
+        queue.push(first.clone());
+        assert_eq!(queue.start_next(), Some(first.id));
+        for request in &batch {
+            queue.push(request.clone());
+        }
+        queue.push(chat.clone());
+
+        // The chat reply goes before the rest of the batch
+        let status = queue.status();
+        assert_eq!(status.waiting.iter().map(|r| r.id).collect::<Vec<_>>(), vec![chat.id, batch[0].id, batch[1].id, batch[2].id]);
+        assert_eq!(status.position_of_owner("session"), Some(1));
+        assert_eq!(status.position(batch[2].id), Some(4));
+        assert_eq!(queue.start_next(), None);
+
+        queue.remove(first.id);
+        assert_eq!(queue.start_next(), Some(chat.id));
+        queue.remove(batch[1].id);
+        queue.remove(chat.id);
+        assert_eq!(queue.start_next(), Some(batch[0].id));
+        assert_eq!(queue.status().waiting.len(), 1);
+        assert_eq!(queue.status().position_of_owner("session"), None);
+    }
+}
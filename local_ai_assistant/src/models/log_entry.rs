@@ -0,0 +1,18 @@
+//! Log Entry Model
+//!
+//! A single structured log record as shown in the Settings > Logs tab.
+
+use serde::{Deserialize, Serialize};
+
+/// One line from the structured log files
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LogEntry {
+    /// RFC 3339 timestamp
+    pub timestamp: String,
+    /// Level name: ERROR, WARN, INFO, DEBUG or TRACE
+    pub level: String,
+    /// Module path that emitted the record
+    pub target: String,
+    /// Formatted message including any structured fields
+    pub message: String,
+}
@@ -0,0 +1,329 @@
+//! Math Rendering
+//!
+//! Renders LaTeX math in Markdown as MathML, which browsers display
+//! natively. Math delimited by `$...$` or `\(...\)` renders inline, and by
+//! `$$...$$` or `\[...\]` as a block. Math is cut out before the Markdown is
+//! rendered, since the Markdown parser would otherwise treat backslashes and
+//! underscores as its own syntax, and put back into the HTML afterwards.
+//! Code spans and fenced code blocks are left alone.
+//!
+//! The converter copies text such as `\text{...}` into its output as is, so
+//! the MathML is cleaned before it reaches the page: only MathML elements
+//! and presentation attributes are kept, and anything else is escaped.
+
+use latex2mathml::{latex_to_mathml, DisplayStyle};
+
+use super::format_profile::escape_html;
+
+/// A math expression cut out of Markdown
+#[derive(Clone, Debug, PartialEq)]
+pub struct MathSpan {
+    pub latex: String,
+    pub block: bool,
+}
+
+fn placeholder(index: usize) -> String {
+    format!("IDORISMATH{}X", index)
+}
+
+/// Replaces math in Markdown with placeholders that survive rendering
+pub fn extract_math(markdown: &str) -> (String, Vec<MathSpan>) {
+    let mut out = String::with_capacity(markdown.len());
+    let mut spans = Vec::new();
+    let mut prose = String::new();
+    let mut fence: Option<String> = None;
+
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        match &fence {
+            Some(marker) => {
+                out.push_str(line);
+                if trimmed.starts_with(marker.as_str()) {
+                    fence = None;
+                }
+            }
+            None if trimmed.starts_with("```") || trimmed.starts_with("~~~") => {
+                out.push_str(&extract_from_prose(&std::mem::take(&mut prose), &mut spans));
+                fence = Some(trimmed[..3].to_string());
+                out.push_str(line);
+            }
+            None => prose.push_str(line),
+        }
+    }
+    out.push_str(&extract_from_prose(&prose, &mut spans));
+    (out, spans)
+}
+
+fn extract_from_prose(text: &str, spans: &mut Vec<MathSpan>) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        // Code spans pass through unchanged
+        if c == '`' {
+            let run = chars[i..].iter().take_while(|&&ch| ch == '`').count();
+            let ticks: String = "`".repeat(run);
+            let rest: String = chars[i + run..].iter().collect();
+            match rest.find(&ticks) {
+                Some(end) => {
+                    let code_chars = rest[..end].chars().count();
+                    out.extend(&chars[i..i + run + code_chars + run]);
+                    i += run + code_chars + run;
+                }
+                None => {
+                    out.push_str(&ticks);
+                    i += run;
+                }
+            }
+            continue;
+        }
+
+        let found = match (c, next) {
+            ('\\', Some('(')) => find_closing(&chars, i + 2, &['\\', ')']).map(|end| (i + 2, end, end + 2, false)),
+            ('\\', Some('[')) => find_closing(&chars, i + 2, &['\\', ']']).map(|end| (i + 2, end, end + 2, true)),
+            ('\\', Some('$')) => {
+                out.push_str("\\$");
+                i += 2;
+                continue;
+            }
+            ('$', Some('$')) => find_closing(&chars, i + 2, &['$', '$']).map(|end| (i + 2, end, end + 2, true)),
+            ('$', _) => find_inline_dollar(&chars, i).map(|end| (i + 1, end, end + 1, false)),
+            _ => None,
+        };
+
+        match found {
+            Some((start, end, resume, block)) => {
+                let latex: String = chars[start..end].iter().collect::<String>().trim().to_string();
+                if block {
+                    out.push_str(&format!("\n\n{}\n\n", placeholder(spans.len())));
+                } else {
+                    out.push_str(&placeholder(spans.len()));
+                }
+                spans.push(MathSpan { latex, block });
+                i = resume;
+            }
+            None => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Index where `closing` next starts at or after `from`
+fn find_closing(chars: &[char], from: usize, closing: &[char]) -> Option<usize> {
+    (from..chars.len().saturating_sub(closing.len() - 1)).find(|&j| chars[j..].starts_with(closing))
+}
+
+/// Closing `$` of inline math opened at `open`, on the same line. Like
+/// Pandoc, the math may not start or end with a space and the closing `$`
+/// may not be followed by a digit, so prices such as "$5 and $10" stay text.
+fn find_inline_dollar(chars: &[char], open: usize) -> Option<usize> {
+    let first = *chars.get(open + 1)?;
+    if first.is_whitespace() || first == '$' {
+        return None;
+    }
+    let mut j = open + 1;
+    while j < chars.len() && chars[j] != '\n' {
+        if chars[j] == '\\' {
+            j += 2;
+            continue;
+        }
+        if chars[j] == '$' {
+            let after_digit = chars.get(j + 1).is_some_and(|ch| ch.is_ascii_digit());
+            if !chars[j - 1].is_whitespace() && !after_digit {
+                return Some(j);
+            }
+            return None;
+        }
+        j += 1;
+    }
+    None
+}
+
+/// MathML elements kept by [`sanitize_mathml`]
+const MATHML_ELEMENTS: &[&str] = &[
+    "math", "mi", "mn", "mo", "ms", "mtext", "mspace", "mrow", "mfrac", "msqrt", "mroot", "mstyle", "merror",
+    "mpadded", "mphantom", "mfenced", "menclose", "msub", "msup", "msubsup", "munder", "mover", "munderover",
+    "mmultiscripts", "mprescripts", "none", "mtable", "mtr", "mtd", "mlabeledtr", "semantics", "annotation",
+];
+
+/// Attributes kept on those elements; none of them take URLs or scripts
+const MATHML_ATTRIBUTES: &[&str] = &[
+    "xmlns", "display", "mathvariant", "mathsize", "stretchy", "fence", "separator", "separators", "accent",
+    "accentunder", "lspace", "rspace", "form", "largeop", "movablelimits", "symmetric", "minsize", "maxsize",
+    "linethickness", "displaystyle", "scriptlevel", "width", "height", "depth", "voffset", "open", "close",
+    "notation", "columnalign", "rowalign", "columnspacing", "rowspacing", "columnlines", "rowlines", "frame",
+    "columnspan", "rowspan", "encoding",
+];
+
+/// Keeps only MathML elements with their allowed attributes. Any other
+/// `<`, including one starting a tag that doesn't parse cleanly, is escaped
+/// so it shows as text.
+pub fn sanitize_mathml(markup: &str) -> String {
+    let mut out = String::with_capacity(markup.len());
+    let mut rest = markup;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match clean_tag(rest) {
+            Some((tag, len)) => {
+                out.push_str(&tag);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push_str("&lt;");
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The allowed tag at the start of `markup`, rebuilt with only allowed
+/// attributes, and the length of the original
+fn clean_tag(markup: &str) -> Option<(String, usize)> {
+    let end = markup.find('>')?;
+    let inner = &markup[1..end];
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+    let (inner, self_closing) = match inner.trim_end().strip_suffix('/') {
+        Some(inner) => (inner, true),
+        None => (inner, false),
+    };
+    let name_len = inner.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(inner.len());
+    let name = &inner[..name_len];
+    if !MATHML_ELEMENTS.contains(&name) {
+        return None;
+    }
+    if closing {
+        return (inner[name_len..].trim().is_empty() && !self_closing).then(|| (format!("</{}>", name), end + 1));
+    }
+
+    let mut tag = format!("<{}", name);
+    for (attribute, value) in parse_attributes(&inner[name_len..])? {
+        if MATHML_ATTRIBUTES.contains(&attribute.as_str()) {
+            tag.push_str(&format!(" {}=\"{}\"", attribute, escape_html(&value)));
+        }
+    }
+    tag.push_str(if self_closing { "/>" } else { ">" });
+    Some((tag, end + 1))
+}
+
+/// Attributes of a tag, each preceded by whitespace; None when malformed
+fn parse_attributes(mut text: &str) -> Option<Vec<(String, String)>> {
+    let mut attributes = Vec::new();
+    loop {
+        let trimmed = text.trim_start();
+        if trimmed.is_empty() {
+            return Some(attributes);
+        }
+        if trimmed.len() == text.len() {
+            return None;
+        }
+        let name_len = trimmed.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | ':' | '_'))).unwrap_or(trimmed.len());
+        if name_len == 0 {
+            return None;
+        }
+        let name = trimmed[..name_len].to_ascii_lowercase();
+        text = &trimmed[name_len..];
+        let mut value = String::new();
+        if let Some(after) = text.trim_start().strip_prefix('=') {
+            let after = after.trim_start();
+            let quote = after.chars().next()?;
+            if quote == '"' || quote == '\'' {
+                let close = after[1..].find(quote)?;
+                value = after[1..1 + close].to_string();
+                text = &after[close + 2..];
+            } else {
+                let len = after.find(char::is_whitespace).unwrap_or(after.len());
+                value = after[..len].to_string();
+                text = &after[len..];
+            }
+        }
+        attributes.push((name, value));
+    }
+}
+
+/// Puts the rendered math back in place of the placeholders. Expressions
+/// that fail to parse are shown as code.
+pub fn restore_math(html: &str, spans: &[MathSpan]) -> String {
+    let mut html = html.to_string();
+    for (index, span) in spans.iter().enumerate() {
+        let style = if span.block { DisplayStyle::Block } else { DisplayStyle::Inline };
+        let rendered = latex_to_mathml(&span.latex, style)
+            .map(|mathml| sanitize_mathml(&mathml))
+            .unwrap_or_else(|_| format!("<code>{}</code>", escape_html(&span.latex)));
+        let key = placeholder(index);
+        if span.block {
+            html = html.replace(&format!("<p>{}</p>", key), &rendered);
+        }
+        html = html.replace(&key, &rendered);
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn latex(markdown: &str) -> Vec<(String, bool)> {
+        extract_math(markdown).1.into_iter().map(|s| (s.latex, s.block)).collect()
+    }
+
+    #[test]
+    fn test_extracts_all_delimiters() {
+        assert_eq!(
+            latex(r"Inline $a^2$ and \(b_1\), block $$c$$ and \[ d \]"),
+            vec![
+                ("a^2".to_string(), false),
+                ("b_1".to_string(), false),
+                ("c".to_string(), true),
+                ("d".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_leaves_prices_and_code_alone() {
+        assert!(latex("It costs $5 and $10 today").is_empty());
+        assert!(latex("Run `echo $HOME$` now").is_empty());
+        assert!(latex("```sh\necho $a$\n```\n").is_empty());
+        assert!(latex(r"Escaped \$x$").is_empty());
+
+        let (text, _) = extract_math("```sh\necho $a$\n```\nthen $x$");
+        assert!(text.starts_with("```sh\necho $a$\n```\nthen IDORISMATH0X"));
+    }
+
+    #[test]
+    fn test_restore_replaces_every_placeholder() {
+        let (markdown, spans) = extract_math(r"Area $\pi r^2$ and $\frac{$");
+        let html = restore_math(&format!("<p>{}</p>", markdown), &spans);
+        assert!(html.contains("<math"));
+        assert!(!html.contains("IDORISMATH"));
+    }
+
+    #[test]
+    fn test_math_cannot_inject_html() {
+        let (markdown, spans) = extract_math(r"See $\text{<img src=x onerror=alert(1)>}$ here");
+        let html = restore_math(&format!("<p>{}</p>", markdown), &spans);
+        assert!(html.contains("<math"));
+        assert!(!html.contains("<img"));
+
+        assert_eq!(
+            sanitize_mathml(r#"<mi onclick="x()" mathvariant='bold'>a</mi><script>b</script><mo/>"#),
+            r#"<mi mathvariant="bold">a</mi>&lt;script>b&lt;/script><mo/>"#
+        );
+        assert_eq!(sanitize_mathml(r#"<mi title="a>b" onclick=x>"#), r#"&lt;mi title="a>b" onclick=x>"#);
+        assert_eq!(sanitize_mathml("<mi\tonmouseover=x>"), "<mi>");
+        assert_eq!(sanitize_mathml("<mix>"), "&lt;mix>");
+    }
+}
@@ -0,0 +1,30 @@
+//! Long-term Memory Model
+//!
+//! Stable facts about the user extracted from conversations and recalled in
+//! later sessions.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// A remembered fact or preference
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MemoryEntry {
+    pub id: Uuid,
+    /// The fact, phrased as a short standalone sentence
+    pub content: String,
+    /// Session the fact was learned in
+    pub source_session_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MemoryEntry {
+    pub fn new(content: String, source_session_id: Option<Uuid>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            content,
+            source_session_id,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -2,16 +2,125 @@
 
 mod chat;
 mod session;
+mod session_title;
 mod document;
 mod settings;
+mod i18n;
 mod model_info;
 pub mod content_template;
+mod content_package;
 pub mod video_gen;
+mod diagnostics;
+mod log_entry;
+mod embedding_config;
+mod profile;
+mod webhook;
+mod memory;
+mod tool;
+mod web_search;
+mod proofreading;
+mod format_profile;
+mod fine_tune;
+mod indexing;
+mod storage_paths;
+mod lan_access;
+mod notification;
+mod background_job;
+mod attachment;
+mod math;
+mod diagram;
+mod comparison;
+mod transcript;
+mod codebase;
+mod speech;
+mod pronunciation;
+mod image_prompt;
+mod voice;
+mod upscale;
+mod image_model;
+mod video_timeline;
+mod subtitles;
+mod video_preset;
+mod asset;
+mod trash;
+mod encryption;
+mod network_audit;
+mod command_palette;
+mod publication;
+mod research;
+mod seo;
+mod readability;
+mod article;
+mod app_error;
+mod capability;
+mod llm_queue;
+mod model_download;
+mod proxy;
+mod disk_space;
 
-pub use chat::{ChatMessage, ChatRole};
-pub use session::Session;
-pub use document::Document;
-pub use settings::{AppSettings, ResponseLanguage, Theme, FontSize};
+pub use chat::{ChatMessage, ChatRole, FeedbackRating, FeedbackRecord, GenerationStats, MessageFeedback, CODE_REFERENCES_HEADING, SOURCES_HEADING, continuation_prompt, render_markdown};
+pub use session::{Session, SessionSummary, SessionOverrides, Persona, DEFAULT_TEMPERATURE, TEMPERATURE_CHOICES, MAX_SESSION_TAGS, parse_tags, tag_counts};
+pub use session_title::{TitleMethod, TitleRules, clean_generated_title, keyword_title};
+pub use document::{Document, VectorStoreStats, RetrievalOptions, RetrievedChunk, DocumentChunk, DocumentDetails, DocumentMetadata, MetadataFilter, SourceType, parse_document_tags, parse_filter_date, clean_search_query, condense_prompt, QueryExpansion, QUERY_HISTORY_MESSAGES, recency_weight, RECENCY_FLOOR, RECENCY_HALF_LIVES, DEFAULT_MIN_SCORE, DEFAULT_TOP_K, CapturedDocument, SimilarityMatch, SimilarityReport};
+pub use settings::{AppSettings, AutoScroll, GenerationLimits, ResponseLanguage, Theme, FontSize, SendShortcut};
+pub use i18n::translate;
+pub use diagnostics::SystemStats;
+pub use log_entry::LogEntry;
+pub use embedding_config::{EmbeddingBackend, EmbeddingConfig, LocalEmbeddingModel, DEFAULT_EMBEDDING_BATCH_SIZE};
+pub use profile::{Profile, DEFAULT_PROFILE_ID};
+pub use webhook::{Webhook, WebhookEvent};
+pub use memory::MemoryEntry;
+pub use article::{Article, ArticleSection};
+pub use app_error::{AppError, ErrorCategory, RecoveryAction, error_message};
+pub use capability::{Capabilities, Feature, Tool, ToolStatus};
+pub use llm_queue::{GenerationQueue, QueueStatus, QueuedRequest, RequestKind};
+pub use model_download::{MAX_DOWNLOAD_ATTEMPTS, RepoFile, RepoListing, ResumeAction, resume_action, retry_delay};
+pub use proxy::{ProxyConfig, DEFAULT_HF_ENDPOINT, HF_MIRRORS};
+pub use disk_space::{
+    SpaceCheck, check_space, format_bytes, parse_df_available, DISK_RESERVE_BYTES, IMAGE_ESTIMATE_BYTES, LOW_DISK_BYTES,
+    SPEECH_ESTIMATE_BYTES, VIDEO_ESTIMATE_BYTES,
+};
+pub use content_package::{
+    ArticleExtras, ContentPackage, ContentTranslation, CropRect, FaqEntry, GenerationProgress, GenerationStage,
+    ImageEdit, ImageOutputFormat, JobState, NarrationAsset, PackageImage, PipelineDefinition, PipelineJob,
+    ProcessedImage, SocialPost, parse_article_extras,
+};
+pub use tool::{ToolInvocation, ToolRun};
+pub use format_profile::{FormatProfile, rewrite_links};
+pub use fine_tune::{FineTuneDataset, FineTuneExample, FineTuneFilter};
+pub use indexing::{DocumentProgress, IndexStage, IndexingJob};
+pub use storage_paths::{StorageLocation, StorageLocationInfo, StoragePaths};
+pub use lan_access::{LanAccessConfig, LanAccessInfo, MIN_PIN_LENGTH};
+pub use notification::{Notification, NotificationLevel, newer_than};
+pub use background_job::{BackgroundJob, JobKind, JobStatus};
+pub use comparison::{CompareAnswer, CompareSide, CompareTally, CompareVote, Comparison, tally_votes};
+pub use diagram::{ContentSegment, DiagramBlock, DiagramKind, split_diagrams};
+pub use codebase::{Codebase, CodeChunk, CodeReference, CODE_CHUNK_LINES, MAX_CODE_CHUNK_LINES, chunk_line_ranges, format_code_references, heuristic_units, parse_citation};
+pub use speech::{AudioOutputDevice, PlaybackQueue, QueuedSpeech, SpeechOutput, PITCH_RANGE, PITCH_SAMPLE_RATE, PLAYBACK_SPEEDS, pitch_filter, speakable_text, split_sentences};
+pub use pronunciation::{PronunciationEntry, SpeechPart, DEFAULT_BREAK_MS, MAX_BREAK_MS, apply_lexicon, parse_speech_markup, render_for_say, render_plain, space_mixed_scripts};
+pub use image_prompt::{ENHANCED_PROMPT_MAX_TOKENS, clean_enhanced_prompt, enhance_prompt_instruction};
+pub use image_model::{CustomImageModel, ImageBaseModel, LoraWeight, CUSTOM_MODEL_PREFIX, DEFAULT_IMAGE_STRENGTH, LORA_SCALE_RANGE, MAX_LORAS, active_loras, custom_model_name, lora_args, validate_custom_model};
+pub use video_timeline::{AssembledVideo, VideoClip, THUMBNAIL_WIDTH, TITLE_CARD_SECONDS, VIDEO_FPS, VIDEO_SIZE, concat_list, fit_filter, move_clip, thumbnail_time, wrap_title};
+pub use asset::{Asset, AssetFilter, AssetKind, AssetOrigin, asset_markdown};
+pub use trash::{TrashItem, TrashKind, TRASH_RETENTION_DAYS, message_excerpt};
+pub use encryption::{EncryptionStatus, MIN_PASSPHRASE_LENGTH, validate_passphrase};
+pub use network_audit::{DomainSummary, NetworkFeature, NetworkRequest, summarize_by_domain};
+pub use command_palette::rank_matches;
+pub use research::{KeyPoint, ResearchBrief, ResearchSource, ResearchSourceKind, ResearchSources, MAX_RESEARCH_SOURCES, MAX_SOURCE_CHARS, parse_source_summary, topic_relevance};
+pub use seo::{
+    KeywordCluster, KeywordReport, KeywordSource, KeywordSuggestion, SeoDefaults, SeoMetadata, candidate_terms,
+    parse_keyword_lines,
+};
+pub use publication::{Publication, PublicationStatus, PublishPlatform, WordPressConfig, month_weeks, shift_month};
+pub use video_preset::{ProviderLimits, VideoPreset, aspect_ratio_label, provider_limits, validate_video_config};
+pub use subtitles::{SubtitleOptions, SubtitleSource, SubtitleTrack, fit_cues, script_cues, to_srt, to_vtt};
+pub use upscale::{UpscaleFactor, UpscaleMethod, UpscaledImage, replace_upscaled, upscaled_file_name};
+pub use voice::{CaptureMode, VoiceTurn, MAX_UTTERANCE_BYTES, take_sentences, voice_prompt};
+pub use transcript::{TranscriptSegment, VideoTranscript, TRANSCRIPT_SECTION_CHARS, format_timestamp, group_segments, transcript_markdown, youtube_video_id};
+pub use attachment::{AttachmentMode, ChatAttachment, MAX_ATTACHMENT_BYTES, MAX_INLINE_CHARS, split_into_chunks};
+pub use proofreading::{IssueKind, ProofreadIssue, SectionReview, find_long_sentences, highlight_segments};
+pub use readability::{DiffKind, ReadabilityRewrite, TARGET_GRADES, grade_label, grade_level, on_target, word_diff};
+pub use web_search::{WebSearchConfig, WebSearchProvider, WebSearchResult, format_web_results, format_web_sources};
 pub use model_info::{ModelInfo, ModelStatus, ModelType, CacheInfo, get_available_models};
 // Commented out unused template exports - will be used in Phase 3.2
 // pub use content_template::{
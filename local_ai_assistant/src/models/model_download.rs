@@ -0,0 +1,178 @@
+//! Model Download Models
+//!
+//! Model files are fetched from the Hugging Face Hub one at a time into a
+//! partial file next to the cache blob. A dropped connection is retried
+//! with a growing delay and picks up where it stopped with an HTTP range
+//! request; a finished file is checked against the size and SHA-256 the
+//! Hub lists before it is moved into the cache.
+
+use std::time::Duration;
+use serde_json::Value;
+
+/// Failed attempts in a row before a file's download gives up
+pub const MAX_DOWNLOAD_ATTEMPTS: u32 = 6;
+
+/// A file in a model repository, as listed by the Hub
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepoFile {
+    /// Path within the repository
+    pub path: String,
+    pub size: u64,
+    /// Git blob ID, used to name small files in the cache
+    pub blob_id: String,
+    /// Checksum of files stored with Git LFS, which names them in the cache
+    pub sha256: Option<String>,
+}
+
+impl RepoFile {
+    /// Name of the file's blob in the Hugging Face cache
+    pub fn etag(&self) -> &str {
+        self.sha256.as_deref().unwrap_or(&self.blob_id)
+    }
+}
+
+/// A repository revision and its files
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepoListing {
+    pub commit: String,
+    pub files: Vec<RepoFile>,
+}
+
+impl RepoListing {
+    /// Parses the Hub's model info response, requested with `blobs=true`.
+    /// The revision, IDs and paths name files in the cache, so anything
+    /// that could point outside it is rejected.
+    pub fn from_json(json: &Value) -> Result<Self, String> {
+        let commit = json["sha"].as_str().ok_or("The model listing has no revision")?.to_string();
+        if !is_hex(&commit) {
+            return Err(format!("The model listing has an invalid revision: {}", commit));
+        }
+        let siblings = json["siblings"].as_array().ok_or("The model listing has no files")?;
+        let files = siblings.iter()
+            .map(|file| {
+                let path = file["rfilename"].as_str().ok_or("A listed file has no name")?;
+                if !is_relative_path(path) {
+                    return Err(format!("The model listing has an invalid file name: {}", path));
+                }
+                let lfs = &file["lfs"];
+                let blob_id = file["blobId"].as_str().ok_or_else(|| format!("{} has no blob ID", path))?;
+                let sha256 = lfs["sha256"].as_str();
+                if !is_hex(blob_id) || !sha256.is_none_or(is_hex) {
+                    return Err(format!("{} has an invalid blob ID or checksum", path));
+                }
+                Ok(RepoFile {
+                    path: path.to_string(),
+                    size: lfs["size"].as_u64().or_else(|| file["size"].as_u64())
+                        .ok_or_else(|| format!("{} has no size", path))?,
+                    blob_id: blob_id.to_string(),
+                    sha256: sha256.map(str::to_string),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { commit, files })
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+}
+
+fn is_hex(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// A path inside the repository: relative, with `/` separators and no
+/// empty, `.` or `..` parts
+fn is_relative_path(path: &str) -> bool {
+    !path.contains('\\') && path.split('/').all(|part| !matches!(part, "" | "." | ".."))
+}
+
+/// What to do with a partial file given the server's response
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResumeAction {
+    /// The server sent the rest of the file
+    Append,
+    /// The server sent the whole file
+    Restart,
+    /// Nothing is left to fetch
+    Complete,
+}
+
+/// Decides how to use a response to a request for the bytes after `have`
+pub fn resume_action(have: u64, status: u16, content_range: Option<&str>) -> Result<ResumeAction, String> {
+    match status {
+        200 => Ok(ResumeAction::Restart),
+        206 => match content_range.and_then(parse_content_range_start) {
+            Some(start) if start == have => Ok(ResumeAction::Append),
+            Some(start) => Err(format!("The server resumed at byte {} instead of {}", start, have)),
+            None => Err("The server sent a partial response without a valid range".to_string()),
+        },
+        416 if have > 0 => Ok(ResumeAction::Complete),
+        _ => Err(format!("HTTP {}", status)),
+    }
+}
+
+/// The first byte of a `Content-Range: bytes start-end/total` header
+fn parse_content_range_start(header: &str) -> Option<u64> {
+    let range = header.trim().strip_prefix("bytes ")?;
+    let (start, _) = range.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+/// How long to wait before the given retry, counting from 1
+pub fn retry_delay(attempt: u32) -> Duration {
+    let seconds = 2u64.saturating_pow(attempt.clamp(1, 6));
+    Duration::from_secs(seconds.min(60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listing() {
+        let json = serde_json::json!({
+            "sha": "abc123",
+            "siblings": [
+                { "rfilename": "config.json", "size": 120, "blobId": "b1" },
+                { "rfilename": "model.gguf", "size": 9000, "blobId": "b2", "lfs": { "sha256": "feed", "size": 9000 } }
+            ]
+        });
+        let listing = RepoListing::from_json(&json).unwrap();
+        assert_eq!(listing.commit, "abc123");
+        assert_eq!(listing.total_size(), 9120);
+        assert_eq!(listing.files[0].etag(), "b1");
+        assert_eq!(listing.files[1].etag(), "feed");
+        assert!(RepoListing::from_json(&serde_json::json!({ "sha": "x" })).is_err());
+
+        let listing_with = |sha: &str, path: &str, blob_id: &str, sha256: &str| RepoListing::from_json(&serde_json::json!({
+            "sha": sha,
+            "siblings": [{ "rfilename": path, "size": 1, "blobId": blob_id, "lfs": { "sha256": sha256, "size": 1 } }]
+        }));
+        assert!(listing_with("abc123", "onnx/model.onnx", "b1", "feed").is_ok());
+        assert!(listing_with("../../x", "model.gguf", "b1", "feed").is_err());
+        assert!(listing_with("abc123", "../model.gguf", "b1", "feed").is_err());
+        assert!(listing_with("abc123", "onnx/../../model.gguf", "b1", "feed").is_err());
+        assert!(listing_with("abc123", "/etc/passwd", "b1", "feed").is_err());
+        assert!(listing_with("abc123", "onnx\\model.onnx", "b1", "feed").is_err());
+        assert!(listing_with("abc123", "", "b1", "feed").is_err());
+        assert!(listing_with("abc123", "model.gguf", "../b1", "feed").is_err());
+        assert!(listing_with("abc123", "model.gguf", "b1", "/tmp/feed").is_err());
+    }
+
+    #[test]
+    fn test_resume_action() {
+        assert_eq!(resume_action(0, 200, None), Ok(ResumeAction::Restart));
+        assert_eq!(resume_action(500, 200, None), Ok(ResumeAction::Restart));
+        assert_eq!(resume_action(500, 206, Some("bytes 500-999/1000")), Ok(ResumeAction::Append));
+        assert!(resume_action(500, 206, Some("bytes 0-999/1000")).is_err());
+        assert!(resume_action(500, 206, None).is_err());
+        assert_eq!(resume_action(1000, 416, None), Ok(ResumeAction::Complete));
+        assert!(resume_action(0, 416, None).is_err());
+        assert!(resume_action(0, 503, None).is_err());
+
+        assert_eq!(retry_delay(1), Duration::from_secs(2));
+        assert_eq!(retry_delay(3), Duration::from_secs(8));
+        assert_eq!(retry_delay(20), Duration::from_secs(60));
+    }
+}
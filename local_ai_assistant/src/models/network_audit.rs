@@ -0,0 +1,174 @@
+//! Network Audit Model
+//!
+//! Records of outbound network requests, shown in Settings > Privacy so
+//! users can see exactly what leaves their machine.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Feature that made a request
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkFeature {
+    Embeddings,
+    WebSearch,
+    Webhooks,
+    VideoGeneration,
+    #[serde(rename = "youtube")]
+    YouTube,
+    Transcription,
+    ContentSources,
+    Export,
+    Publishing,
+    ModelDownloads,
+}
+
+impl NetworkFeature {
+    pub fn all() -> &'static [NetworkFeature] {
+        &[
+            NetworkFeature::Embeddings,
+            NetworkFeature::WebSearch,
+            NetworkFeature::Webhooks,
+            NetworkFeature::VideoGeneration,
+            NetworkFeature::YouTube,
+            NetworkFeature::Transcription,
+            NetworkFeature::ContentSources,
+            NetworkFeature::Export,
+            NetworkFeature::Publishing,
+            NetworkFeature::ModelDownloads,
+        ]
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            NetworkFeature::Embeddings => "embeddings",
+            NetworkFeature::WebSearch => "web_search",
+            NetworkFeature::Webhooks => "webhooks",
+            NetworkFeature::VideoGeneration => "video_generation",
+            NetworkFeature::YouTube => "youtube",
+            NetworkFeature::Transcription => "transcription",
+            NetworkFeature::ContentSources => "content_sources",
+            NetworkFeature::Export => "export",
+            NetworkFeature::Publishing => "publishing",
+            NetworkFeature::ModelDownloads => "model_downloads",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<NetworkFeature> {
+        NetworkFeature::all().iter().copied().find(|f| f.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            NetworkFeature::Embeddings => "Remote embeddings",
+            NetworkFeature::WebSearch => "Web search",
+            NetworkFeature::Webhooks => "Webhooks",
+            NetworkFeature::VideoGeneration => "Video generation",
+            NetworkFeature::YouTube => "YouTube ingestion",
+            NetworkFeature::Transcription => "Audio and podcast download",
+            NetworkFeature::ContentSources => "RSS feeds and articles",
+            NetworkFeature::Export => "Chat export images",
+            NetworkFeature::Publishing => "Scheduled publishing",
+            NetworkFeature::ModelDownloads => "Model downloads",
+        }
+    }
+}
+
+/// One outbound request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NetworkRequest {
+    pub timestamp: DateTime<Utc>,
+    pub feature: NetworkFeature,
+    pub method: String,
+    /// Host the request went to; paths and queries are never recorded
+    pub domain: String,
+    /// Size of the request body
+    pub bytes_sent: u64,
+    /// Size of the response body, when the server announced it
+    pub bytes_received: Option<u64>,
+    pub status: Option<u16>,
+    /// Why the request failed before a response came back
+    pub error: Option<String>,
+}
+
+/// Requests and traffic to one domain
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DomainSummary {
+    pub domain: String,
+    pub requests: usize,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// Features that contacted the domain
+    pub features: Vec<NetworkFeature>,
+}
+
+/// Groups requests by domain, busiest first
+pub fn summarize_by_domain(requests: &[NetworkRequest]) -> Vec<DomainSummary> {
+    let mut summaries: Vec<DomainSummary> = Vec::new();
+    for request in requests {
+        let index = match summaries.iter().position(|s| s.domain == request.domain) {
+            Some(index) => index,
+            None => {
+                summaries.push(DomainSummary {
+                    domain: request.domain.clone(),
+                    requests: 0,
+                    bytes_sent: 0,
+                    bytes_received: 0,
+                    features: Vec::new(),
+                });
+                summaries.len() - 1
+            }
+        };
+        let summary = &mut summaries[index];
+        summary.requests += 1;
+        summary.bytes_sent += request.bytes_sent;
+        summary.bytes_received += request.bytes_received.unwrap_or(0);
+        if !summary.features.contains(&request.feature) {
+            summary.features.push(request.feature);
+        }
+    }
+    summaries.sort_by(|a, b| b.requests.cmp(&a.requests).then_with(|| a.domain.cmp(&b.domain)));
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(domain: &str, feature: NetworkFeature, bytes_received: Option<u64>) -> NetworkRequest {
+        NetworkRequest {
+            timestamp: Utc::now(),
+            feature,
+            method: "GET".to_string(),
+            domain: domain.to_string(),
+            bytes_sent: 10,
+            bytes_received,
+            status: Some(200),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_summarize_by_domain() {
+        let requests = vec![
+            request("api.example.com", NetworkFeature::Embeddings, Some(100)),
+            request("feeds.example.org", NetworkFeature::ContentSources, None),
+            request("api.example.com", NetworkFeature::WebSearch, Some(50)),
+        ];
+        let summaries = summarize_by_domain(&requests);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].domain, "api.example.com");
+        assert_eq!(summaries[0].requests, 2);
+        assert_eq!(summaries[0].bytes_sent, 20);
+        assert_eq!(summaries[0].bytes_received, 150);
+        assert_eq!(summaries[0].features, vec![NetworkFeature::Embeddings, NetworkFeature::WebSearch]);
+        assert_eq!(summaries[1].bytes_received, 0);
+    }
+
+    #[test]
+    fn test_feature_ids_round_trip() {
+        for feature in NetworkFeature::all() {
+            assert_eq!(NetworkFeature::from_id(feature.id()), Some(*feature));
+        }
+    }
+}
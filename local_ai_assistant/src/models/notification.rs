@@ -0,0 +1,74 @@
+//! Notification Model
+//!
+//! Messages about long-running jobs (model downloads, video generation,
+//! content pipelines, indexing) finishing or failing, shown as toasts and in
+//! the notification center.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotificationLevel {
+    Info,
+    Success,
+    Error,
+}
+
+impl NotificationLevel {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            NotificationLevel::Info => "ℹ️",
+            NotificationLevel::Success => "✅",
+            NotificationLevel::Error => "❌",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    /// Increases with every notification, so clients can ask for newer ones
+    pub id: u64,
+    pub level: NotificationLevel,
+    pub title: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub read: bool,
+}
+
+impl Notification {
+    pub fn new(id: u64, level: NotificationLevel, title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            level,
+            title: title.into(),
+            message: message.into(),
+            created_at: Utc::now(),
+            read: false,
+        }
+    }
+}
+
+/// Notifications newer than `last_seen_id`, oldest first
+pub fn newer_than(notifications: &[Notification], last_seen_id: u64) -> Vec<Notification> {
+    let mut newer: Vec<Notification> = notifications.iter().filter(|n| n.id > last_seen_id).cloned().collect();
+    newer.sort_by_key(|n| n.id);
+    newer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newer_than_orders_oldest_first() {
+        let list = vec![
+            Notification::new(3, NotificationLevel::Success, "c", ""),
+            Notification::new(2, NotificationLevel::Error, "b", ""),
+            Notification::new(1, NotificationLevel::Info, "a", ""),
+        ];
+        let newer = newer_than(&list, 1);
+        assert_eq!(newer.iter().map(|n| n.id).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(newer_than(&list, 3).is_empty());
+    }
+}
@@ -0,0 +1,35 @@
+//! User Profile Model
+//!
+//! Profiles are named workspaces sharing one installation. Each profile has
+//! its own sessions, knowledge base, settings and generated assets.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Id of the profile that owns data created before profiles existed
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+/// A named workspace
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Profile {
+    /// Stable identifier, also used as the profile's directory name
+    pub id: String,
+    /// Display name
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Profile {
+    /// The built-in profile
+    pub fn default_profile() -> Self {
+        Self {
+            id: DEFAULT_PROFILE_ID.to_string(),
+            name: "Default".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.id == DEFAULT_PROFILE_ID
+    }
+}
@@ -0,0 +1,275 @@
+//! Pronunciation Model
+//!
+//! A user-editable lexicon of how terms should be spoken, and a small
+//! SSML-like markup for text sent to TTS:
+//!
+//! - `<break time="500ms"/>` pauses (`ms` or `s`, 500 ms when omitted)
+//! - `<emphasis>words</emphasis>` stresses the words
+//! - `<sub alias="sequel">SQL</sub>` or `<phoneme ph="...">text</phoneme>`
+//!   say something other than the written text
+//!
+//! Other text, including stray `<`, is spoken as written.
+
+use std::cmp::Reverse;
+use serde::{Deserialize, Serialize};
+
+/// Pause used by `<break/>` without a time
+pub const DEFAULT_BREAK_MS: u32 = 500;
+
+/// Longest pause accepted
+pub const MAX_BREAK_MS: u32 = 10_000;
+
+/// How one term is spoken
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PronunciationEntry {
+    /// Text as written, e.g. "nginx"
+    pub term: String,
+    /// What the engine is given instead, e.g. "engine x"
+    pub say: String,
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+/// Text to synthesize, split by markup
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpeechPart {
+    Text(String),
+    Pause(u32),
+    Emphasis(String),
+}
+
+/// Replaces lexicon terms in `text`, longest first. Latin terms only match
+/// whole words, so "SQL" does not change "SQLite"; terms next to Chinese
+/// or other scripts always match.
+pub fn apply_lexicon(text: &str, lexicon: &[PronunciationEntry]) -> String {
+    let mut entries: Vec<(String, &PronunciationEntry)> = lexicon.iter()
+        .filter(|e| !e.term.trim().is_empty())
+        .map(|e| {
+            let term = e.term.trim();
+            (if e.case_sensitive { term.to_string() } else { term.to_ascii_lowercase() }, e)
+        })
+        .collect();
+    if entries.is_empty() {
+        return text.to_string();
+    }
+    entries.sort_by_key(|(needle, _)| Reverse(needle.len()));
+
+    let lower = text.to_ascii_lowercase();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    'scan: while i < text.len() {
+        for (needle, entry) in &entries {
+            let haystack = if entry.case_sensitive { &text[i..] } else { &lower[i..] };
+            let end = i + needle.len();
+            if haystack.starts_with(needle.as_str()) && is_word_edge(text, i) && is_word_edge(text, end) {
+                out.push_str(&entry.say);
+                i = end;
+                continue 'scan;
+            }
+        }
+        let c = text[i..].chars().next().unwrap_or_default();
+        out.push(c);
+        i += c.len_utf8().max(1);
+    }
+    out
+}
+
+/// Whether a match may start or end at byte `at`: not between two Latin
+/// letters or digits
+fn is_word_edge(text: &str, at: usize) -> bool {
+    let before = text[..at].chars().next_back();
+    let after = text[at..].chars().next();
+    !matches!((before, after), (Some(b), Some(a)) if b.is_ascii_alphanumeric() && a.is_ascii_alphanumeric())
+}
+
+/// Splits marked-up text into parts, applying the lexicon to plain text
+/// and emphasized words but not to `sub`/`phoneme` replacements
+pub fn parse_speech_markup(text: &str, lexicon: &[PronunciationEntry]) -> Vec<SpeechPart> {
+    let mut parts = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        plain.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        match parse_tag(tail) {
+            Some((part, consumed)) => {
+                if !plain.is_empty() {
+                    parts.push(SpeechPart::Text(apply_lexicon(&std::mem::take(&mut plain), lexicon)));
+                }
+                parts.push(match part {
+                    Tag::Break(ms) => SpeechPart::Pause(ms),
+                    Tag::Emphasis(words) => SpeechPart::Emphasis(apply_lexicon(words, lexicon)),
+                    Tag::Say(spoken) => SpeechPart::Text(spoken.to_string()),
+                });
+                rest = &tail[consumed..];
+            }
+            None => {
+                plain.push('<');
+                rest = &tail[1..];
+            }
+        }
+    }
+    plain.push_str(rest);
+    if !plain.is_empty() {
+        parts.push(SpeechPart::Text(apply_lexicon(&plain, lexicon)));
+    }
+    parts
+}
+
+enum Tag<'a> {
+    Break(u32),
+    Emphasis(&'a str),
+    Say(&'a str),
+}
+
+/// Reads a known tag at the start of `text`, returning it and its length
+fn parse_tag(text: &str) -> Option<(Tag<'_>, usize)> {
+    if text.starts_with("<break") {
+        let end = text.find("/>")?;
+        let ms = attribute(&text[..end], "time").map_or(Some(DEFAULT_BREAK_MS), parse_duration)?;
+        return Some((Tag::Break(ms.min(MAX_BREAK_MS)), end + 2));
+    }
+    for (name, attr) in [("emphasis", None), ("sub", Some("alias")), ("phoneme", Some("ph"))] {
+        let Some(after_name) = text.strip_prefix('<').and_then(|t| t.strip_prefix(name)) else {
+            continue;
+        };
+        if !after_name.starts_with(['>', ' ']) {
+            continue;
+        }
+        let open_end = text.find('>')? + 1;
+        let close = format!("</{}>", name);
+        let inner_end = open_end + text[open_end..].find(&close)?;
+        let inner = &text[open_end..inner_end];
+        let tag = match attr {
+            None => Tag::Emphasis(inner),
+            Some(attr) => Tag::Say(attribute(&text[..open_end], attr)?),
+        };
+        return Some((tag, inner_end + close.len()));
+    }
+    None
+}
+
+/// Value of `name="..."` or `name='...'` in a tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{}=", name))? + name.len() + 1;
+    let quote = tag[start..].chars().next().filter(|q| *q == '"' || *q == '\'')?;
+    let value = &tag[start + 1..];
+    Some(&value[..value.find(quote)?])
+}
+
+/// "500ms", "1.5s" or a plain number of milliseconds
+fn parse_duration(value: &str) -> Option<u32> {
+    let value = value.trim();
+    if let Some(ms) = value.strip_suffix("ms") {
+        ms.trim().parse::<f32>().ok().map(|ms| ms as u32)
+    } else if let Some(s) = value.strip_suffix('s') {
+        s.trim().parse::<f32>().ok().map(|s| (s * 1000.0) as u32)
+    } else {
+        value.parse::<f32>().ok().map(|ms| ms as u32)
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{AC00}'..='\u{D7AF}')
+}
+
+/// Puts a space between Chinese, Japanese or Korean and Latin text, which
+/// helps engines switch language cleanly
+pub fn space_mixed_scripts(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut previous: Option<char> = None;
+    for c in text.chars() {
+        if let Some(p) = previous {
+            if (is_cjk(p) && c.is_ascii_alphanumeric()) || (p.is_ascii_alphanumeric() && is_cjk(c)) {
+                out.push(' ');
+            }
+        }
+        out.push(c);
+        previous = Some(c);
+    }
+    out
+}
+
+/// Text for engines without markup: pauses become punctuation and
+/// emphasis is spoken plainly
+pub fn render_plain(parts: &[SpeechPart]) -> String {
+    let mut out = String::new();
+    for part in parts {
+        match part {
+            SpeechPart::Text(text) | SpeechPart::Emphasis(text) => out.push_str(text),
+            SpeechPart::Pause(ms) if *ms < 400 => out.push_str(", "),
+            SpeechPart::Pause(_) => out.push_str(". "),
+        }
+    }
+    space_mixed_scripts(&out)
+}
+
+/// Text for the macOS `say` command, using its embedded commands for
+/// silence and emphasis
+pub fn render_for_say(parts: &[SpeechPart]) -> String {
+    let mut out = String::new();
+    for part in parts {
+        match part {
+            SpeechPart::Text(text) => out.push_str(text),
+            SpeechPart::Pause(ms) => out.push_str(&format!(" [[slnc {}]] ", ms)),
+            SpeechPart::Emphasis(text) => {
+                let words: Vec<String> = text.split_whitespace().map(|w| format!("[[emph +]]{}", w)).collect();
+                out.push_str(&words.join(" "));
+            }
+        }
+    }
+    space_mixed_scripts(&out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(term: &str, say: &str) -> PronunciationEntry {
+        PronunciationEntry { term: term.to_string(), say: say.to_string(), case_sensitive: false }
+    }
+
+    #[test]
+    fn test_apply_lexicon() {
+        let lexicon = [entry("SQL", "sequel"), entry("nginx", "engine x"), entry("PostgreSQL", "postgres")];
+        assert_eq!(apply_lexicon("Use sql with Nginx.", &lexicon), "Use sequel with engine x.");
+        // Whole words only, longest term first
+        assert_eq!(apply_lexicon("SQLite and PostgreSQL", &lexicon), "SQLite and postgres");
+        // Latin terms inside Chinese text still match
+        assert_eq!(apply_lexicon("我们用SQL查询", &lexicon), "我们用sequel查询");
+
+        let exact = [PronunciationEntry { term: "US".to_string(), say: "U S".to_string(), case_sensitive: true }];
+        assert_eq!(apply_lexicon("US and us", &exact), "U S and us");
+    }
+
+    #[test]
+    fn test_parse_speech_markup() {
+        let lexicon = [entry("SQL", "sequel")];
+        let parts = parse_speech_markup(r#"Hi<break time="1.5s"/>it's <emphasis>very SQL</emphasis>, <sub alias="S Q L">SQL</sub>."#, &lexicon);
+        assert_eq!(parts, vec![
+            SpeechPart::Text("Hi".to_string()),
+            SpeechPart::Pause(1500),
+            SpeechPart::Text("it's ".to_string()),
+            SpeechPart::Emphasis("very sequel".to_string()),
+            SpeechPart::Text(", ".to_string()),
+            SpeechPart::Text("S Q L".to_string()),
+            SpeechPart::Text(".".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_speech_markup_keeps_unknown_text() {
+        assert_eq!(parse_speech_markup("a < b <b>bold</b> <break/>", &[]), vec![
+            SpeechPart::Text("a < b <b>bold</b> ".to_string()),
+            SpeechPart::Pause(DEFAULT_BREAK_MS),
+        ]);
+        assert_eq!(parse_speech_markup("<break time=\"99s\"/>", &[]), vec![SpeechPart::Pause(MAX_BREAK_MS)]);
+    }
+
+    #[test]
+    fn test_render() {
+        let parts = [SpeechPart::Text("用Rust".to_string()), SpeechPart::Pause(800), SpeechPart::Emphasis("really fast".to_string())];
+        assert_eq!(render_plain(&parts), "用 Rust. really fast");
+        assert_eq!(render_for_say(&parts), "用 Rust [[slnc 800]] [[emph +]]really [[emph +]]fast");
+    }
+}
@@ -0,0 +1,179 @@
+//! Proofreading Models
+//!
+//! Issues found by the proofreading pass over a draft section, a quality
+//! score per section, and helpers to highlight and fix issues in place.
+
+use serde::{Deserialize, Serialize};
+
+/// Sentences longer than this many words are flagged
+pub const LONG_SENTENCE_WORDS: usize = 30;
+
+/// Kind of proofreading issue
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    Typo,
+    Grammar,
+    PassiveVoice,
+    LongSentence,
+    Style,
+}
+
+impl IssueKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            IssueKind::Typo => "Typo",
+            IssueKind::Grammar => "Grammar",
+            IssueKind::PassiveVoice => "Passive voice",
+            IssueKind::LongSentence => "Long sentence",
+            IssueKind::Style => "Style",
+        }
+    }
+
+    /// Score penalty per occurrence
+    fn weight(&self) -> f32 {
+        match self {
+            IssueKind::Typo | IssueKind::Grammar => 3.0,
+            IssueKind::PassiveVoice | IssueKind::LongSentence | IssueKind::Style => 1.5,
+        }
+    }
+}
+
+/// A single issue, located by the exact text it applies to
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProofreadIssue {
+    pub kind: IssueKind,
+    /// Exact text from the section
+    pub excerpt: String,
+    /// Replacement for the excerpt; empty if there is no one-click fix
+    #[serde(default)]
+    pub suggestion: String,
+    #[serde(default)]
+    pub explanation: String,
+}
+
+/// Proofreading result for one section
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SectionReview {
+    pub issues: Vec<ProofreadIssue>,
+    /// 0-100, higher is better
+    pub score: u8,
+}
+
+impl SectionReview {
+    pub fn new(issues: Vec<ProofreadIssue>, text: &str) -> Self {
+        let mut review = Self { issues, score: 0 };
+        review.rescore(text);
+        review
+    }
+
+    /// Recomputes the score from the remaining issues, weighted per 100 words
+    pub fn rescore(&mut self, text: &str) {
+        // Short sections are scored as if they had 100 words
+        let words = (text.split_whitespace().count() as f32).max(100.0);
+        let penalty: f32 = self.issues.iter().map(|i| i.kind.weight()).sum();
+        self.score = (100.0 - penalty * 400.0 / words).clamp(0.0, 100.0).round() as u8;
+    }
+
+    /// Replaces the issue's excerpt with its suggestion, drops the issue and
+    /// rescores. Returns the fixed text, or None if the excerpt is gone.
+    pub fn apply_fix(&mut self, text: &str, index: usize) -> Option<String> {
+        let issue = self.issues.get(index)?;
+        if issue.suggestion.is_empty() || !text.contains(&issue.excerpt) {
+            return None;
+        }
+        let fixed = text.replacen(&issue.excerpt, &issue.suggestion, 1);
+        self.issues.remove(index);
+        self.rescore(&fixed);
+        Some(fixed)
+    }
+}
+
+/// Sentences over [`LONG_SENTENCE_WORDS`] words, found without the model
+pub fn find_long_sentences(text: &str) -> Vec<ProofreadIssue> {
+    text.split_inclusive(['.', '!', '?', '。', '！', '？', '\n'])
+        .map(str::trim)
+        .filter(|sentence| sentence.split_whitespace().count() > LONG_SENTENCE_WORDS)
+        .map(|sentence| ProofreadIssue {
+            kind: IssueKind::LongSentence,
+            excerpt: sentence.to_string(),
+            suggestion: String::new(),
+            explanation: format!("Over {} words; consider splitting it", LONG_SENTENCE_WORDS),
+        })
+        .collect()
+}
+
+/// Splits text into (segment, issue index) pairs so issues can be
+/// highlighted inline. Each issue marks the first occurrence of its excerpt
+/// that does not overlap an earlier highlight.
+pub fn highlight_segments(text: &str, issues: &[ProofreadIssue]) -> Vec<(String, Option<usize>)> {
+    let mut ranges: Vec<(usize, usize, usize)> = Vec::new();
+    for (index, issue) in issues.iter().enumerate() {
+        if issue.excerpt.is_empty() {
+            continue;
+        }
+        let found = text.match_indices(issue.excerpt.as_str())
+            .map(|(start, m)| (start, start + m.len()))
+            .find(|(start, end)| ranges.iter().all(|(s, e, _)| end <= s || start >= e));
+        if let Some((start, end)) = found {
+            ranges.push((start, end, index));
+        }
+    }
+    ranges.sort();
+
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    for (start, end, index) in ranges {
+        if start > pos {
+            segments.push((text[pos..start].to_string(), None));
+        }
+        segments.push((text[start..end].to_string(), Some(index)));
+        pos = end;
+    }
+    if pos < text.len() {
+        segments.push((text[pos..].to_string(), None));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(kind: IssueKind, excerpt: &str, suggestion: &str) -> ProofreadIssue {
+        ProofreadIssue { kind, excerpt: excerpt.into(), suggestion: suggestion.into(), explanation: String::new() }
+    }
+
+    #[test]
+    fn test_highlight_segments() {
+        let issues = vec![issue(IssueKind::Typo, "teh", "the"), issue(IssueKind::Style, "very", "")];
+        let segments = highlight_segments("I saw teh very big cat", &issues);
+        assert_eq!(segments, vec![
+            ("I saw ".to_string(), None),
+            ("teh".to_string(), Some(0)),
+            (" ".to_string(), None),
+            ("very".to_string(), Some(1)),
+            (" big cat".to_string(), None),
+        ]);
+    }
+
+    #[test]
+    fn test_apply_fix_rescores() {
+        let text = "I saw teh cat.";
+        let mut review = SectionReview::new(vec![issue(IssueKind::Typo, "teh", "the")], text);
+        assert!(review.score < 100);
+
+        assert_eq!(review.apply_fix(text, 0).as_deref(), Some("I saw the cat."));
+        assert!(review.issues.is_empty());
+        assert_eq!(review.score, 100);
+    }
+
+    #[test]
+    fn test_find_long_sentences() {
+        let long = vec!["word"; LONG_SENTENCE_WORDS + 1].join(" ");
+        let text = format!("Short one. {}. Another short one.", long);
+        let issues = find_long_sentences(&text);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].excerpt, format!("{}.", long));
+    }
+}
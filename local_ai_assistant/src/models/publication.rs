@@ -0,0 +1,255 @@
+//! Publication Model
+//!
+//! Entries of the content calendar: planned topics, drafts in progress,
+//! posts scheduled to go out at a set time, and published posts with the
+//! platform and URL they ended up at. Scheduled WordPress posts are
+//! published by the server when their time comes.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Where a publication stands
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PublicationStatus {
+    /// A topic to write about
+    Planned,
+    /// Being written, usually in a content pipeline
+    Draft,
+    /// Waiting for its date; published automatically where the platform
+    /// supports it
+    Scheduled,
+    Published,
+    /// Automatic publishing failed; see the error
+    Failed,
+}
+
+impl PublicationStatus {
+    pub fn all() -> &'static [PublicationStatus] {
+        &[
+            PublicationStatus::Planned,
+            PublicationStatus::Draft,
+            PublicationStatus::Scheduled,
+            PublicationStatus::Published,
+            PublicationStatus::Failed,
+        ]
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            PublicationStatus::Planned => "planned",
+            PublicationStatus::Draft => "draft",
+            PublicationStatus::Scheduled => "scheduled",
+            PublicationStatus::Published => "published",
+            PublicationStatus::Failed => "failed",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|s| s.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PublicationStatus::Planned => "Planned",
+            PublicationStatus::Draft => "Draft",
+            PublicationStatus::Scheduled => "Scheduled",
+            PublicationStatus::Published => "Published",
+            PublicationStatus::Failed => "Failed",
+        }
+    }
+}
+
+/// Where a publication goes out
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PublishPlatform {
+    #[default]
+    WordPress,
+    Medium,
+    Substack,
+    LinkedIn,
+    #[serde(rename = "x")]
+    X,
+    #[serde(rename = "youtube")]
+    YouTube,
+    Other,
+}
+
+impl PublishPlatform {
+    pub fn all() -> &'static [PublishPlatform] {
+        &[
+            PublishPlatform::WordPress,
+            PublishPlatform::Medium,
+            PublishPlatform::Substack,
+            PublishPlatform::LinkedIn,
+            PublishPlatform::X,
+            PublishPlatform::YouTube,
+            PublishPlatform::Other,
+        ]
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            PublishPlatform::WordPress => "wordpress",
+            PublishPlatform::Medium => "medium",
+            PublishPlatform::Substack => "substack",
+            PublishPlatform::LinkedIn => "linkedin",
+            PublishPlatform::X => "x",
+            PublishPlatform::YouTube => "youtube",
+            PublishPlatform::Other => "other",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Self {
+        Self::all().iter().copied().find(|p| p.id() == id).unwrap_or(PublishPlatform::Other)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PublishPlatform::WordPress => "WordPress",
+            PublishPlatform::Medium => "Medium",
+            PublishPlatform::Substack => "Substack",
+            PublishPlatform::LinkedIn => "LinkedIn",
+            PublishPlatform::X => "X",
+            PublishPlatform::YouTube => "YouTube",
+            PublishPlatform::Other => "Other",
+        }
+    }
+
+    /// Whether scheduled posts are published by the app; the others are
+    /// reminders to publish by hand
+    pub fn can_auto_publish(&self) -> bool {
+        matches!(self, PublishPlatform::WordPress)
+    }
+}
+
+/// One entry of the content calendar
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Publication {
+    pub id: Uuid,
+    pub title: String,
+    pub status: PublicationStatus,
+    pub platform: PublishPlatform,
+    /// Content pipeline job whose package is published
+    pub job_id: Option<Uuid>,
+    /// Planned or scheduled publish time; when it went out once published
+    pub date: DateTime<Utc>,
+    /// Address of the published post
+    pub url: Option<String>,
+    pub notes: String,
+    /// Why automatic publishing failed
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Publication {
+    pub fn new(title: impl Into<String>, date: DateTime<Utc>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            title: title.into(),
+            status: PublicationStatus::Planned,
+            platform: PublishPlatform::default(),
+            job_id: None,
+            date,
+            url: None,
+            notes: String::new(),
+            error: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Whether the scheduler should publish it now
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.status == PublicationStatus::Scheduled && self.platform.can_auto_publish() && self.date <= now
+    }
+}
+
+/// WordPress site that scheduled posts are published to, using an
+/// application password (Users > Profile > Application Passwords)
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WordPressConfig {
+    /// Site address, e.g. https://blog.example.com
+    #[serde(default)]
+    pub site_url: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub app_password: String,
+}
+
+impl WordPressConfig {
+    pub fn is_configured(&self) -> bool {
+        !self.site_url.trim().is_empty() && !self.username.trim().is_empty() && !self.app_password.trim().is_empty()
+    }
+
+    /// REST endpoint for creating posts
+    pub fn posts_endpoint(&self) -> String {
+        format!("{}/wp-json/wp/v2/posts", self.site_url.trim().trim_end_matches('/'))
+    }
+}
+
+/// Weeks shown for a month, Monday first, padded with days of the months
+/// before and after so every week is complete
+pub fn month_weeks(year: i32, month: u32) -> Vec<[NaiveDate; 7]> {
+    let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+        return Vec::new();
+    };
+    let mut day = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+    let mut weeks = Vec::new();
+    while weeks.is_empty() || (day.month() == month && day.year() == year) {
+        let week = std::array::from_fn(|i| day + Duration::days(i as i64));
+        day += Duration::days(7);
+        weeks.push(week);
+    }
+    weeks
+}
+
+/// The month before or after, as (year, month)
+pub fn shift_month(year: i32, month: u32, delta: i32) -> (i32, u32) {
+    let index = year * 12 + month as i32 - 1 + delta;
+    (index.div_euclid(12), index.rem_euclid(12) as u32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_weeks() {
+        // October 2026 starts on a Thursday and ends on a Saturday
+        let weeks = month_weeks(2026, 10);
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0][0], NaiveDate::from_ymd_opt(2026, 9, 28).unwrap());
+        assert_eq!(weeks[4][6], NaiveDate::from_ymd_opt(2026, 11, 1).unwrap());
+
+        // February 2027 starts on a Monday and fills exactly four weeks
+        let weeks = month_weeks(2027, 2);
+        assert_eq!(weeks.len(), 4);
+        assert_eq!(weeks[0][0], NaiveDate::from_ymd_opt(2027, 2, 1).unwrap());
+
+        assert!(month_weeks(2026, 13).is_empty());
+    }
+
+    #[test]
+    fn test_shift_month() {
+        assert_eq!(shift_month(2026, 12, 1), (2027, 1));
+        assert_eq!(shift_month(2026, 1, -1), (2025, 12));
+        assert_eq!(shift_month(2026, 5, 0), (2026, 5));
+    }
+
+    #[test]
+    fn test_is_due() {
+        let now = Utc::now();
+        let mut publication = Publication::new("Launch post", now - Duration::minutes(1));
+        assert!(!publication.is_due(now));
+
+        publication.status = PublicationStatus::Scheduled;
+        assert!(publication.is_due(now));
+        assert!(!publication.is_due(now - Duration::hours(1)));
+
+        publication.platform = PublishPlatform::Medium;
+        assert!(!publication.is_due(now));
+    }
+}
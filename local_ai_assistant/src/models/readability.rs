@@ -0,0 +1,219 @@
+//! Readability Models
+//!
+//! Flesch-Kincaid grade levels for draft sections, the result of rewriting
+//! a section towards a target grade, and a word diff to compare the text
+//! before and after. Grades are only computed for text written with Latin
+//! letters; syllables are estimated from vowel groups.
+
+use serde::{Deserialize, Serialize};
+
+/// A rewrite is on target when its grade is within this many levels
+pub const GRADE_TOLERANCE: f32 = 1.0;
+/// Fewer words than this give no meaningful grade
+const MIN_WORDS: usize = 10;
+/// Word diffs above this many token comparisons are shown as a plain
+/// replacement
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Grades offered as rewrite targets, with their audience
+pub const TARGET_GRADES: &[(u8, &str)] = &[
+    (6, "Grade 6 — general public"),
+    (8, "Grade 8 — plain language"),
+    (10, "Grade 10 — high school"),
+    (12, "Grade 12 — informed readers"),
+    (14, "Grade 14 — specialists"),
+];
+
+/// Flesch-Kincaid grade level of the text, ignoring Markdown images, code
+/// fences, list and heading marks. None for short or non-Latin text.
+pub fn grade_level(text: &str) -> Option<f32> {
+    let prose = strip_markdown(text);
+    let words: Vec<&str> = prose
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|w| w.chars().any(|c| c.is_alphabetic()))
+        .collect();
+    if words.len() < MIN_WORDS || words.iter().any(|w| w.chars().any(|c| c.is_alphabetic() && !c.is_ascii_alphabetic() && !is_latin(c))) {
+        return None;
+    }
+
+    let sentences = prose
+        .split(['.', '!', '?', '\n'])
+        .filter(|s| s.chars().any(|c| c.is_alphabetic()))
+        .count()
+        .max(1);
+    let syllables: usize = words.iter().map(|w| count_syllables(w)).sum();
+    let grade = 0.39 * words.len() as f32 / sentences as f32 + 11.8 * syllables as f32 / words.len() as f32 - 15.59;
+    Some(grade.max(0.0))
+}
+
+fn is_latin(c: char) -> bool {
+    matches!(c, '\u{00C0}'..='\u{024F}')
+}
+
+/// Audience a grade level suits
+pub fn grade_label(grade: f32) -> &'static str {
+    match grade {
+        g if g < 7.0 => "Easy",
+        g if g < 9.0 => "Plain",
+        g if g < 13.0 => "High school",
+        g if g < 16.0 => "College",
+        _ => "Academic",
+    }
+}
+
+/// Whether a grade is close enough to the target
+pub fn on_target(grade: f32, target: f32) -> bool {
+    (grade - target).abs() <= GRADE_TOLERANCE
+}
+
+fn strip_markdown(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("![") && !line.starts_with("```"))
+        .map(|line| line.trim_start_matches(['#', '>', '-', '*']).trim())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Syllables of an English word, estimated from its vowel groups
+fn count_syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y') || (c.is_alphabetic() && !c.is_ascii());
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    // A final silent "e", as in "make", but not "le" as in "table"
+    if word.ends_with('e') && !word.ends_with("le") && count > 1 {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+/// A section rewritten towards a target grade
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReadabilityRewrite {
+    pub original: String,
+    pub text: String,
+    pub target: f32,
+    pub grade_before: Option<f32>,
+    pub grade_after: Option<f32>,
+    /// Rewrites made before the grade landed on target or attempts ran out
+    pub attempts: u32,
+}
+
+impl ReadabilityRewrite {
+    pub fn reached_target(&self) -> bool {
+        self.grade_after.is_some_and(|grade| on_target(grade, self.target))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiffKind {
+    Same,
+    Removed,
+    Added,
+}
+
+/// Word-level differences from one text to another, as runs of unchanged,
+/// removed and added text
+pub fn word_diff(before: &str, after: &str) -> Vec<(DiffKind, String)> {
+    let old = tokens(before);
+    let new = tokens(after);
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        return vec![(DiffKind::Removed, before.to_string()), (DiffKind::Added, after.to_string())];
+    }
+
+    // Longest common subsequence lengths of the suffixes
+    let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i].trim_end() == new[j].trim_end() { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let mut runs: Vec<(DiffKind, String)> = Vec::new();
+    let mut push = |kind: DiffKind, token: &str| match runs.last_mut() {
+        Some((last, text)) if *last == kind => text.push_str(token),
+        _ => runs.push((kind, token.to_string())),
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i].trim_end() == new[j].trim_end() {
+            push(DiffKind::Same, new[j]);
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            push(DiffKind::Removed, old[i]);
+            i += 1;
+        } else {
+            push(DiffKind::Added, new[j]);
+            j += 1;
+        }
+    }
+    runs
+}
+
+/// Words with the whitespace after them, so runs join back into the text;
+/// words are compared without it
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut in_space = false;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            in_space = true;
+        } else if in_space {
+            tokens.push(&text[start..i]);
+            start = i;
+            in_space = false;
+        }
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grade_level() {
+        let simple = "The cat sat on the mat. The dog ran to the park. We had fun in the sun.";
+        let dense = "Comprehensive institutional accountability necessitates systematic evaluation \
+of organizational performance indicators throughout administrative hierarchies.";
+        let easy = grade_level(simple).unwrap();
+        let hard = grade_level(dense).unwrap();
+        assert!(easy < 3.0, "{}", easy);
+        assert!(hard > 16.0, "{}", hard);
+        assert_eq!(grade_label(hard), "Academic");
+
+        assert_eq!(grade_level("Too short."), None);
+        assert_eq!(grade_level("本地模型可以在笔记本电脑上运行，而且不需要网络连接，所以隐私更好，速度也很快。"), None);
+        assert_eq!(count_syllables("table"), 2);
+        assert_eq!(count_syllables("make"), 1);
+    }
+
+    #[test]
+    fn test_word_diff() {
+        let diff = word_diff("The cat sat on the mat.", "The dog sat on the mat.");
+        assert_eq!(diff, vec![
+            (DiffKind::Same, "The ".to_string()),
+            (DiffKind::Removed, "cat ".to_string()),
+            (DiffKind::Added, "dog ".to_string()),
+            (DiffKind::Same, "sat on the mat.".to_string()),
+        ]);
+        let rejoined: String = word_diff("a b c", "a x c d").into_iter()
+            .filter(|(kind, _)| *kind != DiffKind::Removed)
+            .map(|(_, text)| text)
+            .collect();
+        assert_eq!(rejoined, "a x c d");
+    }
+}
@@ -0,0 +1,232 @@
+//! Research Brief Model
+//!
+//! The research stage of the content pipeline gathers sources about the
+//! topic (web search hits, matching RSS feed entries, pages the user named
+//! and knowledge base documents), summarizes each, and merges their key
+//! points into one brief kept with the content package. Points that several
+//! sources make are listed once, citing all of them.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::format_profile::text_data_url;
+
+/// Sources researched for one package
+pub const MAX_RESEARCH_SOURCES: usize = 8;
+/// Characters of a source's text passed to the summarizer
+pub const MAX_SOURCE_CHARS: usize = 6000;
+/// Word overlap above which two key points count as the same point
+const DUPLICATE_SIMILARITY: f32 = 0.6;
+
+/// Where a research source came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResearchSourceKind {
+    /// A page the user asked to include
+    Url,
+    WebSearch,
+    Feed,
+    KnowledgeBase,
+}
+
+impl ResearchSourceKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ResearchSourceKind::Url => "Page",
+            ResearchSourceKind::WebSearch => "Web search",
+            ResearchSourceKind::Feed => "RSS feed",
+            ResearchSourceKind::KnowledgeBase => "Knowledge base",
+        }
+    }
+}
+
+/// Feeds and pages to research besides web search and the knowledge base
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResearchSources {
+    /// RSS or Atom feed URLs; entries about the topic are read
+    #[serde(default)]
+    pub feeds: Vec<String>,
+    /// Pages that are always read
+    #[serde(default)]
+    pub urls: Vec<String>,
+}
+
+/// One source and what was taken from it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResearchSource {
+    pub kind: ResearchSourceKind,
+    pub title: String,
+    pub url: Option<String>,
+    /// One-sentence summary
+    pub summary: String,
+    pub key_points: Vec<String>,
+    /// Why the source could not be read or summarized
+    pub error: Option<String>,
+}
+
+/// A point made by one or more sources
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyPoint {
+    pub text: String,
+    /// Numbers of the sources making it, from 1
+    pub sources: Vec<usize>,
+}
+
+/// Summarized sources and their merged key points
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResearchBrief {
+    pub topic: String,
+    pub sources: Vec<ResearchSource>,
+    pub key_points: Vec<KeyPoint>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ResearchBrief {
+    /// Brief for the summarized sources, with their key points merged
+    pub fn new(topic: impl Into<String>, sources: Vec<ResearchSource>) -> Self {
+        let key_points = dedupe_key_points(&sources);
+        Self { topic: topic.into(), sources, key_points, created_at: Utc::now() }
+    }
+
+    /// Key points with source numbers, used as the package's research notes
+    pub fn notes(&self) -> String {
+        self.key_points.iter()
+            .map(|point| format!("- {} {}", point.text, cite(&point.sources)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Research brief: {}\n\n## Key points\n\n{}\n\n## Sources\n\n", self.topic, self.notes());
+        for (i, source) in self.sources.iter().enumerate() {
+            let title = match &source.url {
+                Some(url) => format!("[{}]({})", source.title, url),
+                None => source.title.clone(),
+            };
+            md.push_str(&format!("{}. {} ({})", i + 1, title, source.kind.display_name()));
+            match &source.error {
+                Some(error) => md.push_str(&format!(" — not read: {}\n", error)),
+                None if source.summary.is_empty() => md.push('\n'),
+                None => md.push_str(&format!(" — {}\n", source.summary)),
+            }
+        }
+        md
+    }
+
+    /// Data URL for downloading the brief as a Markdown file
+    pub fn markdown_data_url(&self) -> String {
+        text_data_url("text/markdown", &self.to_markdown())
+    }
+}
+
+fn cite(sources: &[usize]) -> String {
+    sources.iter().map(|n| format!("[{}]", n)).collect()
+}
+
+/// Splits a summarizer reply into its summary (the first line that is not
+/// a bullet) and its bulleted key points
+pub fn parse_source_summary(reply: &str) -> (String, Vec<String>) {
+    let mut summary = String::new();
+    let mut points = Vec::new();
+    for line in reply.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let bullet = line.strip_prefix(['-', '*', '•'])
+            .or_else(|| line.split_once(". ").filter(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())).map(|(_, rest)| rest));
+        match bullet {
+            Some(point) if !point.trim().is_empty() => points.push(point.trim().to_string()),
+            Some(_) => {}
+            None if summary.is_empty() => {
+                summary = line.strip_prefix("Summary:").unwrap_or(line).trim().to_string();
+            }
+            None => {}
+        }
+    }
+    (summary, points)
+}
+
+/// How many of the topic's words appear in a feed entry, for picking the
+/// entries worth reading
+pub fn topic_relevance(topic: &str, text: &str) -> usize {
+    let text = words(text);
+    words(topic).iter().filter(|w| w.chars().count() > 2 && text.contains(*w)).count()
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Share of words two points have in common
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+/// Key points of all sources in order, with points that say the same thing
+/// merged into the first one and citing every source that made it
+pub fn dedupe_key_points(sources: &[ResearchSource]) -> Vec<KeyPoint> {
+    let mut merged: Vec<(KeyPoint, HashSet<String>)> = Vec::new();
+    for (i, source) in sources.iter().enumerate() {
+        for point in &source.key_points {
+            let point_words = words(point);
+            match merged.iter_mut().find(|(_, w)| similarity(w, &point_words) >= DUPLICATE_SIMILARITY) {
+                Some((existing, _)) => {
+                    if !existing.sources.contains(&(i + 1)) {
+                        existing.sources.push(i + 1);
+                    }
+                }
+                None => merged.push((KeyPoint { text: point.clone(), sources: vec![i + 1] }, point_words)),
+            }
+        }
+    }
+    merged.into_iter().map(|(point, _)| point).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(points: &[&str]) -> ResearchSource {
+        ResearchSource {
+            kind: ResearchSourceKind::WebSearch,
+            title: "Source".into(),
+            url: None,
+            summary: String::new(),
+            key_points: points.iter().map(|p| p.to_string()).collect(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_source_summary() {
+        let (summary, points) = parse_source_summary("Summary: Rust 1.80 ships LazyLock.\n\n- LazyLock is stable\n* OnceLock got helpers\n2. Cargo is faster\n-");
+        assert_eq!(summary, "Rust 1.80 ships LazyLock.");
+        assert_eq!(points, vec!["LazyLock is stable", "OnceLock got helpers", "Cargo is faster"]);
+    }
+
+    #[test]
+    fn test_dedupe_key_points() {
+        let sources = vec![
+            source(&["LazyLock is now stable in Rust", "Cargo builds are faster"]),
+            source(&["In Rust LazyLock is now stable", "Exclusive ranges in patterns"]),
+        ];
+        let points = dedupe_key_points(&sources);
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0].sources, vec![1, 2]);
+        assert_eq!(points[2].sources, vec![2]);
+
+        let brief = ResearchBrief::new("Rust 1.80", sources);
+        assert!(brief.notes().starts_with("- LazyLock is now stable in Rust [1][2]"));
+    }
+
+    #[test]
+    fn test_topic_relevance() {
+        assert_eq!(topic_relevance("Running LLMs locally", "How to run LLMs locally on a laptop"), 2);
+        assert_eq!(topic_relevance("Running LLMs locally", "Gardening tips"), 0);
+    }
+}
@@ -0,0 +1,382 @@
+//! SEO Metadata Model
+//!
+//! Search and sharing metadata for a content package: the page title,
+//! description and keywords, Open Graph and Twitter Card tags, and a
+//! schema.org `Article` in JSON-LD with the cover image and author.
+//! Defaults such as the author and site address are set once and filled
+//! into newly generated metadata. Packages are checked for the fields
+//! these tags need before they are published.
+//!
+//! Keyword research groups terms from the knowledge base and from LLM
+//! brainstorming into clusters of related keywords, scores how well the
+//! draft covers each, and suggests sections for the groups it misses.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::format_profile::escape_html;
+use super::session_title::DEFAULT_STOP_WORDS;
+
+/// Similarity between a keyword and a draft section above which the
+/// section counts as covering it
+pub const COVERED_SIMILARITY: f32 = 0.6;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeoMetadata {
+    pub title: String,
+    pub meta_description: String,
+    pub keywords: Vec<String>,
+    pub slug: String,
+    #[serde(default)]
+    pub author: String,
+    /// Public address of the article; images next to it are shared from
+    /// the same folder
+    #[serde(default)]
+    pub canonical_url: String,
+    #[serde(default)]
+    pub site_name: String,
+    /// Twitter/X account of the site, with the @
+    #[serde(default)]
+    pub twitter_handle: String,
+    /// When the metadata was generated, used as the publish date
+    #[serde(default)]
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+impl SeoMetadata {
+    /// Names of the fields search engines and social previews need that are
+    /// still empty
+    pub fn missing_fields(&self) -> Vec<&'static str> {
+        [
+            ("title", self.title.as_str()),
+            ("description", self.meta_description.as_str()),
+            ("author", self.author.as_str()),
+        ]
+        .into_iter()
+        .filter(|(_, value)| value.trim().is_empty())
+        .map(|(name, _)| name)
+        .collect()
+    }
+
+    /// Address of a file exported next to the article: absolute when the
+    /// canonical URL is known
+    pub fn asset_url(&self, file_name: &str) -> String {
+        let base = self.canonical_url.trim();
+        if base.is_empty() {
+            return file_name.to_string();
+        }
+        let folder = if base.ends_with('/') { base } else { &base[..base.rfind('/').map_or(base.len(), |i| i + 1)] };
+        format!("{}{}", folder, file_name)
+    }
+
+    /// Open Graph and Twitter Card tags for the page head
+    pub fn social_meta_tags(&self, cover_image: Option<&str>) -> String {
+        let mut tags = vec![
+            ("property", "og:type", "article".to_string()),
+            ("property", "og:title", self.title.clone()),
+            ("property", "og:description", self.meta_description.clone()),
+        ];
+        if !self.canonical_url.trim().is_empty() {
+            tags.push(("property", "og:url", self.canonical_url.trim().to_string()));
+        }
+        if !self.site_name.trim().is_empty() {
+            tags.push(("property", "og:site_name", self.site_name.trim().to_string()));
+        }
+        if let Some(image) = cover_image {
+            tags.push(("property", "og:image", self.asset_url(image)));
+        }
+        if !self.author.trim().is_empty() {
+            tags.push(("property", "article:author", self.author.trim().to_string()));
+        }
+        if let Some(published) = self.published_at {
+            tags.push(("property", "article:published_time", published.to_rfc3339()));
+        }
+        let card = if cover_image.is_some() { "summary_large_image" } else { "summary" };
+        tags.push(("name", "twitter:card", card.to_string()));
+        tags.push(("name", "twitter:title", self.title.clone()));
+        tags.push(("name", "twitter:description", self.meta_description.clone()));
+        if let Some(image) = cover_image {
+            tags.push(("name", "twitter:image", self.asset_url(image)));
+        }
+        if !self.twitter_handle.trim().is_empty() {
+            tags.push(("name", "twitter:site", self.twitter_handle.trim().to_string()));
+        }
+
+        tags.into_iter()
+            .map(|(attribute, key, value)| format!("<meta {}=\"{}\" content=\"{}\">\n", attribute, key, escape_html(&value)))
+            .collect()
+    }
+
+    /// schema.org Article markup for the page head
+    pub fn article_json_ld(&self, cover_image: Option<&str>) -> String {
+        let mut article = serde_json::json!({
+            "@context": "https://schema.org",
+            "@type": "Article",
+            "headline": self.title,
+            "description": self.meta_description,
+        });
+        if !self.keywords.is_empty() {
+            article["keywords"] = self.keywords.join(", ").into();
+        }
+        if !self.author.trim().is_empty() {
+            article["author"] = serde_json::json!({ "@type": "Person", "name": self.author.trim() });
+        }
+        if !self.site_name.trim().is_empty() {
+            article["publisher"] = serde_json::json!({ "@type": "Organization", "name": self.site_name.trim() });
+        }
+        if let Some(image) = cover_image {
+            article["image"] = self.asset_url(image).into();
+        }
+        if !self.canonical_url.trim().is_empty() {
+            article["mainEntityOfPage"] = self.canonical_url.trim().into();
+        }
+        if let Some(published) = self.published_at {
+            article["datePublished"] = published.to_rfc3339().into();
+        }
+        json_ld(&article)
+    }
+}
+
+/// Serializes structured data for a `<script type="application/ld+json">`
+/// element
+pub fn json_ld(value: &serde_json::Value) -> String {
+    // A "</script>" inside a value must not end the script element
+    value.to_string().replace("</", "<\\/")
+}
+
+/// Where a suggested keyword came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeywordSource {
+    /// Frequent in knowledge base documents about the topic
+    Corpus,
+    Brainstorm,
+    Both,
+}
+
+impl KeywordSource {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            KeywordSource::Corpus => "Knowledge base",
+            KeywordSource::Brainstorm => "Brainstorm",
+            KeywordSource::Both => "Knowledge base and brainstorm",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeywordSuggestion {
+    pub term: String,
+    pub source: KeywordSource,
+    /// Best similarity to any draft section, 0 to 1
+    pub coverage: f32,
+    /// Whether the draft uses the term itself
+    pub mentioned: bool,
+}
+
+impl KeywordSuggestion {
+    pub fn is_covered(&self) -> bool {
+        self.mentioned || self.coverage >= COVERED_SIMILARITY
+    }
+}
+
+/// Related keywords, named after the shortest of them
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeywordCluster {
+    pub label: String,
+    pub keywords: Vec<KeywordSuggestion>,
+    /// Section that would cover the cluster, for clusters the draft misses
+    pub suggested_section: Option<String>,
+}
+
+impl KeywordCluster {
+    /// Covered when at least half of its keywords are
+    pub fn is_covered(&self) -> bool {
+        self.keywords.iter().filter(|k| k.is_covered()).count() * 2 >= self.keywords.len()
+    }
+
+    /// Mean coverage of its keywords
+    pub fn coverage(&self) -> f32 {
+        if self.keywords.is_empty() {
+            return 0.0;
+        }
+        self.keywords.iter().map(|k| k.coverage).sum::<f32>() / self.keywords.len() as f32
+    }
+}
+
+/// Keyword clusters for a package's topic, least covered first
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeywordReport {
+    pub topic: String,
+    pub clusters: Vec<KeywordCluster>,
+    /// Knowledge base passages the corpus terms were taken from
+    pub passages_read: usize,
+}
+
+/// Frequent words and two-word phrases of the passages, most frequent
+/// first. Terms must appear at least twice; stop words and numbers are
+/// skipped.
+pub fn candidate_terms(passages: &[String], limit: usize) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for passage in passages {
+        let lower = passage.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_alphanumeric() && c != '-')
+            .map(|w| w.trim_matches('-'))
+            .collect();
+        let keep = |w: &str| w.chars().count() > 2 && !w.chars().all(|c| c.is_ascii_digit()) && !DEFAULT_STOP_WORDS.contains(&w);
+        for (i, word) in words.iter().enumerate() {
+            if !keep(word) {
+                continue;
+            }
+            *counts.entry(word.to_string()).or_default() += 1;
+            if let Some(next) = words.get(i + 1).filter(|next| keep(next)) {
+                *counts.entry(format!("{} {}", word, next)).or_default() += 1;
+            }
+        }
+    }
+
+    let mut terms: Vec<(String, usize)> = counts.into_iter().filter(|(_, count)| *count >= 2).collect();
+    // Phrases are rarer than words, so weigh them up to compete
+    terms.sort_by_key(|(term, count)| (std::cmp::Reverse(count * term.split(' ').count()), term.clone()));
+    terms.into_iter().take(limit).map(|(term, _)| term).collect()
+}
+
+/// Keywords from a brainstorming reply with one per line, lowercased and
+/// without bullets, numbering or quotes
+pub fn parse_keyword_lines(reply: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for line in reply.lines() {
+        let line = line.trim().trim_start_matches(['-', '*', '•']);
+        let line = line.split_once(". ")
+            .filter(|(n, _)| !n.trim().is_empty() && n.trim().chars().all(|c| c.is_ascii_digit()))
+            .map_or(line, |(_, rest)| rest);
+        let keyword = line.trim().trim_matches(['"', '\'']).trim().to_lowercase();
+        if !keyword.is_empty() && keyword.split_whitespace().count() <= 5 && !keywords.contains(&keyword) {
+            keywords.push(keyword);
+        }
+    }
+    keywords
+}
+
+/// Values filled into generated SEO metadata where it has none
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeoDefaults {
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub site_name: String,
+    /// Address articles are published under, e.g. https://blog.example.com;
+    /// a package's canonical URL is this plus its slug
+    #[serde(default)]
+    pub site_url: String,
+    #[serde(default)]
+    pub twitter_handle: String,
+}
+
+impl SeoDefaults {
+    pub fn apply(&self, seo: &mut SeoMetadata) {
+        fill(&mut seo.author, &self.author);
+        fill(&mut seo.site_name, &self.site_name);
+        fill(&mut seo.twitter_handle, &self.twitter_handle);
+        let site_url = self.site_url.trim().trim_end_matches('/');
+        if seo.canonical_url.trim().is_empty() && !site_url.is_empty() && !seo.slug.is_empty() {
+            seo.canonical_url = format!("{}/{}/", site_url, seo.slug);
+        }
+    }
+}
+
+fn fill(field: &mut String, default: &str) {
+    if field.trim().is_empty() {
+        *field = default.trim().to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> SeoMetadata {
+        SeoMetadata {
+            title: "Rust for the web".into(),
+            meta_description: "Build \"fast\" apps.".into(),
+            keywords: vec!["rust".into(), "wasm".into()],
+            slug: "rust-for-the-web".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_defaults_and_missing_fields() {
+        let mut seo = metadata();
+        assert_eq!(seo.missing_fields(), vec!["author"]);
+
+        let defaults = SeoDefaults {
+            author: "Ada".into(),
+            site_name: "Ada's Blog".into(),
+            site_url: "https://blog.example.com/".into(),
+            twitter_handle: String::new(),
+        };
+        defaults.apply(&mut seo);
+        assert!(seo.missing_fields().is_empty());
+        assert_eq!(seo.canonical_url, "https://blog.example.com/rust-for-the-web/");
+        assert_eq!(seo.asset_url("header.png"), "https://blog.example.com/rust-for-the-web/header.png");
+
+        seo.author = "Grace".into();
+        defaults.apply(&mut seo);
+        assert_eq!(seo.author, "Grace");
+    }
+
+    #[test]
+    fn test_candidate_terms() {
+        let passages = vec![
+            "Local inference keeps data private. Local inference needs a GPU.".to_string(),
+            "Quantized models make local inference fast on 2024 laptops.".to_string(),
+        ];
+        let terms = candidate_terms(&passages, 3);
+        assert_eq!(terms[0], "local inference");
+        assert!(terms.contains(&"inference".to_string()));
+        assert!(!terms.iter().any(|t| t.contains("2024") || t == "the"));
+    }
+
+    #[test]
+    fn test_parse_keyword_lines_and_coverage() {
+        assert_eq!(
+            parse_keyword_lines("1. Local LLM\n- \"offline AI\"\n* local llm\n\nthis line is far too long to be a keyword at all"),
+            vec!["local llm", "offline ai"]
+        );
+
+        let keyword = |coverage, mentioned| KeywordSuggestion { term: "x".into(), source: KeywordSource::Corpus, coverage, mentioned };
+        let cluster = KeywordCluster {
+            label: "x".into(),
+            keywords: vec![keyword(0.7, false), keyword(0.1, true), keyword(0.1, false)],
+            suggested_section: None,
+        };
+        assert!(cluster.is_covered());
+        assert!((cluster.coverage() - 0.3).abs() < 1e-6);
+        assert!(!KeywordCluster { keywords: vec![keyword(0.2, false), keyword(0.9, false), keyword(0.1, false)], ..cluster }.is_covered());
+    }
+
+    #[test]
+    fn test_social_tags_and_json_ld() {
+        let mut seo = metadata();
+        let tags = seo.social_meta_tags(None);
+        assert!(tags.contains("<meta property=\"og:description\" content=\"Build &quot;fast&quot; apps.\">"));
+        assert!(tags.contains("<meta name=\"twitter:card\" content=\"summary\">"));
+        assert!(!tags.contains("og:url"));
+
+        seo.canonical_url = "https://example.com/posts/rust.html".into();
+        seo.author = "Ada</script>".into();
+        let tags = seo.social_meta_tags(Some("header.webp"));
+        assert!(tags.contains("<meta property=\"og:image\" content=\"https://example.com/posts/header.webp\">"));
+        assert!(tags.contains("<meta name=\"twitter:card\" content=\"summary_large_image\">"));
+
+        let schema = seo.article_json_ld(Some("header.webp"));
+        let parsed: serde_json::Value = serde_json::from_str(&schema).unwrap();
+        assert_eq!(parsed["@type"], "Article");
+        assert_eq!(parsed["author"]["name"], "Ada</script>");
+        assert_eq!(parsed["image"], "https://example.com/posts/header.webp");
+        assert!(!schema.contains("</script>"));
+    }
+}
@@ -4,6 +4,26 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+use super::format_profile::escape_html;
+use super::{ChatMessage, ChatRole};
+
+/// Styles for shared sessions; the page has no external resources
+const SHARE_STYLES: &str = "\
+body{margin:0;background:#0f172a;color:#e2e8f0;font:15px/1.6 -apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif}\
+main{max-width:800px;margin:0 auto;padding:32px 16px}\
+h1{font-size:22px;margin:0 0 4px}\
+.meta{color:#94a3b8;font-size:13px;margin-bottom:24px}\
+.msg{margin:12px 0;padding:12px 16px;border-radius:14px;overflow-wrap:anywhere}\
+.user{background:#4f46e5;color:#fff;margin-left:15%}\
+.assistant{background:#1e293b;margin-right:15%}\
+.system{background:transparent;border:1px dashed #475569;color:#94a3b8}\
+.role{font-size:12px;font-weight:600;opacity:.7;margin-bottom:4px;text-transform:capitalize}\
+pre{padding:12px;border-radius:8px;overflow-x:auto}\
+code{font-family:ui-monospace,Menlo,monospace;font-size:13px}\
+img{max-width:100%;border-radius:8px}\
+a{color:#60a5fa}\
+table{border-collapse:collapse}td,th{border:1px solid #475569;padding:4px 8px}";
+
 /// Represents a chat session
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Session {
@@ -11,6 +31,213 @@ pub struct Session {
     pub title: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Folder the session is filed under (None = unfiled)
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Pinned sessions are listed above all others
+    #[serde(default)]
+    pub pinned: bool,
+    /// Archived sessions are hidden from the main list
+    #[serde(default)]
+    pub archived: bool,
+    /// Summary of the conversation, shown when it is reopened
+    #[serde(default)]
+    pub summary: Option<SessionSummary>,
+    /// Topic tags assigned by the tagging job
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Label of the cluster of similar sessions this one belongs to
+    #[serde(default)]
+    pub topic: Option<String>,
+    /// Model, persona and temperature chosen for this session only
+    #[serde(default)]
+    pub overrides: SessionOverrides,
+}
+
+/// Sampling temperature of chats that don't set their own
+pub const DEFAULT_TEMPERATURE: f32 = 0.7;
+
+/// Temperatures offered in the chat header, from precise to creative
+pub const TEMPERATURE_CHOICES: &[f32] = &[0.2, 0.5, 0.7, 1.0, 1.3];
+
+/// Chat settings that apply to one session instead of the app settings
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionOverrides {
+    /// Model ID from the model list (None = the active model)
+    #[serde(default)]
+    pub model_id: Option<String>,
+    #[serde(default)]
+    pub persona: Persona,
+    /// None uses [`DEFAULT_TEMPERATURE`]
+    #[serde(default)]
+    pub temperature: Option<f32>,
+}
+
+impl SessionOverrides {
+    pub fn temperature(&self) -> f32 {
+        self.temperature.unwrap_or(DEFAULT_TEMPERATURE)
+    }
+}
+
+/// Role the model takes in a session, set through its system prompt
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Persona {
+    /// No system prompt
+    #[default]
+    Assistant,
+    Concise,
+    Tutor,
+    Coder,
+    Writer,
+}
+
+impl Persona {
+    pub fn all() -> &'static [Persona] {
+        &[Persona::Assistant, Persona::Concise, Persona::Tutor, Persona::Coder, Persona::Writer]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Persona::Assistant => "Assistant",
+            Persona::Concise => "Concise",
+            Persona::Tutor => "Tutor",
+            Persona::Coder => "Coder",
+            Persona::Writer => "Writer",
+        }
+    }
+
+    pub fn system_prompt(&self) -> Option<&'static str> {
+        match self {
+            Persona::Assistant => None,
+            Persona::Concise => Some("You are a concise assistant. Answer in as few words as possible, without preamble or repetition."),
+            Persona::Tutor => Some("You are a patient tutor. Explain step by step with simple examples, and check understanding with a short question at the end."),
+            Persona::Coder => Some("You are an expert programmer. Prefer working code over prose, explain briefly, and point out edge cases and pitfalls."),
+            Persona::Writer => Some("You are a skilled writer and editor. Write clear, vivid prose and keep the requested tone and length."),
+        }
+    }
+}
+
+/// A conversation condensed into a summary, takeaways and action items
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub summary: String,
+    pub takeaways: Vec<String>,
+    pub action_items: Vec<String>,
+    /// Messages in the session when it was summarized
+    pub message_count: usize,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionSummary {
+    /// Parses a summary written as "Summary:", "Takeaways:" and
+    /// "Action items:" sections with bulleted lists
+    pub fn parse(text: &str, message_count: usize) -> Self {
+        #[derive(PartialEq)]
+        enum Section { Summary, Takeaways, ActionItems }
+
+        let mut summary = Self { message_count, created_at: Utc::now(), ..Default::default() };
+        let mut section = Section::Summary;
+        for line in text.lines() {
+            let line = line.trim();
+            let rest = if let Some(rest) = strip_heading(line, "summary") {
+                section = Section::Summary;
+                rest
+            } else if let Some(rest) = strip_heading(line, "takeaways").or_else(|| strip_heading(line, "key takeaways")) {
+                section = Section::Takeaways;
+                rest
+            } else if let Some(rest) = strip_heading(line, "action items") {
+                section = Section::ActionItems;
+                rest
+            } else {
+                line
+            };
+
+            let item = rest.trim_start_matches(['-', '*', '•']).trim();
+            if item.is_empty() || item.eq_ignore_ascii_case("none") {
+                continue;
+            }
+            match section {
+                Section::Summary => {
+                    if !summary.summary.is_empty() {
+                        summary.summary.push(' ');
+                    }
+                    summary.summary.push_str(item);
+                }
+                Section::Takeaways => summary.takeaways.push(item.to_string()),
+                Section::ActionItems => summary.action_items.push(item.to_string()),
+            }
+        }
+        summary
+    }
+
+    /// True if messages were added since the summary was written
+    pub fn is_outdated(&self, message_count: usize) -> bool {
+        message_count > self.message_count
+    }
+}
+
+/// Longest tag kept, in characters
+const MAX_TAG_CHARS: usize = 24;
+
+/// Tags kept per session
+pub const MAX_SESSION_TAGS: usize = 3;
+
+/// Parses a comma or line separated list of tags written by the model into
+/// short lowercase tags
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let text = text
+        .split_once(':')
+        .filter(|(head, _)| head.trim().eq_ignore_ascii_case("tags"))
+        .map_or(text, |(_, rest)| rest);
+    let mut tags: Vec<String> = Vec::new();
+    for raw in text.split([',', '\n', ';']) {
+        let tag = raw
+            .trim()
+            .trim_start_matches(|c: char| c == '-' || c == '*' || c == '#' || c.is_ascii_digit() || c == '.')
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'' || c == '.')
+            .to_lowercase();
+        if tag.is_empty() || tag.chars().count() > MAX_TAG_CHARS || tags.contains(&tag) {
+            continue;
+        }
+        tags.push(tag);
+        if tags.len() == MAX_SESSION_TAGS {
+            break;
+        }
+    }
+    tags
+}
+
+/// Tags and topics with the number of sessions matching each, most used
+/// first
+pub fn tag_counts(sessions: &[Session]) -> Vec<(String, usize)> {
+    let mut names: Vec<&str> = sessions
+        .iter()
+        .flat_map(|s| s.tags.iter().map(String::as_str).chain(s.topic.as_deref()))
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+
+    let mut counts: Vec<(String, usize)> = names
+        .into_iter()
+        .map(|name| (name.to_string(), sessions.iter().filter(|s| s.matches_tag(name)).count()))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// The rest of a line starting with a section heading like "Summary:",
+/// "**Summary:**" or "## Summary"
+fn strip_heading<'a>(line: &'a str, heading: &str) -> Option<&'a str> {
+    let line = line.trim_start_matches(['#', '*', ' ']);
+    if !line.get(..heading.len())?.eq_ignore_ascii_case(heading) {
+        return None;
+    }
+    let rest = &line[heading.len()..];
+    if !(rest.is_empty() || rest.starts_with([':', '*'])) {
+        return None;
+    }
+    Some(rest.trim_start_matches([':', '*']).trim())
 }
 
 impl Session {
@@ -21,12 +248,61 @@ impl Session {
             title,
             created_at: now,
             updated_at: now,
+            folder: None,
+            pinned: false,
+            archived: false,
+            summary: None,
+            tags: Vec::new(),
+            topic: None,
+            overrides: SessionOverrides::default(),
         }
     }
 
     pub fn default_title() -> Self {
         Self::new("New Chat".to_string())
     }
+
+    /// True if the session has the tag or belongs to the topic of that name
+    pub fn matches_tag(&self, tag: &str) -> bool {
+        self.topic.as_deref() == Some(tag) || self.tags.iter().any(|t| t == tag)
+    }
+
+    /// The conversation as a single self-contained HTML page. Images must
+    /// already be data URLs for the page to work offline.
+    pub fn to_html(&self, messages: &[ChatMessage]) -> String {
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width,initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<main>\n<h1>{}</h1>\n<div class=\"meta\">{} · {} messages</div>\n",
+            escape_html(&self.title),
+            SHARE_STYLES,
+            escape_html(&self.title),
+            self.created_at.format("%Y-%m-%d %H:%M UTC"),
+            messages.len()
+        );
+        for message in messages {
+            let class = match message.role {
+                ChatRole::User => "user",
+                ChatRole::Assistant => "assistant",
+                ChatRole::System => "system",
+            };
+            html.push_str(&format!(
+                "<div class=\"msg {}\"><div class=\"role\">{}</div>{}</div>\n",
+                class,
+                message.role,
+                message.content_html(true)
+            ));
+        }
+        html.push_str("</main>\n</body>\n</html>\n");
+        html
+    }
+
+    /// The conversation as a Markdown transcript
+    pub fn to_markdown(&self, messages: &[ChatMessage]) -> String {
+        let mut md = format!("# {}\n\n_{}_\n\n", self.title, self.created_at.format("%Y-%m-%d %H:%M UTC"));
+        for message in messages {
+            md.push_str(&format!("## {}\n\n{}\n\n", message.role, message.content.trim()));
+        }
+        md
+    }
 }
 
 impl Default for Session {
@@ -34,3 +310,55 @@ impl Default for Session {
         Self::default_title()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_overrides_default_for_old_records() {
+        let overrides: SessionOverrides = serde_json::from_str("{}").unwrap();
+        assert_eq!(overrides, SessionOverrides::default());
+        assert_eq!(overrides.temperature(), DEFAULT_TEMPERATURE);
+        assert_eq!(overrides.persona.system_prompt(), None);
+
+        let overrides: SessionOverrides = serde_json::from_str(r#"{"persona": "Coder", "temperature": 0.2}"#).unwrap();
+        assert_eq!(overrides.temperature(), 0.2);
+        assert!(overrides.persona.system_prompt().is_some());
+    }
+
+    #[test]
+    fn test_parse_summary_sections() {
+        let text = "**Summary:** We planned a trip\nto Kyoto.\n\nKey takeaways:\n- Go in autumn\n* Book early\n\n## Action items\n- Reserve the ryokan\n";
+        let summary = SessionSummary::parse(text, 6);
+        assert_eq!(summary.summary, "We planned a trip to Kyoto.");
+        assert_eq!(summary.takeaways, vec!["Go in autumn", "Book early"]);
+        assert_eq!(summary.action_items, vec!["Reserve the ryokan"]);
+        assert!(summary.is_outdated(8));
+        assert!(!summary.is_outdated(6));
+
+        let none = SessionSummary::parse("Summary: Small talk.\nTakeaways:\n- None\nAction items: none", 2);
+        assert!(none.takeaways.is_empty() && none.action_items.is_empty());
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(parse_tags("Tags: Rust, #Async, web servers"), vec!["rust", "async", "web servers"]);
+        assert_eq!(parse_tags("1. Travel\n2. Japan\n3. travel\n4. Food"), vec!["travel", "japan", "food"]);
+        assert!(parse_tags("a tag that is much too long to be useful").is_empty());
+    }
+
+    #[test]
+    fn test_tag_counts_include_topics() {
+        let mut a = Session::new("a".into());
+        a.tags = vec!["rust".into()];
+        a.topic = Some("programming".into());
+        let mut b = Session::new("b".into());
+        b.tags = vec!["python".into(), "programming".into()];
+        b.topic = Some("programming".into());
+
+        let counts = tag_counts(&[a, b]);
+        assert_eq!(counts[0], ("programming".to_string(), 2));
+        assert_eq!(counts.len(), 3);
+    }
+}
@@ -0,0 +1,238 @@
+//! Session Title Rules
+//!
+//! How new chats are named: a keyword title taken from the first message,
+//! optionally replaced by one the LLM writes once the conversation has some
+//! substance. Chinese and Japanese have no spaces between words, so their
+//! text is split at stop words instead.
+
+use serde::{Deserialize, Serialize};
+
+/// Words left out of keyword titles, English then Chinese
+pub(crate) const DEFAULT_STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "is", "are", "was", "were", "be", "been", "being",
+    "have", "has", "had", "do", "does", "did", "will", "would", "could",
+    "should", "may", "might", "must", "can", "to", "of", "in", "for",
+    "on", "with", "at", "by", "from", "as", "into", "through", "during",
+    "before", "after", "above", "below", "between", "under", "again",
+    "further", "then", "once", "here", "there", "when", "where", "why",
+    "how", "all", "each", "few", "more", "most", "other", "some", "such",
+    "no", "nor", "not", "only", "own", "same", "so", "than", "too", "very",
+    "just", "and", "but", "if", "or", "because", "until", "while", "about",
+    "what", "which", "who", "whom", "this", "that", "these", "those", "am",
+    "it", "its", "i", "me", "my", "myself", "we", "our", "ours", "you",
+    "your", "yours", "he", "him", "his", "she", "her", "hers", "they",
+    "them", "their", "please", "help", "want", "need", "tell", "explain",
+    "show", "give", "make", "let", "know", "think", "like",
+    "的", "了", "是", "在", "我", "有", "和", "就", "不", "人", "都", "一",
+    "个", "上", "也", "很", "到", "说", "要", "去", "你", "会", "着", "没有",
+    "看", "好", "自己", "这", "那", "吗", "什么", "怎么", "为什么", "如何",
+    "请", "帮", "告诉", "解释", "能", "可以", "想", "用", "一下",
+];
+
+/// How new chats get their title
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum TitleMethod {
+    /// Keywords from the first message, kept unless renamed
+    Keywords,
+    /// Keywords at first, then a title written by the LLM after a few turns
+    #[default]
+    Llm,
+}
+
+impl TitleMethod {
+    pub fn all() -> &'static [TitleMethod] {
+        &[TitleMethod::Llm, TitleMethod::Keywords]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TitleMethod::Keywords => "Keywords only",
+            TitleMethod::Llm => "Keywords, then LLM",
+        }
+    }
+}
+
+/// Settings for naming new chats
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TitleRules {
+    #[serde(default)]
+    pub method: TitleMethod,
+    /// Words left out of keyword titles in addition to the built-in list
+    #[serde(default)]
+    pub custom_stop_words: Vec<String>,
+    /// Keywords taken from the first message
+    #[serde(default = "default_max_keywords")]
+    pub max_keywords: usize,
+    /// Characters in a title, keyword or LLM-written, before it is cut off
+    /// with "..."
+    #[serde(default = "default_max_length")]
+    pub max_length: usize,
+}
+
+fn default_max_keywords() -> usize {
+    4
+}
+
+fn default_max_length() -> usize {
+    40
+}
+
+impl Default for TitleRules {
+    fn default() -> Self {
+        Self {
+            method: TitleMethod::default(),
+            custom_stop_words: Vec::new(),
+            max_keywords: default_max_keywords(),
+            max_length: default_max_length(),
+        }
+    }
+}
+
+impl TitleRules {
+    fn is_stop_word(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        DEFAULT_STOP_WORDS.contains(&word.as_str())
+            || self.custom_stop_words.iter().any(|w| w.trim().to_lowercase() == word)
+    }
+
+    /// Stop words that can appear inside a run of Chinese or Japanese text,
+    /// longest first so "为什么" is removed before "么" could match
+    fn cjk_stop_words(&self) -> Vec<String> {
+        let mut words: Vec<String> = DEFAULT_STOP_WORDS.iter()
+            .map(|w| w.to_string())
+            .chain(self.custom_stop_words.iter().map(|w| w.trim().to_string()))
+            .filter(|w| w.chars().any(is_cjk))
+            .collect();
+        words.sort_by_key(|w| std::cmp::Reverse(w.chars().count()));
+        words
+    }
+}
+
+/// Chinese characters and Japanese kana, which are written without spaces
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+/// Cuts a title to `max_length` characters, ending it with "..."
+fn truncate_title(title: &str, max_length: usize) -> String {
+    if title.chars().count() > max_length {
+        format!("{}...", title.chars().take(max_length.saturating_sub(3)).collect::<String>())
+    } else {
+        title.to_string()
+    }
+}
+
+/// Splits a run of CJK text into the pieces between stop words; single
+/// characters left over are dropped
+fn segment_cjk(run: &str, stop_words: &[String]) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut piece = String::new();
+    let mut rest = run;
+    while let Some(c) = rest.chars().next() {
+        match stop_words.iter().find(|w| rest.starts_with(w.as_str())) {
+            Some(word) => {
+                pieces.push(std::mem::take(&mut piece));
+                rest = &rest[word.len()..];
+            }
+            None => {
+                piece.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    pieces.push(piece);
+    pieces.retain(|p| p.chars().count() > 1);
+    pieces
+}
+
+/// Title made of the first message's keywords, leaving out stop words
+pub fn keyword_title(message: &str, rules: &TitleRules) -> String {
+    let cjk_stop_words = rules.cjk_stop_words();
+    let mut keywords: Vec<String> = Vec::new();
+    let tokens = message
+        .split(|c: char| c.is_whitespace() || matches!(c, '?' | '!' | '.' | ',' | '。' | '？' | '！' | '，' | '、' | '：' | ':' | ';' | '；'))
+        .filter(|w| !w.is_empty());
+    for token in tokens {
+        // Mixed tokens such as "用Rust写" are split into script runs first
+        let mut runs: Vec<(bool, String)> = Vec::new();
+        for c in token.chars() {
+            match runs.last_mut() {
+                Some((cjk, run)) if *cjk == is_cjk(c) => run.push(c),
+                _ => runs.push((is_cjk(c), c.to_string())),
+            }
+        }
+        for (cjk, run) in runs {
+            if cjk {
+                keywords.extend(segment_cjk(&run, &cjk_stop_words));
+            } else if (run.len() > 1 || !run.is_ascii()) && !rules.is_stop_word(&run) {
+                keywords.push(run);
+            }
+        }
+    }
+    keywords.truncate(rules.max_keywords.max(1));
+
+    if keywords.is_empty() {
+        return truncate_title(message.trim(), rules.max_length);
+    }
+
+    // Chinese keywords run together, others are separated by spaces
+    let mut title = String::new();
+    for keyword in &keywords {
+        let joins_cjk = title.chars().last().is_some_and(is_cjk) && keyword.chars().next().is_some_and(is_cjk);
+        if !title.is_empty() && !joins_cjk {
+            title.push(' ');
+        }
+        title.push_str(keyword);
+    }
+    truncate_title(&title, rules.max_length)
+}
+
+/// The title line from an LLM reply, without a "Title:" prefix, quotes or
+/// closing punctuation
+pub fn clean_generated_title(raw: &str, max_length: usize) -> String {
+    let line = raw.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+
+    let line = line
+        .strip_prefix("Title:")
+        .or_else(|| line.strip_prefix("title:"))
+        .unwrap_or(line)
+        .trim();
+
+    let title = line
+        .trim_matches(|c: char| matches!(c, '"' | '\'' | '“' | '”' | '「' | '」' | '*' | '#'))
+        .trim_end_matches(['.', '。', '!', '！', '?', '？'])
+        .trim();
+
+    truncate_title(title, max_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_title() {
+        let rules = TitleRules::default();
+        assert_eq!(keyword_title("How do I write a Rust macro?", &rules), "write Rust macro");
+        assert_eq!(keyword_title("请帮我解释一下量子计算", &rules), "量子计算");
+        assert_eq!(keyword_title("如何用Rust写网络爬虫", &rules), "Rust 写网络爬虫");
+        assert_eq!(keyword_title("Is it?", &rules), "Is it?");
+
+        let rules = TitleRules { custom_stop_words: vec!["Rust".to_string()], max_keywords: 1, ..TitleRules::default() };
+        assert_eq!(keyword_title("How do I write a Rust macro?", &rules), "write");
+
+        let rules = TitleRules { max_length: 10, ..TitleRules::default() };
+        assert_eq!(keyword_title("Kubernetes deployment strategies", &rules), "Kuberne...");
+    }
+
+    #[test]
+    fn test_clean_generated_title() {
+        assert_eq!(clean_generated_title("\"Rust Async Basics.\"\nextra", 50), "Rust Async Basics");
+        assert_eq!(clean_generated_title("Title: EIP-7702 Overview", 50), "EIP-7702 Overview");
+        assert_eq!(clean_generated_title("   \n", 50), "");
+        assert_eq!(clean_generated_title("量子计算入门指南与实践", 8), "量子计算入...");
+    }
+}
@@ -1,6 +1,8 @@
 //! Application Settings Model
 
+use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
+use super::{CustomImageModel, LoraWeight, PronunciationEntry, QueryExpansion, SpeechOutput, TitleRules};
 
 /// Response language options
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -15,6 +17,17 @@ pub enum ResponseLanguage {
 }
 
 impl ResponseLanguage {
+    pub fn all() -> &'static [ResponseLanguage] {
+        &[
+            ResponseLanguage::Chinese,
+            ResponseLanguage::English,
+            ResponseLanguage::Thai,
+            ResponseLanguage::Spanish,
+            ResponseLanguage::French,
+            ResponseLanguage::German,
+        ]
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             ResponseLanguage::Chinese => "Chinese",
@@ -113,13 +126,137 @@ impl FontSize {
         }
     }
 
-    /// Returns inline CSS style for font-size (fallback when Tailwind classes are purged)
-    pub fn font_style(&self) -> &'static str {
+    /// Root font size in pixels; all rem-based sizes in the UI scale with it
+    pub fn root_px(&self) -> u32 {
+        match self {
+            FontSize::Small => 14,
+            FontSize::Medium => 16,
+            FontSize::Large => 18,
+            FontSize::ExtraLarge => 20,
+        }
+    }
+
+    /// One step larger, for zooming in
+    pub fn larger(&self) -> FontSize {
+        match self {
+            FontSize::Small => FontSize::Medium,
+            FontSize::Medium => FontSize::Large,
+            FontSize::Large | FontSize::ExtraLarge => FontSize::ExtraLarge,
+        }
+    }
+
+    /// One step smaller, for zooming out
+    pub fn smaller(&self) -> FontSize {
+        match self {
+            FontSize::Small | FontSize::Medium => FontSize::Small,
+            FontSize::Large => FontSize::Medium,
+            FontSize::ExtraLarge => FontSize::Large,
+        }
+    }
+}
+
+/// Key that sends a chat message; the other Enter combinations add a new line
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SendShortcut {
+    #[default]
+    Enter,
+    /// Ctrl+Enter, or Cmd+Enter on macOS
+    CtrlEnter,
+}
+
+impl SendShortcut {
+    pub fn all() -> &'static [SendShortcut] {
+        &[SendShortcut::Enter, SendShortcut::CtrlEnter]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SendShortcut::Enter => "Enter",
+            SendShortcut::CtrlEnter => "Ctrl+Enter",
+        }
+    }
+
+    /// Whether Enter with these modifiers sends the message
+    pub fn sends(&self, ctrl_or_cmd: bool, shift: bool) -> bool {
+        match self {
+            SendShortcut::Enter => !shift,
+            SendShortcut::CtrlEnter => ctrl_or_cmd,
+        }
+    }
+
+    /// Translation key of the hint shown under the chat input
+    pub fn hint_key(&self) -> &'static str {
+        match self {
+            SendShortcut::Enter => "chat.hint_enter",
+            SendShortcut::CtrlEnter => "chat.hint_ctrl_enter",
+        }
+    }
+}
+
+/// When the chat scrolls down to follow a streaming answer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum AutoScroll {
+    /// Follow only while the view is already at the bottom
+    #[default]
+    AtBottom,
+    Always,
+    Never,
+}
+
+impl AutoScroll {
+    pub fn all() -> &'static [AutoScroll] {
+        &[AutoScroll::AtBottom, AutoScroll::Always, AutoScroll::Never]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AutoScroll::AtBottom => "When at the bottom",
+            AutoScroll::Always => "Always",
+            AutoScroll::Never => "Never",
+        }
+    }
+
+    /// Whether new text scrolls the chat down
+    pub fn follows(&self, at_bottom: bool) -> bool {
         match self {
-            FontSize::Small => "font-size: 0.875rem; line-height: 1.25rem;",
-            FontSize::Medium => "font-size: 1rem; line-height: 1.5rem;",
-            FontSize::Large => "font-size: 1.125rem; line-height: 1.75rem;",
-            FontSize::ExtraLarge => "font-size: 1.25rem; line-height: 1.75rem;",
+            AutoScroll::AtBottom => at_bottom,
+            AutoScroll::Always => true,
+            AutoScroll::Never => false,
+        }
+    }
+}
+
+/// Safeguards applied while a response streams
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenerationLimits {
+    /// Generation ends before any of these strings; they are not shown
+    #[serde(default)]
+    pub stop_sequences: Vec<String>,
+    /// End generation when the output starts looping
+    #[serde(default = "default_true")]
+    pub stop_on_repetition: bool,
+    /// Most tokens a single response may use
+    #[serde(default = "default_max_output_tokens")]
+    pub max_output_tokens: u32,
+}
+
+/// Tokens per response unless the user sets a cap
+pub const DEFAULT_MAX_OUTPUT_TOKENS: u32 = 600;
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_output_tokens() -> u32 {
+    DEFAULT_MAX_OUTPUT_TOKENS
+}
+
+impl Default for GenerationLimits {
+    fn default() -> Self {
+        Self {
+            stop_sequences: Vec::new(),
+            stop_on_repetition: true,
+            max_output_tokens: DEFAULT_MAX_OUTPUT_TOKENS,
         }
     }
 }
@@ -128,18 +265,129 @@ impl FontSize {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AppSettings {
     pub language: ResponseLanguage,
+    /// Language of the interface, separate from the response language
+    #[serde(default = "default_ui_language")]
+    pub ui_language: ResponseLanguage,
     pub theme: Theme,
     pub font_size: FontSize,
     pub model_name: String,
+    #[serde(default)]
+    pub generation: GenerationLimits,
+    /// Show OS notifications when jobs finish (desktop app)
+    #[serde(default = "default_true")]
+    pub desktop_notifications: bool,
+    /// Render LaTeX math in messages
+    #[serde(default = "default_true")]
+    pub render_math: bool,
+    /// Collections whose retrieval favours recently added documents, with
+    /// the half-life of the score boost in days
+    #[serde(default)]
+    pub recency_half_life_days: BTreeMap<String, u32>,
+    /// How questions are rewritten before retrieval
+    #[serde(default)]
+    pub query_expansion: QueryExpansion,
+    /// Build the retrieval query from recent turns plus the new question
+    #[serde(default = "default_true")]
+    pub conversational_retrieval: bool,
+    /// Small model (HuggingFace ID) for quick prompts such as condensing
+    /// follow-up questions; None uses the chat model
+    #[serde(default)]
+    pub routing_model: Option<String>,
+    /// TTS engine used to read messages aloud ("system", "vibevoice" or
+    /// "kokoro")
+    #[serde(default = "default_speech_engine")]
+    pub speech_engine: String,
+    /// Output device, volume, speed and pitch of generated speech
+    #[serde(default)]
+    pub speech_output: SpeechOutput,
+    /// How terms are spoken by TTS
+    #[serde(default)]
+    pub pronunciations: Vec<PronunciationEntry>,
+    /// LoRA weights offered in the image panel, applied when enabled
+    #[serde(default)]
+    pub image_loras: Vec<LoraWeight>,
+    /// Locally saved image models registered in the image panel
+    #[serde(default)]
+    pub custom_image_models: Vec<CustomImageModel>,
+    /// Key that sends a chat message
+    #[serde(default)]
+    pub send_shortcut: SendShortcut,
+    /// Whether the chat follows a streaming answer
+    #[serde(default)]
+    pub auto_scroll: AutoScroll,
+    /// How new chats are named
+    #[serde(default)]
+    pub session_titles: TitleRules,
+}
+
+fn default_ui_language() -> ResponseLanguage {
+    ResponseLanguage::English
+}
+
+fn default_speech_engine() -> String {
+    "system".to_string()
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             language: ResponseLanguage::Chinese,
+            ui_language: default_ui_language(),
             theme: Theme::Dark,
             font_size: FontSize::Medium,
             model_name: "Qwen 2.5 7B".to_string(),
+            generation: GenerationLimits::default(),
+            desktop_notifications: true,
+            render_math: true,
+            recency_half_life_days: BTreeMap::new(),
+            query_expansion: QueryExpansion::Off,
+            conversational_retrieval: true,
+            routing_model: None,
+            speech_engine: default_speech_engine(),
+            speech_output: SpeechOutput::default(),
+            pronunciations: Vec::new(),
+            image_loras: Vec::new(),
+            custom_image_models: Vec::new(),
+            send_shortcut: SendShortcut::Enter,
+            auto_scroll: AutoScroll::AtBottom,
+            session_titles: TitleRules::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_shortcut() {
+        assert!(SendShortcut::Enter.sends(false, false));
+        assert!(!SendShortcut::Enter.sends(false, true));
+        assert!(!SendShortcut::CtrlEnter.sends(false, false));
+        assert!(SendShortcut::CtrlEnter.sends(true, false));
+    }
+
+    #[test]
+    fn test_auto_scroll_follows() {
+        assert!(AutoScroll::AtBottom.follows(true));
+        assert!(!AutoScroll::AtBottom.follows(false));
+        assert!(AutoScroll::Always.follows(false));
+        assert!(!AutoScroll::Never.follows(true));
+    }
+
+    #[test]
+    fn test_font_size_steps() {
+        assert_eq!(FontSize::Medium.larger(), FontSize::Large);
+        assert_eq!(FontSize::ExtraLarge.larger(), FontSize::ExtraLarge);
+        assert_eq!(FontSize::Large.smaller(), FontSize::Medium);
+        assert_eq!(FontSize::Small.smaller(), FontSize::Small);
+    }
+
+    #[test]
+    fn test_send_shortcut_defaults_for_old_settings() {
+        let mut value = serde_json::to_value(AppSettings::default()).unwrap();
+        value.as_object_mut().unwrap().remove("send_shortcut");
+        let settings: AppSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(settings.send_shortcut, SendShortcut::Enter);
+    }
+}
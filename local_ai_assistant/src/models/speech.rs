@@ -0,0 +1,334 @@
+//! Speech Playback Model
+//!
+//! Assistant messages queued for reading aloud. Each message is spoken one
+//! sentence at a time so playback can be paused, skipped and followed along
+//! in the message.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Playback rates offered in the player
+pub const PLAYBACK_SPEEDS: [f32; 6] = [0.75, 1.0, 1.25, 1.5, 1.75, 2.0];
+
+/// Pitch factors allowed, from an octave down to an octave up
+pub const PITCH_RANGE: (f32, f32) = (0.5, 2.0);
+
+/// Sample rate speech is resampled to when its pitch is changed
+pub const PITCH_SAMPLE_RATE: u32 = 24_000;
+
+/// An audio output device reported by the system
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AudioOutputDevice {
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// Where and how generated speech is played, in the TTS panel and when
+/// reading messages aloud
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpeechOutput {
+    /// Output device name; None plays on the system default
+    #[serde(default)]
+    pub device: Option<String>,
+    /// 0.0 to 1.0
+    pub volume: f32,
+    pub speed: f32,
+    /// Pitch factor, 1.0 leaves the voice unchanged
+    pub pitch: f32,
+}
+
+impl Default for SpeechOutput {
+    fn default() -> Self {
+        Self { device: None, volume: 1.0, speed: 1.0, pitch: 1.0 }
+    }
+}
+
+/// ffmpeg audio filter that changes pitch by `pitch` while keeping the
+/// duration, or None when the pitch is unchanged
+pub fn pitch_filter(pitch: f32) -> Option<String> {
+    if !pitch.is_finite() || (pitch - 1.0).abs() < 0.01 {
+        return None;
+    }
+    let pitch = pitch.clamp(PITCH_RANGE.0, PITCH_RANGE.1);
+    Some(format!(
+        "aresample={rate},asetrate={shifted},aresample={rate},atempo={tempo:.4}",
+        rate = PITCH_SAMPLE_RATE,
+        shifted = (PITCH_SAMPLE_RATE as f32 * pitch).round() as u32,
+        tempo = 1.0 / pitch,
+    ))
+}
+
+/// Text of a Markdown message as it should be read: code blocks are left
+/// out, links keep their text and formatting marks are removed
+pub fn speakable_text(markdown: &str) -> String {
+    let mut text = String::new();
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        let line = trimmed.trim_start_matches(['#', '>']).trim_start();
+        let line = line.strip_prefix("- ")
+            .or_else(|| line.strip_prefix("* "))
+            .or_else(|| line.split_once(". ").filter(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())).map(|(_, rest)| rest))
+            .unwrap_or(line);
+        text.push_str(&strip_inline_markup(line));
+        text.push('\n');
+    }
+    text
+}
+
+/// Drops emphasis and code marks, and replaces `[text](url)` with its text
+fn strip_inline_markup(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('[') {
+        let link = rest[start..].split_once("](").and_then(|(label, tail)| {
+            let close = tail.find(')')?;
+            Some((&label[1..], &tail[close + 1..]))
+        });
+        match link {
+            Some((label, tail)) if !label.contains('[') => {
+                out.push_str(&rest[..start]);
+                out.push_str(label);
+                rest = tail;
+            }
+            _ => {
+                out.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out.chars().filter(|c| !matches!(c, '*' | '_' | '`' | '~')).collect()
+}
+
+/// Splits text into sentences at `.`, `!`, `?` (and their CJK forms)
+/// followed by a space, and at line breaks. Empty sentences are dropped.
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    for line in text.lines() {
+        let mut current = String::new();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            current.push(c);
+            let ends = match c {
+                '。' | '！' | '？' => true,
+                '.' | '!' | '?' => chars.peek().is_none_or(|next| next.is_whitespace()),
+                _ => false,
+            };
+            if ends {
+                push_sentence(&mut sentences, &current);
+                current.clear();
+            }
+        }
+        push_sentence(&mut sentences, &current);
+    }
+    sentences
+}
+
+fn push_sentence(sentences: &mut Vec<String>, sentence: &str) {
+    let sentence = sentence.trim();
+    if sentence.chars().any(|c| c.is_alphanumeric()) {
+        sentences.push(sentence.to_string());
+    }
+}
+
+/// A message waiting to be read aloud
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueuedSpeech {
+    pub message_id: Uuid,
+    pub sentences: Vec<String>,
+}
+
+/// Messages to read aloud, the first one being the one playing
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlaybackQueue {
+    pub items: Vec<QueuedSpeech>,
+    /// Sentence of the first item being spoken
+    pub sentence: usize,
+    pub paused: bool,
+}
+
+impl PlaybackQueue {
+    /// Queues a message unless it is already queued. Returns false when it
+    /// has nothing to say.
+    pub fn enqueue(&mut self, message_id: Uuid, content: &str) -> bool {
+        if self.contains(message_id) {
+            return true;
+        }
+        let sentences = split_sentences(&speakable_text(content));
+        if sentences.is_empty() {
+            return false;
+        }
+        if self.items.is_empty() {
+            self.sentence = 0;
+            self.paused = false;
+        }
+        self.items.push(QueuedSpeech { message_id, sentences });
+        true
+    }
+
+    /// Adds sentences to a message still being written, queueing it again
+    /// if everything said so far has played
+    pub fn push_sentences(&mut self, message_id: Uuid, sentences: Vec<String>) {
+        if sentences.is_empty() {
+            return;
+        }
+        match self.items.iter_mut().find(|item| item.message_id == message_id) {
+            Some(item) => item.sentences.extend(sentences),
+            None => {
+                if self.items.is_empty() {
+                    self.sentence = 0;
+                }
+                self.items.push(QueuedSpeech { message_id, sentences });
+            }
+        }
+    }
+
+    pub fn contains(&self, message_id: Uuid) -> bool {
+        self.items.iter().any(|item| item.message_id == message_id)
+    }
+
+    /// Message and sentence index being spoken, with the sentence text
+    pub fn current(&self) -> Option<(Uuid, usize, String)> {
+        let item = self.items.first()?;
+        Some((item.message_id, self.sentence, item.sentences.get(self.sentence)?.clone()))
+    }
+
+    /// The sentence after the current one, possibly in the next message
+    pub fn upcoming(&self) -> Option<(Uuid, usize, String)> {
+        let item = self.items.first()?;
+        match item.sentences.get(self.sentence + 1) {
+            Some(sentence) => Some((item.message_id, self.sentence + 1, sentence.clone())),
+            None => {
+                let next = self.items.get(1)?;
+                Some((next.message_id, 0, next.sentences.first()?.clone()))
+            }
+        }
+    }
+
+    /// Sentence being spoken in a message, if it is the one playing
+    pub fn speaking_in(&self, message_id: Uuid) -> Option<usize> {
+        self.items.first().filter(|item| item.message_id == message_id).map(|_| self.sentence)
+    }
+
+    /// Moves to the next sentence, then to the next message
+    pub fn advance(&mut self) {
+        let Some(item) = self.items.first() else {
+            return;
+        };
+        if self.sentence + 1 < item.sentences.len() {
+            self.sentence += 1;
+        } else {
+            self.skip();
+        }
+    }
+
+    /// Drops the message playing and starts the next one
+    pub fn skip(&mut self) {
+        if !self.items.is_empty() {
+            self.items.remove(0);
+        }
+        self.sentence = 0;
+    }
+
+    /// Removes a message, whether playing or waiting
+    pub fn remove(&mut self, message_id: Uuid) {
+        if self.speaking_in(message_id).is_some() {
+            self.skip();
+        } else {
+            self.items.retain(|item| item.message_id != message_id);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.sentence = 0;
+        self.paused = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speakable_text() {
+        let markdown = "# Title\n\nUse **bold** and `code`, see [the docs](https://x.y).\n\n```rust\nfn main() {}\n```\n- First item\n2. Second item\n";
+        let text = speakable_text(markdown);
+        assert!(text.contains("Title\n"));
+        assert!(text.contains("Use bold and code, see the docs."));
+        assert!(!text.contains("fn main"));
+        assert!(text.contains("First item\nSecond item\n"));
+    }
+
+    #[test]
+    fn test_pitch_filter() {
+        assert_eq!(pitch_filter(1.0), None);
+        assert_eq!(pitch_filter(f32::NAN), None);
+        assert_eq!(pitch_filter(1.25).as_deref(), Some("aresample=24000,asetrate=30000,aresample=24000,atempo=0.8000"));
+        // Clamped to an octave
+        assert!(pitch_filter(4.0).unwrap().contains("asetrate=48000"));
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        let sentences = split_sentences("Hello there. Version 2.5 is out! Is it?\nA list line\n你好。世界！\n...");
+        assert_eq!(sentences, vec!["Hello there.", "Version 2.5 is out!", "Is it?", "A list line", "你好。", "世界！"]);
+    }
+
+    #[test]
+    fn test_playback_queue() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut queue = PlaybackQueue::default();
+        assert!(queue.enqueue(a, "One. Two."));
+        assert!(queue.enqueue(b, "Three."));
+        assert!(!queue.enqueue(Uuid::new_v4(), "```\ncode\n```"));
+        assert_eq!(queue.current(), Some((a, 0, "One.".to_string())));
+        assert_eq!(queue.upcoming(), Some((a, 1, "Two.".to_string())));
+
+        queue.advance();
+        assert_eq!(queue.speaking_in(a), Some(1));
+        assert_eq!(queue.upcoming(), Some((b, 0, "Three.".to_string())));
+        queue.advance();
+        assert_eq!(queue.current(), Some((b, 0, "Three.".to_string())));
+        assert_eq!(queue.speaking_in(a), None);
+
+        queue.skip();
+        assert_eq!(queue.current(), None);
+    }
+
+    #[test]
+    fn test_push_sentences() {
+        let a = Uuid::new_v4();
+        let mut queue = PlaybackQueue::default();
+        queue.push_sentences(a, vec!["One.".to_string()]);
+        queue.push_sentences(a, vec!["Two.".to_string()]);
+        assert_eq!(queue.items[0].sentences.len(), 2);
+        queue.advance();
+        queue.advance();
+        assert!(queue.items.is_empty());
+        queue.push_sentences(a, vec!["Three.".to_string()]);
+        assert_eq!(queue.current(), Some((a, 0, "Three.".to_string())));
+    }
+
+    #[test]
+    fn test_playback_queue_remove() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut queue = PlaybackQueue::default();
+        queue.enqueue(a, "One. Two.");
+        queue.enqueue(b, "Three.");
+        queue.advance();
+        queue.remove(b);
+        assert_eq!(queue.items.len(), 1);
+        assert_eq!(queue.sentence, 1);
+        queue.remove(a);
+        assert!(queue.items.is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+//! Storage Location Model
+//!
+//! The directories iDoris stores its data in, and the user's overrides for
+//! them. Shared between the settings UI and the server.
+
+use serde::{Deserialize, Serialize};
+
+/// A configurable storage directory
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageLocation {
+    /// Chat database, settings, profiles and logs
+    Data,
+    /// Vector index of the context documents
+    Index,
+    /// Context documents of the default profile
+    Context,
+    /// Generated images, videos and content packages
+    Output,
+}
+
+impl StorageLocation {
+    pub fn all() -> [StorageLocation; 4] {
+        [StorageLocation::Data, StorageLocation::Index, StorageLocation::Context, StorageLocation::Output]
+    }
+
+    /// Environment variable that overrides the location
+    pub fn env_var(&self) -> &'static str {
+        match self {
+            StorageLocation::Data => "IDORIS_DATA_DIR",
+            StorageLocation::Index => "IDORIS_INDEX_DIR",
+            StorageLocation::Context => "IDORIS_CONTEXT_DIR",
+            StorageLocation::Output => "IDORIS_OUTPUT_DIR",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            StorageLocation::Data => "Data",
+            StorageLocation::Index => "Vector index",
+            StorageLocation::Context => "Context documents",
+            StorageLocation::Output => "Generated output",
+        }
+    }
+
+    /// Folder name inside the data directory when not configured
+    pub fn default_subdir(&self) -> Option<&'static str> {
+        match self {
+            StorageLocation::Data => None,
+            StorageLocation::Index => Some("index"),
+            StorageLocation::Context => Some("context"),
+            StorageLocation::Output => Some("output"),
+        }
+    }
+}
+
+/// Directories set in the settings UI; `None` uses the default
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StoragePaths {
+    #[serde(default)]
+    pub data: Option<String>,
+    #[serde(default)]
+    pub index: Option<String>,
+    #[serde(default)]
+    pub context: Option<String>,
+    #[serde(default)]
+    pub output: Option<String>,
+}
+
+impl StoragePaths {
+    pub fn get(&self, location: StorageLocation) -> Option<&str> {
+        match location {
+            StorageLocation::Data => self.data.as_deref(),
+            StorageLocation::Index => self.index.as_deref(),
+            StorageLocation::Context => self.context.as_deref(),
+            StorageLocation::Output => self.output.as_deref(),
+        }
+    }
+
+    /// Sets a directory; blank clears the override
+    pub fn set(&mut self, location: StorageLocation, path: Option<String>) {
+        let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+        match location {
+            StorageLocation::Data => self.data = path,
+            StorageLocation::Index => self.index = path,
+            StorageLocation::Context => self.context = path,
+            StorageLocation::Output => self.output = path,
+        }
+    }
+}
+
+/// A location as currently in effect, for display
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StorageLocationInfo {
+    pub location: StorageLocation,
+    /// Directory in use
+    pub path: String,
+    /// Set by the location's environment variable, which the settings UI
+    /// cannot override
+    pub from_env: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_trims_and_clears() {
+        let mut paths = StoragePaths::default();
+        paths.set(StorageLocation::Output, Some("  /srv/idoris/output ".to_string()));
+        assert_eq!(paths.get(StorageLocation::Output), Some("/srv/idoris/output"));
+
+        paths.set(StorageLocation::Output, Some("  ".to_string()));
+        assert_eq!(paths, StoragePaths::default());
+    }
+}
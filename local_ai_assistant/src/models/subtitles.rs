@@ -0,0 +1,271 @@
+//! Subtitle Model
+//!
+//! Subtitles for a package video, timed from the narration script or from
+//! a transcription of the audio, and written as SRT and WebVTT.
+
+use serde::{Deserialize, Serialize};
+
+use super::transcript::TranscriptSegment;
+
+/// Longest subtitle line, in characters; cues hold up to two lines
+const SUBTITLE_LINE_CHARS: usize = 42;
+
+/// Where subtitle text and timing come from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubtitleSource {
+    /// The narration script, timed by its length across the narration
+    #[default]
+    Script,
+    /// The video's audio, transcribed locally with whisper.cpp
+    Transcription,
+}
+
+impl SubtitleSource {
+    pub fn all() -> &'static [SubtitleSource] {
+        &[SubtitleSource::Script, SubtitleSource::Transcription]
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            SubtitleSource::Script => "script",
+            SubtitleSource::Transcription => "transcription",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Self {
+        match id {
+            "transcription" => SubtitleSource::Transcription,
+            _ => SubtitleSource::Script,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SubtitleSource::Script => "Narration script",
+            SubtitleSource::Transcription => "Transcribe audio",
+        }
+    }
+}
+
+/// Subtitle settings of one package video
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubtitleOptions {
+    /// Whether subtitles are made when the video is assembled
+    pub enabled: bool,
+    pub source: SubtitleSource,
+    /// Draw the subtitles into the video picture, besides the files
+    pub burn_in: bool,
+}
+
+/// Subtitle files made for a video
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SubtitleTrack {
+    pub srt_path: String,
+    pub vtt_path: String,
+    pub source: SubtitleSource,
+    pub cue_count: usize,
+    pub burned_in: bool,
+}
+
+/// Splits a script into cues of at most two lines and spreads them over
+/// `duration_ms` in proportion to their length
+pub fn script_cues(script: &str, duration_ms: u64) -> Vec<TranscriptSegment> {
+    let texts: Vec<String> = sentences(script).iter().flat_map(|s| split_text(s, SUBTITLE_LINE_CHARS * 2)).collect();
+    let total: usize = texts.iter().map(|t| t.chars().count()).sum();
+    if total == 0 {
+        return Vec::new();
+    }
+
+    let mut cues = Vec::new();
+    let mut chars_before = 0;
+    for text in texts {
+        let length = text.chars().count();
+        let start_ms = duration_ms * chars_before as u64 / total as u64;
+        chars_before += length;
+        let end_ms = duration_ms * chars_before as u64 / total as u64;
+        cues.push(TranscriptSegment { start_ms, end_ms, text });
+    }
+    cues
+}
+
+/// Splits transcribed segments that are too long for two lines, sharing
+/// out their time
+pub fn fit_cues(segments: &[TranscriptSegment]) -> Vec<TranscriptSegment> {
+    let mut cues = Vec::new();
+    for segment in segments {
+        let parts = split_text(&segment.text, SUBTITLE_LINE_CHARS * 2);
+        let total: usize = parts.iter().map(|p| p.chars().count()).sum();
+        let span = segment.end_ms.saturating_sub(segment.start_ms);
+        let mut chars_before = 0;
+        for text in parts {
+            let start_ms = segment.start_ms + span * chars_before as u64 / total as u64;
+            chars_before += text.chars().count();
+            let end_ms = segment.start_ms + span * chars_before as u64 / total as u64;
+            cues.push(TranscriptSegment { start_ms, end_ms, text });
+        }
+    }
+    cues
+}
+
+/// The cues as a SubRip (.srt) file
+pub fn to_srt(cues: &[TranscriptSegment]) -> String {
+    cues.iter().enumerate().map(|(i, cue)| {
+        format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            cue_timestamp(cue.start_ms, ','),
+            cue_timestamp(cue.end_ms, ','),
+            wrap_cue(&cue.text)
+        )
+    }).collect()
+}
+
+/// The cues as a WebVTT (.vtt) file
+pub fn to_vtt(cues: &[TranscriptSegment]) -> String {
+    let mut vtt = String::from("WEBVTT\n\n");
+    for cue in cues {
+        vtt.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            cue_timestamp(cue.start_ms, '.'),
+            cue_timestamp(cue.end_ms, '.'),
+            // "-->" would end the cue text early
+            wrap_cue(&cue.text).replace("-->", "->")
+        ));
+    }
+    vtt
+}
+
+/// "hh:mm:ss,mmm" (SRT) or "hh:mm:ss.mmm" (WebVTT)
+fn cue_timestamp(ms: u64, separator: char) -> String {
+    let seconds = ms / 1000;
+    format!("{:02}:{:02}:{:02}{}{:03}", seconds / 3600, seconds / 60 % 60, seconds % 60, separator, ms % 1000)
+}
+
+/// Sentences of the script, ending at `.`, `!` or `?` (and their
+/// full-width forms) or a line break
+fn sentences(script: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    for line in script.lines() {
+        let mut current = String::new();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            current.push(c);
+            let ends = matches!(c, '。' | '！' | '？')
+                || (matches!(c, '.' | '!' | '?') && chars.peek().is_none_or(|next| next.is_whitespace()));
+            if ends {
+                sentences.push(current.trim().to_string());
+                current.clear();
+            }
+        }
+        sentences.push(current.trim().to_string());
+    }
+    sentences.retain(|s| !s.is_empty());
+    sentences
+}
+
+/// Splits text into pieces of at most `max_chars`, between words where
+/// there are any
+fn split_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut pieces: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let mut word = word.to_string();
+        // Text without spaces, such as Chinese, is cut anywhere
+        while word.chars().count() > max_chars {
+            if !current.is_empty() {
+                pieces.push(std::mem::take(&mut current));
+            }
+            let cut = word.char_indices().nth(max_chars).map(|(i, _)| i).unwrap_or(word.len());
+            pieces.push(word[..cut].to_string());
+            word = word[cut..].to_string();
+        }
+        if current.is_empty() {
+            current = word;
+        } else if current.chars().count() + 1 + word.chars().count() <= max_chars {
+            current.push(' ');
+            current.push_str(&word);
+        } else {
+            pieces.push(std::mem::replace(&mut current, word));
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+/// Breaks a cue into two lines when it is longer than one
+fn wrap_cue(text: &str) -> String {
+    if text.chars().count() <= SUBTITLE_LINE_CHARS {
+        return text.to_string();
+    }
+    let lines = split_text(text, SUBTITLE_LINE_CHARS);
+    match lines.as_slice() {
+        [first, second] => format!("{}\n{}", first, second),
+        // Words did not pack into two lines; break near the middle
+        _ => {
+            let middle = text.chars().count() / 2;
+            let at = text.char_indices()
+                .filter(|(_, c)| c.is_whitespace())
+                .map(|(i, _)| i)
+                .min_by_key(|i| text[..*i].chars().count().abs_diff(middle));
+            match at {
+                Some(i) => format!("{}\n{}", text[..i].trim(), text[i..].trim()),
+                None => {
+                    let cut = text.char_indices().nth(middle).map(|(i, _)| i).unwrap_or(text.len());
+                    format!("{}\n{}", &text[..cut], &text[cut..])
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_cues() {
+        let cues = script_cues("Local models.\n\nThey run offline! Nothing leaves your machine.", 9000);
+        let texts: Vec<&str> = cues.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["Local models.", "They run offline!", "Nothing leaves your machine."]);
+        assert_eq!(cues[0].start_ms, 0);
+        assert_eq!(cues[1].start_ms, cues[0].end_ms);
+        assert_eq!(cues[2].end_ms, 9000);
+        assert!(script_cues("  \n", 9000).is_empty());
+
+        let chinese = script_cues(&"本地模型".repeat(30), 1000);
+        assert_eq!(chinese.len(), 2);
+        assert_eq!(chinese[0].text.chars().count(), SUBTITLE_LINE_CHARS * 2);
+    }
+
+    #[test]
+    fn test_subtitle_files() {
+        let cues = vec![
+            TranscriptSegment { start_ms: 0, end_ms: 1500, text: "Hello.".to_string() },
+            TranscriptSegment {
+                start_ms: 3_661_001,
+                end_ms: 3_662_000,
+                text: "This sentence is far too long to fit on one subtitle line --> wrap".to_string(),
+            },
+        ];
+        assert_eq!(
+            to_srt(&cues),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello.\n\n\
+             2\n01:01:01,001 --> 01:01:02,000\nThis sentence is far too long to fit on\none subtitle line --> wrap\n\n"
+        );
+        let vtt = to_vtt(&cues);
+        assert!(vtt.starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:01.500\nHello.\n"));
+        assert!(vtt.contains("subtitle line -> wrap"));
+    }
+
+    #[test]
+    fn test_fit_cues() {
+        let long = TranscriptSegment { start_ms: 1000, end_ms: 3000, text: "word ".repeat(30).trim().to_string() };
+        let cues = fit_cues(&[long]);
+        assert_eq!(cues.len(), 2);
+        assert_eq!((cues[0].start_ms, cues[1].end_ms), (1000, 3000));
+        assert_eq!(cues[0].end_ms, cues[1].start_ms);
+    }
+}
@@ -0,0 +1,26 @@
+//! Tool Calling Models
+//!
+//! Results of an LLM turn that may call server-side tools.
+
+use serde::{Deserialize, Serialize};
+
+/// One tool call made by the model while answering
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ToolInvocation {
+    /// Tool name, e.g. "calculator"
+    pub name: String,
+    /// Arguments as sent by the model (JSON)
+    pub arguments: String,
+    /// Text result fed back to the model
+    pub result: String,
+    /// Data URL of generated media (image or audio), if any
+    pub media: Option<String>,
+    pub is_error: bool,
+}
+
+/// Final answer plus the tool calls that led to it
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ToolRun {
+    pub answer: String,
+    pub invocations: Vec<ToolInvocation>,
+}
@@ -0,0 +1,142 @@
+//! Audio Transcript Model
+//!
+//! Timestamped segments of a transcribed audio file, podcast episode or
+//! YouTube video, and the Markdown document they are indexed as. Each
+//! paragraph starts with its minute mark so answers can point into the
+//! recording.
+
+use serde::{Deserialize, Serialize};
+
+/// Transcript text per paragraph before a new one is started
+pub const TRANSCRIPT_SECTION_CHARS: usize = 800;
+
+/// One stretch of speech recognized by the transcriber
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// "mm:ss", or "h:mm:ss" from one hour on
+pub fn format_timestamp(ms: u64) -> String {
+    let seconds = ms / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// Joins segments into paragraphs of about `max_chars`, each with the start
+/// time of its first segment
+pub fn group_segments(segments: &[TranscriptSegment], max_chars: usize) -> Vec<(u64, String)> {
+    let mut sections: Vec<(u64, String)> = Vec::new();
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match sections.last_mut() {
+            Some((_, current)) if current.len() + text.len() < max_chars => {
+                current.push(' ');
+                current.push_str(text);
+            }
+            _ => sections.push((segment.start_ms, text.to_string())),
+        }
+    }
+    sections
+}
+
+/// The transcript as a context document, titled by its first line
+pub fn transcript_markdown(title: &str, source: &str, segments: &[TranscriptSegment]) -> String {
+    let mut markdown = format!(
+        "# {}\n\nTranscript of {}. Paragraphs start with their [mm:ss] time in the recording.\n",
+        title, source
+    );
+    for (start_ms, text) in group_segments(segments, TRANSCRIPT_SECTION_CHARS) {
+        markdown.push_str(&format!("\n[{}] {}\n", format_timestamp(start_ms), text));
+    }
+    markdown
+}
+
+/// A YouTube video added to the knowledge base as a summary and a full
+/// transcript
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VideoTranscript {
+    pub video_id: String,
+    pub title: String,
+    pub url: String,
+    pub summary: String,
+    pub summary_filename: String,
+    pub transcript_filename: String,
+    /// Whether the audio was transcribed locally because the video has no
+    /// captions
+    pub transcribed_locally: bool,
+    pub indexed: bool,
+}
+
+/// The video id of a YouTube watch, short, embed or youtu.be link
+pub fn youtube_video_id(url: &str) -> Option<String> {
+    let url = url.trim();
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = host.trim_start_matches("www.").trim_start_matches("m.").trim_start_matches("music.");
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+
+    let id = match host {
+        "youtu.be" => path.split('/').next(),
+        "youtube.com" | "youtube-nocookie.com" => match path.split('/').collect::<Vec<_>>().as_slice() {
+            ["watch"] => query.split('&').find_map(|pair| pair.strip_prefix("v=")),
+            ["shorts" | "embed" | "live" | "v", id, ..] => Some(*id),
+            _ => None,
+        },
+        _ => None,
+    }?;
+    let id = id.split(['#', '&']).next().unwrap_or_default();
+    let valid = id.len() == 11 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: u64, text: &str) -> TranscriptSegment {
+        TranscriptSegment { start_ms, end_ms: start_ms + 1000, text: text.to_string() }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "00:00");
+        assert_eq!(format_timestamp(754_900), "12:34");
+        assert_eq!(format_timestamp(3_723_000), "1:02:03");
+    }
+
+    #[test]
+    fn test_group_segments() {
+        let segments = [segment(0, " Hello there."), segment(2_000, "  "), segment(4_000, "Welcome back."), segment(61_000, "Next topic.")];
+        let sections = group_segments(&segments, 30);
+        assert_eq!(sections, vec![(0, "Hello there. Welcome back.".to_string()), (61_000, "Next topic.".to_string())]);
+    }
+
+    #[test]
+    fn test_youtube_video_id() {
+        let id = Some("dQw4w9WgXcQ".to_string());
+        assert_eq!(youtube_video_id("https://www.youtube.com/watch?v=dQw4w9WgXcQ"), id);
+        assert_eq!(youtube_video_id("https://youtube.com/watch?list=PL1&v=dQw4w9WgXcQ&t=42"), id);
+        assert_eq!(youtube_video_id("https://youtu.be/dQw4w9WgXcQ?si=abc"), id);
+        assert_eq!(youtube_video_id("https://m.youtube.com/shorts/dQw4w9WgXcQ"), id);
+        assert_eq!(youtube_video_id("youtube.com/embed/dQw4w9WgXcQ"), id);
+        assert_eq!(youtube_video_id("https://www.youtube.com/channel/UCabc"), None);
+        assert_eq!(youtube_video_id("https://example.com/watch?v=dQw4w9WgXcQ"), None);
+    }
+
+    #[test]
+    fn test_transcript_markdown() {
+        let markdown = transcript_markdown("Episode 1", "episode.mp3", &[segment(90_000, "Intro")]);
+        assert!(markdown.starts_with("# Episode 1\n"));
+        assert!(markdown.contains("\n[01:30] Intro\n"));
+    }
+}
@@ -0,0 +1,115 @@
+//! Trash Model
+//!
+//! Deleted sessions, messages, context documents and assets, kept for
+//! `TRASH_RETENTION_DAYS` so they can be restored before they are purged.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Days an item stays in the trash before it is purged
+pub const TRASH_RETENTION_DAYS: i64 = 30;
+
+/// Kind of a deleted item
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashKind {
+    Session,
+    Message,
+    Document,
+    Asset,
+}
+
+impl TrashKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TrashKind::Session => "Chat",
+            TrashKind::Message => "Message",
+            TrashKind::Document => "Document",
+            TrashKind::Asset => "Asset",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            TrashKind::Session => "💬",
+            TrashKind::Message => "✉",
+            TrashKind::Document => "📄",
+            TrashKind::Asset => "🖼",
+        }
+    }
+}
+
+/// An item in the trash
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrashItem {
+    pub kind: TrashKind,
+    /// ID of the session, message, asset or trashed document
+    pub id: String,
+    pub title: String,
+    /// Where the item was, e.g. the chat a message belonged to
+    pub detail: Option<String>,
+    /// File kept on disk until the item is purged
+    pub path: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl TrashItem {
+    /// When the item is purged for good
+    pub fn purge_at(&self) -> DateTime<Utc> {
+        self.deleted_at + Duration::days(TRASH_RETENTION_DAYS)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.purge_at()
+    }
+
+    /// Whole days left before the item is purged, counting a started day
+    pub fn days_left(&self, now: DateTime<Utc>) -> i64 {
+        let left = self.purge_at() - now;
+        if left <= Duration::zero() {
+            return 0;
+        }
+        (left.num_seconds() + 86_399) / 86_400
+    }
+}
+
+/// Title of a deleted message: its first line, shortened
+pub fn message_excerpt(content: &str) -> String {
+    let line = content.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("(empty message)");
+    let mut excerpt: String = line.chars().take(80).collect();
+    if line.chars().count() > 80 {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retention() {
+        let deleted_at = DateTime::parse_from_rfc3339("2026-01-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let item = TrashItem {
+            kind: TrashKind::Session,
+            id: "a".into(),
+            title: "Old chat".into(),
+            detail: None,
+            path: None,
+            deleted_at,
+        };
+        assert_eq!(item.days_left(deleted_at), TRASH_RETENTION_DAYS);
+        assert_eq!(item.days_left(deleted_at + Duration::hours(1)), TRASH_RETENTION_DAYS);
+        assert_eq!(item.days_left(deleted_at + Duration::days(29) + Duration::hours(23)), 1);
+        assert!(!item.is_expired(deleted_at + Duration::days(29)));
+        assert!(item.is_expired(deleted_at + Duration::days(30)));
+        assert_eq!(item.days_left(deleted_at + Duration::days(31)), 0);
+    }
+
+    #[test]
+    fn test_message_excerpt() {
+        assert_eq!(message_excerpt("\n  Hello there\nSecond line"), "Hello there");
+        assert_eq!(message_excerpt(""), "(empty message)");
+        assert_eq!(message_excerpt(&"a".repeat(100)).chars().count(), 81);
+    }
+}
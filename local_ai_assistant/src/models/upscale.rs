@@ -0,0 +1,113 @@
+//! Image Upscaling Model
+//!
+//! Enlarged copies of generated images, saved next to the original.
+
+use serde::{Deserialize, Serialize};
+
+/// How much an image is enlarged
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpscaleFactor {
+    #[default]
+    X2,
+    X4,
+}
+
+impl UpscaleFactor {
+    pub fn all() -> &'static [UpscaleFactor] {
+        &[UpscaleFactor::X2, UpscaleFactor::X4]
+    }
+
+    pub fn scale(&self) -> u32 {
+        match self {
+            UpscaleFactor::X2 => 2,
+            UpscaleFactor::X4 => 4,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            UpscaleFactor::X2 => "2×",
+            UpscaleFactor::X4 => "4×",
+        }
+    }
+}
+
+/// Tool that produced an upscaled copy
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpscaleMethod {
+    /// Real-ESRGAN (`realesrgan-ncnn-vulkan`)
+    RealEsrgan,
+    /// Plain Lanczos resampling, used when Real-ESRGAN is not installed
+    Resample,
+}
+
+impl UpscaleMethod {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            UpscaleMethod::RealEsrgan => "Real-ESRGAN",
+            UpscaleMethod::Resample => "Lanczos resize",
+        }
+    }
+}
+
+/// An upscaled copy of an image
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UpscaledImage {
+    pub path: String,
+    pub factor: UpscaleFactor,
+    /// Whether faces were restored with GFPGAN before upscaling
+    pub face_fix: bool,
+    pub method: UpscaleMethod,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// File name of an upscaled copy, e.g. "header@2x.png" or
+/// "header@4x-faces.png"
+pub fn upscaled_file_name(stem: &str, factor: UpscaleFactor, face_fix: bool) -> String {
+    format!("{}@{}x{}.png", stem, factor.scale(), if face_fix { "-faces" } else { "" })
+}
+
+/// Adds a copy to a list, replacing an earlier one made the same way
+pub fn replace_upscaled(copies: &mut Vec<UpscaledImage>, copy: UpscaledImage) {
+    copies.retain(|c| (c.factor, c.face_fix) != (copy.factor, copy.face_fix));
+    copies.push(copy);
+    copies.sort_by_key(|c| (c.factor.scale(), c.face_fix));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copy(factor: UpscaleFactor, face_fix: bool, width: u32) -> UpscaledImage {
+        UpscaledImage {
+            path: upscaled_file_name("a", factor, face_fix),
+            factor,
+            face_fix,
+            method: UpscaleMethod::Resample,
+            width,
+            height: width,
+        }
+    }
+
+    #[test]
+    fn test_upscaled_file_name() {
+        assert_eq!(upscaled_file_name("header", UpscaleFactor::X2, false), "header@2x.png");
+        assert_eq!(upscaled_file_name("header", UpscaleFactor::X4, true), "header@4x-faces.png");
+    }
+
+    #[test]
+    fn test_replace_upscaled() {
+        let mut copies = vec![copy(UpscaleFactor::X4, false, 4096), copy(UpscaleFactor::X2, false, 2048)];
+        replace_upscaled(&mut copies, copy(UpscaleFactor::X4, false, 4000));
+        replace_upscaled(&mut copies, copy(UpscaleFactor::X2, true, 2048));
+        let made: Vec<_> = copies.iter().map(|c| (c.factor, c.face_fix, c.width)).collect();
+        assert_eq!(made, vec![
+            (UpscaleFactor::X2, false, 2048),
+            (UpscaleFactor::X2, true, 2048),
+            (UpscaleFactor::X4, false, 4000),
+        ]);
+    }
+}
@@ -0,0 +1,182 @@
+//! Video Preset Model
+//!
+//! One-click settings for common social video formats, and the frame sizes
+//! and lengths each video provider accepts.
+
+use serde::{Deserialize, Serialize};
+
+use super::video_gen::{VideoConfig, VideoProvider, VideoQuality};
+
+/// A social video format
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VideoPreset {
+    /// Landscape 1080p for YouTube
+    YouTube,
+    /// Portrait for TikTok, Reels and Shorts
+    Vertical,
+    Square,
+}
+
+impl VideoPreset {
+    pub fn all() -> &'static [VideoPreset] {
+        &[VideoPreset::YouTube, VideoPreset::Vertical, VideoPreset::Square]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            VideoPreset::YouTube => "YouTube 16:9 1080p",
+            VideoPreset::Vertical => "TikTok / Reels 9:16",
+            VideoPreset::Square => "Square 1:1",
+        }
+    }
+
+    pub fn config(&self) -> VideoConfig {
+        let (width, height, duration_seconds, quality) = match self {
+            VideoPreset::YouTube => (1920, 1080, 10, VideoQuality::Premium),
+            VideoPreset::Vertical => (1080, 1920, 10, VideoQuality::Premium),
+            VideoPreset::Square => (1080, 1080, 5, VideoQuality::HD),
+        };
+        VideoConfig { width, height, duration_seconds, fps: 30, quality, style: None }
+    }
+
+    /// The preset's settings, adjusted to the nearest size and length the
+    /// provider accepts
+    pub fn config_for(&self, provider: &VideoProvider) -> VideoConfig {
+        let mut config = self.config();
+        let limits = provider_limits(provider);
+        if let Some(sizes) = limits.sizes {
+            (config.width, config.height) = nearest_size(sizes, config.width, config.height);
+        }
+        if let Some(durations) = limits.durations {
+            config.duration_seconds = durations.iter().copied()
+                .min_by_key(|d| d.abs_diff(config.duration_seconds))
+                .unwrap_or(config.duration_seconds);
+        }
+        config
+    }
+
+    /// The preset whose shape the size has, if any
+    pub fn matching(width: u32, height: u32) -> Option<VideoPreset> {
+        VideoPreset::all().iter().copied().find(|p| {
+            let config = p.config();
+            same_shape((config.width, config.height), (width, height))
+        })
+    }
+}
+
+/// What a provider accepts; `None` means anything within the form's limits
+pub struct ProviderLimits {
+    /// Exact frame sizes, width × height
+    pub sizes: Option<&'static [(u32, u32)]>,
+    pub durations: Option<&'static [u32]>,
+}
+
+/// Frame sizes and lengths accepted by each provider
+pub fn provider_limits(provider: &VideoProvider) -> ProviderLimits {
+    match provider {
+        // Jimeng renders 1080p in these aspect ratios, 5 or 10 seconds long
+        VideoProvider::ByteDance => ProviderLimits {
+            sizes: Some(&[(1920, 1080), (1080, 1920), (1440, 1440), (1664, 1248), (1248, 1664), (2176, 928)]),
+            durations: Some(&[5, 10]),
+        },
+        // Tongyi Wanxiang's 720p and 480p sizes
+        VideoProvider::Alibaba => ProviderLimits {
+            sizes: Some(&[(1280, 720), (720, 1280), (960, 960), (1088, 832), (832, 1088), (832, 480), (480, 832), (624, 624)]),
+            durations: Some(&[5]),
+        },
+        _ => ProviderLimits { sizes: None, durations: None },
+    }
+}
+
+/// Checks the settings against what the provider accepts before they are
+/// submitted
+pub fn validate_video_config(provider: &VideoProvider, config: &VideoConfig) -> Result<(), String> {
+    if !(256..=2048).contains(&config.width.min(config.height)) || config.width.max(config.height) > 4096 {
+        return Err(format!("{}×{} is outside the supported range", config.width, config.height));
+    }
+    let limits = provider_limits(provider);
+    if let Some(sizes) = limits.sizes {
+        if !sizes.contains(&(config.width, config.height)) {
+            let (w, h) = nearest_size(sizes, config.width, config.height);
+            return Err(format!(
+                "{:?} does not support {}×{}; the closest size it supports is {}×{}",
+                provider, config.width, config.height, w, h
+            ));
+        }
+    }
+    if let Some(durations) = limits.durations {
+        if !durations.contains(&config.duration_seconds) {
+            let allowed: Vec<String> = durations.iter().map(|d| format!("{}s", d)).collect();
+            return Err(format!("{:?} videos can be {} long", provider, allowed.join(" or ")));
+        }
+    }
+    Ok(())
+}
+
+/// Aspect ratio name of a size, e.g. "16:9", or `None` for other shapes
+pub fn aspect_ratio_label(width: u32, height: u32) -> Option<&'static str> {
+    const RATIOS: &[((u32, u32), &str)] = &[
+        ((16, 9), "16:9"), ((9, 16), "9:16"), ((1, 1), "1:1"), ((4, 3), "4:3"), ((3, 4), "3:4"), ((21, 9), "21:9"),
+    ];
+    RATIOS.iter().find(|(ratio, _)| same_shape(*ratio, (width, height))).map(|(_, label)| *label)
+}
+
+/// Whether two sizes have about the same aspect ratio
+fn same_shape(a: (u32, u32), b: (u32, u32)) -> bool {
+    let (ra, rb) = (a.0 as f64 / a.1.max(1) as f64, b.0 as f64 / b.1.max(1) as f64);
+    (ra / rb - 1.0).abs() < 0.03
+}
+
+/// The size closest in shape, then in area
+fn nearest_size(sizes: &[(u32, u32)], width: u32, height: u32) -> (u32, u32) {
+    let ratio = (width as f64 / height.max(1) as f64).ln();
+    let area = width as f64 * height as f64;
+    sizes.iter().copied()
+        .min_by(|a, b| {
+            let key = |(w, h): (u32, u32)| {
+                let shape = ((w as f64 / h as f64).ln() - ratio).abs();
+                ((shape * 100.0).round(), (w as f64 * h as f64 - area).abs())
+            };
+            key(*a).partial_cmp(&key(*b)).unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .unwrap_or((width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_for_provider() {
+        let square = VideoPreset::Square.config_for(&VideoProvider::Alibaba);
+        assert_eq!((square.width, square.height, square.duration_seconds), (960, 960, 5));
+        let vertical = VideoPreset::Vertical.config_for(&VideoProvider::ByteDance);
+        assert_eq!((vertical.width, vertical.height, vertical.duration_seconds), (1080, 1920, 10));
+        let youtube = VideoPreset::YouTube.config_for(&VideoProvider::OpenRouter);
+        assert_eq!((youtube.width, youtube.height, youtube.fps), (1920, 1080, 30));
+
+        for preset in VideoPreset::all() {
+            for provider in [VideoProvider::ByteDance, VideoProvider::Alibaba, VideoProvider::Baidu] {
+                assert!(validate_video_config(&provider, &preset.config_for(&provider)).is_ok());
+            }
+        }
+        assert_eq!(VideoPreset::matching(720, 1280), Some(VideoPreset::Vertical));
+        assert_eq!(VideoPreset::matching(1024, 768), None);
+    }
+
+    #[test]
+    fn test_validate_video_config() {
+        let mut config = VideoPreset::YouTube.config();
+        let error = validate_video_config(&VideoProvider::Alibaba, &config).unwrap_err();
+        assert!(error.contains("closest size it supports is 1280×720"));
+        config.duration_seconds = 7;
+        assert!(validate_video_config(&VideoProvider::ByteDance, &config).unwrap_err().contains("5s or 10s"));
+        config.width = 100;
+        assert!(validate_video_config(&VideoProvider::OpenRouter, &config).is_err());
+
+        assert_eq!(aspect_ratio_label(1920, 1080), Some("16:9"));
+        assert_eq!(aspect_ratio_label(1024, 576), Some("16:9"));
+        assert_eq!(aspect_ratio_label(1000, 700), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! Video Timeline Model
+//!
+//! Video clips collected for a content package, in play order, and the
+//! single video assembled from them. Each video has a small JPEG thumbnail
+//! saved next to it.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::subtitles::SubtitleTrack;
+
+/// How long a title card is shown before its clip
+pub const TITLE_CARD_SECONDS: f32 = 3.0;
+
+/// Frame size and rate of the assembled video; clips are scaled and padded
+/// to fit
+pub const VIDEO_SIZE: (u32, u32) = (1280, 720);
+pub const VIDEO_FPS: u32 = 30;
+
+/// Width of video thumbnails; the height follows the aspect ratio
+pub const THUMBNAIL_WIDTH: u32 = 320;
+
+/// Longest line on a title card, in characters
+const TITLE_LINE_CHARS: usize = 32;
+
+/// A clip on a package's timeline
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VideoClip {
+    pub id: Uuid,
+    /// Clip file saved with the package
+    pub path: String,
+    /// Shown in the timeline, e.g. the prompt or original URL
+    pub label: String,
+    /// Text of a title card shown before the clip
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+}
+
+impl VideoClip {
+    pub fn new(path: String, label: String) -> Self {
+        Self { id: Uuid::new_v4(), path, label, title: None, thumbnail: None }
+    }
+}
+
+/// The clips joined into one video, with the narration as its soundtrack
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AssembledVideo {
+    pub path: String,
+    pub duration_ms: u32,
+    pub clip_count: usize,
+    pub with_narration: bool,
+    #[serde(default)]
+    pub subtitles: Option<SubtitleTrack>,
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+}
+
+/// Moves a clip to a new position, keeping the others in order
+pub fn move_clip(clips: &mut Vec<VideoClip>, from: usize, to: usize) {
+    if from >= clips.len() {
+        return;
+    }
+    let clip = clips.remove(from);
+    clips.insert(to.min(clips.len()), clip);
+}
+
+/// Breaks title card text into centred lines of at most
+/// `TITLE_LINE_CHARS`, keeping words whole
+pub fn wrap_title(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= TITLE_LINE_CHARS => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines.join("\n")
+}
+
+/// ffmpeg filter that scales a clip into the video frame, letterboxing
+/// when the aspect ratio differs
+pub fn fit_filter() -> String {
+    let (w, h) = VIDEO_SIZE;
+    format!(
+        "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:color=black,setsar=1,fps={fps},format=yuv420p",
+        w = w,
+        h = h,
+        fps = VIDEO_FPS,
+    )
+}
+
+/// Where in a video its thumbnail frame is taken, in seconds: a little way
+/// in, past fades from black, but never beyond the first few seconds
+pub fn thumbnail_time(duration_seconds: f64) -> f64 {
+    (duration_seconds * 0.1).min(3.0)
+}
+
+/// ffmpeg concat demuxer list for the given files
+pub fn concat_list(files: &[String]) -> String {
+    files.iter().map(|f| format!("file '{}'\n", f.replace('\'', "'\\''"))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clips(labels: &[&str]) -> Vec<VideoClip> {
+        labels.iter().map(|l| VideoClip::new(format!("/clips/{}.mp4", l), l.to_string())).collect()
+    }
+
+    #[test]
+    fn test_move_clip() {
+        let mut timeline = clips(&["a", "b", "c"]);
+        move_clip(&mut timeline, 0, 2);
+        move_clip(&mut timeline, 2, 9);
+        move_clip(&mut timeline, 5, 0);
+        let order: Vec<&str> = timeline.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(order, vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_wrap_title() {
+        assert_eq!(wrap_title("  Part one  "), "Part one");
+        assert_eq!(
+            wrap_title("How local models change the way we write software"),
+            "How local models change the way\nwe write software"
+        );
+    }
+
+    #[test]
+    fn test_concat_list() {
+        let list = concat_list(&["part-0.mp4".to_string(), "it's.mp4".to_string()]);
+        assert_eq!(list, "file 'part-0.mp4'\nfile 'it'\\''s.mp4'\n");
+        assert!(fit_filter().starts_with("scale=1280:720:force_original_aspect_ratio=decrease,pad=1280:720"));
+    }
+
+    #[test]
+    fn test_thumbnail_time() {
+        assert_eq!(thumbnail_time(5.0), 0.5);
+        assert_eq!(thumbnail_time(120.0), 3.0);
+    }
+}
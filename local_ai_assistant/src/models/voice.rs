@@ -0,0 +1,117 @@
+//! Voice Conversation Model
+//!
+//! Turns of a hands-free conversation, and how a streamed reply is cut into
+//! sentences so speech can start before the reply is complete.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{speakable_text, split_sentences};
+
+/// Longest recording accepted for one utterance
+pub const MAX_UTTERANCE_BYTES: usize = 10 * 1024 * 1024;
+
+/// How speech is captured
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CaptureMode {
+    /// Records while the talk button is held
+    #[default]
+    PushToTalk,
+    /// Records whenever speech is detected, stopping after a pause
+    VoiceActivity,
+}
+
+impl CaptureMode {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CaptureMode::PushToTalk => "Push to talk",
+            CaptureMode::VoiceActivity => "Hands-free",
+        }
+    }
+}
+
+/// One side of the conversation, as shown in the transcript
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VoiceTurn {
+    pub id: Uuid,
+    /// Whether the user said it, rather than the assistant
+    pub from_user: bool,
+    pub text: String,
+}
+
+impl VoiceTurn {
+    pub fn user(text: String) -> Self {
+        Self { id: Uuid::new_v4(), from_user: true, text }
+    }
+
+    pub fn assistant() -> Self {
+        Self { id: Uuid::new_v4(), from_user: false, text: String::new() }
+    }
+}
+
+/// The prompt for a spoken question; the first turn asks for replies that
+/// read well aloud
+pub fn voice_prompt(utterance: &str, first_turn: bool) -> String {
+    if first_turn {
+        format!(
+            "We are talking by voice. Answer in a few short spoken sentences, without Markdown, lists or code.\n\n{}",
+            utterance.trim()
+        )
+    } else {
+        utterance.trim().to_string()
+    }
+}
+
+/// Takes the complete sentences off the front of a streamed reply, leaving
+/// the unfinished one in `buffer`. Code blocks are not spoken.
+pub fn take_sentences(buffer: &mut String) -> Vec<String> {
+    let end = buffer.char_indices()
+        .zip(buffer.chars().skip(1).map(Some).chain([None]))
+        .filter(|((_, c), next)| match c {
+            '。' | '！' | '？' | '\n' => true,
+            '.' | '!' | '?' => next.is_some_and(|n| n.is_whitespace()),
+            _ => false,
+        })
+        .map(|((i, c), _)| i + c.len_utf8())
+        .last();
+    let Some(end) = end else {
+        return Vec::new();
+    };
+    // Wait for a code block to close rather than speak half of it
+    if buffer[..end].matches("```").count() % 2 == 1 {
+        return Vec::new();
+    }
+    let complete: String = buffer.drain(..end).collect();
+    split_sentences(&speakable_text(&complete))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_sentences() {
+        let mut buffer = "Sure. The answer is 4".to_string();
+        assert_eq!(take_sentences(&mut buffer), vec!["Sure."]);
+        assert_eq!(buffer, " The answer is 4");
+
+        buffer.push_str(".5 today! And");
+        assert_eq!(take_sentences(&mut buffer), vec!["The answer is 4.5 today!"]);
+        assert_eq!(buffer, " And");
+    }
+
+    #[test]
+    fn test_take_sentences_waits_for_code_blocks() {
+        let mut buffer = "Run this:\n```\nls -la.\n".to_string();
+        assert!(take_sentences(&mut buffer).is_empty());
+        buffer.push_str("```\nDone.\n");
+        assert_eq!(take_sentences(&mut buffer), vec!["Run this:", "Done."]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_voice_prompt() {
+        assert!(voice_prompt(" Hi ", true).ends_with("\n\nHi"));
+        assert_eq!(voice_prompt(" Hi ", false), "Hi");
+    }
+}
@@ -0,0 +1,108 @@
+//! Web Search Models
+//!
+//! Configuration for the optional web search integration and the results
+//! it returns for grounding chat answers.
+
+use serde::{Deserialize, Serialize};
+
+/// Search backend
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebSearchProvider {
+    #[default]
+    Disabled,
+    /// Self-hosted SearxNG instance (JSON format must be enabled)
+    Searxng,
+    /// Brave Search API
+    Brave,
+}
+
+impl WebSearchProvider {
+    pub fn all() -> &'static [WebSearchProvider] {
+        &[WebSearchProvider::Disabled, WebSearchProvider::Searxng, WebSearchProvider::Brave]
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            WebSearchProvider::Disabled => "disabled",
+            WebSearchProvider::Searxng => "searxng",
+            WebSearchProvider::Brave => "brave",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Self {
+        Self::all().iter().copied().find(|p| p.id() == id).unwrap_or_default()
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            WebSearchProvider::Disabled => "Disabled",
+            WebSearchProvider::Searxng => "SearxNG",
+            WebSearchProvider::Brave => "Brave Search API",
+        }
+    }
+}
+
+/// Web search configuration
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WebSearchConfig {
+    pub provider: WebSearchProvider,
+    /// Base URL of the SearxNG instance, e.g. http://localhost:8888
+    #[serde(default)]
+    pub searxng_url: String,
+    #[serde(default)]
+    pub brave_api_key: String,
+}
+
+impl WebSearchConfig {
+    /// Whether the selected provider has what it needs to run
+    pub fn is_configured(&self) -> bool {
+        match self.provider {
+            WebSearchProvider::Disabled => false,
+            WebSearchProvider::Searxng => !self.searxng_url.trim().is_empty(),
+            WebSearchProvider::Brave => !self.brave_api_key.trim().is_empty(),
+        }
+    }
+}
+
+/// A single search hit
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WebSearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+/// Formats results as numbered sources for the prompt
+pub fn format_web_results(results: &[WebSearchResult]) -> String {
+    results.iter()
+        .enumerate()
+        .map(|(i, r)| format!("[{}] {}\n{}\n{}", i + 1, r.title, r.url, r.snippet))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Markdown list of sources matching the citation numbers
+pub fn format_web_sources(results: &[WebSearchResult]) -> String {
+    results.iter()
+        .enumerate()
+        .map(|(i, r)| format!("{}. [{}]({})", i + 1, r.title, r.url))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_citations() {
+        let results = vec![WebSearchResult {
+            title: "Rust".into(),
+            url: "https://rust-lang.org".into(),
+            snippet: "A language".into(),
+        }];
+        assert!(format_web_results(&results).starts_with("[1] Rust\nhttps://rust-lang.org"));
+        assert_eq!(format_web_sources(&results), "1. [Rust](https://rust-lang.org)");
+    }
+}
@@ -0,0 +1,76 @@
+//! Outgoing Webhook Model
+//!
+//! User-configured URLs that receive a JSON POST when generation jobs finish,
+//! for local automation tools such as n8n.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Events a webhook can subscribe to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A video generation job finished
+    VideoCompleted,
+    /// A content package was exported from the content editor
+    ContentExported,
+    /// A scheduled job completed
+    ScheduledJobCompleted,
+}
+
+impl WebhookEvent {
+    pub fn all() -> &'static [WebhookEvent] {
+        &[
+            WebhookEvent::VideoCompleted,
+            WebhookEvent::ContentExported,
+            WebhookEvent::ScheduledJobCompleted,
+        ]
+    }
+
+    /// Identifier sent in the payload's `event` field
+    pub fn id(&self) -> &'static str {
+        match self {
+            WebhookEvent::VideoCompleted => "video.completed",
+            WebhookEvent::ContentExported => "content.exported",
+            WebhookEvent::ScheduledJobCompleted => "job.completed",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            WebhookEvent::VideoCompleted => "Video job finished",
+            WebhookEvent::ContentExported => "Content package exported",
+            WebhookEvent::ScheduledJobCompleted => "Scheduled job completed",
+        }
+    }
+}
+
+/// A configured webhook endpoint
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub name: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+    /// Shared secret for the `X-IDoris-Signature` HMAC-SHA256 header
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+impl Webhook {
+    pub fn new(name: String, url: String, events: Vec<WebhookEvent>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            url,
+            events,
+            enabled: true,
+            secret: None,
+        }
+    }
+
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        self.enabled && self.events.contains(&event)
+    }
+}
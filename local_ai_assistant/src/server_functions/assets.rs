@@ -0,0 +1,57 @@
+//! Asset Library Server Functions
+//!
+//! Browse, tag and reuse generated images, audio and video.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{Asset, AssetFilter, ChatAttachment};
+
+#[cfg(feature = "server")]
+fn parse_asset_id(asset_id: &str) -> Result<uuid::Uuid, ServerFnError> {
+    uuid::Uuid::parse_str(asset_id).map_err(|_| app_error("Invalid asset ID"))
+}
+
+/// Library entries matching the filter, newest first
+#[server]
+pub async fn get_assets(filter: AssetFilter) -> Result<Vec<Asset>, ServerFnError> {
+    crate::core::assets::list(&filter).await.map_err(app_error)
+}
+
+/// Small preview image of an asset as a data URL; `None` for audio
+#[server]
+pub async fn get_asset_preview(asset_id: String) -> Result<Option<String>, ServerFnError> {
+    crate::core::assets::preview(parse_asset_id(&asset_id)?).await.map_err(app_error)
+}
+
+/// The asset's file as a data URL, for playback
+#[server]
+pub async fn get_asset_media(asset_id: String) -> Result<String, ServerFnError> {
+    crate::core::assets::media(parse_asset_id(&asset_id)?).await.map_err(app_error)
+}
+
+/// Markdown that inserts the asset into an article
+#[server]
+pub async fn get_asset_snippet(asset_id: String) -> Result<String, ServerFnError> {
+    crate::core::assets::article_snippet(parse_asset_id(&asset_id)?).await.map_err(app_error)
+}
+
+/// The asset as an attachment for the next chat message
+#[server]
+pub async fn get_asset_attachment(asset_id: String) -> Result<ChatAttachment, ServerFnError> {
+    crate::core::assets::attachment(parse_asset_id(&asset_id)?).await.map_err(app_error)
+}
+
+/// Replaces an asset's tags with the comma-separated list and returns them
+#[server]
+pub async fn set_asset_tags(asset_id: String, tags: String) -> Result<Vec<String>, ServerFnError> {
+    let tags = crate::models::parse_document_tags(&tags);
+    crate::core::assets::set_tags(parse_asset_id(&asset_id)?, &tags).await.map_err(app_error)?;
+    Ok(tags)
+}
+
+/// Deletes the asset's file and removes it from the library
+#[server]
+pub async fn delete_asset(asset_id: String) -> Result<(), ServerFnError> {
+    crate::core::assets::delete(parse_asset_id(&asset_id)?).await.map_err(app_error)
+}
@@ -0,0 +1,34 @@
+//! Chat Attachment Server Functions
+//!
+//! Prepare files dropped onto the chat and add them to the knowledge base,
+//! and upload files dropped onto the Context tab.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{CapturedDocument, ChatAttachment};
+
+/// Extracts a file's text, summarizing it when it is too long to inline
+#[server]
+pub async fn prepare_chat_attachment(name: String, data: Vec<u8>) -> Result<ChatAttachment, ServerFnError> {
+    crate::core::attachments::prepare(&name, data)
+        .await
+        .map_err(|e| app_error(&format!("Attachment failed: {}", e)))
+}
+
+/// Files an attachment's full text into the context folder and index
+#[server]
+pub async fn ingest_chat_attachment(attachment_id: String, name: String) -> Result<CapturedDocument, ServerFnError> {
+    let id = uuid::Uuid::parse_str(&attachment_id).map_err(|_| app_error("Invalid attachment ID"))?;
+    crate::core::attachments::ingest(id, &name)
+        .await
+        .map_err(|e| app_error(&format!("Adding to knowledge base failed: {}", e)))
+}
+
+/// Extracts a file's text and adds it to the context folder and index
+#[server]
+pub async fn upload_context_file(name: String, data: Vec<u8>) -> Result<CapturedDocument, ServerFnError> {
+    crate::core::attachments::upload(&name, data)
+        .await
+        .map_err(|e| app_error(&format!("Upload failed: {}", e)))
+}
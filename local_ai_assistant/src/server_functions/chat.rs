@@ -4,8 +4,10 @@
 //! It leverages Dioxus server functions to bridge client-server communication.
 
 use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
 use dioxus::fullstack::TextStream;
-use crate::models::{ModelInfo, ModelStatus};
+use crate::models::{ModelInfo, ModelStatus, CodeReference, QueueStatus, RetrievalOptions, ToolRun};
 
 /// Initializes the language model for chat functionality.
 ///
@@ -20,7 +22,7 @@ pub async fn init_llm_model() -> Result<(), ServerFnError> {
     {
         use crate::core::llm::init_chat_model;
         init_chat_model().await.map_err(|e| {
-            ServerFnError::new(&format!("Error initializing model: {}", e))
+            app_error(&format!("Error initializing model: {}", e))
         })
     }
     #[cfg(not(feature = "server"))]
@@ -42,7 +44,7 @@ pub async fn init_embedding_model() -> Result<(), ServerFnError> {
     {
         use crate::core::embedding::init_embedding_model as init_embed;
         init_embed().await.map_err(|e| {
-            ServerFnError::new(&format!("Error initializing embedding model: {}", e))
+            app_error(&format!("Error initializing embedding model: {}", e))
         })
     }
     #[cfg(not(feature = "server"))]
@@ -68,9 +70,9 @@ pub async fn get_embedding(txt: String) -> Result<Vec<f32>, ServerFnError> {
             futures::executor::block_on(crate::core::embedding::embed_text(&txt))
         })
             .await
-            .map_err(|e| ServerFnError::new(&e.to_string()))?;
+            .map_err(|e| app_error(&e.to_string()))?;
 
-        result.map_err(|e| ServerFnError::new(&format!("Error embedding text: {}", e)))
+        result.map_err(|e| app_error(&format!("Error embedding text: {}", e)))
     }
     #[cfg(not(feature = "server"))]
     {
@@ -78,22 +80,61 @@ pub async fn get_embedding(txt: String) -> Result<Vec<f32>, ServerFnError> {
     }
 }
 
-/// Resets the current chat session.
+/// Resets a conversation's chat.
 ///
-/// Clears conversation history and resets the chat model's state.
+/// The session's next turn starts without its earlier messages; other
+/// sessions keep their context.
+///
+/// # Arguments
+///
+/// * `session_id` - The conversation to reset
 ///
 /// # Returns
 ///
 /// * `Result<()>` - Success or error with detailed message
 #[server]
-pub async fn reset_chat() -> Result<(), ServerFnError> {
+pub async fn reset_chat(session_id: String) -> Result<(), ServerFnError> {
     #[cfg(feature = "server")]
     {
-        use crate::core::llm::reset_chat as do_reset;
-        do_reset().await.map_err(|e| ServerFnError::new(&format!("Error trying to reset chat: {}", e)))
+        let session_id = uuid::Uuid::parse_str(&session_id).map_err(|e| app_error(&e.to_string()))?;
+        let overrides = crate::storage::database::get_session_overrides(session_id).await.unwrap_or_default();
+        crate::core::llm::reset_session_chat(session_id, &overrides)
+            .map_err(|e| app_error(&format!("Error trying to reset chat: {}", e)))
     }
     #[cfg(not(feature = "server"))]
     {
+        let _ = session_id;
+        Ok(())
+    }
+}
+
+/// Rebuilds a conversation's chat from its stored messages when the session
+/// is opened, so its first turn continues with the session's own context.
+/// Does nothing until the model is loaded.
+///
+/// # Arguments
+///
+/// * `session_id` - The conversation being opened
+#[server]
+pub async fn open_chat_session(session_id: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::core::llm;
+        use crate::storage::database;
+
+        let session_id = uuid::Uuid::parse_str(&session_id).map_err(|e| app_error(&e.to_string()))?;
+        if !llm::is_initialized() || llm::has_session_chat(session_id) {
+            return Ok(());
+        }
+        let history = database::get_session_messages(session_id).await.map_err(app_error)?;
+        let overrides = database::get_session_overrides(session_id).await.unwrap_or_default();
+        llm::open_session_chat(session_id, &history, &overrides)
+            .await
+            .map_err(|e| app_error(&format!("Error opening conversation: {}", e)))
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        let _ = session_id;
         Ok(())
     }
 }
@@ -106,34 +147,115 @@ pub async fn reset_chat() -> Result<(), ServerFnError> {
 /// # Arguments
 ///
 /// * `prompt` - The user's input text
+/// * `session_id` - The conversation the prompt belongs to; its chat and KV
+///   cache are reused across turns, and its overrides pick the model,
+///   persona and temperature. Empty answers without any history.
 ///
 /// # Returns
 ///
 /// * `Result<TextStream>` - Stream of response tokens or error
-#[get("/api/get_response?prompt")]
-pub async fn get_response(prompt: String) -> Result<TextStream> {
+#[get("/api/get_response?prompt&session_id")]
+pub async fn get_response(prompt: String, session_id: String) -> Result<TextStream> {
     use crate::core::llm;
 
     // Check if the model is initialized
     if !llm::is_initialized() {
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Model not initialized"
-        ).into());
+        return Err(super::stream_error(crate::models::AppError::model_not_ready("Model not initialized")).into());
     }
 
     let time = std::time::Instant::now();
-    println!("Processing prompt: {}", prompt);
+    tracing::debug!("Processing prompt: {}", prompt);
 
     // Try to get a stream (now returns an UnboundedReceiver which is a Stream)
-    let rx = llm::try_get_stream(&prompt).map_err(|e| {
-        std::io::Error::new(std::io::ErrorKind::Other, e)
-    })?;
+    let rx = match uuid::Uuid::parse_str(&session_id) {
+        Ok(session_id) => {
+            // History is only needed to rebuild a chat that is not cached
+            let history = if llm::has_session_chat(session_id) {
+                Vec::new()
+            } else {
+                crate::storage::database::get_session_messages(session_id).await.unwrap_or_default()
+            };
+            let overrides = crate::storage::database::get_session_overrides(session_id).await.unwrap_or_default();
+            llm::try_get_session_stream(session_id, &prompt, &history, &overrides).await
+        }
+        Err(_) => llm::try_get_stream(&prompt),
+    }
+    .map_err(super::stream_error)?;
 
-    println!("\nTotal response time: {:?}", time.elapsed());
+    tracing::info!("Total response time: {:?}", time.elapsed());
     Ok(TextStream::new(rx))
 }
 
+/// Checks whether the last response streamed in a conversation stopped at
+/// the token cap, so the UI can offer to continue it.
+///
+/// # Arguments
+///
+/// * `session_id` - The conversation passed to `get_response`
+///
+/// # Returns
+///
+/// * `Result<bool>` - True if the response was cut off
+#[server]
+pub async fn response_was_truncated(session_id: String) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let session_id = uuid::Uuid::parse_str(&session_id).map_err(|e| app_error(&e.to_string()))?;
+        Ok(crate::core::llm::was_truncated(session_id))
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        let _ = session_id;
+        Ok(false)
+    }
+}
+
+/// Returns the generation running on the model and the requests waiting
+/// for it, in the order they will run
+#[server]
+pub async fn get_llm_queue() -> Result<QueueStatus, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        Ok(crate::core::llm_queue::status())
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        Ok(QueueStatus::default())
+    }
+}
+
+/// Answers a prompt, letting the model call the available server-side tools.
+///
+/// # Arguments
+///
+/// * `prompt` - The user's input text
+/// * `allow_code_execution` - Whether the session opted in to the code sandbox
+///
+/// # Returns
+///
+/// * `Result<ToolRun>` - The final answer and the tool calls made
+#[server]
+pub async fn get_tool_response(prompt: String, allow_code_execution: bool) -> Result<ToolRun, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::core::{llm, tools::Tool};
+
+        if !llm::is_initialized() {
+            return Err(app_error(crate::models::AppError::model_not_ready("Model not initialized")));
+        }
+
+        let tools = Tool::available(allow_code_execution);
+        llm::generate_with_tools(prompt, &tools).await.map_err(|e| {
+            tracing::error!("Tool-assisted generation failed: {}", e);
+            app_error(&format!("Error generating response: {}", e))
+        })
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        Ok(ToolRun::default())
+    }
+}
+
 /// Searches the database for relevant context given a query.
 ///
 /// Retrieves documents that match the query from the database.
@@ -142,25 +264,34 @@ pub async fn get_response(prompt: String) -> Result<TextStream> {
 /// # Arguments
 ///
 /// * `q` - The search query
+/// * `options` - Collection, result count and score threshold for this query
+/// * `history` - Earlier messages as "role: text", condensed with the query
+///   so follow-up questions retrieve what they refer to
 ///
 /// # Returns
 ///
-/// * `Result<String>` - Formatted context string with relevance scores or error
+/// * `Result<(String, Vec<CodeReference>)>` - Formatted context string with
+///   relevance scores, and links to the retrieved code chunks, or error
 #[server]
-pub async fn search_context(q: String) -> Result<String, ServerFnError> {
+pub async fn search_context(q: String, options: RetrievalOptions, history: Vec<String>) -> Result<(String, Vec<CodeReference>), ServerFnError> {
     #[cfg(feature = "server")]
     {
-        println!("Searching context for query: {}", q);
-        let documents = crate::core::vector_store::query(&q).await.map_err(|e| {
-            println!("Error querying database: {}", e);
-            ServerFnError::new(&format!("Error querying database: {}", e))
+        tracing::info!("Searching context for query: {}", q);
+        let settings = crate::core::profile::load_settings();
+        let query = crate::core::query_expansion::expand(&q, &history, settings.query_expansion, settings.conversational_retrieval).await;
+        let documents = crate::core::vector_store::query_with(&query, &options).await.map_err(|e| {
+            tracing::error!("Error querying database: {}", e);
+            app_error(&format!("Error querying database: {}", e))
         })?;
 
         if documents.is_empty() {
-            println!("No relevant documents found for query");
-            return Ok(String::new());
+            tracing::info!("No relevant documents found for query");
+            return Ok((String::new(), Vec::new()));
         }
 
+        let titles: Vec<String> = documents.iter().map(|d| d.title.clone()).collect();
+        let references = crate::core::codebase::references_for(&titles).await;
+
         // Format with reference numbers and relevance scores
         let context = documents.into_iter()
             .enumerate()
@@ -176,12 +307,12 @@ pub async fn search_context(q: String) -> Result<String, ServerFnError> {
             .collect::<Vec<_>>()
             .join("\n---\n");
 
-        println!("Found {} relevant documents for RAG", context.matches("[Reference").count());
-        Ok(context)
+        tracing::info!("Found {} relevant documents for RAG", context.matches("[Reference").count());
+        Ok((context, references))
     }
     #[cfg(not(feature = "server"))]
     {
-        Ok(String::new())
+        Ok((String::new(), Vec::new()))
     }
 }
 
@@ -199,8 +330,8 @@ pub async fn init_db() -> Result<(), ServerFnError> {
         crate::core::vector_store::connect_to_database()
             .await
             .map_err(|e| {
-                eprintln!("Error: {:?}", e);
-                ServerFnError::new(e)
+                tracing::error!("Error: {:?}", e);
+                app_error(e)
             })?;
         Ok(())
     }
@@ -224,8 +355,8 @@ pub async fn init_sqlite_db() -> Result<(), ServerFnError> {
         crate::storage::database::init()
             .await
             .map_err(|e| {
-                eprintln!("Error initializing SQLite: {:?}", e);
-                ServerFnError::new(&format!("SQLite init error: {}", e))
+                tracing::error!("Error initializing SQLite: {:?}", e);
+                app_error(&format!("SQLite init error: {}", e))
             })?;
         Ok(())
     }
@@ -269,7 +400,7 @@ pub async fn get_current_model() -> Result<ModelInfo, ServerFnError> {
                 m.status = ModelStatus::Active;
                 m
             })
-            .ok_or_else(|| ServerFnError::new("Current model not found in available models"))
+            .ok_or_else(|| app_error("Current model not found in available models"))
     }
     #[cfg(not(feature = "server"))]
     {
@@ -301,11 +432,11 @@ pub async fn switch_llm_model(model_id: String) -> Result<(), ServerFnError> {
     {
         use crate::core::llm::switch_model;
 
-        println!("Switching to model: {}", model_id);
+        tracing::info!("Switching to model: {}", model_id);
 
         switch_model(&model_id).await.map_err(|e| {
-            eprintln!("Error switching model: {}", e);
-            ServerFnError::new(&format!("Error switching model: {}", e))
+            tracing::error!("Error switching model: {}", e);
+            app_error(&format!("Error switching model: {}", e))
         })
     }
     #[cfg(not(feature = "server"))]
@@ -0,0 +1,41 @@
+//! Codebase Server Functions
+//!
+//! Add, reindex and remove source repositories in the knowledge base.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use uuid::Uuid;
+use crate::models::Codebase;
+
+/// Indexes a local repository; `name` defaults to the folder name
+#[server]
+pub async fn add_codebase(path: String, name: Option<String>) -> Result<Codebase, ServerFnError> {
+    crate::core::codebase::add(&path, name.as_deref())
+        .await
+        .map_err(|e| app_error(&e))
+}
+
+/// Codebases of the active profile
+#[server]
+pub async fn list_codebases() -> Result<Vec<Codebase>, ServerFnError> {
+    crate::core::codebase::list()
+        .await
+        .map_err(|e| app_error(&e))
+}
+
+/// Indexes a codebase again after its files changed
+#[server]
+pub async fn reindex_codebase(id: Uuid) -> Result<Codebase, ServerFnError> {
+    crate::core::codebase::reindex(id)
+        .await
+        .map_err(|e| app_error(&e))
+}
+
+/// Removes a codebase and its chunks from the knowledge base
+#[server]
+pub async fn remove_codebase(id: Uuid) -> Result<(), ServerFnError> {
+    crate::core::codebase::remove(id)
+        .await
+        .map_err(|e| app_error(&e))
+}
@@ -0,0 +1,37 @@
+//! Model Comparison Server Functions
+//!
+//! Stream one side of a side-by-side comparison and keep the results.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use dioxus::fullstack::TextStream;
+use crate::models::Comparison;
+
+/// Streams the answer of one model at one temperature to a prompt, in a
+/// fresh chat.
+///
+/// Dropping the stream on the client stops generation.
+#[post("/api/compare_stream")]
+pub async fn get_compare_stream(model_id: String, temperature: f32, prompt: String) -> Result<TextStream> {
+    let rx = crate::core::llm::stream_with_model(&model_id, prompt, temperature)
+        .await
+        .map_err(super::stream_error)?;
+    Ok(TextStream::new(rx))
+}
+
+/// Saves a comparison, or updates it after a vote
+#[server]
+pub async fn save_comparison(comparison: Comparison) -> Result<(), ServerFnError> {
+    crate::storage::database::save_comparison(&comparison)
+        .await
+        .map_err(|e| app_error(&format!("Failed to save comparison: {}", e)))
+}
+
+/// Returns all comparisons, newest first
+#[server]
+pub async fn get_comparisons() -> Result<Vec<Comparison>, ServerFnError> {
+    crate::storage::database::get_comparisons()
+        .await
+        .map_err(|e| app_error(&format!("Failed to load comparisons: {}", e)))
+}
@@ -1,9 +1,13 @@
 //! Content Workflow Server Functions
 //!
 //! Server functions for Phase 2.4 Content Workflow.
-//! Handles RSS fetching, article extraction, and content generation.
+//! Handles RSS fetching, article extraction, YouTube ingestion, and content generation.
 
 use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use dioxus::fullstack::TextStream;
+use crate::models::{ContentPackage, ReadabilityRewrite, SectionReview, SimilarityReport, VideoTranscript};
 
 
 /// Fetch RSS feed entries
@@ -16,14 +20,14 @@ pub async fn fetch_rss_entries(url: String) -> Result<Vec<(String, String, Strin
 
         let entries = fetch_rss_feed(&url)
             .await
-            .map_err(|e| ServerFnError::new(e))?;
+            .map_err(|e| app_error(e))?;
 
         Ok(entries.into_iter().map(|e| {
             (e.title, e.url, e.summary.unwrap_or_default())
         }).collect())
     }
     #[cfg(not(feature = "server"))]
-    Err(ServerFnError::new("Not available on client"))
+    Err(app_error("Not available on client"))
 }
 
 /// Extract article content from a URL
@@ -36,27 +40,53 @@ pub async fn extract_article_content(url: String) -> Result<(String, String), Se
 
         let article = extract_article(&url)
             .await
-            .map_err(|e| ServerFnError::new(e))?;
+            .map_err(|e| app_error(e))?;
 
         Ok((article.title, article.content))
     }
     #[cfg(not(feature = "server"))]
-    Err(ServerFnError::new("Not available on client"))
+    Err(app_error("Not available on client"))
 }
 
-/// Generate an article outline based on title and template
-/// Returns a list of (section_title, section_prompt) tuples
+/// Add a YouTube video to the knowledge base as a summary and a full
+/// transcript, transcribing the audio locally if it has no captions
 #[server]
-pub async fn generate_outline(
-    title: String,
-    template_name: String,
-) -> Result<Vec<(String, String)>, ServerFnError> {
-    #[cfg(feature = "server")]
-    {
-        use crate::core::llm::get_llm_response;
+pub async fn ingest_youtube_video(url: String, collection: Option<String>) -> Result<VideoTranscript, ServerFnError> {
+    crate::core::youtube::ingest(&url, collection)
+        .await
+        .map_err(|e| app_error(&format!("Adding the video failed: {}", e)))
+}
 
-        let prompt = format!(
-            r#"Generate an article outline for: "{}"
+/// Streams an article outline for the title and template.
+///
+/// The text uses "## Section Title" headings followed by a description, so
+/// the editor can parse sections with `parse_outline` as tokens arrive. The
+/// outline is generated with JSON-constrained decoding and sent section by
+/// section; if that is unavailable, free text is streamed instead.
+#[post("/api/generate_outline")]
+pub async fn generate_outline(title: String, template_name: String) -> Result<TextStream> {
+    use crate::core::content_generator::StructuredOutline;
+    use crate::core::llm;
+
+    let request = format!(
+        "Generate an article outline for: \"{}\"\n\nTemplate style: {}\n\n\
+Create 4-6 sections with clear titles. For each section, provide a brief description of what should be covered.",
+        title, template_name
+    );
+    match llm::generate_structured::<StructuredOutline>(request, None, OUTLINE_MAX_LENGTH).await {
+        Ok(outline) => {
+            // Same text format as the streamed fallback, one section per chunk
+            let (tx, rx) = futures::channel::mpsc::unbounded();
+            for (section, description) in outline.into_sections() {
+                let _ = tx.unbounded_send(format!("## {}\n{}\n\n", section, description));
+            }
+            return Ok(TextStream::new(rx));
+        }
+        Err(e) => tracing::warn!("Falling back to a streamed text outline: {}", e),
+    }
+
+    let prompt = format!(
+        r#"Generate an article outline for: "{}"
 
 Template style: {}
 
@@ -72,44 +102,98 @@ Brief description of what this section should cover.
 (Continue for all sections)
 
 Only output the sections, no introduction or conclusion about the outline itself."#,
-            title, template_name
-        );
+        title, template_name
+    );
 
-        let response = get_llm_response(prompt, None)
-            .await
-            .map_err(|e| ServerFnError::new(format!("LLM error: {:?}", e)))?;
-
-        // Parse the response into sections
-        let sections = parse_outline_response(&response);
-
-        if sections.is_empty() {
-            // Fallback to default sections
-            Ok(vec![
-                ("Introduction".to_string(), "Write an engaging introduction".to_string()),
-                ("Background".to_string(), "Provide context and background".to_string()),
-                ("Main Content".to_string(), "Elaborate on the main topic".to_string()),
-                ("Conclusion".to_string(), "Summarize key points".to_string()),
-            ])
-        } else {
-            Ok(sections)
-        }
-    }
-    #[cfg(not(feature = "server"))]
-    Err(ServerFnError::new("Not available on client"))
+    let rx = llm::stream_standalone(prompt, None, 0.7, OUTLINE_MAX_LENGTH)
+        .map_err(super::stream_error)?;
+    Ok(TextStream::new(rx))
 }
 
-/// Expand a section with AI-generated content
-#[server]
-pub async fn expand_section(
-    section_title: String,
-    context: String,
-) -> Result<String, ServerFnError> {
-    #[cfg(feature = "server")]
-    {
-        use crate::core::llm::get_llm_response;
+/// Streams AI-generated content for a section.
+///
+/// Dropping the stream on the client stops generation.
+#[post("/api/expand_section")]
+pub async fn expand_section(section_title: String, context: String) -> Result<TextStream> {
+    use crate::core::llm;
 
-        let prompt = format!(
-            r#"Write content for the section "{}" in an article titled "{}".
+    let rx = llm::stream_standalone(section_prompt(&section_title, &context), None, 0.7, SECTION_MAX_LENGTH)
+        .map_err(super::stream_error)?;
+    Ok(TextStream::new(rx))
+}
+
+/// Token budget for an outline
+#[cfg(feature = "server")]
+const OUTLINE_MAX_LENGTH: u32 = 600;
+
+/// Sections generated at once by [`expand_sections`]
+#[cfg(feature = "server")]
+const MAX_PARALLEL_SECTIONS: usize = 3;
+/// Token budget per section
+#[cfg(feature = "server")]
+const SECTION_MAX_LENGTH: u32 = 800;
+
+/// Expands several sections concurrently.
+///
+/// Each section runs in its own standalone generation, at most
+/// [`MAX_PARALLEL_SECTIONS`] at a time, so sections do not wait on each other
+/// or pollute the chat history. Progress is streamed as newline-delimited
+/// [`SectionExpansionEvent`]s.
+///
+/// # Arguments
+///
+/// * `sections` - (index in the editor, section title) pairs to expand
+/// * `context` - Article title
+#[post("/api/expand_sections")]
+pub async fn expand_sections(sections: Vec<(usize, String)>, context: String) -> Result<TextStream> {
+    use futures::StreamExt;
+    use crate::core::llm;
+    use crate::models::content_template::SectionExpansionEvent;
+
+    if !llm::is_initialized() {
+        return Err(super::stream_error(crate::models::AppError::model_not_ready("Model not initialized")).into());
+    }
+
+    let (tx, rx) = futures::channel::mpsc::unbounded();
+    tokio::spawn(async move {
+        let started = std::time::Instant::now();
+        let count = sections.len();
+
+        futures::stream::iter(sections)
+            .for_each_concurrent(MAX_PARALLEL_SECTIONS, |(index, title)| {
+                let tx = tx.clone();
+                let prompt = section_prompt(&title, &context);
+                async move {
+                    let send = |event: SectionExpansionEvent| {
+                        let _ = tx.unbounded_send(event.to_line());
+                    };
+                    send(SectionExpansionEvent::Started { index });
+
+                    let mut tokens = match llm::stream_standalone(prompt, None, 0.7, SECTION_MAX_LENGTH) {
+                        Ok(tokens) => tokens,
+                        Err(error) => return send(SectionExpansionEvent::Failed { index, error }),
+                    };
+                    let mut content = String::new();
+                    while let Some(token) = tokens.next().await {
+                        content.push_str(&token);
+                        send(SectionExpansionEvent::Token { index, text: token });
+                    }
+                    send(SectionExpansionEvent::Done { index, content: content.trim().to_string() });
+                }
+            })
+            .await;
+
+        tracing::info!("Expanded {} sections in {:?}", count, started.elapsed());
+    });
+
+    Ok(TextStream::new(rx))
+}
+
+/// Prompt for writing one section of an article
+#[cfg(feature = "server")]
+fn section_prompt(section_title: &str, context: &str) -> String {
+    format!(
+        r#"Write content for the section "{}" in an article titled "{}".
 
 Requirements:
 - Write 2-4 paragraphs of well-structured content
@@ -119,17 +203,79 @@ Requirements:
 - Do not include the section title in your response
 
 Write the section content now:"#,
-            section_title, context
-        );
+        section_title, context
+    )
+}
 
-        let response = get_llm_response(prompt, None)
+/// Translates a finished draft into each language and returns the package
+/// with the translations stored, replacing earlier ones for the same language
+///
+/// # Arguments
+///
+/// * `languages` - Language names (e.g. "French") or codes (e.g. "ja")
+#[server]
+pub async fn translate_content(mut package: ContentPackage, languages: Vec<String>) -> Result<ContentPackage, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::core::content_generator::translate;
+
+        if languages.iter().all(|l| l.trim().is_empty()) {
+            return Err(app_error("Select at least one language"));
+        }
+
+        for language in languages.iter().filter(|l| !l.trim().is_empty()) {
+            let translation = translate(&package, language)
+                .await
+                .map_err(|e| app_error(format!("Translation into {} failed: {}", language, e)))?;
+            package.set_translation(translation);
+        }
+
+        Ok(package)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(app_error("Not available on client"))
+}
+
+/// Proofreads a section for typos, grammar, passive voice and long
+/// sentences, returning the issues and a quality score
+#[server]
+pub async fn proofread_section(content: String) -> Result<SectionReview, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        crate::core::proofreader::proofread(&content)
             .await
-            .map_err(|e| ServerFnError::new(format!("LLM error: {:?}", e)))?;
+            .map_err(|e| app_error(format!("Proofreading failed: {}", e)))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(app_error("Not available on client"))
+}
 
-        Ok(response.trim().to_string())
+/// Rewrites a section until it reads at the target grade level, returning
+/// the rewrite for review
+#[server]
+pub async fn rewrite_section_to_grade(content: String, target: f32) -> Result<ReadabilityRewrite, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        crate::core::readability::rewrite_to_grade(&content, target)
+            .await
+            .map_err(|e| app_error(format!("Rewrite failed: {}", e)))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(app_error("Not available on client"))
+}
+
+/// Scans (section title, text) pairs for passages that closely match a
+/// document in the knowledge base
+#[server]
+pub async fn check_draft_similarity(sections: Vec<(String, String)>) -> Result<SimilarityReport, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        crate::core::similarity_check::check(&sections)
+            .await
+            .map_err(|e| app_error(format!("Similarity check failed: {}", e)))
     }
     #[cfg(not(feature = "server"))]
-    Err(ServerFnError::new("Not available on client"))
+    Err(app_error("Not available on client"))
 }
 
 /// Generate an image prompt based on article content
@@ -153,12 +299,12 @@ Image prompt:"#,
 
         let response = get_llm_response(prompt, None)
             .await
-            .map_err(|e| ServerFnError::new(format!("LLM error: {:?}", e)))?;
+            .map_err(|e| app_error(format!("LLM error: {:?}", e)))?;
 
         Ok(response.trim().to_string())
     }
     #[cfg(not(feature = "server"))]
-    Err(ServerFnError::new("Not available on client"))
+    Err(app_error("Not available on client"))
 }
 
 /// Export content to markdown format
@@ -169,12 +315,16 @@ pub async fn export_to_markdown(
 ) -> Result<String, ServerFnError> {
     let mut md = format!("# {}\n\n", title);
 
+    let section_count = sections.len();
     for (section_title, content) in sections {
         md.push_str(&format!("## {}\n\n", section_title));
         md.push_str(&content);
         md.push_str("\n\n");
     }
 
+    #[cfg(feature = "server")]
+    notify_content_exported(&title, "markdown", section_count, &md);
+
     Ok(md)
 }
 
@@ -188,6 +338,7 @@ pub async fn export_to_html(
 
     let mut md = format!("# {}\n\n", title);
 
+    let section_count = sections.len();
     for (section_title, content) in sections {
         md.push_str(&format!("## {}\n\n", section_title));
         md.push_str(&content);
@@ -196,61 +347,19 @@ pub async fn export_to_html(
 
     let html = markdown_to_html(&md, &Options::default());
 
-    Ok(html)
-}
-
-/// Parse the LLM response into section tuples
-fn parse_outline_response(response: &str) -> Vec<(String, String)> {
-    let mut sections = Vec::new();
-    let mut current_title: Option<String> = None;
-    let mut current_content = String::new();
-
-    for line in response.lines() {
-        let trimmed = line.trim();
-
-        if trimmed.starts_with("## ") {
-            // Save previous section
-            if let Some(title) = current_title.take() {
-                sections.push((title, current_content.trim().to_string()));
-                current_content.clear();
-            }
-            // Start new section
-            current_title = Some(trimmed[3..].trim().to_string());
-        } else if current_title.is_some() && !trimmed.is_empty() {
-            if !current_content.is_empty() {
-                current_content.push(' ');
-            }
-            current_content.push_str(trimmed);
-        }
-    }
-
-    // Save last section
-    if let Some(title) = current_title {
-        sections.push((title, current_content.trim().to_string()));
-    }
+    #[cfg(feature = "server")]
+    notify_content_exported(&title, "html", section_count, &html);
 
-    sections
+    Ok(html)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_parse_outline_response() {
-        let response = r#"## Introduction
-This section introduces the topic.
-
-## Main Content
-This section covers the main points.
-
-## Conclusion
-This section wraps up."#;
-
-        let sections = parse_outline_response(response);
-        assert_eq!(sections.len(), 3);
-        assert_eq!(sections[0].0, "Introduction");
-        assert_eq!(sections[1].0, "Main Content");
-        assert_eq!(sections[2].0, "Conclusion");
-    }
+/// Fires the content export webhook
+#[cfg(feature = "server")]
+fn notify_content_exported(title: &str, format: &str, section_count: usize, content: &str) {
+    crate::core::webhooks::fire(crate::models::WebhookEvent::ContentExported, serde_json::json!({
+        "title": title,
+        "format": format,
+        "section_count": section_count,
+        "content": content,
+    }));
 }
@@ -0,0 +1,166 @@
+//! Content Pipeline Server Functions
+//!
+//! Start, monitor, approve, resume and delete multi-stage content pipeline
+//! jobs, prepare and upscale their images, narrate them, assemble their
+//! videos, and export them as static sites.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{
+    ArticleExtras, AssembledVideo, ContentPackage, ImageEdit, NarrationAsset, PackageImage, PipelineDefinition, PipelineJob, UpscaleFactor,
+    KeywordReport, SeoDefaults, SeoMetadata, SubtitleOptions, VideoClip,
+};
+
+#[cfg(feature = "server")]
+fn parse_job_id(job_id: &str) -> Result<uuid::Uuid, ServerFnError> {
+    uuid::Uuid::parse_str(job_id).map_err(|_| app_error("Invalid job ID"))
+}
+
+/// Starts a pipeline for the topic and returns the job ID
+#[server]
+pub async fn start_content_pipeline(topic: String, pipeline: PipelineDefinition) -> Result<String, ServerFnError> {
+    crate::core::content_generator::start(topic, pipeline)
+        .map(|id| id.to_string())
+        .map_err(|e| app_error(&format!("Failed to start pipeline: {}", e)))
+}
+
+/// Returns all pipeline jobs, newest first
+#[server]
+pub async fn get_content_jobs() -> Result<Vec<PipelineJob>, ServerFnError> {
+    Ok(crate::core::content_generator::list_jobs())
+}
+
+/// Approves the checkpoint a job is waiting on, with optional edits
+#[server]
+pub async fn approve_content_stage(job_id: String, edited: Option<ContentPackage>) -> Result<(), ServerFnError> {
+    crate::core::content_generator::approve(parse_job_id(&job_id)?, edited)
+        .map_err(|e| app_error(&e))
+}
+
+/// Retries a failed job from the stage that failed
+#[server]
+pub async fn resume_content_job(job_id: String) -> Result<(), ServerFnError> {
+    crate::core::content_generator::resume(parse_job_id(&job_id)?)
+        .map_err(|e| app_error(&e))
+}
+
+/// Deletes a job that is not running, including its saved state
+#[server]
+pub async fn delete_content_job(job_id: String) -> Result<(), ServerFnError> {
+    crate::core::content_generator::delete_job(parse_job_id(&job_id)?)
+        .map_err(|e| app_error(&e))
+}
+
+/// Crops, resizes and compresses a package image for export
+#[server]
+pub async fn process_package_image(job_id: String, index: usize, edit: ImageEdit) -> Result<PackageImage, ServerFnError> {
+    let job_id = parse_job_id(&job_id)?;
+    tokio::task::spawn_blocking(move || crate::core::content_generator::process_package_image(job_id, index, &edit))
+        .await
+        .map_err(|e| app_error(&format!("Image processing panicked: {}", e)))?
+        .map_err(|e| app_error(&e))
+}
+
+/// Saves a 2× or 4× copy of a package image next to the original
+#[server]
+pub async fn upscale_package_image(job_id: String, index: usize, factor: UpscaleFactor, face_fix: bool) -> Result<PackageImage, ServerFnError> {
+    crate::core::content_generator::upscale_package_image(parse_job_id(&job_id)?, index, factor, face_fix)
+        .await
+        .map_err(|e| app_error(&e))
+}
+
+/// Saves a clip (URL or local file) with the package and appends it to
+/// its video timeline, with the provider's thumbnail when one is given
+#[server]
+pub async fn add_package_video_clip(
+    job_id: String,
+    source: String,
+    label: String,
+    thumbnail_url: Option<String>,
+) -> Result<VideoClip, ServerFnError> {
+    crate::core::content_generator::add_video_clip(parse_job_id(&job_id)?, &source, &label, thumbnail_url.as_deref())
+        .await
+        .map_err(|e| app_error(&format!("Failed to add clip: {}", e)))
+}
+
+/// Saves the timeline after clips were reordered, removed or titled
+#[server]
+pub async fn set_package_video_clips(job_id: String, clips: Vec<VideoClip>) -> Result<(), ServerFnError> {
+    crate::core::content_generator::set_video_clips(parse_job_id(&job_id)?, clips)
+        .map_err(|e| app_error(&e))
+}
+
+/// A clip or video thumbnail of the package as a data URL
+#[server]
+pub async fn get_video_thumbnail(job_id: String, path: String) -> Result<String, ServerFnError> {
+    crate::core::content_generator::video_thumbnail(parse_job_id(&job_id)?, &path)
+        .map_err(|e| app_error(&e))
+}
+
+/// Saves the package's edited SEO and sharing metadata
+#[server]
+pub async fn set_package_seo(job_id: String, seo: SeoMetadata) -> Result<(), ServerFnError> {
+    crate::core::content_generator::set_seo(parse_job_id(&job_id)?, seo)
+        .map_err(|e| app_error(&e))
+}
+
+/// Keyword clusters for the package's topic, scored against its draft
+#[server]
+pub async fn suggest_package_keywords(job_id: String) -> Result<KeywordReport, ServerFnError> {
+    let job = crate::core::content_generator::get_job(parse_job_id(&job_id)?)
+        .ok_or_else(|| app_error("Job not found"))?;
+    crate::core::seo::suggest_keywords(&job.package)
+        .await
+        .map_err(|e| app_error(&format!("Keyword research failed: {}", e)))
+}
+
+/// Author, site and Twitter account filled into generated SEO metadata
+#[server]
+pub async fn get_seo_defaults() -> Result<SeoDefaults, ServerFnError> {
+    Ok(crate::core::seo::load_defaults())
+}
+
+#[server]
+pub async fn save_seo_defaults(defaults: SeoDefaults) -> Result<(), ServerFnError> {
+    crate::core::seo::save_defaults(&defaults)
+        .map_err(|e| app_error(&format!("Failed to save SEO defaults: {}", e)))
+}
+
+/// Saves the package's edited key takeaways and FAQ
+#[server]
+pub async fn set_package_extras(job_id: String, extras: ArticleExtras) -> Result<(), ServerFnError> {
+    crate::core::content_generator::set_extras(parse_job_id(&job_id)?, extras)
+        .map_err(|e| app_error(&e))
+}
+
+/// Saves how subtitles are made when the package video is assembled
+#[server]
+pub async fn set_package_subtitle_options(job_id: String, options: SubtitleOptions) -> Result<(), ServerFnError> {
+    crate::core::content_generator::set_subtitle_options(parse_job_id(&job_id)?, options)
+        .map_err(|e| app_error(&e))
+}
+
+/// Joins the timeline's clips and the narration into the package video
+#[server]
+pub async fn assemble_package_video(job_id: String) -> Result<AssembledVideo, ServerFnError> {
+    crate::core::content_generator::assemble_video(parse_job_id(&job_id)?)
+        .await
+        .map_err(|e| app_error(&format!("Video assembly failed: {}", e)))
+}
+
+/// Generates a TTS narration of the package's article
+#[server]
+pub async fn narrate_content_package(job_id: String) -> Result<NarrationAsset, ServerFnError> {
+    crate::core::content_generator::narrate(parse_job_id(&job_id)?)
+        .await
+        .map_err(|e| app_error(&format!("Narration failed: {}", e)))
+}
+
+/// Exports the package as a static site and returns its folder
+#[server]
+pub async fn export_content_site(job_id: String) -> Result<String, ServerFnError> {
+    crate::core::content_generator::export_site(parse_job_id(&job_id)?)
+        .map(|dir| dir.display().to_string())
+        .map_err(|e| app_error(&format!("Export failed: {}", e)))
+}
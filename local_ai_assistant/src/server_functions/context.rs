@@ -3,8 +3,11 @@
 //! Functions for managing RAG context documents.
 
 use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use crate::models::{CapturedDocument, DocumentDetails, IndexingJob, QueryExpansion, RetrievedChunk, VectorStoreStats};
 
 /// Get the context folder path - uses the same path as vector_store
 #[cfg(feature = "server")]
@@ -30,13 +33,13 @@ pub async fn list_context_files() -> Result<Vec<ContextFile>, ServerFnError> {
     // Create directory if it doesn't exist
     if !context_dir.exists() {
         fs::create_dir_all(&context_dir)
-            .map_err(|e| ServerFnError::new(&format!("Failed to create context directory: {}", e)))?;
+            .map_err(|e| app_error(&format!("Failed to create context directory: {}", e)))?;
     }
 
     let mut files = Vec::new();
 
     let entries = fs::read_dir(context_dir)
-        .map_err(|e| ServerFnError::new(&format!("Failed to read context directory: {}", e)))?;
+        .map_err(|e| app_error(&format!("Failed to read context directory: {}", e)))?;
 
     for entry in entries {
         if let Ok(entry) = entry {
@@ -82,7 +85,7 @@ pub async fn add_context_document(title: String, content: String) -> Result<(),
     // Create directory if it doesn't exist
     if !context_dir.exists() {
         fs::create_dir_all(&context_dir)
-            .map_err(|e| ServerFnError::new(&format!("Failed to create context directory: {}", e)))?;
+            .map_err(|e| app_error(&format!("Failed to create context directory: {}", e)))?;
     }
 
     // Sanitize filename
@@ -100,9 +103,9 @@ pub async fn add_context_document(title: String, content: String) -> Result<(),
     let path = context_dir.join(&filename);
 
     fs::write(&path, &content)
-        .map_err(|e| ServerFnError::new(&format!("Failed to write file: {}", e)))?;
+        .map_err(|e| app_error(&format!("Failed to write file: {}", e)))?;
 
-    println!("Added context document: {:?}", path);
+    tracing::info!("Added context document: {:?}", path);
 
     // Note: The vector store would need to be reinitialized to include the new document
     // For now, we just save the file
@@ -110,25 +113,12 @@ pub async fn add_context_document(title: String, content: String) -> Result<(),
     Ok(())
 }
 
-/// Delete a context document
+/// Move a context document to the trash
 #[server]
 pub async fn delete_context_document(filename: String) -> Result<(), ServerFnError> {
-    use std::fs;
-
-    // Security check - prevent directory traversal
-    if filename.contains("..") || filename.contains("/") {
-        return Err(ServerFnError::new("Invalid filename"));
-    }
-
-    let context_dir = get_context_dir();
-    let path = context_dir.join(&filename);
-
-    fs::remove_file(&path)
-        .map_err(|e| ServerFnError::new(&format!("Failed to delete file: {}", e)))?;
-
-    println!("Deleted context document: {:?}", path);
-
-    Ok(())
+    crate::core::trash::trash_document(&filename)
+        .await
+        .map_err(|e| app_error(&format!("Failed to delete file: {}", e)))
 }
 
 /// Get content of a context document
@@ -138,33 +128,73 @@ pub async fn get_context_document(filename: String) -> Result<String, ServerFnEr
 
     // Security check - prevent directory traversal
     if filename.contains("..") || filename.contains("/") {
-        return Err(ServerFnError::new("Invalid filename"));
+        return Err(app_error("Invalid filename"));
     }
 
     let context_dir = get_context_dir();
     let path = context_dir.join(&filename);
 
     let content = fs::read_to_string(&path)
-        .map_err(|e| ServerFnError::new(&format!("Failed to read file: {}", e)))?;
+        .map_err(|e| app_error(&format!("Failed to read file: {}", e)))?;
 
     Ok(content)
 }
 
-/// Reload the vector store with updated documents
-/// This adds new documents to the existing database instead of rebuilding
+/// Get a context document's stored text and its chunks with embedding status
+#[server]
+pub async fn get_context_document_details(filename: String) -> Result<DocumentDetails, ServerFnError> {
+    crate::core::vector_store::document_details(&filename)
+        .await
+        .map_err(|e| app_error(&format!("Failed to read document: {}", e)))
+}
+
+/// Remove one chunk of a context document from the index
+#[server]
+pub async fn delete_document_chunk(filename: String, index: usize) -> Result<DocumentDetails, ServerFnError> {
+    crate::core::vector_store::remove_chunk(&filename, index)
+        .await
+        .map_err(|e| app_error(&format!("Failed to delete chunk: {}", e)))
+}
+
+/// Embed one chunk of a context document again
+#[server]
+pub async fn reembed_document_chunk(filename: String, index: usize) -> Result<DocumentDetails, ServerFnError> {
+    crate::core::vector_store::reembed_chunk(&filename, index)
+        .await
+        .map_err(|e| app_error(&format!("Failed to re-embed chunk: {}", e)))
+}
+
+/// Set the tags of a context document, used to filter retrieval
+#[server]
+pub async fn set_context_document_tags(filename: String, tags: Vec<String>) -> Result<DocumentDetails, ServerFnError> {
+    crate::core::vector_store::set_document_tags(&filename, &tags)
+        .await
+        .map_err(|e| app_error(&format!("Failed to save tags: {}", e)))
+}
+
+/// Every tag used on a context document, sorted
+#[server]
+pub async fn list_document_tags() -> Result<Vec<String>, ServerFnError> {
+    let tags = crate::storage::database::get_document_tags()
+        .await
+        .map_err(|e| app_error(&format!("Failed to read tags: {}", e)))?;
+    let mut tags: Vec<String> = tags.into_values().flatten().collect();
+    tags.sort();
+    tags.dedup();
+    Ok(tags)
+}
+
+/// Start re-indexing the context folder in the background. Progress is
+/// reported by [`get_indexing_jobs`].
 #[server]
 pub async fn reload_context_database() -> Result<String, ServerFnError> {
     #[cfg(feature = "server")]
     {
-        // Add new documents to existing vector store
-        match crate::core::vector_store::reload_documents().await {
-            Ok(msg) => {
-                println!("Vector store documents reloaded: {}", msg);
-                Ok(msg)
-            }
+        match crate::core::indexing::start_reload() {
+            Ok(job) => Ok(format!("Indexing {} documents in the background", job.documents.len())),
             Err(e) => {
-                println!("Failed to reload documents: {}", e);
-                Err(ServerFnError::new(&format!("Failed to reload: {}", e)))
+                tracing::error!("Failed to start indexing: {}", e);
+                Err(app_error(&format!("Failed to reload: {}", e)))
             }
         }
     }
@@ -174,3 +204,73 @@ pub async fn reload_context_database() -> Result<String, ServerFnError> {
         Ok("Reload not supported in this build".to_string())
     }
 }
+
+/// Recent indexing jobs with per-document progress, newest first
+#[server]
+pub async fn get_indexing_jobs() -> Result<Vec<IndexingJob>, ServerFnError> {
+    Ok(crate::core::indexing::list_jobs())
+}
+
+/// Largest top-k accepted by the retrieval playground
+#[cfg(feature = "server")]
+const MAX_PLAYGROUND_RESULTS: usize = 50;
+
+/// Get vector store statistics: document and chunk counts, index size and
+/// last index time
+#[server]
+pub async fn get_vector_store_stats() -> Result<VectorStoreStats, ServerFnError> {
+    crate::core::vector_store::get_stats()
+        .await
+        .map_err(|e| app_error(&format!("Failed to read vector store stats: {}", e)))
+}
+
+/// Run a query against the vector store and return the raw top-k chunks
+#[server]
+pub async fn search_vector_store_raw(query: String, top_k: usize) -> Result<Vec<RetrievedChunk>, ServerFnError> {
+    if query.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    crate::core::vector_store::search_raw(&query, top_k.clamp(1, MAX_PLAYGROUND_RESULTS))
+        .await
+        .map_err(|e| app_error(&format!("Search failed: {}", e)))
+}
+
+/// The text a query is embedded as with the given expansion, for comparing
+/// retrieval with and without it in the playground
+#[server]
+pub async fn expand_search_query(query: String, expansion: QueryExpansion) -> Result<String, ServerFnError> {
+    Ok(crate::core::query_expansion::expand(&query, &[], expansion, false).await)
+}
+
+/// List knowledge base collections (subfolders of the context folder)
+#[server]
+pub async fn list_capture_collections() -> Result<Vec<String>, ServerFnError> {
+    Ok(crate::core::quick_capture::list_collections())
+}
+
+/// Transcribe an uploaded audio file in the background and add the
+/// transcript to the knowledge base
+#[server]
+pub async fn transcribe_audio_file(name: String, data: Vec<u8>, collection: Option<String>) -> Result<String, ServerFnError> {
+    crate::core::transcription::start_file(&name, data, collection)
+        .map(|_| format!("Transcribing {} in the background", name))
+        .map_err(|e| app_error(&format!("Transcription failed: {}", e)))
+}
+
+/// Transcribe an audio URL, or the latest episode of a podcast feed, in the
+/// background and add the transcript to the knowledge base
+#[server]
+pub async fn transcribe_audio_url(url: String, collection: Option<String>) -> Result<String, ServerFnError> {
+    crate::core::transcription::start_url(&url, collection)
+        .map(|_| "Downloading and transcribing in the background".to_string())
+        .map_err(|e| app_error(&format!("Transcription failed: {}", e)))
+}
+
+/// Capture text or a URL into the knowledge base
+#[server]
+pub async fn quick_capture(input: String, collection: Option<String>) -> Result<CapturedDocument, ServerFnError> {
+    crate::core::quick_capture::capture(&input, collection.as_deref())
+        .await
+        .map_err(|e| app_error(&format!("Capture failed: {}", e)))
+}
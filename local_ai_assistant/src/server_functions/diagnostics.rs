@@ -0,0 +1,54 @@
+//! Diagnostics Server Functions
+//!
+//! Exposes system resource usage and the optional tools found on this
+//! machine to the UI.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{Capabilities, SystemStats};
+
+/// Returns a snapshot of memory, GPU, disk usage and LLM throughput
+#[server]
+pub async fn get_system_stats() -> Result<SystemStats, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        tokio::task::spawn_blocking(crate::core::diagnostics::collect_stats)
+            .await
+            .map_err(|e| app_error(format!("Error collecting stats: {}", e)))
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        Ok(SystemStats::default())
+    }
+}
+
+/// Returns the optional tools and models found at startup
+#[server]
+pub async fn get_capabilities() -> Result<Capabilities, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        tokio::task::spawn_blocking(crate::core::capabilities::current)
+            .await
+            .map_err(|e| app_error(format!("Error detecting tools: {}", e)))
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        Ok(Capabilities::default())
+    }
+}
+
+/// Looks for the optional tools again, after the user installed one
+#[server]
+pub async fn refresh_capabilities() -> Result<Capabilities, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        tokio::task::spawn_blocking(crate::core::capabilities::detect)
+            .await
+            .map_err(|e| app_error(format!("Error detecting tools: {}", e)))
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        Ok(Capabilities::default())
+    }
+}
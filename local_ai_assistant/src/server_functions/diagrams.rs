@@ -0,0 +1,31 @@
+//! Diagram Server Functions
+//!
+//! Render Mermaid and Graphviz code blocks for messages and previews.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::DiagramKind;
+
+/// The diagram as an SVG data URL, shown as an image so nothing in the
+/// SVG can run in the page
+#[server]
+pub async fn render_diagram(kind: DiagramKind, source: String) -> Result<String, ServerFnError> {
+    use base64::Engine;
+
+    let svg = crate::core::diagrams::render_svg(kind, &source)
+        .await
+        .map_err(|e| app_error(&e))?;
+    Ok(format!("data:image/svg+xml;base64,{}", base64::engine::general_purpose::STANDARD.encode(svg)))
+}
+
+/// The diagram as a PNG data URL, for downloading
+#[server]
+pub async fn export_diagram_png(kind: DiagramKind, source: String) -> Result<String, ServerFnError> {
+    use base64::Engine;
+
+    let png = crate::core::diagrams::render_png(kind, &source)
+        .await
+        .map_err(|e| app_error(&format!("Export failed: {}", e)))?;
+    Ok(format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(&png)))
+}
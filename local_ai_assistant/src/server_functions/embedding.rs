@@ -0,0 +1,50 @@
+//! Embedding Configuration Server Functions
+//!
+//! Read and change the embedding backend used for RAG and its batch size.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{EmbeddingBackend, EmbeddingConfig};
+
+/// Returns the active embedding configuration
+#[server]
+pub async fn get_embedding_config() -> Result<EmbeddingConfig, ServerFnError> {
+    Ok(crate::core::embedding::get_config())
+}
+
+/// Switches the embedding backend and re-embeds memories and indexed
+/// documents when the vector space changed. Returns a status message for
+/// the UI.
+#[server]
+pub async fn set_embedding_backend(backend: EmbeddingBackend) -> Result<String, ServerFnError> {
+    use crate::core::{embedding, memory, vector_store};
+
+    let name = backend.display_name();
+    let changed = embedding::set_backend(backend)
+        .await
+        .map_err(|e| app_error(format!("Error switching embedding model: {}", e)))?;
+
+    if !changed {
+        return Ok(format!("{} is already active", name));
+    }
+
+    memory::reembed_memories()
+        .await
+        .map_err(|e| app_error(format!("Embedding model switched, but re-embedding memories failed: {}", e)))?;
+
+    if vector_store::is_initialized() {
+        vector_store::reembed_documents()
+            .await
+            .map_err(|e| app_error(format!("Embedding model switched, but re-embedding failed: {}", e)))?;
+        Ok(format!("Switched to {} and re-embedded memories and documents", name))
+    } else {
+        Ok(format!("Switched to {}", name))
+    }
+}
+
+/// Sets how many texts are embedded per model call
+#[server]
+pub async fn set_embedding_batch_size(batch_size: usize) -> Result<(), ServerFnError> {
+    crate::core::embedding::set_batch_size(batch_size).map_err(app_error)
+}
@@ -0,0 +1,53 @@
+//! History Encryption Server Functions
+//!
+//! Turn chat history encryption on and off, unlock it on start, lock it
+//! again and change the passphrase.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::EncryptionStatus;
+
+/// Whether history is encrypted and unlocked. Opens the database first,
+/// since the lock screen asks before the chat does.
+#[server]
+pub async fn get_encryption_status() -> Result<EncryptionStatus, ServerFnError> {
+    if !crate::storage::database::is_initialized() {
+        crate::storage::database::init()
+            .await
+            .map_err(|e| app_error(&format!("SQLite init error: {}", e)))?;
+    }
+    Ok(crate::core::encryption::status())
+}
+
+#[server]
+pub async fn unlock_history(passphrase: String) -> Result<(), ServerFnError> {
+    crate::core::encryption::unlock(&passphrase).await.map_err(app_error)
+}
+
+#[server]
+pub async fn lock_history() -> Result<(), ServerFnError> {
+    crate::core::encryption::lock();
+    Ok(())
+}
+
+/// Encrypts all chat history, returning how many sessions and messages
+/// were encrypted
+#[server]
+pub async fn enable_history_encryption(passphrase: String, confirmation: String) -> Result<usize, ServerFnError> {
+    crate::core::encryption::enable(&passphrase, &confirmation).await.map_err(app_error)
+}
+
+/// Re-encrypts all chat history with a key from the new passphrase
+#[server]
+pub async fn change_history_passphrase(current: String, passphrase: String, confirmation: String) -> Result<usize, ServerFnError> {
+    crate::core::encryption::change_passphrase(&current, &passphrase, &confirmation)
+        .await
+        .map_err(app_error)
+}
+
+/// Decrypts all chat history and turns encryption off
+#[server]
+pub async fn disable_history_encryption(passphrase: String) -> Result<usize, ServerFnError> {
+    crate::core::encryption::disable(&passphrase).await.map_err(app_error)
+}
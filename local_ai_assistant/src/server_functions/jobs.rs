@@ -0,0 +1,36 @@
+//! Background Job Server Functions
+//!
+//! List, cancel and retry the tasks in the job registry.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::BackgroundJob;
+
+#[cfg(feature = "server")]
+fn parse_job_id(job_id: &str) -> Result<uuid::Uuid, ServerFnError> {
+    uuid::Uuid::parse_str(job_id).map_err(|_| app_error("Invalid job ID"))
+}
+
+/// Every tracked job, newest first
+#[server]
+pub async fn get_background_jobs() -> Result<Vec<BackgroundJob>, ServerFnError> {
+    Ok(crate::core::jobs::list())
+}
+
+#[server]
+pub async fn cancel_background_job(job_id: String) -> Result<(), ServerFnError> {
+    crate::core::jobs::cancel(parse_job_id(&job_id)?).map_err(app_error)
+}
+
+#[server]
+pub async fn retry_background_job(job_id: String) -> Result<(), ServerFnError> {
+    crate::core::jobs::retry(parse_job_id(&job_id)?).map_err(app_error)
+}
+
+/// Removes finished jobs from the list
+#[server]
+pub async fn clear_finished_jobs() -> Result<(), ServerFnError> {
+    crate::core::jobs::clear_finished();
+    Ok(())
+}
@@ -0,0 +1,22 @@
+//! Local Network Access Server Functions
+//!
+//! Turn on access from other devices on the network and show the address
+//! and QR code to open it with.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{LanAccessConfig, LanAccessInfo};
+
+/// Current settings with the address to open from another device
+#[server]
+pub async fn get_lan_access() -> Result<LanAccessInfo, ServerFnError> {
+    Ok(crate::core::lan_access::info())
+}
+
+/// Saves the settings. Listening on the network starts on the next start.
+#[server]
+pub async fn save_lan_access(config: LanAccessConfig) -> Result<LanAccessInfo, ServerFnError> {
+    crate::core::lan_access::save_config(config).map_err(app_error)?;
+    Ok(crate::core::lan_access::info())
+}
@@ -0,0 +1,37 @@
+//! Log Viewer Server Functions
+//!
+//! Serves recent structured log entries to the Settings > Logs tab.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::LogEntry;
+
+/// Maximum number of entries a single request may return
+#[cfg(feature = "server")]
+const MAX_LOG_ENTRIES: usize = 1000;
+
+/// Returns recent log entries, newest first, optionally filtered by minimum
+/// level (e.g. "WARN") and a case-insensitive search string
+#[server]
+pub async fn get_recent_logs(
+    min_level: Option<String>,
+    search: Option<String>,
+    limit: usize,
+) -> Result<Vec<LogEntry>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let limit = limit.min(MAX_LOG_ENTRIES);
+        tokio::task::spawn_blocking(move || {
+            crate::core::logging::read_recent(min_level.as_deref(), search.as_deref(), limit)
+        })
+        .await
+        .map_err(|e| app_error(e.to_string()))?
+        .map_err(|e| app_error(format!("Error reading logs: {}", e)))
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        let _ = (min_level, search, limit);
+        Ok(vec![])
+    }
+}
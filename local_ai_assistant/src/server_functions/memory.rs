@@ -0,0 +1,58 @@
+//! Memory Server Functions
+//!
+//! Review, recall and update long-term memories of the active profile.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::MemoryEntry;
+
+/// Returns all memories, newest first
+#[server]
+pub async fn get_memories() -> Result<Vec<MemoryEntry>, ServerFnError> {
+    crate::storage::database::get_memories_with_embeddings()
+        .await
+        .map(|memories| memories.into_iter().map(|(entry, _)| entry).collect())
+        .map_err(|e| app_error(&format!("Failed to load memories: {}", e)))
+}
+
+/// Deletes a memory
+#[server]
+pub async fn delete_memory(id: String) -> Result<(), ServerFnError> {
+    let uuid = uuid::Uuid::parse_str(&id).map_err(|_| app_error("Invalid memory ID"))?;
+    crate::storage::database::delete_memory(uuid)
+        .await
+        .map_err(|e| app_error(&format!("Failed to delete memory: {}", e)))
+}
+
+/// Deletes all memories
+#[server]
+pub async fn clear_memories() -> Result<(), ServerFnError> {
+    crate::storage::database::clear_memories()
+        .await
+        .map_err(|e| app_error(&format!("Failed to clear memories: {}", e)))
+}
+
+/// Returns a prompt preamble with the memories relevant to the message
+/// (empty if there are none)
+#[server]
+pub async fn recall_memories(query: String) -> Result<String, ServerFnError> {
+    use crate::core::memory;
+
+    match memory::recall(&query, memory::MAX_RECALLED).await {
+        Ok(memories) => Ok(memory::format_for_prompt(&memories)),
+        Err(e) => {
+            tracing::warn!("Memory recall failed: {}", e);
+            Ok(String::new())
+        }
+    }
+}
+
+/// Extracts and stores new facts from a user message
+#[server]
+pub async fn remember_from_message(session_id: String, message: String) -> Result<Vec<MemoryEntry>, ServerFnError> {
+    let session = uuid::Uuid::parse_str(&session_id).ok();
+    crate::core::memory::remember_exchange(session, &message)
+        .await
+        .map_err(|e| app_error(&format!("Failed to update memory: {}", e)))
+}
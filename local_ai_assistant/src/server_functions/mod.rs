@@ -1,6 +1,13 @@
 //! Server Functions Module
 //!
-//! Dioxus server functions for client-server communication.
+//! Dioxus server functions for client-server communication. Failures are
+//! reported as an [`AppError`](crate::models::AppError), tagged with their
+//! category, so the UI can explain them and offer a way to recover.
+
+#[cfg(feature = "server")]
+use dioxus::prelude::ServerFnError;
+#[cfg(feature = "server")]
+use crate::models::AppError;
 
 mod chat;
 mod session;
@@ -10,6 +17,29 @@ mod tts;
 mod content;
 mod server_video_gen;
 pub mod server_model_manager;
+mod diagnostics;
+mod logs;
+mod embedding;
+mod profile;
+mod webhooks;
+mod memory;
+mod web_search;
+mod content_pipeline;
+mod storage;
+mod lan_access;
+mod notifications;
+mod jobs;
+mod attachments;
+mod diagrams;
+mod compare;
+mod codebase;
+mod voice;
+mod assets;
+mod trash;
+mod encryption;
+mod network_audit;
+mod publishing;
+mod proxy;
 
 pub use chat::*;
 pub use session::*;
@@ -19,3 +49,39 @@ pub use tts::*;
 pub use content::*;
 pub use server_video_gen::*;
 pub use server_model_manager::*;
+pub use diagnostics::*;
+pub use logs::*;
+pub use embedding::*;
+pub use profile::*;
+pub use webhooks::*;
+pub use memory::*;
+pub use web_search::*;
+pub use content_pipeline::*;
+pub use storage::*;
+pub use lan_access::*;
+pub use notifications::*;
+pub use jobs::*;
+pub use attachments::*;
+pub use diagrams::*;
+pub use compare::*;
+pub use codebase::*;
+pub use voice::*;
+pub use assets::*;
+pub use trash::*;
+pub use encryption::*;
+pub use network_audit::*;
+pub use publishing::*;
+pub use proxy::*;
+
+/// Server function error for a failure, tagged with its category; errors
+/// that are already tagged keep theirs
+#[cfg(feature = "server")]
+pub(crate) fn app_error(error: impl std::fmt::Display) -> ServerFnError {
+    ServerFnError::new(AppError::parse(&error.to_string()))
+}
+
+/// Error ending a streaming endpoint, tagged like [`app_error`]
+#[cfg(feature = "server")]
+pub(crate) fn stream_error(error: impl std::fmt::Display) -> std::io::Error {
+    std::io::Error::other(AppError::parse(&error.to_string()).to_string())
+}
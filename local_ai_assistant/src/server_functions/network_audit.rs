@@ -0,0 +1,27 @@
+//! Network Audit Server Functions
+//!
+//! Serves the log of outbound network requests to the Settings > Privacy tab.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{NetworkFeature, NetworkRequest};
+
+/// Maximum number of requests a single call may return
+#[cfg(feature = "server")]
+const MAX_AUDIT_ENTRIES: usize = 1000;
+
+/// Returns recorded requests, newest first, optionally of one feature
+#[server]
+pub async fn get_network_audit(feature: Option<NetworkFeature>, limit: usize) -> Result<Vec<NetworkRequest>, ServerFnError> {
+    let limit = limit.min(MAX_AUDIT_ENTRIES);
+    tokio::task::spawn_blocking(move || crate::core::http::read_audit(feature, limit))
+        .await
+        .map_err(|e| app_error(e.to_string()))?
+        .map_err(|e| app_error(format!("Error reading the network audit log: {}", e)))
+}
+
+#[server]
+pub async fn clear_network_audit() -> Result<(), ServerFnError> {
+    crate::core::http::clear_audit().map_err(app_error)
+}
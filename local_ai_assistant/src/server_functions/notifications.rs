@@ -0,0 +1,24 @@
+//! Notification Server Functions
+//!
+//! Read and manage the notifications posted by background jobs.
+
+use dioxus::prelude::*;
+use crate::models::Notification;
+
+/// Recent notifications, newest first
+#[server]
+pub async fn get_notifications() -> Result<Vec<Notification>, ServerFnError> {
+    Ok(crate::core::notifications::list())
+}
+
+#[server]
+pub async fn mark_notifications_read() -> Result<(), ServerFnError> {
+    crate::core::notifications::mark_all_read();
+    Ok(())
+}
+
+#[server]
+pub async fn clear_notifications() -> Result<(), ServerFnError> {
+    crate::core::notifications::clear();
+    Ok(())
+}
@@ -0,0 +1,59 @@
+//! Profile Server Functions
+//!
+//! List, create and switch user profiles, and load/save per-profile settings.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{AppSettings, Profile};
+
+/// Returns all profiles
+#[server]
+pub async fn get_profiles() -> Result<Vec<Profile>, ServerFnError> {
+    Ok(crate::core::profile::list_profiles())
+}
+
+/// Returns the active profile
+#[server]
+pub async fn get_active_profile() -> Result<Profile, ServerFnError> {
+    Ok(crate::core::profile::active_profile())
+}
+
+/// Creates a new profile (does not switch to it)
+#[server]
+pub async fn create_profile(name: String) -> Result<Profile, ServerFnError> {
+    crate::core::profile::create_profile(&name)
+        .map_err(|e| app_error(&format!("Failed to create profile: {}", e)))
+}
+
+/// Switches the active profile and re-indexes its knowledge base
+#[server]
+pub async fn switch_profile(id: String) -> Result<Profile, ServerFnError> {
+    use crate::core::{profile, vector_store};
+
+    let changed = profile::set_active_profile(&id)
+        .map_err(|e| app_error(&format!("Failed to switch profile: {}", e)))?;
+
+    if changed && vector_store::is_initialized() {
+        // Sessions and settings follow immediately; a failed re-index only
+        // affects RAG, so report it in the logs rather than failing the switch
+        if let Err(e) = vector_store::switch_profile_collection().await {
+            tracing::error!("Error indexing knowledge base for profile {}: {}", id, e);
+        }
+    }
+
+    Ok(profile::active_profile())
+}
+
+/// Returns the settings of the active profile
+#[server]
+pub async fn get_profile_settings() -> Result<AppSettings, ServerFnError> {
+    Ok(crate::core::profile::load_settings())
+}
+
+/// Saves the settings of the active profile
+#[server]
+pub async fn save_profile_settings(settings: AppSettings) -> Result<(), ServerFnError> {
+    crate::core::profile::save_settings(&settings)
+        .map_err(|e| app_error(&format!("Failed to save settings: {}", e)))
+}
@@ -0,0 +1,21 @@
+//! Proxy and Mirror Server Functions
+//!
+//! Read and save the proxy for outbound requests and the Hugging Face
+//! mirror for model downloads.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::ProxyConfig;
+
+#[server]
+pub async fn get_proxy_settings() -> Result<ProxyConfig, ServerFnError> {
+    Ok(crate::core::http::proxy_config())
+}
+
+/// Saves the settings; requests made from now on use them
+#[server]
+pub async fn save_proxy_settings(config: ProxyConfig) -> Result<ProxyConfig, ServerFnError> {
+    crate::core::http::save_proxy_config(config).map_err(app_error)?;
+    Ok(crate::core::http::proxy_config())
+}
@@ -0,0 +1,56 @@
+//! Content Calendar Server Functions
+//!
+//! Plan, schedule and publish calendar entries, and connect the WordPress
+//! site that scheduled posts go to.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{Publication, WordPressConfig};
+
+/// The active profile's calendar entries, by date
+#[server]
+pub async fn get_publications() -> Result<Vec<Publication>, ServerFnError> {
+    crate::storage::database::get_publications()
+        .await
+        .map_err(|e| app_error(&format!("Failed to load the content calendar: {}", e)))
+}
+
+/// Adds or updates a calendar entry
+#[server]
+pub async fn save_publication(publication: Publication) -> Result<(), ServerFnError> {
+    if publication.title.trim().is_empty() {
+        return Err(app_error("Give the entry a title"));
+    }
+    crate::storage::database::save_publication(&publication)
+        .await
+        .map_err(|e| app_error(&format!("Failed to save the entry: {}", e)))
+}
+
+#[server]
+pub async fn delete_publication(id: String) -> Result<(), ServerFnError> {
+    let id = uuid::Uuid::parse_str(&id).map_err(|_| app_error("Invalid entry ID"))?;
+    crate::storage::database::delete_publication(id)
+        .await
+        .map_err(|e| app_error(&format!("Failed to delete the entry: {}", e)))
+}
+
+/// Saves the entry and publishes it right away, returning the post's URL
+#[server]
+pub async fn publish_now(publication: Publication) -> Result<String, ServerFnError> {
+    crate::storage::database::save_publication(&publication)
+        .await
+        .map_err(|e| app_error(&format!("Failed to save the entry: {}", e)))?;
+    crate::core::publishing::publish(publication).await.map_err(app_error)
+}
+
+#[server]
+pub async fn get_wordpress_config() -> Result<WordPressConfig, ServerFnError> {
+    Ok(crate::core::publishing::load_config())
+}
+
+#[server]
+pub async fn save_wordpress_config(config: WordPressConfig) -> Result<(), ServerFnError> {
+    crate::core::publishing::save_config(&config)
+        .map_err(|e| app_error(&format!("Failed to save the WordPress connection: {}", e)))
+}
@@ -4,6 +4,8 @@
 //! Phase 2.2: Image Generation Support
 
 use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
 
 /// Result of image generation returned to client
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -11,6 +13,9 @@ pub struct ImageResult {
     pub data_url: String,
     pub width: u32,
     pub height: u32,
+    /// Saved file on the server, used to make upscaled copies
+    #[serde(default)]
+    pub path: Option<String>,
 }
 
 /// Initializes the image generation model.
@@ -24,7 +29,7 @@ pub async fn init_image_model() -> Result<(), ServerFnError> {
     {
         use crate::core::image_gen;
         image_gen::init_image_model().await.map_err(|e| {
-            ServerFnError::new(&format!("Error initializing image model: {}", e))
+            app_error(&format!("Error initializing image model: {}", e))
         })
     }
     #[cfg(not(feature = "server"))]
@@ -111,8 +116,12 @@ pub async fn get_image_gen_status() -> Result<ImageGenStatus, ServerFnError> {
 /// * `width` - Image width (default: 1024)
 /// * `height` - Image height (default: 1024)
 /// * `steps` - Number of inference steps (uses model default if None)
-/// * `model` - MFLUX model: "schnell" (fast), "dev" (quality), "z-image-turbo" (very fast)
+/// * `model` - MFLUX model: "schnell" (fast), "dev" (quality), "z-image-turbo" (very fast),
+///   or "custom:<name>" for a registered custom model
 /// * `quantize` - Quantization bits: 4 or 8 (default: 8)
+/// * `loras` - LoRA weights to apply; disabled ones are skipped
+/// * `source_image` - ID from `upload_source_image` for image-to-image
+/// * `image_strength` - How much of the source image is kept, from 0 to 1
 ///
 /// # Returns
 ///
@@ -126,6 +135,9 @@ pub async fn generate_image(
     steps: Option<u32>,
     model: Option<String>,
     quantize: Option<u8>,
+    loras: Vec<crate::models::LoraWeight>,
+    source_image: Option<String>,
+    image_strength: Option<f32>,
 ) -> Result<ImageResult, ServerFnError> {
     #[cfg(feature = "server")]
     {
@@ -147,32 +159,51 @@ pub async fn generate_image(
 
         // Parse model selection
         if let Some(m) = model {
-            let mflux_model = match m.as_str() {
-                "dev" => MfluxModel::Dev,
-                "z-image-turbo" => MfluxModel::ZImageTurbo,
-                _ => MfluxModel::Schnell, // Default to schnell
+            let mflux_model = if let Some(name) = crate::models::custom_model_name(&m) {
+                let registered = crate::core::profile::load_settings().custom_image_models;
+                let custom = registered.into_iter().find(|c| c.name == name)
+                    .ok_or_else(|| app_error(&format!("Custom model \"{}\" is not registered", name)))?;
+                MfluxModel::Custom(custom)
+            } else {
+                match m.as_str() {
+                    "dev" => MfluxModel::Dev,
+                    "z-image-turbo" => MfluxModel::ZImageTurbo,
+                    _ => MfluxModel::Schnell, // Default to schnell
+                }
             };
             settings = settings.with_model(mflux_model);
         }
 
+        settings = settings.with_loras(loras);
+
         if let Some(q) = quantize {
             settings = settings.with_quantize(q);
         }
 
+        if let Some(id) = source_image {
+            let path = crate::core::image_gen::source_image_path(&id).map_err(|e| app_error(&e))?;
+            let strength = image_strength.unwrap_or(crate::models::DEFAULT_IMAGE_STRENGTH);
+            settings = settings.with_source_image(path, strength);
+        }
+
         let image = gen_img(settings).await.map_err(|e| {
-            ServerFnError::new(&format!("Error generating image: {}", e))
+            app_error(&format!("Error generating image: {}", e))
         })?;
+        if let Some(path) = &image.path {
+            crate::core::assets::register(path, &prompt, crate::models::AssetOrigin::ImagePanel, None).await;
+        }
 
         Ok(ImageResult {
             data_url: image.to_data_url(),
             width: image.width,
             height: image.height,
+            path: image.path.map(|p| p.display().to_string()),
         })
     }
     #[cfg(not(feature = "server"))]
     {
-        let _ = (prompt, negative_prompt, width, height, steps, model, quantize);
-        Err(ServerFnError::new("Image generation not available on client"))
+        let _ = (prompt, negative_prompt, width, height, steps, model, quantize, loras, source_image, image_strength);
+        Err(app_error("Image generation not available on client"))
     }
 }
 
@@ -191,15 +222,161 @@ pub async fn generate_image(
 pub async fn generate_image_simple(prompt: String) -> Result<String, ServerFnError> {
     #[cfg(feature = "server")]
     {
-        use crate::core::image_gen::generate_image_base64;
+        use crate::core::image_gen::{ImageGenSettings, generate_image};
 
-        generate_image_base64(&prompt).await.map_err(|e| {
-            ServerFnError::new(&format!("Error generating image: {}", e))
-        })
+        let image = generate_image(ImageGenSettings::new(&prompt)).await.map_err(|e| {
+            app_error(&format!("Error generating image: {}", e))
+        })?;
+        if let Some(path) = &image.path {
+            crate::core::assets::register(path, &prompt, crate::models::AssetOrigin::ContentEditor, None).await;
+        }
+        Ok(image.to_data_url())
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        let _ = prompt;
+        Err(app_error("Image generation not available on client"))
+    }
+}
+
+/// Saves a pasted or picked image as the source for image-to-image.
+///
+/// # Arguments
+///
+/// * `data` - The image file, in any format the image crate reads
+///
+/// # Returns
+///
+/// * `Result<String>` - ID to pass to `generate_image` as `source_image`
+#[server]
+pub async fn upload_source_image(data: Vec<u8>) -> Result<String, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        tokio::task::spawn_blocking(move || crate::core::image_gen::save_source_image(&data))
+            .await
+            .map_err(|e| app_error(e.to_string()))?
+            .map_err(|e| app_error(&e))
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        let _ = data;
+        Err(app_error("Image generation not available on client"))
+    }
+}
+
+/// Checks that a custom model's folder exists and holds model weights.
+///
+/// # Arguments
+///
+/// * `model` - The model about to be registered
+///
+/// # Returns
+///
+/// * `Result<()>` - Success, or why the model cannot be used
+#[server]
+pub async fn check_custom_image_model(model: crate::models::CustomImageModel) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        crate::core::image_gen::check_custom_model(&model).map_err(|e| app_error(&e))
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        let _ = model;
+        Err(app_error("Image generation not available on client"))
+    }
+}
+
+/// Checks that a LoRA weights file exists.
+///
+/// # Arguments
+///
+/// * `path` - The `.safetensors` file on the server
+///
+/// # Returns
+///
+/// * `Result<()>` - Success, or why the file cannot be used
+#[server]
+pub async fn check_lora_weights(path: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        crate::core::image_gen::check_lora(&path).map_err(|e| app_error(&e))
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        let _ = path;
+        Err(app_error("Image generation not available on client"))
+    }
+}
+
+/// Rewrites a rough image prompt into a detailed one with the local LLM,
+/// adding style, lighting and composition.
+///
+/// # Arguments
+///
+/// * `prompt` - The user's rough prompt
+///
+/// # Returns
+///
+/// * `Result<String>` - The suggested prompt, for the user to edit
+#[server]
+pub async fn enhance_image_prompt(prompt: String) -> Result<String, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::{ENHANCED_PROMPT_MAX_TOKENS, clean_enhanced_prompt, enhance_prompt_instruction};
+
+        if prompt.trim().is_empty() {
+            return Err(app_error("Write a prompt to enhance"));
+        }
+        let reply = crate::core::llm::get_oneshot_response(enhance_prompt_instruction(&prompt), ENHANCED_PROMPT_MAX_TOKENS)
+            .await
+            .map_err(|e| app_error(&format!("Could not enhance the prompt: {}", e)))?;
+        Ok(clean_enhanced_prompt(&reply, &prompt))
     }
     #[cfg(not(feature = "server"))]
     {
         let _ = prompt;
-        Err(ServerFnError::new("Image generation not available on client"))
+        Err(app_error("Prompt enhancement not available on client"))
+    }
+}
+
+/// Saves an upscaled copy of a generated image next to the original.
+///
+/// # Arguments
+///
+/// * `path` - The generated image, from `ImageResult::path`
+/// * `factor` - 2× or 4×
+/// * `face_fix` - Whether to restore faces with GFPGAN first
+///
+/// # Returns
+///
+/// * `Result<ImageResult>` - The upscaled copy as a data URL, with its path
+#[server]
+pub async fn upscale_generated_image(path: String, factor: crate::models::UpscaleFactor, face_fix: bool) -> Result<ImageResult, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use base64::Engine;
+
+        // Only images in the images folder may be upscaled
+        let images_dir = crate::core::profile::asset_dir("images").canonicalize().map_err(|e| app_error(&e.to_string()))?;
+        let source = std::path::Path::new(&path).canonicalize().map_err(|_| app_error("Image not found"))?;
+        if !source.starts_with(&images_dir) {
+            return Err(app_error("Only generated images can be upscaled"));
+        }
+
+        let upscaled = crate::core::upscale::upscale_image(&source, factor, face_fix)
+            .await
+            .map_err(|e| app_error(&format!("Error upscaling image: {}", e)))?;
+        let data = std::fs::read(&upscaled.path).map_err(|e| app_error(&e.to_string()))?;
+        Ok(ImageResult {
+            data_url: format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(data)),
+            width: upscaled.width,
+            height: upscaled.height,
+            path: Some(upscaled.path),
+        })
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        let _ = (path, factor, face_fix);
+        Err(app_error("Upscaling not available on client"))
     }
 }
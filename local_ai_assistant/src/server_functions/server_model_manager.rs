@@ -3,47 +3,93 @@
 //! Provides server-side functions for managing HuggingFace models
 
 #[cfg(feature = "server")]
-use crate::core::model_manager::ModelManager;
+use crate::core::model_manager::{DownloadEvent, ModelManager};
+#[cfg(feature = "server")]
+use crate::core::jobs::JobSpec;
+#[cfg(feature = "server")]
+use crate::models::JobKind;
 use crate::models::{ModelInfo, CacheInfo};
 use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
 
 #[server]
 pub async fn list_cached_models() -> Result<Vec<ModelInfo>, ServerFnError> {
-    let mut models = ModelManager::get_available_models().await?;
-    ModelManager::check_cached_status(&mut models).await?;
+    let mut models = ModelManager::get_available_models().await.map_err(app_error)?;
+    ModelManager::check_cached_status(&mut models).await.map_err(app_error)?;
     Ok(models)
 }
 
 #[server]
 pub async fn download_model(model_id: String) -> Result<String, ServerFnError> {
-    ModelManager::download_model(&model_id).await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    download_as_job(model_id.clone()).await.map_err(app_error)?;
     Ok(format!("Model {} downloaded successfully", model_id))
 }
 
+/// Downloads the model as a cancellable job in the job registry
+#[cfg(feature = "server")]
+async fn download_as_job(model_id: String) -> Result<String, String> {
+    use crate::core::notifications;
+
+    let retry_id = model_id.clone();
+    JobSpec::new(JobKind::ModelDownload, model_id.clone())
+        .with_retry(move || {
+            start_download(retry_id.clone());
+            Ok(())
+        })
+        .run(move |job| async move {
+            job.log(format!("Downloading {}", model_id));
+            let on_event = move |event| match event {
+                DownloadEvent::Progress { done, total } if total > 0 => job.set_progress(done as f32 / total as f32),
+                DownloadEvent::Progress { .. } => {}
+                DownloadEvent::Retrying(message) | DownloadEvent::LowDiskSpace(message) => job.log(message),
+            };
+            match ModelManager::download_model_with_progress(&model_id, on_event).await {
+                Ok(output) => {
+                    job.log(&output);
+                    notifications::success("Model downloaded", model_id);
+                    Ok(output)
+                }
+                Err(e) => {
+                    notifications::error("Model download failed", e.to_string());
+                    Err(e.to_string())
+                }
+            }
+        })
+        .await
+}
+
+/// Retries a download in the background
+#[cfg(feature = "server")]
+fn start_download(model_id: String) {
+    tokio::spawn(async move {
+        let _ = download_as_job(model_id).await;
+    });
+}
+
 #[server]
 pub async fn delete_model(model_id: String) -> Result<String, ServerFnError> {
     ModelManager::delete_model(&model_id).await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .map_err(|e| app_error(e.to_string()))?;
     Ok(format!("Model {} deleted from cache", model_id))
 }
 
 #[server]
 pub async fn get_cache_info() -> Result<CacheInfo, ServerFnError> {
     ModelManager::get_cache_info().await
-        .map_err(|e| ServerFnError::new(e.to_string()))
+        .map_err(|e| app_error(e.to_string()))
 }
 
 #[server]
 pub async fn init_hf_cache() -> Result<String, ServerFnError> {
     ModelManager::init_hf_cache()
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .map_err(|e| app_error(e.to_string()))?;
     Ok("HuggingFace cache initialized successfully".to_string())
 }
 
 #[server]
 pub async fn ensure_model_cached(model_id: String) -> Result<String, ServerFnError> {
     ModelManager::ensure_model_cached(&model_id).await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .map_err(|e| app_error(e.to_string()))?;
     Ok(format!("Model {} is now cached", model_id))
 }
\ No newline at end of file
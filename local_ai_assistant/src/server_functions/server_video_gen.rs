@@ -1,11 +1,13 @@
 use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
 use anyhow::Result;
 #[cfg(feature = "server")]
 use std::sync::Arc;
 #[cfg(feature = "server")]
 use tokio::sync::Mutex;
 use serde::{Deserialize, Serialize};
-use crate::models::{VideoProvider, VideoModel, VideoConfig, VideoQuality};
+use crate::models::{VideoProvider, VideoModel, VideoConfig, VideoQuality, VideoPreset};
 
 // 仅在 server 特性下导入 video_gen
 #[cfg(feature = "server")]
@@ -26,14 +28,38 @@ pub struct VideoGenForm {
     pub seed: Option<u32>,
 }
 
+impl VideoGenForm {
+    /// Size, length, frame rate and quality as a video config
+    pub fn config(&self) -> VideoConfig {
+        VideoConfig {
+            width: self.width,
+            height: self.height,
+            duration_seconds: self.duration_seconds,
+            fps: self.fps,
+            quality: self.quality,
+            style: None,
+        }
+    }
+
+    /// Applies a preset, fitted to the selected provider
+    pub fn apply_preset(&mut self, preset: VideoPreset) {
+        let config = preset.config_for(&self.provider);
+        self.width = config.width;
+        self.height = config.height;
+        self.duration_seconds = config.duration_seconds;
+        self.fps = config.fps;
+        self.quality = config.quality;
+    }
+}
+
 impl Default for VideoGenForm {
     fn default() -> Self {
         Self {
             prompt: "a lovely white cat is playing in the garden".to_string(),
             negative_prompt: None,
             duration_seconds: 5,
-            width: 1024,
-            height: 576,
+            width: 1920,
+            height: 1080,
             quality: VideoQuality::HD,
             fps: 24,
             provider: VideoProvider::ByteDance, // Default to ByteDance (Cost-effective)
@@ -91,20 +117,14 @@ lazy_static::lazy_static! {
 pub async fn generate_video(form: VideoGenForm) -> Result<VideoResponse, ServerFnError> {
     #[cfg(feature = "server")]
     {
-        let generator = VIDEO_GENERATOR.lock().await;
+        let config = form.config();
+        crate::models::validate_video_config(&form.provider, &config).map_err(|e| app_error(&e))?;
 
         // Build request
         let request = VideoRequest::new(form.prompt)
             .with_model(form.model)
             .with_provider(form.provider)
-            .with_config(VideoConfig {
-                width: form.width,
-                height: form.height,
-                duration_seconds: form.duration_seconds,
-                fps: form.fps,
-                quality: form.quality,
-                style: None,
-            });
+            .with_config(config);
 
         // Set negative prompt and seed
         let mut request = request;
@@ -115,10 +135,10 @@ pub async fn generate_video(form: VideoGenForm) -> Result<VideoResponse, ServerF
             request.seed = Some(seed);
         }
 
-        // Generate video
-        let response = generator.generate_video(request)
+        // Generate video as a cancellable job
+        let response = generate_as_job(request)
             .await
-            .map_err(|e| ServerFnError::new(format!("Video generation failed: {}", e)))?;
+            .map_err(|e| app_error(format!("Video generation failed: {}", e)))?;
 
         // Convert to simplified response format
         Ok(VideoResponse {
@@ -137,27 +157,94 @@ pub async fn generate_video(form: VideoGenForm) -> Result<VideoResponse, ServerF
     }
     #[cfg(not(feature = "server"))]
     {
-        Err(ServerFnError::new("Video generation is only available in server mode."))
+        Err(app_error("Video generation is only available in server mode."))
     }
 }
 
+/// Generates the video as a job in the job registry, with notifications
+/// and the completion webhook
+#[cfg(feature = "server")]
+async fn generate_as_job(request: VideoRequest) -> Result<crate::core::video_gen::VideoResponse, String> {
+    use crate::core::jobs::JobSpec;
+    use crate::core::model_manager::ModelManager;
+    use crate::core::notifications;
+
+    let prompt = request.prompt.clone();
+    let short_prompt: String = prompt.chars().take(80).collect();
+    let retry_request = request.clone();
+    JobSpec::new(crate::models::JobKind::Video, short_prompt.clone())
+        .with_retry(move || {
+            start_generation(retry_request.clone());
+            Ok(())
+        })
+        .run(move |job| async move {
+            job.log(format!("{:?} / {:?}", request.provider, request.model));
+            // Checked before submitting, as providers charge per video
+            let library = crate::core::assets::library_dir();
+            match ModelManager::check_disk_space(&library, crate::models::VIDEO_ESTIMATE_BYTES, "saving the generated video").await {
+                Ok(Some(warning)) => job.log(warning),
+                Ok(None) => {}
+                Err(e) => {
+                    notifications::error("Video generation failed", format!("{}: {}", short_prompt, e));
+                    return Err(e.to_string());
+                }
+            }
+            let generator = VIDEO_GENERATOR.lock().await;
+            let response = match generator.generate_video(request).await {
+                Ok(response) => response,
+                Err(e) => {
+                    notifications::error("Video generation failed", format!("{}: {}", short_prompt, e));
+                    return Err(e.to_string());
+                }
+            };
+
+            match &response.status {
+                VideoStatus::Failed(msg) => {
+                    notifications::error("Video generation failed", format!("{}: {}", short_prompt, msg));
+                    return Err(msg.clone());
+                }
+                VideoStatus::Completed => {
+                    job.log(format!("Video ready: {}", response.video_url));
+                    notifications::success("Video ready", short_prompt);
+                    crate::core::webhooks::fire(crate::models::WebhookEvent::VideoCompleted, serde_json::json!({
+                        "generation_id": response.generation_id,
+                        "prompt": prompt,
+                        "video_url": response.video_url,
+                        "thumbnail_url": response.thumbnail_url,
+                        "duration_seconds": response.duration_seconds,
+                    }));
+                    crate::core::assets::save_video_in_background(
+                        response.video_url.clone(),
+                        response.thumbnail_url.clone(),
+                        prompt.clone(),
+                    );
+                }
+                _ => job.log(format!("Submitted as {}", response.generation_id)),
+            }
+            Ok(response)
+        })
+        .await
+}
+
+/// Retries a generation in the background
+#[cfg(feature = "server")]
+fn start_generation(request: VideoRequest) {
+    tokio::spawn(async move {
+        let _ = generate_as_job(request).await;
+    });
+}
+
 #[server]
 pub async fn estimate_video_cost(form: VideoGenForm) -> Result<f64, ServerFnError> {
     #[cfg(feature = "server")]
     {
         let generator = VIDEO_GENERATOR.lock().await;
 
+        let config = form.config();
         let request = VideoRequest::new(form.prompt)
             .with_model(form.model)
             .with_provider(form.provider)
-            .with_config(VideoConfig {
-                width: form.width,
-                height: form.height,
-                duration_seconds: form.duration_seconds,
-                fps: form.fps,
-                quality: form.quality,
-                style: None,
-            });
+            .with_config(config);
 
         let cost = generator.estimate_cost(&request);
         Ok(cost)
@@ -177,7 +264,7 @@ pub async fn estimate_video_cost(form: VideoGenForm) -> Result<f64, ServerFnErro
          // The macro generates the client stub automatically.
          // I don't need a `cfg(not(server))` block unless I want to override what happens on the server when the feature is disabled.
          
-       Err(ServerFnError::new("Server feature disabled"))
+       Err(app_error("Server feature disabled"))
     }
 }
 
@@ -3,7 +3,9 @@
 //! Session management with SQLite persistence.
 
 use dioxus::prelude::*;
-use crate::models::{Session, ChatMessage};
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{Session, SessionSummary, ChatMessage, FineTuneDataset, FineTuneFilter, MessageFeedback};
 
 /// Creates a new chat session and persists to database
 #[server]
@@ -13,7 +15,7 @@ pub async fn create_session(title: Option<String>) -> Result<Session, ServerFnEr
     let session = Session::new(title.unwrap_or_else(|| "New Chat".to_string()));
 
     if let Err(e) = database::create_session(&session).await {
-        println!("Error creating session in database: {:?}", e);
+        tracing::error!("Error creating session in database: {:?}", e);
         // Still return the session even if persistence fails
     }
 
@@ -28,7 +30,7 @@ pub async fn get_sessions() -> Result<Vec<Session>, ServerFnError> {
     match database::get_all_sessions().await {
         Ok(sessions) => Ok(sessions),
         Err(e) => {
-            println!("Error loading sessions: {:?}", e);
+            tracing::error!("Error loading sessions: {:?}", e);
             Ok(vec![])
         }
     }
@@ -48,13 +50,13 @@ pub async fn get_session(id: String) -> Result<Option<Session>, ServerFnError> {
     match database::get_all_sessions().await {
         Ok(sessions) => Ok(sessions.into_iter().find(|s| s.id == uuid)),
         Err(e) => {
-            println!("Error loading session: {:?}", e);
+            tracing::error!("Error loading session: {:?}", e);
             Ok(None)
         }
     }
 }
 
-/// Deletes a chat session
+/// Moves a chat session to the trash
 #[server]
 pub async fn delete_session(id: String) -> Result<(), ServerFnError> {
     use crate::storage::database;
@@ -62,12 +64,14 @@ pub async fn delete_session(id: String) -> Result<(), ServerFnError> {
 
     let uuid = match Uuid::parse_str(&id) {
         Ok(u) => u,
-        Err(_) => return Err(ServerFnError::new("Invalid session ID")),
+        Err(_) => return Err(app_error("Invalid session ID")),
     };
 
-    if let Err(e) = database::delete_session(uuid).await {
-        println!("Error deleting session: {:?}", e);
+    if let Err(e) = database::trash_session(uuid).await {
+        tracing::error!("Error deleting session: {:?}", e);
     }
+    // Restoring it from the trash rebuilds its chat from the stored messages
+    crate::core::llm::forget_session_chat(uuid);
 
     Ok(())
 }
@@ -80,23 +84,185 @@ pub async fn update_session_title(id: String, title: String) -> Result<(), Serve
 
     let uuid = match Uuid::parse_str(&id) {
         Ok(u) => u,
-        Err(_) => return Err(ServerFnError::new("Invalid session ID")),
+        Err(_) => return Err(app_error("Invalid session ID")),
     };
 
     if let Err(e) = database::update_session_title(uuid, &title).await {
-        println!("Error updating session title: {:?}", e);
+        tracing::error!("Error updating session title: {:?}", e);
     }
 
     Ok(())
 }
 
+/// Moves a session into a folder; an empty or missing folder name unfiles it
+#[server]
+pub async fn set_session_folder(id: String, folder: Option<String>) -> Result<(), ServerFnError> {
+    use crate::storage::database;
+    use uuid::Uuid;
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Err(app_error("Invalid session ID")),
+    };
+
+    let folder = folder
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty());
+
+    if let Err(e) = database::set_session_folder(uuid, folder.as_deref()).await {
+        tracing::error!("Error updating session folder: {:?}", e);
+    }
+
+    Ok(())
+}
+
+/// Pins or unpins a session
+#[server]
+pub async fn set_session_pinned(id: String, pinned: bool) -> Result<(), ServerFnError> {
+    use crate::storage::database;
+    use uuid::Uuid;
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Err(app_error("Invalid session ID")),
+    };
+
+    if let Err(e) = database::set_session_pinned(uuid, pinned).await {
+        tracing::error!("Error updating session pin: {:?}", e);
+    }
+
+    Ok(())
+}
+
+/// Saves the session's model, persona and temperature overrides. A session
+/// that was not saved yet is created with them.
+#[server]
+pub async fn set_session_overrides(session: Session) -> Result<(), ServerFnError> {
+    use crate::storage::database;
+
+    match database::set_session_overrides(session.id, &session.overrides).await {
+        Ok(true) => {}
+        Ok(false) => {
+            if let Err(e) = database::create_session(&session).await {
+                tracing::error!("Error creating session in database: {:?}", e);
+            }
+        }
+        Err(e) => {
+            tracing::error!("Error updating session overrides: {:?}", e);
+            return Err(app_error(&format!("Failed to save the session settings: {}", e)));
+        }
+    }
+    // The next turn starts a chat with the new model and persona
+    crate::core::llm::forget_session_chat(session.id);
+
+    Ok(())
+}
+
+/// Archives or restores a session
+#[server]
+pub async fn set_session_archived(id: String, archived: bool) -> Result<(), ServerFnError> {
+    use crate::storage::database;
+    use uuid::Uuid;
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Err(app_error("Invalid session ID")),
+    };
+
+    if let Err(e) = database::set_session_archived(uuid, archived).await {
+        tracing::error!("Error updating session archive state: {:?}", e);
+    }
+
+    Ok(())
+}
+
+/// Generates a descriptive title for a session from its conversation so far
+/// and stores it. Returns the new title.
+#[server]
+pub async fn generate_session_title(id: String) -> Result<String, ServerFnError> {
+    use crate::storage::database;
+    use crate::core::llm::get_oneshot_response;
+    use uuid::Uuid;
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Err(app_error("Invalid session ID")),
+    };
+
+    let messages = database::get_session_messages(uuid)
+        .await
+        .map_err(|e| app_error(format!("Error loading messages: {}", e)))?;
+
+    if messages.is_empty() {
+        return Err(app_error("Session has no messages to summarize"));
+    }
+
+    // Keep the prompt small: the first few turns carry the topic
+    let transcript = messages.iter()
+        .take(8)
+        .map(|m| format!("{}: {}", m.role, m.content.chars().take(300).collect::<String>()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let prompt = format!(
+        r#"Write a short title (at most 6 words) for the following conversation.
+Reply with the title only, in the same language as the conversation, without quotes or punctuation at the end.
+
+{}
+
+Title:"#,
+        transcript
+    );
+
+    let response = get_oneshot_response(prompt, 24)
+        .await
+        .map_err(|e| app_error(format!("LLM error: {}", e)))?;
+
+    let max_length = crate::core::profile::load_settings().session_titles.max_length;
+    let title = crate::models::clean_generated_title(&response, max_length);
+    if title.is_empty() {
+        return Err(app_error("Model returned an empty title"));
+    }
+
+    if let Err(e) = database::update_session_title(uuid, &title).await {
+        tracing::error!("Error updating session title: {:?}", e);
+    }
+
+    Ok(title)
+}
+
+/// Summarizes a session with the LLM and stores the summary with it
+#[server]
+pub async fn summarize_session(id: String) -> Result<SessionSummary, ServerFnError> {
+    let uuid = uuid::Uuid::parse_str(&id).map_err(|_| app_error("Invalid session ID"))?;
+    crate::core::session_summary::summarize(uuid)
+        .await
+        .map_err(|e| app_error(format!("Failed to summarize session: {}", e)))
+}
+
+/// Starts the background job that tags sessions and groups them into
+/// topics. Without `retag_all`, it only runs if some sessions are untagged.
+#[server]
+pub async fn start_session_tagging(retag_all: bool) -> Result<(), ServerFnError> {
+    use crate::core::session_tags;
+
+    let result = if retag_all { session_tags::start(true) } else { session_tags::start_if_needed().await };
+    result.map_err(|e| app_error(format!("Failed to start session tagging: {}", e)))
+}
+
+/// Whether the session tagging job is running
+#[server]
+pub async fn is_session_tagging_running() -> Result<bool, ServerFnError> {
+    Ok(crate::core::session_tags::is_running())
+}
+
 /// Save a message to database
 #[server]
 pub async fn save_message(message: ChatMessage) -> Result<(), ServerFnError> {
     use crate::storage::database;
 
     if let Err(e) = database::save_message(&message).await {
-        println!("Error saving message: {:?}", e);
+        tracing::error!("Error saving message: {:?}", e);
     }
 
     Ok(())
@@ -116,8 +282,145 @@ pub async fn get_session_messages(session_id: String) -> Result<Vec<ChatMessage>
     match database::get_session_messages(uuid).await {
         Ok(messages) => Ok(messages),
         Err(e) => {
-            println!("Error loading messages: {:?}", e);
+            tracing::error!("Error loading messages: {:?}", e);
             Ok(vec![])
         }
     }
 }
+
+/// Rates an assistant message, or clears the rating with `None`
+#[server]
+pub async fn set_message_feedback(message_id: String, feedback: Option<MessageFeedback>) -> Result<(), ServerFnError> {
+    use crate::storage::database;
+    use uuid::Uuid;
+
+    let uuid = match Uuid::parse_str(&message_id) {
+        Ok(u) => u,
+        Err(_) => return Err(app_error("Invalid message ID")),
+    };
+
+    database::set_message_feedback(uuid, feedback.as_ref())
+        .await
+        .map_err(|e| app_error(format!("Error saving feedback: {}", e)))
+}
+
+/// Exports every rated response as JSONL, one record per line with the
+/// prompt, response, rating and comment
+#[server]
+pub async fn export_feedback() -> Result<String, ServerFnError> {
+    use crate::models::FeedbackRecord;
+    use crate::storage::database;
+
+    let session_ids = database::get_sessions_with_feedback()
+        .await
+        .map_err(|e| app_error(format!("Error loading feedback: {}", e)))?;
+
+    let mut records = Vec::new();
+    for session_id in session_ids {
+        let messages = database::get_session_messages(session_id)
+            .await
+            .map_err(|e| app_error(format!("Error loading messages: {}", e)))?;
+        records.extend(FeedbackRecord::collect(&messages));
+    }
+    records.sort_by_key(|r| r.rated_at);
+
+    let mut jsonl = String::new();
+    for record in &records {
+        jsonl.push_str(&serde_json::to_string(record).map_err(|e| app_error(e.to_string()))?);
+        jsonl.push('\n');
+    }
+    Ok(jsonl)
+}
+
+/// Converts chat history into a JSONL dataset for chat fine-tuning
+#[server]
+pub async fn export_fine_tune_dataset(filter: FineTuneFilter) -> Result<FineTuneDataset, ServerFnError> {
+    crate::core::fine_tune::export_dataset(&filter)
+        .await
+        .map_err(app_error)
+}
+
+/// Renders a session as a self-contained HTML page for sharing. Images are
+/// embedded as data URLs so the file works without the app or a network.
+#[server]
+pub async fn share_session_html(id: String) -> Result<String, ServerFnError> {
+    use crate::models::rewrite_links;
+    use crate::storage::database;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Err(app_error("Invalid session ID")),
+    };
+
+    let session = database::get_all_sessions()
+        .await
+        .map_err(|e| app_error(format!("Error loading sessions: {}", e)))?
+        .into_iter()
+        .find(|s| s.id == uuid)
+        .ok_or_else(|| app_error("Session not found"))?;
+    let mut messages = database::get_session_messages(uuid)
+        .await
+        .map_err(|e| app_error(format!("Error loading messages: {}", e)))?;
+
+    // Fetch each image once, then swap the URLs in
+    let mut inlined: HashMap<String, String> = HashMap::new();
+    for message in &messages {
+        let mut urls = Vec::new();
+        rewrite_links(&message.content, |is_image, _, url| {
+            if is_image && !url.starts_with("data:") {
+                urls.push(url.to_string());
+            }
+            String::new()
+        });
+        for url in urls {
+            if inlined.contains_key(&url) {
+                continue;
+            }
+            match inline_image(&url).await {
+                Ok(data_url) => {
+                    inlined.insert(url, data_url);
+                }
+                Err(e) => tracing::warn!("Leaving image {} linked: {}", url, e),
+            }
+        }
+    }
+    for message in &mut messages {
+        message.content = rewrite_links(&message.content, |is_image, text, url| {
+            let url = if is_image { inlined.get(url).map(String::as_str).unwrap_or(url) } else { url };
+            format!("{}[{}]({})", if is_image { "!" } else { "" }, text, url)
+        });
+    }
+
+    Ok(session.to_html(&messages))
+}
+
+/// Reads an image from a local path or http(s) URL into a data URL
+#[cfg(feature = "server")]
+async fn inline_image(url: &str) -> Result<String, String> {
+    use base64::Engine;
+
+    let (data, content_type) = if url.starts_with("http://") || url.starts_with("https://") {
+        let response = crate::core::http::get(crate::models::NetworkFeature::Export, url)
+            .await
+            .map_err(|e| e.to_string())?
+            .error_for_status()
+            .map_err(|e| e.to_string())?;
+        let content_type = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        (response.bytes().await.map_err(|e| e.to_string())?.to_vec(), content_type)
+    } else {
+        let path = url.strip_prefix("file://").unwrap_or(url);
+        (std::fs::read(path).map_err(|e| e.to_string())?, None)
+    };
+
+    let mime_type = content_type
+        .filter(|t| t.starts_with("image/"))
+        .or_else(|| image::guess_format(&data).ok().map(|f| f.to_mime_type().to_string()))
+        .ok_or("Not an image")?;
+    Ok(format!("data:{};base64,{}", mime_type, base64::engine::general_purpose::STANDARD.encode(&data)))
+}
+
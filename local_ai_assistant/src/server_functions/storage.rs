@@ -0,0 +1,28 @@
+//! Storage Location Server Functions
+//!
+//! Show and change where data, the vector index, context documents and
+//! generated output are stored.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{StorageLocationInfo, StoragePaths};
+
+/// Every storage location with the directory in effect
+#[server]
+pub async fn get_storage_locations() -> Result<Vec<StorageLocationInfo>, ServerFnError> {
+    Ok(crate::core::paths::locations())
+}
+
+/// Directories set in the settings UI
+#[server]
+pub async fn get_storage_overrides() -> Result<StoragePaths, ServerFnError> {
+    Ok(crate::core::paths::overrides())
+}
+
+/// Saves new directories. They take effect, and existing data is moved
+/// there, on the next start.
+#[server]
+pub async fn set_storage_overrides(paths: StoragePaths) -> Result<(), ServerFnError> {
+    crate::core::paths::set_overrides(paths).map_err(app_error)
+}
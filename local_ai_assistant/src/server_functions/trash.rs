@@ -0,0 +1,41 @@
+//! Trash Server Functions
+//!
+//! List, restore and purge deleted sessions, messages, documents and assets.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{TrashItem, TrashKind};
+
+/// Everything in the trash, most recently deleted first
+#[server]
+pub async fn get_trash() -> Result<Vec<TrashItem>, ServerFnError> {
+    crate::core::trash::list().await.map_err(app_error)
+}
+
+/// Moves a single chat message to the trash
+#[server]
+pub async fn trash_message(message_id: String) -> Result<(), ServerFnError> {
+    let id = uuid::Uuid::parse_str(&message_id).map_err(|_| app_error("Invalid message ID"))?;
+    crate::storage::database::trash_message(id)
+        .await
+        .map_err(|e| app_error(&format!("Failed to delete message: {}", e)))
+}
+
+#[server]
+pub async fn restore_trash_item(kind: TrashKind, id: String) -> Result<(), ServerFnError> {
+    crate::core::trash::restore(kind, &id).await.map_err(app_error)
+}
+
+/// Deletes an item for good
+#[server]
+pub async fn purge_trash_item(kind: TrashKind, id: String) -> Result<(), ServerFnError> {
+    crate::core::trash::purge(kind, &id).await.map_err(app_error)
+}
+
+/// Deletes everything in the trash for good, returning how many items
+/// were removed
+#[server]
+pub async fn empty_trash() -> Result<usize, ServerFnError> {
+    crate::core::trash::empty().await.map_err(app_error)
+}
@@ -4,6 +4,10 @@
 //! Phase 2.3: TTS Support
 
 use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+
+use crate::models::AudioOutputDevice;
 
 /// Generates speech from text using the specified engine.
 ///
@@ -12,6 +16,7 @@ use dioxus::prelude::*;
 /// * `text` - The text to convert to speech
 /// * `engine` - The TTS engine to use ("system", "vibevoice", "kokoro")
 /// * `speed` - Speech speed multiplier (0.5 to 2.0)
+/// * `keep` - Save the audio to the asset library
 ///
 /// # Returns
 ///
@@ -21,6 +26,7 @@ pub async fn generate_tts(
     text: String,
     engine: String,
     speed: f32,
+    keep: bool,
 ) -> Result<String, ServerFnError> {
     #[cfg(feature = "server")]
     {
@@ -32,20 +38,28 @@ pub async fn generate_tts(
             _ => TtsEngine::System,
         };
 
+        // Pitch is a saved setting rather than a per-request option
+        let pitch = crate::core::profile::load_settings().speech_output.pitch;
         let settings = TtsSettings::new(&text)
             .with_engine(tts_engine)
-            .with_speed(speed);
+            .with_speed(speed)
+            .with_pitch(pitch);
 
         let audio = generate_speech(settings).await.map_err(|e| {
-            ServerFnError::new(&format!("Error generating speech: {}", e))
+            app_error(&format!("Error generating speech: {}", e))
         })?;
+        if keep {
+            if let Err(e) = crate::core::assets::save(&audio.data, &audio.format, &text, crate::models::AssetOrigin::TtsPanel).await {
+                tracing::warn!("Could not save generated audio to the asset library: {}", e);
+            }
+        }
 
         Ok(audio.to_data_url())
     }
     #[cfg(not(feature = "server"))]
     {
-        let _ = (text, engine, speed);
-        Err(ServerFnError::new("TTS not available on client"))
+        let _ = (text, engine, speed, keep);
+        Err(app_error("TTS not available on client"))
     }
 }
 
@@ -136,3 +150,20 @@ pub async fn get_available_tts_engines() -> Result<Vec<String>, ServerFnError> {
         Ok(vec!["System TTS".to_string()])
     }
 }
+
+/// Lists the audio output devices of the machine running the app.
+///
+/// # Returns
+///
+/// * `Result<Vec<AudioOutputDevice>>` - Device names, the default first
+#[server]
+pub async fn list_audio_output_devices() -> Result<Vec<AudioOutputDevice>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        Ok(crate::core::tts::output_devices())
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,16 @@
+//! Voice Mode Server Functions
+//!
+//! Speech recognition for the Voice panel; replies are streamed with
+//! `get_response` and spoken with `generate_tts`.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+
+/// Transcribe a recorded utterance from the microphone
+#[server]
+pub async fn transcribe_speech(data: Vec<u8>) -> Result<String, ServerFnError> {
+    crate::core::transcription::transcribe_utterance(data)
+        .await
+        .map_err(|e| app_error(&format!("Speech recognition failed: {}", e)))
+}
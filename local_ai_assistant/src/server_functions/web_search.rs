@@ -0,0 +1,31 @@
+//! Web Search Server Functions
+//!
+//! Configure the optional web search backend and query it for chat grounding.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::{WebSearchConfig, WebSearchResult};
+
+/// Returns the web search configuration
+#[server]
+pub async fn get_web_search_config() -> Result<WebSearchConfig, ServerFnError> {
+    Ok(crate::core::web_search::load_config())
+}
+
+/// Saves the web search configuration
+#[server]
+pub async fn save_web_search_config(config: WebSearchConfig) -> Result<(), ServerFnError> {
+    crate::core::web_search::save_config(&config)
+        .map_err(|e| app_error(&format!("Failed to save web search settings: {}", e)))
+}
+
+/// Searches the web with the configured provider
+#[server]
+pub async fn search_web(query: String) -> Result<Vec<WebSearchResult>, ServerFnError> {
+    use crate::core::web_search;
+
+    web_search::search(&query, web_search::DEFAULT_RESULT_COUNT)
+        .await
+        .map_err(|e| app_error(&e))
+}
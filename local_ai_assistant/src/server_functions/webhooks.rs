@@ -0,0 +1,29 @@
+//! Webhook Server Functions
+//!
+//! Manage outgoing webhooks from the Settings page.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use super::app_error;
+use crate::models::Webhook;
+
+/// Returns the configured webhooks
+#[server]
+pub async fn get_webhooks() -> Result<Vec<Webhook>, ServerFnError> {
+    Ok(crate::core::webhooks::list())
+}
+
+/// Replaces the configured webhooks
+#[server]
+pub async fn save_webhooks(webhooks: Vec<Webhook>) -> Result<(), ServerFnError> {
+    crate::core::webhooks::save(&webhooks)
+        .map_err(|e| app_error(&format!("Failed to save webhooks: {}", e)))
+}
+
+/// Sends a test payload to a webhook
+#[server]
+pub async fn test_webhook(webhook: Webhook) -> Result<String, ServerFnError> {
+    crate::core::webhooks::send_test(&webhook)
+        .await
+        .map_err(|e| app_error(&format!("Test delivery failed: {}", e)))
+}
@@ -9,7 +9,7 @@ use crate::core::{embedding, vector_store};
 
 /// Index documents from a directory
 pub async fn index_directory(dir: &Path) -> Result<usize> {
-    println!("Indexing documents from: {:?}", dir);
+    tracing::info!("Indexing documents from: {:?}", dir);
 
     // TODO: Read markdown files and chunk them
     // TODO: Generate embeddings
@@ -20,7 +20,7 @@ pub async fn index_directory(dir: &Path) -> Result<usize> {
 
 /// Search for relevant documents
 pub async fn search(query: &str) -> Result<Vec<Document>> {
-    println!("Searching for: {}", query);
+    tracing::info!("Searching for: {}", query);
 
     // Generate query embedding
     let query_embedding = embedding::embed_text(query).await?;
@@ -1,90 +1,48 @@
 //! SQLite Database Module
 //!
-//! Handles persistent storage for sessions and messages.
+//! Handles persistent storage for sessions and messages, the index of the
+//! asset library, the trash, and the content calendar. Chat text,
+//! attachments, memories and comparisons go through `core::encryption`,
+//! which encrypts them when history encryption is on.
 
+use std::collections::HashMap;
 use std::sync::OnceLock;
 use tokio::sync::Mutex;
 use anyhow::Result;
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
-use crate::models::{Session, ChatMessage, ChatRole};
+use crate::core::attachments;
+use crate::core::encryption::{self, EncryptionConfig};
+use crate::models::{Asset, AssetKind, AssetOrigin, TrashItem, TrashKind, message_excerpt, Session, SessionOverrides, SessionSummary, ChatMessage, ChatRole, Codebase, Comparison, CompareVote, FeedbackRating, MemoryEntry, MessageFeedback, Publication, PublicationStatus, PublishPlatform};
 
 static DATABASE: OnceLock<Mutex<Connection>> = OnceLock::new();
 
-/// Get the project root directory (where Cargo.toml is)
-fn get_project_root() -> std::path::PathBuf {
-    // Try to find the project root by looking for Cargo.toml
-    let mut path = std::env::current_exe()
-        .unwrap_or_else(|_| std::path::PathBuf::from("."));
-
-    // Walk up the directory tree looking for Cargo.toml or use a fixed path
-    for _ in 0..10 {
-        if path.join("Cargo.toml").exists() {
-            return path;
-        }
-        if let Some(parent) = path.parent() {
-            path = parent.to_path_buf();
-        } else {
-            break;
-        }
-    }
-
-    // Fallback to the local_ai_assistant project directory
-    let fallback = std::path::PathBuf::from("/Volumes/UltraDisk/Dev2/crypto-projects/AI-test/local_ai_assistant");
-    if fallback.exists() {
-        return fallback;
-    }
-
-    // Last resort: use current directory
-    std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))
+/// Directory holding the SQLite database and other local data
+pub fn get_data_dir() -> std::path::PathBuf {
+    crate::core::paths::data_dir()
 }
 
 /// Initialize the database
 pub async fn init() -> Result<()> {
-    // Use project root for data directory
-    let project_root = get_project_root();
-    let data_dir = project_root.join("data");
+    let data_dir = get_data_dir();
 
     // Create data directory if it doesn't exist
     std::fs::create_dir_all(&data_dir)?;
 
     let db_path = data_dir.join("assistant.db");
-    println!("Initializing database: {:?}", db_path);
+    tracing::info!("Initializing database: {:?}", db_path);
 
-    let conn = Connection::open(db_path)?;
+    let mut conn = Connection::open(db_path)?;
 
-    // Create tables
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS sessions (
-            id TEXT PRIMARY KEY,
-            title TEXT NOT NULL,
-            created_at TEXT NOT NULL,
-            updated_at TEXT NOT NULL
-        )",
-        [],
-    )?;
+    let version = super::migrations::run(&mut conn)?;
+    tracing::info!("Database schema at version {}", version);
 
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS messages (
-            id TEXT PRIMARY KEY,
-            session_id TEXT NOT NULL,
-            role TEXT NOT NULL,
-            content TEXT NOT NULL,
-            created_at TEXT NOT NULL,
-            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
-        )",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_messages_session ON messages(session_id)",
-        [],
-    )?;
+    encryption::set_enabled(read_encryption_config(&conn)?.is_some());
 
     DATABASE.get_or_init(|| Mutex::new(conn));
-    println!("Database initialized successfully");
+    tracing::info!("Database initialized successfully");
     Ok(())
 }
 
@@ -98,48 +56,97 @@ fn get_db() -> Option<&'static Mutex<Connection>> {
     DATABASE.get()
 }
 
+/// Encrypts chat text for storage when history encryption is on
+fn seal(text: &str) -> Result<String> {
+    encryption::seal(text).map_err(anyhow::Error::msg)
+}
+
+/// Reads a chat text column, decrypting it when it was stored encrypted
+fn get_text(row: &rusqlite::Row, index: usize) -> rusqlite::Result<String> {
+    let text: String = row.get(index)?;
+    encryption::open(&text)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, e.into()))
+}
+
+fn get_optional_text(row: &rusqlite::Row, index: usize) -> rusqlite::Result<Option<String>> {
+    match row.get::<_, Option<String>>(index)? {
+        Some(_) => get_text(row, index).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Fails while encrypted chat history is locked, rather than reading
+/// nothing
+fn ensure_unlocked() -> Result<()> {
+    encryption::ensure_unlocked().map_err(anyhow::Error::msg)
+}
+
 /// Create a new session
 pub async fn create_session(session: &Session) -> Result<()> {
     let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
     let conn = db.lock().await;
 
     conn.execute(
-        "INSERT INTO sessions (id, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
-        [
-            &session.id.to_string(),
-            &session.title,
-            &session.created_at.to_rfc3339(),
-            &session.updated_at.to_rfc3339(),
+        "INSERT INTO sessions (id, title, created_at, updated_at, folder, pinned, archived, profile_id, summary, tags, topic, overrides) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        rusqlite::params![
+            session.id.to_string(),
+            seal(&session.title)?,
+            session.created_at.to_rfc3339(),
+            session.updated_at.to_rfc3339(),
+            session.folder,
+            session.pinned,
+            session.archived,
+            crate::core::profile::active_profile_id(),
+            session.summary.as_ref().map(serde_json::to_string).transpose()?.map(|json| seal(&json)).transpose()?,
+            seal(&serde_json::to_string(&session.tags)?)?,
+            session.topic.as_deref().map(seal).transpose()?,
+            serde_json::to_string(&session.overrides)?,
         ],
     )?;
 
     Ok(())
 }
 
-/// Get all sessions ordered by updated_at desc
+/// Get all sessions of the active profile, pinned first, then ordered by updated_at desc
 pub async fn get_all_sessions() -> Result<Vec<Session>> {
+    get_profile_sessions(&crate::core::profile::active_profile_id()).await
+}
+
+/// Get all sessions of a profile, in the same order as [`get_all_sessions`]
+pub async fn get_profile_sessions(profile_id: &str) -> Result<Vec<Session>> {
+    ensure_unlocked()?;
     let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
     let conn = db.lock().await;
 
     let mut stmt = conn.prepare(
-        "SELECT id, title, created_at, updated_at FROM sessions ORDER BY updated_at DESC"
+        "SELECT id, title, created_at, updated_at, folder, pinned, archived, summary, tags, topic, overrides FROM sessions WHERE profile_id = ?1 AND deleted_at IS NULL ORDER BY pinned DESC, updated_at DESC"
     )?;
 
-    let sessions = stmt.query_map([], |row| {
+    let sessions = stmt.query_map([profile_id], |row| {
         let id_str: String = row.get(0)?;
-        let title: String = row.get(1)?;
+        let title = get_text(row, 1)?;
         let created_at_str: String = row.get(2)?;
         let updated_at_str: String = row.get(3)?;
+        let folder: Option<String> = row.get(4)?;
+        let pinned: bool = row.get(5)?;
+        let archived: bool = row.get(6)?;
+        let summary_json = get_optional_text(row, 7)?;
+        let tags_json = get_optional_text(row, 8)?;
+        let topic = get_optional_text(row, 9)?;
+        let overrides_json: Option<String> = row.get(10)?;
 
-        Ok((id_str, title, created_at_str, updated_at_str))
+        Ok((id_str, title, created_at_str, updated_at_str, folder, pinned, archived, summary_json, tags_json, topic, overrides_json))
     })?
     .filter_map(|r| r.ok())
-    .filter_map(|(id_str, title, created_at_str, updated_at_str)| {
+    .filter_map(|(id_str, title, created_at_str, updated_at_str, folder, pinned, archived, summary_json, tags_json, topic, overrides_json)| {
         let id = Uuid::parse_str(&id_str).ok()?;
         let created_at = DateTime::parse_from_rfc3339(&created_at_str).ok()?.with_timezone(&Utc);
         let updated_at = DateTime::parse_from_rfc3339(&updated_at_str).ok()?.with_timezone(&Utc);
+        let summary = summary_json.and_then(|json| serde_json::from_str(&json).ok());
+        let tags = tags_json.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default();
+        let overrides = overrides_json.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default();
 
-        Some(Session { id, title, created_at, updated_at })
+        Some(Session { id, title, created_at, updated_at, folder, pinned, archived, summary, tags, topic, overrides })
     })
     .collect();
 
@@ -154,7 +161,7 @@ pub async fn update_session_title(session_id: Uuid, title: &str) -> Result<()> {
     conn.execute(
         "UPDATE sessions SET title = ?1, updated_at = ?2 WHERE id = ?3",
         [
-            title,
+            &seal(title)?,
             &Utc::now().to_rfc3339(),
             &session_id.to_string(),
         ],
@@ -163,6 +170,123 @@ pub async fn update_session_title(session_id: Uuid, title: &str) -> Result<()> {
     Ok(())
 }
 
+/// Move a session into a folder (None removes it from any folder)
+pub async fn set_session_folder(session_id: Uuid, folder: Option<&str>) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        "UPDATE sessions SET folder = ?1 WHERE id = ?2",
+        rusqlite::params![folder, session_id.to_string()],
+    )?;
+
+    Ok(())
+}
+
+/// Pin or unpin a session
+pub async fn set_session_pinned(session_id: Uuid, pinned: bool) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        "UPDATE sessions SET pinned = ?1 WHERE id = ?2",
+        rusqlite::params![pinned, session_id.to_string()],
+    )?;
+
+    Ok(())
+}
+
+/// Store a session's summary (None removes it)
+pub async fn set_session_summary(session_id: Uuid, summary: Option<&SessionSummary>) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        "UPDATE sessions SET summary = ?1 WHERE id = ?2",
+        rusqlite::params![
+            summary.map(serde_json::to_string).transpose()?.map(|json| seal(&json)).transpose()?,
+            session_id.to_string(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Store a session's model, persona and temperature overrides. Returns
+/// false if the session has not been saved yet.
+pub async fn set_session_overrides(session_id: Uuid, overrides: &SessionOverrides) -> Result<bool> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let updated = conn.execute(
+        "UPDATE sessions SET overrides = ?1 WHERE id = ?2",
+        rusqlite::params![serde_json::to_string(overrides)?, session_id.to_string()],
+    )?;
+
+    Ok(updated > 0)
+}
+
+/// A session's overrides; the defaults if it has none or doesn't exist
+pub async fn get_session_overrides(session_id: Uuid) -> Result<SessionOverrides> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let json: Option<String> = conn
+        .query_row(
+            "SELECT overrides FROM sessions WHERE id = ?1",
+            [session_id.to_string()],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+
+    Ok(json.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default())
+}
+
+/// Store the topic tags of a session
+pub async fn set_session_tags(session_id: Uuid, tags: &[String]) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        "UPDATE sessions SET tags = ?1 WHERE id = ?2",
+        rusqlite::params![seal(&serde_json::to_string(tags)?)?, session_id.to_string()],
+    )?;
+
+    Ok(())
+}
+
+/// Store the topics of sessions clustered together (None = no cluster)
+pub async fn set_session_topics(topics: &[(Uuid, Option<String>)]) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let mut conn = db.lock().await;
+
+    let tx = conn.transaction()?;
+    for (session_id, topic) in topics {
+        tx.execute(
+            "UPDATE sessions SET topic = ?1 WHERE id = ?2",
+            rusqlite::params![topic.as_deref().map(seal).transpose()?, session_id.to_string()],
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Archive or unarchive a session
+pub async fn set_session_archived(session_id: Uuid, archived: bool) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    // Archived sessions are never pinned
+    conn.execute(
+        "UPDATE sessions SET archived = ?1, pinned = CASE WHEN ?1 THEN 0 ELSE pinned END WHERE id = ?2",
+        rusqlite::params![archived, session_id.to_string()],
+    )?;
+
+    Ok(())
+}
+
 /// Delete a session and all its messages
 pub async fn delete_session(session_id: Uuid) -> Result<()> {
     let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
@@ -194,14 +318,27 @@ pub async fn save_message(message: &ChatMessage) -> Result<()> {
         ChatRole::System => "system",
     };
 
+    let feedback = message.feedback.as_ref();
+    let attachments = if message.attachments.is_empty() {
+        None
+    } else {
+        Some(seal(&serde_json::to_string(&message.attachments)?)?)
+    };
+    let generation = message.generation.as_ref().map(serde_json::to_string).transpose()?;
     conn.execute(
-        "INSERT OR REPLACE INTO messages (id, session_id, role, content, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-        [
-            &message.id.to_string(),
-            &message.session_id.to_string(),
+        "INSERT OR REPLACE INTO messages (id, session_id, role, content, created_at, feedback_rating, feedback_comment, feedback_at, attachments, generation_stats)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        rusqlite::params![
+            message.id.to_string(),
+            message.session_id.to_string(),
             role_str,
-            &message.content,
-            &message.created_at.to_rfc3339(),
+            seal(&message.content)?,
+            message.created_at.to_rfc3339(),
+            feedback.map(|f| f.rating.as_str()),
+            feedback.and_then(|f| f.comment.as_deref()).map(seal).transpose()?,
+            feedback.map(|f| f.created_at.to_rfc3339()),
+            attachments,
+            generation,
         ],
     )?;
 
@@ -219,24 +356,47 @@ pub async fn save_message(message: &ChatMessage) -> Result<()> {
 
 /// Get all messages for a session
 pub async fn get_session_messages(session_id: Uuid) -> Result<Vec<ChatMessage>> {
+    ensure_unlocked()?;
     let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
     let conn = db.lock().await;
 
     let mut stmt = conn.prepare(
-        "SELECT id, session_id, role, content, created_at FROM messages WHERE session_id = ?1 ORDER BY created_at ASC"
+        "SELECT id, session_id, role, content, created_at, feedback_rating, feedback_comment, feedback_at, attachments, generation_stats
+         FROM messages WHERE session_id = ?1 AND deleted_at IS NULL ORDER BY created_at ASC"
     )?;
 
     let messages = stmt.query_map([&session_id.to_string()], |row| {
         let id_str: String = row.get(0)?;
         let session_id_str: String = row.get(1)?;
         let role_str: String = row.get(2)?;
-        let content: String = row.get(3)?;
+        let content = get_text(row, 3)?;
         let created_at_str: String = row.get(4)?;
+        let feedback_rating: Option<String> = row.get(5)?;
+        let feedback_comment = get_optional_text(row, 6)?;
+        let feedback_at: Option<String> = row.get(7)?;
+        let attachments = get_optional_text(row, 8)?;
+        let generation: Option<String> = row.get(9)?;
+
+        let feedback = feedback_rating
+            .and_then(|r| FeedbackRating::parse(&r))
+            .map(|rating| MessageFeedback {
+                rating,
+                comment: feedback_comment,
+                created_at: feedback_at
+                    .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+                    .map(|at| at.with_timezone(&Utc))
+                    .unwrap_or_else(Utc::now),
+            });
 
-        Ok((id_str, session_id_str, role_str, content, created_at_str))
+        let attachments = attachments
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        let generation = generation.and_then(|json| serde_json::from_str(&json).ok());
+
+        Ok((id_str, session_id_str, role_str, content, created_at_str, feedback, attachments, generation))
     })?
     .filter_map(|r| r.ok())
-    .filter_map(|(id_str, session_id_str, role_str, content, created_at_str)| {
+    .filter_map(|(id_str, session_id_str, role_str, content, created_at_str, feedback, attachments, generation)| {
         let id = Uuid::parse_str(&id_str).ok()?;
         let session_id = Uuid::parse_str(&session_id_str).ok()?;
         let role = match role_str.as_str() {
@@ -247,9 +407,794 @@ pub async fn get_session_messages(session_id: Uuid) -> Result<Vec<ChatMessage>>
         };
         let created_at = DateTime::parse_from_rfc3339(&created_at_str).ok()?.with_timezone(&Utc);
 
-        Some(ChatMessage { id, session_id, role, content, created_at })
+        Some(ChatMessage { id, session_id, role, content, created_at, feedback, attachments, generation })
     })
     .collect();
 
     Ok(messages)
 }
+
+/// Set or clear the user's feedback on a message
+pub async fn set_message_feedback(message_id: Uuid, feedback: Option<&MessageFeedback>) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        "UPDATE messages SET feedback_rating = ?1, feedback_comment = ?2, feedback_at = ?3 WHERE id = ?4",
+        rusqlite::params![
+            feedback.map(|f| f.rating.as_str()),
+            feedback.and_then(|f| f.comment.as_deref()).map(seal).transpose()?,
+            feedback.map(|f| f.created_at.to_rfc3339()),
+            message_id.to_string(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// IDs of the sessions that contain rated messages
+pub async fn get_sessions_with_feedback() -> Result<Vec<Uuid>> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let mut stmt = conn.prepare("SELECT DISTINCT session_id FROM messages WHERE feedback_rating IS NOT NULL AND deleted_at IS NULL")?;
+    let ids = stmt.query_map([], |row| row.get::<_, String>(0))?
+        .filter_map(|r| r.ok())
+        .filter_map(|id| Uuid::parse_str(&id).ok())
+        .collect();
+
+    Ok(ids)
+}
+
+/// Save a memory with its embedding for the active profile
+pub async fn save_memory(memory: &MemoryEntry, embedding: &[f32]) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let blob: Vec<u8> = embedding.iter().flat_map(|v| v.to_le_bytes()).collect();
+    conn.execute(
+        "INSERT INTO memories (id, profile_id, content, embedding, source_session_id, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            memory.id.to_string(),
+            crate::core::profile::active_profile_id(),
+            seal(&memory.content)?,
+            blob,
+            memory.source_session_id.map(|id| id.to_string()),
+            memory.created_at.to_rfc3339(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Get all memories of the active profile with their embeddings, newest first
+pub async fn get_memories_with_embeddings() -> Result<Vec<(MemoryEntry, Vec<f32>)>> {
+    ensure_unlocked()?;
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, content, embedding, source_session_id, created_at FROM memories WHERE profile_id = ?1 ORDER BY created_at DESC"
+    )?;
+
+    let memories = stmt.query_map([crate::core::profile::active_profile_id()], |row| {
+        let id_str: String = row.get(0)?;
+        let content = get_text(row, 1)?;
+        let blob: Vec<u8> = row.get(2)?;
+        let session_str: Option<String> = row.get(3)?;
+        let created_at_str: String = row.get(4)?;
+
+        Ok((id_str, content, blob, session_str, created_at_str))
+    })?
+    .filter_map(|r| r.ok())
+    .filter_map(|(id_str, content, blob, session_str, created_at_str)| {
+        let id = Uuid::parse_str(&id_str).ok()?;
+        let source_session_id = session_str.and_then(|s| Uuid::parse_str(&s).ok());
+        let created_at = DateTime::parse_from_rfc3339(&created_at_str).ok()?.with_timezone(&Utc);
+        let embedding = blob
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+
+        Some((MemoryEntry { id, content, source_session_id, created_at }, embedding))
+    })
+    .collect();
+
+    Ok(memories)
+}
+
+/// IDs and contents of the memories of every profile, for re-embedding
+pub async fn get_all_memory_contents() -> Result<Vec<(Uuid, String)>> {
+    ensure_unlocked()?;
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let mut stmt = conn.prepare("SELECT id, content FROM memories")?;
+    let memories = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, get_text(row, 1)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .into_iter()
+        .filter_map(|(id, content)| Some((Uuid::parse_str(&id).ok()?, content)))
+        .collect();
+
+    Ok(memories)
+}
+
+/// Replace the embeddings of memories, all in one transaction
+pub async fn set_memory_embeddings(embeddings: &[(Uuid, Vec<f32>)]) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let mut conn = db.lock().await;
+
+    let tx = conn.transaction()?;
+    for (id, embedding) in embeddings {
+        let blob: Vec<u8> = embedding.iter().flat_map(|v| v.to_le_bytes()).collect();
+        tx.execute("UPDATE memories SET embedding = ?1 WHERE id = ?2", rusqlite::params![blob, id.to_string()])?;
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Delete a single memory
+pub async fn delete_memory(memory_id: Uuid) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute("DELETE FROM memories WHERE id = ?1", [&memory_id.to_string()])?;
+
+    Ok(())
+}
+
+/// Delete all memories of the active profile
+pub async fn clear_memories() -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute("DELETE FROM memories WHERE profile_id = ?1", [crate::core::profile::active_profile_id()])?;
+
+    Ok(())
+}
+
+/// Save a model comparison for the active profile, replacing an earlier
+/// version of it
+pub async fn save_comparison(comparison: &Comparison) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO comparisons (id, profile_id, prompt, left_answer, right_answer, vote, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            comparison.id.to_string(),
+            crate::core::profile::active_profile_id(),
+            seal(&comparison.prompt)?,
+            seal(&serde_json::to_string(&comparison.left)?)?,
+            seal(&serde_json::to_string(&comparison.right)?)?,
+            comparison.vote.map(|v| v.as_str()),
+            comparison.created_at.to_rfc3339(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Get all model comparisons of the active profile, newest first
+pub async fn get_comparisons() -> Result<Vec<Comparison>> {
+    ensure_unlocked()?;
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, prompt, left_answer, right_answer, vote, created_at FROM comparisons WHERE profile_id = ?1 ORDER BY created_at DESC"
+    )?;
+
+    let comparisons = stmt.query_map([crate::core::profile::active_profile_id()], |row| {
+        let id_str: String = row.get(0)?;
+        let prompt = get_text(row, 1)?;
+        let left_json = get_text(row, 2)?;
+        let right_json = get_text(row, 3)?;
+        let vote_str: Option<String> = row.get(4)?;
+        let created_at_str: String = row.get(5)?;
+
+        Ok((id_str, prompt, left_json, right_json, vote_str, created_at_str))
+    })?
+    .filter_map(|r| r.ok())
+    .filter_map(|(id_str, prompt, left_json, right_json, vote_str, created_at_str)| {
+        let id = Uuid::parse_str(&id_str).ok()?;
+        let left = serde_json::from_str(&left_json).ok()?;
+        let right = serde_json::from_str(&right_json).ok()?;
+        let vote = vote_str.as_deref().and_then(CompareVote::parse);
+        let created_at = DateTime::parse_from_rfc3339(&created_at_str).ok()?.with_timezone(&Utc);
+
+        Some(Comparison { id, prompt, left, right, vote, created_at })
+    })
+    .collect();
+
+    Ok(comparisons)
+}
+
+/// Save a codebase of the active profile, replacing an earlier version of it
+pub async fn save_codebase(codebase: &Codebase) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO codebases (id, profile_id, name, root, file_count, chunk_count, indexed_at, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            codebase.id.to_string(),
+            crate::core::profile::active_profile_id(),
+            codebase.name,
+            codebase.root,
+            codebase.file_count as i64,
+            codebase.chunk_count as i64,
+            codebase.indexed_at.map(|at| at.to_rfc3339()),
+            codebase.created_at.to_rfc3339(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Get the codebases of the active profile, by name
+pub async fn get_codebases() -> Result<Vec<Codebase>> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, name, root, file_count, chunk_count, indexed_at, created_at FROM codebases WHERE profile_id = ?1 ORDER BY name"
+    )?;
+
+    let codebases = stmt.query_map([crate::core::profile::active_profile_id()], |row| {
+        let id_str: String = row.get(0)?;
+        let name: String = row.get(1)?;
+        let root: String = row.get(2)?;
+        let file_count: i64 = row.get(3)?;
+        let chunk_count: i64 = row.get(4)?;
+        let indexed_at_str: Option<String> = row.get(5)?;
+        let created_at_str: String = row.get(6)?;
+
+        Ok((id_str, name, root, file_count, chunk_count, indexed_at_str, created_at_str))
+    })?
+    .filter_map(|r| r.ok())
+    .filter_map(|(id_str, name, root, file_count, chunk_count, indexed_at_str, created_at_str)| {
+        let id = Uuid::parse_str(&id_str).ok()?;
+        let indexed_at = indexed_at_str
+            .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+            .map(|at| at.with_timezone(&Utc));
+        let created_at = DateTime::parse_from_rfc3339(&created_at_str).ok()?.with_timezone(&Utc);
+
+        Some(Codebase {
+            id,
+            name,
+            root,
+            file_count: file_count as usize,
+            chunk_count: chunk_count as usize,
+            indexed_at,
+            created_at,
+        })
+    })
+    .collect();
+
+    Ok(codebases)
+}
+
+/// Delete a codebase of the active profile
+pub async fn delete_codebase(id: Uuid) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        "DELETE FROM codebases WHERE id = ?1 AND profile_id = ?2",
+        rusqlite::params![id.to_string(), crate::core::profile::active_profile_id()],
+    )?;
+
+    Ok(())
+}
+
+/// Store the tags of a context document, by its path in the context folder
+pub async fn set_document_tags(path: &str, tags: &[String]) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    if tags.is_empty() {
+        conn.execute(
+            "DELETE FROM document_tags WHERE profile_id = ?1 AND path = ?2",
+            rusqlite::params![crate::core::profile::active_profile_id(), path],
+        )?;
+    } else {
+        conn.execute(
+            "INSERT OR REPLACE INTO document_tags (profile_id, path, tags) VALUES (?1, ?2, ?3)",
+            rusqlite::params![crate::core::profile::active_profile_id(), path, serde_json::to_string(tags)?],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Get the tags of the active profile's context documents, by path
+pub async fn get_document_tags() -> Result<HashMap<String, Vec<String>>> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let mut stmt = conn.prepare("SELECT path, tags FROM document_tags WHERE profile_id = ?1")?;
+    let tags = stmt.query_map([crate::core::profile::active_profile_id()], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?
+    .filter_map(|r| r.ok())
+    .filter_map(|(path, json)| Some((path, serde_json::from_str(&json).ok()?)))
+    .collect();
+
+    Ok(tags)
+}
+
+/// Add an asset to the active profile's library. Returns false when its
+/// file is already in the library.
+pub async fn save_asset(asset: &Asset) -> Result<bool> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO assets (id, profile_id, kind, path, prompt, origin, origin_id, tags, size_bytes, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        rusqlite::params![
+            asset.id.to_string(),
+            crate::core::profile::active_profile_id(),
+            asset.kind.id(),
+            asset.path,
+            asset.prompt,
+            asset.origin.id(),
+            asset.origin_id,
+            serde_json::to_string(&asset.tags)?,
+            asset.size_bytes as i64,
+            asset.created_at.to_rfc3339(),
+        ],
+    )?;
+
+    Ok(inserted > 0)
+}
+
+/// Get the active profile's assets, newest first
+pub async fn get_assets() -> Result<Vec<Asset>> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, path, prompt, origin, origin_id, tags, size_bytes, created_at FROM assets WHERE profile_id = ?1 AND deleted_at IS NULL ORDER BY created_at DESC"
+    )?;
+    let assets = stmt.query_map([crate::core::profile::active_profile_id()], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, Option<String>>(5)?,
+            row.get::<_, String>(6)?,
+            row.get::<_, i64>(7)?,
+            row.get::<_, String>(8)?,
+        ))
+    })?
+    .filter_map(|r| r.ok())
+    .filter_map(|(id, kind, path, prompt, origin, origin_id, tags, size_bytes, created_at)| {
+        Some(Asset {
+            id: Uuid::parse_str(&id).ok()?,
+            kind: AssetKind::from_id(&kind)?,
+            path,
+            prompt,
+            origin: AssetOrigin::from_id(&origin).unwrap_or(AssetOrigin::Imported),
+            origin_id,
+            tags: serde_json::from_str(&tags).unwrap_or_default(),
+            size_bytes: size_bytes.max(0) as u64,
+            created_at: DateTime::parse_from_rfc3339(&created_at).ok()?.with_timezone(&Utc),
+        })
+    })
+    .collect();
+
+    Ok(assets)
+}
+
+/// Replace the tags of an asset of the active profile
+pub async fn set_asset_tags(id: Uuid, tags: &[String]) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        "UPDATE assets SET tags = ?1 WHERE id = ?2 AND profile_id = ?3",
+        rusqlite::params![serde_json::to_string(tags)?, id.to_string(), crate::core::profile::active_profile_id()],
+    )?;
+
+    Ok(())
+}
+
+/// Remove assets of the active profile from the library
+pub async fn delete_assets(ids: &[Uuid]) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    for id in ids {
+        conn.execute(
+            "DELETE FROM assets WHERE id = ?1 AND profile_id = ?2",
+            rusqlite::params![id.to_string(), crate::core::profile::active_profile_id()],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Move a session and its messages to the trash
+pub async fn trash_session(session_id: Uuid) -> Result<()> {
+    set_deleted_at("sessions", &session_id.to_string(), Some(Utc::now())).await
+}
+
+/// Move a single message to the trash
+pub async fn trash_message(message_id: Uuid) -> Result<()> {
+    set_deleted_at("messages", &message_id.to_string(), Some(Utc::now())).await
+}
+
+/// Move an asset to the trash; its file stays until it is purged
+pub async fn trash_asset(asset_id: Uuid) -> Result<()> {
+    set_deleted_at("assets", &asset_id.to_string(), Some(Utc::now())).await
+}
+
+/// Take a session, message or asset back out of the trash
+pub async fn restore_trashed(kind: TrashKind, id: &str) -> Result<()> {
+    match kind {
+        TrashKind::Session => set_deleted_at("sessions", id, None).await,
+        TrashKind::Message => set_deleted_at("messages", id, None).await,
+        TrashKind::Asset => set_deleted_at("assets", id, None).await,
+        TrashKind::Document => Err(anyhow::anyhow!("Documents are restored from the trash folder")),
+    }
+}
+
+async fn set_deleted_at(table: &str, id: &str, deleted_at: Option<DateTime<Utc>>) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        &format!("UPDATE {} SET deleted_at = ?1 WHERE id = ?2", table),
+        rusqlite::params![deleted_at.map(|at| at.to_rfc3339()), id],
+    )?;
+
+    Ok(())
+}
+
+/// Record a context document moved to the trash folder
+pub async fn save_trashed_document(id: Uuid, filename: &str, trash_path: &str) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        "INSERT INTO trashed_documents (id, profile_id, filename, trash_path, deleted_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+            id.to_string(),
+            crate::core::profile::active_profile_id(),
+            filename,
+            trash_path,
+            Utc::now().to_rfc3339(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Forget a trashed context document after it was restored or purged
+pub async fn delete_trashed_document(id: &str) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute("DELETE FROM trashed_documents WHERE id = ?1", [id])?;
+
+    Ok(())
+}
+
+/// Delete a trashed message for good
+pub async fn delete_message(message_id: &str) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute("DELETE FROM messages WHERE id = ?1", [message_id])?;
+
+    Ok(())
+}
+
+/// Everything in the active profile's trash, most recently deleted first.
+/// Messages of a trashed session are part of the session rather than
+/// listed on their own.
+pub async fn get_trash() -> Result<Vec<TrashItem>> {
+    ensure_unlocked()?;
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+    let profile_id = crate::core::profile::active_profile_id();
+
+    // (kind, id, title, detail, path, deleted_at)
+    type Row = (TrashKind, String, String, Option<String>, Option<String>, String);
+    let mut rows: Vec<Row> = Vec::new();
+
+    let mut stmt = conn.prepare(
+        "SELECT id, title, deleted_at FROM sessions WHERE profile_id = ?1 AND deleted_at IS NOT NULL"
+    )?;
+    rows.extend(stmt.query_map([&profile_id], |row| {
+        Ok((TrashKind::Session, row.get(0)?, get_text(row, 1)?, None, None, row.get(2)?))
+    })?.filter_map(|r| r.ok()));
+
+    let mut stmt = conn.prepare(
+        "SELECT m.id, m.content, s.title, m.deleted_at FROM messages m JOIN sessions s ON s.id = m.session_id
+         WHERE s.profile_id = ?1 AND s.deleted_at IS NULL AND m.deleted_at IS NOT NULL"
+    )?;
+    rows.extend(stmt.query_map([&profile_id], |row| {
+        let content = get_text(row, 1)?;
+        Ok((TrashKind::Message, row.get(0)?, message_excerpt(&content), Some(get_text(row, 2)?), None, row.get(3)?))
+    })?.filter_map(|r| r.ok()));
+
+    let mut stmt = conn.prepare(
+        "SELECT id, filename, trash_path, deleted_at FROM trashed_documents WHERE profile_id = ?1"
+    )?;
+    rows.extend(stmt.query_map([&profile_id], |row| {
+        Ok((TrashKind::Document, row.get(0)?, row.get(1)?, None, Some(row.get(2)?), row.get(3)?))
+    })?.filter_map(|r| r.ok()));
+
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, path, prompt, origin, deleted_at FROM assets WHERE profile_id = ?1 AND deleted_at IS NOT NULL"
+    )?;
+    rows.extend(stmt.query_map([&profile_id], |row| {
+        let kind: String = row.get(1)?;
+        let path: String = row.get(2)?;
+        let prompt: String = row.get(3)?;
+        let origin: String = row.get(4)?;
+        let asset = Asset::new(
+            AssetKind::from_id(&kind).unwrap_or(AssetKind::Image),
+            path.clone(),
+            prompt,
+            AssetOrigin::from_id(&origin).unwrap_or(AssetOrigin::Imported),
+        );
+        let detail = format!("{} from {}", asset.kind.display_name(), asset.origin.display_name());
+        Ok((TrashKind::Asset, row.get(0)?, asset.title(), Some(detail), Some(path), row.get(5)?))
+    })?.filter_map(|r| r.ok()));
+
+    let mut items: Vec<TrashItem> = rows.into_iter()
+        .filter_map(|(kind, id, title, detail, path, deleted_at)| {
+            let deleted_at = DateTime::parse_from_rfc3339(&deleted_at).ok()?.with_timezone(&Utc);
+            Some(TrashItem { kind, id, title, detail, path, deleted_at })
+        })
+        .collect();
+    items.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+
+    Ok(items)
+}
+
+const PUBLICATION_COLUMNS: &str = "id, title, status, platform, job_id, date, url, notes, error, created_at";
+
+fn read_publication(row: &rusqlite::Row) -> rusqlite::Result<Option<Publication>> {
+    let id: String = row.get(0)?;
+    let status: String = row.get(2)?;
+    let platform: String = row.get(3)?;
+    let job_id: Option<String> = row.get(4)?;
+    let date: String = row.get(5)?;
+    let created_at: String = row.get(9)?;
+
+    let parse_time = |at: &str| DateTime::parse_from_rfc3339(at).ok().map(|at| at.with_timezone(&Utc));
+    let (Ok(id), Some(status), Some(date), Some(created_at)) = (
+        Uuid::parse_str(&id),
+        PublicationStatus::from_id(&status),
+        parse_time(&date),
+        parse_time(&created_at),
+    ) else {
+        return Ok(None);
+    };
+
+    Ok(Some(Publication {
+        id,
+        title: row.get(1)?,
+        status,
+        platform: PublishPlatform::from_id(&platform),
+        job_id: job_id.and_then(|id| Uuid::parse_str(&id).ok()),
+        date,
+        url: row.get(6)?,
+        notes: row.get(7)?,
+        error: row.get(8)?,
+        created_at,
+    }))
+}
+
+/// Add or replace a content calendar entry of the active profile
+pub async fn save_publication(publication: &Publication) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        &format!("INSERT OR REPLACE INTO publications ({}, profile_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)", PUBLICATION_COLUMNS),
+        rusqlite::params![
+            publication.id.to_string(),
+            publication.title,
+            publication.status.id(),
+            publication.platform.id(),
+            publication.job_id.map(|id| id.to_string()),
+            publication.date.to_rfc3339(),
+            publication.url,
+            publication.notes,
+            publication.error,
+            publication.created_at.to_rfc3339(),
+            crate::core::profile::active_profile_id(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Get the active profile's content calendar, by date
+pub async fn get_publications() -> Result<Vec<Publication>> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM publications WHERE profile_id = ?1 ORDER BY date", PUBLICATION_COLUMNS
+    ))?;
+    let publications = stmt.query_map([crate::core::profile::active_profile_id()], read_publication)?
+        .filter_map(|r| r.ok().flatten())
+        .collect();
+
+    Ok(publications)
+}
+
+/// Get the scheduled entries of every profile, for the publishing scheduler
+pub async fn get_scheduled_publications() -> Result<Vec<Publication>> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM publications WHERE status = ?1 ORDER BY date", PUBLICATION_COLUMNS
+    ))?;
+    let publications = stmt.query_map([PublicationStatus::Scheduled.id()], read_publication)?
+        .filter_map(|r| r.ok().flatten())
+        .collect();
+
+    Ok(publications)
+}
+
+/// Record the outcome of publishing an entry, whichever profile it
+/// belongs to
+pub async fn set_publication_result(id: Uuid, result: &Result<String, String>) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    match result {
+        Ok(url) => conn.execute(
+            "UPDATE publications SET status = ?1, url = ?2, date = ?3, error = NULL WHERE id = ?4",
+            rusqlite::params![PublicationStatus::Published.id(), url, Utc::now().to_rfc3339(), id.to_string()],
+        )?,
+        Err(e) => conn.execute(
+            "UPDATE publications SET status = ?1, error = ?2 WHERE id = ?3",
+            rusqlite::params![PublicationStatus::Failed.id(), e, id.to_string()],
+        )?,
+    };
+
+    Ok(())
+}
+
+/// Delete a content calendar entry of the active profile
+pub async fn delete_publication(id: Uuid) -> Result<()> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+
+    conn.execute(
+        "DELETE FROM publications WHERE id = ?1 AND profile_id = ?2",
+        rusqlite::params![id.to_string(), crate::core::profile::active_profile_id()],
+    )?;
+
+    Ok(())
+}
+
+fn read_encryption_config(conn: &Connection) -> Result<Option<EncryptionConfig>> {
+    let mut stmt = conn.prepare("SELECT salt, verifier FROM encryption WHERE id = 1")?;
+    let config = stmt.query_map([], |row| Ok(EncryptionConfig { salt: row.get(0)?, verifier: row.get(1)? }))?
+        .filter_map(|r| r.ok())
+        .next();
+    Ok(config)
+}
+
+/// Salt and passphrase check of the history encryption, if it is on
+pub async fn get_encryption_config() -> Result<Option<EncryptionConfig>> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let conn = db.lock().await;
+    read_encryption_config(&conn)
+}
+
+/// Rewrites the chat text of every profile and the saved attachment texts
+/// with `recode` and stores the new encryption config (None turns
+/// encryption off), all in one transaction; the attachment files are
+/// replaced once it commits. `on_commit` runs before the database is released, so no
+/// write in between uses the old key. Returns the number of rows rewritten.
+pub async fn recode_history(
+    recode: &dyn Fn(&str) -> Result<String, String>,
+    config: Option<&EncryptionConfig>,
+    on_commit: impl FnOnce(),
+) -> Result<usize> {
+    let db = get_db().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let mut conn = db.lock().await;
+    let recode_optional = |text: Option<String>| -> Result<Option<String>> {
+        text.map(|t| recode(&t).map_err(anyhow::Error::msg)).transpose()
+    };
+
+    let tx = conn.transaction()?;
+    let mut count = 0;
+
+    let sessions: Vec<(String, String, Option<String>, Option<String>, Option<String>)> = tx
+        .prepare("SELECT id, title, summary, tags, topic FROM sessions")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (id, title, summary, tags, topic) in sessions {
+        tx.execute(
+            "UPDATE sessions SET title = ?1, summary = ?2, tags = ?3, topic = ?4 WHERE id = ?5",
+            rusqlite::params![
+                recode(&title).map_err(anyhow::Error::msg)?,
+                recode_optional(summary)?,
+                recode_optional(tags)?,
+                recode_optional(topic)?,
+                id,
+            ],
+        )?;
+        count += 1;
+    }
+
+    let messages: Vec<(String, String, Option<String>, Option<String>)> = tx
+        .prepare("SELECT id, content, attachments, feedback_comment FROM messages")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (id, content, attachments, comment) in messages {
+        tx.execute(
+            "UPDATE messages SET content = ?1, attachments = ?2, feedback_comment = ?3 WHERE id = ?4",
+            rusqlite::params![
+                recode(&content).map_err(anyhow::Error::msg)?,
+                recode_optional(attachments)?,
+                recode_optional(comment)?,
+                id,
+            ],
+        )?;
+        count += 1;
+    }
+
+    let memories: Vec<(String, String)> = tx
+        .prepare("SELECT id, content FROM memories")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (id, content) in memories {
+        tx.execute(
+            "UPDATE memories SET content = ?1 WHERE id = ?2",
+            rusqlite::params![recode(&content).map_err(anyhow::Error::msg)?, id],
+        )?;
+        count += 1;
+    }
+
+    let comparisons: Vec<(String, String, String, String)> = tx
+        .prepare("SELECT id, prompt, left_answer, right_answer FROM comparisons")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (id, prompt, left, right) in comparisons {
+        tx.execute(
+            "UPDATE comparisons SET prompt = ?1, left_answer = ?2, right_answer = ?3 WHERE id = ?4",
+            rusqlite::params![
+                recode(&prompt).map_err(anyhow::Error::msg)?,
+                recode(&left).map_err(anyhow::Error::msg)?,
+                recode(&right).map_err(anyhow::Error::msg)?,
+                id,
+            ],
+        )?;
+        count += 1;
+    }
+
+    tx.execute("DELETE FROM encryption", [])?;
+    if let Some(config) = config {
+        tx.execute(
+            "INSERT INTO encryption (id, salt, verifier, created_at) VALUES (1, ?1, ?2, ?3)",
+            rusqlite::params![config.salt, config.verifier, Utc::now().to_rfc3339()],
+        )?;
+    }
+
+    let staged = attachments::stage_recoded_texts(recode).map_err(anyhow::Error::msg)?;
+    count += staged.len();
+    if let Err(e) = tx.commit() {
+        attachments::discard_recoded_texts(staged);
+        return Err(e.into());
+    }
+    attachments::apply_recoded_texts(staged);
+    on_commit();
+
+    Ok(count)
+}
@@ -0,0 +1,487 @@
+//! SQLite Schema Migrations
+//!
+//! Versioned migrations for the session database. The applied version is
+//! tracked in SQLite's `PRAGMA user_version`; each pending migration runs in
+//! its own transaction, so a failed upgrade leaves the database at the last
+//! good version.
+//!
+//! To change the schema, append a new entry to [`MIGRATIONS`] with the next
+//! version number. Never edit a migration that has already shipped.
+
+use anyhow::{bail, Result};
+use rusqlite::Connection;
+
+/// A single schema upgrade step
+pub struct Migration {
+    /// Schema version after this migration has been applied
+    pub version: u32,
+    /// Short description for logs
+    pub description: &'static str,
+    /// Applies the migration
+    pub up: fn(&Connection) -> Result<()>,
+}
+
+/// All migrations, in ascending version order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "create sessions and messages tables",
+        up: create_initial_schema,
+    },
+    Migration {
+        version: 2,
+        description: "add session folders, pinning and archiving",
+        up: add_session_organization,
+    },
+    Migration {
+        version: 3,
+        description: "scope sessions to user profiles",
+        up: add_session_profiles,
+    },
+    Migration {
+        version: 4,
+        description: "add long-term memories",
+        up: create_memories,
+    },
+    Migration {
+        version: 5,
+        description: "add message feedback",
+        up: add_message_feedback,
+    },
+    Migration {
+        version: 6,
+        description: "add message attachments",
+        up: add_message_attachments,
+    },
+    Migration {
+        version: 7,
+        description: "add message generation stats",
+        up: add_generation_stats,
+    },
+    Migration {
+        version: 8,
+        description: "add model comparisons",
+        up: create_comparisons,
+    },
+    Migration {
+        version: 9,
+        description: "add session summaries",
+        up: add_session_summaries,
+    },
+    Migration {
+        version: 10,
+        description: "add session tags and topics",
+        up: add_session_tags,
+    },
+    Migration {
+        version: 11,
+        description: "add codebases",
+        up: create_codebases,
+    },
+    Migration {
+        version: 12,
+        description: "add document tags",
+        up: create_document_tags,
+    },
+    Migration {
+        version: 13,
+        description: "add the asset library",
+        up: create_assets,
+    },
+    Migration {
+        version: 14,
+        description: "add the trash",
+        up: add_trash,
+    },
+    Migration {
+        version: 15,
+        description: "add chat history encryption",
+        up: create_encryption,
+    },
+    Migration {
+        version: 16,
+        description: "add per-session chat overrides",
+        up: add_session_overrides,
+    },
+    Migration {
+        version: 17,
+        description: "add the content calendar",
+        up: create_publications,
+    },
+];
+
+/// Current schema version of the database
+pub fn current_version(conn: &Connection) -> Result<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Applies all pending migrations and returns the resulting schema version
+pub fn run(conn: &mut Connection) -> Result<u32> {
+    run_migrations(conn, MIGRATIONS)
+}
+
+fn run_migrations(conn: &mut Connection, migrations: &[Migration]) -> Result<u32> {
+    let mut version = current_version(conn)?;
+    let latest = migrations.last().map(|m| m.version).unwrap_or(0);
+
+    if version > latest {
+        bail!(
+            "Database schema version {} is newer than this build supports ({}). Please update the application.",
+            version, latest
+        );
+    }
+
+    let applied = version;
+    for migration in migrations.iter().filter(|m| m.version > applied) {
+        tracing::info!("Applying database migration {}: {}", migration.version, migration.description);
+
+        let tx = conn.transaction()?;
+        (migration.up)(&tx)?;
+        // PRAGMA does not accept bound parameters
+        tx.execute_batch(&format!("PRAGMA user_version = {}", migration.version))?;
+        tx.commit()?;
+
+        version = migration.version;
+    }
+
+    Ok(version)
+}
+
+/// Add a column to an existing table unless it is already present
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+        tracing::info!("Added column {}.{}", table, column);
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Migrations
+//
+// Early installs created these tables without tracking a version, so the
+// first migrations must tolerate objects that already exist.
+// ---------------------------------------------------------------------------
+
+fn create_initial_schema(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS messages (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_messages_session ON messages(session_id);",
+    )?;
+    Ok(())
+}
+
+fn add_session_organization(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "sessions", "folder", "TEXT")?;
+    add_column_if_missing(conn, "sessions", "pinned", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "sessions", "archived", "INTEGER NOT NULL DEFAULT 0")?;
+    Ok(())
+}
+
+fn add_session_profiles(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "sessions", "profile_id", "TEXT NOT NULL DEFAULT 'default'")?;
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_sessions_profile ON sessions(profile_id)")?;
+    Ok(())
+}
+
+fn create_memories(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS memories (
+            id TEXT PRIMARY KEY,
+            profile_id TEXT NOT NULL DEFAULT 'default',
+            content TEXT NOT NULL,
+            embedding BLOB NOT NULL,
+            source_session_id TEXT,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_memories_profile ON memories(profile_id);",
+    )?;
+    Ok(())
+}
+
+fn add_message_feedback(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "messages", "feedback_rating", "TEXT")?;
+    add_column_if_missing(conn, "messages", "feedback_comment", "TEXT")?;
+    add_column_if_missing(conn, "messages", "feedback_at", "TEXT")?;
+    Ok(())
+}
+
+/// Attachments are stored as a JSON array
+fn add_message_attachments(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "messages", "attachments", "TEXT")?;
+    Ok(())
+}
+
+/// Generation stats are stored as a JSON object
+fn add_generation_stats(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "messages", "generation_stats", "TEXT")?;
+    Ok(())
+}
+
+/// Both answers are stored as JSON objects
+fn create_comparisons(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS comparisons (
+            id TEXT PRIMARY KEY,
+            profile_id TEXT NOT NULL DEFAULT 'default',
+            prompt TEXT NOT NULL,
+            left_answer TEXT NOT NULL,
+            right_answer TEXT NOT NULL,
+            vote TEXT,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_comparisons_profile ON comparisons(profile_id);",
+    )?;
+    Ok(())
+}
+
+/// Summaries are stored as a JSON object
+fn add_session_summaries(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "sessions", "summary", "TEXT")?;
+    Ok(())
+}
+
+/// Tags are stored as a JSON array
+fn add_session_tags(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "sessions", "tags", "TEXT")?;
+    add_column_if_missing(conn, "sessions", "topic", "TEXT")?;
+    Ok(())
+}
+
+fn create_codebases(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS codebases (
+            id TEXT PRIMARY KEY,
+            profile_id TEXT NOT NULL DEFAULT 'default',
+            name TEXT NOT NULL,
+            root TEXT NOT NULL,
+            file_count INTEGER NOT NULL DEFAULT 0,
+            chunk_count INTEGER NOT NULL DEFAULT 0,
+            indexed_at TEXT,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_codebases_profile ON codebases(profile_id);",
+    )?;
+    Ok(())
+}
+
+fn create_document_tags(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS document_tags (
+            profile_id TEXT NOT NULL DEFAULT 'default',
+            path TEXT NOT NULL,
+            tags TEXT NOT NULL,
+            PRIMARY KEY (profile_id, path)
+        );",
+    )?;
+    Ok(())
+}
+
+/// Tags are stored as a JSON array
+fn create_assets(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS assets (
+            id TEXT PRIMARY KEY,
+            profile_id TEXT NOT NULL DEFAULT 'default',
+            kind TEXT NOT NULL,
+            path TEXT NOT NULL,
+            prompt TEXT NOT NULL DEFAULT '',
+            origin TEXT NOT NULL,
+            origin_id TEXT,
+            tags TEXT NOT NULL DEFAULT '[]',
+            size_bytes INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            UNIQUE (profile_id, path)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_assets_profile ON assets(profile_id, created_at);",
+    )?;
+    Ok(())
+}
+
+/// Deleted rows keep a `deleted_at` time until they are purged; deleted
+/// context documents are moved to the trash folder and listed here
+fn add_trash(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "sessions", "deleted_at", "TEXT")?;
+    add_column_if_missing(conn, "messages", "deleted_at", "TEXT")?;
+    add_column_if_missing(conn, "assets", "deleted_at", "TEXT")?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS trashed_documents (
+            id TEXT PRIMARY KEY,
+            profile_id TEXT NOT NULL DEFAULT 'default',
+            filename TEXT NOT NULL,
+            trash_path TEXT NOT NULL,
+            deleted_at TEXT NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+/// Salt and passphrase check of the chat history encryption; the row only
+/// exists while encryption is on
+fn create_encryption(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS encryption (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            salt TEXT NOT NULL,
+            verifier TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+/// Overrides are stored as a JSON object
+fn add_session_overrides(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "sessions", "overrides", "TEXT")?;
+    Ok(())
+}
+
+/// Entries of the content calendar; `date` is the planned, scheduled or
+/// published time
+fn create_publications(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS publications (
+            id TEXT PRIMARY KEY,
+            profile_id TEXT NOT NULL DEFAULT 'default',
+            title TEXT NOT NULL,
+            status TEXT NOT NULL,
+            platform TEXT NOT NULL,
+            job_id TEXT,
+            date TEXT NOT NULL,
+            url TEXT,
+            notes TEXT NOT NULL DEFAULT '',
+            error TEXT,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_publications_profile ON publications(profile_id, date);",
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn latest_version() -> u32 {
+        MIGRATIONS.last().unwrap().version
+    }
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).unwrap();
+        stmt.query_map([], |row| row.get::<_, String>(1))
+            .unwrap()
+            .filter_map(|r| r.ok())
+            .collect()
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        let versions: Vec<u32> = MIGRATIONS.iter().map(|m| m.version).collect();
+        let expected: Vec<u32> = (1..=MIGRATIONS.len() as u32).collect();
+        assert_eq!(versions, expected);
+    }
+
+    #[test]
+    fn test_fresh_database_reaches_latest_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 0);
+
+        assert_eq!(run(&mut conn).unwrap(), latest_version());
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        assert!(columns(&conn, "sessions").contains(&"pinned".to_string()));
+        assert!(columns(&conn, "sessions").contains(&"summary".to_string()));
+        assert!(columns(&conn, "sessions").contains(&"topic".to_string()));
+        assert!(columns(&conn, "messages").contains(&"session_id".to_string()));
+        assert!(columns(&conn, "messages").contains(&"feedback_rating".to_string()));
+        assert!(columns(&conn, "messages").contains(&"attachments".to_string()));
+        assert!(columns(&conn, "messages").contains(&"generation_stats".to_string()));
+        assert!(columns(&conn, "comparisons").contains(&"vote".to_string()));
+        assert!(columns(&conn, "codebases").contains(&"root".to_string()));
+        assert!(columns(&conn, "document_tags").contains(&"tags".to_string()));
+        assert!(columns(&conn, "assets").contains(&"origin".to_string()));
+        assert!(columns(&conn, "messages").contains(&"deleted_at".to_string()));
+        assert!(columns(&conn, "trashed_documents").contains(&"trash_path".to_string()));
+        assert!(columns(&conn, "encryption").contains(&"verifier".to_string()));
+        assert!(columns(&conn, "sessions").contains(&"overrides".to_string()));
+        assert!(columns(&conn, "publications").contains(&"platform".to_string()));
+    }
+
+    #[test]
+    fn test_run_is_idempotent() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn).unwrap();
+        assert_eq!(run(&mut conn).unwrap(), latest_version());
+    }
+
+    #[test]
+    fn test_upgrades_unversioned_legacy_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE sessions (id TEXT PRIMARY KEY, title TEXT NOT NULL, created_at TEXT NOT NULL, updated_at TEXT NOT NULL);
+             INSERT INTO sessions VALUES ('a', 'Old chat', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z');",
+        ).unwrap();
+
+        run(&mut conn).unwrap();
+
+        let (title, pinned): (String, bool) = conn
+            .query_row("SELECT title, pinned FROM sessions WHERE id = 'a'", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!(title, "Old chat");
+        assert!(!pinned);
+
+        let profile: String = conn
+            .query_row("SELECT profile_id FROM sessions WHERE id = 'a'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(profile, "default");
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        fn broken(conn: &Connection) -> Result<()> {
+            conn.execute_batch("CREATE TABLE half_done (id TEXT)")?;
+            bail!("boom")
+        }
+        let migrations = [
+            Migration { version: 1, description: "initial", up: create_initial_schema },
+            Migration { version: 2, description: "broken", up: broken },
+        ];
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert!(run_migrations(&mut conn, &migrations).is_err());
+        assert_eq!(current_version(&conn).unwrap(), 1);
+        assert!(columns(&conn, "half_done").is_empty());
+    }
+
+    #[test]
+    fn test_rejects_newer_schema() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!("PRAGMA user_version = {}", latest_version() + 1)).unwrap();
+        assert!(run(&mut conn).is_err());
+    }
+}
@@ -1,3 +1,4 @@
 //! Storage Module
 
 pub mod database;
+pub mod migrations;
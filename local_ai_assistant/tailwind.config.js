@@ -0,0 +1,9 @@
+/** @type {import('tailwindcss').Config} */
+module.exports = {
+  mode: "all",
+  content: ["./src/**/*.{rs,html,css}"],
+  theme: {
+    extend: {},
+  },
+  plugins: [],
+};